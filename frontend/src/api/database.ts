@@ -3,6 +3,7 @@
  */
 
 import type { ApiResponse } from '@/types/common';
+import type { ImportResult } from '@/types/database';
 import type { QueryResult } from '@/types/sql-editor';
 import { invokeCommand } from './base';
 
@@ -33,13 +34,15 @@ export async function exportDatabase(
 export async function importDatabase(
   connectionId: string,
   filePath: string,
-  database: string
-): Promise<ApiResponse<void>> {
+  database: string,
+  dropExisting = false
+): Promise<ApiResponse<ImportResult>> {
   // For now, use the default connection from backend
   // TODO: Update backend to support connection parameter
-  return await invokeCommand<void>('import_database', {
+  return await invokeCommand<ImportResult>('import_database', {
     filePath,
     database,
+    dropExisting,
   });
 }
 
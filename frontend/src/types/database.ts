@@ -8,6 +8,14 @@ export interface DatabaseInfo {
   tables?: number;
 }
 
+export interface ImportResult {
+  database: string;
+  errors: number;
+  warnings: number;
+  /** 目标数据库在导入前已存在；未确认删除时导入被拒绝 */
+  database_exists: boolean;
+}
+
 export interface TableInfo {
   name: string;
   schema: string;
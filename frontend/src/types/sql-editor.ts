@@ -48,6 +48,8 @@ export interface QueryResult {
   error?: string;
   /** Position of error in SQL (if available) */
   errorPosition?: ErrorPosition;
+  /** Server notices raised while the query ran, e.g. RAISE NOTICE output ("NOTICE: ...") */
+  notices?: string[];
 }
 
 /**
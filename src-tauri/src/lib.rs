@@ -6,7 +6,8 @@ use std::fs::File;
 use std::io::Read;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use tauri::Emitter;
 
 // Models module for database advanced features
 pub mod models;
@@ -15,13 +16,75 @@ pub mod models;
 pub mod services;
 
 use models::query::QueryResult;
-use models::data::BatchOperationResponse;
+use models::data::{BatchChangesResponse, BatchOperationResponse, CsvImportMode, CsvImportResult};
+use services::connection_router::{self, Endpoint, EndpointConfig, ReconnectPolicy, RoutingConfig};
+use services::tls::{SslMode, TlsSettings};
 use services::query_executor;
-use services::transaction_manager;
+use services::transaction_manager::{self, BatchOptions};
+
+/// 顶层 `database` 连接配置的名称
+const DEFAULT_PROFILE: &str = "default";
 
 #[derive(Serialize, Deserialize, Clone)]
 struct Config {
+    /// 默认的连接配置（名称为 `default`）
     database: DatabaseConfig,
+    /// 其他命名的连接配置，如 dev/staging/prod
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    profiles: HashMap<String, DatabaseConfig>,
+    /// 当前使用的连接配置名称，未设置时使用 `database`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active_profile: Option<String>,
+    /// 命令行工具路径
+    #[serde(default)]
+    tools: ToolsConfig,
+    /// SQL 执行日志的保留天数和总大小上限
+    #[serde(default)]
+    sql_log: services::sql_logger::SqlLoggerConfig,
+}
+
+/// pg_dump、pg_restore、psql 的可执行文件路径，未设置时在 PATH 中查找
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct ToolsConfig {
+    #[serde(default)]
+    pg_dump_path: Option<String>,
+    #[serde(default)]
+    pg_restore_path: Option<String>,
+    #[serde(default)]
+    psql_path: Option<String>,
+}
+
+impl Config {
+    /// 按名称查找连接配置，`default` 指顶层的 `database`（`profiles` 中有同名配置时以其为准）
+    fn profile(&self, name: &str) -> Option<&DatabaseConfig> {
+        self.profiles
+            .get(name)
+            .or_else(|| (name == DEFAULT_PROFILE).then_some(&self.database))
+    }
+
+    /// 当前使用的连接配置名称
+    fn active_profile_name(&self) -> &str {
+        self.active_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// 当前使用的连接配置，`active_profile` 指向不存在的配置时回退到 `database`
+    fn active_database_config(&self) -> &DatabaseConfig {
+        let name = self.active_profile_name();
+        self.profile(name).unwrap_or_else(|| {
+            log::warn!("连接配置 {} 不存在，使用默认配置", name);
+            &self.database
+        })
+    }
+
+    /// 所有连接配置的名称，`default` 在前，其余按名称排序
+    fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        names.sort();
+        if !self.profiles.contains_key(DEFAULT_PROFILE) {
+            names.insert(0, DEFAULT_PROFILE.to_string());
+        }
+        names
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -32,6 +95,97 @@ struct DatabaseConfig {
     password: String,
     #[serde(default)]
     default_database: String,
+    /// 只读副本地址（可选）
+    #[serde(default)]
+    read_host: Option<String>,
+    /// 只读副本端口（可选，默认与主库相同）
+    #[serde(default)]
+    read_port: Option<String>,
+    /// 是否将 SELECT 语句发往只读副本
+    #[serde(default)]
+    prefer_replica_for_reads: bool,
+    /// SSL 模式（disable/prefer/require/verify-ca/verify-full），也接受 libpq 的写法 `sslmode`
+    #[serde(default, alias = "sslmode")]
+    ssl_mode: SslMode,
+    /// 根证书路径，用于验证服务器证书，也接受 `root_cert`
+    #[serde(default, alias = "root_cert")]
+    ssl_root_cert: Option<String>,
+    /// 客户端证书路径（双向 TLS）
+    #[serde(default)]
+    ssl_cert: Option<String>,
+    /// 客户端私钥路径（双向 TLS，PKCS#8 PEM 格式）
+    #[serde(default)]
+    ssl_key: Option<String>,
+    /// 连接断开后建立连接的最大重试次数（默认 3）
+    #[serde(default)]
+    reconnect_max_retries: Option<u32>,
+    /// 第一次重试前的等待毫秒数，之后每次翻倍（默认 200）
+    #[serde(default)]
+    reconnect_backoff_ms: Option<u64>,
+    /// 建立连接的超时秒数（默认 10）
+    #[serde(default)]
+    connect_timeout_secs: Option<u64>,
+    /// 每个数据库连接池的最大连接数（默认 10）
+    #[serde(default)]
+    pool_max_size: Option<usize>,
+}
+
+impl DatabaseConfig {
+    fn reconnect_policy(&self) -> ReconnectPolicy {
+        let default = ReconnectPolicy::default();
+        ReconnectPolicy {
+            max_retries: self.reconnect_max_retries.unwrap_or(default.max_retries),
+            initial_backoff: self
+                .reconnect_backoff_ms
+                .map(std::time::Duration::from_millis)
+                .unwrap_or(default.initial_backoff),
+            max_backoff: default.max_backoff,
+        }
+    }
+
+    fn connect_timeout(&self) -> std::time::Duration {
+        self.connect_timeout_secs
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(connection_router::DEFAULT_CONNECT_TIMEOUT)
+    }
+
+    fn pool_max_size(&self) -> usize {
+        self.pool_max_size.unwrap_or(connection_router::DEFAULT_POOL_MAX_SIZE)
+    }
+
+    fn tls_settings(&self) -> TlsSettings {
+        TlsSettings {
+            ssl_mode: self.ssl_mode,
+            ssl_root_cert: self.ssl_root_cert.clone(),
+            ssl_cert: self.ssl_cert.clone(),
+            ssl_key: self.ssl_key.clone(),
+        }
+    }
+}
+
+/// 连接配置概要（不含密码）
+#[derive(Serialize)]
+struct ProfileSummary {
+    name: String,
+    host: String,
+    port: String,
+    user: String,
+    default_database: String,
+    /// 是否为当前使用的连接配置
+    active: bool,
+}
+
+impl ProfileSummary {
+    fn new(name: &str, config: &DatabaseConfig, active: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            host: config.host.clone(),
+            port: config.port.clone(),
+            user: config.user.clone(),
+            default_database: config.default_database.clone(),
+            active,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -43,15 +197,45 @@ struct ApiResponse<T> {
 
 // Application state for managing database connections
 struct AppState {
-    connections: Arc<Mutex<HashMap<String, tokio_postgres::Client>>>,
+    /// 每个数据库的连接池
+    connections: Arc<Mutex<connection_router::ConnectionPools>>,
+    /// 新建记录时使用的列类型缓存
+    column_types: Arc<Mutex<services::record_writer::ColumnTypeCache>>,
+    /// 通过 disable_table_triggers 禁用了触发器的表，批量操作失败时自动重新启用
+    disabled_triggers: Arc<Mutex<services::bulk_load::DisabledTriggerRegistry>>,
+    /// 批量操作时延迟约束检查的数据库
+    deferred_constraints: Arc<Mutex<HashSet<String>>>,
+    /// 每个数据库的 LISTEN 连接及其订阅的频道
+    listeners: Arc<Mutex<HashMap<String, services::notification_listener::NotificationListener>>>,
+    /// 正在执行的语句的取消令牌
+    running_queries: Arc<Mutex<services::query_cancel::QueryCancelRegistry>>,
+    /// SQL 编辑器中显式开启的事务，每个事务独占一个专用连接
+    transactions: Arc<Mutex<services::editor_transaction::TransactionRegistry>>,
+    /// 启动时的数据库连接检查结果
+    startup_status: Arc<Mutex<services::startup_check::StartupStatus>>,
 }
 
 impl AppState {
-    fn new() -> Self {
+    fn new(startup_status: services::startup_check::StartupStatus) -> Self {
         Self {
             connections: Arc::new(Mutex::new(HashMap::new())),
+            column_types: Arc::new(Mutex::new(services::record_writer::ColumnTypeCache::new())),
+            disabled_triggers: Arc::new(Mutex::new(services::bulk_load::DisabledTriggerRegistry::new())),
+            deferred_constraints: Arc::new(Mutex::new(HashSet::new())),
+            listeners: Arc::new(Mutex::new(HashMap::new())),
+            running_queries: Arc::new(Mutex::new(services::query_cancel::QueryCancelRegistry::new())),
+            transactions: Arc::new(Mutex::new(services::editor_transaction::TransactionRegistry::new())),
+            startup_status: Arc::new(Mutex::new(startup_status)),
         }
     }
+
+    /// 从指定数据库的（主库）连接池中取出一个连接，没有可用的空闲连接时建立新连接
+    ///
+    /// 连接在返回值被丢弃时归还连接池，其他命令可以同时使用池中的其他连接。
+    async fn get_or_connect(&self, database: &str) -> Result<connection_router::PooledConnection, String> {
+        let pool = connection_pool(&mut *self.connections.lock().await, database)?;
+        connection_router::get_connection(&pool).await
+    }
 }
 
 // New types for database explorer
@@ -76,9 +260,24 @@ struct ColumnInfo {
 #[derive(Serialize, Deserialize)]
 struct TableData {
     columns: Vec<ColumnInfo>,
+    /// 主键列（按主键定义顺序）
+    #[serde(rename = "primaryKey")]
+    primary_key: Vec<String>,
+    /// 与 `primaryKey` 相同，保留原有字段名以兼容已有的调用方
+    #[serde(rename = "primaryKeyColumns")]
+    primary_key_columns: Vec<String>,
+    #[serde(rename = "hasPrimaryKey")]
+    has_primary_key: bool,
+    /// 没有主键时每行附带的定位列（`ctid`），编辑和删除时以 `{"ctid": ...}` 作为主键传回
+    #[serde(rename = "rowIdColumn")]
+    row_id_column: Option<String>,
+    editable: bool,
     rows: Vec<serde_json::Value>,
     #[serde(rename = "totalRows")]
     total_rows: i64,
+    /// `totalRows` 是否为统计信息中的估算值
+    #[serde(rename = "totalRowsEstimated")]
+    total_rows_estimated: bool,
     page: u32,
     #[serde(rename = "pageSize")]
     page_size: u32,
@@ -123,19 +322,124 @@ fn load_config() -> Config {
             user: "postgres".to_string(),
             password: "postgres".to_string(),
             default_database: "personnel_db".to_string(),
-        }
+            read_host: None,
+            read_port: None,
+            prefer_replica_for_reads: false,
+            ssl_mode: SslMode::Disable,
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
+            reconnect_max_retries: None,
+            reconnect_backoff_ms: None,
+            connect_timeout_secs: None,
+            pool_max_size: None,
+        },
+        profiles: HashMap::new(),
+        active_profile: None,
+        tools: ToolsConfig::default(),
+        sql_log: services::sql_logger::SqlLoggerConfig::default(),
     }
 }
 
+/// 将配置写回配置文件
+fn save_config(config: &Config) -> Result<(), String> {
+    let config_path = get_config_path();
+    let contents = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("无法序列化配置: {}", e))?;
+    std::fs::write(&config_path, contents)
+        .map_err(|e| format!("无法写入配置文件 {}: {}", config_path.display(), e))?;
+    log::info!("已保存配置文件: {}", config_path.display());
+    Ok(())
+}
+
+/// 当前使用的连接配置，环境变量优先
 fn get_db_config() -> DatabaseConfig {
     let config = load_config();
-    
+    apply_env_overrides(config.active_database_config().clone(), |name| env::var(name).ok())
+}
+
+/// 用环境变量覆盖连接配置中的对应字段，`var` 按名称读取环境变量
+fn apply_env_overrides(database: DatabaseConfig, var: impl Fn(&str) -> Option<String>) -> DatabaseConfig {
     DatabaseConfig {
-        host: env::var("PG_HOST").unwrap_or(config.database.host),
-        port: env::var("PG_PORT").unwrap_or(config.database.port),
-        user: env::var("PG_USER").unwrap_or(config.database.user),
-        password: env::var("PG_PASSWORD").unwrap_or(config.database.password),
-        default_database: config.database.default_database,
+        host: var("PG_HOST").unwrap_or(database.host),
+        port: var("PG_PORT").unwrap_or(database.port),
+        user: var("PG_USER").unwrap_or(database.user),
+        password: var("PG_PASSWORD").unwrap_or(database.password),
+        default_database: database.default_database,
+        read_host: var("PG_READ_HOST").or(database.read_host),
+        read_port: var("PG_READ_PORT").or(database.read_port),
+        prefer_replica_for_reads: database.prefer_replica_for_reads,
+        ssl_mode: var("PG_SSL_MODE")
+            .and_then(|mode| match mode.parse() {
+                Ok(mode) => Some(mode),
+                Err(e) => {
+                    log::warn!("忽略环境变量 PG_SSL_MODE: {}", e);
+                    None
+                }
+            })
+            .unwrap_or(database.ssl_mode),
+        ssl_root_cert: var("PG_SSL_ROOT_CERT").or(database.ssl_root_cert),
+        ssl_cert: var("PG_SSL_CERT").or(database.ssl_cert),
+        ssl_key: var("PG_SSL_KEY").or(database.ssl_key),
+        reconnect_max_retries: var("PG_RECONNECT_MAX_RETRIES")
+            .and_then(|v| v.parse().ok())
+            .or(database.reconnect_max_retries),
+        reconnect_backoff_ms: var("PG_RECONNECT_BACKOFF_MS")
+            .and_then(|v| v.parse().ok())
+            .or(database.reconnect_backoff_ms),
+        connect_timeout_secs: var("PG_CONNECT_TIMEOUT_SECS")
+            .and_then(|v| v.parse().ok())
+            .or(database.connect_timeout_secs),
+        pool_max_size: var("PG_POOL_MAX_SIZE")
+            .and_then(|v| v.parse().ok())
+            .or(database.pool_max_size),
+    }
+}
+
+/// 获取 PostgreSQL 命令行工具，使用配置文件中 `tools` 的路径
+fn get_pg_tool(name: &'static str) -> services::pg_tools::PgTool {
+    let tools = load_config().tools;
+    let path = match name {
+        "pg_dump" => tools.pg_dump_path,
+        "pg_restore" => tools.pg_restore_path,
+        "psql" => tools.psql_path,
+        _ => None,
+    };
+    services::pg_tools::PgTool::new(name, path)
+}
+
+fn get_routing_config() -> RoutingConfig {
+    routing_config(get_db_config())
+}
+
+/// 由连接配置生成读写路由配置
+fn routing_config(config: DatabaseConfig) -> RoutingConfig {
+    let tls = config.tls_settings();
+    let reconnect = config.reconnect_policy();
+    let connect_timeout = config.connect_timeout();
+    let pool_max_size = config.pool_max_size();
+
+    let read = config.read_host.clone().map(|host| EndpointConfig {
+        host,
+        port: config.read_port.clone().unwrap_or_else(|| config.port.clone()),
+        user: config.user.clone(),
+        password: config.password.clone(),
+        tls: tls.clone(),
+    });
+
+    RoutingConfig {
+        write: EndpointConfig {
+            host: config.host,
+            port: config.port,
+            user: config.user,
+            password: config.password,
+            tls,
+        },
+        read,
+        prefer_replica_for_reads: config.prefer_replica_for_reads,
+        reconnect,
+        connect_timeout,
+        pool_max_size,
     }
 }
 
@@ -155,64 +459,93 @@ fn get_log_dir() -> Result<PathBuf, String> {
     Ok(log_dir)
 }
 
-// SQL Execution Command
-#[tauri::command]
-async fn execute_sql(
+/// 按配置文件中 `sql_log` 的保留设置清理旧的 SQL 执行日志（创建日志记录器时执行清理）
+fn prune_sql_logs() {
+    if let Err(e) = get_log_dir()
+        .and_then(|log_dir| services::sql_logger::SqlLogger::with_config(log_dir, load_config().sql_log))
+    {
+        log::warn!("清理 SQL 日志失败: {}", e);
+    }
+}
+
+fn get_data_dir() -> Result<PathBuf, String> {
+    let mut data_dir = dirs::home_dir().ok_or("无法获取用户目录")?;
+    data_dir.push("pg-db-tool-data");
+    std::fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("无法创建数据目录: {}", e))?;
+    Ok(data_dir)
+}
+
+/// 获取指定数据库的（主库）连接池，尚不存在时创建
+fn connection_pool(
+    connections: &mut connection_router::ConnectionPools,
+    database: &str,
+) -> Result<connection_router::ConnectionPool, String> {
+    connection_router::connection_pool(connections, &get_routing_config(), database, Endpoint::Write)
+}
+
+/// 解析命令的 schema 和表名
+///
+/// 指定了 `schema` 时表名按原样使用；否则表名可以带 `schema.` 前缀，都没有时默认为 public。
+fn resolve_table_name(schema: Option<String>, table: &str) -> (String, String) {
+    match schema {
+        Some(schema) => (schema, table.to_string()),
+        None => match table.split_once('.') {
+            Some((schema, name)) => (schema.to_string(), name.to_string()),
+            None => ("public".to_string(), table.to_string()),
+        },
+    }
+}
+
+/// 启动时检查配置的默认数据库能否连接
+///
+/// 结果保存到 `startup_status`，并通过 `startup-status` 事件通知界面。连接失败不影响应用运行。
+async fn check_startup_connection(
+    app: tauri::AppHandle,
+    connections: Arc<Mutex<connection_router::ConnectionPools>>,
+    startup_status: Arc<Mutex<services::startup_check::StartupStatus>>,
+    routing: RoutingConfig,
     database: String,
-    sql: String,
-    state: tauri::State<'_, AppState>,
-) -> Result<ApiResponse<QueryResult>, String> {
-    log::info!("========== 执行 SQL ==========");
-    log::info!("数据库: {}", database);
-    log::info!("SQL: {}", sql);
-    
-    let config = get_db_config();
-    
-    // Build connection string
-    let connection_string = format!(
-        "host={} port={} user={} password={} dbname={}",
-        config.host, config.port, config.user, config.password, database
-    );
-    
-    // Get or create connection
-    let mut connections = state.connections.lock().await;
-    
-    // Check if we have an existing connection for this database
-    let connection_key = format!("{}:{}", config.host, database);
-    
-    if !connections.contains_key(&connection_key) {
-        // Create new connection
-        log::info!("创建新的数据库连接: {}", connection_key);
-        
-        let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls)
-            .await
-            .map_err(|e| format!("无法连接到数据库: {}", e))?;
-        
-        // Spawn connection handler
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                log::error!("数据库连接错误: {}", e);
-            }
-        });
-        
-        connections.insert(connection_key.clone(), client);
+) {
+    log::info!("========== 启动连接检查 ==========");
+    log::info!("主库: {}:{}, 数据库: {}", routing.write.host, routing.write.port, database);
+
+    let status = services::startup_check::check_connection(
+        &connections,
+        &routing,
+        &database,
+        services::startup_check::STARTUP_CHECK_TIMEOUT,
+    ).await;
+
+    match &status.error {
+        None => log::info!("数据库连接正常，服务器版本: {}", status.server_version.as_deref().unwrap_or("")),
+        Some(e) => log::error!("无法连接数据库 {}: {}", database, e),
     }
-    
-    let client = connections.get(&connection_key)
-        .ok_or_else(|| "无法获取数据库连接".to_string())?;
-    
-    // Execute SQL
-    let result = query_executor::execute_sql(client, &sql).await;
-    
-    log::info!("SQL 执行完成，耗时: {} ms", result.duration_ms);
-    
-    // 记录 SQL 执行日志
+
+    *startup_status.lock().await = status.clone();
+    if let Err(e) = app.emit("startup-status", status) {
+        log::warn!("发送启动状态事件失败: {}", e);
+    }
+}
+
+/// 获取启动时的数据库连接检查结果
+///
+/// 检查尚未完成时 `state` 为 `checking`，界面可等待 `startup-status` 事件。
+#[tauri::command]
+async fn get_startup_status(
+    state: tauri::State<'_, AppState>,
+) -> Result<services::startup_check::StartupStatus, String> {
+    Ok(state.startup_status.lock().await.clone())
+}
+
+/// 将 SQL 执行结果写入 SQL 执行日志，写入失败只记录警告
+fn log_sql_execution(database: &str, sql: &str, result: &QueryResult) {
     if let Ok(log_dir) = get_log_dir() {
-        if let Ok(logger) = services::sql_logger::SqlLogger::new(log_dir) {
+        if let Ok(logger) = services::sql_logger::SqlLogger::with_config(log_dir, load_config().sql_log) {
             let log_entry = if result.result_type == models::query::QueryResultType::Error {
                 services::sql_logger::SqlLogEntry::error(
-                    database.clone(),
-                    sql.clone(),
+                    database.to_string(),
+                    sql.to_string(),
                     result.duration_ms,
                     result.error.clone().unwrap_or_else(|| "未知错误".to_string()),
                     result.error_position.as_ref().map(|pos| format!("Line {}, Column {}", pos.line, pos.column)),
@@ -224,12 +557,13 @@ async fn execute_sql(
                     models::query::QueryResultType::Update => "UPDATE",
                     models::query::QueryResultType::Delete => "DELETE",
                     models::query::QueryResultType::Ddl => "DDL",
+                    models::query::QueryResultType::Copy => "COPY",
                     _ => "UNKNOWN",
                 }.to_string();
 
                 services::sql_logger::SqlLogEntry::success(
-                    database.clone(),
-                    sql.clone(),
+                    database.to_string(),
+                    sql.to_string(),
                     result.duration_ms,
                     query_type,
                     result.affected_rows,
@@ -244,6 +578,53 @@ async fn execute_sql(
             }
         }
     }
+}
+
+// SQL Execution Command
+#[tauri::command]
+async fn execute_sql(
+    database: String,
+    sql: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<QueryResult>, String> {
+    log::info!("========== 执行 SQL ==========");
+    log::info!("数据库: {}", database);
+    log::info!("SQL: {}", sql);
+    
+    // 根据语句类型选择主库或只读副本
+    let routing = get_routing_config();
+    let endpoint = routing.route(&sql);
+    log::info!("路由端点: {:?}", endpoint);
+    
+    // Execute SQL（连接断开时自动重连，只读查询会透明重试）
+    let pool = connection_router::connection_pool(&mut *state.connections.lock().await, &routing, &database, endpoint)?;
+    let connection = connection_router::get_connection(&pool).await?;
+
+    // 登记取消令牌，以便 cancel_all_queries 取消执行中的语句
+    let query_id = state.running_queries.lock().await.register(
+        &database,
+        connection.cancel_token(),
+        routing.tls_settings(endpoint).clone(),
+    );
+    let result = connection_router::execute_with_reconnect(&pool, connection, &sql).await;
+    state.running_queries.lock().await.unregister(query_id);
+    let result = result?;
+    
+    log::info!("SQL 执行完成，耗时: {} ms", result.duration_ms);
+    if let Some(warning) = &result.warning {
+        log::warn!("{}", warning);
+    }
+    
+    // 记录 SQL 执行日志
+    log_sql_execution(&database, &sql, &result);
+    
+    // DDL 可能修改了表结构（例如列的类型），丢弃该数据库缓存的列类型
+    let runs_ddl = query_executor::parse_sql_statements(&sql)
+        .into_iter()
+        .any(|statement| query_executor::determine_query_type(statement) == models::query::QueryResultType::Ddl);
+    if runs_ddl {
+        state.column_types.lock().await.invalidate_database(&database);
+    }
     
     // 将 QueryResult 包装为 ApiResponse
     let response = if result.result_type == models::query::QueryResultType::Error {
@@ -263,197 +644,1436 @@ async fn execute_sql(
     Ok(response)
 }
 
-// Schema Management Commands
-
-/// Get complete table schema including columns, constraints, and indexes
+/// 取消本应用正在执行的全部语句（紧急停止）
+///
+/// 只向本应用登记的会话发送取消请求，不影响其他客户端。返回发送的取消请求数量。
 #[tauri::command]
-async fn get_table_schema(
-    database: String,
-    schema: String,
-    table: String,
+async fn cancel_all_queries(
     state: tauri::State<'_, AppState>,
-) -> Result<models::schema::TableSchema, String> {
-    log::info!("========== 获取表结构 ==========");
-    log::info!("数据库: {}, Schema: {}, 表: {}", database, schema, table);
-    
-    let config = get_db_config();
-    let connection_string = format!(
-        "host={} port={} user={} password={} dbname={}",
-        config.host, config.port, config.user, config.password, database
-    );
-    
-    let mut connections = state.connections.lock().await;
-    let connection_key = format!("{}:{}", config.host, database);
-    
-    if !connections.contains_key(&connection_key) {
-        let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls)
-            .await
-            .map_err(|e| format!("无法连接到数据库: {}", e))?;
-        
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                log::error!("数据库连接错误: {}", e);
-            }
+) -> Result<ApiResponse<usize>, String> {
+    log::info!("========== 取消全部查询 ==========");
+
+    let running = state.running_queries.lock().await.running();
+    if running.is_empty() {
+        log::info!("没有正在执行的查询");
+        return Ok(ApiResponse {
+            success: true,
+            message: "没有正在执行的查询".to_string(),
+            data: Some(0),
         });
-        
-        connections.insert(connection_key.clone(), client);
     }
-    
-    let client = connections.get(&connection_key)
-        .ok_or_else(|| "无法获取数据库连接".to_string())?;
-    
-    let table_schema = services::schema_service::get_table_schema(client, &schema, &table).await?;
-    
-    log::info!("表结构获取完成");
-    Ok(table_schema)
+
+    let requested = services::query_cancel::cancel_all(&running).await;
+    log::info!("已发送 {}/{} 个取消请求", requested, running.len());
+
+    Ok(ApiResponse {
+        success: requested == running.len(),
+        message: format!("已请求取消 {} 个正在执行的查询", requested),
+        data: Some(requested),
+    })
 }
 
-/// Create a new table based on table design
+/// 在 SQL 编辑器中开启显式事务，返回事务编号（tx_id）
+///
+/// 事务独占一个专用连接，之后通过 `execute_in_transaction` 执行语句，最后调用
+/// `commit_transaction` 或 `rollback_transaction` 结束。闲置过久的事务会被自动回滚。
 #[tauri::command]
-async fn create_table(
+async fn begin_transaction(
     database: String,
-    design: models::schema::TableDesign,
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    log::info!("========== 创建表 ==========");
-    log::info!("数据库: {}, 表: {}.{}", database, design.schema, design.table_name);
-    
-    let config = get_db_config();
-    let connection_string = format!(
-        "host={} port={} user={} password={} dbname={}",
-        config.host, config.port, config.user, config.password, database
-    );
-    
-    let mut connections = state.connections.lock().await;
-    let connection_key = format!("{}:{}", config.host, database);
-    
-    if !connections.contains_key(&connection_key) {
-        let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls)
-            .await
-            .map_err(|e| format!("无法连接到数据库: {}", e))?;
-        
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                log::error!("数据库连接错误: {}", e);
-            }
-        });
-        
-        connections.insert(connection_key.clone(), client);
-    }
-    
-    let client = connections.get(&connection_key)
-        .ok_or_else(|| "无法获取数据库连接".to_string())?;
-    
-    // Generate DDL
-    let ddl = services::ddl_generator::generate_create_table(&design);
-    log::info!("生成的 DDL:\n{}", ddl);
-    
-    // Execute DDL
-    let result = query_executor::execute_sql(client, &ddl).await;
-    
-    if result.result_type == models::query::QueryResultType::Error {
-        let error_msg = result.error.unwrap_or_else(|| "未知错误".to_string());
-        log::error!("创建表失败: {}", error_msg);
-        return Err(error_msg);
+) -> Result<ApiResponse<String>, String> {
+    log::info!("========== 开启事务 ==========");
+    log::info!("数据库: {}", database);
+
+    let client = connection_router::connect_transaction(&get_routing_config(), &database).await?;
+
+    match services::editor_transaction::EditorTransaction::begin(&database, client).await {
+        Ok(transaction) => {
+            let tx_id = state.transactions.lock().await.insert(transaction);
+            log::info!("事务已开启: {}", tx_id);
+            Ok(ApiResponse {
+                success: true,
+                message: "事务已开启".to_string(),
+                data: Some(tx_id),
+            })
+        }
+        Err(e) => {
+            log::error!("{}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
     }
-    
-    log::info!("表创建成功");
-    Ok(())
 }
 
-/// Alter an existing table based on table changes
+/// 在已开启的事务中执行 SQL
+///
+/// 语句失败后事务中的后续语句都会被服务器拒绝，需要回滚事务（或回滚到保存点）。
 #[tauri::command]
-async fn alter_table(
-    database: String,
-    schema: String,
-    table: String,
-    changes: models::schema::TableChanges,
+#[allow(non_snake_case)]
+async fn execute_in_transaction(
+    txId: String,
+    sql: String,
     state: tauri::State<'_, AppState>,
-) -> Result<(), String> {
-    log::info!("========== 修改表 ==========");
-    log::info!("数据库: {}, 表: {}.{}", database, schema, table);
-    
-    let config = get_db_config();
-    let connection_string = format!(
-        "host={} port={} user={} password={} dbname={}",
-        config.host, config.port, config.user, config.password, database
+) -> Result<ApiResponse<QueryResult>, String> {
+    log::info!("========== 在事务中执行 SQL ==========");
+    log::info!("事务: {}", txId);
+    log::info!("SQL: {}", sql);
+
+    let transaction = state.transactions.lock().await.get(&txId)
+        .ok_or_else(|| format!("事务 {} 不存在或已结束", txId))?;
+    let mut transaction = transaction.lock().await;
+
+    // 登记取消令牌，以便 cancel_all_queries 取消执行中的语句
+    let query_id = state.running_queries.lock().await.register(
+        &transaction.database,
+        transaction.client().cancel_token(),
+        get_routing_config().tls_settings(Endpoint::Write).clone(),
     );
-    
-    let mut connections = state.connections.lock().await;
-    let connection_key = format!("{}:{}", config.host, database);
-    
-    if !connections.contains_key(&connection_key) {
-        let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls)
-            .await
-            .map_err(|e| format!("无法连接到数据库: {}", e))?;
-        
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                log::error!("数据库连接错误: {}", e);
-            }
+    let result = transaction.execute(&sql).await;
+    state.running_queries.lock().await.unregister(query_id);
+
+    log::info!("SQL 执行完成，耗时: {} ms", result.duration_ms);
+    log_sql_execution(&transaction.database, &sql, &result);
+
+    // 连接断开时服务器已回滚事务
+    if transaction.client().is_closed() {
+        state.transactions.lock().await.remove(&txId);
+        log::error!("事务 {} 的连接已断开，事务已回滚", txId);
+        return Ok(ApiResponse {
+            success: false,
+            message: "连接已断开，事务已回滚".to_string(),
+            data: Some(result),
         });
-        
-        connections.insert(connection_key.clone(), client);
-    }
-    
-    let client = connections.get(&connection_key)
-        .ok_or_else(|| "无法获取数据库连接".to_string())?;
-    
-    // Generate ALTER TABLE statements
-    let statements = services::ddl_generator::generate_alter_table(&schema, &table, &changes);
-    log::info!("生成的 ALTER TABLE 语句数量: {}", statements.len());
-    
-    // Execute all statements
-    for (i, statement) in statements.iter().enumerate() {
-        log::info!("执行语句 {}: {}", i + 1, statement);
-        let result = query_executor::execute_sql(client, statement).await;
-        
-        if result.result_type == models::query::QueryResultType::Error {
-            let error_msg = result.error.unwrap_or_else(|| "未知错误".to_string());
-            log::error!("修改表失败 (语句 {}): {}", i + 1, error_msg);
-            return Err(format!("语句 {} 失败: {}", i + 1, error_msg));
-        }
     }
-    
-    log::info!("表修改成功");
-    Ok(())
-}
 
-/// Get database objects for auto-completion
+    let response = if result.result_type == models::query::QueryResultType::Error {
+        ApiResponse {
+            success: false,
+            message: result.error.clone().unwrap_or_else(|| "SQL 执行失败".to_string()),
+            data: Some(result),
+        }
+    } else {
+        ApiResponse {
+            success: true,
+            message: "SQL 执行成功".to_string(),
+            data: Some(result),
+        }
+    };
+
+    Ok(response)
+}
+
+/// 提交事务并关闭其连接
+///
+/// 提交失败（例如延迟检查的约束被违反）时事务已被服务器回滚。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn commit_transaction(
+    txId: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<()>, String> {
+    log::info!("========== 提交事务 ==========");
+    log::info!("事务: {}", txId);
+
+    let transaction = state.transactions.lock().await.remove(&txId)
+        .ok_or_else(|| format!("事务 {} 不存在或已结束", txId))?;
+    let mut transaction = transaction.lock().await;
+
+    match transaction.commit().await {
+        Ok(()) => {
+            log::info!("事务已提交，持续 {} ms", transaction.age().as_millis());
+            Ok(ApiResponse {
+                success: true,
+                message: "事务已提交".to_string(),
+                data: None,
+            })
+        }
+        Err(e) => {
+            log::error!("{}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 回滚事务并关闭其连接
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn rollback_transaction(
+    txId: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<()>, String> {
+    log::info!("========== 回滚事务 ==========");
+    log::info!("事务: {}", txId);
+
+    let transaction = state.transactions.lock().await.remove(&txId)
+        .ok_or_else(|| format!("事务 {} 不存在或已结束", txId))?;
+    let mut transaction = transaction.lock().await;
+
+    match transaction.rollback().await {
+        Ok(()) => {
+            log::info!("事务已回滚");
+            Ok(ApiResponse {
+                success: true,
+                message: "事务已回滚".to_string(),
+                data: None,
+            })
+        }
+        Err(e) => {
+            // 连接随事务一起关闭，服务器会回滚未提交的事务
+            log::error!("{}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 定期回滚闲置过久（已被放弃）的事务并关闭其连接
+async fn reap_abandoned_transactions(
+    transactions: Arc<Mutex<services::editor_transaction::TransactionRegistry>>,
+) {
+    let mut interval = tokio::time::interval(services::editor_transaction::IDLE_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        let idle = transactions.lock().await
+            .take_idle(services::editor_transaction::DEFAULT_IDLE_TIMEOUT);
+        if !idle.is_empty() {
+            let count = services::editor_transaction::rollback_abandoned(idle).await;
+            log::info!("已自动回滚 {} 个闲置的事务", count);
+        }
+    }
+}
+
+/// 预览 UPDATE/DELETE 语句将影响的行，不会修改任何数据
+#[tauri::command]
+async fn preview_affected_rows(
+    database: String,
+    sql: String,
+    limit: Option<i64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::query::AffectedRowsPreview>, String> {
+    log::info!("========== 预览受影响的行 ==========");
+    log::info!("数据库: {}", database);
+    log::info!("SQL: {}", sql);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let limit = limit.unwrap_or(services::dml_preview::DEFAULT_PREVIEW_LIMIT);
+    match services::dml_preview::preview_affected_rows(client, &sql, limit).await {
+        Ok(preview) => {
+            log::info!("将影响 {} 行", preview.affected_count);
+            if let Some(warning) = &preview.warning {
+                log::warn!("{}", warning);
+            }
+            Ok(ApiResponse {
+                success: true,
+                message: format!("该语句将影响 {} 行", preview.affected_count),
+                data: Some(preview),
+            })
+        }
+        Err(e) => {
+            log::error!("预览受影响的行失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 检查 SQL 中读写数据库服务器文件系统的 COPY 语句（COPY FROM/TO '文件'、PROGRAM）
+///
+/// 这些语句在服务器上执行，而不是在本机。对文件形式的 COPY 返回改写为
+/// FROM STDIN / TO STDOUT 的语句，可通过 execute_client_copy 使用本机文件执行。
+#[tauri::command]
+async fn check_server_copy(
+    sql: String,
+) -> Result<ApiResponse<Vec<models::query::ServerCopyStatement>>, String> {
+    log::info!("========== 检查服务器端 COPY ==========");
+    log::info!("SQL: {}", sql);
+
+    let copies = services::query_executor::find_server_copies(&sql);
+    for copy in &copies {
+        log::warn!("{}", copy.warning);
+    }
+
+    Ok(ApiResponse {
+        success: true,
+        message: if copies.is_empty() {
+            "没有在服务器上读写文件的 COPY 语句".to_string()
+        } else {
+            format!("{} 条 COPY 语句将在数据库服务器上读写文件或执行命令", copies.len())
+        },
+        data: Some(copies),
+    })
+}
+
+/// 使用本机文件执行 COPY ... FROM STDIN 或 COPY ... TO STDOUT
+///
+/// FROM STDIN 读取本机文件的内容发送给服务器；TO STDOUT 将输出写入本机文件（覆盖已有内容）。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn execute_client_copy(
+    database: String,
+    sql: String,
+    filePath: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<QueryResult>, String> {
+    log::info!("========== 使用本机文件执行 COPY ==========");
+    log::info!("数据库: {}, 文件: {}", database, filePath);
+    log::info!("SQL: {}", sql);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let result = services::query_executor::execute_client_copy(client, &sql, &filePath).await;
+
+    if result.result_type == models::query::QueryResultType::Error {
+        log::error!("COPY 执行失败: {:?}", result.error);
+        return Ok(ApiResponse {
+            success: false,
+            message: result.error.clone().unwrap_or_else(|| "COPY 执行失败".to_string()),
+            data: Some(result),
+        });
+    }
+
+    log::info!("COPY 执行完成，耗时: {} ms", result.duration_ms);
+    Ok(ApiResponse {
+        success: true,
+        message: match result.affected_rows {
+            Some(rows) => format!("已从 {} 导入 {} 行", filePath, rows),
+            None => format!("已导出到 {}", filePath),
+        },
+        data: Some(result),
+    })
+}
+
+/// 解释 SQLSTATE 错误码（如 `55P03`）：错误类别、条件名、说明以及常见原因和解决办法
+///
+/// 错误码即 SQL 执行失败信息中 `Error code:` 后的 5 位代码。
+#[tauri::command]
+async fn explain_error(
+    code: String,
+) -> Result<ApiResponse<models::query::ErrorExplanation>, String> {
+    log::info!("========== 解释错误码 ==========");
+    log::info!("错误码: {}", code);
+
+    match services::error_codes::explain_error(&code) {
+        Ok(explanation) => Ok(ApiResponse {
+            success: true,
+            message: format!(
+                "{}: {}",
+                explanation.code,
+                explanation.condition_name.as_deref().unwrap_or(&explanation.class_name)
+            ),
+            data: Some(explanation),
+        }),
+        Err(e) => {
+            log::warn!("{}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 查看语句的执行计划（EXPLAIN FORMAT JSON）
+///
+/// `analyze` 为 true 时使用 EXPLAIN (ANALYZE, FORMAT JSON, BUFFERS)，会实际执行该语句；
+/// 返回 JSON 格式的执行计划以及总成本和估算行数。
+#[tauri::command]
+async fn explain_query(
+    database: String,
+    sql: String,
+    analyze: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::query::QueryPlan>, String> {
+    log::info!("========== 查看执行计划 ==========");
+    log::info!("数据库: {}, ANALYZE: {}", database, analyze);
+    log::info!("SQL: {}", sql);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::explain::explain_query(client, &sql, analyze).await {
+        Ok(plan) => {
+            log::info!("执行计划获取完成，总成本: {}，估算行数: {}", plan.total_cost, plan.estimated_rows);
+            let message = match &plan.warning {
+                Some(warning) => {
+                    log::warn!("{}", warning);
+                    warning.clone()
+                }
+                None => format!("总成本 {:.2}，估算 {} 行", plan.total_cost, plan.estimated_rows),
+            };
+            Ok(ApiResponse {
+                success: true,
+                message,
+                data: Some(plan),
+            })
+        }
+        Err(e) => {
+            log::error!("获取执行计划失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 执行 EXPLAIN (ANALYZE, BUFFERS) 并返回结构化的执行计划树
+///
+/// 注意：ANALYZE 会实际执行该语句，INSERT/UPDATE/DELETE 的数据修改会生效。
+#[tauri::command]
+async fn explain_analyze_buffers(
+    database: String,
+    sql: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::query::ExplainAnalyzeResult>, String> {
+    log::info!("========== EXPLAIN ANALYZE ==========");
+    log::info!("数据库: {}", database);
+    log::info!("SQL: {}", sql);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::explain::explain_analyze_buffers(client, &sql).await {
+        Ok(result) => {
+            log::info!("执行计划分析完成，执行耗时: {} ms", result.execution_time_ms);
+            let message = match &result.warning {
+                Some(warning) => {
+                    log::warn!("{}", warning);
+                    warning.clone()
+                }
+                None => "执行计划分析完成".to_string(),
+            };
+            Ok(ApiResponse {
+                success: true,
+                message,
+                data: Some(result),
+            })
+        }
+        Err(e) => {
+            log::error!("执行计划分析失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 多次运行查询并统计执行时间（最小、最大、平均、中位数、p95、标准差）
+///
+/// 只允许只读语句（SELECT、EXPLAIN）。所有运行使用同一个连接；`resetBetweenRuns`
+/// 为 true 时每次运行前丢弃缓存的执行计划。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn benchmark_query(
+    database: String,
+    sql: String,
+    runs: u32,
+    warmupRuns: Option<u32>,
+    resetBetweenRuns: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::query::BenchmarkResult>, String> {
+    log::info!("========== 查询性能测试 ==========");
+    log::info!("数据库: {}, 运行次数: {}, 预热次数: {:?}", database, runs, warmupRuns);
+    log::info!("SQL: {}", sql);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let options = services::query_benchmark::BenchmarkOptions {
+        runs,
+        warmup_runs: warmupRuns.unwrap_or(0),
+        reset_between_runs: resetBetweenRuns.unwrap_or(false),
+    };
+
+    match services::query_benchmark::benchmark_query(client, &sql, &options).await {
+        Ok(result) => {
+            log::info!(
+                "性能测试完成，平均 {:.3} ms，中位数 {:.3} ms，p95 {:.3} ms",
+                result.mean_ms, result.median_ms, result.p95_ms
+            );
+            Ok(ApiResponse {
+                success: true,
+                message: format!("运行 {} 次，平均 {:.3} ms", result.runs, result.mean_ms),
+                data: Some(result),
+            })
+        }
+        Err(e) => {
+            log::error!("性能测试失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 将查询当前的执行计划与保存的基线比较
+///
+/// 首次执行时保存执行计划作为基线；之后返回节点类型变化（如索引扫描变为顺序扫描）
+/// 和成本变化。使用 EXPLAIN（不带 ANALYZE），不会实际执行语句。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn compare_plans(
+    database: String,
+    sql: String,
+    updateBaseline: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::query::PlanComparison>, String> {
+    log::info!("========== 比较执行计划 ==========");
+    log::info!("数据库: {}", database);
+    log::info!("SQL: {}", sql);
+
+    let store = services::plan_baseline::PlanBaselineStore::new(get_data_dir()?.join("plan_baselines.json"));
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::plan_baseline::compare_plans(client, &store, &database, &sql, updateBaseline.unwrap_or(false)).await {
+        Ok(comparison) => {
+            let message = if comparison.baseline_created {
+                "已保存当前执行计划作为基线".to_string()
+            } else if comparison.plan_changed {
+                log::warn!("执行计划已变化，共 {} 处差异", comparison.changes.len());
+                format!(
+                    "执行计划与 {} 的基线相比发生了变化，估算成本变化 {:+.1}%",
+                    comparison.baseline_captured_at, comparison.cost_change_percent
+                )
+            } else {
+                format!(
+                    "执行计划结构与 {} 的基线相同，估算成本变化 {:+.1}%",
+                    comparison.baseline_captured_at, comparison.cost_change_percent
+                )
+            };
+            log::info!("{}", message);
+            Ok(ApiResponse {
+                success: true,
+                message,
+                data: Some(comparison),
+            })
+        }
+        Err(e) => {
+            log::error!("比较执行计划失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 查询模板文件的存储
+fn query_template_store() -> Result<services::query_templates::QueryTemplateStore, String> {
+    Ok(services::query_templates::QueryTemplateStore::new(get_data_dir()?.join("query_templates.json")))
+}
+
+/// 获取保存的查询模板
+#[tauri::command]
+async fn list_query_templates() -> Result<ApiResponse<Vec<services::query_templates::QueryTemplate>>, String> {
+    log::info!("========== 获取查询模板 ==========");
+
+    let templates = query_template_store()?.load()?;
+
+    log::info!("共 {} 个查询模板", templates.len());
+    Ok(ApiResponse {
+        success: true,
+        message: format!("共 {} 个查询模板", templates.len()),
+        data: Some(templates),
+    })
+}
+
+/// 保存查询模板，模板中使用 `:名称` 形式的参数
+///
+/// 未提供 id 时新建模板，否则更新已有的模板。
+#[tauri::command]
+async fn save_query_template(
+    id: Option<String>,
+    name: String,
+    sql: String,
+) -> Result<ApiResponse<services::query_templates::QueryTemplate>, String> {
+    log::info!("========== 保存查询模板 ==========");
+    log::info!("模板: {} ({:?})", name, id);
+    log::info!("SQL: {}", sql);
+
+    match query_template_store()?.upsert(id.as_deref(), &name, &sql) {
+        Ok(template) => {
+            log::info!("查询模板已保存: {}，参数: {:?}", template.id, template.parameters);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("查询模板 {} 已保存", template.name),
+                data: Some(template),
+            })
+        }
+        Err(e) => {
+            log::error!("保存查询模板失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 删除查询模板
+#[tauri::command]
+async fn delete_query_template(id: String) -> Result<ApiResponse<()>, String> {
+    log::info!("========== 删除查询模板 ==========");
+    log::info!("模板: {}", id);
+
+    query_template_store()?.delete(&id)?;
+
+    log::info!("查询模板已删除");
+    Ok(ApiResponse {
+        success: true,
+        message: "查询模板已删除".to_string(),
+        data: None,
+    })
+}
+
+/// 使用给定的参数值执行查询模板
+///
+/// 模板中的 `:名称` 参数被替换为绑定参数（`$1`、`$2`……），参数值不会拼接到 SQL 中。
+/// 每个参数都必须提供值，null 表示 SQL NULL。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn run_template(
+    database: String,
+    templateId: String,
+    params: HashMap<String, serde_json::Value>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<QueryResult>, String> {
+    log::info!("========== 执行查询模板 ==========");
+    log::info!("数据库: {}, 模板: {}", database, templateId);
+
+    let template = query_template_store()?.get(&templateId)?;
+    log::info!("SQL: {}", template.sql);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let result = match services::query_templates::run_template(client, &template, &params).await {
+        Ok(result) => result,
+        Err(e) => {
+            log::error!("执行查询模板失败: {}", e);
+            return Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            });
+        }
+    };
+
+    if result.result_type == models::query::QueryResultType::Error {
+        log::error!("查询模板执行失败: {:?}", result.error);
+        return Ok(ApiResponse {
+            success: false,
+            message: result.error.clone().unwrap_or_else(|| "SQL 执行失败".to_string()),
+            data: Some(result),
+        });
+    }
+
+    log::info!("查询模板执行完成，耗时: {} ms", result.duration_ms);
+    Ok(ApiResponse {
+        success: true,
+        message: "SQL 执行成功".to_string(),
+        data: Some(result),
+    })
+}
+
+/// 执行带 `$1..$N` 绑定参数的 SQL
+///
+/// `params[0]` 绑定到 `$1`，依此类推；参数值不会拼接到 SQL 中，类型由服务器推断。
+#[tauri::command]
+async fn execute_parameterized_sql(
+    database: String,
+    sql: String,
+    params: Vec<serde_json::Value>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<QueryResult>, String> {
+    log::info!("========== 执行参数化 SQL ==========");
+    log::info!("数据库: {}, 参数数量: {}", database, params.len());
+    log::info!("SQL: {}", sql);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let result = query_executor::execute_with_json_params(client, &sql, &params).await;
+
+    if result.result_type == models::query::QueryResultType::Error {
+        log::error!("参数化 SQL 执行失败: {:?}", result.error);
+        return Ok(ApiResponse {
+            success: false,
+            message: result.error.clone().unwrap_or_else(|| "SQL 执行失败".to_string()),
+            data: Some(result),
+        });
+    }
+
+    log::info!("参数化 SQL 执行完成，耗时: {} ms", result.duration_ms);
+    Ok(ApiResponse {
+        success: true,
+        message: "SQL 执行成功".to_string(),
+        data: Some(result),
+    })
+}
+
+/// 流式执行大结果集的 SELECT 查询
+///
+/// 结果不会一次性读入内存：每读取 `batchSize`（默认 500）行发送一次 `query-chunk` 事件，
+/// 第一批携带列信息；读取结束（或出错）后发送 `query-done` 事件，内容与命令返回值相同。
+/// `maxRows` 限制最多读取的行数。小结果集仍可使用 `execute_sql` 一次性返回。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn execute_sql_streaming(
+    app: tauri::AppHandle,
+    database: String,
+    sql: String,
+    batchSize: Option<u32>,
+    maxRows: Option<u64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::query::QueryStreamSummary>, String> {
+    log::info!("========== 流式执行 SQL ==========");
+    log::info!("数据库: {}, 每批: {:?}, 最多: {:?} 行", database, batchSize, maxRows);
+    log::info!("SQL: {}", sql);
+
+    let mut connection = state.get_or_connect(&database).await?;
+    let client = &mut **connection;
+
+    // 登记取消令牌，以便 cancel_all_queries 取消执行中的语句
+    let query_id = state.running_queries.lock().await.register(
+        &database,
+        client.cancel_token(),
+        get_routing_config().tls_settings(Endpoint::Write).clone(),
+    );
+    let result = query_executor::stream_select(
+        client,
+        &sql,
+        batchSize.unwrap_or(500) as usize,
+        maxRows,
+        |chunk| {
+            app.emit("query-chunk", chunk)
+                .map_err(|e| format!("发送数据事件失败: {}", e))
+        },
+    ).await;
+    state.running_queries.lock().await.unregister(query_id);
+
+    let response = match result {
+        Ok(summary) => {
+            log::info!("流式执行完成，共 {} 行，耗时: {} ms", summary.total_rows, summary.duration_ms);
+            ApiResponse {
+                success: true,
+                message: if summary.truncated {
+                    format!("已读取前 {} 行，结果未全部读取", summary.total_rows)
+                } else {
+                    format!("查询成功，共 {} 行", summary.total_rows)
+                },
+                data: Some(summary),
+            }
+        }
+        Err(e) => {
+            log::error!("流式执行 SQL 失败: {}", e);
+            ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            }
+        }
+    };
+
+    if let Err(e) = app.emit("query-done", &response) {
+        log::error!("发送 query-done 事件失败: {}", e);
+    }
+    Ok(response)
+}
+
+// Schema Management Commands
+
+/// Get complete table schema including columns, constraints, and indexes
+#[tauri::command]
+async fn get_table_schema(
+    database: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<models::schema::TableSchema, String> {
+    log::info!("========== 获取表结构 ==========");
+    log::info!("数据库: {}, Schema: {}, 表: {}", database, schema, table);
+    
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+    
+    let table_schema = services::schema_service::get_table_schema(client, &schema, &table).await?;
+    
+    log::info!("表结构获取完成");
+    Ok(table_schema)
+}
+
+/// Preview the values produced by each column default of a table
+///
+/// Used by the new-record form to pre-fill fields. Sequence and identity
+/// defaults are reported as "auto" instead of being evaluated.
+#[tauri::command]
+async fn preview_defaults(
+    database: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<models::schema::ColumnDefaultPreview>, String> {
+    log::info!("========== 预览列默认值 ==========");
+    log::info!("数据库: {}, Schema: {}, 表: {}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let previews = services::schema_service::preview_defaults(client, &schema, &table).await?;
+
+    log::info!("默认值预览完成，共 {} 列", previews.len());
+    Ok(previews)
+}
+
+/// Get the table comment and column comments, for tooltips in the explorer
+///
+/// Much cheaper than `get_table_schema` when only the descriptions are needed.
+#[tauri::command]
+async fn get_table_documentation(
+    database: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<models::schema::TableDocumentation, String> {
+    log::info!("========== 获取表注释 ==========");
+    log::info!("数据库: {}, Schema: {}, 表: {}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let documentation = services::schema_service::get_table_documentation(client, &schema, &table).await?;
+
+    log::info!("表注释获取完成，共 {} 列", documentation.columns.len());
+    Ok(documentation)
+}
+
+/// Generate a runnable DDL script (CREATE TABLE, indexes, comments) for an existing table
+#[tauri::command]
+async fn script_table(
+    database: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<models::schema::TableScript, String> {
+    log::info!("========== 生成建表脚本 ==========");
+    log::info!("数据库: {}, Schema: {}, 表: {}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let script = services::table_script::script_table(client, &schema, &table).await?;
+
+    for warning in &script.warnings {
+        log::warn!("建表脚本无法完整复现: {}", warning);
+    }
+    log::info!("建表脚本生成完成，长度 {} 字符", script.script.len());
+    Ok(script)
+}
+
+/// List the tablespaces of the cluster with their location and size
 #[tauri::command]
-async fn get_database_objects(
+async fn list_tablespaces(
     database: String,
-    object_type: String,
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<String>, String> {
-    log::info!("========== 获取数据库对象 ==========");
-    log::info!("数据库: {}, 对象类型: {}", database, object_type);
-    
-    let config = get_db_config();
-    let connection_string = format!(
-        "host={} port={} user={} password={} dbname={}",
-        config.host, config.port, config.user, config.password, database
+) -> Result<ApiResponse<Vec<models::schema::TablespaceInfo>>, String> {
+    log::info!("========== 列出表空间 ==========");
+    log::info!("数据库: {}", database);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::schema_service::list_tablespaces(client).await {
+        Ok(tablespaces) => {
+            log::info!("找到 {} 个表空间", tablespaces.len());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("找到 {} 个表空间", tablespaces.len()),
+                data: Some(tablespaces),
+            })
+        }
+        Err(e) => {
+            log::error!("列出表空间失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// List the views and materialized views of the database
+#[tauri::command]
+async fn list_views(
+    database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<models::schema::ViewInfo>>, String> {
+    log::info!("========== 列出视图 ==========");
+    log::info!("数据库: {}", database);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::schema_service::list_views(client).await {
+        Ok(views) => {
+            log::info!("找到 {} 个视图", views.len());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("找到 {} 个视图", views.len()),
+                data: Some(views),
+            })
+        }
+        Err(e) => {
+            log::error!("列出视图失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// Get the SELECT statement of a view or materialized view (`pg_get_viewdef`)
+#[tauri::command]
+async fn get_view_definition(
+    database: String,
+    schema: String,
+    view: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<String>, String> {
+    log::info!("========== 获取视图定义 ==========");
+    log::info!("数据库: {}, 视图: {}.{}", database, schema, view);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::schema_service::get_view_definition(client, &schema, &view).await {
+        Ok(definition) => Ok(ApiResponse {
+            success: true,
+            message: "视图定义获取成功".to_string(),
+            data: Some(definition),
+        }),
+        Err(e) => {
+            log::error!("获取视图定义失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// List the functions and procedures of a schema
+#[tauri::command]
+async fn list_functions(
+    database: String,
+    schema: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<models::schema::FunctionInfo>>, String> {
+    log::info!("========== 列出函数 ==========");
+    log::info!("数据库: {}, Schema: {}", database, schema);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::schema_service::list_functions(client, &schema).await {
+        Ok(functions) => {
+            log::info!("找到 {} 个函数", functions.len());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("找到 {} 个函数", functions.len()),
+                data: Some(functions),
+            })
+        }
+        Err(e) => {
+            log::error!("列出函数失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// Get the CREATE OR REPLACE statement of a function or procedure (`pg_get_functiondef`)
+///
+/// `arguments` is the argument list from `list_functions`, which tells overloads apart.
+#[tauri::command]
+async fn get_function_definition(
+    database: String,
+    schema: String,
+    name: String,
+    arguments: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<String>, String> {
+    log::info!("========== 获取函数定义 ==========");
+    log::info!("数据库: {}, 函数: {}.{}({})", database, schema, name, arguments);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::schema_service::get_function_definition(client, &schema, &name, &arguments).await {
+        Ok(definition) => Ok(ApiResponse {
+            success: true,
+            message: "函数定义获取成功".to_string(),
+            data: Some(definition),
+        }),
+        Err(e) => {
+            log::error!("获取函数定义失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// List the sequences of a schema with their current value and owning column
+#[tauri::command]
+async fn list_sequences(
+    database: String,
+    schema: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<models::schema::SequenceInfo>>, String> {
+    log::info!("========== 列出序列 ==========");
+    log::info!("数据库: {}, Schema: {}", database, schema);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::schema_service::list_sequences(client, &schema).await {
+        Ok(sequences) => {
+            log::info!("找到 {} 个序列", sequences.len());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("找到 {} 个序列", sequences.len()),
+                data: Some(sequences),
+            })
+        }
+        Err(e) => {
+            log::error!("列出序列失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// Set the next value of a sequence, e.g. to `MAX(id) + 1` after a bulk import
+#[tauri::command]
+async fn reset_sequence(
+    database: String,
+    schema: String,
+    sequence: String,
+    value: i64,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<()>, String> {
+    log::info!("========== 重置序列 ==========");
+    log::info!("数据库: {}, 序列: {}.{}, 下一个值: {}", database, schema, sequence, value);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::schema_service::reset_sequence(client, &schema, &sequence, value).await {
+        Ok(()) => Ok(ApiResponse {
+            success: true,
+            message: format!("序列 {}.{} 的下一个值已设为 {}", schema, sequence, value),
+            data: None,
+        }),
+        Err(e) => {
+            log::error!("重置序列失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// Get per-column constraints of a table for client-side validation of the edit form
+#[tauri::command]
+async fn get_edit_constraints(
+    database: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<models::schema::ColumnEditConstraints>, String> {
+    log::info!("========== 获取编辑约束 ==========");
+    log::info!("数据库: {}, Schema: {}, 表: {}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let constraints = services::schema_service::get_edit_constraints(client, &schema, &table).await?;
+
+    log::info!("编辑约束获取完成，共 {} 列", constraints.len());
+    Ok(constraints)
+}
+
+/// Get planner statistics (pg_stats) for each column of a table without scanning it
+#[tauri::command]
+async fn get_column_stats(
+    database: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::schema::TableColumnStats>, String> {
+    log::info!("========== 获取列统计信息 ==========");
+    log::info!("数据库: {}, Schema: {}, 表: {}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::column_stats::get_column_stats(client, &schema, &table).await {
+        Ok(stats) => {
+            log::info!("列统计信息获取完成，共 {} 列", stats.columns.len());
+            let message = match &stats.last_analyzed {
+                Some(time) => format!("统计信息来自 {} 的 ANALYZE，可能与当前数据不同", time),
+                None => "该表尚未执行过 ANALYZE，统计信息可能缺失".to_string(),
+            };
+            Ok(ApiResponse {
+                success: true,
+                message,
+                data: Some(stats),
+            })
+        }
+        Err(e) => {
+            log::error!("获取列统计信息失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// Get size, row estimate and last vacuum/analyze times of a table
+#[tauri::command]
+async fn get_table_stats(
+    database: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::schema::TableStats>, String> {
+    log::info!("========== 获取表统计信息 ==========");
+    log::info!("数据库: {}, Schema: {}, 表: {}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::table_stats::get_table_stats(client, &schema, &table).await {
+        Ok(stats) => {
+            log::info!(
+                "表统计信息获取完成，总大小 {} 字节，估算 {} 行",
+                stats.total_bytes,
+                stats.estimated_rows
+            );
+            Ok(ApiResponse {
+                success: true,
+                message: "表统计信息获取成功".to_string(),
+                data: Some(stats),
+            })
+        }
+        Err(e) => {
+            log::error!("获取表统计信息失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// Compare the schemas of two databases and list their differences
+///
+/// Reports tables present in only one database and, for tables present in
+/// both, column, constraint, and index differences.
+#[tauri::command]
+async fn diff_database_schemas(
+    source_database: String,
+    target_database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<models::schema::DatabaseSchemaDiff, String> {
+    log::info!("========== 比较数据库结构 ==========");
+    log::info!("源数据库: {}, 目标数据库: {}", source_database, target_database);
+
+    let source = state.get_or_connect(&source_database).await?;
+    let target = state.get_or_connect(&target_database).await?;
+
+    let report = services::schema_diff::diff_database_schemas(
+        &source,
+        &target,
+        &source_database,
+        &target_database,
+    ).await?;
+
+    log::info!(
+        "结构比较完成: 仅源库 {} 个表, 仅目标库 {} 个表, 结构不同 {} 个表",
+        report.only_in_source.len(),
+        report.only_in_target.len(),
+        report.changed_tables.len()
     );
+    Ok(report)
+}
+
+/// Create a new table based on table design
+#[tauri::command]
+async fn create_table(
+    database: String,
+    design: models::schema::TableDesign,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    log::info!("========== 创建表 ==========");
+    log::info!("数据库: {}, 表: {}.{}", database, design.schema, design.table_name);
     
-    let mut connections = state.connections.lock().await;
-    let connection_key = format!("{}:{}", config.host, database);
+    services::ddl_generator::validate_storage_parameter_names(
+        design.storage_parameters.keys().map(String::as_str),
+    )?;
     
-    if !connections.contains_key(&connection_key) {
-        let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls)
-            .await
-            .map_err(|e| format!("无法连接到数据库: {}", e))?;
-        
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                log::error!("数据库连接错误: {}", e);
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+    
+    // Generate DDL
+    let ddl = services::ddl_generator::generate_create_table(&design);
+    log::info!("生成的 DDL:\n{}", ddl);
+    
+    // Execute DDL
+    let result = query_executor::execute_sql(client, &ddl).await;
+    
+    if result.result_type == models::query::QueryResultType::Error {
+        let error_msg = result.error.unwrap_or_else(|| "未知错误".to_string());
+        log::error!("创建表失败: {}", error_msg);
+        return Err(error_msg);
+    }
+    
+    log::info!("表创建成功");
+    Ok(())
+}
+
+/// Alter an existing table based on table changes
+#[tauri::command]
+async fn alter_table(
+    database: String,
+    schema: String,
+    table: String,
+    mut changes: models::schema::TableChanges,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    log::info!("========== 修改表 ==========");
+    log::info!("数据库: {}, 表: {}.{}", database, schema, table);
+    
+    services::ddl_generator::validate_storage_parameter_names(
+        changes
+            .set_storage_parameters
+            .keys()
+            .chain(&changes.reset_storage_parameters)
+            .map(String::as_str),
+    )?;
+    
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+    
+    // 未提供原定义的列从数据库读取，以便只修改实际变化的属性，
+    // 并为安全的类型转换生成默认的 USING 子句
+    if changes.modified_columns.iter().any(|m| m.old_definition.is_none()) {
+        let table_schema = services::schema_service::get_table_schema(client, &schema, &table).await?;
+        for modification in &mut changes.modified_columns {
+            if modification.old_definition.is_none() {
+                modification.old_definition = table_schema
+                    .columns
+                    .iter()
+                    .find(|column| column.name == modification.old_name)
+                    .cloned();
             }
-        });
+        }
+    }
+    
+    // Generate ALTER TABLE statements
+    let statements = services::ddl_generator::generate_alter_table(&schema, &table, &changes);
+    log::info!("生成的 ALTER TABLE 语句数量: {}", statements.len());
+    
+    // Execute all statements
+    for (i, statement) in statements.iter().enumerate() {
+        log::info!("执行语句 {}: {}", i + 1, statement);
+        let result = query_executor::execute_sql(client, statement).await;
         
-        connections.insert(connection_key.clone(), client);
+        if result.result_type == models::query::QueryResultType::Error {
+            let error_msg = result.error.unwrap_or_else(|| "未知错误".to_string());
+            log::error!("修改表失败 (语句 {}): {}", i + 1, error_msg);
+            return Err(format!("语句 {} 失败: {}", i + 1, error_msg));
+        }
+    }
+    
+    // 表结构已变化，丢弃缓存的列类型
+    state.column_types.lock().await.invalidate(&database, &schema, &table);
+    
+    log::info!("表修改成功");
+    Ok(())
+}
+
+/// Create a schema in the target database
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn create_schema(
+    database: String,
+    name: String,
+    owner: Option<String>,
+    ifNotExists: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<()>, String> {
+    log::info!("========== 创建模式 ==========");
+    log::info!("数据库: {}, 模式: {}, 所有者: {:?}", database, name, owner);
+
+    if let Err(e) = services::ddl_generator::validate_schema_name(&name) {
+        return Ok(ApiResponse { success: false, message: e, data: None });
+    }
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let existed = services::schema_service::schema_exists(client, &name).await?;
+    if existed && !ifNotExists {
+        return Ok(ApiResponse {
+            success: false,
+            message: format!("模式 {} 已存在", name),
+            data: None,
+        });
+    }
+
+    let ddl = services::ddl_generator::generate_create_schema(&name, owner.as_deref(), ifNotExists);
+    log::info!("生成的 DDL: {}", ddl);
+
+    let result = query_executor::execute_sql(client, &ddl).await;
+    if result.result_type == models::query::QueryResultType::Error {
+        let error_msg = result.error.unwrap_or_else(|| "未知错误".to_string());
+        log::error!("创建模式失败: {}", error_msg);
+        return Ok(ApiResponse { success: false, message: error_msg, data: None });
+    }
+
+    let message = if existed {
+        format!("模式 {} 已存在，已跳过", name)
+    } else {
+        format!("模式 {} 创建成功", name)
+    };
+    log::info!("{}", message);
+    Ok(ApiResponse { success: true, message, data: None })
+}
+
+/// Drop a schema from the target database
+///
+/// Without `cascade`, a schema that still contains objects is not dropped and
+/// the objects are listed in the message.
+#[tauri::command]
+async fn drop_schema(
+    database: String,
+    name: String,
+    cascade: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<String>>, String> {
+    log::info!("========== 删除模式 ==========");
+    log::info!("数据库: {}, 模式: {}, CASCADE: {}", database, name, cascade);
+
+    if let Err(e) = services::ddl_generator::validate_schema_name(&name) {
+        return Ok(ApiResponse { success: false, message: e, data: None });
+    }
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    if !services::schema_service::schema_exists(client, &name).await? {
+        return Ok(ApiResponse {
+            success: false,
+            message: format!("模式 {} 不存在", name),
+            data: None,
+        });
+    }
+
+    let dependents = services::schema_service::get_schema_dependents(client, &name).await?;
+    if !dependents.is_empty() && !cascade {
+        log::warn!("模式 {} 中仍有 {} 个对象，未删除", name, dependents.len());
+        return Ok(ApiResponse {
+            success: false,
+            message: format!(
+                "模式 {} 中仍有 {} 个对象（{}），请使用 cascade 一并删除",
+                name,
+                dependents.len(),
+                dependents.join(", ")
+            ),
+            data: Some(dependents),
+        });
+    }
+
+    let ddl = services::ddl_generator::generate_drop_schema(&name, cascade);
+    log::info!("生成的 DDL: {}", ddl);
+
+    let result = query_executor::execute_sql(client, &ddl).await;
+    if result.result_type == models::query::QueryResultType::Error {
+        let error_msg = result.error.unwrap_or_else(|| "未知错误".to_string());
+        log::error!("删除模式失败: {}", error_msg);
+        return Ok(ApiResponse { success: false, message: error_msg, data: None });
+    }
+
+    // 模式中的表已被删除，丢弃缓存的列类型
+    if !dependents.is_empty() {
+        state.column_types.lock().await.invalidate_schema(&database, &name);
     }
+
+    log::info!("模式 {} 删除成功，同时删除了 {} 个对象", name, dependents.len());
+    Ok(ApiResponse {
+        success: true,
+        message: format!("模式 {} 删除成功，同时删除了 {} 个对象", name, dependents.len()),
+        data: Some(dependents),
+    })
+}
+
+/// Get database objects for auto-completion
+#[tauri::command]
+async fn get_database_objects(
+    database: String,
+    object_type: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    log::info!("========== 获取数据库对象 ==========");
+    log::info!("数据库: {}, 对象类型: {}", database, object_type);
     
-    let client = connections.get(&connection_key)
-        .ok_or_else(|| "无法获取数据库连接".to_string())?;
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
     
     let objects = services::schema_service::get_database_objects(client, &object_type).await?;
     
@@ -461,187 +2081,1083 @@ async fn get_database_objects(
     Ok(objects)
 }
 
+/// Search tables, views, functions and columns by substring or regular expression
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn search_objects(
+    database: String,
+    pattern: String,
+    regex: Option<bool>,
+    kinds: Option<Vec<models::schema::SearchObjectKind>>,
+    includeComments: Option<bool>,
+    page: Option<u32>,
+    pageSize: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<models::schema::ObjectSearchPage, String> {
+    log::info!("========== 搜索数据库对象 ==========");
+    log::info!("数据库: {}, 搜索: {}, 正则: {:?}, 类型: {:?}", database, pattern, regex, kinds);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let search = services::object_search::ObjectSearch {
+        pattern,
+        regex: regex.unwrap_or(false),
+        kinds: kinds.unwrap_or_default(),
+        include_comments: includeComments.unwrap_or(false),
+        page: page.unwrap_or(1),
+        page_size: pageSize.unwrap_or(services::object_search::DEFAULT_PAGE_SIZE),
+    };
+    let result = services::object_search::search_objects(client, &search).await?;
+
+    log::info!("找到 {} 个对象，返回第 {} 页 {} 个", result.total, result.page, result.matches.len());
+    Ok(result)
+}
+
 // 使用 pg_dump 导出数据库
+//
+// `format` 默认为自定义格式（.backup）；纯文本 SQL 导出为 .sql 文件，目录格式导出为一个目录，tar 格式为 .tar 文件。
+// `tables` 非空时只导出这些表（`schema.table`，省略 schema 时为 public）；`schema_only` 只导出结构，
+// `data_only` 只导出数据，两者不能同时指定。
+// pg_dump 输出的每行进度信息以 `backup-progress` 事件发送，结束时再发送一个带有结果的事件。
 #[tauri::command]
-async fn export_database(database: String) -> Result<ApiResponse<String>, String> {
+async fn export_database(
+    app: tauri::AppHandle,
+    database: String,
+    format: Option<models::data::DumpFormat>,
+    tables: Option<Vec<String>>,
+    schema_only: Option<bool>,
+    data_only: Option<bool>,
+) -> Result<ApiResponse<String>, String> {
     log::info!("========== 开始导出数据库 (pg_dump) ==========");
-    log::info!("数据库: {}", database);
+    let format = format.unwrap_or_default();
+    let tables = tables.unwrap_or_default();
+    let schema_only = schema_only.unwrap_or(false);
+    let data_only = data_only.unwrap_or(false);
+    log::info!(
+        "数据库: {}, 格式: {:?}, 表: {:?}, 仅结构: {}, 仅数据: {}",
+        database, format, tables, schema_only, data_only
+    );
+
+    if schema_only && data_only {
+        return Err("schema_only 和 data_only 不能同时指定".to_string());
+    }
     
     let config = get_db_config();
     let export_dir = get_export_dir()?;
     
+    // 文件名：{数据库名}[_tables][_schema|_data]_{时间戳}{扩展名}
+    let mut name_parts = vec![database.clone()];
+    if !tables.is_empty() {
+        name_parts.push("tables".to_string());
+    }
+    if schema_only {
+        name_parts.push("schema".to_string());
+    } else if data_only {
+        name_parts.push("data".to_string());
+    }
     let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("{}_{}.backup", database, timestamp);
+    name_parts.push(timestamp.to_string());
+    let filename = format!("{}{}", name_parts.join("_"), format.extension());
     let file_path = export_dir.join(&filename);
     
     log::info!("导出文件: {}", file_path.display());
+    let file_path_str = file_path.to_string_lossy().to_string();
+    let emit = |event: services::pg_tools::ProgressEvent| {
+        if let Err(e) = app.emit("backup-progress", event) {
+            log::warn!("发送导出进度事件失败: {}", e);
+        }
+    };
+
+    let result = async {
+        // 密码通过临时 .pgpass 文件传给 pg_dump，pg_dump 结束后删除
+        let pgpass = services::pgpass::PgPassFile::new(&config.user, &config.password)?;
+
+        // 使用 pg_dump 导出（目录格式时由 pg_dump 创建目录）
+        let pg_dump = get_pg_tool("pg_dump");
+        let mut command = pg_dump.command();
+        command
+            .arg("-h").arg(&config.host)
+            .arg("-p").arg(&config.port)
+            .arg("-U").arg(&config.user)
+            .arg("-F").arg(format.pg_dump_flag())
+            .arg("-b")  // 包含大对象
+            .arg("-v")  // 详细模式，进度信息写到标准错误
+            .arg("-f").arg(&file_path);
+        for table in &tables {
+            // 加引号的名称按原样匹配，不做大小写转换和通配符展开
+            let (schema, name) = resolve_table_name(None, table);
+            command.arg("-t").arg(services::sql_utils::qualified_name(&schema, &name));
+        }
+        if schema_only {
+            command.arg("--schema-only");
+        }
+        if data_only {
+            command.arg("--data-only");
+        }
+        command
+            .arg(&database)
+            .env_remove("PGPASSWORD")
+            .env("PGPASSFILE", pgpass.path())
+            .envs(config.tls_settings().libpq_env());
+
+        let output = pg_dump
+            .run_with_progress(command, |line| {
+                log::info!("pg_dump: {}", line);
+                emit(services::pg_tools::ProgressEvent::line(&database, &file_path_str, line));
+            })
+            .await?;
+        if !output.success {
+            return Err(format!("导出失败: {}", output.stderr));
+        }
+        Ok::<(), String>(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        log::error!("pg_dump 失败: {}", e);
+        emit(services::pg_tools::ProgressEvent::finished(&database, &file_path_str, false, &e));
+        return Err(e);
+    }
     
-    // 使用 pg_dump 导出（自定义格式，压缩）
-    let output = std::process::Command::new("pg_dump")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-F").arg("c")  // 自定义格式（压缩）
-        .arg("-b")  // 包含大对象
-        .arg("-v")  // 详细模式
-        .arg("-f").arg(&file_path)
-        .arg(&database)
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法执行 pg_dump: {}. 请确保 PostgreSQL 已安装并且 pg_dump 在 PATH 中", e))?;
+    // 获取文件大小（目录格式为目录中各文件的总大小）
+    let size: u64 = match std::fs::read_dir(&file_path) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok()?.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum(),
+        Err(_) => std::fs::metadata(&file_path).map(|metadata| metadata.len()).unwrap_or(0),
+    };
+    log::info!("导出文件大小: {} KB", size / 1024);
     
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        log::error!("pg_dump 失败: {}", stderr);
-        return Err(format!("导出失败: {}", stderr));
+    log::info!("========== 导出完成 ==========");
+
+    let scope = match (tables.len(), schema_only, data_only) {
+        (0, false, false) => "数据库".to_string(),
+        (0, true, _) => "数据库结构".to_string(),
+        (0, _, true) => "数据库数据".to_string(),
+        (n, true, _) => format!("{} 个表的结构", n),
+        (n, _, true) => format!("{} 个表的数据", n),
+        (n, false, false) => format!("{} 个表", n),
+    };
+
+    let message = format!(
+        "{}已导出到 {}（文件名格式：{{数据库名}}[_tables][_schema|_data]_{{时间戳}}{}）",
+        scope,
+        file_path.display(),
+        format.extension()
+    );
+    emit(services::pg_tools::ProgressEvent::finished(&database, &file_path_str, true, &message));
+
+    Ok(ApiResponse {
+        success: true,
+        message,
+        data: Some(file_path_str),
+    })
+}
+
+/// Export the structure of a whole database (no data) as a dependency-ordered DDL script
+#[tauri::command]
+async fn export_schema_ddl(
+    database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<String>, String> {
+    log::info!("========== 导出数据库结构脚本 ==========");
+    log::info!("数据库: {}", database);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let script = services::schema_export::script_database(client, &database).await?;
+    for warning in &script.warnings {
+        log::warn!("结构脚本无法完整复现: {}", warning);
     }
+
+    let export_dir = get_export_dir()?;
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let file_path = export_dir.join(format!("{}_schema_{}.sql", database, timestamp));
+    std::fs::write(&file_path, &script.script)
+        .map_err(|e| format!("无法写入文件 {}: {}", file_path.display(), e))?;
+
+    log::info!("结构脚本已导出到 {}，长度 {} 字符", file_path.display(), script.script.len());
+
+    let message = if script.warnings.is_empty() {
+        format!("数据库结构已导出到 {}", file_path.display())
+    } else {
+        format!(
+            "数据库结构已导出到 {}，{} 个对象无法完整复现，详见脚本开头的警告",
+            file_path.display(),
+            script.warnings.len()
+        )
+    };
+
+    Ok(ApiResponse {
+        success: true,
+        message,
+        data: Some(file_path.to_string_lossy().to_string()),
+    })
+}
+
+/// 将单张表导出为 CSV 文件（含表头），保存到导出目录
+#[tauri::command]
+async fn export_table_csv(
+    database: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::data::TableExportResult>, String> {
+    log::info!("========== 导出表为 CSV ==========");
+    log::info!("数据库: {}, 表: {}.{}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let file_path = get_export_dir()?.join(format!("{}.csv", export_file_stem(&database, &schema, &table)));
+    log::info!("导出文件: {}", file_path.display());
+
+    match services::data_exporter::export_table_csv(client, &schema, &table, &file_path).await {
+        Ok(result) => {
+            log::info!("CSV 导出完成，共 {} 行，文件大小: {} 字节", result.rows, result.bytes);
+            Ok(ApiResponse {
+                success: true,
+                message: format!(
+                    "表 {}.{} 已导出到 {}，共 {} 行，{} 字节",
+                    schema, table, result.file_path, result.rows, result.bytes
+                ),
+                data: Some(result),
+            })
+        }
+        Err(e) => {
+            log::error!("导出 CSV 失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 将单张表导出为 JSON 文件（对象数组），保存到导出目录
+#[tauri::command]
+async fn export_table_json(
+    database: String,
+    schema: String,
+    table: String,
+    pretty: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::data::TableExportResult>, String> {
+    log::info!("========== 导出表为 JSON ==========");
+    log::info!("数据库: {}, 表: {}.{}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let file_path = get_export_dir()?.join(format!("{}.json", export_file_stem(&database, &schema, &table)));
+    log::info!("导出文件: {}", file_path.display());
+
+    match services::data_exporter::export_table_json(client, &schema, &table, &file_path, pretty).await {
+        Ok(result) => {
+            log::info!("JSON 导出完成，共 {} 行，文件大小: {} 字节", result.rows, result.bytes);
+            Ok(ApiResponse {
+                success: true,
+                message: format!(
+                    "表 {}.{} 已导出到 {}，共 {} 行，{} 字节",
+                    schema, table, result.file_path, result.rows, result.bytes
+                ),
+                data: Some(result),
+            })
+        }
+        Err(e) => {
+            log::error!("导出 JSON 失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 单表导出文件名（不含扩展名）：`<数据库>_<模式>.<表>_<时间>`
+///
+/// 表名中可能包含路径分隔符等字符，文件名中替换为下划线。
+fn export_file_stem(database: &str, schema: &str, table: &str) -> String {
+    let name: String = format!("{}.{}", schema, table)
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    format!("{}_{}_{}", database, name, timestamp)
+}
+
+// 使用 pg_restore 导入数据库
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn import_database(
+    app: tauri::AppHandle,
+    filePath: String,
+    database: String,
+    drop_existing: bool
+) -> Result<ApiResponse<models::data::ImportResult>, String> {
+    log::info!("========== 开始导入数据库 (pg_restore) ==========");
+    log::info!("文件: {}", filePath);
+    log::info!("目标数据库: {}", database);
+    log::info!("覆盖已有数据库: {}", drop_existing);
     
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    log::info!("pg_dump 输出: {}", stderr);
-    
-    // 获取文件大小
-    if let Ok(metadata) = std::fs::metadata(&file_path) {
-        let size_kb = metadata.len() / 1024;
-        log::info!("导出文件大小: {} KB", size_kb);
+    let config = get_db_config();
+    let path = PathBuf::from(&filePath);
+
+    if !path.exists() {
+        return Err(format!("文件不存在: {}", filePath));
+    }
+
+    let emit = |event: services::pg_tools::ProgressEvent| {
+        if let Err(e) = app.emit("restore-progress", event) {
+            log::warn!("发送导入进度事件失败: {}", e);
+        }
+    };
+
+    let result = async {
+        // 密码通过临时 .pgpass 文件传给 psql 和 pg_restore，导入结束后删除
+        let pgpass = services::pgpass::PgPassFile::new(&config.user, &config.password)?;
+        let psql = get_pg_tool("psql");
+        let pg_restore = get_pg_tool("pg_restore");
+
+        // 连接到 postgres 数据库来创建目标数据库
+        let psql_check = psql.command()
+            .arg("-h").arg(&config.host)
+            .arg("-p").arg(&config.port)
+            .arg("-U").arg(&config.user)
+            .arg("-d").arg("postgres")
+            .arg("-t")
+            .arg("-c").arg(format!("SELECT 1 FROM pg_database WHERE datname='{}'", database))
+            .env_remove("PGPASSWORD")
+            .env("PGPASSFILE", pgpass.path())
+            .envs(config.tls_settings().libpq_env())
+            .output()
+            .map_err(|e| psql.error(e))?;
+
+        let db_exists = String::from_utf8_lossy(&psql_check.stdout).trim().contains("1");
+
+        if db_exists {
+            if !drop_existing {
+                // 不是错误：返回标记，由前端向用户确认后以 drop_existing = true 重新导入
+                return Ok(models::data::ImportResult::refused_existing(&database));
+            }
+
+            // 删除前记录现有数据量，便于误删后核对
+            let row_count_output = psql.command()
+                .arg("-h").arg(&config.host)
+                .arg("-p").arg(&config.port)
+                .arg("-U").arg(&config.user)
+                .arg("-d").arg(&database)
+                .arg("-t")
+                .arg("-A")
+                .arg("-c").arg("SELECT count(*), COALESCE(sum(n_live_tup), 0) FROM pg_stat_user_tables")
+                .env_remove("PGPASSWORD")
+                .env("PGPASSFILE", pgpass.path())
+                .envs(config.tls_settings().libpq_env())
+                .output();
+
+            match row_count_output {
+                Ok(output) if output.status.success() => {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    let mut counts = stdout.trim().split('|');
+                    log::warn!(
+                        "即将删除已有数据库 {}: {} 个表，约 {} 行数据",
+                        database,
+                        counts.next().unwrap_or("?"),
+                        counts.next().unwrap_or("?")
+                    );
+                }
+                Ok(output) => {
+                    log::warn!("无法统计数据库 {} 的行数: {}", database, String::from_utf8_lossy(&output.stderr).trim());
+                }
+                Err(e) => {
+                    log::warn!("无法统计数据库 {} 的行数: {}", database, psql.error(e));
+                }
+            }
+
+            log::info!("数据库 {} 已存在，正在删除...", database);
+        
+            // 终止所有连接
+            let _ = psql.command()
+                .arg("-h").arg(&config.host)
+                .arg("-p").arg(&config.port)
+                .arg("-U").arg(&config.user)
+                .arg("-d").arg("postgres")
+                .arg("-c").arg(format!(
+                    "SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE datname = '{}' AND pid <> pg_backend_pid()",
+                    database
+                ))
+                .env_remove("PGPASSWORD")
+                .env("PGPASSFILE", pgpass.path())
+                .envs(config.tls_settings().libpq_env())
+                .output();
+
+            // 删除数据库
+            let drop_output = psql.command()
+                .arg("-h").arg(&config.host)
+                .arg("-p").arg(&config.port)
+                .arg("-U").arg(&config.user)
+                .arg("-d").arg("postgres")
+                .arg("-c").arg(format!("DROP DATABASE IF EXISTS \"{}\"", database))
+                .env_remove("PGPASSWORD")
+                .env("PGPASSFILE", pgpass.path())
+                .envs(config.tls_settings().libpq_env())
+                .output()
+                .map_err(|e| format!("无法删除数据库: {}", psql.error(e)))?;
+
+            if !drop_output.status.success() {
+                let stderr = String::from_utf8_lossy(&drop_output.stderr);
+                log::warn!("删除数据库警告: {}", stderr);
+            }
+        }
+
+        // 创建新数据库
+        log::info!("创建数据库 {}...", database);
+        let create_output = psql.command()
+            .arg("-h").arg(&config.host)
+            .arg("-p").arg(&config.port)
+            .arg("-U").arg(&config.user)
+            .arg("-d").arg("postgres")
+            .arg("-c").arg(format!("CREATE DATABASE \"{}\"", database))
+            .env_remove("PGPASSWORD")
+            .env("PGPASSFILE", pgpass.path())
+            .envs(config.tls_settings().libpq_env())
+            .output()
+            .map_err(|e| format!("无法创建数据库: {}", psql.error(e)))?;
+
+        if !create_output.status.success() {
+            let stderr = String::from_utf8_lossy(&create_output.stderr);
+            log::error!("创建数据库失败: {}", stderr);
+            return Err(format!("创建数据库失败: {}", stderr));
+        }
+
+        // 使用 pg_restore 导入
+        log::info!("正在导入数据...");
+        let mut command = pg_restore.command();
+        command
+            .arg("-h").arg(&config.host)
+            .arg("-p").arg(&config.port)
+            .arg("-U").arg(&config.user)
+            .arg("-d").arg(&database)
+            .arg("-v")  // 详细模式，进度信息写到标准错误
+            .arg("--no-owner")  // 不恢复所有权
+            .arg("--no-acl")  // 不恢复访问权限
+            .arg(&filePath)
+            .env_remove("PGPASSWORD")
+            .env("PGPASSFILE", pgpass.path())
+            .envs(config.tls_settings().libpq_env());
+
+        let restore_output = pg_restore
+            .run_with_progress(command, |line| {
+                log::info!("pg_restore: {}", line);
+                emit(services::pg_tools::ProgressEvent::line(&database, &filePath, line));
+            })
+            .await?;
+
+        let mut import_result = models::data::ImportResult::from_pg_restore(&database, &restore_output.stderr);
+        import_result.database_exists = db_exists;
+        if !restore_output.success {
+            // 非零退出但没有识别出错误行时（输出格式不同的版本），至少计为一个错误
+            import_result.errors = import_result.errors.max(1);
+            log::warn!(
+                "pg_restore 返回非零状态码: {} 个错误, {} 个警告",
+                import_result.errors, import_result.warnings
+            );
+        }
+
+        Ok::<_, String>(import_result)
+    }
+    .await;
+
+    let import_result = match result {
+        Ok(import_result) => import_result,
+        Err(e) => {
+            log::error!("导入失败: {}", e);
+            emit(services::pg_tools::ProgressEvent::finished(&database, &filePath, false, &e));
+            return Err(e);
+        }
+    };
+
+    if import_result.database_exists && !drop_existing {
+        let message = format!(
+            "数据库 {} 已存在。导入会删除并重新创建该数据库，如确认覆盖，请设置 drop_existing 为 true",
+            database
+        );
+        log::warn!("{}", message);
+        emit(services::pg_tools::ProgressEvent::finished(&database, &filePath, false, &message));
+        return Ok(ApiResponse {
+            success: false,
+            message,
+            data: Some(import_result),
+        });
+    }
+
+    log::info!("========== 导入完成 ==========");
+
+    // 有错误时部分对象可能没有导入，但数据库已创建，仍然返回统计结果
+    let success = import_result.errors == 0;
+    let message = if success && import_result.warnings == 0 {
+        format!("数据库 {} 导入成功", database)
+    } else if success {
+        format!("数据库 {} 导入成功，有 {} 个警告", database, import_result.warnings)
+    } else {
+        format!(
+            "数据库 {} 导入完成，但有 {} 个错误、{} 个警告，部分对象或数据可能没有导入，请查看日志",
+            database, import_result.errors, import_result.warnings
+        )
+    };
+    emit(services::pg_tools::ProgressEvent::finished(&database, &filePath, success, &message));
+
+    Ok(ApiResponse {
+        success,
+        message,
+        data: Some(import_result),
+    })
+}
+
+/// 将 CSV 文件导入到已有的表
+///
+/// Copy 模式下任何错误行都会使整个导入回滚，并报告出错的行号；
+/// Staged 模式会导入所有有效行，并返回被拒绝的行及其原始值。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn import_csv(
+    filePath: String,
+    database: String,
+    schema: String,
+    table: String,
+    has_header: bool,
+    mode: Option<CsvImportMode>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<CsvImportResult>, String> {
+    log::info!("========== 导入 CSV ==========");
+    log::info!("文件: {}, 数据库: {}, 表: {}.{}", filePath, database, schema, table);
+
+    let data = std::fs::read(&filePath)
+        .map_err(|e| format!("无法读取文件 {}: {}", filePath, e))?;
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let result = services::csv_import::import_csv(
+        client,
+        &schema,
+        &table,
+        &data,
+        has_header,
+        mode.unwrap_or_default(),
+    ).await;
+
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            return Err(match restore_triggers_after_failure(&state, client, &database, &schema, &table).await {
+                Some(note) => format!("{}。{}", e, note),
+                None => e,
+            });
+        }
+    };
+
+    log::info!("CSV 导入完成: 导入 {} 行, 拒绝 {} 行", result.rows_loaded, result.rejected_rows.len());
+
+    let restore_note = if result.rolled_back {
+        restore_triggers_after_failure(&state, client, &database, &schema, &table).await
+    } else {
+        None
+    };
+
+    let message = match result.rejected_rows.first() {
+        Some(row) if result.rolled_back => {
+            format!("第 {} 行数据有误，导入已回滚: {}", row.row_number, row.error)
+        }
+        Some(_) => format!(
+            "导入 {} 行，拒绝 {} 行",
+            result.rows_loaded,
+            result.rejected_rows.len()
+        ),
+        None => format!("成功导入 {} 行", result.rows_loaded),
+    };
+    let message = match restore_note {
+        Some(note) => format!("{}。{}", message, note),
+        None => message,
+    };
+
+    Ok(ApiResponse {
+        success: result.rejected_rows.is_empty(),
+        message,
+        data: Some(result),
+    })
+}
+
+/// 以流的方式将 CSV 文件导入到已有的表，返回导入的行数
+///
+/// 文件按块发送给 COPY，不会一次性读入内存，适合大文件；任何错误都会回滚整个导入。
+/// `import_csv` 会读入整个文件，以便返回被拒绝行的原始内容，并支持 Staged 模式。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn import_table_csv(
+    filePath: String,
+    database: String,
+    schema: String,
+    table: String,
+    has_header: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<u64>, String> {
+    log::info!("========== 导入 CSV 文件 ==========");
+    log::info!("文件: {}, 数据库: {}, 表: {}.{}", filePath, database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let result = services::csv_import::import_csv_file(
+        client,
+        &schema,
+        &table,
+        std::path::Path::new(&filePath),
+        has_header,
+    ).await;
+
+    match result {
+        Ok(rows_loaded) => {
+            log::info!("CSV 文件导入完成: 导入 {} 行", rows_loaded);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("成功导入 {} 行", rows_loaded),
+                data: Some(rows_loaded),
+            })
+        }
+        Err(e) => Err(match restore_triggers_after_failure(&state, client, &database, &schema, &table).await {
+            Some(note) => format!("{}。{}", e, note),
+            None => e,
+        }),
+    }
+}
+
+/// 将表数据生成为 INSERT 语句脚本
+#[tauri::command]
+async fn generate_insert_script(
+    database: String,
+    schema: String,
+    table: String,
+    where_clause: Option<String>,
+    limit: Option<i64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<String>, String> {
+    log::info!("========== 生成 INSERT 脚本 ==========");
+    log::info!("数据库: {}, 表: {}.{}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::data_exporter::generate_insert_script(
+        client,
+        &schema,
+        &table,
+        where_clause.as_deref(),
+        limit,
+    ).await {
+        Ok(script) => Ok(ApiResponse {
+            success: true,
+            message: "INSERT 脚本生成成功".to_string(),
+            data: Some(script),
+        }),
+        Err(e) => {
+            log::error!("生成 INSERT 脚本失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 获取锁信息以及阻塞关系（哪个会话在等待哪个会话）
+#[tauri::command]
+async fn get_locks(
+    database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::monitoring::LockReport>, String> {
+    log::info!("========== 查询锁信息 ==========");
+    log::info!("数据库: {}", database);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::activity_monitor::get_locks(client).await {
+        Ok(report) => {
+            log::info!("共 {} 个锁, {} 个阻塞关系", report.locks.len(), report.blocking.len());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("发现 {} 个阻塞关系", report.blocking.len()),
+                data: Some(report),
+            })
+        }
+        Err(e) => {
+            log::error!("查询锁信息失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 获取复制状态（主库/备库角色、复制延迟、已连接的备库）
+#[tauri::command]
+async fn get_replication_status(
+    database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<models::monitoring::ReplicationStatus>, String> {
+    log::info!("========== 查询复制状态 ==========");
+    log::info!("数据库: {}", database);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::activity_monitor::get_replication_status(client).await {
+        Ok(status) => {
+            let message = if !status.configured {
+                "未配置复制".to_string()
+            } else if status.role == models::monitoring::ServerRole::Standby {
+                "当前连接的服务器为备库".to_string()
+            } else {
+                format!("当前连接的服务器为主库，已连接 {} 个备库", status.standbys.len())
+            };
+            log::info!("{}", message);
+            Ok(ApiResponse {
+                success: true,
+                message,
+                data: Some(status),
+            })
+        }
+        Err(e) => {
+            log::error!("查询复制状态失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
     }
-    
-    log::info!("========== 导出完成 ==========");
+}
 
-    Ok(ApiResponse {
-        success: true,
-        message: format!("数据库已导出到 {}", file_path.display()),
-        data: Some(file_path.to_string_lossy().to_string()),
-    })
+/// 列出连接到数据库的会话及其正在执行的查询（不包括本工具执行查询的连接）
+#[tauri::command]
+async fn list_active_sessions(
+    database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<models::monitoring::ActiveSession>>, String> {
+    log::info!("========== 查询活动会话 ==========");
+    log::info!("数据库: {}", database);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::activity_monitor::list_active_sessions(client).await {
+        Ok(sessions) => {
+            let active = sessions
+                .iter()
+                .filter(|session| session.state.as_deref() == Some("active"))
+                .count();
+            log::info!("共 {} 个会话, {} 个正在执行查询", sessions.len(), active);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("共 {} 个会话，{} 个正在执行查询", sessions.len(), active),
+                data: Some(sessions),
+            })
+        }
+        Err(e) => {
+            log::error!("查询活动会话失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
 }
 
-// 使用 pg_restore 导入数据库
+/// 终止指定的后端进程（会话）
 #[tauri::command]
-#[allow(non_snake_case)]
-async fn import_database(
-    filePath: String,
-    database: String
+async fn terminate_session(
+    database: String,
+    pid: i32,
+    state: tauri::State<'_, AppState>,
 ) -> Result<ApiResponse<()>, String> {
-    log::info!("========== 开始导入数据库 (pg_restore) ==========");
-    log::info!("文件: {}", filePath);
-    log::info!("目标数据库: {}", database);
-    
-    let config = get_db_config();
-    let path = PathBuf::from(&filePath);
+    log::info!("========== 终止会话 ==========");
+    log::info!("数据库: {}, PID: {}", database, pid);
 
-    if !path.exists() {
-        return Err(format!("文件不存在: {}", filePath));
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::activity_monitor::terminate_backend(client, pid).await {
+        Ok(true) => Ok(ApiResponse {
+            success: true,
+            message: format!("会话 {} 已终止", pid),
+            data: None,
+        }),
+        Ok(false) => Ok(ApiResponse {
+            success: false,
+            message: format!("会话 {} 不存在或无法终止", pid),
+            data: None,
+        }),
+        Err(e) => {
+            log::error!("终止会话失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
     }
+}
 
-    // 连接到 postgres 数据库来创建目标数据库
-    let psql_check = std::process::Command::new("psql")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg("postgres")
-        .arg("-t")
-        .arg("-c").arg(format!("SELECT 1 FROM pg_database WHERE datname='{}'", database))
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法执行 psql: {}. 请确保 PostgreSQL 已安装并且 psql 在 PATH 中", e))?;
+/// 查找未使用和冗余的索引，返回候选的 DROP INDEX 语句
+#[tauri::command]
+async fn find_redundant_indexes(
+    database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<models::monitoring::RedundantIndex>>, String> {
+    log::info!("========== 查找冗余索引 ==========");
+    log::info!("数据库: {}", database);
 
-    let db_exists = String::from_utf8_lossy(&psql_check.stdout).trim().contains("1");
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
 
-    if db_exists {
-        log::info!("数据库 {} 已存在，正在删除...", database);
-        
-        // 终止所有连接
-        let _ = std::process::Command::new("psql")
-            .arg("-h").arg(&config.host)
-            .arg("-p").arg(&config.port)
-            .arg("-U").arg(&config.user)
-            .arg("-d").arg("postgres")
-            .arg("-c").arg(format!(
-                "SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE datname = '{}' AND pid <> pg_backend_pid()",
-                database
-            ))
-            .env("PGPASSWORD", &config.password)
-            .output();
+    match services::index_advisor::find_redundant_indexes(client).await {
+        Ok(candidates) => {
+            log::info!("发现 {} 个候选索引", candidates.len());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("发现 {} 个可删除的候选索引", candidates.len()),
+                data: Some(candidates),
+            })
+        }
+        Err(e) => {
+            log::error!("查找冗余索引失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
 
-        // 删除数据库
-        let drop_output = std::process::Command::new("psql")
-            .arg("-h").arg(&config.host)
-            .arg("-p").arg(&config.port)
-            .arg("-U").arg(&config.user)
-            .arg("-d").arg("postgres")
-            .arg("-c").arg(format!("DROP DATABASE IF EXISTS \"{}\"", database))
-            .env("PGPASSWORD", &config.password)
-            .output()
-            .map_err(|e| format!("无法删除数据库: {}", e))?;
+/// 列出模式中每个索引的扫描次数和大小，并标记可能未使用和重复的索引
+#[tauri::command]
+async fn get_index_usage(
+    database: String,
+    schema: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<models::monitoring::IndexUsage>>, String> {
+    log::info!("========== 获取索引使用情况 ==========");
+    log::info!("数据库: {}, Schema: {}", database, schema);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
 
-        if !drop_output.status.success() {
-            let stderr = String::from_utf8_lossy(&drop_output.stderr);
-            log::warn!("删除数据库警告: {}", stderr);
+    match services::index_advisor::get_index_usage(client, &schema).await {
+        Ok(indexes) => {
+            let unused = indexes.iter().filter(|index| index.possibly_unused).count();
+            let duplicates = indexes.iter().filter(|index| index.duplicate_of.is_some()).count();
+            log::info!("共 {} 个索引，{} 个可能未使用，{} 个重复", indexes.len(), unused, duplicates);
+            Ok(ApiResponse {
+                success: true,
+                message: format!(
+                    "共 {} 个索引，其中 {} 个可能未使用，{} 个重复",
+                    indexes.len(),
+                    unused,
+                    duplicates
+                ),
+                data: Some(indexes),
+            })
+        }
+        Err(e) => {
+            log::error!("获取索引使用情况失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
         }
     }
+}
 
-    // 创建新数据库
-    log::info!("创建数据库 {}...", database);
-    let create_output = std::process::Command::new("psql")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg("postgres")
-        .arg("-c").arg(format!("CREATE DATABASE \"{}\"", database))
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法创建数据库: {}", e))?;
+/// 估算表和索引的膨胀（基于统计信息的估算值，按膨胀字节数从大到小排序）
+#[tauri::command]
+async fn get_table_bloat(
+    database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<models::monitoring::BloatEstimate>>, String> {
+    log::info!("========== 估算表膨胀 ==========");
+    log::info!("数据库: {}", database);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
 
-    if !create_output.status.success() {
-        let stderr = String::from_utf8_lossy(&create_output.stderr);
-        log::error!("创建数据库失败: {}", stderr);
-        return Err(format!("创建数据库失败: {}", stderr));
+    match services::bloat::get_table_bloat(client).await {
+        Ok(estimates) => {
+            log::info!("共估算 {} 个表和索引", estimates.len());
+            Ok(ApiResponse {
+                success: true,
+                message: "膨胀数据为基于统计信息的估算值，请先执行 ANALYZE 以提高准确性".to_string(),
+                data: Some(estimates),
+            })
+        }
+        Err(e) => {
+            log::error!("估算表膨胀失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
     }
+}
 
-    // 使用 pg_restore 导入
-    log::info!("正在导入数据...");
-    let restore_output = std::process::Command::new("pg_restore")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg(&database)
-        .arg("-v")  // 详细模式
-        .arg("--no-owner")  // 不恢复所有权
-        .arg("--no-acl")  // 不恢复访问权限
-        .arg(&filePath)
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法执行 pg_restore: {}", e))?;
+/// 重建表、索引或整个数据库的索引
+///
+/// 在专用的自动提交连接上执行（REINDEX 不能在事务中运行），服务器发出的每条通知
+/// 都会以 `reindex-notice` 事件发送给前端。
+#[tauri::command]
+async fn reindex(
+    app: tauri::AppHandle,
+    database: String,
+    target: models::monitoring::ReindexTarget,
+    concurrently: bool,
+) -> Result<ApiResponse<models::monitoring::ReindexResult>, String> {
+    log::info!("========== 重建索引 ==========");
+    log::info!("数据库: {}, 目标: {:?}, CONCURRENTLY: {}", database, target, concurrently);
+
+    let (client, mut notices) = connection_router::connect_maintenance(&get_routing_config(), &database).await?;
+
+    let result = services::maintenance::reindex(
+        &client,
+        &mut notices,
+        &target,
+        concurrently,
+        |notice| {
+            log::info!("{}", notice);
+            if let Err(e) = app.emit("reindex-notice", notice) {
+                log::warn!("发送通知事件失败: {}", e);
+            }
+        },
+    ).await;
+
+    match result {
+        Ok(result) => {
+            log::info!("重建索引完成，耗时: {} ms", result.duration_ms);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("重建索引完成，耗时 {} ms", result.duration_ms),
+                data: Some(result),
+            })
+        }
+        Err(e) => {
+            log::error!("重建索引失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 对表执行 `VACUUM [FULL] [ANALYZE]`
+///
+/// 在专用的自动提交连接上执行（VACUUM 不能在事务中运行）。`full` 会重写整张表并在
+/// 执行期间阻塞读写。
+#[tauri::command]
+async fn vacuum_table(
+    database: String,
+    schema: String,
+    table: String,
+    full: bool,
+    analyze: bool,
+) -> Result<ApiResponse<models::monitoring::MaintenanceResult>, String> {
+    log::info!("========== 清理表 ==========");
+    log::info!("数据库: {}, Schema: {}, 表: {}, FULL: {}, ANALYZE: {}", database, schema, table, full, analyze);
 
-    let stderr = String::from_utf8_lossy(&restore_output.stderr);
-    log::info!("pg_restore 输出: {}", stderr);
+    let (client, mut notices) = connection_router::connect_maintenance(&get_routing_config(), &database).await?;
 
-    if !restore_output.status.success() {
-        log::warn!("pg_restore 返回非零状态码，但这可能是正常的（某些警告）");
+    match services::maintenance::vacuum_table(&client, &mut notices, &schema, &table, full, analyze).await {
+        Ok(result) => {
+            log::info!("清理表完成，耗时: {} ms", result.duration_ms);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("{} 完成，耗时 {} ms", result.statement, result.duration_ms),
+                data: Some(result),
+            })
+        }
+        Err(e) => {
+            log::error!("清理表失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
     }
+}
 
-    log::info!("========== 导入完成 ==========");
+/// 对表执行 `ANALYZE`，更新查询规划器的统计信息
+#[tauri::command]
+async fn analyze_table(
+    database: String,
+    schema: String,
+    table: String,
+) -> Result<ApiResponse<models::monitoring::MaintenanceResult>, String> {
+    log::info!("========== 分析表 ==========");
+    log::info!("数据库: {}, Schema: {}, 表: {}", database, schema, table);
 
-    Ok(ApiResponse {
-        success: true,
-        message: format!("数据库 {} 导入成功", database),
-        data: None,
-    })
+    let (client, mut notices) = connection_router::connect_maintenance(&get_routing_config(), &database).await?;
+
+    match services::maintenance::analyze_table(&client, &mut notices, &schema, &table).await {
+        Ok(result) => {
+            log::info!("分析表完成，耗时: {} ms", result.duration_ms);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("{} 完成，耗时 {} ms", result.statement, result.duration_ms),
+                data: Some(result),
+            })
+        }
+        Err(e) => {
+            log::error!("分析表失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
 }
 
 #[tauri::command]
 async fn list_databases() -> Result<ApiResponse<Vec<String>>, String> {
     let config = get_db_config();
+    let pgpass = services::pgpass::PgPassFile::new(&config.user, &config.password)?;
+    let psql = get_pg_tool("psql");
     
-    let output = std::process::Command::new("psql")
+    let output = psql.command()
         .arg("-h").arg(&config.host)
         .arg("-p").arg(&config.port)
         .arg("-U").arg(&config.user)
         .arg("-d").arg("postgres")
         .arg("-t")
         .arg("-c").arg("SELECT datname FROM pg_database WHERE datistemplate = false ORDER BY datname")
-        .env("PGPASSWORD", &config.password)
+        .env_remove("PGPASSWORD")
+        .env("PGPASSFILE", pgpass.path())
+        .envs(config.tls_settings().libpq_env())
         .output()
-        .map_err(|e| format!("无法执行 psql: {}", e))?;
+        .map_err(|e| psql.error(e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -662,12 +3178,184 @@ async fn list_databases() -> Result<ApiResponse<Vec<String>>, String> {
     })
 }
 
+/// 检查服务和数据库服务器是否可达
+///
+/// 使用临时连接连接默认数据库，最多等待配置的连接超时时间，不影响已有的连接。
+/// 成功时返回 `SELECT 1` 的延迟和服务器版本。
+#[tauri::command]
+async fn check_health() -> Result<ApiResponse<connection_router::ServerHealth>, String> {
+    let mut database = get_db_config().default_database;
+    if database.is_empty() {
+        database = "postgres".to_string();
+    }
+
+    match connection_router::check_health(&get_routing_config(), &database).await {
+        Ok(health) => Ok(ApiResponse {
+            success: true,
+            message: format!(
+                "数据库连接正常，延迟 {:.1} ms，服务器版本 {}",
+                health.latency_ms, health.server_version
+            ),
+            data: Some(health),
+        }),
+        Err(e) => {
+            log::warn!("健康检查失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 关闭指定数据库的连接（包括只读副本上的连接），返回关闭的连接数
+///
+/// 之后再访问该数据库时会重新建立连接。
+#[tauri::command]
+async fn close_connection(
+    database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<usize>, String> {
+    log::info!("========== 关闭数据库连接 ==========");
+    log::info!("数据库: {}", database);
+
+    let closed = connection_router::close_database_pools(&mut *state.connections.lock().await, &database);
+    log::info!("已关闭 {} 个连接", closed);
+
+    Ok(ApiResponse {
+        success: true,
+        message: format!("已关闭数据库 {} 的 {} 个连接", database, closed),
+        data: Some(closed),
+    })
+}
+
+/// 关闭所有数据库连接（如应用退出前），返回关闭的连接数
+#[tauri::command]
+async fn close_all_connections(
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<usize>, String> {
+    log::info!("========== 关闭所有数据库连接 ==========");
+
+    let closed = connection_router::close_all_pools(&mut *state.connections.lock().await);
+    log::info!("已关闭 {} 个连接", closed);
+
+    Ok(ApiResponse {
+        success: true,
+        message: format!("已关闭 {} 个连接", closed),
+        data: Some(closed),
+    })
+}
+
+/// 列出所有连接配置（不含密码），`active` 标记当前使用的配置
 #[tauri::command]
-async fn check_health() -> Result<ApiResponse<()>, String> {
+async fn list_profiles() -> Result<ApiResponse<Vec<ProfileSummary>>, String> {
+    let config = load_config();
+    let active = config.active_profile_name();
+    let profiles: Vec<ProfileSummary> = config
+        .profile_names()
+        .iter()
+        .filter_map(|name| {
+            config
+                .profile(name)
+                .map(|profile| ProfileSummary::new(name, profile, name == active))
+        })
+        .collect();
+
     Ok(ApiResponse {
         success: true,
-        message: "服务运行正常".to_string(),
-        data: None,
+        message: format!("共 {} 个连接配置，当前使用 {}", profiles.len(), active),
+        data: Some(profiles),
+    })
+}
+
+/// 切换当前使用的连接配置，并写回配置文件
+///
+/// 之后的命令使用新配置中的服务器和用户建立连接；环境变量仍然覆盖对应的字段。
+#[tauri::command]
+async fn set_active_profile(name: String) -> Result<ApiResponse<ProfileSummary>, String> {
+    log::info!("========== 切换连接配置 ==========");
+    log::info!("连接配置: {}", name);
+
+    let mut config = load_config();
+    let summary = match config.profile(&name) {
+        Some(profile) => ProfileSummary::new(&name, profile, true),
+        None => {
+            return Ok(ApiResponse {
+                success: false,
+                message: format!("连接配置不存在: {}", name),
+                data: None,
+            });
+        }
+    };
+
+    config.active_profile = Some(name.clone());
+    if let Err(e) = save_config(&config) {
+        log::error!("保存配置失败: {}", e);
+        return Ok(ApiResponse {
+            success: false,
+            message: e,
+            data: None,
+        });
+    }
+
+    log::info!("已切换到连接配置 {}: {}:{}", name, summary.host, summary.port);
+    Ok(ApiResponse {
+        success: true,
+        message: format!("已切换到连接配置 {}", name),
+        data: Some(summary),
+    })
+}
+
+/// 校验并保存当前使用的连接配置
+///
+/// 主机不能为空，端口必须是数字；测试连接成功后才写入配置文件（更新当前使用的连接配置）。
+/// 返回保存后实际生效的配置（环境变量覆盖后的结果），其中的密码为空。
+#[tauri::command]
+async fn save_database_config(config: DatabaseConfig) -> Result<ApiResponse<DatabaseConfig>, String> {
+    log::info!("========== 保存连接配置 ==========");
+    log::info!("主机: {}:{}, 用户: {}", config.host, config.port, config.user);
+
+    let failed = |message: String| {
+        log::warn!("保存连接配置失败: {}", message);
+        Ok(ApiResponse {
+            success: false,
+            message,
+            data: None,
+        })
+    };
+
+    if config.host.trim().is_empty() {
+        return failed("主机地址不能为空".to_string());
+    }
+    if config.port.trim().parse::<u16>().is_err() {
+        return failed(format!("端口必须是数字: {}", config.port));
+    }
+
+    let mut database = config.default_database.clone();
+    if database.is_empty() {
+        database = "postgres".to_string();
+    }
+    if let Err(e) = connection_router::check_health(&routing_config(config.clone()), &database).await {
+        return failed(format!("测试连接失败，配置未保存: {}", e));
+    }
+
+    let mut saved = load_config();
+    let active = saved.active_profile_name().to_string();
+    match saved.profiles.get_mut(&active) {
+        Some(profile) => *profile = config,
+        None => saved.database = config,
+    }
+    if let Err(e) = save_config(&saved) {
+        return failed(e);
+    }
+
+    let mut resolved = get_db_config();
+    resolved.password = String::new();
+    Ok(ApiResponse {
+        success: true,
+        message: format!("连接配置 {} 已保存", active),
+        data: Some(resolved),
     })
 }
 
@@ -683,54 +3371,145 @@ async fn get_log_dir_path() -> Result<String, String> {
     Ok(log_dir.to_string_lossy().to_string())
 }
 
+/// 分页查询 SQL 执行历史（按时间倒序），可按数据库、查询类型、执行状态和日期筛选
+#[tauri::command]
+async fn get_sql_history(
+    limit: usize,
+    offset: usize,
+    filter: Option<services::sql_logger::SqlHistoryFilter>,
+) -> Result<ApiResponse<services::sql_logger::SqlHistoryPage>, String> {
+    log::info!("========== 查询 SQL 历史 ==========");
+    let filter = filter.unwrap_or_default();
+    log::info!("筛选条件: {:?}, 数量: {}, 跳过: {}", filter, limit, offset);
+    
+    let log_dir = get_log_dir()?;
+    
+    let backend = load_config().sql_log.backend;
+    
+    match services::sql_logger::query_history(&log_dir, backend, &filter, limit, offset) {
+        Ok(history) => {
+            log::info!("共 {} 条记录", history.total);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("共 {} 条记录", history.total),
+                data: Some(history),
+            })
+        }
+        Err(e) => {
+            log::error!("查询 SQL 历史失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 在所有日期的 SQL 执行历史中搜索 SQL 文本（不区分大小写）
+#[tauri::command]
+async fn search_sql_history(
+    term: String,
+    limit: usize,
+) -> Result<ApiResponse<Vec<services::sql_logger::SqlLogEntry>>, String> {
+    log::info!("========== 搜索 SQL 历史 ==========");
+    log::info!("搜索内容: {}, 数量: {}", term, limit);
+    
+    let log_dir = get_log_dir()?;
+    
+    let backend = load_config().sql_log.backend;
+    
+    match services::sql_logger::search_history(&log_dir, backend, &term, limit) {
+        Ok(entries) => {
+            log::info!("找到 {} 条记录", entries.len());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("找到 {} 条记录", entries.len()),
+                data: Some(entries),
+            })
+        }
+        Err(e) => {
+            log::error!("搜索 SQL 历史失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 将筛选后的 SQL 执行历史导出为 CSV 或 JSON 文件
+#[tauri::command]
+async fn export_sql_history(
+    filter: services::sql_logger::SqlHistoryFilter,
+    format: services::sql_logger::HistoryExportFormat,
+) -> Result<ApiResponse<String>, String> {
+    log::info!("========== 导出 SQL 历史 ==========");
+    log::info!("筛选条件: {:?}, 格式: {:?}", filter, format);
+    
+    let log_dir = get_log_dir()?;
+    let export_dir = get_export_dir()?;
+    
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let extension = match format {
+        services::sql_logger::HistoryExportFormat::Csv => "csv",
+        services::sql_logger::HistoryExportFormat::Json => "json",
+    };
+    let file_path = export_dir.join(format!("sql_history_{}.{}", timestamp, extension));
+    
+    let backend = load_config().sql_log.backend;
+    
+    match services::sql_logger::export_history(&log_dir, backend, &filter, format, &file_path) {
+        Ok(count) => {
+            log::info!("已导出 {} 条记录到 {}", count, file_path.display());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("已导出 {} 条记录到 {}", count, file_path.display()),
+                data: Some(file_path.to_string_lossy().to_string()),
+            })
+        }
+        Err(e) => {
+            log::error!("导出 SQL 历史失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
 // Database Explorer APIs
 #[tauri::command]
-async fn list_tables(database: String) -> Result<ApiResponse<Vec<TableInfo>>, String> {
+async fn list_tables(
+    database: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<TableInfo>>, String> {
     log::info!("========== 列出表 ==========");
     log::info!("数据库: {}", database);
     
-    let config = get_db_config();
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
     
     let query = "SELECT 
-        schemaname as schema, 
-        relname as name,
-        n_live_tup as row_count
+        schemaname::text AS schema, 
+        relname::text AS name,
+        n_live_tup AS row_count
     FROM pg_stat_user_tables 
     ORDER BY schemaname, relname";
     
-    let output = std::process::Command::new("psql")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg(&database)
-        .arg("-t")
-        .arg("-A")
-        .arg("-F").arg("|")
-        .arg("-c").arg(query)
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法执行 psql: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("查询表列表失败: {}", stderr));
-    }
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询表列表失败: {}", e))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let tables: Vec<TableInfo> = stdout
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() >= 2 {
-                Some(TableInfo {
-                    schema: parts[0].trim().to_string(),
-                    name: parts[1].trim().to_string(),
-                    row_count: parts.get(2).and_then(|s| s.trim().parse().ok()),
-                })
-            } else {
-                None
-            }
+    let tables: Vec<TableInfo> = rows
+        .iter()
+        .map(|row| TableInfo {
+            schema: row.get("schema"),
+            name: row.get("name"),
+            row_count: row.get("row_count"),
         })
         .collect();
 
@@ -743,466 +3522,683 @@ async fn list_tables(database: String) -> Result<ApiResponse<Vec<TableInfo>>, St
     })
 }
 
+/// 分页查询表数据
+///
+/// 默认返回带类型的 JSON 值（数字、布尔、嵌套 JSON）；`rawText` 为 true 时每列都以
+/// PostgreSQL 的文本形式返回，用于查看工具未专门处理的类型的精确表示。
+/// `filters` 按列过滤（多个条件为 AND），`sort` 指定排序列；`totalRows` 为过滤后的行数。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn get_table_data(
+    database: String,
+    schema: Option<String>,
+    table: String,
+    page: u32,
+    pageSize: u32,
+    rawText: Option<bool>,
+    filters: Option<Vec<models::data::ColumnFilter>>,
+    sort: Option<Vec<models::data::SortColumn>>,
+    estimate: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<TableData>, String> {
+    let raw_text = rawText.unwrap_or(false);
+    let query = services::table_data::TableQuery {
+        filters: filters.unwrap_or_default(),
+        sort: sort.unwrap_or_default(),
+        estimate_count: estimate.unwrap_or(false),
+    };
+    log::info!("========== 查询表数据 ==========");
+    log::info!("数据库: {}, Schema: {:?}, 表: {}, 页: {}, 每页: {}, 原始文本: {}, 估算行数: {}", database, schema, table, page, pageSize, raw_text, query.estimate_count);
+    log::info!("过滤条件: {:?}, 排序: {:?}", query.filters, query.sort);
+
+    let (schema, table_name) = resolve_table_name(schema, &table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::table_data::read_table_page(client, &schema, &table_name, page, pageSize, raw_text, &query).await {
+        Ok(table_page) => {
+            log::info!(
+                "返回 {} 行数据，总共{} {} 行",
+                table_page.rows.len(),
+                if table_page.total_rows_estimated { "约" } else { "" },
+                table_page.total_rows
+            );
+            let has_primary_key = !table_page.primary_key_columns.is_empty();
+            let editable = has_primary_key || table_page.row_id_column.is_some();
+            if !has_primary_key {
+                if editable {
+                    log::info!("表 {}.{} 没有主键，按 ctid 定位行", schema, table_name);
+                } else {
+                    log::info!("表 {}.{} 没有主键，数据不可编辑", schema, table_name);
+                }
+            }
+
+            let columns = table_page
+                .columns
+                .into_iter()
+                .map(|column| ColumnInfo {
+                    name: column.name,
+                    data_type: column.type_name,
+                    nullable: column.nullable,
+                    is_primary_key: column.is_primary_key,
+                })
+                .collect();
+
+            Ok(ApiResponse {
+                success: true,
+                message: format!("查询成功，返回 {} 行", table_page.rows.len()),
+                data: Some(TableData {
+                    columns,
+                    primary_key: table_page.primary_key_columns.clone(),
+                    primary_key_columns: table_page.primary_key_columns,
+                    has_primary_key,
+                    row_id_column: table_page.row_id_column,
+                    editable,
+                    rows: table_page.rows,
+                    total_rows: table_page.total_rows,
+                    total_rows_estimated: table_page.total_rows_estimated,
+                    page,
+                    page_size: pageSize,
+                }),
+            })
+        }
+        Err(e) => {
+            log::error!("查询表数据失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 以 keyset 分页流式读取表数据
+///
+/// 每读取一批数据就发送一次 `table-rows-batch` 事件，事件中携带本批最后一行的主键，
+/// 前端可将其作为 `afterKey` 传回以继续加载，实现大表的无限滚动。
 #[tauri::command]
 #[allow(non_snake_case)]
-async fn get_table_data(
+async fn stream_table_rows(
+    app: tauri::AppHandle,
     database: String,
+    schema: String,
     table: String,
-    page: u32,
-    pageSize: u32,
-) -> Result<ApiResponse<TableData>, String> {
-    log::info!("========== 查询表数据 ==========");
-    log::info!("数据库: {}, 表: {}, 页: {}, 每页: {}", database, table, page, pageSize);
-    
-    let config = get_db_config();
-    
-    // Get column information
-    let column_query = format!(
-        "SELECT 
-            a.attname as name,
-            pg_catalog.format_type(a.atttypid, a.atttypmod) as type,
-            NOT a.attnotnull as nullable,
-            COALESCE((SELECT true FROM pg_index i WHERE i.indrelid = a.attrelid AND a.attnum = ANY(i.indkey) AND i.indisprimary), false) as is_primary_key
-        FROM pg_catalog.pg_attribute a
-        WHERE a.attrelid = '{}'::regclass
-        AND a.attnum > 0
-        AND NOT a.attisdropped
-        ORDER BY a.attnum",
-        table
-    );
-    
-    let column_output = std::process::Command::new("psql")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg(&database)
-        .arg("-t")
-        .arg("-A")
-        .arg("-F").arg("|")
-        .arg("-c").arg(&column_query)
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法查询列信息: {}", e))?;
+    batchSize: u32,
+    afterKey: Option<Vec<String>>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<u64>, String> {
+    log::info!("========== 流式读取表数据 ==========");
+    log::info!("数据库: {}, 表: {}.{}, 每批: {}", database, schema, table, batchSize);
 
-    if !column_output.status.success() {
-        let stderr = String::from_utf8_lossy(&column_output.stderr);
-        return Err(format!("查询列信息失败: {}", stderr));
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let result = services::row_streamer::stream_table_rows(
+        client,
+        &schema,
+        &table,
+        batchSize,
+        afterKey,
+        |batch| {
+            app.emit("table-rows-batch", batch)
+                .map_err(|e| format!("发送数据事件失败: {}", e))
+        },
+    ).await;
+
+    match result {
+        Ok(total_rows) => {
+            log::info!("流式读取完成，共 {} 行", total_rows);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("共读取 {} 行", total_rows),
+                data: Some(total_rows),
+            })
+        }
+        Err(e) => {
+            log::error!("流式读取表数据失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
     }
+}
 
-    let column_stdout = String::from_utf8_lossy(&column_output.stdout);
-    let columns: Vec<ColumnInfo> = column_stdout
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split('|').collect();
-            if parts.len() >= 4 {
-                Some(ColumnInfo {
-                    name: parts[0].trim().to_string(),
-                    data_type: parts[1].trim().to_string(),
-                    nullable: parts[2].trim() == "t",
-                    is_primary_key: parts[3].trim() == "t",
-                })
-            } else {
-                None
-            }
-        })
-        .collect();
+/// 订阅一个或多个 LISTEN 频道
+///
+/// 每个数据库使用一个专用的监听连接，已订阅的频道不会重复 LISTEN。收到的通知以
+/// `pg-notification` 事件发送给前端，事件中包含 `channel`、`payload`，负载是合法 JSON
+/// 时还包含解析后的 `parsed`。监听连接断开后，下次订阅时会重新连接并恢复之前的频道。
+#[tauri::command]
+async fn listen_channels(
+    app: tauri::AppHandle,
+    database: String,
+    channels: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<String>>, String> {
+    log::info!("========== 订阅通知频道 ==========");
+    log::info!("数据库: {}, 频道: {:?}", database, channels);
 
-    // Get total row count
-    let count_query = format!("SELECT COUNT(*) FROM {}", table);
-    let count_output = std::process::Command::new("psql")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg(&database)
-        .arg("-t")
-        .arg("-c").arg(&count_query)
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法查询行数: {}", e))?;
-
-    let total_rows: i64 = String::from_utf8_lossy(&count_output.stdout)
-        .trim()
-        .parse()
-        .unwrap_or(0);
-
-    // Get paginated data
-    let offset = (page - 1) * pageSize;
-    let data_query = format!(
-        "SELECT * FROM {} LIMIT {} OFFSET {}",
-        table, pageSize, offset
-    );
-    
-    let data_output = std::process::Command::new("psql")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg(&database)
-        .arg("-t")
-        .arg("-A")
-        .arg("-F").arg("|")
-        .arg("-c").arg(&data_query)
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法查询数据: {}", e))?;
+    let mut listeners = state.listeners.lock().await;
 
-    if !data_output.status.success() {
-        let stderr = String::from_utf8_lossy(&data_output.stderr);
-        return Err(format!("查询数据失败: {}", stderr));
+    let mut channels = channels;
+    if listeners.get(&database).is_some_and(|listener| listener.is_closed()) {
+        log::warn!("数据库 {} 的监听连接已断开，重新连接", database);
+        if let Some(previous) = listeners.remove(&database) {
+            channels.extend(previous.channels());
+        }
     }
 
-    let data_stdout = String::from_utf8_lossy(&data_output.stdout);
-    let rows: Vec<serde_json::Value> = data_stdout
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| {
-            let values: Vec<&str> = line.split('|').collect();
-            let mut row = serde_json::Map::new();
-            for (i, col) in columns.iter().enumerate() {
-                if let Some(value) = values.get(i) {
-                    row.insert(col.name.clone(), serde_json::Value::String(value.to_string()));
+    if !listeners.contains_key(&database) {
+        let (client, notifications) = connection_router::connect_listener(&get_routing_config(), &database).await?;
+        let listener = services::notification_listener::NotificationListener::new(
+            client,
+            notifications,
+            move |event| {
+                log::debug!("收到频道 {} 的通知", event.channel);
+                if let Err(e) = app.emit("pg-notification", event) {
+                    log::warn!("发送通知事件失败: {}", e);
                 }
-            }
-            serde_json::Value::Object(row)
-        })
-        .collect();
+            },
+        );
+        listeners.insert(database.clone(), listener);
+    }
 
-    log::info!("返回 {} 行数据，总共 {} 行", rows.len(), total_rows);
+    let listener = listeners.get_mut(&database)
+        .ok_or_else(|| "无法获取监听连接".to_string())?;
 
-    Ok(ApiResponse {
-        success: true,
-        message: format!("查询成功，返回 {} 行", rows.len()),
-        data: Some(TableData {
-            columns,
-            rows,
-            total_rows,
-            page,
-            page_size: pageSize,
-        }),
-    })
+    match listener.listen(&channels).await {
+        Ok(added) => {
+            log::info!("新订阅频道: {:?}", added);
+            let active = listener.channels();
+            Ok(ApiResponse {
+                success: true,
+                message: format!("新订阅 {} 个频道，当前共订阅 {} 个频道", added.len(), active.len()),
+                data: Some(active),
+            })
+        }
+        Err(e) => {
+            log::error!("订阅频道失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: Some(listener.channels()),
+            })
+        }
+    }
+}
+
+/// 取消订阅 LISTEN 频道
+#[tauri::command]
+async fn unlisten_channels(
+    database: String,
+    channels: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<String>>, String> {
+    log::info!("========== 取消订阅通知频道 ==========");
+    log::info!("数据库: {}, 频道: {:?}", database, channels);
+
+    let mut listeners = state.listeners.lock().await;
+    let listener = match listeners.get_mut(&database) {
+        Some(listener) => listener,
+        None => {
+            return Ok(ApiResponse {
+                success: true,
+                message: "当前没有订阅任何频道".to_string(),
+                data: Some(Vec::new()),
+            });
+        }
+    };
+
+    match listener.unlisten(&channels).await {
+        Ok(removed) => {
+            log::info!("已取消订阅频道: {:?}", removed);
+            let active = listener.channels();
+            if active.is_empty() {
+                // 没有订阅的频道时关闭监听连接
+                listeners.remove(&database);
+            }
+            Ok(ApiResponse {
+                success: true,
+                message: format!("已取消订阅 {} 个频道，当前共订阅 {} 个频道", removed.len(), active.len()),
+                data: Some(active),
+            })
+        }
+        Err(e) => {
+            log::error!("取消订阅频道失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: Some(listener.channels()),
+            })
+        }
+    }
 }
 
 #[tauri::command]
 async fn create_record(
     database: String,
+    schema: Option<String>,
     table: String,
     data: serde_json::Value,
+    state: tauri::State<'_, AppState>,
 ) -> Result<ApiResponse<()>, String> {
     log::info!("========== 创建记录 ==========");
-    log::info!("数据库: {}, 表: {}", database, table);
-    
-    let config = get_db_config();
+    log::info!("数据库: {}, Schema: {:?}, 表: {}", database, schema, table);
     
     let obj = data.as_object().ok_or("数据必须是对象")?;
     
-    let columns: Vec<String> = obj.keys().cloned().collect();
-    let values: Vec<String> = obj.values()
-        .map(|v| match v {
-            serde_json::Value::String(s) => format!("'{}'", s.replace("'", "''")),
-            serde_json::Value::Number(n) => n.to_string(),
-            serde_json::Value::Bool(b) => b.to_string(),
-            serde_json::Value::Null => "NULL".to_string(),
-            _ => format!("'{}'", v.to_string().replace("'", "''")),
-        })
-        .collect();
-    
-    let insert_query = format!(
-        "INSERT INTO {} ({}) VALUES ({})",
-        table,
-        columns.join(", "),
-        values.join(", ")
-    );
+    let (schema, table_name) = resolve_table_name(schema, &table);
     
-    log::info!("执行 SQL: {}", insert_query);
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
     
-    let output = std::process::Command::new("psql")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg(&database)
-        .arg("-c").arg(&insert_query)
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法执行插入: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("插入失败: {}", stderr));
+    match services::record_writer::insert_record(
+        client,
+        &state.column_types,
+        &database,
+        &schema,
+        &table_name,
+        obj,
+    )
+    .await
+    {
+        Ok(_) => {
+            log::info!("记录创建成功");
+            Ok(ApiResponse {
+                success: true,
+                message: "记录创建成功".to_string(),
+                data: None,
+            })
+        }
+        Err(e) => {
+            log::error!("插入失败: {}", e);
+            Err(format!("插入失败: {}", e))
+        }
     }
-
-    log::info!("记录创建成功");
-
-    Ok(ApiResponse {
-        success: true,
-        message: "记录创建成功".to_string(),
-        data: None,
-    })
 }
 
 #[tauri::command]
 #[allow(non_snake_case)]
 async fn update_record(
     database: String,
+    schema: Option<String>,
     table: String,
     primaryKey: serde_json::Value,
     data: serde_json::Value,
+    state: tauri::State<'_, AppState>,
 ) -> Result<ApiResponse<()>, String> {
     log::info!("========== 更新记录 ==========");
-    log::info!("数据库: {}, 表: {}", database, table);
-    
-    let config = get_db_config();
+    log::info!("数据库: {}, Schema: {:?}, 表: {}", database, schema, table);
     
     let pk_obj = primaryKey.as_object().ok_or("主键必须是对象")?;
     let data_obj = data.as_object().ok_or("数据必须是对象")?;
     
-    let set_clauses: Vec<String> = data_obj.iter()
-        .map(|(k, v)| {
-            let value_str = match v {
-                serde_json::Value::String(s) => format!("'{}'", s.replace("'", "''")),
-                serde_json::Value::Number(n) => n.to_string(),
-                serde_json::Value::Bool(b) => b.to_string(),
-                serde_json::Value::Null => "NULL".to_string(),
-                _ => format!("'{}'", v.to_string().replace("'", "''")),
-            };
-            format!("{} = {}", k, value_str)
-        })
-        .collect();
-    
-    let where_clauses: Vec<String> = pk_obj.iter()
-        .map(|(k, v)| {
-            let value_str = match v {
-                serde_json::Value::String(s) => format!("'{}'", s.replace("'", "''")),
-                serde_json::Value::Number(n) => n.to_string(),
-                _ => format!("'{}'", v.to_string().replace("'", "''")),
-            };
-            format!("{} = {}", k, value_str)
-        })
-        .collect();
-    
-    let update_query = format!(
-        "UPDATE {} SET {} WHERE {}",
-        table,
-        set_clauses.join(", "),
-        where_clauses.join(" AND ")
-    );
+    let (schema, table_name) = resolve_table_name(schema, &table);
     
-    log::info!("执行 SQL: {}", update_query);
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
     
-    let output = std::process::Command::new("psql")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg(&database)
-        .arg("-c").arg(&update_query)
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法执行更新: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("更新失败: {}", stderr));
+    match services::record_writer::update_record(
+        client,
+        &state.column_types,
+        &database,
+        &schema,
+        &table_name,
+        pk_obj,
+        data_obj,
+    )
+    .await
+    {
+        Ok(rows) => {
+            log::info!("记录更新成功，影响 {} 行", rows);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("记录更新成功，影响 {} 行", rows),
+                data: None,
+            })
+        }
+        Err(e) => {
+            log::error!("更新失败: {}", e);
+            Err(format!("更新失败: {}", e))
+        }
     }
-
-    log::info!("记录更新成功");
-
-    Ok(ApiResponse {
-        success: true,
-        message: "记录更新成功".to_string(),
-        data: None,
-    })
 }
 
 #[tauri::command]
 #[allow(non_snake_case)]
 async fn delete_record(
     database: String,
+    schema: Option<String>,
     table: String,
     primaryKey: serde_json::Value,
+    state: tauri::State<'_, AppState>,
 ) -> Result<ApiResponse<()>, String> {
     log::info!("========== 删除记录 ==========");
-    log::info!("数据库: {}, 表: {}", database, table);
-    
-    let config = get_db_config();
+    log::info!("数据库: {}, Schema: {:?}, 表: {}", database, schema, table);
     
     let pk_obj = primaryKey.as_object().ok_or("主键必须是对象")?;
     
-    let where_clauses: Vec<String> = pk_obj.iter()
-        .map(|(k, v)| {
-            let value_str = match v {
-                serde_json::Value::String(s) => format!("'{}'", s.replace("'", "''")),
-                serde_json::Value::Number(n) => n.to_string(),
-                _ => format!("'{}'", v.to_string().replace("'", "''")),
-            };
-            format!("{} = {}", k, value_str)
-        })
-        .collect();
-    
-    let delete_query = format!(
-        "DELETE FROM {} WHERE {}",
-        table,
-        where_clauses.join(" AND ")
-    );
+    let (schema, table_name) = resolve_table_name(schema, &table);
     
-    log::info!("执行 SQL: {}", delete_query);
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
     
-    let output = std::process::Command::new("psql")
-        .arg("-h").arg(&config.host)
-        .arg("-p").arg(&config.port)
-        .arg("-U").arg(&config.user)
-        .arg("-d").arg(&database)
-        .arg("-c").arg(&delete_query)
-        .env("PGPASSWORD", &config.password)
-        .output()
-        .map_err(|e| format!("无法执行删除: {}", e))?;
+    match services::record_writer::delete_record(
+        client,
+        &state.column_types,
+        &database,
+        &schema,
+        &table_name,
+        pk_obj,
+    )
+    .await
+    {
+        Ok(rows) => {
+            log::info!("记录删除成功，影响 {} 行", rows);
+            Ok(ApiResponse {
+                success: true,
+                message: format!("记录删除成功，影响 {} 行", rows),
+                data: None,
+            })
+        }
+        Err(e) => {
+            log::error!("删除失败: {}", e);
+            Err(format!("删除失败: {}", e))
+        }
+    }
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("删除失败: {}", stderr));
+// Batch Data Operations Commands
+
+/// 批量操作使用的事务选项
+async fn batch_options(state: &AppState, database: &str) -> BatchOptions {
+    BatchOptions {
+        defer_constraints: state.deferred_constraints.lock().await.contains(database),
+        ..Default::default()
+    }
+}
+
+/// 批量操作失败后，如果目标表的触发器是通过 disable_table_triggers 禁用的，则自动重新启用
+///
+/// 返回需要附加到错误信息中的说明。
+async fn restore_triggers_after_failure(
+    state: &AppState,
+    client: &tokio_postgres::Client,
+    database: &str,
+    schema: &str,
+    table: &str,
+) -> Option<String> {
+    let mut registry = state.disabled_triggers.lock().await;
+    if !registry.is_disabled(database, schema, table) {
+        return None;
+    }
+
+    log::warn!("批量操作失败，重新启用表 {}.{} 的触发器", schema, table);
+    match services::bulk_load::enable_triggers(client, schema, table).await {
+        Ok(_) => {
+            registry.mark_enabled(database, schema, table);
+            Some(format!("已自动重新启用表 {}.{} 的触发器", schema, table))
+        }
+        Err(e) => {
+            log::error!("重新启用触发器失败: {}", e);
+            Some(format!("自动重新启用表 {}.{} 的触发器失败，请手动启用: {}", schema, table, e))
+        }
+    }
+}
+
+/// 禁用表上的全部触发器以加快批量导入
+///
+/// 禁用期间不检查外键、不执行业务触发器，因此必须传入 `acknowledgeRisk = true`。
+/// 之后的批量操作（导入 CSV、批量插入/更新/删除）失败时会自动重新启用。
+#[tauri::command]
+#[allow(non_snake_case)]
+async fn disable_table_triggers(
+    database: String,
+    schema: String,
+    table: String,
+    acknowledgeRisk: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<String>>, String> {
+    log::info!("========== 禁用表触发器 ==========");
+    log::info!("数据库: {}, 表: {}.{}", database, schema, table);
+
+    if !acknowledgeRisk {
+        return Ok(ApiResponse {
+            success: false,
+            message: "禁用触发器后将不再检查外键约束、不执行任何触发器，且在重新启用前对所有会话生效。请确认已了解风险（acknowledgeRisk）".to_string(),
+            data: None,
+        });
+    }
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::bulk_load::disable_triggers(client, &schema, &table).await {
+        Ok(triggers) => {
+            state.disabled_triggers.lock().await.mark_disabled(&database, &schema, &table);
+            log::warn!("表 {}.{} 的 {} 个触发器已禁用", schema, table, triggers.len());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("已禁用表 {}.{} 的 {} 个触发器，导入完成后请重新启用", schema, table, triggers.len()),
+                data: Some(triggers),
+            })
+        }
+        Err(e) => {
+            log::error!("禁用触发器失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
+    }
+}
+
+/// 重新启用表上的全部触发器
+#[tauri::command]
+async fn enable_table_triggers(
+    database: String,
+    schema: String,
+    table: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<Vec<String>>, String> {
+    log::info!("========== 启用表触发器 ==========");
+    log::info!("数据库: {}, 表: {}.{}", database, schema, table);
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    match services::bulk_load::enable_triggers(client, &schema, &table).await {
+        Ok(triggers) => {
+            state.disabled_triggers.lock().await.mark_enabled(&database, &schema, &table);
+            log::info!("表 {}.{} 的 {} 个触发器已启用", schema, table, triggers.len());
+            Ok(ApiResponse {
+                success: true,
+                message: format!("已启用表 {}.{} 的 {} 个触发器", schema, table, triggers.len()),
+                data: Some(triggers),
+            })
+        }
+        Err(e) => {
+            log::error!("启用触发器失败: {}", e);
+            Ok(ApiResponse {
+                success: false,
+                message: e,
+                data: None,
+            })
+        }
     }
+}
 
-    log::info!("记录删除成功");
+/// 设置之后的批量操作是否延迟约束检查（SET CONSTRAINTS ALL DEFERRED）
+///
+/// 设置对该数据库后续的批量插入/更新/删除生效，直到关闭或应用退出。
+/// 只影响声明为 DEFERRABLE 的约束。
+#[tauri::command]
+async fn set_constraints_deferred(
+    database: String,
+    deferred: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiResponse<()>, String> {
+    log::info!("========== 设置延迟约束 ==========");
+    log::info!("数据库: {}, 延迟: {}", database, deferred);
+
+    let mut databases = state.deferred_constraints.lock().await;
+    let message = if deferred {
+        databases.insert(database.clone());
+        format!("数据库 {} 的批量操作将在提交时检查可延迟约束", database)
+    } else {
+        databases.remove(&database);
+        format!("数据库 {} 的批量操作将立即检查约束", database)
+    };
 
     Ok(ApiResponse {
         success: true,
-        message: "记录删除成功".to_string(),
+        message,
         data: None,
     })
 }
 
-// Batch Data Operations Commands
-
 /// 批量更新多行数据
+///
+/// `continue_on_error` 为 true 时跳过失败的行并提交其余的行，`per_row_results` 给出每行的结果。
+/// `isolation` 指定事务隔离级别；序列化失败时响应的 `retryable` 为 true。
 #[tauri::command]
 async fn batch_update_rows(
     database: String,
     schema: String,
     table: String,
     updates: Vec<crate::models::data::RowUpdate>,
+    continue_on_error: Option<bool>,
+    isolation: Option<crate::models::data::IsolationLevel>,
     state: tauri::State<'_, AppState>,
 ) -> Result<BatchOperationResponse, String> {
     log::info!("========== 批量更新行 ==========");
     log::info!("数据库: {}, 表: {}.{}, 更新数量: {}", database, schema, table, updates.len());
     
-    let config = get_db_config();
-    let connection_string = format!(
-        "host={} port={} user={} password={} dbname={}",
-        config.host, config.port, config.user, config.password, database
-    );
-    
-    let mut connections = state.connections.lock().await;
-    let connection_key = format!("{}:{}", config.host, database);
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
     
-    if !connections.contains_key(&connection_key) {
-        let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls)
-            .await
-            .map_err(|e| format!("无法连接到数据库: {}", e))?;
-        
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                log::error!("数据库连接错误: {}", e);
-            }
-        });
-        
-        connections.insert(connection_key.clone(), client);
+    let options = BatchOptions {
+        continue_on_error: continue_on_error.unwrap_or(false),
+        isolation,
+        ..batch_options(&state, &database).await
+    };
+    let mut result = transaction_manager::batch_update_rows_with_options(client, &schema, &table, updates, &options).await;
+    if !result.success {
+        if let Some(note) = restore_triggers_after_failure(&state, client, &database, &schema, &table).await {
+            result.error = result.error.map(|e| format!("{}。{}", e, note));
+        }
     }
     
-    let client = connections.get(&connection_key)
-        .ok_or_else(|| "无法获取数据库连接".to_string())?;
-    
-    let result = transaction_manager::batch_update_rows(client, &schema, &table, updates).await;
-    
     log::info!("批量更新完成: success={}, rows_affected={}", result.success, result.rows_affected);
     Ok(result)
 }
 
 /// 批量插入多行数据
+///
+/// `continue_on_error` 为 true 时跳过失败的行并提交其余的行，`per_row_results` 给出每行的结果。
+/// `isolation` 指定事务隔离级别；序列化失败时响应的 `retryable` 为 true。
 #[tauri::command]
 async fn batch_insert_rows(
     database: String,
     schema: String,
     table: String,
     rows: Vec<std::collections::HashMap<String, serde_json::Value>>,
+    continue_on_error: Option<bool>,
+    isolation: Option<crate::models::data::IsolationLevel>,
     state: tauri::State<'_, AppState>,
 ) -> Result<BatchOperationResponse, String> {
     log::info!("========== 批量插入行 ==========");
     log::info!("数据库: {}, 表: {}.{}, 插入数量: {}", database, schema, table, rows.len());
     
-    let config = get_db_config();
-    let connection_string = format!(
-        "host={} port={} user={} password={} dbname={}",
-        config.host, config.port, config.user, config.password, database
-    );
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
     
-    let mut connections = state.connections.lock().await;
-    let connection_key = format!("{}:{}", config.host, database);
-    
-    if !connections.contains_key(&connection_key) {
-        let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls)
-            .await
-            .map_err(|e| format!("无法连接到数据库: {}", e))?;
-        
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                log::error!("数据库连接错误: {}", e);
-            }
-        });
-        
-        connections.insert(connection_key.clone(), client);
+    let options = BatchOptions {
+        continue_on_error: continue_on_error.unwrap_or(false),
+        isolation,
+        ..batch_options(&state, &database).await
+    };
+    let mut result = transaction_manager::batch_insert_rows_with_options(client, &schema, &table, rows, &options).await;
+    if !result.success {
+        if let Some(note) = restore_triggers_after_failure(&state, client, &database, &schema, &table).await {
+            result.error = result.error.map(|e| format!("{}。{}", e, note));
+        }
     }
     
-    let client = connections.get(&connection_key)
-        .ok_or_else(|| "无法获取数据库连接".to_string())?;
-    
-    let result = transaction_manager::batch_insert_rows(client, &schema, &table, rows).await;
-    
     log::info!("批量插入完成: success={}, rows_affected={}", result.success, result.rows_affected);
     Ok(result)
 }
 
 /// 批量删除多行数据
+///
+/// `continue_on_error` 为 true 时跳过失败的行并提交其余的行，`per_row_results` 给出每行的结果。
+/// `isolation` 指定事务隔离级别；序列化失败时响应的 `retryable` 为 true。
 #[tauri::command]
 async fn batch_delete_rows(
     database: String,
     schema: String,
     table: String,
     primary_keys: Vec<std::collections::HashMap<String, serde_json::Value>>,
+    continue_on_error: Option<bool>,
+    isolation: Option<crate::models::data::IsolationLevel>,
     state: tauri::State<'_, AppState>,
 ) -> Result<BatchOperationResponse, String> {
     log::info!("========== 批量删除行 ==========");
     log::info!("数据库: {}, 表: {}.{}, 删除数量: {}", database, schema, table, primary_keys.len());
     
-    let config = get_db_config();
-    let connection_string = format!(
-        "host={} port={} user={} password={} dbname={}",
-        config.host, config.port, config.user, config.password, database
-    );
-    
-    let mut connections = state.connections.lock().await;
-    let connection_key = format!("{}:{}", config.host, database);
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
     
-    if !connections.contains_key(&connection_key) {
-        let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls)
-            .await
-            .map_err(|e| format!("无法连接到数据库: {}", e))?;
-        
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                log::error!("数据库连接错误: {}", e);
-            }
-        });
-        
-        connections.insert(connection_key.clone(), client);
+    let options = BatchOptions {
+        continue_on_error: continue_on_error.unwrap_or(false),
+        isolation,
+        ..batch_options(&state, &database).await
+    };
+    let mut result = transaction_manager::batch_delete_rows_with_options(client, &schema, &table, primary_keys, &options).await;
+    if !result.success {
+        if let Some(note) = restore_triggers_after_failure(&state, client, &database, &schema, &table).await {
+            result.error = result.error.map(|e| format!("{}。{}", e, note));
+        }
     }
     
-    let client = connections.get(&connection_key)
-        .ok_or_else(|| "无法获取数据库连接".to_string())?;
-    
-    let result = transaction_manager::batch_delete_rows(client, &schema, &table, primary_keys).await;
-    
     log::info!("批量删除完成: success={}, rows_affected={}", result.success, result.rows_affected);
     Ok(result)
 }
 
+/// 在单个事务中按顺序应用混合的插入/更新/删除变更
+///
+/// 每个变更为 `{op: "insert" | "update" | "delete", ...}`，任何变更失败时全部回滚。
+#[tauri::command]
+async fn batch_apply_changes(
+    database: String,
+    schema: String,
+    table: String,
+    changes: Vec<crate::models::data::RowChange>,
+    state: tauri::State<'_, AppState>,
+) -> Result<BatchChangesResponse, String> {
+    log::info!("========== 批量应用变更 ==========");
+    log::info!("数据库: {}, 表: {}.{}, 变更数量: {}", database, schema, table, changes.len());
+
+    let connection = state.get_or_connect(&database).await?;
+    let client = &*connection;
+
+    let options = batch_options(&state, &database).await;
+    let mut result = transaction_manager::batch_apply_changes_with_options(client, &schema, &table, changes, &options).await;
+    if !result.success {
+        if let Some(note) = restore_triggers_after_failure(&state, client, &database, &schema, &table).await {
+            result.error = result.error.map(|e| format!("{}。{}", e, note));
+        }
+    }
+
+    log::info!("批量应用变更完成: success={}, rows_affected={}", result.success, result.rows_affected);
+    Ok(result)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     setup_logger().expect("无法设置日志");
@@ -1211,32 +4207,125 @@ pub fn run() {
     log::info!("PostgreSQL 数据库工具启动中 (pg_dump/pg_restore)...");
     log::info!("========================================");
 
-    let app_state = AppState::new();
+    prune_sql_logs();
+
+    let routing = get_routing_config();
+    let mut startup_database = get_db_config().default_database;
+    if startup_database.is_empty() {
+        // 未配置默认数据库时检查服务器自带的 postgres 数据库
+        startup_database = "postgres".to_string();
+    }
+
+    let app_state = AppState::new(services::startup_check::StartupStatus::checking(&routing, &startup_database));
+    let connections = app_state.connections.clone();
+    let startup_status = app_state.startup_status.clone();
+    let transactions = app_state.transactions.clone();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(app_state)
+        .setup(move |app| {
+            // 在后台检查连接，不阻塞窗口显示
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(check_startup_connection(
+                handle,
+                connections,
+                startup_status,
+                routing,
+                startup_database,
+            ));
+            tauri::async_runtime::spawn(reap_abandoned_transactions(transactions));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
+            get_startup_status,
             execute_sql,
+            cancel_all_queries,
+            begin_transaction,
+            execute_in_transaction,
+            commit_transaction,
+            rollback_transaction,
+            explain_query,
+            explain_analyze_buffers,
+            benchmark_query,
+            compare_plans,
+            list_query_templates,
+            save_query_template,
+            delete_query_template,
+            run_template,
+            execute_parameterized_sql,
+            execute_sql_streaming,
+            preview_affected_rows,
+            check_server_copy,
+            execute_client_copy,
+            explain_error,
             get_table_schema,
+            preview_defaults,
+            get_table_documentation,
+            script_table,
+            list_tablespaces,
+            list_views,
+            get_view_definition,
+            list_functions,
+            get_function_definition,
+            list_sequences,
+            reset_sequence,
+            get_edit_constraints,
+            get_column_stats,
+            get_table_stats,
+            diff_database_schemas,
             create_table,
             alter_table,
+            create_schema,
+            drop_schema,
             get_database_objects,
+            search_objects,
             export_database,
+            export_schema_ddl,
+            export_table_csv,
+            export_table_json,
             import_database,
+            import_csv,
+            import_table_csv,
+            generate_insert_script,
+            get_locks,
+            get_replication_status,
+            list_active_sessions,
+            terminate_session,
+            find_redundant_indexes,
+            get_index_usage,
+            get_table_bloat,
+            reindex,
+            vacuum_table,
+            analyze_table,
             list_databases,
             check_health,
+            close_connection,
+            close_all_connections,
+            list_profiles,
+            set_active_profile,
+            save_database_config,
             get_export_dir_path,
             get_log_dir_path,
+            get_sql_history,
+            search_sql_history,
+            export_sql_history,
             list_tables,
             get_table_data,
+            stream_table_rows,
+            listen_channels,
+            unlisten_channels,
             create_record,
             update_record,
             delete_record,
             batch_update_rows,
             batch_insert_rows,
-            batch_delete_rows
+            batch_delete_rows,
+            batch_apply_changes,
+            disable_table_triggers,
+            enable_table_triggers,
+            set_constraints_deferred
         ])
         .run(tauri::generate_context!())
         .expect("运行 Tauri 应用时出错");
@@ -1266,3 +4355,73 @@ fn setup_logger() -> Result<(), fern::InitError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_data_field_names() {
+        let data = TableData {
+            columns: Vec::new(),
+            primary_key: vec!["order_id".to_string(), "line".to_string()],
+            primary_key_columns: vec!["order_id".to_string(), "line".to_string()],
+            has_primary_key: true,
+            row_id_column: None,
+            editable: true,
+            rows: Vec::new(),
+            total_rows: 0,
+            total_rows_estimated: false,
+            page: 1,
+            page_size: 50,
+        };
+
+        let value = serde_json::to_value(&data).unwrap();
+        assert_eq!(value["primaryKey"], serde_json::json!(["order_id", "line"]));
+        assert_eq!(value["primaryKeyColumns"], value["primaryKey"]);
+        assert_eq!(value["hasPrimaryKey"], true);
+        assert!(value["rowIdColumn"].is_null());
+        assert_eq!(value["totalRowsEstimated"], false);
+        assert_eq!(value["pageSize"], 50);
+    }
+
+    /// 配置文件中的连接配置
+    fn file_config() -> DatabaseConfig {
+        serde_json::from_value(serde_json::json!({
+            "host": "localhost",
+            "port": "5432",
+            "user": "postgres",
+            "password": "postgres",
+            "connect_timeout_secs": 30,
+            "pool_max_size": 4
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_env_overrides_connection_settings() {
+        let env = HashMap::from([
+            ("PG_HOST", "db.internal"),
+            ("PG_CONNECT_TIMEOUT_SECS", "3"),
+            ("PG_POOL_MAX_SIZE", "20"),
+        ]);
+        let config = apply_env_overrides(file_config(), |name| env.get(name).map(|v| v.to_string()));
+
+        assert_eq!(config.host, "db.internal");
+        assert_eq!(config.connect_timeout_secs, Some(3));
+        assert_eq!(config.connect_timeout(), std::time::Duration::from_secs(3));
+        assert_eq!(config.pool_max_size, Some(20));
+    }
+
+    #[test]
+    fn test_invalid_env_values_keep_file_settings() {
+        let env = HashMap::from([("PG_CONNECT_TIMEOUT_SECS", "soon"), ("PG_POOL_MAX_SIZE", "-1")]);
+        let config = apply_env_overrides(file_config(), |name| env.get(name).map(|v| v.to_string()));
+        assert_eq!(config.connect_timeout_secs, Some(30));
+        assert_eq!(config.pool_max_size, Some(4));
+
+        let config = apply_env_overrides(file_config(), |_| None);
+        assert_eq!(config.connect_timeout_secs, Some(30));
+        assert_eq!(config.pool_max_size, Some(4));
+    }
+}
@@ -4,7 +4,13 @@
  * This module defines types for data manipulation operations including:
  * - Row update operations
  * - Batch update, insert, and delete requests
+ * - Per-row results of batches that continue past failing rows
+ * - Transaction isolation levels for batch operations
+ * - Mixed batches of ordered insert/update/delete changes
  * - Data modification tracking
+ * - CSV import modes and rejected row reports
+ * - Keyset-paginated row batches streamed to the explorer
+ * - Column filters and sort order for browsing table data
  * 
  * Validates: Requirements 9.1, 10.1, 12.1
  */
@@ -69,6 +75,290 @@ pub struct BatchOperationResponse {
     pub rows_affected: u64,
     /// Error message if operation failed
     pub error: Option<String>,
+    /// Result of each row, in request order; only filled when the batch continues past failing rows
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub per_row_results: Vec<RowResult>,
+    /// Whether the batch failed with a serialization failure (SQLSTATE 40001) and may succeed if retried
+    pub retryable: bool,
+    /// Index (in request order) of the row that made the batch fail, if a specific row caused it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_index: Option<usize>,
+    /// The failing row as it was sent (the row for inserts, `{primary_key, changes}` for updates,
+    /// the primary key for deletes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_row: Option<serde_json::Value>,
+}
+
+/// Transaction isolation level of a batch operation
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IsolationLevel {
+    /// READ COMMITTED (PostgreSQL's default)
+    ReadCommitted,
+    /// REPEATABLE READ
+    RepeatableRead,
+    /// SERIALIZABLE; commits may fail with a serialization failure and must then be retried
+    Serializable,
+}
+
+/// Result of a single row in a batch that continues past failing rows
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RowResult {
+    /// Index of the row in the request
+    pub index: usize,
+    /// Whether the row's statement succeeded and was kept
+    pub success: bool,
+    /// Number of rows affected by the row's statement (0 if it failed)
+    pub rows_affected: u64,
+    /// Error message if the row's statement failed
+    pub error: Option<String>,
+}
+
+/// One change in a mixed batch, tagged by `op` (`{"op": "insert" | "update" | "delete", ...}`)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum RowChange {
+    /// Insert a row (map of column name to value)
+    Insert {
+        row: HashMap<String, serde_json::Value>,
+    },
+    /// Update the row identified by `primary_key`
+    Update {
+        primary_key: HashMap<String, serde_json::Value>,
+        changes: HashMap<String, serde_json::Value>,
+    },
+    /// Delete the row identified by `primary_key`
+    Delete {
+        primary_key: HashMap<String, serde_json::Value>,
+    },
+}
+
+/// Response from applying a mixed batch of changes
+#[derive(Debug, Serialize, Clone)]
+pub struct BatchChangesResponse {
+    /// Whether all changes were committed
+    pub success: bool,
+    /// Total number of rows affected
+    pub rows_affected: u64,
+    /// Rows affected by each change, in request order (empty if the batch was rolled back)
+    pub operation_rows_affected: Vec<u64>,
+    /// Index of the change that failed, if the failure was caused by a specific change
+    pub failed_operation: Option<usize>,
+    /// Error message if the batch failed
+    pub error: Option<String>,
+}
+
+/// How a CSV file is loaded into a table
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvImportMode {
+    /// COPY straight into the target table; any bad row rolls back the whole import
+    #[default]
+    Copy,
+    /// COPY into an all-text staging table, then convert and insert in batches, collecting bad rows
+    Staged,
+}
+
+/// Output format of pg_dump
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DumpFormat {
+    /// Custom (compressed) format, restorable with pg_restore
+    #[default]
+    Custom,
+    /// Plain SQL script that can be read and diffed directly
+    PlainSql,
+    /// Directory format, one file per table
+    Directory,
+    /// tar archive
+    Tar,
+}
+
+impl DumpFormat {
+    /// Value of pg_dump's `-F` option
+    pub fn pg_dump_flag(&self) -> &'static str {
+        match self {
+            DumpFormat::Custom => "c",
+            DumpFormat::PlainSql => "p",
+            DumpFormat::Directory => "d",
+            DumpFormat::Tar => "t",
+        }
+    }
+
+    /// File extension of the dump (none for the directory format)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DumpFormat::Custom => ".backup",
+            DumpFormat::PlainSql => ".sql",
+            DumpFormat::Directory => "",
+            DumpFormat::Tar => ".tar",
+        }
+    }
+}
+
+/// Result of a database import (pg_restore)
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ImportResult {
+    /// Target database of the import
+    pub database: String,
+    /// Number of errors reported by pg_restore
+    pub errors: u32,
+    /// Number of warnings reported by pg_restore
+    pub warnings: u32,
+    /// Whether the target database already existed before the import
+    pub database_exists: bool,
+}
+
+impl ImportResult {
+    /// Result of an import refused because the target database exists and dropping it was not confirmed
+    pub fn refused_existing(database: &str) -> Self {
+        Self {
+            database: database.to_string(),
+            errors: 0,
+            warnings: 0,
+            database_exists: true,
+        }
+    }
+
+    /// Count errors and warnings in pg_restore's stderr output
+    ///
+    /// Each error starts with `pg_restore: error:` (`pg_restore: [archiver (db)] Error from TOC entry`
+    /// on PostgreSQL 11 and earlier). Without `--exit-on-error`, pg_restore ends with
+    /// `errors ignored on restore: N`; when present that count wins, and the line itself is not a warning.
+    pub fn from_pg_restore(database: &str, stderr: &str) -> Self {
+        let mut errors = 0u32;
+        let mut warnings = 0u32;
+        let mut ignored_errors = None;
+
+        for line in stderr.lines() {
+            let message = line.trim();
+            let message = message.strip_prefix("pg_restore:").unwrap_or(message).trim_start();
+            let lower = message.to_lowercase();
+
+            if let Some(index) = lower.find("errors ignored on restore:") {
+                ignored_errors = lower[index + "errors ignored on restore:".len()..]
+                    .trim()
+                    .parse::<u32>()
+                    .ok();
+            } else if lower.starts_with("error:") || lower.starts_with("[archiver (db)] error from toc entry") {
+                errors += 1;
+            } else if lower.starts_with("warning:") {
+                warnings += 1;
+            }
+        }
+
+        Self {
+            database: database.to_string(),
+            errors: ignored_errors.unwrap_or(errors),
+            warnings,
+            database_exists: false,
+        }
+    }
+}
+
+/// A row rejected during import
+#[derive(Debug, Serialize, Clone)]
+pub struct RejectedRow {
+    /// Data row number (1-based, excluding the header)
+    pub row_number: u64,
+    /// Column that caused the error, if known
+    pub column: Option<String>,
+    /// Raw values of the row, in target column order
+    pub values: Vec<Option<String>>,
+    /// Error message
+    pub error: String,
+}
+
+/// Result of a CSV import
+#[derive(Debug, Serialize, Clone)]
+pub struct CsvImportResult {
+    /// Import mode that was used
+    pub mode: CsvImportMode,
+    /// Number of rows loaded
+    pub rows_loaded: u64,
+    /// Rows that were rejected
+    pub rejected_rows: Vec<RejectedRow>,
+    /// Whether the whole import was rolled back (true in COPY mode when a row failed)
+    pub rolled_back: bool,
+}
+
+/// Filter applied when browsing table data (`{"column": "age", "operator": ">", "value": 30}`)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ColumnFilter {
+    /// Column name
+    pub column: String,
+    /// Comparison operator
+    pub operator: FilterOperator,
+    /// Value to compare against; ignored for `IS NULL`
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+/// Operator of a column filter
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum FilterOperator {
+    #[serde(rename = "=")]
+    Eq,
+    #[serde(rename = "!=")]
+    NotEq,
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = ">")]
+    Gt,
+    /// Text match (`%` and `_` are wildcards)
+    #[serde(rename = "LIKE", alias = "like")]
+    Like,
+    #[serde(rename = "IS NULL", alias = "is null")]
+    IsNull,
+}
+
+/// Sort column used when browsing table data
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SortColumn {
+    /// Column name
+    pub column: String,
+    /// Sort direction, ascending by default
+    #[serde(default)]
+    pub direction: SortDirection,
+}
+
+/// Sort direction
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    #[serde(alias = "ASC")]
+    Asc,
+    #[serde(alias = "DESC")]
+    Desc,
+}
+
+/// Result of a single-table export (CSV or JSON)
+#[derive(Debug, Serialize, Clone)]
+pub struct TableExportResult {
+    /// Path of the exported file
+    pub file_path: String,
+    /// Number of data rows exported (excluding the header)
+    pub rows: u64,
+    /// File size in bytes
+    pub bytes: u64,
+}
+
+/// A batch of rows sent to the frontend while streaming table data (`table-rows-batch` event payload)
+#[derive(Debug, Serialize, Clone)]
+pub struct TableRowsBatch {
+    /// Schema name
+    pub schema: String,
+    /// Table name
+    pub table: String,
+    /// Batch number, starting at 0
+    pub batch_index: u64,
+    /// Rows in this batch, each a JSON object of column name -> value
+    pub rows: Vec<serde_json::Value>,
+    /// Primary key of the last row in this batch (as text, in key column order), used to fetch the next batch
+    pub last_key: Option<Vec<String>>,
+    /// Whether the end of the table has been reached
+    pub done: bool,
 }
 
 impl RowUpdate {
@@ -137,6 +427,23 @@ impl BatchOperationResponse {
             success: true,
             rows_affected,
             error: None,
+            per_row_results: Vec::new(),
+            retryable: false,
+            failed_index: None,
+            failed_row: None,
+        }
+    }
+
+    /// Create a response for a committed batch whose failing rows were skipped
+    pub fn with_row_results(per_row_results: Vec<RowResult>) -> Self {
+        Self {
+            success: true,
+            rows_affected: per_row_results.iter().map(|r| r.rows_affected).sum(),
+            error: None,
+            per_row_results,
+            retryable: false,
+            failed_index: None,
+            failed_row: None,
         }
     }
 
@@ -146,6 +453,34 @@ impl BatchOperationResponse {
             success: false,
             rows_affected: 0,
             error: Some(error),
+            per_row_results: Vec::new(),
+            retryable: false,
+            failed_index: None,
+            failed_row: None,
+        }
+    }
+}
+
+impl BatchChangesResponse {
+    /// Create a successful response from the per-change affected counts
+    pub fn success(operation_rows_affected: Vec<u64>) -> Self {
+        Self {
+            success: true,
+            rows_affected: operation_rows_affected.iter().sum(),
+            operation_rows_affected,
+            failed_operation: None,
+            error: None,
+        }
+    }
+
+    /// Create an error response
+    pub fn error(failed_operation: Option<usize>, error: String) -> Self {
+        Self {
+            success: false,
+            rows_affected: 0,
+            operation_rows_affected: Vec::new(),
+            failed_operation,
+            error: Some(error),
         }
     }
 }
@@ -230,6 +565,39 @@ mod tests {
         assert_eq!(request.primary_keys.len(), 1);
     }
 
+    #[test]
+    fn test_import_result_counts_pg_restore_messages() {
+        let clean = "pg_restore: connecting to database for restore\n\
+                     pg_restore: creating TABLE \"public.users\"\n";
+        assert_eq!(
+            ImportResult::from_pg_restore("app", clean),
+            ImportResult { database: "app".to_string(), errors: 0, warnings: 0, database_exists: false }
+        );
+
+        let partial = "pg_restore: creating EXTENSION \"pgcrypto\"\n\
+                       pg_restore: while PROCESSING TOC:\n\
+                       pg_restore: from TOC entry 2; 3079 16385 EXTENSION pgcrypto\n\
+                       pg_restore: error: could not execute query: ERROR:  extension \"pgcrypto\" is not available\n\
+                       pg_restore: error: could not execute query: ERROR:  role \"app\" does not exist\n\
+                       pg_restore: warning: errors ignored on restore: 2\n";
+        let result = ImportResult::from_pg_restore("app", partial);
+        assert_eq!(result.errors, 2);
+        assert_eq!(result.warnings, 0);
+
+        // Older pg_restore versions, and the summary line taking precedence over counted lines
+        let legacy = "pg_restore: [archiver (db)] Error while PROCESSING TOC:\n\
+                      pg_restore: [archiver (db)] Error from TOC entry 5; 2615 2200 SCHEMA public\n\
+                      pg_restore: warning: could not set default_table_access_method\n\
+                      WARNING: errors ignored on restore: 3\n";
+        let result = ImportResult::from_pg_restore("app", legacy);
+        assert_eq!(result.errors, 3);
+        assert_eq!(result.warnings, 1);
+
+        // Fatal errors end the restore without a summary line
+        let fatal = "pg_restore: error: connection to server failed: FATAL:  database \"app\" does not exist\n";
+        assert_eq!(ImportResult::from_pg_restore("app", fatal).errors, 1);
+    }
+
     #[test]
     fn test_batch_operation_response() {
         let success = BatchOperationResponse::success(5);
@@ -242,4 +610,97 @@ mod tests {
         assert_eq!(error.rows_affected, 0);
         assert!(error.error.is_some());
     }
+
+    #[test]
+    fn test_batch_operation_response_with_row_results() {
+        let response = BatchOperationResponse::with_row_results(vec![
+            RowResult { index: 0, success: true, rows_affected: 1, error: None },
+            RowResult { index: 1, success: false, rows_affected: 0, error: Some("duplicate key".to_string()) },
+            RowResult { index: 2, success: true, rows_affected: 1, error: None },
+        ]);
+        assert!(response.success);
+        assert_eq!(response.rows_affected, 2);
+        assert_eq!(response.per_row_results.len(), 3);
+
+        // Atomic batches do not serialize per-row results
+        let json = serde_json::to_value(BatchOperationResponse::success(1)).unwrap();
+        assert!(json.get("per_row_results").is_none());
+    }
+
+    #[test]
+    fn test_isolation_level_deserialization() {
+        let level: IsolationLevel = serde_json::from_str("\"repeatable_read\"").unwrap();
+        assert_eq!(level, IsolationLevel::RepeatableRead);
+        assert!(serde_json::from_str::<IsolationLevel>("\"snapshot\"").is_err());
+    }
+
+    #[test]
+    fn test_row_change_deserialization() {
+        let changes: Vec<RowChange> = serde_json::from_value(serde_json::json!([
+            {"op": "insert", "row": {"id": 3, "name": "Carol"}},
+            {"op": "update", "primary_key": {"id": 1}, "changes": {"name": "Alice"}},
+            {"op": "delete", "primary_key": {"id": 2}}
+        ]))
+        .unwrap();
+
+        assert!(matches!(&changes[0], RowChange::Insert { row } if row.len() == 2));
+        assert!(matches!(&changes[1], RowChange::Update { changes, .. } if changes.len() == 1));
+        assert!(matches!(&changes[2], RowChange::Delete { primary_key } if primary_key.len() == 1));
+
+        let unknown: Result<RowChange, _> =
+            serde_json::from_value(serde_json::json!({"op": "upsert", "row": {}}));
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_filters_and_sort() {
+        let filters: Vec<ColumnFilter> = serde_json::from_value(serde_json::json!([
+            {"column": "age", "operator": ">", "value": 30},
+            {"column": "name", "operator": "LIKE", "value": "A%"},
+            {"column": "email", "operator": "IS NULL"}
+        ]))
+        .unwrap();
+        assert_eq!(filters[0].operator, FilterOperator::Gt);
+        assert_eq!(filters[1].operator, FilterOperator::Like);
+        assert_eq!(filters[2].operator, FilterOperator::IsNull);
+        assert!(filters[2].value.is_null());
+
+        let invalid: Result<ColumnFilter, _> =
+            serde_json::from_value(serde_json::json!({"column": "age", "operator": "; DROP", "value": 1}));
+        assert!(invalid.is_err());
+
+        let sort: Vec<SortColumn> = serde_json::from_value(serde_json::json!([
+            {"column": "age", "direction": "DESC"},
+            {"column": "name"}
+        ]))
+        .unwrap();
+        assert_eq!(sort[0].direction, SortDirection::Desc);
+        assert_eq!(sort[1].direction, SortDirection::Asc);
+    }
+
+    #[test]
+    fn test_batch_changes_response() {
+        let success = BatchChangesResponse::success(vec![1, 0, 2]);
+        assert!(success.success);
+        assert_eq!(success.rows_affected, 3);
+        assert_eq!(success.operation_rows_affected, vec![1, 0, 2]);
+
+        let error = BatchChangesResponse::error(Some(1), "Database error".to_string());
+        assert!(!error.success);
+        assert_eq!(error.failed_operation, Some(1));
+        assert!(error.operation_rows_affected.is_empty());
+    }
+
+    #[test]
+    fn test_dump_format() {
+        let format: DumpFormat = serde_json::from_value(serde_json::json!("plain_sql")).unwrap();
+        assert_eq!(format, DumpFormat::PlainSql);
+        assert_eq!(format.pg_dump_flag(), "p");
+        assert_eq!(format.extension(), ".sql");
+        assert_eq!(DumpFormat::default(), DumpFormat::Custom);
+        assert_eq!(DumpFormat::Custom.extension(), ".backup");
+        assert_eq!(DumpFormat::Directory.pg_dump_flag(), "d");
+        assert_eq!(DumpFormat::Directory.extension(), "");
+        assert_eq!(DumpFormat::Tar.pg_dump_flag(), "t");
+    }
 }
@@ -6,19 +6,31 @@
  * - Query execution results (query.rs)
  * - Database schema definitions (schema.rs)
  * - Data manipulation operations (data.rs)
+ * - Server activity diagnostics (monitoring.rs)
  */
 
 pub mod query;
 pub mod schema;
 pub mod data;
+pub mod monitoring;
 
 // Re-export commonly used types for convenience
-pub use query::{QueryResult, QueryResultType, ColumnInfo, ErrorPosition};
+pub use query::{
+    QueryResult, QueryResultType, ColumnInfo, ErrorPosition, PlanNode, ExplainAnalyzeResult,
+    AffectedRowsPreview, PlanChangeKind, PlanNodeChange, PlanComparison,
+};
 pub use schema::{
     TableSchema, ColumnDefinition, ConstraintDefinition, IndexDefinition,
-    TableDesign, TableChanges, ColumnModification,
+    TableDesign, TableChanges, ColumnModification, ColumnDefaultPreview, DefaultPreviewKind,
+    DiffKind, SchemaItemDiff, TableSchemaDiff, DatabaseSchemaDiff, ColumnEditConstraints,
+    ColumnStats, TableColumnStats, TableStats,
 };
 pub use data::{
     RowUpdate, BatchUpdateRequest, BatchInsertRequest, BatchDeleteRequest,
-    BatchOperationResponse,
+    BatchOperationResponse, RowResult, IsolationLevel, CsvImportMode, CsvImportResult, DumpFormat, ImportResult, RejectedRow, TableRowsBatch,
+};
+pub use monitoring::{
+    LockInfo, BlockingPair, LockReport, ActiveSession, ServerRole, StandbyInfo, WalReceiverInfo,
+    ReplicationStatus, IndexIssue, RedundantIndex, IndexUsage, BloatObjectKind, BloatEstimate,
+    ReindexTarget, ReindexResult, MaintenanceResult,
 };
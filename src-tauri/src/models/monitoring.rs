@@ -0,0 +1,277 @@
+/**
+ * Monitoring Type Definitions
+ * 
+ * This module defines types for server activity diagnostics including:
+ * - Locks held or awaited by sessions
+ * - Blocker/blocked session pairs (the blocking tree)
+ * - Replication role, lag, and connected standbys
+ * - Unused and redundant index candidates
+ * - Estimated table and index bloat
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// A lock held or awaited by a session, from `pg_locks`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LockInfo {
+    /// Backend process ID
+    pub pid: i32,
+    /// Lockable object type (relation, transactionid, tuple, ...)
+    pub lock_type: String,
+    /// Lock mode (e.g. "AccessExclusiveLock")
+    pub mode: String,
+    /// Whether the lock is held (true) or awaited (false)
+    pub granted: bool,
+    /// Locked relation, if the lock is on a relation
+    pub relation: Option<String>,
+    /// Database name of the session
+    pub database: Option<String>,
+    /// User name of the session
+    pub user_name: Option<String>,
+    /// Session state (active, idle in transaction, ...)
+    pub state: Option<String>,
+    /// Current or most recent query of the session
+    pub query: Option<String>,
+}
+
+/// A session blocked by another session
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockingPair {
+    /// PID of the waiting session
+    pub blocked_pid: i32,
+    /// Query the waiting session is trying to run
+    pub blocked_query: Option<String>,
+    /// Seconds the blocked query has been running
+    pub blocked_duration_secs: Option<f64>,
+    /// PID of the session holding the conflicting lock
+    pub blocking_pid: i32,
+    /// Current or most recent query of the blocking session
+    pub blocking_query: Option<String>,
+    /// State of the blocking session (e.g. "idle in transaction")
+    pub blocking_state: Option<String>,
+}
+
+/// Lock diagnostics for the server
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LockReport {
+    /// All locks except those of the diagnosing session
+    pub locks: Vec<LockInfo>,
+    /// Blocker/blocked pairs
+    pub blocking: Vec<BlockingPair>,
+}
+
+/// A client session connected to the database (`pg_stat_activity`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActiveSession {
+    /// Backend process ID
+    pub pid: i32,
+    /// User name of the session
+    pub user_name: Option<String>,
+    /// Application name reported by the client
+    pub application_name: Option<String>,
+    /// Client address (None for Unix socket connections)
+    pub client_addr: Option<String>,
+    /// Session state (active, idle, idle in transaction, ...)
+    pub state: Option<String>,
+    /// What the session is waiting for (`wait_event_type: wait_event`), if anything
+    pub wait_event: Option<String>,
+    /// Current query, or the most recent one for idle sessions
+    pub query: Option<String>,
+    /// When the current or most recent query started
+    pub query_start: Option<String>,
+    /// Seconds since the current or most recent query started
+    pub duration_secs: Option<f64>,
+}
+
+/// Replication role of the connected server
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerRole {
+    /// Accepts writes; may stream WAL to standbys
+    Primary,
+    /// In recovery; replays WAL from a primary or archive
+    Standby,
+}
+
+/// A standby connected to the primary, from `pg_stat_replication`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StandbyInfo {
+    /// Application name reported by the standby
+    pub application_name: Option<String>,
+    /// Standby address (None for Unix socket connections)
+    pub client_addr: Option<String>,
+    /// WAL sender state (streaming, catchup, ...)
+    pub state: Option<String>,
+    /// Synchronous state (async, sync, potential, quorum)
+    pub sync_state: Option<String>,
+    /// Last WAL location sent to the standby
+    pub sent_lsn: Option<String>,
+    /// Last WAL location replayed on the standby
+    pub replay_lsn: Option<String>,
+    /// Bytes between the primary's current WAL location and the standby's replay location
+    pub lag_bytes: Option<i64>,
+    /// Replay lag in seconds, as measured by the primary
+    pub lag_secs: Option<f64>,
+}
+
+/// WAL receiver status on a standby, from `pg_stat_wal_receiver`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WalReceiverInfo {
+    /// Receiver status (streaming, waiting, ...); None without pg_read_all_stats
+    pub status: Option<String>,
+    /// Primary host the receiver is connected to
+    pub sender_host: Option<String>,
+    /// Primary port the receiver is connected to
+    pub sender_port: Option<i32>,
+}
+
+/// Replication status of the connected server
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReplicationStatus {
+    /// Whether the server is a primary or a standby
+    pub role: ServerRole,
+    /// Whether any replication is set up (standbys connected, or running as a standby)
+    pub configured: bool,
+    /// Current WAL write location (primary) or last received location (standby)
+    pub current_lsn: Option<String>,
+    /// Last replayed WAL location (standby only)
+    pub replay_lsn: Option<String>,
+    /// Bytes received but not yet replayed (standby only)
+    pub lag_bytes: Option<i64>,
+    /// Seconds since the last replayed transaction (standby only; 0 when fully caught up)
+    pub lag_secs: Option<f64>,
+    /// Connected standbys (primary only)
+    pub standbys: Vec<StandbyInfo>,
+    /// WAL receiver status (standby only; None when replaying from an archive)
+    pub wal_receiver: Option<WalReceiverInfo>,
+}
+
+/// Why an index is suggested for removal
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexIssue {
+    /// The index has not been scanned since statistics were last reset
+    Unused,
+    /// The index's key columns are a leading prefix of another index on the same table
+    Redundant,
+}
+
+/// An index that is a candidate for `DROP INDEX`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedundantIndex {
+    /// Schema of the table
+    pub schema: String,
+    /// Table the index belongs to
+    pub table: String,
+    /// Index name
+    pub index_name: String,
+    /// Index definition (`pg_get_indexdef`)
+    pub definition: String,
+    /// Why the index is flagged
+    pub issue: IndexIssue,
+    /// Index that covers this one (redundant indexes only)
+    pub covered_by: Option<String>,
+    /// Number of index scans since statistics were last reset
+    pub scans: i64,
+    /// On-disk size of the index in bytes
+    pub size_bytes: i64,
+    /// Human-readable explanation
+    pub rationale: String,
+    /// Statement that drops the index
+    pub drop_statement: String,
+}
+
+/// Usage statistics of a single index
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IndexUsage {
+    /// Schema of the table
+    pub schema: String,
+    /// Table the index belongs to
+    pub table: String,
+    /// Index name
+    pub index_name: String,
+    /// Index definition (`pg_get_indexdef`)
+    pub definition: String,
+    /// Number of index scans since statistics were last reset (`pg_stat_user_indexes.idx_scan`)
+    pub scans: i64,
+    /// On-disk size of the index in bytes
+    pub size_bytes: i64,
+    /// Whether the index enforces uniqueness
+    pub is_unique: bool,
+    /// Whether the index is the table's primary key
+    pub is_primary: bool,
+    /// The index has never been scanned since statistics were last reset
+    pub possibly_unused: bool,
+    /// Another index on the same table that makes this one redundant (never set for
+    /// unique, primary key, or constraint-backing indexes)
+    pub duplicate_of: Option<String>,
+}
+
+/// Kind of relation a bloat estimate is for
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BloatObjectKind {
+    Table,
+    Index,
+}
+
+/// Estimated bloat of a table or B-tree index
+///
+/// Values are estimates derived from `pg_stats` and `pg_class`, not measurements.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BloatEstimate {
+    /// Table or index
+    pub kind: BloatObjectKind,
+    /// Schema of the table
+    pub schema: String,
+    /// Table name (for indexes, the indexed table)
+    pub table: String,
+    /// Index name (indexes only)
+    pub index_name: Option<String>,
+    /// Current on-disk size in bytes (including TOAST for tables)
+    pub real_size_bytes: i64,
+    /// Estimated bytes beyond what the live rows need at the current fillfactor
+    pub estimated_bloat_bytes: i64,
+    /// Estimated bloat as a percentage of the real size
+    pub estimated_bloat_percent: f64,
+    /// Fillfactor used in the estimate
+    pub fillfactor: i32,
+    /// Whether statistics are missing or unusable, making the estimate unreliable
+    pub unreliable: bool,
+}
+
+/// What a `REINDEX` rebuilds
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReindexTarget {
+    /// All indexes of a table (including its TOAST table)
+    Table { schema: String, name: String },
+    /// A single index
+    Index { schema: String, name: String },
+    /// All indexes in the current database
+    Database,
+}
+
+/// Outcome of a `REINDEX`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReindexResult {
+    /// Statement that was executed
+    pub statement: String,
+    /// Whether the indexes were rebuilt with `CONCURRENTLY`
+    pub concurrently: bool,
+    /// Elapsed time in milliseconds
+    pub duration_ms: u64,
+    /// Notices sent by the server while reindexing (`SEVERITY: message`)
+    pub notices: Vec<String>,
+}
+
+/// Outcome of a `VACUUM` or `ANALYZE`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MaintenanceResult {
+    /// Statement that was executed
+    pub statement: String,
+    /// Elapsed time in milliseconds
+    pub duration_ms: u64,
+    /// Notices sent by the server while running the statement (`SEVERITY: message`)
+    pub notices: Vec<String>,
+}
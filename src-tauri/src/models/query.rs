@@ -6,6 +6,13 @@
  * - Query result types (SELECT, INSERT, UPDATE, DELETE, DDL, Error)
  * - Error position information
  * - Column metadata
+ * - EXPLAIN ANALYZE plan trees with buffer usage
+ * - EXPLAIN (FORMAT JSON) plans with their total cost and estimated rows
+ * - Previews of rows affected by UPDATE/DELETE statements
+ * - Server-side COPY statements and their client-side rewrite
+ * - Explanations of SQLSTATE error codes
+ * - Query benchmark timing statistics
+ * - Chunks and summary of a streamed SELECT
  * 
  * Validates: Requirements 2.1, 2.3, 2.4, 2.5, 2.7
  */
@@ -30,6 +37,13 @@ pub struct QueryResult {
     pub error: Option<String>,
     /// Position of error in SQL (if available)
     pub error_position: Option<ErrorPosition>,
+    /// Warning about how the statement ran (e.g. a COPY that used the server's filesystem)
+    #[serde(default)]
+    pub warning: Option<String>,
+    /// Notices sent by the server while the statement ran (`SEVERITY: message`),
+    /// such as the output of `RAISE NOTICE` in PL/pgSQL
+    #[serde(default)]
+    pub notices: Vec<String>,
 }
 
 /// Type of query result
@@ -45,6 +59,8 @@ pub enum QueryResultType {
     Delete,
     /// DDL operation (CREATE, ALTER, DROP, etc.)
     Ddl,
+    /// COPY operation
+    Copy,
     /// Query execution error
     Error,
 }
@@ -71,6 +87,256 @@ pub struct ColumnInfo {
     pub is_primary_key: bool,
 }
 
+/// A node of an EXPLAIN (ANALYZE, BUFFERS) plan tree
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanNode {
+    /// Node type (e.g. "Seq Scan", "Hash Join")
+    pub node_type: String,
+    /// Scanned relation, for scan nodes
+    pub relation_name: Option<String>,
+    /// Relation alias, for scan nodes
+    pub alias: Option<String>,
+    /// Relationship to the parent node (Outer, Inner, ...)
+    pub parent_relationship: Option<String>,
+    /// Estimated startup cost
+    pub startup_cost: f64,
+    /// Estimated total cost
+    pub total_cost: f64,
+    /// Estimated rows per loop
+    pub estimated_rows: f64,
+    /// Actual rows per loop
+    pub actual_rows: f64,
+    /// Actual time to the first row per loop, in milliseconds
+    pub actual_startup_time_ms: f64,
+    /// Actual total time per loop, in milliseconds
+    pub actual_total_time_ms: f64,
+    /// Number of times the node was executed
+    pub loops: u64,
+    /// Shared buffer blocks found in cache
+    pub shared_hit_blocks: u64,
+    /// Shared buffer blocks read from disk
+    pub shared_read_blocks: u64,
+    /// Child nodes
+    pub children: Vec<PlanNode>,
+}
+
+/// Result of EXPLAIN (ANALYZE, BUFFERS)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExplainAnalyzeResult {
+    /// Root node of the plan tree
+    pub plan: PlanNode,
+    /// Planning time in milliseconds
+    pub planning_time_ms: f64,
+    /// Execution time in milliseconds
+    pub execution_time_ms: f64,
+    /// Warning about side effects when the statement modifies data
+    pub warning: Option<String>,
+}
+
+/// Plan returned by EXPLAIN (FORMAT JSON), optionally with ANALYZE and BUFFERS
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueryPlan {
+    /// The "Plan" object of the EXPLAIN output, as returned by the server
+    pub plan: serde_json::Value,
+    /// Estimated total cost of the root node
+    pub total_cost: f64,
+    /// Estimated number of rows returned by the root node
+    pub estimated_rows: f64,
+    /// Planning time in milliseconds (ANALYZE only)
+    pub planning_time_ms: Option<f64>,
+    /// Execution time in milliseconds (ANALYZE only)
+    pub execution_time_ms: Option<f64>,
+    /// Warning about side effects when the statement modifies data
+    pub warning: Option<String>,
+}
+
+/// Kind of difference between a baseline plan node and the current one
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanChangeKind {
+    /// The node at this position has a different type (e.g. Index Scan became Seq Scan)
+    NodeTypeChanged,
+    /// Same node type, but the estimated total cost changed significantly
+    CostChanged,
+    /// The node exists only in the current plan
+    NodeAdded,
+    /// The node exists only in the baseline plan
+    NodeRemoved,
+}
+
+/// A single difference between a baseline plan and the current plan
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanNodeChange {
+    /// Position of the node as child indexes from the root, e.g. "0.1" (root is "0")
+    pub path: String,
+    /// Kind of difference
+    pub kind: PlanChangeKind,
+    /// Node type in the baseline plan
+    pub before_node_type: Option<String>,
+    /// Node type in the current plan
+    pub after_node_type: Option<String>,
+    /// Scanned relation, for scan nodes
+    pub relation_name: Option<String>,
+    /// Estimated total cost in the baseline plan
+    pub before_cost: Option<f64>,
+    /// Estimated total cost in the current plan
+    pub after_cost: Option<f64>,
+    /// Human-readable description of the change
+    pub description: String,
+}
+
+/// Result of comparing a query's current plan with its stored baseline
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanComparison {
+    /// Hash of the normalized query, used as the baseline key
+    pub query_hash: String,
+    /// Whether no baseline existed and the current plan was stored as the baseline
+    pub baseline_created: bool,
+    /// When the baseline was captured
+    pub baseline_captured_at: String,
+    /// Estimated total cost of the baseline plan
+    pub baseline_cost: f64,
+    /// Estimated total cost of the current plan
+    pub current_cost: f64,
+    /// Relative change of the total cost in percent (positive means more expensive)
+    pub cost_change_percent: f64,
+    /// Whether any node type changed or nodes were added or removed
+    pub plan_changed: bool,
+    /// Node-level differences, in tree order
+    pub changes: Vec<PlanNodeChange>,
+    /// Current plan tree
+    pub current_plan: PlanNode,
+}
+
+/// Rows that an UPDATE or DELETE statement would affect, without running it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AffectedRowsPreview {
+    /// Type of the previewed statement (Update or Delete)
+    pub statement_type: QueryResultType,
+    /// Target table as written in the statement
+    pub table: String,
+    /// SELECT statement used to find the affected rows
+    pub preview_sql: String,
+    /// Total number of rows the statement would affect
+    pub affected_count: i64,
+    /// Column information of the returned rows
+    pub columns: Vec<ColumnInfo>,
+    /// Affected rows (all columns for DELETE, current values of the changed columns for UPDATE)
+    pub rows: Vec<HashMap<String, serde_json::Value>>,
+    /// Whether only the first rows are returned
+    pub truncated: bool,
+    /// Whether the statement has no WHERE clause and affects the whole table
+    pub full_table: bool,
+    /// Warning shown before running the statement
+    pub warning: Option<String>,
+}
+
+/// Whether a COPY statement reads from or writes to its target
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyDirection {
+    /// `COPY ... FROM`
+    From,
+    /// `COPY ... TO`
+    To,
+}
+
+/// What a server-side COPY statement reads from or writes to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerCopyTarget {
+    /// A file path on the database server
+    File,
+    /// A shell command run on the database server (`PROGRAM`)
+    Program,
+}
+
+/// A COPY statement that reads or writes the database server's filesystem
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServerCopyStatement {
+    /// Whether the statement reads (FROM) or writes (TO)
+    pub direction: CopyDirection,
+    /// Whether the target is a file or a program
+    pub target: ServerCopyTarget,
+    /// File path or shell command, as the server will see it
+    pub location: String,
+    /// 1-based character position of the file path or PROGRAM keyword in the SQL
+    pub position: usize,
+    /// Explanation shown before or after running the statement
+    pub warning: String,
+    /// The same statement using `FROM STDIN` / `TO STDOUT`, for running it with a file
+    /// on the client instead (file targets only)
+    pub client_sql: Option<String>,
+}
+
+/// Explanation of a SQLSTATE error code
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ErrorExplanation {
+    /// The five-character SQLSTATE code
+    pub code: String,
+    /// Error class (first two characters of the code)
+    pub class_code: String,
+    /// Error class name (e.g. "Object Not In Prerequisite State")
+    pub class_name: String,
+    /// Condition name (e.g. "lock_not_available"); None for codes not in the standard table
+    pub condition_name: Option<String>,
+    /// What the error means
+    pub description: String,
+    /// Common causes
+    pub causes: Vec<String>,
+    /// Common fixes
+    pub fixes: Vec<String>,
+}
+
+/// Timing statistics of a query run several times
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BenchmarkResult {
+    /// Number of measured runs
+    pub runs: u32,
+    /// Number of warmup runs executed before measuring (not included in the statistics)
+    pub warmup_runs: u32,
+    /// Whether cached plans were discarded before every run
+    pub reset_between_runs: bool,
+    /// Fastest run in milliseconds
+    pub min_ms: f64,
+    /// Slowest run in milliseconds
+    pub max_ms: f64,
+    /// Mean run time in milliseconds
+    pub mean_ms: f64,
+    /// Median run time in milliseconds
+    pub median_ms: f64,
+    /// 95th percentile run time in milliseconds (nearest rank)
+    pub p95_ms: f64,
+    /// Sample standard deviation in milliseconds (0 for a single run)
+    pub std_dev_ms: f64,
+    /// Rows returned by the last run (None for EXPLAIN)
+    pub row_count: Option<usize>,
+    /// Time of each measured run in milliseconds, in execution order
+    pub timings_ms: Vec<f64>,
+}
+
+/// A batch of rows from a streamed SELECT (payload of the `query-chunk` event)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueryChunk {
+    /// Chunk number, starting at 0
+    pub chunk_index: u64,
+    /// Column information, only set on the first chunk
+    pub columns: Option<Vec<ColumnInfo>>,
+    /// Rows of this chunk (as JSON values)
+    pub rows: Vec<HashMap<String, serde_json::Value>>,
+}
+
+/// Result of a streamed SELECT (payload of the `query-done` event)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueryStreamSummary {
+    /// Number of rows sent in chunks
+    pub total_rows: u64,
+    /// Whether reading stopped at the row limit before the end of the result
+    pub truncated: bool,
+    /// Query execution duration in milliseconds
+    pub duration_ms: u64,
+}
+
 impl QueryResult {
     /// Create a successful SELECT result
     pub fn select(
@@ -86,6 +352,8 @@ impl QueryResult {
             duration_ms,
             error: None,
             error_position: None,
+            warning: None,
+            notices: Vec::new(),
         }
     }
 
@@ -99,6 +367,8 @@ impl QueryResult {
             duration_ms,
             error: None,
             error_position: None,
+            warning: None,
+            notices: Vec::new(),
         }
     }
 
@@ -112,6 +382,8 @@ impl QueryResult {
             duration_ms,
             error: None,
             error_position: None,
+            warning: None,
+            notices: Vec::new(),
         }
     }
 
@@ -125,6 +397,8 @@ impl QueryResult {
             duration_ms,
             error: Some(error),
             error_position,
+            warning: None,
+            notices: Vec::new(),
         }
     }
 }
@@ -2,16 +2,26 @@
  * Schema Type Definitions
  * 
  * This module defines types for database schema management including:
- * - Table schema with columns, constraints, and indexes
+ * - Table schema with columns, constraints, indexes, and triggers
  * - Column definitions with data types and properties
  * - Constraint definitions (primary key, foreign key, unique, check)
  * - Index definitions
  * - Table design and modification structures
+ * - Column default value previews
+ * - Schema comparison reports
+ * - Per-column edit constraints for client-side validation
+ * - Tablespaces
+ * - Views and materialized views
+ * - Functions and procedures
+ * - Sequences
+ * - Table and database DDL scripts
+ * - Object search results
  * 
  * Validates: Requirements 5.1, 6.1, 7.1, 8.1
  */
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Complete schema information for a table
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,6 +36,18 @@ pub struct TableSchema {
     pub constraints: Vec<ConstraintDefinition>,
     /// List of indexes
     pub indexes: Vec<IndexDefinition>,
+    /// Table storage parameters from `pg_class.reloptions` (e.g. fillfactor)
+    #[serde(default)]
+    pub storage_parameters: HashMap<String, String>,
+    /// Tablespace of the table; None if it is in the database's default tablespace
+    #[serde(default)]
+    pub tablespace: Option<String>,
+    /// User-defined triggers on the table
+    #[serde(default)]
+    pub triggers: Vec<TriggerInfo>,
+    /// Table comment; None if none is set
+    #[serde(default)]
+    pub comment: Option<String>,
 }
 
 /// Definition of a table column
@@ -45,10 +67,29 @@ pub struct ColumnDefinition {
     pub is_nullable: bool,
     /// Default value expression
     pub column_default: Option<String>,
+    /// Expression of a stored generated column (`GENERATED ALWAYS AS (expr) STORED`)
+    #[serde(default)]
+    pub generated_expression: Option<String>,
+    /// Identity kind of an identity column (`GENERATED ... AS IDENTITY`)
+    #[serde(default)]
+    pub identity: Option<IdentityKind>,
     /// Whether this column is part of the primary key
     pub is_primary_key: bool,
     /// Whether this column has a unique constraint
     pub is_unique: bool,
+    /// Column comment emitted as `COMMENT ON COLUMN`
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// How an identity column generates its values
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityKind {
+    /// `GENERATED ALWAYS AS IDENTITY`: explicit values are rejected unless OVERRIDING SYSTEM VALUE is used
+    Always,
+    /// `GENERATED BY DEFAULT AS IDENTITY`: explicit values are accepted
+    ByDefault,
 }
 
 /// Definition of a table constraint
@@ -85,6 +126,23 @@ pub struct IndexDefinition {
     pub is_unique: bool,
 }
 
+/// A trigger on a table
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TriggerInfo {
+    /// Trigger name
+    pub name: String,
+    /// When the trigger fires (BEFORE, AFTER, INSTEAD OF)
+    pub timing: String,
+    /// Events that fire the trigger (INSERT, UPDATE, DELETE, TRUNCATE)
+    pub events: Vec<String>,
+    /// Whether the trigger fires for each row (false: once per statement)
+    pub for_each_row: bool,
+    /// Trigger function, as `schema.name`
+    pub function: String,
+    /// Whether the trigger is enabled
+    pub enabled: bool,
+}
+
 /// Design for creating or modifying a table
 #[derive(Debug, Deserialize, Clone)]
 pub struct TableDesign {
@@ -98,6 +156,15 @@ pub struct TableDesign {
     pub constraints: Vec<ConstraintDefinition>,
     /// List of indexes
     pub indexes: Vec<IndexDefinition>,
+    /// Table storage parameters emitted as `WITH (...)` (e.g. fillfactor, autovacuum settings)
+    #[serde(default)]
+    pub storage_parameters: HashMap<String, String>,
+    /// Tablespace emitted as `TABLESPACE name`; None uses the database's default tablespace
+    #[serde(default)]
+    pub tablespace: Option<String>,
+    /// Table comment emitted as `COMMENT ON TABLE`
+    #[serde(default)]
+    pub comment: Option<String>,
 }
 
 /// Changes to be applied to an existing table
@@ -113,10 +180,19 @@ pub struct TableChanges {
     pub added_constraints: Vec<ConstraintDefinition>,
     /// Constraint names to be dropped
     pub dropped_constraints: Vec<String>,
+    /// Constraints to be renamed, as (old name, new name)
+    #[serde(default)]
+    pub renamed_constraints: Vec<(String, String)>,
     /// Indexes to be added
     pub added_indexes: Vec<IndexDefinition>,
     /// Index names to be dropped
     pub dropped_indexes: Vec<String>,
+    /// Storage parameters to be set with `ALTER TABLE ... SET (...)`
+    #[serde(default)]
+    pub set_storage_parameters: HashMap<String, String>,
+    /// Storage parameter names to be reset with `ALTER TABLE ... RESET (...)`
+    #[serde(default)]
+    pub reset_storage_parameters: Vec<String>,
 }
 
 /// Modification to an existing column
@@ -126,6 +202,356 @@ pub struct ColumnModification {
     pub old_name: String,
     /// New column definition
     pub new_definition: ColumnDefinition,
+    /// Current column definition; only attributes that differ from it are altered.
+    /// When absent, every attribute is re-applied
+    #[serde(default)]
+    pub old_definition: Option<ColumnDefinition>,
+    /// Expression converting existing values to the new type, emitted as
+    /// `ALTER COLUMN ... TYPE ... USING <expression>`
+    #[serde(default)]
+    pub using_expression: Option<String>,
+}
+
+/// How a column default was previewed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultPreviewKind {
+    /// The default expression was evaluated and produced a sample value
+    Value,
+    /// The value is generated on insert (sequence or identity) and is not evaluated
+    Auto,
+    /// The default expression could not be evaluated
+    Error,
+}
+
+/// Preview of the value a column default would produce
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnDefaultPreview {
+    /// Column name
+    pub column: String,
+    /// Default expression (None for identity columns)
+    pub expression: Option<String>,
+    /// Preview kind
+    pub kind: DefaultPreviewKind,
+    /// Sample value as text (None if the default evaluates to NULL or was not evaluated)
+    pub value: Option<String>,
+    /// Error message if the expression could not be evaluated
+    pub error: Option<String>,
+}
+
+/// Kind of difference between two schema items
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    /// Present only in the target
+    Added,
+    /// Present only in the source
+    Removed,
+    /// Present in both with different definitions
+    Changed,
+}
+
+/// Difference of a single column, constraint, or index
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaItemDiff {
+    /// Item name (column, constraint, or index name)
+    pub name: String,
+    /// Kind of difference
+    pub kind: DiffKind,
+    /// Definition in the source (None if added)
+    pub source: Option<serde_json::Value>,
+    /// Definition in the target (None if removed)
+    pub target: Option<serde_json::Value>,
+}
+
+/// Structural differences of a table present in both databases
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableSchemaDiff {
+    /// Schema name
+    pub schema: String,
+    /// Table name
+    pub table_name: String,
+    /// Column differences
+    pub columns: Vec<SchemaItemDiff>,
+    /// Constraint differences
+    pub constraints: Vec<SchemaItemDiff>,
+    /// Index differences
+    pub indexes: Vec<SchemaItemDiff>,
+}
+
+/// Result of comparing the schemas of two databases
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DatabaseSchemaDiff {
+    /// Source database name
+    pub source_database: String,
+    /// Target database name
+    pub target_database: String,
+    /// Tables present only in the source, as "schema.table"
+    pub only_in_source: Vec<String>,
+    /// Tables present only in the target, as "schema.table"
+    pub only_in_target: Vec<String>,
+    /// Tables present in both with structural differences
+    pub changed_tables: Vec<TableSchemaDiff>,
+}
+
+/// Constraints on a single column, shaped for validating an edit form
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnEditConstraints {
+    /// Column name
+    pub name: String,
+    /// PostgreSQL data type
+    pub data_type: String,
+    /// Whether the column allows NULL values
+    pub nullable: bool,
+    /// Default value expression
+    pub default: Option<String>,
+    /// Maximum length for character types
+    pub max_length: Option<i32>,
+    /// Numeric precision
+    pub numeric_precision: Option<i32>,
+    /// Numeric scale
+    pub numeric_scale: Option<i32>,
+    /// Allowed labels, if the column is an enum type
+    pub enum_options: Option<Vec<String>>,
+    /// CHECK expressions that reference this column
+    pub check_expressions: Vec<String>,
+}
+
+/// Planner statistics for a single column, as recorded in `pg_stats`
+///
+/// All statistics fields are `None` when the column has no statistics
+/// (the table was never analyzed, or the column was added afterwards).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnStats {
+    /// Column name
+    pub name: String,
+    /// PostgreSQL data type
+    pub data_type: String,
+    /// Fraction of rows that are NULL
+    pub null_frac: Option<f64>,
+    /// Average width in bytes of non-null values
+    pub avg_width: Option<i32>,
+    /// Raw `n_distinct`: positive values are a count, negative values are minus the
+    /// fraction of rows (e.g. -1 means every row is distinct)
+    pub n_distinct: Option<f64>,
+    /// `n_distinct` converted to an estimated count using the table's row estimate
+    pub estimated_distinct: Option<f64>,
+    /// Most common values, as text
+    pub most_common_vals: Option<Vec<String>>,
+    /// Frequencies of the most common values
+    pub most_common_freqs: Option<Vec<f64>>,
+    /// Boundaries dividing the remaining values into equal-population buckets, as text
+    pub histogram_bounds: Option<Vec<String>>,
+}
+
+/// Planner statistics for all columns of a table
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableColumnStats {
+    /// Schema name
+    pub schema: String,
+    /// Table name
+    pub table: String,
+    /// When the statistics were gathered (latest of manual and auto ANALYZE);
+    /// None if the table has not been analyzed since statistics were reset
+    pub last_analyzed: Option<String>,
+    /// Estimated row count (`pg_class.reltuples`); negative if never analyzed
+    pub estimated_rows: f64,
+    /// Per-column statistics, in column order
+    pub columns: Vec<ColumnStats>,
+}
+
+/// Storage size, row counts, and maintenance history of a table
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableStats {
+    /// Schema name
+    pub schema: String,
+    /// Table name
+    pub table: String,
+    /// Total size in bytes, including indexes and TOAST (`pg_total_relation_size`)
+    pub total_bytes: i64,
+    /// Size of the table's main data in bytes (`pg_relation_size`)
+    pub table_bytes: i64,
+    /// Size of all indexes in bytes (`pg_indexes_size`)
+    pub indexes_bytes: i64,
+    /// Size of the TOAST table and other forks (free space map, visibility map) in bytes
+    pub toast_bytes: i64,
+    /// Estimated row count (`pg_class.reltuples`, or `n_live_tup` if never analyzed)
+    pub estimated_rows: i64,
+    /// Live rows according to the statistics collector
+    pub live_rows: Option<i64>,
+    /// Dead rows not yet removed by VACUUM; a high count relative to live rows indicates bloat
+    pub dead_rows: Option<i64>,
+    /// Last manual VACUUM
+    pub last_vacuum: Option<String>,
+    /// Last autovacuum
+    pub last_autovacuum: Option<String>,
+    /// Last manual ANALYZE
+    pub last_analyze: Option<String>,
+    /// Last autoanalyze
+    pub last_autoanalyze: Option<String>,
+}
+
+/// Comment on a single column (`COMMENT ON COLUMN`)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColumnDocumentation {
+    /// Column name
+    pub name: String,
+    /// Column comment; empty if none is set
+    pub comment: String,
+}
+
+/// Table and column comments, without the rest of the schema
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableDocumentation {
+    /// Table comment; empty if none is set
+    pub table_comment: String,
+    /// Column comments, in column order
+    pub columns: Vec<ColumnDocumentation>,
+}
+
+/// A tablespace, as listed in `pg_tablespace`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TablespaceInfo {
+    /// Tablespace name
+    pub name: String,
+    /// Owner role
+    pub owner: String,
+    /// Directory on the server; None for the built-in `pg_default` and `pg_global`
+    pub location: Option<String>,
+    /// Total size in bytes; None if the current user may not read it
+    pub size_bytes: Option<i64>,
+    /// Whether this is the current database's default tablespace
+    pub is_database_default: bool,
+}
+
+/// A view or materialized view
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ViewInfo {
+    /// Schema name
+    pub schema: String,
+    /// View name
+    pub name: String,
+    /// Whether this is a materialized view
+    pub is_materialized: bool,
+}
+
+/// A function or procedure, as listed in `pg_proc`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FunctionInfo {
+    /// Schema name
+    pub schema: String,
+    /// Routine name
+    pub name: String,
+    /// Argument list identifying the routine among overloads (e.g. "a integer, b text")
+    pub arguments: String,
+    /// Result type (e.g. "integer", "SETOF users", "TABLE(id integer)"); None for procedures
+    pub return_type: Option<String>,
+    /// Implementation language (sql, plpgsql, c, internal, ...)
+    pub language: String,
+    /// Whether this is a procedure (CALL) rather than a function
+    pub is_procedure: bool,
+}
+
+/// A sequence, as listed in `pg_sequences`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SequenceInfo {
+    /// Schema name
+    pub schema: String,
+    /// Sequence name
+    pub name: String,
+    /// Data type (smallint, integer or bigint)
+    pub data_type: String,
+    /// Last value returned by nextval; None if never used (or not readable by the current user)
+    pub last_value: Option<i64>,
+    /// Start value
+    pub start_value: i64,
+    /// Increment
+    pub increment: i64,
+    /// Minimum value
+    pub min_value: i64,
+    /// Maximum value
+    pub max_value: i64,
+    /// Whether the sequence wraps around at its limit
+    pub cycle: bool,
+    /// Column that owns the sequence (serial or identity column), as `table.column`
+    pub owned_by: Option<String>,
+}
+
+/// Runnable DDL that recreates an existing table
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableScript {
+    /// CREATE TABLE, indexes, sequence ownership and comments, separated by blank lines
+    pub script: String,
+    /// Table features the script can't reproduce (e.g. partitioning)
+    pub warnings: Vec<String>,
+}
+
+/// Runnable DDL that recreates the structure of a whole database (no data)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaScript {
+    /// Dependency-ordered DDL, grouped into commented sections
+    pub script: String,
+    /// Objects the script can't reproduce (e.g. domains, aggregates, generated columns)
+    pub warnings: Vec<String>,
+}
+
+/// Kind of object matched by an object search
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchObjectKind {
+    /// Table (including partitioned and foreign tables)
+    Table,
+    /// View or materialized view
+    View,
+    /// Function or procedure
+    Function,
+    /// Column of a table or view
+    Column,
+}
+
+impl SearchObjectKind {
+    /// All kinds, used when a search doesn't name any
+    pub const ALL: [SearchObjectKind; 4] = [Self::Table, Self::View, Self::Function, Self::Column];
+
+    /// Name used in the search query
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Table => "table",
+            Self::View => "view",
+            Self::Function => "function",
+            Self::Column => "column",
+        }
+    }
+}
+
+/// An object matched by an object search
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ObjectSearchMatch {
+    /// Object kind
+    pub kind: SearchObjectKind,
+    /// Schema name
+    pub schema: String,
+    /// Object name (the column name for columns)
+    pub name: String,
+    /// Owning table or view, for columns
+    pub table: Option<String>,
+    /// Object comment
+    pub comment: Option<String>,
+    /// Whether the name matched; false when only the comment did
+    pub name_matched: bool,
+}
+
+/// One page of object search results
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ObjectSearchPage {
+    /// Matches on this page, name matches first
+    pub matches: Vec<ObjectSearchMatch>,
+    /// Total number of matches across all pages
+    pub total: i64,
+    /// Page number (1-based)
+    pub page: u32,
+    /// Page size actually used (capped)
+    pub page_size: u32,
 }
 
 impl TableSchema {
@@ -137,6 +563,10 @@ impl TableSchema {
             columns: Vec::new(),
             constraints: Vec::new(),
             indexes: Vec::new(),
+            storage_parameters: HashMap::new(),
+            tablespace: None,
+            triggers: Vec::new(),
+            comment: None,
         }
     }
 
@@ -167,8 +597,11 @@ impl ColumnDefinition {
             numeric_scale: None,
             is_nullable,
             column_default: None,
+            generated_expression: None,
+            identity: None,
             is_primary_key: false,
             is_unique: false,
+            comment: None,
         }
     }
 
@@ -0,0 +1,301 @@
+/**
+ * Activity Monitor Service
+ *
+ * This module provides read-only server activity diagnostics including:
+ * - Listing locks held or awaited by each session (pg_locks + pg_stat_activity)
+ * - Detecting which sessions are blocked and by whom (pg_blocking_pids)
+ * - Listing the sessions connected to the current database and what they are running
+ * - Terminating a backend chosen by the user
+ * - Reporting replication role, lag, and connected standbys
+ */
+
+use crate::models::monitoring::{
+    ActiveSession, BlockingPair, LockInfo, LockReport, ReplicationStatus, ServerRole, StandbyInfo,
+    WalReceiverInfo,
+};
+use tokio_postgres::Client;
+
+/// Get all locks and the blocker/blocked session pairs
+///
+/// The diagnosing session's own locks are excluded.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+///
+/// # Returns
+/// * `Result<LockReport, String>` - Locks and blocking pairs or error message
+pub async fn get_locks(client: &Client) -> Result<LockReport, String> {
+    let locks = get_lock_list(client).await?;
+    let blocking = get_blocking_pairs(client).await?;
+
+    Ok(LockReport { locks, blocking })
+}
+
+/// Get locks from pg_locks joined with the owning session
+async fn get_lock_list(client: &Client) -> Result<Vec<LockInfo>, String> {
+    let query = r#"
+        SELECT
+            l.pid,
+            l.locktype,
+            l.mode,
+            l.granted,
+            CASE WHEN l.relation IS NOT NULL THEN l.relation::regclass::text END,
+            a.datname::text,
+            a.usename::text,
+            a.state,
+            a.query
+        FROM pg_locks l
+        LEFT JOIN pg_stat_activity a ON a.pid = l.pid
+        WHERE l.pid IS NOT NULL AND l.pid <> pg_backend_pid()
+        ORDER BY l.granted, l.pid, l.locktype
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("Failed to query locks: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| LockInfo {
+            pid: row.get(0),
+            lock_type: row.get(1),
+            mode: row.get(2),
+            granted: row.get(3),
+            relation: row.get(4),
+            database: row.get(5),
+            user_name: row.get(6),
+            state: row.get(7),
+            query: row.get(8),
+        })
+        .collect())
+}
+
+/// Get blocked sessions paired with each session blocking them
+async fn get_blocking_pairs(client: &Client) -> Result<Vec<BlockingPair>, String> {
+    let query = r#"
+        SELECT
+            blocked.pid,
+            blocked.query,
+            EXTRACT(EPOCH FROM now() - blocked.query_start)::float8,
+            blocker.pid,
+            blocker.query,
+            blocker.state
+        FROM pg_stat_activity blocked
+        CROSS JOIN LATERAL unnest(pg_blocking_pids(blocked.pid)) AS b(pid)
+        JOIN pg_stat_activity blocker ON blocker.pid = b.pid
+        ORDER BY blocker.pid, blocked.pid
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("Failed to query blocking sessions: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| BlockingPair {
+            blocked_pid: row.get(0),
+            blocked_query: row.get(1),
+            blocked_duration_secs: row.get(2),
+            blocking_pid: row.get(3),
+            blocking_query: row.get(4),
+            blocking_state: row.get(5),
+        })
+        .collect())
+}
+
+/// Get the client sessions connected to the current database
+///
+/// The listing session itself is excluded. Sessions running a query are listed
+/// first, longest-running first.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+///
+/// # Returns
+/// * `Result<Vec<ActiveSession>, String>` - Sessions or error message
+pub async fn list_active_sessions(client: &Client) -> Result<Vec<ActiveSession>, String> {
+    let query = r#"
+        SELECT
+            pid,
+            usename::text,
+            application_name,
+            host(client_addr),
+            state,
+            wait_event_type || ': ' || wait_event,
+            query,
+            query_start::text,
+            EXTRACT(EPOCH FROM now() - query_start)::float8
+        FROM pg_stat_activity
+        WHERE datname = current_database()
+          AND backend_type = 'client backend'
+          AND pid <> pg_backend_pid()
+        ORDER BY state = 'active' DESC, query_start NULLS LAST, pid
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("Failed to query sessions: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ActiveSession {
+            pid: row.get(0),
+            user_name: row.get(1),
+            application_name: row.get(2),
+            client_addr: row.get(3),
+            state: row.get(4),
+            wait_event: row.get(5),
+            query: row.get(6),
+            query_start: row.get(7),
+            duration_secs: row.get(8),
+        })
+        .collect())
+}
+
+/// Get the replication status of the connected server
+///
+/// Whether the server is a primary or a standby is detected with
+/// `pg_is_in_recovery()`. A primary without connected standbys is reported with
+/// `configured: false` rather than as an error.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+///
+/// # Returns
+/// * `Result<ReplicationStatus, String>` - Replication status or error message
+pub async fn get_replication_status(client: &Client) -> Result<ReplicationStatus, String> {
+    let in_recovery: bool = client
+        .query_one("SELECT pg_is_in_recovery()", &[])
+        .await
+        .map_err(|e| format!("Failed to query recovery state: {}", e))?
+        .get(0);
+
+    if in_recovery {
+        get_standby_status(client).await
+    } else {
+        get_primary_status(client).await
+    }
+}
+
+/// Replication status on a primary: current WAL location and connected standbys
+async fn get_primary_status(client: &Client) -> Result<ReplicationStatus, String> {
+    let current_lsn: String = client
+        .query_one("SELECT pg_current_wal_lsn()::text", &[])
+        .await
+        .map_err(|e| format!("Failed to query WAL location: {}", e))?
+        .get(0);
+
+    let query = r#"
+        SELECT
+            application_name,
+            host(client_addr),
+            state,
+            sync_state,
+            sent_lsn::text,
+            replay_lsn::text,
+            pg_wal_lsn_diff(pg_current_wal_lsn(), replay_lsn)::bigint,
+            EXTRACT(EPOCH FROM replay_lag)::float8
+        FROM pg_stat_replication
+        ORDER BY application_name
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("Failed to query replication status: {}", e))?;
+
+    let standbys: Vec<StandbyInfo> = rows
+        .iter()
+        .map(|row| StandbyInfo {
+            application_name: row.get(0),
+            client_addr: row.get(1),
+            state: row.get(2),
+            sync_state: row.get(3),
+            sent_lsn: row.get(4),
+            replay_lsn: row.get(5),
+            lag_bytes: row.get(6),
+            lag_secs: row.get(7),
+        })
+        .collect();
+
+    Ok(ReplicationStatus {
+        role: ServerRole::Primary,
+        configured: !standbys.is_empty(),
+        current_lsn: Some(current_lsn),
+        replay_lsn: None,
+        lag_bytes: None,
+        lag_secs: None,
+        standbys,
+        wal_receiver: None,
+    })
+}
+
+/// Replication status on a standby: received/replayed WAL locations and the WAL receiver
+async fn get_standby_status(client: &Client) -> Result<ReplicationStatus, String> {
+    let query = r#"
+        SELECT
+            pg_last_wal_receive_lsn()::text,
+            pg_last_wal_replay_lsn()::text,
+            pg_wal_lsn_diff(pg_last_wal_receive_lsn(), pg_last_wal_replay_lsn())::bigint,
+            CASE
+                WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+                ELSE EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())
+            END::float8
+    "#;
+
+    let row = client
+        .query_one(query, &[])
+        .await
+        .map_err(|e| format!("Failed to query standby WAL location: {}", e))?;
+
+    let receiver_rows = client
+        .query("SELECT status, sender_host, sender_port FROM pg_stat_wal_receiver", &[])
+        .await
+        .map_err(|e| format!("Failed to query WAL receiver: {}", e))?;
+
+    let wal_receiver = receiver_rows.first().map(|r| WalReceiverInfo {
+        status: r.get(0),
+        sender_host: r.get(1),
+        sender_port: r.get(2),
+    });
+
+    Ok(ReplicationStatus {
+        role: ServerRole::Standby,
+        configured: true,
+        current_lsn: row.get(0),
+        replay_lsn: row.get(1),
+        lag_bytes: row.get(2),
+        lag_secs: row.get(3),
+        standbys: Vec::new(),
+        wal_receiver,
+    })
+}
+
+/// Terminate a backend process
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `pid` - Process ID of the backend to terminate
+///
+/// # Returns
+/// * `Result<bool, String>` - Whether the signal was sent, or error message
+pub async fn terminate_backend(client: &Client, pid: i32) -> Result<bool, String> {
+    let own_pid: i32 = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .map_err(|e| format!("Failed to query backend pid: {}", e))?
+        .get(0);
+    if pid == own_pid {
+        return Err(format!("Backend {} is this tool's own connection", pid));
+    }
+
+    let row = client
+        .query_one("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .map_err(|e| format!("Failed to terminate backend {}: {}", pid, e))?;
+
+    Ok(row.get(0))
+}
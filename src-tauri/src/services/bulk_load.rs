@@ -0,0 +1,138 @@
+/**
+ * Bulk Load Service
+ *
+ * 此模块提供批量导入数据时临时关闭触发器的功能：
+ * - 禁用/启用表上的全部触发器（ALTER TABLE ... DISABLE/ENABLE TRIGGER ALL）
+ * - 记录当前由本工具禁用了触发器的表，导入失败时据此自动恢复
+ *
+ * 数据完整性风险：
+ * - DISABLE TRIGGER ALL 同时禁用外键检查使用的内部触发器，禁用期间写入的数据
+ *   不会检查外键，重新启用后也不会补检，可能留下孤立的引用
+ * - 业务触发器（审计、级联更新、计算列等）在禁用期间不会执行
+ * - 禁用状态保存在系统目录中，对所有会话生效，且在本工具退出后仍然保持，
+ *   必须显式重新启用
+ * - 需要表的所有者权限；禁用外键的内部触发器需要超级用户权限
+ */
+
+use crate::services::sql_utils::qualified_name;
+use std::collections::HashSet;
+use tokio_postgres::Client;
+
+/// 由本工具禁用了触发器的表（数据库、模式、表）
+#[derive(Debug, Default)]
+pub struct DisabledTriggerRegistry {
+    tables: HashSet<(String, String, String)>,
+}
+
+impl DisabledTriggerRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录表的触发器已被禁用
+    pub fn mark_disabled(&mut self, database: &str, schema: &str, table: &str) {
+        self.tables
+            .insert((database.to_string(), schema.to_string(), table.to_string()));
+    }
+
+    /// 记录表的触发器已重新启用，返回该表之前是否被记录为禁用
+    pub fn mark_enabled(&mut self, database: &str, schema: &str, table: &str) -> bool {
+        self.tables
+            .remove(&(database.to_string(), schema.to_string(), table.to_string()))
+    }
+
+    /// 表的触发器当前是否由本工具禁用
+    pub fn is_disabled(&self, database: &str, schema: &str, table: &str) -> bool {
+        self.tables
+            .contains(&(database.to_string(), schema.to_string(), table.to_string()))
+    }
+}
+
+/// 禁用表上的全部触发器（包括外键检查使用的内部触发器）
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+///
+/// # Returns
+/// * `Result<Vec<String>, String>` - 被禁用的触发器名称或错误信息
+pub async fn disable_triggers(client: &Client, schema: &str, table: &str) -> Result<Vec<String>, String> {
+    set_triggers_enabled(client, schema, table, false).await
+}
+
+/// 重新启用表上的全部触发器
+///
+/// 重新启用不会对禁用期间写入的数据补做外键检查或执行触发器。
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+///
+/// # Returns
+/// * `Result<Vec<String>, String>` - 被启用的触发器名称或错误信息
+pub async fn enable_triggers(client: &Client, schema: &str, table: &str) -> Result<Vec<String>, String> {
+    set_triggers_enabled(client, schema, table, true).await
+}
+
+async fn set_triggers_enabled(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    enabled: bool,
+) -> Result<Vec<String>, String> {
+    let row = client
+        .query_opt(
+            r#"
+            SELECT
+                pg_has_role(c.relowner, 'USAGE'),
+                ARRAY(SELECT t.tgname::text FROM pg_trigger t WHERE t.tgrelid = c.oid ORDER BY t.tgname)
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'p')
+            "#,
+            &[&schema, &table],
+        )
+        .await
+        .map_err(|e| format!("查询表 {}.{} 失败: {}", schema, table, e))?
+        .ok_or_else(|| format!("表 {}.{} 不存在", schema, table))?;
+
+    let is_owner: bool = row.get(0);
+    if !is_owner {
+        return Err(format!("只有表 {}.{} 的所有者才能禁用或启用触发器", schema, table));
+    }
+    let triggers: Vec<String> = row.get(1);
+
+    let sql = format!(
+        "ALTER TABLE {} {} TRIGGER ALL",
+        qualified_name(schema, table),
+        if enabled { "ENABLE" } else { "DISABLE" }
+    );
+    log::info!("执行: {}", sql);
+
+    client.batch_execute(&sql).await.map_err(|e| match e.as_db_error() {
+        Some(db_error) => db_error.message().to_string(),
+        None => e.to_string(),
+    })?;
+
+    Ok(triggers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let mut registry = DisabledTriggerRegistry::new();
+        registry.mark_disabled("db", "public", "orders");
+
+        assert!(registry.is_disabled("db", "public", "orders"));
+        assert!(!registry.is_disabled("other_db", "public", "orders"));
+        assert!(registry.mark_enabled("db", "public", "orders"));
+        assert!(!registry.mark_enabled("db", "public", "orders"));
+        assert!(!registry.is_disabled("db", "public", "orders"));
+    }
+}
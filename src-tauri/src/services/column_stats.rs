@@ -0,0 +1,132 @@
+/**
+ * Column Statistics Service
+ *
+ * This module reads the planner's per-column statistics from `pg_stats`
+ * (NULL fraction, distinct count, most common values, histogram, average width).
+ * No table data is scanned, so the overview is instant even for large tables,
+ * but it only reflects the data as of the last ANALYZE.
+ */
+
+use crate::models::schema::{ColumnStats, TableColumnStats};
+use tokio_postgres::Client;
+
+/// Get planner statistics for every column of a table
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `table` - Table name
+///
+/// # Returns
+/// * `Result<TableColumnStats, String>` - Column statistics and the analyze timestamp, or error message
+pub async fn get_column_stats(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<TableColumnStats, String> {
+    let table_row = client
+        .query_opt(
+            r#"
+            SELECT GREATEST(s.last_analyze, s.last_autoanalyze)::text, c.reltuples::float8
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            LEFT JOIN pg_stat_all_tables s ON s.relid = c.oid
+            WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'p', 'm', 'f')
+            "#,
+            &[&schema, &table],
+        )
+        .await
+        .map_err(|e| format!("Failed to query table statistics: {}", e))?
+        .ok_or_else(|| format!("Table {}.{} does not exist", schema, table))?;
+
+    let last_analyzed: Option<String> = table_row.get(0);
+    let estimated_rows: f64 = table_row.get(1);
+
+    // Partitioned tables only have statistics for the whole hierarchy (inherited = true);
+    // for other tables prefer the statistics of the table itself
+    let query = r#"
+        SELECT
+            a.attname::text,
+            format_type(a.atttypid, a.atttypmod),
+            s.null_frac::float8,
+            s.avg_width,
+            s.n_distinct::float8,
+            s.most_common_vals::text::text[],
+            s.most_common_freqs::float8[],
+            s.histogram_bounds::text::text[]
+        FROM pg_attribute a
+        JOIN pg_class c ON c.oid = a.attrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN LATERAL (
+            SELECT *
+            FROM pg_stats st
+            WHERE st.schemaname = n.nspname
+              AND st.tablename = c.relname
+              AND st.attname = a.attname
+            ORDER BY st.inherited
+            LIMIT 1
+        ) s ON true
+        WHERE n.nspname = $1
+          AND c.relname = $2
+          AND a.attnum > 0
+          AND NOT a.attisdropped
+        ORDER BY a.attnum
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query column statistics: {}", e))?;
+
+    let columns = rows
+        .iter()
+        .map(|row| {
+            let n_distinct: Option<f64> = row.get(4);
+            ColumnStats {
+                name: row.get(0),
+                data_type: row.get(1),
+                null_frac: row.get(2),
+                avg_width: row.get(3),
+                n_distinct,
+                estimated_distinct: n_distinct.map(|n| estimate_distinct(n, estimated_rows)),
+                most_common_vals: row.get(5),
+                most_common_freqs: row.get(6),
+                histogram_bounds: row.get(7),
+            }
+        })
+        .collect();
+
+    Ok(TableColumnStats {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        last_analyzed,
+        estimated_rows,
+        columns,
+    })
+}
+
+/// Convert `n_distinct` to an estimated count of distinct values
+///
+/// Negative values are a multiplier on the row count, which lets the estimate scale
+/// as the table grows.
+fn estimate_distinct(n_distinct: f64, estimated_rows: f64) -> f64 {
+    if n_distinct >= 0.0 {
+        n_distinct
+    } else {
+        (-n_distinct * estimated_rows.max(0.0)).round()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_distinct() {
+        assert_eq!(estimate_distinct(5.0, 1000.0), 5.0);
+        assert_eq!(estimate_distinct(-1.0, 1000.0), 1000.0);
+        assert_eq!(estimate_distinct(-0.25, 1000.0), 250.0);
+        // Never-analyzed tables report reltuples = -1
+        assert_eq!(estimate_distinct(-1.0, -1.0), 0.0);
+    }
+}
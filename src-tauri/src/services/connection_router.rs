@@ -0,0 +1,814 @@
+/**
+ * Connection Router Service
+ *
+ * 此模块负责在主库（写）与只读副本（读）之间路由连接：
+ * - 根据语句类型决定使用读端点还是写端点
+ * - 为每个端点维护独立的连接池（连接池表中使用不同的键），并发的命令各自从池中取得连接，互不阻塞
+ * - 连接池的最大连接数可配置（`pool_max_size`），连接数达到上限时等待其他命令归还连接
+ * - 通过 application_name 标记连接所属的端点，便于在服务端识别
+ * - 按端点的 TLS 配置建立连接
+ * - 建立连接有超时限制（`connect_timeout`），服务器不可达时不会长时间挂起
+ * - 从池中取出连接时检查其是否已断开，并用一个有超时的 `DISCARD ALL` 重置上一个使用者留下的会话状态、
+ *   同时确认服务器仍有响应，失效或无法重置的连接被丢弃并透明地换成新连接（例如服务器重启或网络中断后）
+ * - 连接断开时按指数退避自动重连，并透明地重试失败的只读查询（写操作和调用了可能有副作用的函数的查询不重试）
+ * - 为维护操作和 LISTEN 建立不放入连接池的专用连接
+ * - 连接从连接池中移除或连接池被释放时中止其后台连接任务，避免任务和套接字泄漏
+ * - 可以关闭指定数据库或全部数据库的连接池，释放不再使用的连接
+ */
+
+use crate::models::query::{QueryResult, QueryResultType};
+use crate::services::query_executor::{self, determine_query_type, parse_sql_statements};
+use crate::services::sql_lexer::{find_write_keyword, tokenize, Token, TokenKind};
+use crate::services::tls::{self, NoticeBuffer, TlsSettings};
+use deadpool::managed::{self, Metrics, RecycleError, RecycleResult};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio_postgres::error::DbError;
+use tokio_postgres::{Client, Notification};
+
+/// 写端点（主库）连接使用的 application_name
+pub const WRITE_APPLICATION_NAME: &str = "pg-db-tool";
+
+/// 读端点（只读副本）连接使用的 application_name
+pub const READ_APPLICATION_NAME: &str = "pg-db-tool-read";
+
+/// 维护操作（REINDEX 等）专用连接使用的 application_name
+pub const MAINTENANCE_APPLICATION_NAME: &str = "pg-db-tool-maintenance";
+
+/// LISTEN 专用连接使用的 application_name
+pub const LISTEN_APPLICATION_NAME: &str = "pg-db-tool-listen";
+
+/// SQL 编辑器中显式事务的专用连接使用的 application_name
+pub const TRANSACTION_APPLICATION_NAME: &str = "pg-db-tool-transaction";
+
+/// 健康检查临时连接使用的 application_name
+pub const HEALTH_CHECK_APPLICATION_NAME: &str = "pg-db-tool-health";
+
+/// 默认的建立连接超时时间
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 从连接池取出空闲连接时重置会话并确认其仍然可用的超时时间
+pub const LIVENESS_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 默认的每个连接池的最大连接数
+pub const DEFAULT_POOL_MAX_SIZE: usize = 10;
+
+/// 连接端点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Endpoint {
+    /// 只读副本
+    Read,
+    /// 主库
+    Write,
+}
+
+/// 单个端点的连接参数
+#[derive(Debug, Clone)]
+pub struct EndpointConfig {
+    pub host: String,
+    pub port: String,
+    pub user: String,
+    pub password: String,
+    /// TLS 配置
+    pub tls: TlsSettings,
+}
+
+/// 连接池中的连接：客户端及其后台连接任务
+///
+/// 被移出连接池或连接池被释放时（即值被 drop 时）中止连接任务，
+/// 确保旧连接的任务结束、套接字被关闭。可以像 `Client` 一样使用。
+pub struct PooledClient {
+    client: Client,
+    task: JoinHandle<()>,
+    notices: NoticeBuffer,
+}
+
+impl PooledClient {
+    /// 由客户端和运行其连接的任务创建，服务器通知只写入日志
+    pub fn new(client: Client, task: JoinHandle<()>) -> Self {
+        Self::with_notices(client, task, NoticeBuffer::default())
+    }
+
+    /// 由客户端、运行其连接的任务和连接任务写入的通知缓存创建
+    pub fn with_notices(client: Client, task: JoinHandle<()>, notices: NoticeBuffer) -> Self {
+        Self { client, task, notices }
+    }
+
+    /// 取出并清空连接收到的服务器通知（`SEVERITY: message`）
+    pub fn take_notices(&self) -> Vec<String> {
+        self.notices.take()
+    }
+
+    /// 后台连接任务的句柄，可用于检查任务是否已结束
+    pub fn task(&self) -> AbortHandle {
+        self.task.abort_handle()
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.client
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        &mut self.client
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 为一个端点上的一个数据库建立连接的连接池管理器
+pub struct ConnectionManager {
+    /// 连接池在连接池表中的键，用于日志
+    key: String,
+    /// 连接的数据库
+    database: String,
+    connection_string: String,
+    tls: TlsSettings,
+    reconnect: ReconnectPolicy,
+    connect_timeout: Duration,
+}
+
+impl managed::Manager for ConnectionManager {
+    type Type = PooledClient;
+    type Error = String;
+
+    async fn create(&self) -> Result<PooledClient, String> {
+        log::info!("创建新的数据库连接: {}", self.key);
+        let (client, task, notices) = connect_with_retry(
+            &self.connection_string,
+            &self.tls,
+            &self.reconnect,
+            self.connect_timeout,
+        )
+        .await?;
+        Ok(PooledClient::with_notices(client, task, notices))
+    }
+
+    async fn recycle(&self, client: &mut PooledClient, _metrics: &Metrics) -> RecycleResult<String> {
+        // 服务器重启或终止会话后，连接任务会结束，客户端随之变为已关闭
+        if client.is_closed() || client.task.is_finished() {
+            log::warn!("数据库连接已断开，丢弃并重新连接: {}", self.key);
+            return Err(RecycleError::Backend("数据库连接已断开".to_string()));
+        }
+
+        // 上一个使用者可能留下了会话状态（SET/set_config 设置的参数、临时表、咨询锁、LISTEN、
+        // 预备语句等），用 DISCARD ALL 重置，使下一个使用者拿到干净的会话。
+        // 网络中断等情况下连接处于半开状态，客户端无法察觉，这条语句同时确认服务器仍有响应。
+        // 事务未结束的连接上 DISCARD ALL 会失败，这样的连接也被丢弃
+        match tokio::time::timeout(LIVENESS_CHECK_TIMEOUT, client.batch_execute("DISCARD ALL")).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => {
+                log::warn!("重置数据库会话失败，丢弃并重新连接: {}: {}", self.key, e);
+                Err(RecycleError::Backend(format!("重置数据库会话失败: {}", e)))
+            }
+            Err(_) => {
+                log::warn!("数据库连接检查超时，丢弃并重新连接: {}", self.key);
+                Err(RecycleError::Backend("数据库连接检查超时".to_string()))
+            }
+        }
+    }
+}
+
+/// 一个端点上一个数据库的连接池，克隆后共享同一个池
+pub type ConnectionPool = managed::Pool<ConnectionManager>;
+
+/// 从连接池中取出的连接，被 drop 时归还连接池；可以像 `Client` 一样使用
+pub type PooledConnection = managed::Object<ConnectionManager>;
+
+/// 连接池表，键由端点和数据库组成（见 `connection_pool`）
+pub type ConnectionPools = HashMap<String, ConnectionPool>;
+
+/// 连接断开后的自动重连策略
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    /// 首次连接失败后的最大重试次数（0 表示不重试）
+    pub max_retries: u32,
+    /// 第一次重试前的等待时间，之后每次翻倍
+    pub initial_backoff: Duration,
+    /// 单次等待时间的上限
+    pub max_backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// 第 `retry` 次重试（从 0 开始）前的等待时间
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+/// 读写路由配置
+#[derive(Debug, Clone)]
+pub struct RoutingConfig {
+    /// 主库端点
+    pub write: EndpointConfig,
+    /// 只读副本端点（未配置时所有语句都发往主库）
+    pub read: Option<EndpointConfig>,
+    /// 是否将只读语句发往副本
+    pub prefer_replica_for_reads: bool,
+    /// 自动重连策略
+    pub reconnect: ReconnectPolicy,
+    /// 单次建立连接（包括 TLS 握手和认证）的超时时间，超时不重试
+    pub connect_timeout: Duration,
+    /// 每个连接池的最大连接数（小于 1 时按 1 处理）
+    pub pool_max_size: usize,
+}
+
+impl RoutingConfig {
+    /// 根据 SQL 语句类型选择端点
+    ///
+    /// 仅当启用了副本读取、配置了读端点，并且所有语句都是只读的 SELECT（见 `is_read_only`）时才使用读端点；
+    /// 其他情况（包括无法识别的语句）一律发往主库。
+    pub fn route(&self, sql: &str) -> Endpoint {
+        if self.prefer_replica_for_reads && self.read.is_some() && is_read_only(sql) {
+            Endpoint::Read
+        } else {
+            Endpoint::Write
+        }
+    }
+
+    /// 端点连接使用的 TLS 配置，未配置读端点时回退到主库
+    pub fn tls_settings(&self, endpoint: Endpoint) -> &TlsSettings {
+        &self.endpoint_config(endpoint).0.tls
+    }
+
+    /// 获取端点的连接参数，未配置读端点时回退到主库
+    fn endpoint_config(&self, endpoint: Endpoint) -> (&EndpointConfig, Endpoint) {
+        match (endpoint, &self.read) {
+            (Endpoint::Read, Some(read)) => (read, Endpoint::Read),
+            _ => (&self.write, Endpoint::Write),
+        }
+    }
+}
+
+/// 已知没有副作用的函数，以及后面可以跟括号的关键字和类型名（均为小写）
+///
+/// 不在此列表中的函数调用（包括 `nextval`、`set_config`、`pg_terminate_backend`、`pg_advisory_lock`
+/// 和所有用户定义的函数）都可能修改数据或会话状态，调用它们的 SELECT 不视为只读。
+const SIDE_EFFECT_FREE_CALLS: &[&str] = &[
+    // 关键字
+    "all", "and", "any", "array", "as", "between", "by", "case", "else", "exists", "filter", "from", "in", "join",
+    "not", "on", "or", "over", "row", "select", "some", "then", "using", "values", "when", "where", "within",
+    // 类型修饰符，如 `x::varchar(10)`
+    "bit", "char", "character", "decimal", "float", "interval", "numeric", "time", "timestamp", "timestamptz",
+    "varchar",
+    // 聚合函数和窗口函数
+    "array_agg", "avg", "bool_and", "bool_or", "count", "cume_dist", "dense_rank", "every", "first_value",
+    "json_agg", "json_object_agg", "jsonb_agg", "jsonb_object_agg", "lag", "last_value", "lead", "max", "min",
+    "ntile", "percent_rank", "rank", "row_number", "string_agg", "sum",
+    // 条件表达式和类型转换
+    "cast", "coalesce", "greatest", "least", "nullif",
+    // 字符串函数
+    "btrim", "char_length", "concat", "concat_ws", "format", "left", "length", "lower", "lpad", "ltrim", "md5",
+    "octet_length", "position", "regexp_match", "regexp_matches", "regexp_replace", "replace", "right", "rpad",
+    "rtrim", "split_part", "starts_with", "string_to_array", "strpos", "substr", "substring", "trim", "upper",
+    // 数学函数
+    "abs", "ceil", "ceiling", "floor", "mod", "power", "round", "sqrt", "trunc",
+    // 日期时间函数
+    "age", "date_part", "date_trunc", "extract", "make_date", "make_interval", "now", "to_char", "to_date",
+    "to_number", "to_timestamp",
+    // 数组和 JSON 函数
+    "array_length", "array_to_string", "cardinality", "generate_series", "json_build_object",
+    "jsonb_array_elements", "jsonb_build_object", "jsonb_each", "row_to_json", "to_json", "to_jsonb", "unnest",
+    // 系统信息函数
+    "col_description", "current_database", "current_schema", "current_setting", "format_type",
+    "has_table_privilege", "obj_description", "pg_backend_pid", "pg_database_size", "pg_get_constraintdef",
+    "pg_get_functiondef", "pg_get_indexdef", "pg_get_viewdef", "pg_indexes_size", "pg_relation_size",
+    "pg_size_pretty", "pg_table_size", "pg_total_relation_size", "pg_typeof", "version",
+];
+
+/// SQL 是否只包含只读的 SELECT 语句（可以安全地发往副本或在重连后重试）
+///
+/// 包含修改数据的 CTE、`SELECT ... INTO` 或 `FOR UPDATE`/`FOR SHARE` 行锁的语句视为写操作。
+/// 调用了不在 `SIDE_EFFECT_FREE_CALLS` 中的函数的语句（如 `SELECT nextval(...)`）同样不视为只读，
+/// 以免在重连后重复执行其副作用，或被发往无法执行它的副本。
+pub fn is_read_only(sql: &str) -> bool {
+    let statements = parse_sql_statements(sql.trim());
+    !statements.is_empty()
+        && statements.iter().all(|statement| {
+            let tokens = tokenize(statement);
+            determine_query_type(statement) == QueryResultType::Select
+                && find_write_keyword(statement, &tokens).is_none()
+                && find_unsafe_call(statement, &tokens).is_none()
+        })
+}
+
+/// 第一个可能有副作用的函数调用（后面紧跟 `(` 且不在 `SIDE_EFFECT_FREE_CALLS` 中的标识符）
+///
+/// 带引号的函数名总是视为可能有副作用。
+fn find_unsafe_call<'a>(sql: &str, tokens: &'a [Token]) -> Option<&'a Token> {
+    tokens.windows(2).find_map(|pair| {
+        let (name, next) = (&pair[0], &pair[1]);
+        if !name.is_identifier() || next.kind != TokenKind::Symbol('(') {
+            return None;
+        }
+        let known = name.kind == TokenKind::Word
+            && SIDE_EFFECT_FREE_CALLS.contains(&name.text(sql).to_lowercase().as_str());
+        (!known).then_some(name)
+    })
+}
+
+/// 连接池在连接池表中的键
+///
+/// 由端点、用户、主机、端口和数据库组成，不同服务器或不同用户上的同名数据库不会共用连接。
+fn connection_key(endpoint: Endpoint, config: &EndpointConfig, database: &str) -> String {
+    let prefix = match endpoint {
+        Endpoint::Write => "write",
+        Endpoint::Read => "read",
+    };
+    format!("{}:{}@{}:{}/{}", prefix, config.user, config.host, config.port, database)
+}
+
+/// 获取指定端点和数据库的连接池，尚不存在时创建
+///
+/// 读端点与写端点使用各自的连接池，互不复用。创建连接池时不会建立连接，
+/// 连接在第一次取用时建立，网络层面的失败按 `config.reconnect` 的策略重试。
+/// 返回的连接池是表中连接池的克隆，调用方可以在释放连接池表的锁之后再取用连接。
+pub fn connection_pool(
+    pools: &mut ConnectionPools,
+    config: &RoutingConfig,
+    database: &str,
+    endpoint: Endpoint,
+) -> Result<ConnectionPool, String> {
+    let (endpoint_config, endpoint) = config.endpoint_config(endpoint);
+    let key = connection_key(endpoint, endpoint_config, database);
+
+    if let Some(pool) = pools.get(&key) {
+        return Ok(pool.clone());
+    }
+
+    let application_name = match endpoint {
+        Endpoint::Write => WRITE_APPLICATION_NAME,
+        Endpoint::Read => READ_APPLICATION_NAME,
+    };
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={} application_name={}",
+        endpoint_config.host,
+        endpoint_config.port,
+        endpoint_config.user,
+        endpoint_config.password,
+        database,
+        application_name
+    );
+    let manager = ConnectionManager {
+        key: key.clone(),
+        database: database.to_string(),
+        connection_string,
+        tls: endpoint_config.tls.clone(),
+        reconnect: config.reconnect.clone(),
+        connect_timeout: config.connect_timeout,
+    };
+
+    log::info!("创建连接池: {} (最大连接数 {})", key, config.pool_max_size.max(1));
+    let pool = ConnectionPool::builder(manager)
+        .max_size(config.pool_max_size.max(1))
+        .build()
+        .map_err(|e| format!("无法创建连接池: {}", e))?;
+    pools.insert(key, pool.clone());
+
+    Ok(pool)
+}
+
+/// 关闭指定数据库的连接池（包括主库和只读副本），返回关闭的连接数
+///
+/// 连接池从表中移除，池中的空闲连接立即断开；正在使用的连接在用完后断开，也计入返回的连接数。
+/// 之后再访问该数据库时会创建新的连接池。
+pub fn close_database_pools(pools: &mut ConnectionPools, database: &str) -> usize {
+    close_pools_matching(pools, |pool| pool.manager().database == database)
+}
+
+/// 关闭所有连接池，返回关闭的连接数
+pub fn close_all_pools(pools: &mut ConnectionPools) -> usize {
+    close_pools_matching(pools, |_| true)
+}
+
+fn close_pools_matching(pools: &mut ConnectionPools, matches: impl Fn(&ConnectionPool) -> bool) -> usize {
+    let mut closed = 0;
+    pools.retain(|key, pool| {
+        if !matches(pool) {
+            return true;
+        }
+        let size = pool.status().size;
+        log::info!("关闭连接池: {} ({} 个连接)", key, size);
+        closed += size;
+        pool.close();
+        false
+    });
+    closed
+}
+
+/// 从连接池中取出一个连接
+///
+/// 优先复用空闲连接；已断开的空闲连接会被丢弃，没有可用的空闲连接时建立新连接。
+/// 连接数已达上限时等待其他调用方归还连接。
+pub async fn get_connection(pool: &ConnectionPool) -> Result<PooledConnection, String> {
+    pool.get().await.map_err(|e| match e {
+        managed::PoolError::Backend(e) => e,
+        e => format!("无法从连接池获取连接: {}", e),
+    })
+}
+
+/// 在主库上建立一个不放入连接池的专用连接，并接收服务器通知
+///
+/// 用于不能在事务中执行的维护语句（如 `REINDEX ... CONCURRENTLY`）：专用连接处于
+/// 自动提交模式，不会受到共享连接上未结束事务的影响，也不会阻塞其他命令。
+///
+/// # Arguments
+/// * `config` - 路由配置
+/// * `database` - 数据库名称
+///
+/// # Returns
+/// * `Result<(Client, UnboundedReceiver<DbError>), String>` - 客户端连接和通知接收端，或错误信息
+pub async fn connect_maintenance(
+    config: &RoutingConfig,
+    database: &str,
+) -> Result<(Client, UnboundedReceiver<DbError>), String> {
+    log::info!("创建维护连接: {}:{}", config.write.host, database);
+    let connection_string = dedicated_connection_string(config, database, MAINTENANCE_APPLICATION_NAME);
+    within_connect_timeout(config.connect_timeout, tls::connect_with_notices(&connection_string, &config.write.tls)).await
+}
+
+/// 在主库上建立一个用于 LISTEN 的专用连接，并接收异步通知
+///
+/// 监听需要一直保持连接，因此不使用共享连接，避免与查询互相阻塞。
+///
+/// # Arguments
+/// * `config` - 路由配置
+/// * `database` - 数据库名称
+///
+/// # Returns
+/// * `Result<(Client, UnboundedReceiver<Notification>), String>` - 客户端连接和异步通知接收端，或错误信息
+pub async fn connect_listener(
+    config: &RoutingConfig,
+    database: &str,
+) -> Result<(Client, UnboundedReceiver<Notification>), String> {
+    log::info!("创建监听连接: {}:{}", config.write.host, database);
+    let connection_string = dedicated_connection_string(config, database, LISTEN_APPLICATION_NAME);
+    within_connect_timeout(
+        config.connect_timeout,
+        tls::connect_with_notifications(&connection_string, &config.write.tls),
+    )
+    .await
+}
+
+/// 在主库上建立一个不放入连接池的专用连接，用于 SQL 编辑器中的显式事务
+///
+/// 事务中的所有语句必须在同一个连接上执行，因此每个打开的事务独占一个连接；
+/// 返回值被丢弃时连接关闭，服务器随之回滚尚未提交的事务。
+///
+/// # Arguments
+/// * `config` - 路由配置
+/// * `database` - 数据库名称
+///
+/// # Returns
+/// * `Result<PooledClient, String>` - 客户端连接或错误信息
+pub async fn connect_transaction(config: &RoutingConfig, database: &str) -> Result<PooledClient, String> {
+    log::info!("创建事务连接: {}:{}", config.write.host, database);
+    let connection_string = dedicated_connection_string(config, database, TRANSACTION_APPLICATION_NAME);
+    let (client, task, notices) = within_connect_timeout(
+        config.connect_timeout,
+        tls::connect_with_notice_buffer(&connection_string, &config.write.tls),
+    )
+    .await?;
+    Ok(PooledClient::with_notices(client, task, notices))
+}
+
+/// 数据库健康检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerHealth {
+    /// `SELECT 1` 的往返耗时（毫秒）
+    pub latency_ms: f64,
+    /// 服务器版本（`SHOW server_version`）
+    pub server_version: String,
+}
+
+/// 在主库上建立一个临时连接并执行 `SELECT 1`，检查数据库是否可达
+///
+/// 连接和查询都必须在 `config.connect_timeout` 内完成，失败不重试，连接不放入连接池。
+///
+/// # Arguments
+/// * `config` - 路由配置
+/// * `database` - 数据库名称
+///
+/// # Returns
+/// * `Result<ServerHealth, String>` - 查询延迟和服务器版本，或错误信息（包括超时）
+pub async fn check_health(config: &RoutingConfig, database: &str) -> Result<ServerHealth, String> {
+    let connection_string = dedicated_connection_string(config, database, HEALTH_CHECK_APPLICATION_NAME);
+    within_connect_timeout(config.connect_timeout, async {
+        let (client, task) = tls::connect_with_task(&connection_string, &config.write.tls).await?;
+        let result = async {
+            let start = Instant::now();
+            client.simple_query("SELECT 1").await?;
+            let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+            let row = client.query_one("SHOW server_version", &[]).await?;
+            Ok::<_, tokio_postgres::Error>(ServerHealth {
+                latency_ms,
+                server_version: row.get(0),
+            })
+        }
+        .await;
+        task.abort();
+        result.map_err(|e| format!("数据库无响应: {}", e))
+    })
+    .await
+}
+
+/// 在 `timeout` 内完成建立连接，超时返回错误
+async fn within_connect_timeout<T, E: Into<String>>(
+    timeout: Duration,
+    connect: impl std::future::Future<Output = Result<T, E>>,
+) -> Result<T, String> {
+    match tokio::time::timeout(timeout, connect).await {
+        Ok(result) => result.map_err(Into::into),
+        Err(_) => Err(connect_timeout_error(timeout)),
+    }
+}
+
+/// 建立连接超时的错误信息
+fn connect_timeout_error(timeout: Duration) -> String {
+    format!("连接超时：{} 秒内未能连接到数据库服务器", timeout.as_secs_f64())
+}
+
+/// 主库上专用连接的连接字符串
+fn dedicated_connection_string(config: &RoutingConfig, database: &str, application_name: &str) -> String {
+    let endpoint_config = &config.write;
+    format!(
+        "host={} port={} user={} password={} dbname={} application_name={}",
+        endpoint_config.host,
+        endpoint_config.port,
+        endpoint_config.user,
+        endpoint_config.password,
+        database,
+        application_name
+    )
+}
+
+/// 建立连接，网络层面的失败按指数退避重试
+///
+/// 每次尝试都受 `timeout` 限制；超时说明服务器不可达或无响应，直接返回错误而不重试，避免界面长时间等待。
+async fn connect_with_retry(
+    connection_string: &str,
+    tls_settings: &TlsSettings,
+    policy: &ReconnectPolicy,
+    timeout: Duration,
+) -> Result<(Client, JoinHandle<()>, NoticeBuffer), String> {
+    let mut retry = 0;
+    loop {
+        let attempt = tokio::time::timeout(timeout, tls::connect_with_notice_buffer(connection_string, tls_settings))
+            .await
+            .map_err(|_| connect_timeout_error(timeout))?;
+        match attempt {
+            Ok(connected) => return Ok(connected),
+            Err(e) if e.is_retryable() && retry < policy.max_retries => {
+                let backoff = policy.backoff(retry);
+                log::warn!(
+                    "{}，{} ms 后重试 ({}/{})",
+                    e,
+                    backoff.as_millis(),
+                    retry + 1,
+                    policy.max_retries
+                );
+                tokio::time::sleep(backoff).await;
+                retry += 1;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// 在从连接池取出的连接上执行 SQL，连接断开时自动重连
+///
+/// 如果执行失败是因为连接已断开，会将该连接移出连接池；只读查询（见 `is_read_only`）在池中的另一个连接上
+/// 透明地重试一次，写操作和调用了可能有副作用的函数的查询不重试（无法确定是否已在服务器上执行），
+/// 直接返回错误，下一次操作时再重连。
+/// 执行期间服务器发送的通知（如 `RAISE NOTICE`）附加到结果的 `notices` 中。
+///
+/// # Arguments
+/// * `pool` - 连接所属的连接池
+/// * `connection` - 执行 SQL 的连接
+/// * `sql` - 要执行的 SQL
+///
+/// # Returns
+/// * `Result<QueryResult, String>` - 执行结果；无法建立连接时返回错误信息
+pub async fn execute_with_reconnect(
+    pool: &ConnectionPool,
+    connection: PooledConnection,
+    sql: &str,
+) -> Result<QueryResult, String> {
+    // 丢弃之前的语句在该连接上留下的通知
+    connection.take_notices();
+    let mut result = query_executor::execute_sql(&connection, sql).await;
+    result.notices = connection.take_notices();
+
+    if result.result_type != QueryResultType::Error || !connection_lost(&connection).await {
+        return Ok(result);
+    }
+
+    let key = &pool.manager().key;
+    drop(PooledConnection::take(connection));
+
+    if !is_read_only(sql) {
+        log::warn!("执行期间数据库连接已断开，写操作或可能有副作用的查询不自动重试: {}", key);
+        return Ok(result);
+    }
+
+    log::warn!("执行期间数据库连接已断开，重新连接后重试只读查询: {}", key);
+    let connection = get_connection(pool).await?;
+    connection.take_notices();
+    let mut result = query_executor::execute_sql(&connection, sql).await;
+    result.notices = connection.take_notices();
+    Ok(result)
+}
+
+/// 判断执行失败后连接是否已断开（而不是 SQL 本身出错）
+async fn connection_lost(client: &Client) -> bool {
+    // 服务器终止会话时，客户端可能还未察觉，用一个空查询确认
+    client.is_closed() || client.simple_query("").await.is_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(host: &str) -> EndpointConfig {
+        EndpointConfig {
+            host: host.to_string(),
+            port: "5432".to_string(),
+            user: "postgres".to_string(),
+            password: "postgres".to_string(),
+            tls: TlsSettings::default(),
+        }
+    }
+
+    fn routing(read: Option<EndpointConfig>, prefer_replica_for_reads: bool) -> RoutingConfig {
+        RoutingConfig {
+            write: endpoint("primary"),
+            read,
+            prefer_replica_for_reads,
+            reconnect: ReconnectPolicy::default(),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            pool_max_size: DEFAULT_POOL_MAX_SIZE,
+        }
+    }
+
+    #[test]
+    fn test_route_selects_to_replica() {
+        let config = routing(Some(endpoint("replica")), true);
+        assert_eq!(config.route("SELECT * FROM users"), Endpoint::Read);
+        assert_eq!(config.route("  -- comment\nSELECT 1; SELECT 2"), Endpoint::Read);
+        assert_eq!(config.route("INSERT INTO users VALUES (1)"), Endpoint::Write);
+        assert_eq!(config.route("SELECT 1; DELETE FROM users"), Endpoint::Write);
+        assert_eq!(config.route("VACUUM users"), Endpoint::Write);
+        assert_eq!(config.route(""), Endpoint::Write);
+    }
+
+    #[test]
+    fn test_route_without_replica_goes_to_primary() {
+        assert_eq!(routing(None, true).route("SELECT 1"), Endpoint::Write);
+        assert_eq!(
+            routing(Some(endpoint("replica")), false).route("SELECT 1"),
+            Endpoint::Write
+        );
+    }
+
+    #[test]
+    fn test_read_endpoint_falls_back_to_primary() {
+        let config = routing(None, true);
+        let (endpoint_config, endpoint) = config.endpoint_config(Endpoint::Read);
+        assert_eq!(endpoint, Endpoint::Write);
+        assert_eq!(endpoint_config.host, "primary");
+    }
+
+    #[test]
+    fn test_connection_key_includes_server_and_user() {
+        let primary = endpoint("db");
+        let mut other_port = endpoint("db");
+        other_port.port = "5433".to_string();
+        let mut other_user = endpoint("db");
+        other_user.user = "reporting".to_string();
+
+        let key = connection_key(Endpoint::Write, &primary, "app");
+        assert_eq!(key, "write:postgres@db:5432/app");
+        assert_ne!(key, connection_key(Endpoint::Write, &other_port, "app"));
+        assert_ne!(key, connection_key(Endpoint::Write, &other_user, "app"));
+        assert_ne!(key, connection_key(Endpoint::Read, &primary, "app"));
+        assert_ne!(key, connection_key(Endpoint::Write, &primary, "other"));
+    }
+
+    #[test]
+    fn test_connection_pool_is_created_once_per_key() {
+        let mut config = routing(Some(endpoint("replica")), true);
+        config.pool_max_size = 0;
+        let mut pools = ConnectionPools::new();
+
+        let pool = connection_pool(&mut pools, &config, "app", Endpoint::Write).unwrap();
+        connection_pool(&mut pools, &config, "app", Endpoint::Write).unwrap();
+        assert_eq!(pools.len(), 1);
+        // 创建连接池时不建立连接
+        assert_eq!(pool.status().size, 0);
+        assert_eq!(pool.status().max_size, 1);
+
+        connection_pool(&mut pools, &config, "app", Endpoint::Read).unwrap();
+        connection_pool(&mut pools, &config, "other", Endpoint::Write).unwrap();
+        assert_eq!(pools.len(), 3);
+    }
+
+    #[test]
+    fn test_close_database_pools_removes_read_and_write_pools() {
+        let config = routing(Some(endpoint("replica")), true);
+        let mut pools = ConnectionPools::new();
+        let pool = connection_pool(&mut pools, &config, "app", Endpoint::Write).unwrap();
+        connection_pool(&mut pools, &config, "app", Endpoint::Read).unwrap();
+        connection_pool(&mut pools, &config, "other", Endpoint::Write).unwrap();
+
+        assert_eq!(close_database_pools(&mut pools, "app"), 0);
+        assert_eq!(pools.len(), 1);
+        assert!(pool.is_closed());
+        assert_eq!(close_database_pools(&mut pools, "missing"), 0);
+        assert_eq!(pools.len(), 1);
+
+        close_all_pools(&mut pools);
+        assert!(pools.is_empty());
+    }
+
+    #[test]
+    fn test_connect_timeout_error() {
+        assert_eq!(
+            connect_timeout_error(Duration::from_secs(10)),
+            "连接超时：10 秒内未能连接到数据库服务器"
+        );
+        assert_eq!(
+            connect_timeout_error(Duration::from_millis(1500)),
+            "连接超时：1.5 秒内未能连接到数据库服务器"
+        );
+    }
+
+    #[test]
+    fn test_reconnect_backoff() {
+        let policy = ReconnectPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_only_selects_are_read_only() {
+        assert!(is_read_only("SELECT 1"));
+        assert!(is_read_only("SELECT 1; SELECT 2;"));
+        assert!(!is_read_only("UPDATE users SET name = 'x'"));
+        assert!(!is_read_only("SELECT 1; DELETE FROM users"));
+        assert!(!is_read_only(""));
+        assert!(!is_read_only("WITH d AS (DELETE FROM users RETURNING *) SELECT * FROM d"));
+        assert!(!is_read_only("SELECT * INTO users_copy FROM users"));
+        assert!(!is_read_only("SELECT * FROM users FOR UPDATE"));
+        assert!(!is_read_only("SELECT * FROM users FOR KEY SHARE"));
+        assert!(is_read_only("WITH t AS (SELECT 1) SELECT * FROM t"));
+    }
+
+    #[test]
+    fn test_selects_calling_side_effect_functions_are_not_read_only() {
+        assert!(!is_read_only("SELECT nextval('users_id_seq')"));
+        assert!(!is_read_only("SELECT pg_catalog.NEXTVAL('users_id_seq')"));
+        assert!(!is_read_only("SELECT set_config('app.user', 'alice', false)"));
+        assert!(!is_read_only("SELECT pg_terminate_backend(1234)"));
+        assert!(!is_read_only("SELECT pg_advisory_lock(1)"));
+        assert!(!is_read_only("SELECT archive_old_orders()"));
+        assert!(!is_read_only("SELECT \"count\"(*) FROM users"));
+
+        assert!(is_read_only("SELECT count(*), max(id) FROM users WHERE id IN (1, 2)"));
+        assert!(is_read_only("SELECT pg_backend_pid() AS pid"));
+        assert!(is_read_only("SELECT current_setting('application_name') AS app"));
+        assert!(is_read_only("SELECT name::varchar(10) FROM users WHERE EXISTS (SELECT 1)"));
+        assert!(is_read_only("SELECT 'nextval(1)' AS text"));
+    }
+}
@@ -0,0 +1,787 @@
+/**
+ * CSV Import Service
+ *
+ * 此模块提供 CSV 文件导入功能，支持两种模式：
+ * - Copy：直接使用 COPY FROM STDIN 导入，速度最快；出现错误行时整体回滚，
+ *   并从 PostgreSQL 的 COPY 错误上下文中解析出行号和列名
+ * - Staged：先 COPY 到全文本类型的临时表，再分批转换类型插入目标表，
+ *   出错的批次会逐行重试，收集所有被拒绝的行及其原始值
+ *
+ * 大文件可以使用 `import_csv_file` 以流的方式按 Copy 模式导入，不会一次性读入内存。
+ */
+
+use crate::models::data::{CsvImportMode, CsvImportResult, RejectedRow};
+use crate::services::schema_service::{get_writable_columns, WritableColumn};
+use crate::services::sql_utils::{qualified_name, quote_identifier};
+use bytes::Bytes;
+use futures_util::{pin_mut, SinkExt};
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio_postgres::Client;
+
+/// 每次发送给 COPY 的数据块大小
+const COPY_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Staged 模式下每批转换插入的行数
+const STAGED_BATCH_SIZE: i64 = 1000;
+
+/// Staged 模式使用的临时表名
+const STAGE_TABLE: &str = "__csv_import_stage";
+
+/// 将 CSV 数据导入到已有的表
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `data` - CSV 文件内容
+/// * `has_header` - 第一行是否为表头；有表头时按表头列名匹配目标列
+/// * `mode` - 导入模式
+///
+/// # Returns
+/// * `Result<CsvImportResult, String>` - 导入行数与被拒绝的行，或无法导入时的错误信息
+pub async fn import_csv(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    data: &[u8],
+    has_header: bool,
+    mode: CsvImportMode,
+) -> Result<CsvImportResult, String> {
+    let table_columns = get_writable_columns(client, schema, table).await?;
+    if table_columns.is_empty() {
+        return Err(format!("表 {}.{} 不存在或没有可写入的列", schema, table));
+    }
+
+    let columns = if has_header {
+        resolve_header_columns(data, table_columns)?
+    } else {
+        table_columns
+    };
+    check_column_count(data, has_header, &columns)?;
+
+    log::info!(
+        "CSV 导入: {}.{}, 模式: {:?}, 列数: {}",
+        schema, table, mode, columns.len()
+    );
+
+    match mode {
+        CsvImportMode::Copy => import_with_copy(client, schema, table, &columns, data, has_header).await,
+        CsvImportMode::Staged => import_with_staging(client, schema, table, &columns, data, has_header).await,
+    }
+}
+
+/// 按 CSV 表头确定导入的列及顺序
+fn resolve_header_columns(
+    data: &[u8],
+    table_columns: Vec<WritableColumn>,
+) -> Result<Vec<WritableColumn>, String> {
+    let text = String::from_utf8_lossy(data);
+    let header_line = text.lines().next().ok_or("CSV 文件为空")?;
+    let header = parse_csv_line(header_line);
+
+    let mut remaining = table_columns;
+    let mut columns = Vec::with_capacity(header.len());
+
+    for name in header {
+        let name = name.trim();
+        let position = remaining
+            .iter()
+            .position(|c| c.name == name)
+            .or_else(|| remaining.iter().position(|c| c.name.eq_ignore_ascii_case(name)))
+            .ok_or_else(|| format!("CSV 表头中的列 {} 在表中不存在", name))?;
+        columns.push(remaining.remove(position));
+    }
+
+    Ok(columns)
+}
+
+/// 检查每条数据记录的列数与导入的目标列数是否一致
+///
+/// 在发送数据前给出明确的错误和行号，而不是 COPY 的 "extra data after last expected column"。
+fn check_column_count(data: &[u8], has_header: bool, columns: &[WritableColumn]) -> Result<(), String> {
+    let mut checker = ColumnCountChecker::new(has_header, columns);
+    checker.feed(data)?;
+    checker.finish()
+}
+
+/// 按块读取 CSV 文件，检查每条数据记录的列数
+async fn check_file_column_count(path: &Path, has_header: bool, columns: &[WritableColumn]) -> Result<(), String> {
+    let mut file = File::open(path)
+        .await
+        .map_err(|e| format!("无法读取文件 {}: {}", path.display(), e))?;
+    let mut checker = ColumnCountChecker::new(has_header, columns);
+
+    let mut buffer = vec![0u8; COPY_CHUNK_SIZE];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("读取文件失败: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        checker.feed(&buffer[..read])?;
+    }
+
+    checker.finish()
+}
+
+/// 逐块扫描 CSV 数据，统计每条记录的字段数
+///
+/// 引号内的逗号和换行属于字段内容，因此一条记录可以跨多行；报告的行号是记录开始的行
+/// （文件中的行号，包含表头），与 COPY 错误中的行号一致。
+/// 数据末尾引号未闭合时不报错，由 COPY 报告 "unterminated CSV quoted field"。
+struct ColumnCountChecker<'a> {
+    columns: &'a [WritableColumn],
+    skip_header: bool,
+    in_quotes: bool,
+    /// 当前所在的行号（从 1 开始）
+    line: u64,
+    /// 当前记录开始的行号
+    record_line: u64,
+    /// 当前记录已读到的字段数
+    fields: usize,
+    /// 当前记录是否还没有任何内容
+    record_empty: bool,
+}
+
+impl<'a> ColumnCountChecker<'a> {
+    fn new(has_header: bool, columns: &'a [WritableColumn]) -> Self {
+        Self {
+            columns,
+            skip_header: has_header,
+            in_quotes: false,
+            line: 1,
+            record_line: 1,
+            fields: 1,
+            record_empty: true,
+        }
+    }
+
+    fn feed(&mut self, chunk: &[u8]) -> Result<(), String> {
+        for &byte in chunk {
+            match byte {
+                // 转义的 `""` 连续切换两次，状态不变
+                b'"' => self.in_quotes = !self.in_quotes,
+                b',' if !self.in_quotes => self.fields += 1,
+                b'\n' if self.in_quotes => self.line += 1,
+                b'\n' => {
+                    self.end_record()?;
+                    self.line += 1;
+                    self.record_line = self.line;
+                    self.fields = 1;
+                    self.record_empty = true;
+                    continue;
+                }
+                _ => {}
+            }
+            self.record_empty = false;
+        }
+        Ok(())
+    }
+
+    /// 检查最后一条没有以换行结尾的记录
+    fn finish(&mut self) -> Result<(), String> {
+        if self.record_empty || self.in_quotes {
+            return Ok(());
+        }
+        self.end_record()
+    }
+
+    fn end_record(&mut self) -> Result<(), String> {
+        if std::mem::take(&mut self.skip_header) || self.fields == self.columns.len() {
+            return Ok(());
+        }
+
+        Err(format!(
+            "CSV 第 {} 行有 {} 列，但导入的目标列有 {} 列（{}），请检查文件的分隔符和列顺序",
+            self.record_line,
+            self.fields,
+            self.columns.len(),
+            self.columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+        ))
+    }
+}
+
+/// Copy 模式：直接 COPY 到目标表，失败时整体回滚并报告出错的行
+async fn import_with_copy(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    columns: &[WritableColumn],
+    data: &[u8],
+    has_header: bool,
+) -> Result<CsvImportResult, String> {
+    let copy_sql = copy_statement(schema, table, columns, has_header);
+
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|e| format!("无法开始事务: {}", e))?;
+
+    match copy_data(client, &copy_sql, data).await {
+        Ok(rows_loaded) => {
+            if let Err(e) = client.batch_execute("COMMIT").await {
+                let _ = client.batch_execute("ROLLBACK").await;
+                return Err(format!("提交事务失败: {}", e));
+            }
+            Ok(CsvImportResult {
+                mode: CsvImportMode::Copy,
+                rows_loaded,
+                rejected_rows: Vec::new(),
+                rolled_back: false,
+            })
+        }
+        Err(e) => {
+            let _ = client.batch_execute("ROLLBACK").await;
+            log::error!("COPY 导入失败: {}", describe_error(&e));
+
+            match rejected_row_from_copy_error(&e, data, has_header) {
+                Some(rejected) => Ok(CsvImportResult {
+                    mode: CsvImportMode::Copy,
+                    rows_loaded: 0,
+                    rejected_rows: vec![rejected],
+                    rolled_back: true,
+                }),
+                None => Err(format!("COPY 导入失败: {}", describe_error(&e))),
+            }
+        }
+    }
+}
+
+/// 以流的方式将 CSV 文件导入到已有的表，返回导入的行数
+///
+/// 文件按块读取并发送给 `COPY ... FROM STDIN`，不会一次性读入内存。导入前先按块扫描一遍文件，
+/// 检查每条记录的列数。导入在一个事务中进行，任何错误都会回滚整个导入。
+/// 与 `import_csv` 的 Copy 模式不同，出错时只返回 COPY 报告的错误和行号，不包含出错行的原始内容。
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `path` - CSV 文件路径
+/// * `has_header` - 第一行是否为表头；有表头时按表头列名匹配目标列
+///
+/// # Returns
+/// * `Result<u64, String>` - 导入的行数或错误信息
+pub async fn import_csv_file(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    path: &Path,
+    has_header: bool,
+) -> Result<u64, String> {
+    let table_columns = get_writable_columns(client, schema, table).await?;
+    if table_columns.is_empty() {
+        return Err(format!("表 {}.{} 不存在或没有可写入的列", schema, table));
+    }
+
+    let columns = if has_header {
+        resolve_header_columns(&read_leading_lines(path, 1).await?, table_columns)?
+    } else {
+        table_columns
+    };
+    check_file_column_count(path, has_header, &columns).await?;
+
+    log::info!("CSV 文件导入: {}.{}, 列数: {}", schema, table, columns.len());
+
+    let copy_sql = copy_statement(schema, table, &columns, has_header);
+    let mut file = File::open(path)
+        .await
+        .map_err(|e| format!("无法读取文件 {}: {}", path.display(), e))?;
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|e| format!("无法开始事务: {}", e))?;
+
+    match copy_file(client, &copy_sql, &mut file).await {
+        Ok(rows_loaded) => {
+            if let Err(e) = client.batch_execute("COMMIT").await {
+                let _ = client.batch_execute("ROLLBACK").await;
+                return Err(format!("提交事务失败: {}", e));
+            }
+            Ok(rows_loaded)
+        }
+        Err(e) => {
+            let _ = client.batch_execute("ROLLBACK").await;
+            log::error!("COPY 导入失败: {}", e);
+            Err(format!("COPY 导入失败，导入已回滚: {}", e))
+        }
+    }
+}
+
+/// 读取文件开头的 `count` 行（包含换行符）
+async fn read_leading_lines(path: &Path, count: usize) -> Result<Vec<u8>, String> {
+    let file = File::open(path)
+        .await
+        .map_err(|e| format!("无法读取文件 {}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let mut lines = Vec::new();
+    for _ in 0..count {
+        let read = reader
+            .read_until(b'\n', &mut lines)
+            .await
+            .map_err(|e| format!("无法读取文件 {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+    }
+    Ok(lines)
+}
+
+/// 构建导入目标列的 `COPY ... FROM STDIN` 语句
+fn copy_statement(schema: &str, table: &str, columns: &[WritableColumn], has_header: bool) -> String {
+    let column_list = columns
+        .iter()
+        .map(|c| quote_identifier(&c.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "COPY {} ({}) FROM STDIN WITH (FORMAT csv, HEADER {})",
+        qualified_name(schema, table),
+        column_list,
+        has_header
+    )
+}
+
+/// Staged 模式：先导入全文本临时表，再分批转换插入
+async fn import_with_staging(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    columns: &[WritableColumn],
+    data: &[u8],
+    has_header: bool,
+) -> Result<CsvImportResult, String> {
+    client
+        .batch_execute("BEGIN")
+        .await
+        .map_err(|e| format!("无法开始事务: {}", e))?;
+
+    match load_and_validate(client, schema, table, columns, data, has_header).await {
+        Ok(result) => {
+            if let Err(e) = client.batch_execute("COMMIT").await {
+                let _ = client.batch_execute("ROLLBACK").await;
+                return Err(format!("提交事务失败: {}", e));
+            }
+            Ok(result)
+        }
+        Err(e) => {
+            let _ = client.batch_execute("ROLLBACK").await;
+            Err(e)
+        }
+    }
+}
+
+/// 在已开启的事务中完成 Staged 导入
+async fn load_and_validate(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    columns: &[WritableColumn],
+    data: &[u8],
+    has_header: bool,
+) -> Result<CsvImportResult, String> {
+    let stage_columns: Vec<String> = (1..=columns.len()).map(|i| format!("c{}", i)).collect();
+
+    // 临时表使用 bigserial 记录 CSV 中的数据行号
+    let create_sql = format!(
+        "CREATE TEMP TABLE {} (__row bigserial, {}) ON COMMIT DROP",
+        STAGE_TABLE,
+        stage_columns
+            .iter()
+            .map(|c| format!("{} text", c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    client
+        .batch_execute(&create_sql)
+        .await
+        .map_err(|e| format!("无法创建临时表: {}", e))?;
+
+    let copy_sql = format!(
+        "COPY {} ({}) FROM STDIN WITH (FORMAT csv, HEADER {})",
+        STAGE_TABLE,
+        stage_columns.join(", "),
+        has_header
+    );
+    let total_rows = copy_data(client, &copy_sql, data)
+        .await
+        .map_err(|e| format!("无法读取 CSV 数据: {}", describe_error(&e)))? as i64;
+
+    log::info!("已读取 {} 行到临时表，开始转换", total_rows);
+
+    // COPY 允许写入 GENERATED ALWAYS 标识列，INSERT 需要显式声明
+    let overriding = if columns.iter().any(|c| c.is_identity_always) {
+        " OVERRIDING SYSTEM VALUE"
+    } else {
+        ""
+    };
+
+    let insert_sql = format!(
+        "INSERT INTO {} ({}){} SELECT {} FROM {} WHERE __row BETWEEN $1 AND $2 ORDER BY __row",
+        qualified_name(schema, table),
+        columns
+            .iter()
+            .map(|c| quote_identifier(&c.name))
+            .collect::<Vec<_>>()
+            .join(", "),
+        overriding,
+        columns
+            .iter()
+            .zip(&stage_columns)
+            .map(|(col, stage)| format!("CAST({} AS {})", stage, col.data_type))
+            .collect::<Vec<_>>()
+            .join(", "),
+        STAGE_TABLE
+    );
+
+    let mut rows_loaded = 0u64;
+    let mut rejected_rows = Vec::new();
+    let mut batch_start = 1i64;
+
+    while batch_start <= total_rows {
+        let batch_end = (batch_start + STAGED_BATCH_SIZE - 1).min(total_rows);
+
+        execute_control(client, "SAVEPOINT csv_import_batch").await?;
+        match client.execute(&insert_sql, &[&batch_start, &batch_end]).await {
+            Ok(affected) => {
+                execute_control(client, "RELEASE SAVEPOINT csv_import_batch").await?;
+                rows_loaded += affected;
+            }
+            Err(_) => {
+                execute_control(client, "ROLLBACK TO SAVEPOINT csv_import_batch").await?;
+                execute_control(client, "RELEASE SAVEPOINT csv_import_batch").await?;
+
+                // 批次失败时逐行重试，定位出错的行
+                for row_id in batch_start..=batch_end {
+                    execute_control(client, "SAVEPOINT csv_import_row").await?;
+                    match client.execute(&insert_sql, &[&row_id, &row_id]).await {
+                        Ok(affected) => {
+                            execute_control(client, "RELEASE SAVEPOINT csv_import_row").await?;
+                            rows_loaded += affected;
+                        }
+                        Err(e) => {
+                            execute_control(client, "ROLLBACK TO SAVEPOINT csv_import_row").await?;
+                            execute_control(client, "RELEASE SAVEPOINT csv_import_row").await?;
+                            let rejected =
+                                build_rejected_row(client, columns, &stage_columns, row_id, &e).await?;
+                            rejected_rows.push(rejected);
+                        }
+                    }
+                }
+            }
+        }
+
+        batch_start = batch_end + 1;
+    }
+
+    log::info!("转换完成: 导入 {} 行, 拒绝 {} 行", rows_loaded, rejected_rows.len());
+
+    Ok(CsvImportResult {
+        mode: CsvImportMode::Staged,
+        rows_loaded,
+        rejected_rows,
+        rolled_back: false,
+    })
+}
+
+/// 通过 COPY FROM STDIN 分块发送数据，返回 COPY 的行数
+pub(crate) async fn copy_data(
+    client: &Client,
+    copy_sql: &str,
+    data: &[u8],
+) -> Result<u64, tokio_postgres::Error> {
+    let sink = client.copy_in::<_, Bytes>(copy_sql).await?;
+    pin_mut!(sink);
+
+    for chunk in data.chunks(COPY_CHUNK_SIZE) {
+        sink.send(Bytes::copy_from_slice(chunk)).await?;
+    }
+
+    sink.finish().await
+}
+
+/// 通过 COPY FROM STDIN 分块发送文件内容，返回 COPY 的行数
+async fn copy_file(client: &Client, copy_sql: &str, file: &mut File) -> Result<u64, String> {
+    let sink = client
+        .copy_in::<_, Bytes>(copy_sql)
+        .await
+        .map_err(|e| describe_error(&e))?;
+    pin_mut!(sink);
+
+    let mut buffer = vec![0u8; COPY_CHUNK_SIZE];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("读取文件失败: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        sink.send(Bytes::copy_from_slice(&buffer[..read]))
+            .await
+            .map_err(|e| describe_error(&e))?;
+    }
+
+    sink.finish().await.map_err(|e| describe_error(&e))
+}
+
+/// 执行事务控制语句（SAVEPOINT / RELEASE / ROLLBACK TO）
+async fn execute_control(client: &Client, sql: &str) -> Result<(), String> {
+    client
+        .batch_execute(sql)
+        .await
+        .map_err(|e| format!("执行 {} 失败: {}", sql, e))
+}
+
+/// 读取被拒绝行的原始值，并尽量定位出错的列
+async fn build_rejected_row(
+    client: &Client,
+    columns: &[WritableColumn],
+    stage_columns: &[String],
+    row_id: i64,
+    error: &tokio_postgres::Error,
+) -> Result<RejectedRow, String> {
+    let select_sql = format!(
+        "SELECT {} FROM {} WHERE __row = $1",
+        stage_columns.join(", "),
+        STAGE_TABLE
+    );
+    let row = client
+        .query_one(&select_sql, &[&row_id])
+        .await
+        .map_err(|e| format!("无法读取第 {} 行数据: {}", row_id, e))?;
+
+    let values: Vec<Option<String>> = (0..stage_columns.len()).map(|i| row.get(i)).collect();
+    let column = find_failing_column(client, columns, &values, error).await?;
+
+    Ok(RejectedRow {
+        row_number: row_id as u64,
+        column,
+        values,
+        error: describe_error(error),
+    })
+}
+
+/// 定位导致插入失败的列
+///
+/// 约束错误（如 NOT NULL）由 PostgreSQL 直接给出列名；
+/// 类型转换错误（22 类）则逐列尝试 CAST 找到第一个无法转换的值。
+async fn find_failing_column(
+    client: &Client,
+    columns: &[WritableColumn],
+    values: &[Option<String>],
+    error: &tokio_postgres::Error,
+) -> Result<Option<String>, String> {
+    let db_error = match error.as_db_error() {
+        Some(db_error) => db_error,
+        None => return Ok(None),
+    };
+
+    if let Some(column) = db_error.column() {
+        return Ok(Some(column.to_string()));
+    }
+
+    if !db_error.code().code().starts_with("22") {
+        return Ok(None);
+    }
+
+    for (column, value) in columns.iter().zip(values) {
+        let value = match value {
+            Some(value) => value,
+            None => continue,
+        };
+
+        let probe_sql = format!("SELECT CAST($1::text AS {})", column.data_type);
+        execute_control(client, "SAVEPOINT csv_import_probe").await?;
+        let probe = client.query_one(&probe_sql, &[value]).await;
+        if probe.is_err() {
+            execute_control(client, "ROLLBACK TO SAVEPOINT csv_import_probe").await?;
+        }
+        execute_control(client, "RELEASE SAVEPOINT csv_import_probe").await?;
+
+        if probe.is_err() {
+            return Ok(Some(column.name.clone()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// 从 COPY 错误中构建被拒绝的行
+fn rejected_row_from_copy_error(
+    error: &tokio_postgres::Error,
+    data: &[u8],
+    has_header: bool,
+) -> Option<RejectedRow> {
+    let db_error = error.as_db_error()?;
+    let (line_number, column) = parse_copy_context(db_error.where_()?)?;
+
+    let values = String::from_utf8_lossy(data)
+        .lines()
+        .nth(line_number.saturating_sub(1) as usize)
+        .map(|line| parse_csv_line(line).into_iter().map(Some).collect())
+        .unwrap_or_default();
+
+    let row_number = if has_header {
+        line_number.saturating_sub(1)
+    } else {
+        line_number
+    };
+
+    Some(RejectedRow {
+        row_number,
+        column,
+        values,
+        error: db_error.message().to_string(),
+    })
+}
+
+/// 解析 COPY 错误上下文，返回 (行号, 列名)
+///
+/// PostgreSQL 的上下文格式如：`COPY users, line 3, column age: "abc"`
+/// 或 `COPY users, line 3: "1,2,3"`（行号为文件中的行号，包含表头）。
+fn parse_copy_context(context: &str) -> Option<(u64, Option<String>)> {
+    let copy_line = context
+        .lines()
+        .find(|line| line.trim_start().starts_with("COPY "))?;
+
+    let marker = ", line ";
+    let after_line = &copy_line[copy_line.find(marker)? + marker.len()..];
+    let digits: String = after_line.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let line_number = digits.parse::<u64>().ok()?;
+
+    let column = after_line[digits.len()..]
+        .strip_prefix(", column ")
+        .map(|rest| match rest.find(':') {
+            Some(end) => rest[..end].to_string(),
+            None => rest.to_string(),
+        });
+
+    Some((line_number, column))
+}
+
+/// 解析一行 CSV（支持双引号包裹和 "" 转义）
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    current.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' => in_quotes = true,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(ch),
+        }
+    }
+    fields.push(current);
+
+    fields
+}
+
+/// 组合数据库错误信息和上下文
+fn describe_error(error: &tokio_postgres::Error) -> String {
+    match error.as_db_error() {
+        Some(db_error) => match db_error.where_() {
+            Some(context) => format!("{} ({})", db_error.message(), context),
+            None => db_error.message().to_string(),
+        },
+        None => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_copy_context_with_column() {
+        let context = "COPY people, line 3, column age: \"abc\"";
+        assert_eq!(parse_copy_context(context), Some((3, Some("age".to_string()))));
+    }
+
+    #[test]
+    fn test_parse_copy_context_without_column() {
+        let context = "COPY people, line 12: \"1,2,3,4\"";
+        assert_eq!(parse_copy_context(context), Some((12, None)));
+    }
+
+    #[test]
+    fn test_parse_copy_context_invalid() {
+        assert_eq!(parse_copy_context("SQL statement \"SELECT 1\""), None);
+    }
+
+    #[test]
+    fn test_parse_csv_line() {
+        assert_eq!(parse_csv_line("1,Alice,30"), vec!["1", "Alice", "30"]);
+        assert_eq!(
+            parse_csv_line("2,\"Smith, John\",\"say \"\"hi\"\"\""),
+            vec!["2", "Smith, John", "say \"hi\""]
+        );
+        assert_eq!(parse_csv_line("3,,"), vec!["3", "", ""]);
+    }
+
+    #[test]
+    fn test_check_column_count() {
+        let columns: Vec<WritableColumn> = ["id", "name"]
+            .iter()
+            .map(|name| WritableColumn {
+                name: name.to_string(),
+                data_type: "text".to_string(),
+                is_identity_always: false,
+            })
+            .collect();
+
+        assert!(check_column_count(b"1,\"a, b\"\n", false, &columns).is_ok());
+        assert!(check_column_count(b"id,name\n1,Alice\n", true, &columns).is_ok());
+
+        let error = check_column_count(b"1,Alice,30\n", false, &columns).unwrap_err();
+        assert!(error.contains("第 1 行有 3 列"));
+        assert!(error.contains("id, name"));
+        assert!(check_column_count(b"id,name\n1\n", true, &columns).is_err());
+
+        assert!(check_column_count(b"id,name\n", true, &columns).is_ok());
+        assert!(check_column_count(b"1,Alice", false, &columns).is_ok());
+    }
+
+    #[test]
+    fn test_check_column_count_every_record() {
+        let columns: Vec<WritableColumn> = ["id", "name"]
+            .iter()
+            .map(|name| WritableColumn {
+                name: name.to_string(),
+                data_type: "text".to_string(),
+                is_identity_always: false,
+            })
+            .collect();
+
+        // 后面的行列数不一致时报告该行的行号（包含表头）
+        let error = check_column_count(b"id,name\n1,Alice\n2,Bob\n3,Carol,extra\n", true, &columns).unwrap_err();
+        assert!(error.contains("第 4 行有 3 列"), "{}", error);
+        let error = check_column_count(b"1,Alice\n2", false, &columns).unwrap_err();
+        assert!(error.contains("第 2 行有 1 列"), "{}", error);
+
+        // 引号中的逗号和换行属于字段内容，后续记录的行号随之后移
+        let data = b"1,\"line1\nline2, more\"\n2,\"say \"\"hi\"\"\"\n3\n";
+        assert!(check_column_count(&data[..data.len() - 2], false, &columns).is_ok());
+        let error = check_column_count(data, false, &columns).unwrap_err();
+        assert!(error.contains("第 4 行有 1 列"), "{}", error);
+
+        // 分块扫描的结果与一次扫描相同
+        let mut checker = ColumnCountChecker::new(false, &columns);
+        let error = data.chunks(3).try_for_each(|chunk| checker.feed(chunk)).unwrap_err();
+        assert!(error.contains("第 4 行有 1 列"), "{}", error);
+    }
+}
@@ -0,0 +1,368 @@
+/**
+ * Data Exporter Service
+ *
+ * 此模块提供表数据导出功能，包括：
+ * - 将表数据生成为 INSERT 语句脚本（用于复制粘贴、种子数据）
+ * - 按列类型格式化 SQL 字面量
+ * - 将单张表通过 COPY ... TO STDOUT 流式导出为 CSV 文件
+ * - 将单张表导出为 JSON 对象数组文件
+ */
+
+use crate::models::data::TableExportResult;
+use crate::services::query_executor::row_to_hashmap;
+use crate::services::schema_service::get_writable_columns;
+use crate::services::sql_utils::{qualified_name, quote_identifier, quote_literal};
+use futures_util::{pin_mut, TryStreamExt};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::io::Write;
+use std::path::Path;
+use tokio_postgres::Client;
+
+/// 生成表数据的 INSERT 语句脚本
+///
+/// 每一行生成一条 `INSERT INTO "schema"."table" (cols) VALUES (...);`，
+/// 值以 PostgreSQL 的文本形式读出并按列类型格式化，重新执行脚本即可还原数据。
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `where_clause` - 可选的过滤条件（不含 WHERE 关键字）
+/// * `limit` - 可选的最大行数
+///
+/// # Returns
+/// * `Result<String, String>` - INSERT 脚本文本或错误信息
+pub async fn generate_insert_script(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    where_clause: Option<&str>,
+    limit: Option<i64>,
+) -> Result<String, String> {
+    if let Some(limit) = limit {
+        if limit < 0 {
+            return Err(format!("行数限制不能为负数: {}", limit));
+        }
+    }
+
+    let columns = get_writable_columns(client, schema, table).await?;
+    if columns.is_empty() {
+        return Err(format!("表 {}.{} 不存在或没有可导出的列", schema, table));
+    }
+
+    let target = qualified_name(schema, table);
+    let column_list = columns
+        .iter()
+        .map(|c| quote_identifier(&c.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let select_list = columns
+        .iter()
+        .map(|c| format!("{}::text", quote_identifier(&c.name)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut query = format!("SELECT {} FROM {}", select_list, target);
+    if let Some(filter) = where_clause.map(str::trim).filter(|w| !w.is_empty()) {
+        query.push_str(&format!(" WHERE {}", filter));
+    }
+    if let Some(limit) = limit {
+        query.push_str(&format!(" LIMIT {}", limit));
+    }
+
+    let rows = client
+        .query(&query, &[])
+        .await
+        .map_err(|e| format!("Failed to query table data: {}", e))?;
+
+    // GENERATED ALWAYS 标识列需要显式覆盖才能写入原值
+    let overriding = if columns.iter().any(|c| c.is_identity_always) {
+        " OVERRIDING SYSTEM VALUE"
+    } else {
+        ""
+    };
+
+    let mut script = format!("-- {}.{}: {} rows\n", schema, table, rows.len());
+    for row in &rows {
+        let values = columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format_sql_literal(row.get::<_, Option<&str>>(i), &c.data_type))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        script.push_str(&format!(
+            "INSERT INTO {} ({}){} VALUES ({});\n",
+            target, column_list, overriding, values
+        ));
+    }
+
+    Ok(script)
+}
+
+/// 将列值的文本形式格式化为对应类型的 SQL 字面量
+///
+/// - NULL 输出为 `NULL`
+/// - 数值类型直接输出（NaN/Infinity 除外）
+/// - 布尔类型输出为 `TRUE`/`FALSE`
+/// - 字符类型输出为转义后的字符串字面量
+/// - 其他类型输出为带显式类型转换的字面量，例如 `'2024-01-01'::date`
+pub fn format_sql_literal(value: Option<&str>, data_type: &str) -> String {
+    let value = match value {
+        Some(value) => value,
+        None => return "NULL".to_string(),
+    };
+
+    let base_type = data_type.split('(').next().unwrap_or(data_type).trim();
+
+    match base_type {
+        "smallint" | "integer" | "bigint" | "real" | "double precision" | "numeric" => {
+            let is_plain_number = value.parse::<f64>().is_ok()
+                && !value.chars().any(|c| c.is_ascii_alphabetic() && c != 'e' && c != 'E');
+            if is_plain_number {
+                value.to_string()
+            } else {
+                format!("{}::{}", quote_literal(value), data_type)
+            }
+        }
+        "boolean" => {
+            if value == "true" {
+                "TRUE".to_string()
+            } else {
+                "FALSE".to_string()
+            }
+        }
+        "text" | "character varying" | "character" => quote_literal(value),
+        _ => format!("{}::{}", quote_literal(value), data_type),
+    }
+}
+
+/// 将整张表导出为带表头的 CSV 文件
+///
+/// 通过 `COPY (SELECT * FROM ...) TO STDOUT WITH CSV HEADER` 流式写入文件，
+/// 不会把整张表读入内存；逗号、引号、换行等由 PostgreSQL 按 CSV 规则加引号转义。
+/// 导出失败时删除已写入的部分文件。
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `file_path` - 输出文件路径
+///
+/// # Returns
+/// * `Result<TableExportResult, String>` - 文件路径、行数和字节数，或错误信息
+pub async fn export_table_csv(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+) -> Result<TableExportResult, String> {
+    let sql = format!(
+        "COPY (SELECT * FROM {}) TO STDOUT WITH CSV HEADER",
+        qualified_name(schema, table)
+    );
+
+    let result = copy_csv_to_file(client, &sql, file_path).await;
+    if result.is_err() {
+        let _ = std::fs::remove_file(file_path);
+    }
+    let (rows, bytes) = result?;
+
+    Ok(TableExportResult {
+        file_path: file_path.to_string_lossy().to_string(),
+        rows,
+        bytes,
+    })
+}
+
+/// 执行 COPY ... TO STDOUT 并写入文件，返回 (数据行数, 字节数)
+async fn copy_csv_to_file(client: &Client, sql: &str, file_path: &Path) -> Result<(u64, u64), String> {
+    let mut file = std::fs::File::create(file_path)
+        .map_err(|e| format!("无法创建文件 {}: {}", file_path.display(), e))?;
+
+    let stream = client
+        .copy_out(sql)
+        .await
+        .map_err(|e| format!("Failed to export table: {}", e))?;
+    pin_mut!(stream);
+
+    let mut counter = CsvRecordCounter::default();
+    let mut bytes = 0u64;
+    while let Some(chunk) = stream
+        .try_next()
+        .await
+        .map_err(|e| format!("Failed to export table: {}", e))?
+    {
+        counter.feed(&chunk);
+        bytes += chunk.len() as u64;
+        file.write_all(&chunk)
+            .map_err(|e| format!("无法写入文件 {}: {}", file_path.display(), e))?;
+    }
+
+    // 第一条记录是表头
+    Ok((counter.records.saturating_sub(1), bytes))
+}
+
+/// 将整张表导出为 JSON 文件
+///
+/// 文件内容为对象数组，每行一个对象，键按列顺序排列。值按列类型转换（与查询结果相同）：
+/// NULL 为 `null`，日期时间为 ISO 8601 字符串，json/jsonb 列为嵌套的 JSON。
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `file_path` - 输出文件路径
+/// * `pretty` - 是否缩进格式化输出
+///
+/// # Returns
+/// * `Result<TableExportResult, String>` - 文件路径、行数和字节数，或错误信息
+pub async fn export_table_json(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    pretty: bool,
+) -> Result<TableExportResult, String> {
+    let rows = client
+        .query(&format!("SELECT * FROM {}", qualified_name(schema, table)), &[])
+        .await
+        .map_err(|e| format!("Failed to query table data: {}", e))?;
+
+    let json_rows: Vec<JsonRow> = rows
+        .iter()
+        .map(|row| {
+            let mut values = row_to_hashmap(row);
+            JsonRow(
+                row.columns()
+                    .iter()
+                    .map(|c| (c.name().to_string(), values.remove(c.name()).unwrap_or_default()))
+                    .collect(),
+            )
+        })
+        .collect();
+
+    let file = std::fs::File::create(file_path)
+        .map_err(|e| format!("无法创建文件 {}: {}", file_path.display(), e))?;
+    let mut writer = std::io::BufWriter::new(file);
+    let written = if pretty {
+        serde_json::to_writer_pretty(&mut writer, &json_rows)
+    } else {
+        serde_json::to_writer(&mut writer, &json_rows)
+    };
+    written
+        .map_err(|e| e.to_string())
+        .and_then(|_| writer.flush().map_err(|e| e.to_string()))
+        .map_err(|e| format!("无法写入文件 {}: {}", file_path.display(), e))?;
+
+    let bytes = std::fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
+
+    Ok(TableExportResult {
+        file_path: file_path.to_string_lossy().to_string(),
+        rows: json_rows.len() as u64,
+        bytes,
+    })
+}
+
+/// 按列顺序序列化的一行（HashMap 不保留列顺序）
+struct JsonRow(Vec<(String, serde_json::Value)>);
+
+impl Serialize for JsonRow {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in &self.0 {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+/// 统计 CSV 文本中的记录数
+///
+/// 引号内的换行属于字段内容，不作为记录结束；转义的引号 `""` 会连续切换两次状态，
+/// 因此只需跟踪是否处于引号内。数据可以分多次传入。
+#[derive(Default)]
+struct CsvRecordCounter {
+    in_quotes: bool,
+    records: u64,
+}
+
+impl CsvRecordCounter {
+    fn feed(&mut self, data: &[u8]) {
+        for &byte in data {
+            match byte {
+                b'"' => self.in_quotes = !self.in_quotes,
+                b'\n' if !self.in_quotes => self.records += 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_row_keeps_column_order() {
+        let row = JsonRow(vec![
+            ("z".to_string(), serde_json::json!(1)),
+            ("a".to_string(), serde_json::Value::Null),
+        ]);
+        assert_eq!(serde_json::to_string(&row).unwrap(), r#"{"z":1,"a":null}"#);
+    }
+
+    #[test]
+    fn test_csv_record_counter() {
+        let mut counter = CsvRecordCounter::default();
+        counter.feed(b"id,note\n1,\"a, \"\"quoted\"\"");
+        counter.feed(b"\nsecond line\"\n2,\n");
+        assert_eq!(counter.records, 3);
+        assert!(!counter.in_quotes);
+    }
+
+    #[test]
+    fn test_format_sql_literal_null() {
+        assert_eq!(format_sql_literal(None, "integer"), "NULL");
+        assert_eq!(format_sql_literal(None, "text"), "NULL");
+    }
+
+    #[test]
+    fn test_format_sql_literal_numbers() {
+        assert_eq!(format_sql_literal(Some("42"), "integer"), "42");
+        assert_eq!(format_sql_literal(Some("-3.50"), "numeric(10,2)"), "-3.50");
+        assert_eq!(format_sql_literal(Some("1e+30"), "real"), "1e+30");
+        assert_eq!(format_sql_literal(Some("NaN"), "double precision"), "'NaN'::double precision");
+    }
+
+    #[test]
+    fn test_format_sql_literal_boolean() {
+        assert_eq!(format_sql_literal(Some("true"), "boolean"), "TRUE");
+        assert_eq!(format_sql_literal(Some("false"), "boolean"), "FALSE");
+    }
+
+    #[test]
+    fn test_format_sql_literal_strings() {
+        assert_eq!(format_sql_literal(Some("O'Brien"), "text"), "'O''Brien'");
+        assert_eq!(
+            format_sql_literal(Some("abc"), "character varying(10)"),
+            "'abc'"
+        );
+    }
+
+    #[test]
+    fn test_format_sql_literal_casts_other_types() {
+        assert_eq!(
+            format_sql_literal(Some("2024-01-31"), "date"),
+            "'2024-01-31'::date"
+        );
+        assert_eq!(
+            format_sql_literal(Some("{1,2,3}"), "integer[]"),
+            "'{1,2,3}'::integer[]"
+        );
+        assert_eq!(
+            format_sql_literal(Some("{\"a\": 1}"), "jsonb"),
+            "'{\"a\": 1}'::jsonb"
+        );
+    }
+}
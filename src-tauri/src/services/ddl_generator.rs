@@ -6,23 +6,73 @@
  * - Generating ALTER TABLE statements for table modifications
  * - Generating CREATE INDEX statements
  * - Generating constraint definitions
+ * - Generating table storage parameter clauses (WITH / SET / RESET)
+ * - Generating CREATE SCHEMA / DROP SCHEMA statements
+ * - Generating COMMENT ON statements for tables and columns
  * 
  * Validates: Requirements 7.1, 7.2, 7.3, 7.4, 7.5
  */
 
 use crate::models::schema::{
     TableDesign, TableChanges, ColumnDefinition, ConstraintDefinition, 
-    IndexDefinition, ColumnModification,
+    IndexDefinition, ColumnModification, TableDocumentation, IdentityKind,
 };
+use crate::services::sql_utils::{quote_identifier, quote_literal};
+use std::collections::HashMap;
+
+/// Maximum identifier length in bytes (NAMEDATALEN - 1)
+const MAX_IDENTIFIER_LENGTH: usize = 63;
 
 /// Generate CREATE TABLE DDL statement from table design
 /// 
+/// Foreign keys are not written inline; they follow the table as
+/// `ALTER TABLE ... ADD CONSTRAINT` statements.
+/// 
 /// # Arguments
 /// * `design` - Table design specification
 /// 
 /// # Returns
-/// * `String` - Complete CREATE TABLE statement with constraints and indexes
+/// * `String` - Complete CREATE TABLE statement with constraints, indexes and comments
 pub fn generate_create_table(design: &TableDesign) -> String {
+    let (ddl, foreign_keys) = generate_table_and_foreign_keys(design);
+    
+    if foreign_keys.is_empty() {
+        ddl
+    } else {
+        format!("{}\n\n{}", ddl, foreign_keys.join("\n"))
+    }
+}
+
+/// Generate CREATE TABLE DDL statements for several related tables
+/// 
+/// All tables are created first and their foreign keys added afterwards,
+/// so tables may reference each other regardless of their order in `designs`.
+/// 
+/// # Arguments
+/// * `designs` - Table design specifications
+/// 
+/// # Returns
+/// * `String` - CREATE TABLE statements followed by all ALTER TABLE ... ADD CONSTRAINT statements
+pub fn generate_create_tables(designs: &[TableDesign]) -> String {
+    let mut sections = Vec::new();
+    let mut foreign_keys = Vec::new();
+    
+    for design in designs {
+        let (ddl, table_foreign_keys) = generate_table_and_foreign_keys(design);
+        sections.push(ddl);
+        foreign_keys.extend(table_foreign_keys);
+    }
+    
+    if !foreign_keys.is_empty() {
+        sections.push(foreign_keys.join("\n"));
+    }
+    
+    sections.join("\n\n")
+}
+
+/// Generate the CREATE TABLE, index and comment statements of a table, and
+/// separately the ALTER TABLE statements adding its foreign keys
+fn generate_table_and_foreign_keys(design: &TableDesign) -> (String, Vec<String>) {
     let mut ddl = Vec::new();
     
     // CREATE TABLE header
@@ -54,7 +104,19 @@ pub fn generate_create_table(design: &TableDesign) -> String {
         ddl.push(table_constraints.join(",\n"));
     }
     
-    ddl.push("\n);".to_string());
+    ddl.push("\n)".to_string());
+    
+    // Storage parameters
+    if !design.storage_parameters.is_empty() {
+        ddl.push(format!(" WITH ({})", format_storage_parameters(&design.storage_parameters)));
+    }
+    
+    // Tablespace
+    if let Some(tablespace) = &design.tablespace {
+        ddl.push(format!(" TABLESPACE {}", escape_identifier(tablespace)));
+    }
+    
+    ddl.push(";".to_string());
     
     // Index definitions (separate statements)
     let index_statements: Vec<String> = design
@@ -68,7 +130,31 @@ pub fn generate_create_table(design: &TableDesign) -> String {
         ddl.push(index_statements.join("\n\n"));
     }
     
-    ddl.concat()
+    // Table and column comments (separate statements)
+    let comment_statements = comment_statements(
+        &design.schema,
+        &design.table_name,
+        design.comment.as_deref(),
+        design
+            .columns
+            .iter()
+            .filter_map(|col| col.comment.as_deref().map(|comment| (col.name.as_str(), comment))),
+    );
+    
+    if !comment_statements.is_empty() {
+        ddl.push("\n\n".to_string());
+        ddl.push(comment_statements.join("\n"));
+    }
+    
+    // Foreign keys (added once the referenced tables exist)
+    let foreign_keys = design
+        .constraints
+        .iter()
+        .filter(|c| !should_include_in_create_table(c))
+        .map(|c| generate_add_constraint(&design.schema, &design.table_name, c))
+        .collect();
+    
+    (ddl.concat(), foreign_keys)
 }
 
 /// Generate ALTER TABLE DDL statements from table changes
@@ -88,7 +174,17 @@ pub fn generate_alter_table(
     let mut statements = Vec::new();
     let table_name = format!("{}.{}", escape_identifier(schema), escape_identifier(table));
     
-    // Drop constraints first (they may depend on columns)
+    // Rename constraints first, keeping their validated state
+    for (old_name, new_name) in &changes.renamed_constraints {
+        statements.push(format!(
+            "ALTER TABLE {} RENAME CONSTRAINT {} TO {};",
+            table_name,
+            escape_identifier(old_name),
+            escape_identifier(new_name)
+        ));
+    }
+    
+    // Drop constraints (they may depend on columns)
     for constraint_name in &changes.dropped_constraints {
         statements.push(format!(
             "ALTER TABLE {} DROP CONSTRAINT {};",
@@ -134,11 +230,7 @@ pub fn generate_alter_table(
     
     // Add constraints
     for constraint in &changes.added_constraints {
-        statements.push(format!(
-            "ALTER TABLE {} ADD {};",
-            table_name,
-            generate_constraint_definition(constraint)
-        ));
+        statements.push(generate_add_constraint(schema, table, constraint));
     }
     
     // Add indexes
@@ -146,10 +238,171 @@ pub fn generate_alter_table(
         statements.push(generate_create_index(schema, table, index));
     }
     
+    // Storage parameters
+    if !changes.set_storage_parameters.is_empty() {
+        statements.push(format!(
+            "ALTER TABLE {} SET ({});",
+            table_name,
+            format_storage_parameters(&changes.set_storage_parameters)
+        ));
+    }
+    
+    if !changes.reset_storage_parameters.is_empty() {
+        statements.push(format!(
+            "ALTER TABLE {} RESET ({});",
+            table_name,
+            changes.reset_storage_parameters.join(", ")
+        ));
+    }
+    
+    statements
+}
+
+/// Generate ALTER TABLE ... ADD CONSTRAINT statement
+///
+/// # Arguments
+/// * `schema` - Schema name
+/// * `table` - Table name
+/// * `constraint` - Constraint to add
+///
+/// # Returns
+/// * `String` - ALTER TABLE statement
+pub fn generate_add_constraint(schema: &str, table: &str, constraint: &ConstraintDefinition) -> String {
+    format!(
+        "ALTER TABLE {}.{} ADD {};",
+        escape_identifier(schema),
+        escape_identifier(table),
+        generate_constraint_definition(constraint)
+    )
+}
+
+/// Validate a schema name before creating or dropping it
+///
+/// Rejects empty names, names longer than 63 bytes (PostgreSQL would silently
+/// truncate them), names containing NUL, and the reserved `pg_` prefix.
+///
+/// # Arguments
+/// * `name` - Schema name
+///
+/// # Returns
+/// * `Result<(), String>` - Ok if the name is usable, or error message
+pub fn validate_schema_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Schema name cannot be empty".to_string());
+    }
+    if name.len() > MAX_IDENTIFIER_LENGTH {
+        return Err(format!(
+            "Schema name is too long: {} bytes (maximum {} bytes)",
+            name.len(),
+            MAX_IDENTIFIER_LENGTH
+        ));
+    }
+    if name.contains('\0') {
+        return Err("Schema name cannot contain NUL characters".to_string());
+    }
+    if name.to_lowercase().starts_with("pg_") || name == "information_schema" {
+        return Err(format!("{} is a reserved system schema name", name));
+    }
+    Ok(())
+}
+
+/// Generate CREATE SCHEMA statement
+///
+/// # Arguments
+/// * `name` - Schema name
+/// * `owner` - Optional role that will own the schema
+/// * `if_not_exists` - Add `IF NOT EXISTS`
+///
+/// # Returns
+/// * `String` - CREATE SCHEMA statement with quoted identifiers
+pub fn generate_create_schema(name: &str, owner: Option<&str>, if_not_exists: bool) -> String {
+    let mut ddl = format!(
+        "CREATE SCHEMA {}{}",
+        if if_not_exists { "IF NOT EXISTS " } else { "" },
+        quote_identifier(name)
+    );
+
+    if let Some(owner) = owner {
+        ddl.push_str(&format!(" AUTHORIZATION {}", quote_identifier(owner)));
+    }
+
+    ddl.push(';');
+    ddl
+}
+
+/// Generate DROP SCHEMA statement
+///
+/// # Arguments
+/// * `name` - Schema name
+/// * `cascade` - Also drop the objects in the schema (`CASCADE` instead of `RESTRICT`)
+///
+/// # Returns
+/// * `String` - DROP SCHEMA statement with quoted identifiers
+pub fn generate_drop_schema(name: &str, cascade: bool) -> String {
+    format!(
+        "DROP SCHEMA {} {};",
+        quote_identifier(name),
+        if cascade { "CASCADE" } else { "RESTRICT" }
+    )
+}
+
+/// Generate COMMENT ON statements for a table and its columns
+///
+/// Empty comments are skipped.
+///
+/// # Arguments
+/// * `schema` - Schema name
+/// * `table` - Table name
+/// * `documentation` - Table and column comments
+///
+/// # Returns
+/// * `Vec<String>` - COMMENT ON TABLE / COMMENT ON COLUMN statements
+pub fn generate_comment_statements(
+    schema: &str,
+    table: &str,
+    documentation: &TableDocumentation,
+) -> Vec<String> {
+    comment_statements(
+        schema,
+        table,
+        Some(documentation.table_comment.as_str()),
+        documentation
+            .columns
+            .iter()
+            .map(|column| (column.name.as_str(), column.comment.as_str())),
+    )
+}
+
+/// Build COMMENT ON TABLE / COMMENT ON COLUMN statements, skipping empty comments
+fn comment_statements<'a>(
+    schema: &str,
+    table: &str,
+    table_comment: Option<&str>,
+    column_comments: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Vec<String> {
+    let table_name = format!("{}.{}", escape_identifier(schema), escape_identifier(table));
+    let mut statements = Vec::new();
+
+    if let Some(comment) = table_comment.filter(|c| !c.is_empty()) {
+        statements.push(format!("COMMENT ON TABLE {} IS {};", table_name, quote_literal(comment)));
+    }
+
+    for (column, comment) in column_comments.filter(|(_, c)| !c.is_empty()) {
+        statements.push(format!(
+            "COMMENT ON COLUMN {}.{} IS {};",
+            table_name,
+            escape_identifier(column),
+            quote_literal(comment)
+        ));
+    }
+
     statements
 }
 
 /// Generate column definition for CREATE TABLE or ALTER TABLE ADD COLUMN
+///
+/// Generated and identity columns get their GENERATED clause instead of a DEFAULT;
+/// if both are set, the generation expression wins.
 fn generate_column_definition(column: &ColumnDefinition) -> String {
     let mut parts = vec![escape_identifier(&column.name)];
     
@@ -157,13 +410,18 @@ fn generate_column_definition(column: &ColumnDefinition) -> String {
     let data_type = format_data_type(column);
     parts.push(data_type);
     
+    // Generated or identity column
+    let generation = generate_generation_clause(column);
+    let has_generation = generation.is_some();
+    parts.extend(generation);
+    
     // NULL constraint
     if !column.is_nullable {
         parts.push("NOT NULL".to_string());
     }
     
-    // Default value
-    if let Some(ref default) = column.column_default {
+    // Default value (not allowed together with a GENERATED clause)
+    if let Some(default) = column.column_default.as_ref().filter(|_| !has_generation) {
         parts.push(format!("DEFAULT {}", default));
     }
     
@@ -175,6 +433,18 @@ fn generate_column_definition(column: &ColumnDefinition) -> String {
     parts.join(" ")
 }
 
+/// Generate the GENERATED clause of a generated or identity column, if any
+fn generate_generation_clause(column: &ColumnDefinition) -> Option<String> {
+    if let Some(expression) = &column.generated_expression {
+        return Some(format!("GENERATED ALWAYS AS ({}) STORED", expression));
+    }
+    
+    column.identity.map(|identity| match identity {
+        IdentityKind::Always => "GENERATED ALWAYS AS IDENTITY".to_string(),
+        IdentityKind::ByDefault => "GENERATED BY DEFAULT AS IDENTITY".to_string(),
+    })
+}
+
 /// Format data type with length or precision
 fn format_data_type(column: &ColumnDefinition) -> String {
     let base_type = column.data_type.to_uppercase();
@@ -283,25 +553,80 @@ fn generate_create_index(schema: &str, table: &str, index: &IndexDefinition) ->
         "".to_string()
     };
     
+    // The index is always created in the table's schema, so its name can't be qualified
     format!(
-        "CREATE {}INDEX {}.{}{} ON {}.{} ({});",
+        "CREATE {}INDEX {} ON {}.{}{} ({});",
         unique,
-        escape_identifier(schema),
         escape_identifier(&index.index_name),
-        index_type,
         escape_identifier(schema),
         escape_identifier(table),
+        index_type,
         columns
     )
 }
 
+/// Check that storage parameter names are plain option names
+///
+/// Names are inserted into `WITH (...)`, `SET (...)` and `RESET (...)` unquoted,
+/// so anything other than `[a-z_][a-z0-9_.]*` (e.g. `toast.autovacuum_enabled`)
+/// is rejected.
+///
+/// # Arguments
+/// * `names` - Storage parameter names
+///
+/// # Returns
+/// * `Result<(), String>` - Ok if every name is usable, or error message
+pub fn validate_storage_parameter_names<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+) -> Result<(), String> {
+    for name in names {
+        let mut chars = name.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.');
+        if !valid {
+            return Err(format!("Invalid storage parameter name: {}", name));
+        }
+    }
+    Ok(())
+}
+
+/// Format storage parameters as `name=value, ...`, sorted by name
+///
+/// Plain numbers and words (e.g. `on`, `0.1`) are written as is; other values
+/// are quoted.
+fn format_storage_parameters(parameters: &HashMap<String, String>) -> String {
+    let mut names: Vec<&String> = parameters.keys().collect();
+    names.sort();
+    
+    names
+        .into_iter()
+        .map(|name| {
+            let value = &parameters[name];
+            let is_plain = !value.is_empty()
+                && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-');
+            if is_plain {
+                format!("{}={}", name, value)
+            } else {
+                format!("{}='{}'", name, value.replace('\'', "''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Generate ALTER TABLE statements for column modifications
+///
+/// With the old definition, only the attributes that differ (name, type,
+/// nullability, default) are altered; an unchanged column produces no statements.
 fn generate_column_modifications(
     table_name: &str,
     modification: &ColumnModification,
 ) -> Vec<String> {
     let mut statements = Vec::new();
     let old_name = escape_identifier(&modification.old_name);
+    let old_col = modification.old_definition.as_ref();
     let new_col = &modification.new_definition;
     let new_name = escape_identifier(&new_col.name);
     
@@ -313,47 +638,133 @@ fn generate_column_modifications(
         ));
     }
     
-    // Change data type
+    // Change data type, converting existing values with USING when given or known to be safe
     let data_type = format_data_type(new_col);
-    statements.push(format!(
-        "ALTER TABLE {} ALTER COLUMN {} TYPE {};",
-        table_name, new_name, data_type
-    ));
+    let old_data_type = old_col.map(format_data_type);
+    let type_changed = old_data_type
+        .as_deref()
+        .is_none_or(|old_type| normalize_type_name(old_type) != normalize_type_name(&data_type));
+    let using_expression = match modification.using_expression.as_deref().map(str::trim) {
+        Some(expression) if !expression.is_empty() => Some(expression.to_string()),
+        _ => old_data_type
+            .as_deref()
+            .filter(|old_type| is_safe_cast(old_type, &data_type))
+            .map(|_| format!("{}::{}", new_name, data_type)),
+    };
+    if type_changed {
+        match using_expression {
+            Some(expression) => statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {};",
+                table_name, new_name, data_type, expression
+            )),
+            None => statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {};",
+                table_name, new_name, data_type
+            )),
+        }
+    }
     
     // Change nullable
-    if new_col.is_nullable {
-        statements.push(format!(
-            "ALTER TABLE {} ALTER COLUMN {} DROP NOT NULL;",
-            table_name, new_name
-        ));
-    } else {
-        statements.push(format!(
-            "ALTER TABLE {} ALTER COLUMN {} SET NOT NULL;",
-            table_name, new_name
-        ));
+    if old_col.is_none_or(|old| old.is_nullable != new_col.is_nullable) {
+        if new_col.is_nullable {
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} DROP NOT NULL;",
+                table_name, new_name
+            ));
+        } else {
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} SET NOT NULL;",
+                table_name, new_name
+            ));
+        }
     }
     
     // Change default value
-    if let Some(ref default) = new_col.column_default {
-        statements.push(format!(
-            "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
-            table_name, new_name, default
-        ));
-    } else {
-        statements.push(format!(
-            "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
-            table_name, new_name
-        ));
+    let old_default = old_col.map(|old| old.column_default.as_deref().map(str::trim));
+    if old_default != Some(new_col.column_default.as_deref().map(str::trim)) {
+        if let Some(ref default) = new_col.column_default {
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+                table_name, new_name, default
+            ));
+        } else {
+            statements.push(format!(
+                "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+                table_name, new_name
+            ));
+        }
     }
     
     statements
 }
 
+/// Check if converting a column from `old_type` to `new_type` with an explicit cast
+/// can't fail or silently change values
+///
+/// Covers conversions to unbounded text, widening numeric conversions, date/time
+/// widening and json to jsonb. Casts to length-limited character types are excluded
+/// because an explicit cast truncates instead of raising an error. Unchanged types
+/// need no cast.
+fn is_safe_cast(old_type: &str, new_type: &str) -> bool {
+    let old_type = normalize_type_name(old_type);
+    let new_type = normalize_type_name(new_type);
+    if old_type == new_type || old_type.0.ends_with("[]") || new_type.0.ends_with("[]") {
+        return false;
+    }
+
+    let (old_base, new_base) = (old_type.0.as_str(), new_type.0.as_str());
+    match new_base {
+        "text" => true,
+        "character varying" => new_type.1.is_empty(),
+        "integer" => old_base == "smallint",
+        "bigint" => matches!(old_base, "smallint" | "integer"),
+        "numeric" => matches!(old_base, "smallint" | "integer" | "bigint"),
+        "double precision" => matches!(old_base, "smallint" | "integer" | "real"),
+        "timestamp without time zone" | "timestamp with time zone" => old_base == "date",
+        "jsonb" => old_base == "json",
+        _ => false,
+    }
+}
+
+/// Normalize a type name for comparison into (base type, modifiers)
+///
+/// The base is lowercased with aliases resolved (`int4` -> `integer`,
+/// `timestamptz` -> `timestamp with time zone`); modifiers such as `(10,2)` are
+/// returned without spaces, or empty.
+fn normalize_type_name(type_name: &str) -> (String, String) {
+    let type_name = type_name.trim().to_lowercase();
+    let (base, modifier) = match (type_name.find('('), type_name.find(')')) {
+        (Some(open), Some(close)) if open < close => (
+            format!("{} {}", type_name[..open].trim(), type_name[close + 1..].trim()),
+            type_name[open..=close].replace(' ', ""),
+        ),
+        _ => (type_name.clone(), String::new()),
+    };
+
+    let base = match base.trim() {
+        "int" | "int4" => "integer",
+        "int2" => "smallint",
+        "int8" => "bigint",
+        "decimal" => "numeric",
+        "float4" => "real",
+        "float8" | "float" => "double precision",
+        "varchar" => "character varying",
+        "char" | "bpchar" => "character",
+        "bool" => "boolean",
+        "timestamp" => "timestamp without time zone",
+        "timestamptz" => "timestamp with time zone",
+        other => other,
+    }
+    .to_string();
+
+    (base, modifier)
+}
+
 /// Check if constraint should be included in CREATE TABLE statement
 /// (vs. added separately with ALTER TABLE)
-fn should_include_in_create_table(_constraint: &ConstraintDefinition) -> bool {
-    // All constraints can be included in CREATE TABLE
-    true
+fn should_include_in_create_table(constraint: &ConstraintDefinition) -> bool {
+    // Foreign keys may reference tables that do not exist yet
+    constraint.constraint_type != "FOREIGN KEY"
 }
 
 /// Escape SQL identifier (table name, column name, etc.)
@@ -402,8 +813,11 @@ mod tests {
             numeric_scale: None,
             is_nullable: false,
             column_default: None,
+            generated_expression: None,
+            identity: None,
             is_primary_key: false,
             is_unique: false,
+            comment: None,
         };
         
         let def = generate_column_definition(&col);
@@ -420,8 +834,11 @@ mod tests {
             numeric_scale: None,
             is_nullable: true,
             column_default: None,
+            generated_expression: None,
+            identity: None,
             is_primary_key: false,
             is_unique: true,
+            comment: None,
         };
         
         let def = generate_column_definition(&col);
@@ -438,8 +855,11 @@ mod tests {
             numeric_scale: None,
             is_nullable: false,
             column_default: Some("CURRENT_TIMESTAMP".to_string()),
+            generated_expression: None,
+            identity: None,
             is_primary_key: false,
             is_unique: false,
+            comment: None,
         };
         
         let def = generate_column_definition(&col);
@@ -506,9 +926,18 @@ mod tests {
         );
         
         let stmt = generate_create_index("public", "users", &index);
-        assert!(stmt.contains("CREATE UNIQUE INDEX"));
-        assert!(stmt.contains("public.users_email_idx"));
-        assert!(stmt.contains("ON public.users (email)"));
+        assert_eq!(stmt, "CREATE UNIQUE INDEX users_email_idx ON public.users (email);");
+
+        let gin = IndexDefinition {
+            index_name: "docs_tags_idx".to_string(),
+            columns: vec!["tags".to_string()],
+            index_type: "gin".to_string(),
+            is_unique: false,
+        };
+        assert_eq!(
+            generate_create_index("public", "docs", &gin),
+            "CREATE INDEX docs_tags_idx ON public.docs USING GIN (tags);"
+        );
     }
 
     #[test]
@@ -531,8 +960,11 @@ mod tests {
             numeric_scale: None,
             is_nullable: true,
             column_default: None,
+            generated_expression: None,
+            identity: None,
             is_primary_key: false,
             is_unique: false,
+            comment: None,
         };
         assert_eq!(format_data_type(&col1), "VARCHAR(100)");
         
@@ -544,9 +976,420 @@ mod tests {
             numeric_scale: Some(2),
             is_nullable: true,
             column_default: None,
+            generated_expression: None,
+            identity: None,
             is_primary_key: false,
             is_unique: false,
+            comment: None,
         };
         assert_eq!(format_data_type(&col2), "NUMERIC(10, 2)");
     }
+
+    #[test]
+    fn test_create_table_adds_self_referencing_foreign_key_afterwards() {
+        let design = TableDesign {
+            table_name: "employees".to_string(),
+            schema: "public".to_string(),
+            columns: vec![
+                ColumnDefinition::new("id".to_string(), "integer".to_string(), false),
+                ColumnDefinition::new("manager_id".to_string(), "integer".to_string(), true),
+            ],
+            constraints: vec![
+                ConstraintDefinition::primary_key("employees_pkey".to_string(), vec!["id".to_string()]),
+                ConstraintDefinition::foreign_key(
+                    "employees_manager_id_fkey".to_string(),
+                    vec!["manager_id".to_string()],
+                    "public.employees".to_string(),
+                    vec!["id".to_string()],
+                ),
+            ],
+            indexes: vec![],
+            storage_parameters: HashMap::new(),
+            tablespace: None,
+            comment: None,
+        };
+        
+        let ddl = generate_create_table(&design);
+        let (create, foreign_key) = ddl.split_once("\n\n").unwrap();
+        assert!(create.contains("CONSTRAINT employees_pkey PRIMARY KEY (id)"));
+        assert!(!create.contains("FOREIGN KEY"));
+        assert_eq!(
+            foreign_key,
+            "ALTER TABLE public.employees ADD CONSTRAINT employees_manager_id_fkey \
+             FOREIGN KEY (manager_id) REFERENCES public.employees (id);"
+        );
+    }
+
+    #[test]
+    fn test_create_tables_adds_foreign_keys_after_all_tables() {
+        let table = |name: &str, constraints: Vec<ConstraintDefinition>| TableDesign {
+            table_name: name.to_string(),
+            schema: "public".to_string(),
+            columns: vec![
+                ColumnDefinition::new("id".to_string(), "integer".to_string(), false),
+                ColumnDefinition::new("customer_id".to_string(), "integer".to_string(), true),
+            ],
+            constraints,
+            indexes: vec![],
+            storage_parameters: HashMap::new(),
+            tablespace: None,
+            comment: None,
+        };
+        // orders references customers but comes first
+        let designs = vec![
+            table(
+                "orders",
+                vec![ConstraintDefinition::foreign_key(
+                    "orders_customer_id_fkey".to_string(),
+                    vec!["customer_id".to_string()],
+                    "public.customers".to_string(),
+                    vec!["id".to_string()],
+                )],
+            ),
+            table(
+                "customers",
+                vec![ConstraintDefinition::primary_key("customers_pkey".to_string(), vec!["id".to_string()])],
+            ),
+        ];
+        
+        let ddl = generate_create_tables(&designs);
+        let orders = ddl.find("CREATE TABLE public.orders").unwrap();
+        let customers = ddl.find("CREATE TABLE public.customers").unwrap();
+        let foreign_key = ddl.find("ALTER TABLE public.orders ADD CONSTRAINT orders_customer_id_fkey").unwrap();
+        assert!(orders < customers && customers < foreign_key);
+        assert!(ddl.ends_with("REFERENCES public.customers (id);"));
+    }
+
+    #[test]
+    fn test_create_table_with_storage_parameters() {
+        let mut design = TableDesign {
+            table_name: "events".to_string(),
+            schema: "public".to_string(),
+            columns: vec![ColumnDefinition::new("id".to_string(), "integer".to_string(), false)],
+            constraints: vec![],
+            indexes: vec![],
+            storage_parameters: HashMap::new(),
+            tablespace: None,
+            comment: None,
+        };
+        assert!(generate_create_table(&design).ends_with("\n);"));
+        
+        design.storage_parameters.insert("fillfactor".to_string(), "70".to_string());
+        design.storage_parameters.insert("autovacuum_vacuum_scale_factor".to_string(), "0.1".to_string());
+        
+        let ddl = generate_create_table(&design);
+        assert!(ddl.ends_with("\n) WITH (autovacuum_vacuum_scale_factor=0.1, fillfactor=70);"));
+    }
+
+    #[test]
+    fn test_create_table_with_tablespace() {
+        let mut design = TableDesign {
+            table_name: "events".to_string(),
+            schema: "public".to_string(),
+            columns: vec![ColumnDefinition::new("id".to_string(), "integer".to_string(), false)],
+            constraints: vec![],
+            indexes: vec![],
+            storage_parameters: HashMap::new(),
+            tablespace: Some("cold_storage".to_string()),
+            comment: None,
+        };
+        assert!(generate_create_table(&design).ends_with("\n) TABLESPACE cold_storage;"));
+
+        design.storage_parameters.insert("fillfactor".to_string(), "70".to_string());
+        assert!(generate_create_table(&design).ends_with("\n) WITH (fillfactor=70) TABLESPACE cold_storage;"));
+    }
+
+    #[test]
+    fn test_alter_table_storage_parameters() {
+        let mut set_storage_parameters = HashMap::new();
+        set_storage_parameters.insert("autovacuum_enabled".to_string(), "false".to_string());
+        
+        let changes = TableChanges {
+            added_columns: vec![],
+            modified_columns: vec![],
+            dropped_columns: vec![],
+            added_constraints: vec![],
+            dropped_constraints: vec![],
+            renamed_constraints: vec![],
+            added_indexes: vec![],
+            dropped_indexes: vec![],
+            set_storage_parameters,
+            reset_storage_parameters: vec!["fillfactor".to_string()],
+        };
+        
+        let statements = generate_alter_table("public", "events", &changes);
+        assert_eq!(
+            statements,
+            vec![
+                "ALTER TABLE public.events SET (autovacuum_enabled=false);".to_string(),
+                "ALTER TABLE public.events RESET (fillfactor);".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alter_table_renames_constraints_before_drops() {
+        let changes = TableChanges {
+            added_columns: vec![],
+            modified_columns: vec![],
+            dropped_columns: vec![],
+            added_constraints: vec![],
+            dropped_constraints: vec!["orders_note_check".to_string()],
+            renamed_constraints: vec![
+                ("orders_amount_check".to_string(), "Amount positive".to_string()),
+            ],
+            added_indexes: vec![],
+            dropped_indexes: vec![],
+            set_storage_parameters: HashMap::new(),
+            reset_storage_parameters: vec![],
+        };
+        
+        let statements = generate_alter_table("public", "orders", &changes);
+        assert_eq!(
+            statements,
+            vec![
+                "ALTER TABLE public.orders RENAME CONSTRAINT orders_amount_check TO \"Amount positive\";".to_string(),
+                "ALTER TABLE public.orders DROP CONSTRAINT orders_note_check;".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_storage_parameters_quotes_unusual_values() {
+        let mut parameters = HashMap::new();
+        parameters.insert("some_option".to_string(), "a b".to_string());
+        assert_eq!(format_storage_parameters(&parameters), "some_option='a b'");
+    }
+
+    #[test]
+    fn test_generate_schema_statements() {
+        assert_eq!(
+            generate_create_schema("Sales", Some("app_owner"), true),
+            "CREATE SCHEMA IF NOT EXISTS \"Sales\" AUTHORIZATION \"app_owner\";"
+        );
+        assert_eq!(generate_create_schema("sales", None, false), "CREATE SCHEMA \"sales\";");
+        assert_eq!(generate_drop_schema("sales", true), "DROP SCHEMA \"sales\" CASCADE;");
+        assert_eq!(generate_drop_schema("sales", false), "DROP SCHEMA \"sales\" RESTRICT;");
+    }
+
+    #[test]
+    fn test_validate_storage_parameter_names() {
+        assert!(validate_storage_parameter_names(["fillfactor", "toast.autovacuum_enabled", "_x1"]).is_ok());
+        assert!(validate_storage_parameter_names([]).is_ok());
+        assert!(validate_storage_parameter_names([""]).is_err());
+        assert!(validate_storage_parameter_names(["1fillfactor"]).is_err());
+        assert!(validate_storage_parameter_names(["FillFactor"]).is_err());
+        assert!(validate_storage_parameter_names(["fillfactor=10); DROP TABLE users; --"]).is_err());
+    }
+
+    #[test]
+    fn test_validate_schema_name() {
+        assert!(validate_schema_name("sales").is_ok());
+        assert!(validate_schema_name("Sales 2024").is_ok());
+        assert!(validate_schema_name("").is_err());
+        assert!(validate_schema_name("pg_custom").is_err());
+        assert!(validate_schema_name(&"a".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn test_generate_comment_statements() {
+        let documentation = TableDocumentation {
+            table_comment: "Customer orders".to_string(),
+            columns: vec![
+                crate::models::schema::ColumnDocumentation {
+                    name: "id".to_string(),
+                    comment: String::new(),
+                },
+                crate::models::schema::ColumnDocumentation {
+                    name: "note".to_string(),
+                    comment: "Buyer's note".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            generate_comment_statements("public", "orders", &documentation),
+            vec![
+                "COMMENT ON TABLE public.orders IS 'Customer orders';".to_string(),
+                "COMMENT ON COLUMN public.orders.note IS 'Buyer''s note';".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generated_column_definition() {
+        let mut col = ColumnDefinition::new("total".to_string(), "numeric".to_string(), true);
+        col.generated_expression = Some("price * quantity".to_string());
+        col.column_default = Some("0".to_string());
+
+        assert_eq!(
+            generate_column_definition(&col),
+            "total NUMERIC GENERATED ALWAYS AS (price * quantity) STORED"
+        );
+    }
+
+    #[test]
+    fn test_identity_column_definition() {
+        let mut col = ColumnDefinition::new("id".to_string(), "bigint".to_string(), false);
+        col.identity = Some(IdentityKind::Always);
+        assert_eq!(
+            generate_column_definition(&col),
+            "id BIGINT GENERATED ALWAYS AS IDENTITY NOT NULL"
+        );
+
+        col.identity = Some(IdentityKind::ByDefault);
+        col.column_default = Some("nextval('orders_id_seq'::regclass)".to_string());
+        assert_eq!(
+            generate_column_definition(&col),
+            "id BIGINT GENERATED BY DEFAULT AS IDENTITY NOT NULL"
+        );
+    }
+
+    #[test]
+    fn test_create_table_with_comments() {
+        let mut note = ColumnDefinition::new("note".to_string(), "text".to_string(), true);
+        note.comment = Some("Buyer's note".to_string());
+        let mut design = TableDesign {
+            table_name: "orders".to_string(),
+            schema: "public".to_string(),
+            columns: vec![ColumnDefinition::new("id".to_string(), "integer".to_string(), false), note],
+            constraints: vec![],
+            indexes: vec![],
+            storage_parameters: HashMap::new(),
+            tablespace: None,
+            comment: None,
+        };
+        assert!(generate_create_table(&design).ends_with(
+            "\n);\n\nCOMMENT ON COLUMN public.orders.note IS 'Buyer''s note';"
+        ));
+
+        design.comment = Some("Customer orders".to_string());
+        let ddl = generate_create_table(&design);
+        assert!(ddl.ends_with(
+            "\n);\n\nCOMMENT ON TABLE public.orders IS 'Customer orders';\n\
+             COMMENT ON COLUMN public.orders.note IS 'Buyer''s note';"
+        ));
+    }
+
+    fn column(name: &str, data_type: &str) -> ColumnDefinition {
+        ColumnDefinition {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            character_maximum_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            is_nullable: true,
+            column_default: None,
+            generated_expression: None,
+            identity: None,
+            is_primary_key: false,
+            is_unique: false,
+            comment: None,
+        }
+    }
+
+    fn modification(old_type: Option<&str>, new_type: &str, using_expression: Option<&str>) -> ColumnModification {
+        ColumnModification {
+            old_name: "amount".to_string(),
+            new_definition: column("amount", new_type),
+            old_definition: old_type.map(|old_type| column("amount", old_type)),
+            using_expression: using_expression.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_alter_column_type_with_using_expression() {
+        let statements = generate_column_modifications(
+            "public.orders",
+            &modification(Some("text"), "integer", Some("NULLIF(trim(amount), '')::integer")),
+        );
+        assert_eq!(
+            statements,
+            vec!["ALTER TABLE public.orders ALTER COLUMN amount TYPE INTEGER USING NULLIF(trim(amount), '')::integer;"]
+        );
+
+        // text -> integer can fail, so no USING is added by default
+        let statements = generate_column_modifications("public.orders", &modification(Some("text"), "integer", None));
+        assert_eq!(statements, vec!["ALTER TABLE public.orders ALTER COLUMN amount TYPE INTEGER;"]);
+
+        // A blank expression is ignored
+        let statements = generate_column_modifications("public.orders", &modification(None, "integer", Some("  ")));
+        assert_eq!(statements[0], "ALTER TABLE public.orders ALTER COLUMN amount TYPE INTEGER;");
+    }
+
+    #[test]
+    fn test_alter_column_type_default_using_for_safe_casts() {
+        let statements = generate_column_modifications("public.orders", &modification(Some("integer"), "bigint", None));
+        assert_eq!(statements, vec!["ALTER TABLE public.orders ALTER COLUMN amount TYPE BIGINT USING amount::BIGINT;"]);
+
+        assert!(is_safe_cast("integer", "TEXT"));
+        assert!(is_safe_cast("character varying(20)", "text"));
+        assert!(is_safe_cast("json", "JSONB"));
+        assert!(is_safe_cast("date", "timestamp with time zone"));
+        assert!(!is_safe_cast("integer", "INTEGER"));
+        assert!(!is_safe_cast("text", "VARCHAR(10)"));
+        assert!(!is_safe_cast("bigint", "INTEGER"));
+        assert!(!is_safe_cast("integer[]", "text"));
+    }
+
+    #[test]
+    fn test_unchanged_column_produces_no_statements() {
+        let mut old = column("price", "numeric");
+        old.numeric_precision = Some(10);
+        old.numeric_scale = Some(2);
+        old.is_nullable = false;
+        old.column_default = Some("0".to_string());
+
+        let modification = ColumnModification {
+            old_name: "price".to_string(),
+            new_definition: old.clone(),
+            old_definition: Some(old.clone()),
+            using_expression: None,
+        };
+        assert!(generate_column_modifications("public.products", &modification).is_empty());
+
+        // Type aliases are the same type
+        let modification = ColumnModification {
+            old_name: "id".to_string(),
+            new_definition: column("id", "int4"),
+            old_definition: Some(column("id", "integer")),
+            using_expression: None,
+        };
+        assert!(generate_column_modifications("public.products", &modification).is_empty());
+    }
+
+    #[test]
+    fn test_changing_only_the_default_produces_one_statement() {
+        let mut old = column("price", "numeric");
+        old.numeric_precision = Some(10);
+        old.numeric_scale = Some(2);
+        old.is_nullable = false;
+        let mut new = old.clone();
+        new.column_default = Some("0".to_string());
+
+        let modification = ColumnModification {
+            old_name: "price".to_string(),
+            new_definition: new,
+            old_definition: Some(old.clone()),
+            using_expression: None,
+        };
+        assert_eq!(
+            generate_column_modifications("public.products", &modification),
+            vec!["ALTER TABLE public.products ALTER COLUMN price SET DEFAULT 0;"]
+        );
+
+        // A changed precision is a type change
+        let mut new = old.clone();
+        new.numeric_precision = Some(12);
+        let modification = ColumnModification {
+            old_name: "price".to_string(),
+            new_definition: new,
+            old_definition: Some(old),
+            using_expression: None,
+        };
+        assert_eq!(
+            generate_column_modifications("public.products", &modification),
+            vec!["ALTER TABLE public.products ALTER COLUMN price TYPE NUMERIC(12, 2);"]
+        );
+    }
 }
@@ -0,0 +1,412 @@
+/**
+ * DML Preview Service
+ *
+ * This module previews destructive statements before they are run:
+ * - Rewrites a DELETE or UPDATE into a SELECT with the same target table and predicate
+ * - Returns the affected row count and the first affected rows
+ * - For UPDATE, returns the current values of the columns being changed
+ * - Warns loudly when the statement has no WHERE clause
+ *
+ * The preview runs in a READ ONLY transaction that is always rolled back, so the
+ * statement itself is never executed.
+ */
+
+use crate::models::query::{AffectedRowsPreview, ColumnInfo, QueryResultType};
+use crate::services::query_executor::{self, parse_sql_statements};
+use crate::services::sql_lexer::{tokenize, Token, TokenKind};
+use std::collections::HashMap;
+use tokio_postgres::Client;
+
+/// Number of affected rows returned when no limit is given
+pub const DEFAULT_PREVIEW_LIMIT: i64 = 100;
+
+/// Warning for statements without a WHERE clause
+const FULL_TABLE_WARNING: &str = "Warning: this statement has no WHERE clause and will affect every row in the table";
+
+/// Preview the rows an UPDATE or DELETE statement would affect
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `sql` - A single UPDATE or DELETE statement
+/// * `limit` - Maximum number of rows to return
+///
+/// # Returns
+/// * `Result<AffectedRowsPreview, String>` - Affected row count and rows, or error message
+pub async fn preview_affected_rows(
+    client: &Client,
+    sql: &str,
+    limit: i64,
+) -> Result<AffectedRowsPreview, String> {
+    let statements = parse_sql_statements(sql.trim());
+    let statement = match statements.as_slice() {
+        [statement] => *statement,
+        [] => return Err("SQL statement is empty".to_string()),
+        _ => return Err("Preview only supports a single statement".to_string()),
+    };
+
+    let dml = parse_dml(statement)?;
+    let preview_sql = build_preview_query(&dml);
+    let limit = limit.max(1);
+
+    client
+        .batch_execute("BEGIN READ ONLY")
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    let result = run_preview(client, &preview_sql, limit).await;
+
+    client
+        .batch_execute("ROLLBACK")
+        .await
+        .map_err(|e| format!("Failed to rollback transaction: {}", e))?;
+
+    let (affected_count, columns, rows) = result?;
+    let full_table = dml.where_clause.is_none();
+
+    Ok(AffectedRowsPreview {
+        statement_type: dml.kind,
+        table: dml.target.to_string(),
+        preview_sql,
+        affected_count,
+        columns,
+        truncated: affected_count > rows.len() as i64,
+        rows,
+        full_table,
+        warning: if full_table {
+            Some(FULL_TABLE_WARNING.to_string())
+        } else {
+            None
+        },
+    })
+}
+
+type PreviewRows = (i64, Vec<ColumnInfo>, Vec<HashMap<String, serde_json::Value>>);
+
+/// Count and fetch the affected rows inside the read-only transaction
+async fn run_preview(client: &Client, preview_sql: &str, limit: i64) -> Result<PreviewRows, String> {
+    let count: i64 = client
+        .query_one(
+            format!("SELECT count(*) FROM ({}) AS __preview", preview_sql).as_str(),
+            &[],
+        )
+        .await
+        .map_err(|e| match e.as_db_error() {
+            Some(db_error) => db_error.message().to_string(),
+            None => e.to_string(),
+        })?
+        .get(0);
+
+    let result = query_executor::execute_sql(client, &format!("{} LIMIT {}", preview_sql, limit)).await;
+    if result.result_type == QueryResultType::Error {
+        return Err(result.error.unwrap_or_else(|| "Failed to fetch affected rows".to_string()));
+    }
+
+    Ok((
+        count,
+        result.columns.unwrap_or_default(),
+        result.rows.unwrap_or_default(),
+    ))
+}
+
+/// A parsed UPDATE or DELETE statement
+#[derive(Debug, PartialEq)]
+struct DmlStatement<'a> {
+    kind: QueryResultType,
+    /// Target table as written (possibly schema-qualified and quoted)
+    target: &'a str,
+    only: bool,
+    alias: Option<&'a str>,
+    /// Columns assigned by UPDATE ... SET, as written
+    set_columns: Vec<&'a str>,
+    /// DELETE ... USING or UPDATE ... FROM list
+    extra_from: Option<&'a str>,
+    where_clause: Option<&'a str>,
+}
+
+/// Build a SELECT returning the rows the statement would affect
+///
+/// Joins from USING/FROM are moved into a correlated EXISTS so that each target
+/// row is returned once, matching the number of rows the statement affects.
+fn build_preview_query(dml: &DmlStatement) -> String {
+    let reference = dml.alias.unwrap_or(dml.target);
+
+    let select_list = if dml.kind == QueryResultType::Update {
+        dml.set_columns
+            .iter()
+            .map(|column| format!("{}.{}", reference, column))
+            .collect::<Vec<_>>()
+            .join(", ")
+    } else {
+        format!("{}.*", reference)
+    };
+
+    let mut from = format!("{}{}", if dml.only { "ONLY " } else { "" }, dml.target);
+    if let Some(alias) = dml.alias {
+        from.push_str(&format!(" AS {}", alias));
+    }
+
+    let mut sql = format!("SELECT {} FROM {}", select_list, from);
+    match (dml.extra_from, dml.where_clause) {
+        (Some(extra), Some(condition)) => {
+            sql.push_str(&format!(" WHERE EXISTS (SELECT 1 FROM {} WHERE {})", extra, condition))
+        }
+        (Some(extra), None) => sql.push_str(&format!(" WHERE EXISTS (SELECT 1 FROM {})", extra)),
+        (None, Some(condition)) => sql.push_str(&format!(" WHERE {}", condition)),
+        (None, None) => {}
+    }
+
+    sql
+}
+
+/// Parse an UPDATE or DELETE statement into its target, SET columns, and clauses
+fn parse_dml(sql: &str) -> Result<DmlStatement<'_>, String> {
+    let tokens = tokenize(sql);
+    let mut pos = 0;
+
+    let kind = match tokens.first() {
+        Some(token) if token.is_keyword(sql, "DELETE") => QueryResultType::Delete,
+        Some(token) if token.is_keyword(sql, "UPDATE") => QueryResultType::Update,
+        Some(token) if token.is_keyword(sql, "WITH") => {
+            return Err("Statements with a WITH clause cannot be previewed".to_string())
+        }
+        _ => return Err("Only DELETE and UPDATE statements can be previewed".to_string()),
+    };
+    pos += 1;
+
+    if kind == QueryResultType::Delete {
+        if !tokens.get(pos).is_some_and(|t| t.is_keyword(sql, "FROM")) {
+            return Err("Expected FROM after DELETE".to_string());
+        }
+        pos += 1;
+    }
+
+    let only = tokens.get(pos).is_some_and(|t| t.is_keyword(sql, "ONLY"));
+    if only {
+        pos += 1;
+    }
+
+    // Target table: ident(.ident)*
+    let target_start = match tokens.get(pos) {
+        Some(token) if token.is_identifier() => token.start,
+        _ => return Err("Expected a table name".to_string()),
+    };
+    let mut target_end = tokens[pos].end;
+    pos += 1;
+    while tokens.get(pos).is_some_and(|t| t.kind == TokenKind::Symbol('.'))
+        && tokens.get(pos + 1).is_some_and(|t| t.is_identifier())
+    {
+        target_end = tokens[pos + 1].end;
+        pos += 2;
+    }
+    let target = &sql[target_start..target_end];
+
+    // Optional "*" for including child tables
+    if tokens.get(pos).is_some_and(|t| t.kind == TokenKind::Symbol('*')) {
+        pos += 1;
+    }
+
+    let clause_keywords: &[&str] = match kind {
+        QueryResultType::Delete => &["USING", "WHERE", "RETURNING"],
+        _ => &["SET", "FROM", "WHERE", "RETURNING"],
+    };
+
+    // Optional alias
+    let mut alias = None;
+    if tokens.get(pos).is_some_and(|t| t.is_keyword(sql, "AS")) {
+        pos += 1;
+        match tokens.get(pos) {
+            Some(token) if token.is_identifier() => {
+                alias = Some(token.text(sql));
+                pos += 1;
+            }
+            _ => return Err("Expected an alias after AS".to_string()),
+        }
+    } else if let Some(token) = tokens.get(pos) {
+        if token.is_identifier() && !clause_keywords.iter().any(|k| token.is_keyword(sql, k)) {
+            alias = Some(token.text(sql));
+            pos += 1;
+        }
+    }
+
+    // Locate the top-level clause keywords
+    let clause_positions: Vec<(usize, &str)> = tokens
+        .iter()
+        .enumerate()
+        .skip(pos)
+        .filter(|(_, t)| t.depth == 0)
+        .filter_map(|(i, t)| {
+            clause_keywords
+                .iter()
+                .find(|k| t.is_keyword(sql, k))
+                .map(|k| (i, *k))
+        })
+        .collect();
+
+    let clause = |keyword: &str| clause_range(&clause_positions, keyword, tokens.len());
+    let clause_text = |range: Option<(usize, usize)>| match range {
+        Some((first, last)) if first < last => Some(sql[tokens[first].start..tokens[last - 1].end].trim()),
+        _ => None,
+    };
+
+    if clause_positions.first().map(|(i, _)| *i) != Some(pos) && pos < tokens.len() {
+        return Err(format!(
+            "Unexpected text after table name: {}",
+            tokens[pos].text(sql)
+        ));
+    }
+
+    let mut set_columns = Vec::new();
+    if kind == QueryResultType::Update {
+        let (first, last) = match clause("SET") {
+            Some(range) if clause_positions[0].1 == "SET" => range,
+            _ => return Err("Expected SET after the table name".to_string()),
+        };
+        set_columns = parse_set_columns(sql, &tokens[first..last])?;
+    }
+
+    let extra_from = match kind {
+        QueryResultType::Delete => clause_text(clause("USING")),
+        _ => clause_text(clause("FROM")),
+    };
+
+    let where_clause = clause_text(clause("WHERE"));
+    if let Some(condition) = where_clause {
+        let mut words = condition.split_whitespace();
+        let current = words.next().is_some_and(|w| w.eq_ignore_ascii_case("CURRENT"));
+        if current && words.next().is_some_and(|w| w.eq_ignore_ascii_case("OF")) {
+            return Err("WHERE CURRENT OF cannot be previewed".to_string());
+        }
+    }
+    if clause("WHERE").is_some() && where_clause.is_none() {
+        return Err("WHERE clause is empty".to_string());
+    }
+
+    Ok(DmlStatement {
+        kind,
+        target,
+        only,
+        alias,
+        set_columns,
+        extra_from,
+        where_clause,
+    })
+}
+
+/// Token range of a clause: from after its keyword up to the next clause keyword
+fn clause_range(clause_positions: &[(usize, &str)], keyword: &str, token_count: usize) -> Option<(usize, usize)> {
+    let index = clause_positions.iter().position(|(_, k)| *k == keyword)?;
+    let first = clause_positions[index].0 + 1;
+    let last = clause_positions
+        .get(index + 1)
+        .map(|(i, _)| *i)
+        .unwrap_or(token_count);
+    Some((first, last))
+}
+
+/// Extract the assigned column names from the tokens of a SET clause
+///
+/// Handles `col = expr`, `col[1] = expr`, `col.field = expr`, and `(a, b) = (...)`.
+fn parse_set_columns<'a>(sql: &'a str, tokens: &[Token]) -> Result<Vec<&'a str>, String> {
+    let mut columns: Vec<&str> = Vec::new();
+    let mut push = |column: &'a str| {
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    };
+
+    // Split the clause into assignments on top-level commas
+    let mut assignments: Vec<&[Token]> = Vec::new();
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if token.depth == 0 && token.kind == TokenKind::Symbol(',') {
+            assignments.push(&tokens[start..i]);
+            start = i + 1;
+        }
+    }
+    assignments.push(&tokens[start..]);
+
+    for assignment in assignments {
+        match assignment.first() {
+            Some(token) if token.kind == TokenKind::Symbol('(') => {
+                // (a, b) = ...: the first identifier of each comma-separated item
+                let mut expect_column = true;
+                for token in &assignment[1..] {
+                    if token.depth == 0 && token.kind == TokenKind::Symbol(')') {
+                        break;
+                    }
+                    if token.depth == 1 && token.kind == TokenKind::Symbol(',') {
+                        expect_column = true;
+                    } else if expect_column && token.is_identifier() {
+                        push(token.text(sql));
+                        expect_column = false;
+                    }
+                }
+            }
+            Some(token) if token.is_identifier() => push(token.text(sql)),
+            _ => return Err("Invalid SET clause".to_string()),
+        }
+    }
+
+    if columns.is_empty() {
+        return Err("SET clause has no columns".to_string());
+    }
+
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview(sql: &str) -> String {
+        build_preview_query(&parse_dml(sql).unwrap())
+    }
+
+    #[test]
+    fn test_delete_preview() {
+        assert_eq!(
+            preview("DELETE FROM public.orders WHERE status = 'cancelled' AND note <> 'it''s; WHERE'"),
+            "SELECT public.orders.* FROM public.orders WHERE status = 'cancelled' AND note <> 'it''s; WHERE'"
+        );
+        assert_eq!(
+            preview("delete from orders o where o.id in (select order_id from refunds where amount > 0) returning *"),
+            "SELECT o.* FROM orders AS o WHERE o.id in (select order_id from refunds where amount > 0)"
+        );
+    }
+
+    #[test]
+    fn test_delete_using_preview() {
+        assert_eq!(
+            preview("DELETE FROM orders AS o USING customers c WHERE o.customer_id = c.id AND c.banned"),
+            "SELECT o.* FROM orders AS o WHERE EXISTS (SELECT 1 FROM customers c WHERE o.customer_id = c.id AND c.banned)"
+        );
+    }
+
+    #[test]
+    fn test_update_preview_selects_changed_columns() {
+        assert_eq!(
+            preview("UPDATE ONLY \"Users\" u SET name = upper(name), (age, \"Score\") = (1, 2), tags[1] = 'x' WHERE u.id = $1"),
+            "SELECT u.name, u.age, u.\"Score\", u.tags FROM ONLY \"Users\" AS u WHERE u.id = $1"
+        );
+        assert_eq!(
+            preview("UPDATE accounts SET balance = balance - t.amount FROM transfers t WHERE t.account_id = accounts.id"),
+            "SELECT accounts.balance FROM accounts WHERE EXISTS (SELECT 1 FROM transfers t WHERE t.account_id = accounts.id)"
+        );
+    }
+
+    #[test]
+    fn test_statement_without_where() {
+        let dml = parse_dml("DELETE FROM audit_log").unwrap();
+        assert!(dml.where_clause.is_none());
+        assert_eq!(build_preview_query(&dml), "SELECT audit_log.* FROM audit_log");
+    }
+
+    #[test]
+    fn test_unsupported_statements() {
+        assert!(parse_dml("SELECT * FROM users").is_err());
+        assert!(parse_dml("INSERT INTO users VALUES (1)").is_err());
+        assert!(parse_dml("WITH x AS (SELECT 1) DELETE FROM users").is_err());
+        assert!(parse_dml("DELETE FROM users WHERE CURRENT OF cur").is_err());
+        assert!(parse_dml("UPDATE users WHERE id = 1").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+/**
+ * Editor Transaction Service
+ *
+ * 此模块支持在 SQL 编辑器中显式开启事务，执行多条语句后再提交或回滚：
+ * - 每个打开的事务独占一个专用连接（事务必须始终在同一个连接上执行），不放入连接池
+ * - 事务以编号（tx_id）登记，后续语句、提交和回滚都按编号找到对应的连接
+ * - 长时间未使用的事务视为已被放弃，自动回滚并关闭连接
+ *
+ * 事务内不允许执行 BEGIN、COMMIT、ROLLBACK 等事务控制语句（保存点除外），
+ * 否则登记的事务状态会与服务器上的实际状态不一致。
+ */
+
+use crate::models::query::QueryResult;
+use crate::services::connection_router::PooledClient;
+use crate::services::query_executor;
+use crate::services::sql_lexer::{tokenize, TokenKind};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// 事务闲置多久后视为已被放弃
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// 检查闲置事务的间隔
+pub const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 一个打开的事务及其专用连接
+pub struct EditorTransaction {
+    /// 事务编号
+    pub id: String,
+    /// 数据库名称
+    pub database: String,
+    client: PooledClient,
+    started_at: Instant,
+    last_used: Instant,
+}
+
+impl EditorTransaction {
+    /// 在专用连接上执行 `BEGIN`，开启一个新事务
+    ///
+    /// # Arguments
+    /// * `database` - 数据库名称
+    /// * `client` - 专用连接，事务结束时随之关闭
+    ///
+    /// # Returns
+    /// * `Result<EditorTransaction, String>` - 打开的事务或错误信息
+    pub async fn begin(database: &str, client: PooledClient) -> Result<Self, String> {
+        client
+            .batch_execute("BEGIN")
+            .await
+            .map_err(|e| format!("无法开启事务: {}", e))?;
+
+        let now = Instant::now();
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            database: database.to_string(),
+            client,
+            started_at: now,
+            last_used: now,
+        })
+    }
+
+    /// 专用连接，可用于登记取消令牌
+    pub fn client(&self) -> &PooledClient {
+        &self.client
+    }
+
+    /// 在事务中执行 SQL（可以包含多条语句）
+    ///
+    /// 语句失败后服务器会拒绝该事务中的后续语句，只能回滚（或回滚到保存点）。
+    pub async fn execute(&mut self, sql: &str) -> QueryResult {
+        self.last_used = Instant::now();
+        self.client.take_notices();
+        let mut result = match check_no_transaction_control(sql) {
+            Ok(()) => query_executor::execute_sql(&self.client, sql).await,
+            Err(message) => QueryResult::error(message, None, 0),
+        };
+        result.notices = self.client.take_notices();
+        self.last_used = Instant::now();
+        result
+    }
+
+    /// 提交事务
+    pub async fn commit(&mut self) -> Result<(), String> {
+        self.client
+            .batch_execute("COMMIT")
+            .await
+            .map_err(|e| format!("提交事务失败: {}", e))
+    }
+
+    /// 回滚事务
+    pub async fn rollback(&mut self) -> Result<(), String> {
+        self.client
+            .batch_execute("ROLLBACK")
+            .await
+            .map_err(|e| format!("回滚事务失败: {}", e))
+    }
+
+    /// 事务开启以来的时间
+    pub fn age(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// 上一条语句结束以来的时间
+    pub fn idle_for(&self) -> Duration {
+        self.last_used.elapsed()
+    }
+}
+
+/// 打开的事务登记表
+#[derive(Default)]
+pub struct TransactionRegistry {
+    open: HashMap<String, Arc<Mutex<EditorTransaction>>>,
+}
+
+impl TransactionRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记一个打开的事务，返回其编号
+    pub fn insert(&mut self, transaction: EditorTransaction) -> String {
+        let id = transaction.id.clone();
+        self.open.insert(id.clone(), Arc::new(Mutex::new(transaction)));
+        id
+    }
+
+    /// 按编号查找事务
+    pub fn get(&self, id: &str) -> Option<Arc<Mutex<EditorTransaction>>> {
+        self.open.get(id).cloned()
+    }
+
+    /// 注销事务（提交或回滚前调用，之后不能再按编号找到该事务）
+    pub fn remove(&mut self, id: &str) -> Option<Arc<Mutex<EditorTransaction>>> {
+        self.open.remove(id)
+    }
+
+    /// 打开的事务数量
+    pub fn len(&self) -> usize {
+        self.open.len()
+    }
+
+    /// 是否没有打开的事务
+    pub fn is_empty(&self) -> bool {
+        self.open.is_empty()
+    }
+
+    /// 注销闲置超过 `timeout` 的事务并返回它们
+    ///
+    /// 正在执行语句的事务不算闲置。
+    pub fn take_idle(&mut self, timeout: Duration) -> Vec<Arc<Mutex<EditorTransaction>>> {
+        let idle: Vec<String> = self
+            .open
+            .iter()
+            .filter(|(_, transaction)| {
+                transaction
+                    .try_lock()
+                    .is_ok_and(|transaction| transaction.idle_for() >= timeout)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        idle.iter().filter_map(|id| self.open.remove(id)).collect()
+    }
+}
+
+/// 回滚已被放弃的事务并关闭其连接
+///
+/// # Arguments
+/// * `transactions` - 已从登记表中注销的事务
+///
+/// # Returns
+/// * `usize` - 回滚的事务数量；回滚失败时连接仍会关闭，服务器随之回滚事务
+pub async fn rollback_abandoned(transactions: Vec<Arc<Mutex<EditorTransaction>>>) -> usize {
+    let count = transactions.len();
+    for transaction in transactions {
+        let mut transaction = transaction.lock().await;
+        log::warn!(
+            "事务 {}（数据库 {}）已闲置 {} 秒，自动回滚",
+            transaction.id,
+            transaction.database,
+            transaction.idle_for().as_secs()
+        );
+        if let Err(e) = transaction.rollback().await {
+            log::error!("{}", e);
+        }
+    }
+    count
+}
+
+/// 拒绝会改变事务状态的语句（`SAVEPOINT`、`ROLLBACK TO` 和 `RELEASE` 除外）
+fn check_no_transaction_control(sql: &str) -> Result<(), String> {
+    let tokens = tokenize(sql);
+    let mut statement_start = true;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind == TokenKind::Symbol(';') {
+            statement_start = true;
+            continue;
+        }
+        if !statement_start {
+            continue;
+        }
+        statement_start = false;
+
+        let keyword = token.text(sql).to_uppercase();
+        let rollback_to_savepoint = keyword == "ROLLBACK"
+            && tokens
+                .get(i + 1)
+                .is_some_and(|next| next.is_keyword(sql, "TO"));
+        if token.kind == TokenKind::Word
+            && ["BEGIN", "START", "COMMIT", "END", "ROLLBACK", "ABORT"].contains(&keyword.as_str())
+            && !rollback_to_savepoint
+        {
+            return Err(format!(
+                "事务中不能执行 {}，请使用提交或回滚事务命令（可以使用 SAVEPOINT 和 ROLLBACK TO SAVEPOINT）",
+                keyword
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_control_is_rejected() {
+        assert!(check_no_transaction_control("INSERT INTO t VALUES (1); SELECT * FROM t").is_ok());
+        assert!(check_no_transaction_control("SAVEPOINT a; ROLLBACK TO SAVEPOINT a; RELEASE a").is_ok());
+        assert!(check_no_transaction_control("rollback to a").is_ok());
+        // 字符串和函数体中的关键字不算语句
+        assert!(check_no_transaction_control("SELECT 'COMMIT'; DO $$ BEGIN PERFORM 1; END $$").is_ok());
+
+        assert!(check_no_transaction_control("COMMIT").is_err());
+        assert!(check_no_transaction_control("UPDATE t SET a = 1; rollback;").is_err());
+        assert!(check_no_transaction_control("  begin isolation level serializable").is_err());
+        assert!(check_no_transaction_control("START TRANSACTION").is_err());
+        assert!(check_no_transaction_control("SELECT 1; END").is_err());
+    }
+}
@@ -0,0 +1,706 @@
+/**
+ * Error Code Service
+ *
+ * This module explains PostgreSQL SQLSTATE error codes:
+ * - Embeds the standard table of error classes and condition names
+ * - Adds a description and common causes and fixes for frequently seen codes,
+ *   falling back to guidance for the whole class
+ * - Codes missing from the table (e.g. from extensions) are explained by their class
+ */
+
+use crate::models::query::ErrorExplanation;
+
+/// Description, causes and fixes for an error code or class
+struct Guidance {
+    description: &'static str,
+    causes: &'static [&'static str],
+    fixes: &'static [&'static str],
+}
+
+/// Standard error classes (first two characters of the SQLSTATE)
+const ERROR_CLASSES: &[(&str, &str)] = &[
+    ("00", "Successful Completion"),
+    ("01", "Warning"),
+    ("02", "No Data"),
+    ("03", "SQL Statement Not Yet Complete"),
+    ("08", "Connection Exception"),
+    ("09", "Triggered Action Exception"),
+    ("0A", "Feature Not Supported"),
+    ("0B", "Invalid Transaction Initiation"),
+    ("0F", "Locator Exception"),
+    ("0L", "Invalid Grantor"),
+    ("0P", "Invalid Role Specification"),
+    ("0Z", "Diagnostics Exception"),
+    ("20", "Case Not Found"),
+    ("21", "Cardinality Violation"),
+    ("22", "Data Exception"),
+    ("23", "Integrity Constraint Violation"),
+    ("24", "Invalid Cursor State"),
+    ("25", "Invalid Transaction State"),
+    ("26", "Invalid SQL Statement Name"),
+    ("27", "Triggered Data Change Violation"),
+    ("28", "Invalid Authorization Specification"),
+    ("2B", "Dependent Privilege Descriptors Still Exist"),
+    ("2D", "Invalid Transaction Termination"),
+    ("2F", "SQL Routine Exception"),
+    ("34", "Invalid Cursor Name"),
+    ("38", "External Routine Exception"),
+    ("39", "External Routine Invocation Exception"),
+    ("3B", "Savepoint Exception"),
+    ("3D", "Invalid Catalog Name"),
+    ("3F", "Invalid Schema Name"),
+    ("40", "Transaction Rollback"),
+    ("42", "Syntax Error or Access Rule Violation"),
+    ("44", "WITH CHECK OPTION Violation"),
+    ("53", "Insufficient Resources"),
+    ("54", "Program Limit Exceeded"),
+    ("55", "Object Not In Prerequisite State"),
+    ("57", "Operator Intervention"),
+    ("58", "System Error"),
+    ("72", "Snapshot Failure"),
+    ("F0", "Configuration File Error"),
+    ("HV", "Foreign Data Wrapper Error"),
+    ("P0", "PL/pgSQL Error"),
+    ("XX", "Internal Error"),
+];
+
+/// Standard error codes and their condition names (PostgreSQL Appendix A)
+const ERROR_CODES: &[(&str, &str)] = &[
+    ("00000", "successful_completion"),
+    ("01000", "warning"),
+    ("0100C", "dynamic_result_sets_returned"),
+    ("01008", "implicit_zero_bit_padding"),
+    ("01003", "null_value_eliminated_in_set_function"),
+    ("01007", "privilege_not_granted"),
+    ("01006", "privilege_not_revoked"),
+    ("01004", "string_data_right_truncation"),
+    ("01P01", "deprecated_feature"),
+    ("02000", "no_data"),
+    ("02001", "no_additional_dynamic_result_sets_returned"),
+    ("03000", "sql_statement_not_yet_complete"),
+    ("08000", "connection_exception"),
+    ("08003", "connection_does_not_exist"),
+    ("08006", "connection_failure"),
+    ("08001", "sqlclient_unable_to_establish_sqlconnection"),
+    ("08004", "sqlserver_rejected_establishment_of_sqlconnection"),
+    ("08007", "transaction_resolution_unknown"),
+    ("08P01", "protocol_violation"),
+    ("09000", "triggered_action_exception"),
+    ("0A000", "feature_not_supported"),
+    ("0B000", "invalid_transaction_initiation"),
+    ("0F000", "locator_exception"),
+    ("0F001", "invalid_locator_specification"),
+    ("0L000", "invalid_grantor"),
+    ("0LP01", "invalid_grant_operation"),
+    ("0P000", "invalid_role_specification"),
+    ("0Z000", "diagnostics_exception"),
+    ("0Z002", "stacked_diagnostics_accessed_without_active_handler"),
+    ("20000", "case_not_found"),
+    ("21000", "cardinality_violation"),
+    ("22000", "data_exception"),
+    ("2202E", "array_subscript_error"),
+    ("22021", "character_not_in_repertoire"),
+    ("22008", "datetime_field_overflow"),
+    ("22012", "division_by_zero"),
+    ("22005", "error_in_assignment"),
+    ("2200B", "escape_character_conflict"),
+    ("22022", "indicator_overflow"),
+    ("22015", "interval_field_overflow"),
+    ("2201E", "invalid_argument_for_logarithm"),
+    ("22014", "invalid_argument_for_ntile_function"),
+    ("22016", "invalid_argument_for_nth_value_function"),
+    ("2201F", "invalid_argument_for_power_function"),
+    ("2201G", "invalid_argument_for_width_bucket_function"),
+    ("22018", "invalid_character_value_for_cast"),
+    ("22007", "invalid_datetime_format"),
+    ("22019", "invalid_escape_character"),
+    ("2200D", "invalid_escape_octet"),
+    ("22025", "invalid_escape_sequence"),
+    ("22P06", "nonstandard_use_of_escape_character"),
+    ("22010", "invalid_indicator_parameter_value"),
+    ("22023", "invalid_parameter_value"),
+    ("22013", "invalid_preceding_or_following_size"),
+    ("2201B", "invalid_regular_expression"),
+    ("2201W", "invalid_row_count_in_limit_clause"),
+    ("2201X", "invalid_row_count_in_result_offset_clause"),
+    ("2202H", "invalid_tablesample_argument"),
+    ("2202G", "invalid_tablesample_repeat"),
+    ("22009", "invalid_time_zone_displacement_value"),
+    ("2200C", "invalid_use_of_escape_character"),
+    ("2200G", "most_specific_type_mismatch"),
+    ("22004", "null_value_not_allowed"),
+    ("22002", "null_value_no_indicator_parameter"),
+    ("22003", "numeric_value_out_of_range"),
+    ("2200H", "sequence_generator_limit_exceeded"),
+    ("22026", "string_data_length_mismatch"),
+    ("22001", "string_data_right_truncation"),
+    ("22011", "substring_error"),
+    ("22027", "trim_error"),
+    ("22024", "unterminated_c_string"),
+    ("2200F", "zero_length_character_string"),
+    ("22P01", "floating_point_exception"),
+    ("22P02", "invalid_text_representation"),
+    ("22P03", "invalid_binary_representation"),
+    ("22P04", "bad_copy_file_format"),
+    ("22P05", "untranslatable_character"),
+    ("2200L", "not_an_xml_document"),
+    ("2200M", "invalid_xml_document"),
+    ("2200N", "invalid_xml_content"),
+    ("2200S", "invalid_xml_comment"),
+    ("2200T", "invalid_xml_processing_instruction"),
+    ("22030", "duplicate_json_object_key_value"),
+    ("22031", "invalid_argument_for_sql_json_datetime_function"),
+    ("22032", "invalid_json_text"),
+    ("22033", "invalid_sql_json_subscript"),
+    ("22034", "more_than_one_sql_json_item"),
+    ("22035", "no_sql_json_item"),
+    ("22036", "non_numeric_sql_json_item"),
+    ("22037", "non_unique_keys_in_a_json_object"),
+    ("22038", "singleton_sql_json_item_required"),
+    ("22039", "sql_json_array_not_found"),
+    ("2203A", "sql_json_member_not_found"),
+    ("2203B", "sql_json_number_not_found"),
+    ("2203C", "sql_json_object_not_found"),
+    ("2203D", "too_many_json_array_elements"),
+    ("2203E", "too_many_json_object_members"),
+    ("2203F", "sql_json_scalar_required"),
+    ("2203G", "sql_json_item_cannot_be_cast_to_target_type"),
+    ("23000", "integrity_constraint_violation"),
+    ("23001", "restrict_violation"),
+    ("23502", "not_null_violation"),
+    ("23503", "foreign_key_violation"),
+    ("23505", "unique_violation"),
+    ("23514", "check_violation"),
+    ("23P01", "exclusion_violation"),
+    ("24000", "invalid_cursor_state"),
+    ("25000", "invalid_transaction_state"),
+    ("25001", "active_sql_transaction"),
+    ("25002", "branch_transaction_already_active"),
+    ("25008", "held_cursor_requires_same_isolation_level"),
+    ("25003", "inappropriate_access_mode_for_branch_transaction"),
+    ("25004", "inappropriate_isolation_level_for_branch_transaction"),
+    ("25005", "no_active_sql_transaction_for_branch_transaction"),
+    ("25006", "read_only_sql_transaction"),
+    ("25007", "schema_and_data_statement_mixing_not_supported"),
+    ("25P01", "no_active_sql_transaction"),
+    ("25P02", "in_failed_sql_transaction"),
+    ("25P03", "idle_in_transaction_session_timeout"),
+    ("26000", "invalid_sql_statement_name"),
+    ("27000", "triggered_data_change_violation"),
+    ("28000", "invalid_authorization_specification"),
+    ("28P01", "invalid_password"),
+    ("2B000", "dependent_privilege_descriptors_still_exist"),
+    ("2BP01", "dependent_objects_still_exist"),
+    ("2D000", "invalid_transaction_termination"),
+    ("2F000", "sql_routine_exception"),
+    ("2F005", "function_executed_no_return_statement"),
+    ("2F002", "modifying_sql_data_not_permitted"),
+    ("2F003", "prohibited_sql_statement_attempted"),
+    ("2F004", "reading_sql_data_not_permitted"),
+    ("34000", "invalid_cursor_name"),
+    ("38000", "external_routine_exception"),
+    ("38001", "containing_sql_not_permitted"),
+    ("38002", "modifying_sql_data_not_permitted"),
+    ("38003", "prohibited_sql_statement_attempted"),
+    ("38004", "reading_sql_data_not_permitted"),
+    ("39000", "external_routine_invocation_exception"),
+    ("39001", "invalid_sqlstate_returned"),
+    ("39004", "null_value_not_allowed"),
+    ("39P01", "trigger_protocol_violated"),
+    ("39P02", "srf_protocol_violated"),
+    ("39P03", "event_trigger_protocol_violated"),
+    ("3B000", "savepoint_exception"),
+    ("3B001", "invalid_savepoint_specification"),
+    ("3D000", "invalid_catalog_name"),
+    ("3F000", "invalid_schema_name"),
+    ("40000", "transaction_rollback"),
+    ("40002", "transaction_integrity_constraint_violation"),
+    ("40001", "serialization_failure"),
+    ("40003", "statement_completion_unknown"),
+    ("40P01", "deadlock_detected"),
+    ("42000", "syntax_error_or_access_rule_violation"),
+    ("42601", "syntax_error"),
+    ("42501", "insufficient_privilege"),
+    ("42846", "cannot_coerce"),
+    ("42803", "grouping_error"),
+    ("42P20", "windowing_error"),
+    ("42P19", "invalid_recursion"),
+    ("42830", "invalid_foreign_key"),
+    ("42602", "invalid_name"),
+    ("42622", "name_too_long"),
+    ("42939", "reserved_name"),
+    ("42804", "datatype_mismatch"),
+    ("42P18", "indeterminate_datatype"),
+    ("42P21", "collation_mismatch"),
+    ("42P22", "indeterminate_collation"),
+    ("42809", "wrong_object_type"),
+    ("428C9", "generated_always"),
+    ("42703", "undefined_column"),
+    ("42883", "undefined_function"),
+    ("42P01", "undefined_table"),
+    ("42P02", "undefined_parameter"),
+    ("42704", "undefined_object"),
+    ("42701", "duplicate_column"),
+    ("42P03", "duplicate_cursor"),
+    ("42P04", "duplicate_database"),
+    ("42723", "duplicate_function"),
+    ("42P05", "duplicate_prepared_statement"),
+    ("42P06", "duplicate_schema"),
+    ("42P07", "duplicate_table"),
+    ("42712", "duplicate_alias"),
+    ("42710", "duplicate_object"),
+    ("42702", "ambiguous_column"),
+    ("42725", "ambiguous_function"),
+    ("42P08", "ambiguous_parameter"),
+    ("42P09", "ambiguous_alias"),
+    ("42P10", "invalid_column_reference"),
+    ("42611", "invalid_column_definition"),
+    ("42P11", "invalid_cursor_definition"),
+    ("42P12", "invalid_database_definition"),
+    ("42P13", "invalid_function_definition"),
+    ("42P14", "invalid_prepared_statement_definition"),
+    ("42P15", "invalid_schema_definition"),
+    ("42P16", "invalid_table_definition"),
+    ("42P17", "invalid_object_definition"),
+    ("44000", "with_check_option_violation"),
+    ("53000", "insufficient_resources"),
+    ("53100", "disk_full"),
+    ("53200", "out_of_memory"),
+    ("53300", "too_many_connections"),
+    ("53400", "configuration_limit_exceeded"),
+    ("54000", "program_limit_exceeded"),
+    ("54001", "statement_too_complex"),
+    ("54011", "too_many_columns"),
+    ("54023", "too_many_arguments"),
+    ("55000", "object_not_in_prerequisite_state"),
+    ("55006", "object_in_use"),
+    ("55P02", "cant_change_runtime_param"),
+    ("55P03", "lock_not_available"),
+    ("55P04", "unsafe_new_enum_value_usage"),
+    ("57000", "operator_intervention"),
+    ("57014", "query_canceled"),
+    ("57P01", "admin_shutdown"),
+    ("57P02", "crash_shutdown"),
+    ("57P03", "cannot_connect_now"),
+    ("57P04", "database_dropped"),
+    ("57P05", "idle_session_timeout"),
+    ("58000", "system_error"),
+    ("58030", "io_error"),
+    ("58P01", "undefined_file"),
+    ("58P02", "duplicate_file"),
+    ("72000", "snapshot_too_old"),
+    ("F0000", "config_file_error"),
+    ("F0001", "lock_file_exists"),
+    ("HV000", "fdw_error"),
+    ("HV005", "fdw_column_name_not_found"),
+    ("HV002", "fdw_dynamic_parameter_value_needed"),
+    ("HV010", "fdw_function_sequence_error"),
+    ("HV021", "fdw_inconsistent_descriptor_information"),
+    ("HV024", "fdw_invalid_attribute_value"),
+    ("HV007", "fdw_invalid_column_name"),
+    ("HV008", "fdw_invalid_column_number"),
+    ("HV004", "fdw_invalid_data_type"),
+    ("HV006", "fdw_invalid_data_type_descriptors"),
+    ("HV091", "fdw_invalid_descriptor_field_identifier"),
+    ("HV00B", "fdw_invalid_handle"),
+    ("HV00C", "fdw_invalid_option_index"),
+    ("HV00D", "fdw_invalid_option_name"),
+    ("HV090", "fdw_invalid_string_length_or_buffer_length"),
+    ("HV00A", "fdw_invalid_string_format"),
+    ("HV009", "fdw_invalid_use_of_null_pointer"),
+    ("HV014", "fdw_too_many_handles"),
+    ("HV001", "fdw_out_of_memory"),
+    ("HV00P", "fdw_no_schemas"),
+    ("HV00J", "fdw_option_name_not_found"),
+    ("HV00K", "fdw_reply_handle"),
+    ("HV00Q", "fdw_schema_not_found"),
+    ("HV00R", "fdw_table_not_found"),
+    ("HV00L", "fdw_unable_to_create_execution"),
+    ("HV00M", "fdw_unable_to_create_reply"),
+    ("HV00N", "fdw_unable_to_establish_connection"),
+    ("P0000", "plpgsql_error"),
+    ("P0001", "raise_exception"),
+    ("P0002", "no_data_found"),
+    ("P0003", "too_many_rows"),
+    ("P0004", "assert_failure"),
+    ("XX000", "internal_error"),
+    ("XX001", "data_corrupted"),
+    ("XX002", "index_corrupted"),
+];
+
+/// Guidance for frequently seen error codes
+const CODE_GUIDANCE: &[(&str, Guidance)] = &[
+    ("08006", Guidance {
+        description: "The connection to the server was lost while the statement was running.",
+        causes: &["The server was restarted or crashed", "A network interruption or firewall closed the idle connection"],
+        fixes: &["Run the statement again; the connection is re-established automatically", "Check the server log and network stability if it keeps happening"],
+    }),
+    ("0A000", Guidance {
+        description: "The statement uses a feature PostgreSQL does not support in this form.",
+        causes: &["Cross-database references such as db.schema.table", "Syntax from another database system", "A feature that is not available in this server version"],
+        fixes: &["Rewrite the statement using PostgreSQL syntax", "Use postgres_fdw or dblink to reach another database"],
+    }),
+    ("21000", Guidance {
+        description: "A subquery used as a single value returned more than one row.",
+        causes: &["A scalar subquery (e.g. `= (SELECT ...)`) matches several rows", "An UPDATE ... FROM joins several source rows to one target row"],
+        fixes: &["Use IN or EXISTS instead of =", "Add conditions, LIMIT 1 or an aggregate so the subquery returns one row"],
+    }),
+    ("22001", Guidance {
+        description: "A value is longer than the column's maximum length.",
+        causes: &["Inserting text longer than a varchar(n) or char(n) column allows"],
+        fixes: &["Shorten the value", "Widen the column with ALTER TABLE ... ALTER COLUMN ... TYPE varchar(larger) or text"],
+    }),
+    ("22003", Guidance {
+        description: "A number is outside the range of its data type.",
+        causes: &["A value too large for integer or smallint", "A numeric(p, s) value with more digits than the precision allows", "Arithmetic overflow in an expression"],
+        fixes: &["Use a wider type such as bigint or numeric", "Cast operands to a wider type before the calculation"],
+    }),
+    ("22007", Guidance {
+        description: "A date or time value is not in a recognized format.",
+        causes: &["A date string in an unexpected order or separator", "DateStyle settings that differ from the input format"],
+        fixes: &["Use ISO 8601 format (YYYY-MM-DD, YYYY-MM-DD HH:MI:SS)", "Parse the value explicitly with to_date or to_timestamp"],
+    }),
+    ("22008", Guidance {
+        description: "A date or time value, or the result of date arithmetic, is out of range.",
+        causes: &["An invalid day or month (e.g. 2024-02-30)", "Date arithmetic producing a value outside the supported range"],
+        fixes: &["Check the input values", "Validate dates before inserting them"],
+    }),
+    ("22012", Guidance {
+        description: "An expression divided by zero.",
+        causes: &["A divisor column or expression that is 0 for some rows"],
+        fixes: &["Use NULLIF(divisor, 0) so the result is NULL instead of an error", "Filter out rows where the divisor is 0"],
+    }),
+    ("22P02", Guidance {
+        description: "A text value cannot be converted to the target data type.",
+        causes: &["Non-numeric text compared with or inserted into a numeric column", "An invalid uuid, boolean or enum value", "An empty string used where a number is expected"],
+        fixes: &["Correct the value or the column it is compared with", "Use NULL instead of an empty string", "Cast explicitly and check the data with a query first"],
+    }),
+    ("23502", Guidance {
+        description: "A NOT NULL column was given no value.",
+        causes: &["The column was omitted from INSERT and has no default", "An UPDATE set the column to NULL"],
+        fixes: &["Provide a value for the column", "Add a DEFAULT to the column, or drop the NOT NULL constraint if NULL is valid"],
+    }),
+    ("23503", Guidance {
+        description: "A foreign key constraint was violated.",
+        causes: &["Inserting a row that references a key which does not exist", "Deleting or updating a row that other rows still reference"],
+        fixes: &["Insert the referenced row first", "Delete or update the referencing rows first, or define the foreign key with ON DELETE CASCADE / SET NULL"],
+    }),
+    ("23505", Guidance {
+        description: "A unique constraint or unique index was violated.",
+        causes: &["Inserting a value that already exists", "A sequence behind a serial column is out of sync after manual inserts or a restore"],
+        fixes: &["Use INSERT ... ON CONFLICT DO NOTHING / DO UPDATE", "Reset the sequence with setval to the column's maximum value"],
+    }),
+    ("23514", Guidance {
+        description: "A CHECK constraint was violated.",
+        causes: &["A value outside the range or set of values the constraint allows"],
+        fixes: &["Correct the value; the constraint definition is shown in the table's structure"],
+    }),
+    ("23P01", Guidance {
+        description: "An exclusion constraint was violated.",
+        causes: &["A row overlaps an existing row, e.g. overlapping time ranges for the same resource"],
+        fixes: &["Adjust the value so it no longer conflicts", "Find the conflicting row using the key shown in the error detail"],
+    }),
+    ("25P02", Guidance {
+        description: "An earlier statement in the transaction failed, so the rest of the transaction is ignored.",
+        causes: &["A statement failed inside an open transaction and the transaction was not rolled back"],
+        fixes: &["Run ROLLBACK (or ROLLBACK TO SAVEPOINT) and repeat the transaction", "Use savepoints around statements that may fail"],
+    }),
+    ("28P01", Guidance {
+        description: "Password authentication failed.",
+        causes: &["A wrong user name or password", "The password expired (VALID UNTIL)"],
+        fixes: &["Check the user and password in the configuration", "Ask an administrator to reset the password"],
+    }),
+    ("2BP01", Guidance {
+        description: "An object can't be dropped because other objects depend on it.",
+        causes: &["Dropping a table referenced by views, foreign keys or functions", "Dropping a type or schema that is still in use"],
+        fixes: &["Drop the dependent objects first", "Use DROP ... CASCADE after reviewing what will also be dropped"],
+    }),
+    ("3D000", Guidance {
+        description: "The database does not exist.",
+        causes: &["A misspelled database name", "The database was dropped or never created"],
+        fixes: &["Check the database name (names are case-sensitive when created with quotes)", "Create the database first"],
+    }),
+    ("3F000", Guidance {
+        description: "The schema does not exist.",
+        causes: &["A misspelled schema name", "The schema was not created in this database"],
+        fixes: &["Check the schema name", "Create the schema with CREATE SCHEMA"],
+    }),
+    ("40001", Guidance {
+        description: "The transaction was rolled back because it conflicts with a concurrent transaction.",
+        causes: &["Concurrent transactions at REPEATABLE READ or SERIALIZABLE isolation modifying the same data"],
+        fixes: &["Retry the whole transaction", "Keep transactions short to reduce conflicts"],
+    }),
+    ("40P01", Guidance {
+        description: "A deadlock was detected and this transaction was rolled back to resolve it.",
+        causes: &["Two transactions lock the same rows or tables in opposite order"],
+        fixes: &["Retry the transaction", "Lock rows and tables in a consistent order in all transactions"],
+    }),
+    ("42501", Guidance {
+        description: "The current user lacks the privilege for this operation.",
+        causes: &["Missing SELECT, INSERT, UPDATE or DELETE privileges on a table", "Missing USAGE privilege on the schema", "An operation that needs the table owner or a superuser"],
+        fixes: &["Ask the owner or an administrator to GRANT the privilege", "Connect as a user with the required role"],
+    }),
+    ("42601", Guidance {
+        description: "The SQL statement has a syntax error.",
+        causes: &["A typo or missing comma, parenthesis or keyword", "A reserved word used as an identifier without quotes", "Syntax from another database system"],
+        fixes: &["Check the statement near the reported position", "Quote identifiers that are reserved words with double quotes"],
+    }),
+    ("42702", Guidance {
+        description: "A column name matches columns of more than one table in the query.",
+        causes: &["Joined tables that share a column name, referenced without a table qualifier"],
+        fixes: &["Qualify the column with its table name or alias, e.g. u.id"],
+    }),
+    ("42703", Guidance {
+        description: "The column does not exist.",
+        causes: &["A misspelled column name", "A mixed-case column created with quotes but referenced without them", "A string literal written in double quotes instead of single quotes"],
+        fixes: &["Check the column name in the table structure", "Use double quotes for mixed-case names and single quotes for strings"],
+    }),
+    ("42804", Guidance {
+        description: "An expression has a different data type than expected.",
+        causes: &["Assigning a value of the wrong type to a column", "CASE or UNION branches with incompatible types"],
+        fixes: &["Cast the expression explicitly, e.g. value::integer", "Use USING in ALTER COLUMN ... TYPE to convert existing data"],
+    }),
+    ("42883", Guidance {
+        description: "No function or operator matches the given name and argument types.",
+        causes: &["A misspelled function name", "Arguments of types the function does not accept, e.g. comparing integer with text", "A function from an extension that is not installed"],
+        fixes: &["Add explicit casts to the arguments", "Install the extension that provides the function"],
+    }),
+    ("42P01", Guidance {
+        description: "The table or view does not exist.",
+        causes: &["A misspelled table name", "The table is in a schema that is not on the search_path", "A mixed-case table created with quotes but referenced without them"],
+        fixes: &["Check the table name", "Qualify the table with its schema, e.g. sales.orders", "Use double quotes for mixed-case names"],
+    }),
+    ("42P07", Guidance {
+        description: "A table, index or other relation with this name already exists.",
+        causes: &["Running a CREATE statement twice"],
+        fixes: &["Use CREATE ... IF NOT EXISTS", "Drop or rename the existing object first"],
+    }),
+    ("53300", Guidance {
+        description: "The server has reached its maximum number of connections.",
+        causes: &["Too many open connections from applications", "Idle connections that are never closed"],
+        fixes: &["Close unused connections or terminate idle sessions", "Use a connection pooler, or raise max_connections"],
+    }),
+    ("55P03", Guidance {
+        description: "A lock could not be acquired immediately.",
+        causes: &["NOWAIT or SKIP LOCKED was not possible because another transaction holds the lock", "lock_timeout expired while waiting for a lock"],
+        fixes: &["Find the blocking session in the activity monitor and wait for it or end it", "Retry later, or increase lock_timeout"],
+    }),
+    ("57014", Guidance {
+        description: "The statement was canceled.",
+        causes: &["The user canceled the query", "statement_timeout expired"],
+        fixes: &["Optimize the query or add indexes", "Increase statement_timeout if the query is expected to run long"],
+    }),
+    ("57P01", Guidance {
+        description: "The server is shutting down, or an administrator terminated the session.",
+        causes: &["A server restart or shutdown", "pg_terminate_backend was called on this session"],
+        fixes: &["Reconnect and run the statement again"],
+    }),
+];
+
+/// Guidance for error classes, used when a code has no specific guidance
+const CLASS_GUIDANCE: &[(&str, Guidance)] = &[
+    ("08", Guidance {
+        description: "A problem with the connection between the client and the server.",
+        causes: &["The server is unreachable, restarting or refusing connections", "Network problems"],
+        fixes: &["Check that the server is running and reachable", "Reconnect and run the statement again"],
+    }),
+    ("22", Guidance {
+        description: "A value is invalid for its data type or for the operation applied to it.",
+        causes: &["Input data in an unexpected format", "Values outside the range of the data type"],
+        fixes: &["Check and correct the input values", "Cast values explicitly to the expected type"],
+    }),
+    ("23", Guidance {
+        description: "The change would violate a constraint defined on the table.",
+        causes: &["Duplicate, missing or invalid values for constrained columns"],
+        fixes: &["Review the table's constraints and correct the data"],
+    }),
+    ("25", Guidance {
+        description: "The statement is not allowed in the current transaction state.",
+        causes: &["A previous error aborted the transaction", "A statement that can't run inside a transaction block", "A write in a read-only transaction"],
+        fixes: &["Roll back the transaction and try again", "Run statements such as VACUUM or CREATE DATABASE outside a transaction"],
+    }),
+    ("28", Guidance {
+        description: "The user could not be authenticated or is not allowed to connect.",
+        causes: &["Wrong credentials", "pg_hba.conf does not allow this user, database or host"],
+        fixes: &["Check the user name and password", "Ask an administrator to allow the connection in pg_hba.conf"],
+    }),
+    ("40", Guidance {
+        description: "The transaction was rolled back by the server.",
+        causes: &["Conflicts with concurrent transactions"],
+        fixes: &["Retry the transaction"],
+    }),
+    ("42", Guidance {
+        description: "The statement has a syntax error, refers to an object that doesn't exist, or is not permitted.",
+        causes: &["Typos in keywords or names", "Missing objects or privileges", "Type mismatches"],
+        fixes: &["Check the statement near the reported position", "Check object names and privileges"],
+    }),
+    ("53", Guidance {
+        description: "The server ran out of a resource such as disk space, memory or connections.",
+        causes: &["Heavy load or large operations", "Server limits set too low"],
+        fixes: &["Free the resource or reduce the size of the operation", "Ask an administrator to raise the server limits"],
+    }),
+    ("54", Guidance {
+        description: "A system limit was exceeded.",
+        causes: &["Too many columns, arguments or nesting levels", "A statement that is too complex"],
+        fixes: &["Simplify the statement or split it into smaller parts"],
+    }),
+    ("55", Guidance {
+        description: "The object is not in the state the operation requires.",
+        causes: &["The object is locked or in use by another session", "A required setting or prerequisite is missing"],
+        fixes: &["Wait for other sessions to finish, or check the prerequisites of the operation"],
+    }),
+    ("57", Guidance {
+        description: "The statement or session was stopped by the server or an administrator.",
+        causes: &["Cancellation, timeouts, or a server shutdown"],
+        fixes: &["Reconnect if needed and run the statement again"],
+    }),
+    ("58", Guidance {
+        description: "An error outside PostgreSQL itself, usually in the operating system or storage.",
+        causes: &["File system or disk errors", "Missing files"],
+        fixes: &["Check the server log and the server's storage"],
+    }),
+    ("XX", Guidance {
+        description: "An internal server error, which may indicate data corruption or a server bug.",
+        causes: &["Corrupted data or indexes", "A PostgreSQL bug"],
+        fixes: &["Check the server log", "Rebuild affected indexes with REINDEX, and restore from backup if data is corrupted"],
+    }),
+];
+
+/// Explain a SQLSTATE error code
+///
+/// # Arguments
+/// * `code` - Five-character SQLSTATE code (case-insensitive), e.g. "55P03"
+///
+/// # Returns
+/// * `Result<ErrorExplanation, String>` - Class, condition name, description,
+///   causes and fixes, or error message if the code is malformed or its class is unknown
+pub fn explain_error(code: &str) -> Result<ErrorExplanation, String> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() != 5 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("Invalid SQLSTATE code: {} (expected 5 letters or digits)", code));
+    }
+
+    let class_code = &code[..2];
+    let class_name = ERROR_CLASSES
+        .iter()
+        .find(|(class, _)| *class == class_code)
+        .map(|(_, name)| *name)
+        .ok_or_else(|| format!("Unknown SQLSTATE class: {}", class_code))?;
+    let condition_name = ERROR_CODES
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map(|(_, name)| *name);
+
+    let code_guidance = lookup(CODE_GUIDANCE, &code);
+    let class_guidance = lookup(CLASS_GUIDANCE, class_code);
+
+    let description = match (code_guidance, condition_name, class_guidance) {
+        (Some(guidance), _, _) => guidance.description.to_string(),
+        (None, Some(condition), _) => format!("{}.", humanize_condition(condition)),
+        (None, None, Some(guidance)) => guidance.description.to_string(),
+        (None, None, None) => format!("An error of class {} ({}).", class_code, class_name),
+    };
+    let guidance = code_guidance.or(class_guidance);
+
+    Ok(ErrorExplanation {
+        class_code: class_code.to_string(),
+        class_name: class_name.to_string(),
+        condition_name: condition_name.map(str::to_string),
+        description,
+        causes: guidance.map(|g| to_strings(g.causes)).unwrap_or_default(),
+        fixes: guidance.map(|g| to_strings(g.fixes)).unwrap_or_default(),
+        code,
+    })
+}
+
+/// Find the guidance for a code or class
+fn lookup<'a>(table: &'a [(&str, Guidance)], key: &str) -> Option<&'a Guidance> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, guidance)| guidance)
+}
+
+/// Turn a condition name into a sentence, e.g. `lock_not_available` -> `Lock not available`
+fn humanize_condition(condition: &str) -> String {
+    let text = condition.replace('_', " ");
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => text,
+    }
+}
+
+/// Copy static strings into owned strings
+fn to_strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_lock_not_available() {
+        let explanation = explain_error("55p03").unwrap();
+        assert_eq!(explanation.code, "55P03");
+        assert_eq!(explanation.class_code, "55");
+        assert_eq!(explanation.class_name, "Object Not In Prerequisite State");
+        assert_eq!(explanation.condition_name.as_deref(), Some("lock_not_available"));
+        assert!(explanation.description.contains("lock"));
+        assert!(!explanation.causes.is_empty());
+        assert!(!explanation.fixes.is_empty());
+    }
+
+    #[test]
+    fn test_explain_representative_codes() {
+        let cases = [
+            ("23505", "Integrity Constraint Violation", "unique_violation"),
+            ("42P01", "Syntax Error or Access Rule Violation", "undefined_table"),
+            ("40P01", "Transaction Rollback", "deadlock_detected"),
+            ("22012", "Data Exception", "division_by_zero"),
+            ("08006", "Connection Exception", "connection_failure"),
+        ];
+
+        for (code, class_name, condition) in cases {
+            let explanation = explain_error(code).unwrap();
+            assert_eq!(explanation.class_name, class_name, "{}", code);
+            assert_eq!(explanation.condition_name.as_deref(), Some(condition), "{}", code);
+            assert!(!explanation.fixes.is_empty(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_explain_code_without_specific_guidance() {
+        // Known condition, guidance from its class
+        let explanation = explain_error("22026").unwrap();
+        assert_eq!(explanation.condition_name.as_deref(), Some("string_data_length_mismatch"));
+        assert_eq!(explanation.description, "String data length mismatch.");
+        assert!(!explanation.causes.is_empty());
+
+        // Unknown code in a known class
+        let explanation = explain_error("42ZZZ").unwrap();
+        assert!(explanation.condition_name.is_none());
+        assert_eq!(explanation.class_name, "Syntax Error or Access Rule Violation");
+
+        // Known condition in a class without guidance
+        let explanation = explain_error("P0002").unwrap();
+        assert_eq!(explanation.condition_name.as_deref(), Some("no_data_found"));
+        assert!(explanation.causes.is_empty());
+    }
+
+    #[test]
+    fn test_explain_invalid_codes() {
+        assert!(explain_error("123").is_err());
+        assert!(explain_error("55-03").is_err());
+        assert!(explain_error("QQ000").is_err());
+    }
+
+    #[test]
+    fn test_error_code_table_is_consistent() {
+        for (code, _) in ERROR_CODES {
+            assert_eq!(code.len(), 5);
+            assert!(ERROR_CLASSES.iter().any(|(class, _)| *class == &code[..2]), "{}", code);
+        }
+        for (code, _) in CODE_GUIDANCE {
+            assert!(ERROR_CODES.iter().any(|(known, _)| known == code), "{}", code);
+        }
+    }
+}
@@ -0,0 +1,397 @@
+/**
+ * Explain Service
+ *
+ * This module provides query plan analysis functionality including:
+ * - Running EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) on a statement
+ * - Running EXPLAIN (FORMAT JSON) to get the estimated plan without executing
+ * - Returning the raw JSON plan with its total cost and estimated rows
+ * - Parsing the JSON plan into a recursive node tree with timing and buffer usage
+ *
+ * Note: ANALYZE actually executes the statement. For INSERT/UPDATE/DELETE the
+ * changes are made as usual, so callers must warn users before running it.
+ */
+
+use crate::models::query::{ExplainAnalyzeResult, PlanNode, QueryPlan, QueryResultType};
+use crate::services::query_executor::{determine_query_type, parse_sql_statements};
+use crate::services::sql_lexer::tokenize;
+use serde_json::Value;
+use tokio_postgres::{Client, SimpleQueryMessage};
+
+/// Warning attached to results for statements that may modify data
+const SIDE_EFFECT_WARNING: &str =
+    "EXPLAIN ANALYZE executed the statement, so its data changes have been applied (run it in a transaction and roll back to avoid this)";
+
+/// Run EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) and return the parsed plan tree
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `sql` - A single SQL statement to analyze
+///
+/// # Returns
+/// * `Result<ExplainAnalyzeResult, String>` - Plan tree with timings, or error message
+///
+/// The statement is executed. If it is not a SELECT, the result carries a warning
+/// that its side effects have been applied.
+pub async fn explain_analyze_buffers(client: &Client, sql: &str) -> Result<ExplainAnalyzeResult, String> {
+    let statement = single_statement(sql, "EXPLAIN ANALYZE")?;
+
+    let explain_sql = format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) {}", statement);
+    let plan_json = run_explain(client, &explain_sql).await?;
+
+    let mut result = parse_explain_json(&plan_json)?;
+
+    if determine_query_type(statement) != QueryResultType::Select {
+        result.warning = Some(SIDE_EFFECT_WARNING.to_string());
+    }
+
+    Ok(result)
+}
+
+/// Run EXPLAIN (FORMAT JSON) without executing the statement
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `sql` - A single SQL statement
+///
+/// # Returns
+/// * `Result<PlanNode, String>` - Estimated plan tree (actual timings and rows are 0), or error message
+pub async fn explain_plan(client: &Client, sql: &str) -> Result<PlanNode, String> {
+    let statement = single_statement(sql, "EXPLAIN")?;
+
+    let plan_json = run_explain(client, &format!("EXPLAIN (FORMAT JSON) {}", statement)).await?;
+
+    Ok(parse_explain_json(&plan_json)?.plan)
+}
+
+/// Run EXPLAIN (FORMAT JSON), or EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) when `analyze` is set
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `sql` - A single SELECT, INSERT, UPDATE or DELETE statement
+/// * `analyze` - Whether to execute the statement and include actual timings
+///
+/// # Returns
+/// * `Result<QueryPlan, String>` - The JSON plan with its cost and row estimate, or error message
+pub async fn explain_query(client: &Client, sql: &str, analyze: bool) -> Result<QueryPlan, String> {
+    let statement = single_statement(sql, "EXPLAIN")?;
+    let query_type = check_explainable(statement)?;
+
+    let options = if analyze { "ANALYZE, FORMAT JSON, BUFFERS" } else { "FORMAT JSON" };
+    let plan_json = run_explain(client, &format!("EXPLAIN ({}) {}", options, statement)).await?;
+
+    let mut plan = parse_query_plan(&plan_json)?;
+    if analyze && query_type != QueryResultType::Select {
+        plan.warning = Some(SIDE_EFFECT_WARNING.to_string());
+    }
+
+    Ok(plan)
+}
+
+/// Check that a statement can be explained and return its type
+fn check_explainable(statement: &str) -> Result<QueryResultType, String> {
+    if tokenize(statement).first().is_some_and(|t| t.is_keyword(statement, "EXPLAIN")) {
+        return Err("Statement already starts with EXPLAIN; remove it to view the plan".to_string());
+    }
+
+    match determine_query_type(statement) {
+        query_type @ (QueryResultType::Select
+        | QueryResultType::Insert
+        | QueryResultType::Update
+        | QueryResultType::Delete) => Ok(query_type),
+        _ => Err("Only SELECT, INSERT, UPDATE and DELETE statements can be explained; DDL, COPY and similar statements cannot".to_string()),
+    }
+}
+
+/// Parse the output of EXPLAIN (FORMAT JSON) into a `QueryPlan`, keeping the plan as JSON
+pub fn parse_query_plan(json: &str) -> Result<QueryPlan, String> {
+    let mut value: Value = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse plan JSON: {}", e))?;
+
+    let root = value
+        .get_mut(0)
+        .ok_or_else(|| "Plan JSON is not a non-empty array".to_string())?;
+    let plan = root
+        .get_mut("Plan")
+        .map(Value::take)
+        .ok_or_else(|| "Plan JSON has no \"Plan\" node".to_string())?;
+
+    Ok(QueryPlan {
+        total_cost: f64_field(&plan, "Total Cost"),
+        estimated_rows: f64_field(&plan, "Plan Rows"),
+        planning_time_ms: root.get("Planning Time").and_then(Value::as_f64),
+        execution_time_ms: root.get("Execution Time").and_then(Value::as_f64),
+        plan,
+        warning: None,
+    })
+}
+
+/// Extract the only statement in `sql`
+fn single_statement<'a>(sql: &'a str, command: &str) -> Result<&'a str, String> {
+    let statements = parse_sql_statements(sql.trim());
+    match statements.as_slice() {
+        [statement] => Ok(*statement),
+        [] => Err("SQL statement is empty".to_string()),
+        _ => Err(format!("{} only supports a single statement", command)),
+    }
+}
+
+/// Run an EXPLAIN ... FORMAT JSON statement and return the plan as JSON text
+async fn run_explain(client: &Client, explain_sql: &str) -> Result<String, String> {
+    // simple_query returns the json column as text
+    let messages = client
+        .simple_query(explain_sql)
+        .await
+        .map_err(|e| format!("Failed to run EXPLAIN: {}", e))?;
+
+    messages
+        .iter()
+        .find_map(|message| match message {
+            SimpleQueryMessage::Row(row) => row.get(0).map(|s| s.to_string()),
+            _ => None,
+        })
+        .ok_or_else(|| "EXPLAIN returned no plan".to_string())
+}
+
+/// Parse the output of EXPLAIN (ANALYZE, FORMAT JSON)
+///
+/// # Arguments
+/// * `json` - JSON text of the form `[{"Plan": {...}, "Planning Time": .., "Execution Time": ..}]`
+///
+/// # Returns
+/// * `Result<ExplainAnalyzeResult, String>` - Parsed plan tree or error message
+pub fn parse_explain_json(json: &str) -> Result<ExplainAnalyzeResult, String> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse plan JSON: {}", e))?;
+
+    let root = value
+        .get(0)
+        .ok_or_else(|| "Plan JSON is not a non-empty array".to_string())?;
+    let plan = root
+        .get("Plan")
+        .ok_or_else(|| "Plan JSON has no \"Plan\" node".to_string())?;
+
+    Ok(ExplainAnalyzeResult {
+        plan: parse_plan_node(plan)?,
+        planning_time_ms: f64_field(root, "Planning Time"),
+        execution_time_ms: f64_field(root, "Execution Time"),
+        warning: None,
+    })
+}
+
+/// Parse a single plan node and its children
+fn parse_plan_node(node: &Value) -> Result<PlanNode, String> {
+    let node_type = node
+        .get("Node Type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Plan node has no \"Node Type\"".to_string())?
+        .to_string();
+
+    let children = match node.get("Plans").and_then(Value::as_array) {
+        Some(plans) => plans
+            .iter()
+            .map(parse_plan_node)
+            .collect::<Result<Vec<_>, _>>()?,
+        None => Vec::new(),
+    };
+
+    Ok(PlanNode {
+        node_type,
+        relation_name: str_field(node, "Relation Name"),
+        alias: str_field(node, "Alias"),
+        parent_relationship: str_field(node, "Parent Relationship"),
+        startup_cost: f64_field(node, "Startup Cost"),
+        total_cost: f64_field(node, "Total Cost"),
+        estimated_rows: f64_field(node, "Plan Rows"),
+        actual_rows: f64_field(node, "Actual Rows"),
+        actual_startup_time_ms: f64_field(node, "Actual Startup Time"),
+        actual_total_time_ms: f64_field(node, "Actual Total Time"),
+        loops: u64_field(node, "Actual Loops"),
+        shared_hit_blocks: u64_field(node, "Shared Hit Blocks"),
+        shared_read_blocks: u64_field(node, "Shared Read Blocks"),
+        children,
+    })
+}
+
+fn str_field(node: &Value, key: &str) -> Option<String> {
+    node.get(key).and_then(Value::as_str).map(|s| s.to_string())
+}
+
+fn f64_field(node: &Value, key: &str) -> f64 {
+    node.get(key).and_then(Value::as_f64).unwrap_or(0.0)
+}
+
+fn u64_field(node: &Value, key: &str) -> u64 {
+    node.get(key).and_then(Value::as_u64).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTI_NODE_PLAN: &str = r#"[
+      {
+        "Plan": {
+          "Node Type": "Limit",
+          "Startup Cost": 338.29,
+          "Total Cost": 353.03,
+          "Plan Rows": 5,
+          "Actual Startup Time": 0.072,
+          "Actual Total Time": 0.080,
+          "Actual Rows": 5,
+          "Actual Loops": 1,
+          "Shared Hit Blocks": 3,
+          "Shared Read Blocks": 0,
+          "Plans": [
+            {
+              "Node Type": "Hash Join",
+              "Parent Relationship": "Outer",
+              "Join Type": "Inner",
+              "Startup Cost": 1.45,
+              "Total Cost": 40.12,
+              "Plan Rows": 2260,
+              "Actual Startup Time": 0.031,
+              "Actual Total Time": 0.068,
+              "Actual Rows": 6,
+              "Actual Loops": 1,
+              "Shared Hit Blocks": 3,
+              "Shared Read Blocks": 0,
+              "Plans": [
+                {
+                  "Node Type": "Seq Scan",
+                  "Parent Relationship": "Outer",
+                  "Relation Name": "orders",
+                  "Alias": "o",
+                  "Startup Cost": 0.00,
+                  "Total Cost": 32.60,
+                  "Plan Rows": 2260,
+                  "Actual Startup Time": 0.005,
+                  "Actual Total Time": 0.012,
+                  "Actual Rows": 50,
+                  "Actual Loops": 1,
+                  "Shared Hit Blocks": 1,
+                  "Shared Read Blocks": 7
+                },
+                {
+                  "Node Type": "Hash",
+                  "Parent Relationship": "Inner",
+                  "Startup Cost": 1.20,
+                  "Total Cost": 1.20,
+                  "Plan Rows": 20,
+                  "Actual Startup Time": 0.010,
+                  "Actual Total Time": 0.010,
+                  "Actual Rows": 20,
+                  "Actual Loops": 1,
+                  "Shared Hit Blocks": 2,
+                  "Shared Read Blocks": 0,
+                  "Plans": [
+                    {
+                      "Node Type": "Index Scan",
+                      "Parent Relationship": "Outer",
+                      "Relation Name": "customers",
+                      "Alias": "c",
+                      "Startup Cost": 0.15,
+                      "Total Cost": 1.20,
+                      "Plan Rows": 20,
+                      "Actual Startup Time": 0.002,
+                      "Actual Total Time": 0.004,
+                      "Actual Rows": 1,
+                      "Actual Loops": 20,
+                      "Shared Hit Blocks": 2,
+                      "Shared Read Blocks": 0
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        },
+        "Planning Time": 0.304,
+        "Triggers": [],
+        "Execution Time": 0.158
+      }
+    ]"#;
+
+    #[test]
+    fn test_parse_multi_node_plan() {
+        let result = parse_explain_json(MULTI_NODE_PLAN).unwrap();
+
+        assert_eq!(result.planning_time_ms, 0.304);
+        assert_eq!(result.execution_time_ms, 0.158);
+        assert!(result.warning.is_none());
+
+        let limit = &result.plan;
+        assert_eq!(limit.node_type, "Limit");
+        assert_eq!(limit.estimated_rows, 5.0);
+        assert_eq!(limit.children.len(), 1);
+
+        let join = &limit.children[0];
+        assert_eq!(join.node_type, "Hash Join");
+        assert_eq!(join.parent_relationship.as_deref(), Some("Outer"));
+        assert_eq!(join.children.len(), 2);
+
+        let scan = &join.children[0];
+        assert_eq!(scan.node_type, "Seq Scan");
+        assert_eq!(scan.relation_name.as_deref(), Some("orders"));
+        assert_eq!(scan.alias.as_deref(), Some("o"));
+        assert_eq!(scan.estimated_rows, 2260.0);
+        assert_eq!(scan.actual_rows, 50.0);
+        assert_eq!(scan.actual_total_time_ms, 0.012);
+        assert_eq!(scan.shared_hit_blocks, 1);
+        assert_eq!(scan.shared_read_blocks, 7);
+        assert!(scan.children.is_empty());
+
+        let index_scan = &join.children[1].children[0];
+        assert_eq!(index_scan.node_type, "Index Scan");
+        assert_eq!(index_scan.relation_name.as_deref(), Some("customers"));
+        assert_eq!(index_scan.loops, 20);
+    }
+
+    #[test]
+    fn test_parse_plan_with_missing_optional_fields() {
+        let json = r#"[{"Plan": {"Node Type": "Result", "Plan Rows": 1}}]"#;
+        let result = parse_explain_json(json).unwrap();
+
+        assert_eq!(result.plan.node_type, "Result");
+        assert_eq!(result.plan.actual_rows, 0.0);
+        assert_eq!(result.plan.loops, 0);
+        assert_eq!(result.execution_time_ms, 0.0);
+        assert!(result.plan.relation_name.is_none());
+    }
+
+    #[test]
+    fn test_parse_query_plan() {
+        let plan = parse_query_plan(MULTI_NODE_PLAN).unwrap();
+
+        assert_eq!(plan.total_cost, 353.03);
+        assert_eq!(plan.estimated_rows, 5.0);
+        assert_eq!(plan.planning_time_ms, Some(0.304));
+        assert_eq!(plan.execution_time_ms, Some(0.158));
+        assert_eq!(plan.plan["Node Type"], "Limit");
+        assert_eq!(plan.plan["Plans"][0]["Plans"][0]["Relation Name"], "orders");
+
+        let estimated = parse_query_plan(r#"[{"Plan": {"Node Type": "Result", "Total Cost": 0.01, "Plan Rows": 1}}]"#).unwrap();
+        assert_eq!(estimated.total_cost, 0.01);
+        assert!(estimated.planning_time_ms.is_none());
+        assert!(estimated.execution_time_ms.is_none());
+
+        assert!(parse_query_plan("[]").is_err());
+    }
+
+    #[test]
+    fn test_check_explainable() {
+        assert_eq!(check_explainable("SELECT 1").unwrap(), QueryResultType::Select);
+        assert_eq!(check_explainable("with t as (select 1) select * from t").unwrap(), QueryResultType::Select);
+        assert_eq!(check_explainable("DELETE FROM users WHERE id = 1").unwrap(), QueryResultType::Delete);
+        assert!(check_explainable("EXPLAIN SELECT 1").is_err());
+        assert!(check_explainable("-- plan\nexplain(analyze) SELECT 1").is_err());
+        assert!(check_explainable("CREATE TABLE t (id int)").is_err());
+        assert!(check_explainable("COPY users TO STDOUT").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_plan_json() {
+        assert!(parse_explain_json("not json").is_err());
+        assert!(parse_explain_json("[]").is_err());
+        assert!(parse_explain_json(r#"[{"Plan": {"Plan Rows": 1}}]"#).is_err());
+    }
+}
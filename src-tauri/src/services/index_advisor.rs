@@ -0,0 +1,380 @@
+/**
+ * Index Advisor Service
+ *
+ * This module finds indexes that are candidates for removal:
+ * - Indexes with zero scans since statistics were last reset
+ * - Indexes whose key columns are a leading prefix of another index on the same table
+ *
+ * Unique, primary key, and constraint-backing indexes are never flagged, since
+ * dropping them changes behavior rather than just performance.
+ *
+ * It also produces a usage report listing every index of a schema with its scan
+ * count and size, for reviewing indexes rather than only the drop candidates.
+ */
+
+use crate::models::monitoring::{IndexIssue, IndexUsage, RedundantIndex};
+use crate::services::sql_utils::qualified_name;
+use tokio_postgres::Client;
+
+/// Index definition and usage statistics from the catalog
+#[derive(Debug, Clone)]
+struct IndexInfo {
+    schema: String,
+    table: String,
+    name: String,
+    definition: String,
+    access_method: String,
+    is_unique: bool,
+    is_primary: bool,
+    backs_constraint: bool,
+    /// Key column attnums (0 for expression columns), excluding INCLUDE columns
+    key_columns: Vec<String>,
+    /// Operator class OIDs of the key columns
+    opclasses: Vec<String>,
+    /// Collation OIDs of the key columns
+    collations: Vec<String>,
+    /// Per-column flags (DESC, NULLS FIRST)
+    options: Vec<String>,
+    expressions: Option<String>,
+    predicate: Option<String>,
+    scans: i64,
+    size_bytes: i64,
+}
+
+impl IndexInfo {
+    /// Indexes that enforce uniqueness or back a constraint must not be dropped
+    fn is_protected(&self) -> bool {
+        self.is_unique || self.is_primary || self.backs_constraint
+    }
+}
+
+/// Find unused and redundant indexes in all user schemas
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+///
+/// # Returns
+/// * `Result<Vec<RedundantIndex>, String>` - Candidate indexes with DROP statements, or error message
+pub async fn find_redundant_indexes(client: &Client) -> Result<Vec<RedundantIndex>, String> {
+    let indexes = get_index_info(client, None).await?;
+
+    let stats_reset: Option<String> = client
+        .query_one(
+            "SELECT stats_reset::text FROM pg_stat_database WHERE datname = current_database()",
+            &[],
+        )
+        .await
+        .map_err(|e| format!("Failed to query statistics reset time: {}", e))?
+        .get(0);
+
+    Ok(analyze_indexes(&indexes, stats_reset.as_deref()))
+}
+
+/// Get scan count, size, and duplicate/unused flags for every index in a schema
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+///
+/// # Returns
+/// * `Result<Vec<IndexUsage>, String>` - Indexes ordered by table and name, or error message
+pub async fn get_index_usage(client: &Client, schema: &str) -> Result<Vec<IndexUsage>, String> {
+    let indexes = get_index_info(client, Some(schema)).await?;
+    Ok(summarize_usage(&indexes))
+}
+
+/// Get all valid indexes in user schemas (or only in `schema`) with their usage statistics
+async fn get_index_info(client: &Client, schema: Option<&str>) -> Result<Vec<IndexInfo>, String> {
+    let query = r#"
+        SELECT
+            n.nspname::text,
+            t.relname::text,
+            ic.relname::text,
+            pg_get_indexdef(i.indexrelid),
+            am.amname::text,
+            i.indisunique,
+            i.indisprimary,
+            EXISTS (SELECT 1 FROM pg_constraint con WHERE con.conindid = i.indexrelid),
+            (string_to_array(i.indkey::text, ' '))[1:i.indnkeyatts],
+            string_to_array(i.indclass::text, ' '),
+            string_to_array(i.indcollation::text, ' '),
+            string_to_array(i.indoption::text, ' '),
+            pg_get_expr(i.indexprs, i.indrelid),
+            pg_get_expr(i.indpred, i.indrelid),
+            COALESCE(s.idx_scan, 0),
+            pg_relation_size(i.indexrelid)
+        FROM pg_index i
+        JOIN pg_class ic ON ic.oid = i.indexrelid
+        JOIN pg_class t ON t.oid = i.indrelid
+        JOIN pg_namespace n ON n.oid = t.relnamespace
+        JOIN pg_am am ON am.oid = ic.relam
+        LEFT JOIN pg_stat_user_indexes s ON s.indexrelid = i.indexrelid
+        WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+          AND n.nspname NOT LIKE 'pg_toast%'
+          AND i.indisvalid
+          AND ($1::text IS NULL OR n.nspname = $1)
+        ORDER BY n.nspname, t.relname, ic.relname
+    "#;
+
+    let rows = client
+        .query(query, &[&schema])
+        .await
+        .map_err(|e| format!("Failed to query indexes: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| IndexInfo {
+            schema: row.get(0),
+            table: row.get(1),
+            name: row.get(2),
+            definition: row.get(3),
+            access_method: row.get(4),
+            is_unique: row.get(5),
+            is_primary: row.get(6),
+            backs_constraint: row.get(7),
+            key_columns: row.get(8),
+            opclasses: row.get(9),
+            collations: row.get(10),
+            options: row.get(11),
+            expressions: row.get(12),
+            predicate: row.get(13),
+            scans: row.get(14),
+            size_bytes: row.get(15),
+        })
+        .collect())
+}
+
+/// Flag redundant and unused indexes
+///
+/// An index is redundant when another index on the same table has the same
+/// key columns (with matching operator classes, collations, and ordering) as a
+/// leading prefix. Of two identical indexes only one is flagged, preferring to
+/// keep a protected index, then the more-scanned one. Unused indexes that are
+/// not redundant are reported separately.
+fn analyze_indexes(indexes: &[IndexInfo], stats_reset: Option<&str>) -> Vec<RedundantIndex> {
+    let since = match stats_reset {
+        Some(reset) => format!("since statistics were reset at {}", reset),
+        None => "since statistics collection began".to_string(),
+    };
+
+    let mut candidates = Vec::new();
+
+    for index in indexes.iter().filter(|index| !index.is_protected()) {
+        let covering = indexes
+            .iter()
+            .filter(|other| !std::ptr::eq(*other, index))
+            .find(|other| is_covered_by(index, other));
+
+        let drop_statement = format!("DROP INDEX {};", qualified_name(&index.schema, &index.name));
+
+        let candidate = match covering {
+            Some(other) => RedundantIndex {
+                schema: index.schema.clone(),
+                table: index.table.clone(),
+                index_name: index.name.clone(),
+                definition: index.definition.clone(),
+                issue: IndexIssue::Redundant,
+                covered_by: Some(other.name.clone()),
+                scans: index.scans,
+                size_bytes: index.size_bytes,
+                rationale: if index.key_columns.len() == other.key_columns.len() {
+                    format!("Has the same key columns as index {}; it is a duplicate", other.name)
+                } else {
+                    format!("Key columns are a prefix of index {}, which queries can use instead", other.name)
+                },
+                drop_statement,
+            },
+            None if index.scans == 0 => RedundantIndex {
+                schema: index.schema.clone(),
+                table: index.table.clone(),
+                index_name: index.name.clone(),
+                definition: index.definition.clone(),
+                issue: IndexIssue::Unused,
+                covered_by: None,
+                scans: 0,
+                size_bytes: index.size_bytes,
+                rationale: format!("Not scanned {}", since),
+                drop_statement,
+            },
+            None => continue,
+        };
+
+        candidates.push(candidate);
+    }
+
+    candidates
+}
+
+/// Build the usage report; duplicates are detected the same way as in `analyze_indexes`
+fn summarize_usage(indexes: &[IndexInfo]) -> Vec<IndexUsage> {
+    indexes
+        .iter()
+        .map(|index| {
+            let duplicate_of = if index.is_protected() {
+                None
+            } else {
+                indexes
+                    .iter()
+                    .filter(|other| !std::ptr::eq(*other, index))
+                    .find(|other| is_covered_by(index, other))
+                    .map(|other| other.name.clone())
+            };
+
+            IndexUsage {
+                schema: index.schema.clone(),
+                table: index.table.clone(),
+                index_name: index.name.clone(),
+                definition: index.definition.clone(),
+                scans: index.scans,
+                size_bytes: index.size_bytes,
+                is_unique: index.is_unique,
+                is_primary: index.is_primary,
+                possibly_unused: index.scans == 0,
+                duplicate_of,
+            }
+        })
+        .collect()
+}
+
+/// Whether `index` is made unnecessary by `other`
+fn is_covered_by(index: &IndexInfo, other: &IndexInfo) -> bool {
+    if index.schema != other.schema
+        || index.table != other.table
+        || index.access_method != other.access_method
+        || index.expressions != other.expressions
+        || index.predicate != other.predicate
+    {
+        return false;
+    }
+
+    let len = index.key_columns.len();
+    if len == 0 || len > other.key_columns.len() {
+        return false;
+    }
+
+    // Only B-tree indexes can serve lookups on a leading prefix of their columns
+    if len < other.key_columns.len() && index.access_method != "btree" {
+        return false;
+    }
+
+    let prefix_matches = index.key_columns[..] == other.key_columns[..len]
+        && index.opclasses.get(..len) == other.opclasses.get(..len)
+        && index.collations.get(..len) == other.collations.get(..len)
+        && index.options.get(..len) == other.options.get(..len);
+    if !prefix_matches {
+        return false;
+    }
+
+    if len < other.key_columns.len() {
+        return true;
+    }
+
+    // Identical indexes: keep exactly one, preferring protected, then more-used, then by name
+    other.is_protected()
+        || other.scans > index.scans
+        || (other.scans == index.scans && other.name < index.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(name: &str, columns: &[&str]) -> IndexInfo {
+        IndexInfo {
+            schema: "public".to_string(),
+            table: "orders".to_string(),
+            name: name.to_string(),
+            definition: format!("CREATE INDEX {} ON public.orders", name),
+            access_method: "btree".to_string(),
+            is_unique: false,
+            is_primary: false,
+            backs_constraint: false,
+            key_columns: columns.iter().map(|c| c.to_string()).collect(),
+            opclasses: columns.iter().map(|_| "1978".to_string()).collect(),
+            collations: columns.iter().map(|_| "0".to_string()).collect(),
+            options: columns.iter().map(|_| "0".to_string()).collect(),
+            expressions: None,
+            predicate: None,
+            scans: 10,
+            size_bytes: 8192,
+        }
+    }
+
+    #[test]
+    fn test_prefix_index_is_redundant() {
+        let indexes = vec![index("orders_a", &["2"]), index("orders_a_b", &["2", "3"])];
+        let result = analyze_indexes(&indexes, None);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].index_name, "orders_a");
+        assert_eq!(result[0].issue, IndexIssue::Redundant);
+        assert_eq!(result[0].covered_by.as_deref(), Some("orders_a_b"));
+        assert_eq!(result[0].drop_statement, "DROP INDEX \"public\".\"orders_a\";");
+    }
+
+    #[test]
+    fn test_identical_indexes_flag_only_one() {
+        let mut busy = index("orders_b", &["3"]);
+        busy.scans = 100;
+        let indexes = vec![index("orders_a", &["3"]), busy];
+        let result = analyze_indexes(&indexes, None);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].index_name, "orders_a");
+        assert_eq!(result[0].covered_by.as_deref(), Some("orders_b"));
+    }
+
+    #[test]
+    fn test_protected_indexes_are_never_flagged() {
+        let mut pkey = index("orders_pkey", &["1"]);
+        pkey.is_primary = true;
+        pkey.is_unique = true;
+        pkey.scans = 0;
+        let mut unique = index("orders_a_key", &["2"]);
+        unique.is_unique = true;
+        let indexes = vec![pkey, unique, index("orders_a_b", &["2", "3"])];
+
+        assert!(analyze_indexes(&indexes, None).is_empty());
+    }
+
+    #[test]
+    fn test_non_matching_indexes_are_kept() {
+        let mut desc = index("orders_a_desc", &["2"]);
+        desc.options = vec!["3".to_string()];
+        let mut partial = index("orders_a_partial", &["2"]);
+        partial.predicate = Some("(a > 0)".to_string());
+        let mut hash = index("orders_hash_a", &["2"]);
+        hash.access_method = "hash".to_string();
+        let indexes = vec![desc, partial, hash, index("orders_a_b", &["2", "3"])];
+
+        assert!(analyze_indexes(&indexes, None).is_empty());
+    }
+
+    #[test]
+    fn test_unused_index() {
+        let mut unused = index("orders_c", &["4"]);
+        unused.scans = 0;
+        let result = analyze_indexes(&[unused], Some("2024-01-01 00:00:00+00"));
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].issue, IndexIssue::Unused);
+        assert!(result[0].rationale.contains("2024-01-01"));
+    }
+
+    #[test]
+    fn test_usage_report_lists_every_index() {
+        let mut pkey = index("orders_pkey", &["1"]);
+        pkey.is_primary = true;
+        pkey.is_unique = true;
+        pkey.scans = 0;
+        let indexes = vec![pkey, index("orders_a", &["2"]), index("orders_a_b", &["2", "3"])];
+        let report = summarize_usage(&indexes);
+
+        assert_eq!(report.len(), 3);
+        assert!(report[0].possibly_unused);
+        assert!(report[0].duplicate_of.is_none());
+        assert_eq!(report[1].duplicate_of.as_deref(), Some("orders_a_b"));
+        assert!(!report[1].possibly_unused);
+        assert!(report[2].duplicate_of.is_none());
+    }
+}
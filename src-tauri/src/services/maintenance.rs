@@ -0,0 +1,355 @@
+/**
+ * Maintenance Service
+ *
+ * This module runs index maintenance statements:
+ * - `REINDEX TABLE`, `REINDEX INDEX`, and `REINDEX DATABASE`
+ * - Optional `CONCURRENTLY` (PostgreSQL 12+), which avoids blocking writes
+ *
+ * - `VACUUM [FULL] [ANALYZE]` and `ANALYZE` of a single table
+ *
+ * REINDEX and VACUUM cannot run inside a transaction block (and `CONCURRENTLY`
+ * cannot be rolled back), so callers should pass a dedicated autocommit connection,
+ * such as one from `connection_router::connect_maintenance`, together with its notice
+ * receiver. `VERBOSE` is always requested for REINDEX so the server reports each
+ * rebuilt index.
+ *
+ * VACUUM and ANALYZE do not fail when the user lacks the privilege; the server only
+ * warns that it is skipping the table. Such warnings are turned into errors here.
+ */
+
+use crate::models::monitoring::{MaintenanceResult, ReindexResult, ReindexTarget};
+use crate::services::sql_utils::{qualified_name, quote_identifier};
+use std::time::Instant;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio_postgres::error::{DbError, SqlState};
+use tokio_postgres::Client;
+
+/// First server version (`server_version_num`) that supports `REINDEX ... CONCURRENTLY`
+pub const MIN_CONCURRENT_REINDEX_VERSION: i32 = 120000;
+
+/// Rebuild the indexes of a table, a single index, or the current database
+///
+/// # Arguments
+/// * `client` - Dedicated autocommit PostgreSQL client connection
+/// * `notices` - Receiver for notices sent on `client`'s connection
+/// * `target` - What to reindex
+/// * `concurrently` - Rebuild without blocking writes (PostgreSQL 12+)
+/// * `on_notice` - Called with each notice (`SEVERITY: message`) as it arrives
+///
+/// # Returns
+/// * `Result<ReindexResult, String>` - Executed statement, elapsed time, and notices, or error message
+pub async fn reindex<F>(
+    client: &Client,
+    notices: &mut UnboundedReceiver<DbError>,
+    target: &ReindexTarget,
+    concurrently: bool,
+    mut on_notice: F,
+) -> Result<ReindexResult, String>
+where
+    F: FnMut(&str),
+{
+    if concurrently {
+        let version = get_server_version(client).await?;
+        if version < MIN_CONCURRENT_REINDEX_VERSION {
+            return Err(format!(
+                "REINDEX CONCURRENTLY requires PostgreSQL 12 or later (server version {})",
+                version
+            ));
+        }
+    }
+
+    let object = resolve_target(client, target).await?;
+    let statement = build_reindex_statement(target, &object, concurrently);
+    log::info!("执行: {}", statement);
+
+    let mut received = Vec::new();
+    let (result, duration_ms) = execute_with_notices(client, notices, &statement, |notice| {
+        let text = format_notice(notice);
+        on_notice(&text);
+        received.push(text);
+    })
+    .await;
+
+    result.map_err(|e| statement_error("REINDEX", &e))?;
+
+    Ok(ReindexResult {
+        statement,
+        concurrently,
+        duration_ms,
+        notices: received,
+    })
+}
+
+/// Vacuum a single table, optionally rewriting it (`FULL`) and updating its statistics (`ANALYZE`)
+///
+/// # Arguments
+/// * `client` - Dedicated autocommit PostgreSQL client connection
+/// * `notices` - Receiver for notices sent on `client`'s connection
+/// * `schema` - Schema name
+/// * `table` - Table name
+/// * `full` - Rewrite the table to return space to the operating system (takes an exclusive lock)
+/// * `analyze` - Also update planner statistics
+///
+/// # Returns
+/// * `Result<MaintenanceResult, String>` - Executed statement, elapsed time, and notices, or error message
+pub async fn vacuum_table(
+    client: &Client,
+    notices: &mut UnboundedReceiver<DbError>,
+    schema: &str,
+    table: &str,
+    full: bool,
+    analyze: bool,
+) -> Result<MaintenanceResult, String> {
+    let object = resolve_relation(client, schema, table, &["r", "m", "p"], "Table").await?;
+    let statement = build_vacuum_statement(&object, full, analyze);
+    run_table_maintenance(client, notices, "VACUUM", statement).await
+}
+
+/// Update the planner statistics of a single table
+///
+/// # Arguments
+/// * `client` - Dedicated autocommit PostgreSQL client connection
+/// * `notices` - Receiver for notices sent on `client`'s connection
+/// * `schema` - Schema name
+/// * `table` - Table name
+///
+/// # Returns
+/// * `Result<MaintenanceResult, String>` - Executed statement, elapsed time, and notices, or error message
+pub async fn analyze_table(
+    client: &Client,
+    notices: &mut UnboundedReceiver<DbError>,
+    schema: &str,
+    table: &str,
+) -> Result<MaintenanceResult, String> {
+    let object = resolve_relation(client, schema, table, &["r", "m", "p", "f"], "Table").await?;
+    let statement = format!("ANALYZE {}", object);
+    run_table_maintenance(client, notices, "ANALYZE", statement).await
+}
+
+/// Run a VACUUM or ANALYZE statement, reporting a skipped table as a permission error
+async fn run_table_maintenance(
+    client: &Client,
+    notices: &mut UnboundedReceiver<DbError>,
+    command: &str,
+    statement: String,
+) -> Result<MaintenanceResult, String> {
+    log::info!("执行: {}", statement);
+
+    let mut received = Vec::new();
+    let mut skipped = None;
+    let (result, duration_ms) = execute_with_notices(client, notices, &statement, |notice| {
+        if is_permission_skip(notice) {
+            skipped = Some(notice.message().to_string());
+        }
+        received.push(format_notice(notice));
+    })
+    .await;
+
+    result.map_err(|e| statement_error(command, &e))?;
+    if let Some(message) = skipped {
+        return Err(format!("Permission denied: {}", message));
+    }
+
+    Ok(MaintenanceResult {
+        statement,
+        duration_ms,
+        notices: received,
+    })
+}
+
+/// Execute a statement while passing every notice sent on the connection to `on_notice`
+///
+/// Returns the statement's result and its elapsed time in milliseconds.
+async fn execute_with_notices<F>(
+    client: &Client,
+    notices: &mut UnboundedReceiver<DbError>,
+    statement: &str,
+    mut on_notice: F,
+) -> (Result<(), tokio_postgres::Error>, u64)
+where
+    F: FnMut(&DbError),
+{
+    // Discard anything left over from earlier statements on this connection
+    while notices.try_recv().is_ok() {}
+
+    let start = Instant::now();
+    let execution = client.batch_execute(statement);
+    tokio::pin!(execution);
+
+    let result = loop {
+        tokio::select! {
+            result = &mut execution => break result,
+            Some(notice) = notices.recv() => on_notice(&notice),
+        }
+    };
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    // Notices sent just before the command completed may still be queued
+    while let Ok(notice) = notices.try_recv() {
+        on_notice(&notice);
+    }
+
+    (result, duration_ms)
+}
+
+/// Format a notice as `SEVERITY: message`
+fn format_notice(notice: &DbError) -> String {
+    format!("{}: {}", notice.severity(), notice.message())
+}
+
+/// Describe a failed maintenance statement, calling out missing privileges
+fn statement_error(command: &str, error: &tokio_postgres::Error) -> String {
+    match error.as_db_error() {
+        Some(db_error) if db_error.code() == &SqlState::INSUFFICIENT_PRIVILEGE => {
+            format!("{} failed: permission denied: {}", command, db_error.message())
+        }
+        Some(db_error) => format!("{} failed: {}", command, db_error.message()),
+        None => format!("{} failed: {}", command, error),
+    }
+}
+
+/// Whether a notice is the warning VACUUM/ANALYZE send instead of an error when the
+/// current user may not maintain the table
+///
+/// PostgreSQL 15 and earlier: `skipping "t" --- only table or database owner can vacuum it`;
+/// PostgreSQL 16 and later: `permission denied to vacuum "t", skipping it`.
+fn is_permission_skip(notice: &DbError) -> bool {
+    is_permission_skip_message(notice.severity(), notice.message())
+}
+
+fn is_permission_skip_message(severity: &str, message: &str) -> bool {
+    severity == "WARNING"
+        && (message.starts_with("permission denied to")
+            || (message.starts_with("skipping") && message.contains("only table or database owner")))
+}
+
+/// Get `server_version_num` (e.g. 150004)
+async fn get_server_version(client: &Client) -> Result<i32, String> {
+    let version: String = client
+        .query_one("SELECT current_setting('server_version_num')", &[])
+        .await
+        .map_err(|e| format!("Failed to query server version: {}", e))?
+        .get(0);
+
+    version
+        .parse()
+        .map_err(|_| format!("Unexpected server version: {}", version))
+}
+
+/// Check that the target exists and return its quoted name
+async fn resolve_target(client: &Client, target: &ReindexTarget) -> Result<String, String> {
+    match target {
+        ReindexTarget::Table { schema, name } => {
+            resolve_relation(client, schema, name, &["r", "m", "p"], "Table").await
+        }
+        ReindexTarget::Index { schema, name } => {
+            resolve_relation(client, schema, name, &["i", "I"], "Index").await
+        }
+        ReindexTarget::Database => {
+            let database: String = client
+                .query_one("SELECT current_database()::text", &[])
+                .await
+                .map_err(|e| format!("Failed to query current database: {}", e))?
+                .get(0);
+            Ok(quote_identifier(&database))
+        }
+    }
+}
+
+/// Check that a relation of one of the allowed kinds exists and return its quoted name
+async fn resolve_relation(
+    client: &Client,
+    schema: &str,
+    name: &str,
+    allowed_kinds: &[&str],
+    description: &str,
+) -> Result<String, String> {
+    let row = client
+        .query_opt(
+            "SELECT c.relkind::text
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1 AND c.relname = $2",
+            &[&schema, &name],
+        )
+        .await
+        .map_err(|e| format!("Failed to look up {}.{}: {}", schema, name, e))?;
+
+    match row {
+        Some(row) if allowed_kinds.contains(&row.get::<_, String>(0).as_str()) => {
+            Ok(qualified_name(schema, name))
+        }
+        Some(_) => Err(format!("{}.{} is not a {}", schema, name, description.to_lowercase())),
+        None => Err(format!("{} {}.{} does not exist", description, schema, name)),
+    }
+}
+
+/// Build the REINDEX statement for an already-quoted object name
+fn build_reindex_statement(target: &ReindexTarget, object: &str, concurrently: bool) -> String {
+    let kind = match target {
+        ReindexTarget::Table { .. } => "TABLE",
+        ReindexTarget::Index { .. } => "INDEX",
+        ReindexTarget::Database => "DATABASE",
+    };
+
+    format!(
+        "REINDEX (VERBOSE) {}{} {}",
+        kind,
+        if concurrently { " CONCURRENTLY" } else { "" },
+        object
+    )
+}
+
+/// Build the VACUUM statement for an already-quoted table name
+fn build_vacuum_statement(object: &str, full: bool, analyze: bool) -> String {
+    format!(
+        "VACUUM{}{} {}",
+        if full { " FULL" } else { "" },
+        if analyze { " ANALYZE" } else { "" },
+        object
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_reindex_statement() {
+        let table = ReindexTarget::Table {
+            schema: "public".to_string(),
+            name: "orders".to_string(),
+        };
+        assert_eq!(
+            build_reindex_statement(&table, "\"public\".\"orders\"", true),
+            "REINDEX (VERBOSE) TABLE CONCURRENTLY \"public\".\"orders\""
+        );
+        assert_eq!(
+            build_reindex_statement(&ReindexTarget::Database, "\"personnel_db\"", false),
+            "REINDEX (VERBOSE) DATABASE \"personnel_db\""
+        );
+    }
+
+    #[test]
+    fn test_build_vacuum_statement() {
+        assert_eq!(build_vacuum_statement("\"public\".\"orders\"", false, false), "VACUUM \"public\".\"orders\"");
+        assert_eq!(
+            build_vacuum_statement("\"public\".\"orders\"", true, true),
+            "VACUUM FULL ANALYZE \"public\".\"orders\""
+        );
+    }
+
+    #[test]
+    fn test_is_permission_skip_message() {
+        assert!(is_permission_skip_message(
+            "WARNING",
+            "skipping \"orders\" --- only table or database owner can vacuum it"
+        ));
+        assert!(is_permission_skip_message("WARNING", "permission denied to vacuum \"orders\", skipping it"));
+        assert!(!is_permission_skip_message("INFO", "vacuuming \"public.orders\""));
+        assert!(!is_permission_skip_message(
+            "WARNING",
+            "skipping vacuum of \"orders\" --- lock not available"
+        ));
+    }
+}
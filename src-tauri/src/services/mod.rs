@@ -9,3 +9,36 @@ pub mod schema_service;
 pub mod ddl_generator;
 pub mod transaction_manager;
 pub mod sql_logger;
+pub mod sqlite_log;
+pub mod sql_utils;
+pub mod csv_import;
+pub mod data_exporter;
+pub mod row_streamer;
+pub mod schema_diff;
+pub mod activity_monitor;
+pub mod explain;
+pub mod connection_router;
+pub mod record_writer;
+pub mod index_advisor;
+pub mod tls;
+pub mod dml_preview;
+pub mod bloat;
+pub mod maintenance;
+pub mod column_stats;
+pub mod table_stats;
+pub mod bulk_load;
+pub mod plan_baseline;
+pub mod notification_listener;
+pub mod table_data;
+pub mod query_cancel;
+pub mod editor_transaction;
+pub mod table_script;
+pub mod startup_check;
+pub mod sql_lexer;
+pub mod query_templates;
+pub mod error_codes;
+pub mod schema_export;
+pub mod object_search;
+pub mod query_benchmark;
+pub mod pgpass;
+pub mod pg_tools;
@@ -0,0 +1,186 @@
+/**
+ * Notification Listener Service
+ *
+ * 此模块提供 LISTEN/NOTIFY 支持：
+ * - 在专用连接上同时监听多个频道，重复订阅的频道只执行一次 LISTEN
+ * - 将收到的异步通知转换为事件交给回调（例如以事件形式发送给前端）
+ * - 负载是合法 JSON 时同时提供解析后的值
+ *
+ * 频道名按原样加引号，区分大小写：`NOTIFY orders` 对应频道 `orders`，
+ * `pg_notify('Orders', ...)` 对应频道 `Orders`。
+ */
+
+use crate::services::sql_utils::quote_identifier;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio_postgres::{Client, Notification};
+
+/// 频道名的最大长度（字节），与 PostgreSQL 标识符长度一致
+const MAX_CHANNEL_LENGTH: usize = 63;
+
+/// 收到的一条异步通知
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NotificationEvent {
+    /// 频道名
+    pub channel: String,
+    /// 原始负载
+    pub payload: String,
+    /// 负载是合法 JSON 时解析后的值
+    pub parsed: Option<serde_json::Value>,
+    /// 发送通知的后端进程 ID
+    pub process_id: i32,
+}
+
+impl NotificationEvent {
+    /// 从异步通知创建事件
+    pub fn new(channel: &str, payload: &str, process_id: i32) -> Self {
+        Self {
+            channel: channel.to_string(),
+            payload: payload.to_string(),
+            parsed: serde_json::from_str(payload).ok(),
+            process_id,
+        }
+    }
+}
+
+impl From<&Notification> for NotificationEvent {
+    fn from(notification: &Notification) -> Self {
+        Self::new(
+            notification.channel(),
+            notification.payload(),
+            notification.process_id(),
+        )
+    }
+}
+
+/// 一个数据库上的 LISTEN 连接及其订阅的频道
+pub struct NotificationListener {
+    client: Client,
+    channels: BTreeSet<String>,
+}
+
+impl NotificationListener {
+    /// 使用专用连接创建监听器，并在后台将收到的通知交给 `on_notification`
+    ///
+    /// 后台任务在连接关闭时结束。
+    ///
+    /// # Arguments
+    /// * `client` - 专用连接（不要与其他查询共享）
+    /// * `notifications` - 该连接的异步通知接收端
+    /// * `on_notification` - 处理每条通知的回调
+    pub fn new<F>(client: Client, mut notifications: UnboundedReceiver<Notification>, on_notification: F) -> Self
+    where
+        F: Fn(NotificationEvent) + Send + 'static,
+    {
+        tokio::spawn(async move {
+            while let Some(notification) = notifications.recv().await {
+                on_notification(NotificationEvent::from(&notification));
+            }
+            log::info!("监听连接已关闭");
+        });
+
+        Self {
+            client,
+            channels: BTreeSet::new(),
+        }
+    }
+
+    /// 订阅频道，已订阅的频道会被跳过
+    ///
+    /// # Arguments
+    /// * `channels` - 要订阅的频道名
+    ///
+    /// # Returns
+    /// * `Result<Vec<String>, String>` - 本次新订阅的频道或错误信息
+    pub async fn listen(&mut self, channels: &[String]) -> Result<Vec<String>, String> {
+        for channel in channels {
+            validate_channel(channel)?;
+        }
+
+        let mut added = Vec::new();
+        for channel in channels {
+            if self.channels.contains(channel) {
+                continue;
+            }
+
+            self.client
+                .batch_execute(&format!("LISTEN {}", quote_identifier(channel)))
+                .await
+                .map_err(|e| format!("订阅频道 {} 失败: {}", channel, e))?;
+            self.channels.insert(channel.clone());
+            added.push(channel.clone());
+        }
+
+        Ok(added)
+    }
+
+    /// 取消订阅频道，未订阅的频道会被跳过
+    ///
+    /// # Arguments
+    /// * `channels` - 要取消订阅的频道名
+    ///
+    /// # Returns
+    /// * `Result<Vec<String>, String>` - 本次取消订阅的频道或错误信息
+    pub async fn unlisten(&mut self, channels: &[String]) -> Result<Vec<String>, String> {
+        let mut removed = Vec::new();
+        for channel in channels {
+            if !self.channels.contains(channel) {
+                continue;
+            }
+
+            self.client
+                .batch_execute(&format!("UNLISTEN {}", quote_identifier(channel)))
+                .await
+                .map_err(|e| format!("取消订阅频道 {} 失败: {}", channel, e))?;
+            self.channels.remove(channel);
+            removed.push(channel.clone());
+        }
+
+        Ok(removed)
+    }
+
+    /// 当前订阅的频道（按名称排序）
+    pub fn channels(&self) -> Vec<String> {
+        self.channels.iter().cloned().collect()
+    }
+
+    /// 监听连接是否已断开
+    pub fn is_closed(&self) -> bool {
+        self.client.is_closed()
+    }
+}
+
+/// 检查频道名是否可用
+fn validate_channel(channel: &str) -> Result<(), String> {
+    if channel.is_empty() {
+        return Err("频道名不能为空".to_string());
+    }
+    if channel.len() > MAX_CHANNEL_LENGTH {
+        return Err(format!("频道名 {} 过长（最多 {} 字节）", channel, MAX_CHANNEL_LENGTH));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_event_parses_json_payload() {
+        let event = NotificationEvent::new("orders", r#"{"id": 7, "status": "paid"}"#, 42);
+        assert_eq!(event.parsed, Some(json!({"id": 7, "status": "paid"})));
+
+        let event = NotificationEvent::new("orders", "order 7 paid", 42);
+        assert_eq!(event.parsed, None);
+        assert_eq!(event.payload, "order 7 paid");
+    }
+
+    #[test]
+    fn test_validate_channel() {
+        assert!(validate_channel("orders").is_ok());
+        assert!(validate_channel("").is_err());
+        assert!(validate_channel(&"c".repeat(64)).is_err());
+    }
+}
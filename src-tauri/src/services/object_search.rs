@@ -0,0 +1,181 @@
+/**
+ * Object Search Service
+ *
+ * This module searches the database explorer for objects by name:
+ * - Tables, views, functions and columns, optionally restricted to some kinds
+ * - Case-insensitive substring (`ILIKE`) or regular expression (`~*`) matching,
+ *   evaluated by the server against the system catalogs
+ * - Optionally also matches object comments
+ * - Column matches report the table or view that owns the column
+ * - Results are paginated, with the page size capped at `MAX_PAGE_SIZE`
+ */
+
+use crate::models::schema::{ObjectSearchMatch, ObjectSearchPage, SearchObjectKind};
+use tokio_postgres::Client;
+
+/// Largest page size a search may request
+pub const MAX_PAGE_SIZE: u32 = 200;
+
+/// Page size when none is given
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Search options
+#[derive(Debug, Clone)]
+pub struct ObjectSearch {
+    /// Substring, or regular expression when `regex` is set
+    pub pattern: String,
+    /// Treat `pattern` as a POSIX regular expression
+    pub regex: bool,
+    /// Object kinds to search; empty means all kinds
+    pub kinds: Vec<SearchObjectKind>,
+    /// Also match object comments
+    pub include_comments: bool,
+    /// Page number (1-based)
+    pub page: u32,
+    /// Matches per page
+    pub page_size: u32,
+}
+
+/// Build an `ILIKE` pattern matching `text` anywhere, with `%`, `_` and `\` taken literally
+fn contains_pattern(text: &str) -> String {
+    let mut pattern = String::with_capacity(text.len() + 2);
+    pattern.push('%');
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Clamp a requested page and page size, returning (page, page_size)
+fn normalize_page(page: u32, page_size: u32) -> (u32, u32) {
+    let page_size = if page_size == 0 { DEFAULT_PAGE_SIZE } else { page_size.min(MAX_PAGE_SIZE) };
+    (page.max(1), page_size)
+}
+
+/// Parse an object kind name as returned by the search query
+fn parse_kind(kind: &str) -> SearchObjectKind {
+    match kind {
+        "view" => SearchObjectKind::View,
+        "function" => SearchObjectKind::Function,
+        "column" => SearchObjectKind::Column,
+        _ => SearchObjectKind::Table,
+    }
+}
+
+/// Search tables, views, functions and columns by name
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `search` - Pattern, kinds and page to return
+///
+/// # Returns
+/// * `Result<ObjectSearchPage, String>` - One page of matches and the total count, or error message
+pub async fn search_objects(client: &Client, search: &ObjectSearch) -> Result<ObjectSearchPage, String> {
+    if search.pattern.trim().is_empty() {
+        return Err("搜索内容不能为空".to_string());
+    }
+
+    let (page, page_size) = normalize_page(search.page, search.page_size);
+    let kinds: Vec<&str> = if search.kinds.is_empty() {
+        SearchObjectKind::ALL.iter().map(|kind| kind.as_str()).collect()
+    } else {
+        search.kinds.iter().map(|kind| kind.as_str()).collect()
+    };
+    let (operator, pattern) = if search.regex {
+        ("~*", search.pattern.clone())
+    } else {
+        ("ILIKE", contains_pattern(&search.pattern))
+    };
+
+    let query = format!(
+        r#"
+        WITH objects AS (
+            SELECT 'table' AS kind, n.nspname::text AS schema, c.relname::text AS name,
+                   NULL::text AS table_name, obj_description(c.oid, 'pg_class') AS comment
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relkind IN ('r', 'p', 'f') AND 'table' = ANY($2)
+            UNION ALL
+            SELECT 'view', n.nspname::text, c.relname::text, NULL, obj_description(c.oid, 'pg_class')
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relkind IN ('v', 'm') AND 'view' = ANY($2)
+            UNION ALL
+            SELECT 'function', n.nspname::text, p.proname::text, NULL, obj_description(p.oid, 'pg_proc')
+            FROM pg_proc p
+            JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE p.prokind IN ('f', 'p') AND 'function' = ANY($2)
+            UNION ALL
+            SELECT 'column', n.nspname::text, a.attname::text, c.relname::text, col_description(c.oid, a.attnum)
+            FROM pg_attribute a
+            JOIN pg_class c ON c.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE a.attnum > 0 AND NOT a.attisdropped
+              AND c.relkind IN ('r', 'p', 'f', 'v', 'm') AND 'column' = ANY($2)
+        )
+        SELECT kind, schema, name, table_name, comment, name {op} $1, COUNT(*) OVER ()
+        FROM objects
+        WHERE schema NOT LIKE 'pg\_%' AND schema <> 'information_schema'
+          AND (name {op} $1 OR ($3 AND comment {op} $1))
+        ORDER BY name {op} $1 DESC, lower(name), schema, table_name, kind
+        LIMIT $4 OFFSET $5
+        "#,
+        op = operator
+    );
+
+    let limit = i64::from(page_size);
+    let offset = i64::from(page - 1) * limit;
+
+    let rows = client
+        .query(&query, &[&pattern, &kinds, &search.include_comments, &limit, &offset])
+        .await
+        .map_err(|e| match e.as_db_error() {
+            // 2201B: invalid_regular_expression
+            Some(db_error) if db_error.code().code() == "2201B" => {
+                format!("正则表达式无效: {}", db_error.message())
+            }
+            _ => format!("搜索数据库对象失败: {}", e),
+        })?;
+
+    let total = rows.first().map_or(0, |row| row.get(6));
+    let matches = rows
+        .iter()
+        .map(|row| ObjectSearchMatch {
+            kind: parse_kind(row.get(0)),
+            schema: row.get(1),
+            name: row.get(2),
+            table: row.get(3),
+            comment: row.get(4),
+            name_matched: row.get(5),
+        })
+        .collect();
+
+    Ok(ObjectSearchPage {
+        matches,
+        total,
+        page,
+        page_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_pattern_escapes_wildcards() {
+        assert_eq!(contains_pattern("user"), "%user%");
+        assert_eq!(contains_pattern("100%_off\\"), "%100\\%\\_off\\\\%");
+    }
+
+    #[test]
+    fn test_normalize_page() {
+        assert_eq!(normalize_page(0, 0), (1, DEFAULT_PAGE_SIZE));
+        assert_eq!(normalize_page(3, 20), (3, 20));
+        assert_eq!(normalize_page(1, 10_000), (1, MAX_PAGE_SIZE));
+    }
+}
@@ -0,0 +1,195 @@
+/**
+ * PostgreSQL Tools Service
+ *
+ * 此模块负责定位 pg_dump、pg_restore、psql 等命令行工具：
+ * - 配置了工具路径时使用该路径（如 macOS 上 Postgres.app 的安装目录）
+ * - 未配置时按工具名在 PATH 中查找
+ * - 无法启动时返回包含工具名和所尝试路径的错误信息
+ * - 运行工具时逐行读取其标准错误输出（`-v` 进度信息），供界面显示进度
+ */
+
+use serde::Serialize;
+use std::io::ErrorKind;
+use std::process::{Command, Stdio};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// 一个 PostgreSQL 命令行工具及其可执行文件路径
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgTool {
+    /// 工具名，如 `pg_dump`
+    name: &'static str,
+    /// 配置的路径，未配置时为 `None`
+    path: Option<String>,
+}
+
+impl PgTool {
+    /// 创建工具，`path` 为空或只含空白时视为未配置
+    pub fn new(name: &'static str, path: Option<String>) -> Self {
+        Self {
+            name,
+            path: path.filter(|path| !path.trim().is_empty()),
+        }
+    }
+
+    /// 实际执行的程序：配置的路径或工具名
+    pub fn program(&self) -> &str {
+        self.path.as_deref().unwrap_or(self.name)
+    }
+
+    /// 创建执行该工具的命令
+    pub fn command(&self) -> Command {
+        Command::new(self.program())
+    }
+
+    /// 无法启动该工具时的错误信息
+    pub fn error(&self, e: std::io::Error) -> String {
+        match (&self.path, e.kind()) {
+            (Some(path), ErrorKind::NotFound) => format!(
+                "找不到 {}：配置的路径 {} 不存在，请检查配置文件中的 tools.{}_path",
+                self.name, path, self.name
+            ),
+            (None, ErrorKind::NotFound) => format!(
+                "找不到 {}：PATH 中没有 {}。请确保 PostgreSQL 已安装，或在配置文件的 tools.{}_path 中指定完整路径",
+                self.name, self.name, self.name
+            ),
+            _ => format!("无法执行 {}（{}）: {}", self.name, self.program(), e),
+        }
+    }
+
+    /// 运行命令并逐行读取标准错误输出
+    ///
+    /// pg_dump/pg_restore 在 `-v` 模式下将进度信息写到标准错误，每读到一行调用一次 `on_line`。
+    /// 非 UTF-8 的输出（如 Windows 上的本地编码）按有损方式转换。标准输出被丢弃。
+    ///
+    /// # Arguments
+    /// * `command` - 由 `command()` 创建并设置好参数的命令
+    /// * `on_line` - 每行输出的回调（不含换行符）
+    ///
+    /// # Returns
+    /// * `Result<ToolOutput, String>` - 进程是否成功退出和完整的标准错误输出；无法启动时返回错误信息
+    pub async fn run_with_progress(
+        &self,
+        command: Command,
+        mut on_line: impl FnMut(&str),
+    ) -> Result<ToolOutput, String> {
+        let mut child = tokio::process::Command::from(command)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| self.error(e))?;
+
+        let mut stderr = String::new();
+        if let Some(pipe) = child.stderr.take() {
+            let mut reader = BufReader::new(pipe);
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                let read = reader
+                    .read_until(b'\n', &mut buf)
+                    .await
+                    .map_err(|e| format!("读取 {} 的输出失败: {}", self.name, e))?;
+                if read == 0 {
+                    break;
+                }
+                let line = String::from_utf8_lossy(&buf);
+                let line = line.trim_end_matches(['\r', '\n']);
+                on_line(line);
+                stderr.push_str(line);
+                stderr.push('\n');
+            }
+        }
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|e| format!("等待 {} 结束失败: {}", self.name, e))?;
+
+        Ok(ToolOutput {
+            success: status.success(),
+            stderr,
+        })
+    }
+}
+
+/// 工具运行结束后的结果
+#[derive(Debug, Clone)]
+pub struct ToolOutput {
+    /// 进程是否以状态码 0 退出
+    pub success: bool,
+    /// 完整的标准错误输出
+    pub stderr: String,
+}
+
+/// 导出/导入进度事件（`backup-progress`/`restore-progress`）
+///
+/// 运行期间每行输出发送一个事件（`line` 有值）；最后发送一个 `finished` 为 true 的事件，
+/// 其中 `success` 和 `message` 给出结果。
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressEvent {
+    /// 数据库名称
+    pub database: String,
+    /// 导出或导入的文件路径
+    pub file_path: String,
+    /// pg_dump/pg_restore 输出的一行进度信息
+    pub line: Option<String>,
+    /// 是否为结束事件
+    pub finished: bool,
+    /// 结束事件中表示是否成功
+    pub success: Option<bool>,
+    /// 结束事件中的结果说明或错误信息
+    pub message: Option<String>,
+}
+
+impl ProgressEvent {
+    /// 一行进度信息
+    pub fn line(database: &str, file_path: &str, line: &str) -> Self {
+        Self {
+            database: database.to_string(),
+            file_path: file_path.to_string(),
+            line: Some(line.to_string()),
+            finished: false,
+            success: None,
+            message: None,
+        }
+    }
+
+    /// 结束事件
+    pub fn finished(database: &str, file_path: &str, success: bool, message: &str) -> Self {
+        Self {
+            database: database.to_string(),
+            file_path: file_path.to_string(),
+            line: None,
+            finished: true,
+            success: Some(success),
+            message: Some(message.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_falls_back_to_path_lookup() {
+        assert_eq!(PgTool::new("pg_dump", None).program(), "pg_dump");
+        assert_eq!(PgTool::new("pg_dump", Some("  ".to_string())).program(), "pg_dump");
+        assert_eq!(
+            PgTool::new("psql", Some("/opt/pg/bin/psql".to_string())).program(),
+            "/opt/pg/bin/psql"
+        );
+    }
+
+    #[test]
+    fn test_missing_tool_error_names_binary_and_path() {
+        let tool = PgTool::new("pg_restore", Some("/no/such/dir/pg_restore".to_string()));
+        let error = tool.command().output().map_err(|e| tool.error(e)).unwrap_err();
+        assert!(error.contains("pg_restore"), "{}", error);
+        assert!(error.contains("/no/such/dir/pg_restore"), "{}", error);
+        assert!(error.contains("tools.pg_restore_path"), "{}", error);
+
+        let tool = PgTool::new("pg_dump", None);
+        let error = tool.error(std::io::Error::from(ErrorKind::NotFound));
+        assert!(error.contains("PATH 中没有 pg_dump"), "{}", error);
+    }
+}
@@ -0,0 +1,95 @@
+/**
+ * Password File Service
+ *
+ * 此模块为 psql、pg_dump、pg_restore 等命令行工具提供密码：
+ * - 将密码写入仅当前用户可读写（0600）的临时 .pgpass 文件，通过 `PGPASSFILE` 传给子进程
+ * - 不再使用 `PGPASSWORD` 环境变量，避免密码出现在 `/proc/<pid>/environ` 或进程列表中
+ * - 文件在值被 drop 时删除，调用方应在子进程结束后再释放
+ */
+
+use std::io::Write;
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// 临时的 .pgpass 文件，被 drop 时删除
+pub struct PgPassFile {
+    file: NamedTempFile,
+}
+
+impl PgPassFile {
+    /// 创建只包含一条记录的 .pgpass 文件，匹配任意主机、端口和数据库
+    ///
+    /// 文件只传给本次调用的子进程，因此不需要限定主机和数据库。
+    ///
+    /// # Arguments
+    /// * `user` - 数据库用户名
+    /// * `password` - 密码
+    ///
+    /// # Returns
+    /// * `Result<PgPassFile, String>` - 临时文件或错误信息
+    pub fn new(user: &str, password: &str) -> Result<Self, String> {
+        let mut file = tempfile::Builder::new()
+            .prefix("pg-db-tool-")
+            .suffix(".pgpass")
+            .tempfile()
+            .map_err(|e| format!("无法创建临时密码文件: {}", e))?;
+
+        // tempfile 在 Unix 上已使用 0600 创建文件，这里显式设置，libpq 会忽略权限更宽的文件
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.as_file()
+                .set_permissions(std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("无法设置临时密码文件权限: {}", e))?;
+        }
+
+        writeln!(file, "{}", pgpass_line(user, password))
+            .and_then(|_| file.flush())
+            .map_err(|e| format!("无法写入临时密码文件: {}", e))?;
+
+        Ok(Self { file })
+    }
+
+    /// 文件路径，作为子进程的 `PGPASSFILE` 环境变量
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+}
+
+/// .pgpass 中的一行：`hostname:port:database:username:password`
+fn pgpass_line(user: &str, password: &str) -> String {
+    format!("*:*:*:{}:{}", escape(user), escape(password))
+}
+
+/// 转义 .pgpass 字段中的 `\` 和 `:`
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(':', "\\:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pgpass_line_escapes_separators() {
+        assert_eq!(pgpass_line("postgres", "secret"), "*:*:*:postgres:secret");
+        assert_eq!(pgpass_line("app", r"a:b\c"), r"*:*:*:app:a\:b\\c");
+    }
+
+    #[test]
+    fn test_pgpass_file_is_private_and_removed_on_drop() {
+        let pgpass = PgPassFile::new("postgres", "p:w").unwrap();
+        let path = pgpass.path().to_path_buf();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "*:*:*:postgres:p\\:w\n");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        drop(pgpass);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,438 @@
+/**
+ * Plan Baseline Service
+ *
+ * This module detects query plan regressions:
+ * - Normalizes a query (case, whitespace, comments) and hashes it to a stable key
+ * - Stores the first EXPLAIN plan of each query as a baseline in a local JSON file
+ * - On later runs, compares the current plan with the baseline node by node,
+ *   reporting node type changes (e.g. an Index Scan became a Seq Scan), added or
+ *   removed nodes, and significant estimated cost changes
+ *
+ * Plans are obtained with EXPLAIN (FORMAT JSON), so the statement is never executed.
+ */
+
+use crate::models::query::{PlanChangeKind, PlanComparison, PlanNode, PlanNodeChange};
+use crate::services::explain;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio_postgres::Client;
+
+/// Minimum relative change of a node's estimated total cost (in percent) that is reported
+pub const COST_CHANGE_THRESHOLD_PERCENT: f64 = 20.0;
+
+/// Cost differences smaller than this are ignored, so trivial nodes do not produce noise
+const MIN_COST_DIFFERENCE: f64 = 1.0;
+
+/// A stored baseline plan
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanBaseline {
+    /// Database the plan was captured in
+    pub database: String,
+    /// Normalized query text
+    pub query: String,
+    /// When the plan was captured
+    pub captured_at: String,
+    /// Plan tree
+    pub plan: PlanNode,
+}
+
+/// Baselines persisted as a JSON object in a single file, keyed by `<database>:<query hash>`
+#[derive(Debug, Clone)]
+pub struct PlanBaselineStore {
+    path: PathBuf,
+}
+
+impl PlanBaselineStore {
+    /// Create a store backed by the given file (created on first save)
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Load all baselines; a missing file means no baselines yet
+    pub fn load(&self) -> Result<HashMap<String, PlanBaseline>, String> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read plan baseline file {}: {}", self.path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Plan baseline file {} is malformed: {}", self.path.display(), e))
+    }
+
+    /// Replace the stored baselines
+    pub fn save(&self, baselines: &HashMap<String, PlanBaseline>) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(baselines)
+            .map_err(|e| format!("Failed to serialize plan baselines: {}", e))?;
+        std::fs::write(&self.path, contents)
+            .map_err(|e| format!("Failed to write plan baseline file {}: {}", self.path.display(), e))
+    }
+}
+
+/// Compare a query's current plan with its stored baseline
+///
+/// The first time a query is seen its plan is stored as the baseline and returned
+/// without changes. Later calls diff against the baseline; with `update_baseline`
+/// the current plan then replaces it.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `store` - Baseline store
+/// * `database` - Database name (baselines are kept per database)
+/// * `sql` - A single SQL statement
+/// * `update_baseline` - Store the current plan as the new baseline after comparing
+///
+/// # Returns
+/// * `Result<PlanComparison, String>` - Structured plan diff, or error message
+pub async fn compare_plans(
+    client: &Client,
+    store: &PlanBaselineStore,
+    database: &str,
+    sql: &str,
+    update_baseline: bool,
+) -> Result<PlanComparison, String> {
+    let query = normalize_query(sql);
+    let query_hash = hash_query(&query);
+    let key = format!("{}:{}", database, query_hash);
+
+    let current_plan = explain::explain_plan(client, sql).await?;
+    let mut baselines = store.load()?;
+
+    let current = PlanBaseline {
+        database: database.to_string(),
+        query,
+        captured_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        plan: current_plan,
+    };
+
+    let comparison = match baselines.get(&key) {
+        Some(baseline) => build_comparison(query_hash, baseline, &current.plan, false),
+        None => build_comparison(query_hash, &current, &current.plan, true),
+    };
+
+    if comparison.baseline_created || update_baseline {
+        baselines.insert(key, current);
+        store.save(&baselines)?;
+    }
+
+    Ok(comparison)
+}
+
+/// Build the comparison between a baseline and the current plan
+fn build_comparison(
+    query_hash: String,
+    baseline: &PlanBaseline,
+    current_plan: &PlanNode,
+    baseline_created: bool,
+) -> PlanComparison {
+    let changes = diff_plans(&baseline.plan, current_plan);
+    let plan_changed = changes
+        .iter()
+        .any(|change| change.kind != PlanChangeKind::CostChanged);
+
+    PlanComparison {
+        query_hash,
+        baseline_created,
+        baseline_captured_at: baseline.captured_at.clone(),
+        baseline_cost: baseline.plan.total_cost,
+        current_cost: current_plan.total_cost,
+        cost_change_percent: percent_change(baseline.plan.total_cost, current_plan.total_cost),
+        plan_changed,
+        changes,
+        current_plan: current_plan.clone(),
+    }
+}
+
+/// Normalize a query so that formatting differences map to the same baseline
+///
+/// Comments are removed, runs of whitespace become a single space, text outside
+/// quotes is lowercased, and trailing semicolons are dropped. Quoted strings and
+/// identifiers are kept verbatim.
+pub fn normalize_query(sql: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut normalized = String::with_capacity(sql.len());
+    let mut pending_space = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+
+        if ch == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            pending_space = true;
+            continue;
+        }
+
+        if ch == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+            pending_space = true;
+            continue;
+        }
+
+        if ch.is_whitespace() {
+            pending_space = true;
+            i += 1;
+            continue;
+        }
+
+        if pending_space && !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        pending_space = false;
+
+        if ch == '\'' || ch == '"' {
+            // Copy the quoted text verbatim; a doubled quote is an escaped quote
+            normalized.push(ch);
+            i += 1;
+            while i < chars.len() {
+                normalized.push(chars[i]);
+                if chars[i] == ch {
+                    if chars.get(i + 1) == Some(&ch) {
+                        normalized.push(ch);
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+
+        normalized.extend(ch.to_lowercase());
+        i += 1;
+    }
+
+    normalized.trim_end_matches([';', ' ']).to_string()
+}
+
+/// Stable 64-bit FNV-1a hash of the normalized query, as 16 hex digits
+///
+/// `std::hash` is not guaranteed to be stable across Rust releases, which would
+/// orphan the persisted baselines.
+pub fn hash_query(normalized_query: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in normalized_query.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Compare two plan trees node by node
+///
+/// Children are matched by position. When a node's type changes, its children are
+/// still compared, since the rest of the subtree is often unchanged.
+pub fn diff_plans(baseline: &PlanNode, current: &PlanNode) -> Vec<PlanNodeChange> {
+    let mut changes = Vec::new();
+    diff_nodes(baseline, current, "0", &mut changes);
+    changes
+}
+
+fn diff_nodes(before: &PlanNode, after: &PlanNode, path: &str, changes: &mut Vec<PlanNodeChange>) {
+    if before.node_type != after.node_type {
+        changes.push(PlanNodeChange {
+            path: path.to_string(),
+            kind: PlanChangeKind::NodeTypeChanged,
+            before_node_type: Some(before.node_type.clone()),
+            after_node_type: Some(after.node_type.clone()),
+            relation_name: after.relation_name.clone().or_else(|| before.relation_name.clone()),
+            before_cost: Some(before.total_cost),
+            after_cost: Some(after.total_cost),
+            description: format!("{} changed to {}", describe_node(before), describe_node(after)),
+        });
+    } else {
+        let change = percent_change(before.total_cost, after.total_cost);
+        if change.abs() >= COST_CHANGE_THRESHOLD_PERCENT
+            && (after.total_cost - before.total_cost).abs() >= MIN_COST_DIFFERENCE
+        {
+            changes.push(PlanNodeChange {
+                path: path.to_string(),
+                kind: PlanChangeKind::CostChanged,
+                before_node_type: Some(before.node_type.clone()),
+                after_node_type: Some(after.node_type.clone()),
+                relation_name: after.relation_name.clone(),
+                before_cost: Some(before.total_cost),
+                after_cost: Some(after.total_cost),
+                description: format!(
+                    "Estimated cost of {} changed from {:.2} to {:.2} ({:+.1}%)",
+                    describe_node(after),
+                    before.total_cost,
+                    after.total_cost,
+                    change
+                ),
+            });
+        }
+    }
+
+    let count = before.children.len().max(after.children.len());
+    for index in 0..count {
+        let child_path = format!("{}.{}", path, index);
+        match (before.children.get(index), after.children.get(index)) {
+            (Some(before_child), Some(after_child)) => {
+                diff_nodes(before_child, after_child, &child_path, changes)
+            }
+            (Some(removed), None) => changes.push(PlanNodeChange {
+                path: child_path,
+                kind: PlanChangeKind::NodeRemoved,
+                before_node_type: Some(removed.node_type.clone()),
+                after_node_type: None,
+                relation_name: removed.relation_name.clone(),
+                before_cost: Some(removed.total_cost),
+                after_cost: None,
+                description: format!("{} is no longer in the plan", describe_node(removed)),
+            }),
+            (None, Some(added)) => changes.push(PlanNodeChange {
+                path: child_path,
+                kind: PlanChangeKind::NodeAdded,
+                before_node_type: None,
+                after_node_type: Some(added.node_type.clone()),
+                relation_name: added.relation_name.clone(),
+                before_cost: None,
+                after_cost: Some(added.total_cost),
+                description: format!("Added {}", describe_node(added)),
+            }),
+            (None, None) => {}
+        }
+    }
+}
+
+/// Node type with the scanned relation, e.g. "Index Scan (orders)"
+fn describe_node(node: &PlanNode) -> String {
+    match &node.relation_name {
+        Some(relation) => format!("{} ({})", node.node_type, relation),
+        None => node.node_type.clone(),
+    }
+}
+
+/// Relative change from `before` to `after` in percent
+fn percent_change(before: f64, after: f64) -> f64 {
+    if before == 0.0 {
+        if after == 0.0 {
+            0.0
+        } else {
+            100.0
+        }
+    } else {
+        (after - before) / before * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::explain::parse_explain_json;
+
+    fn plan(json: &str) -> PlanNode {
+        parse_explain_json(json).unwrap().plan
+    }
+
+    const INDEX_PLAN: &str = r#"[{"Plan": {
+        "Node Type": "Sort", "Startup Cost": 8.31, "Total Cost": 8.32, "Plan Rows": 1,
+        "Plans": [{
+            "Node Type": "Index Scan", "Parent Relationship": "Outer", "Relation Name": "orders",
+            "Startup Cost": 0.29, "Total Cost": 8.30, "Plan Rows": 1
+        }]
+    }}]"#;
+
+    const SEQ_PLAN: &str = r#"[{"Plan": {
+        "Node Type": "Sort", "Startup Cost": 180.01, "Total Cost": 180.02, "Plan Rows": 1,
+        "Plans": [{
+            "Node Type": "Seq Scan", "Parent Relationship": "Outer", "Relation Name": "orders",
+            "Startup Cost": 0.00, "Total Cost": 180.00, "Plan Rows": 1
+        }]
+    }}]"#;
+
+    #[test]
+    fn test_node_type_change_is_reported() {
+        let changes = diff_plans(&plan(INDEX_PLAN), &plan(SEQ_PLAN));
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path, "0");
+        assert_eq!(changes[0].kind, PlanChangeKind::CostChanged);
+        assert_eq!(changes[1].path, "0.0");
+        assert_eq!(changes[1].kind, PlanChangeKind::NodeTypeChanged);
+        assert_eq!(changes[1].before_node_type.as_deref(), Some("Index Scan"));
+        assert_eq!(changes[1].after_node_type.as_deref(), Some("Seq Scan"));
+        assert_eq!(changes[1].relation_name.as_deref(), Some("orders"));
+    }
+
+    #[test]
+    fn test_identical_plans_have_no_changes() {
+        assert!(diff_plans(&plan(INDEX_PLAN), &plan(INDEX_PLAN)).is_empty());
+    }
+
+    #[test]
+    fn test_removed_child_is_reported() {
+        let mut current = plan(INDEX_PLAN);
+        current.children.clear();
+        let changes = diff_plans(&plan(INDEX_PLAN), &current);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, PlanChangeKind::NodeRemoved);
+        assert_eq!(changes[0].path, "0.0");
+    }
+
+    #[test]
+    fn test_build_comparison_flags_plan_change() {
+        let baseline = PlanBaseline {
+            database: "db".to_string(),
+            query: "select 1".to_string(),
+            captured_at: "2024-01-01 00:00:00".to_string(),
+            plan: plan(INDEX_PLAN),
+        };
+        let comparison = build_comparison("abc".to_string(), &baseline, &plan(SEQ_PLAN), false);
+
+        assert!(comparison.plan_changed);
+        assert!(comparison.cost_change_percent > 1000.0);
+        assert_eq!(comparison.baseline_cost, 8.32);
+    }
+
+    #[test]
+    fn test_normalize_query() {
+        assert_eq!(
+            normalize_query("SELECT *\n  FROM Orders -- recent\n WHERE name = 'O''Brien X';"),
+            "select * from orders where name = 'O''Brien X'"
+        );
+        assert_eq!(
+            normalize_query("select /* hint */ \"MixedCase\" from t"),
+            "select \"MixedCase\" from t"
+        );
+        assert_eq!(
+            hash_query(&normalize_query("SELECT 1;")),
+            hash_query(&normalize_query("select   1"))
+        );
+        assert_ne!(hash_query("select 1"), hash_query("select 2"));
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PlanBaselineStore::new(dir.path().join("plan_baselines.json"));
+        assert!(store.load().unwrap().is_empty());
+
+        let mut baselines = HashMap::new();
+        baselines.insert(
+            "db:abc".to_string(),
+            PlanBaseline {
+                database: "db".to_string(),
+                query: "select 1".to_string(),
+                captured_at: "2024-01-01 00:00:00".to_string(),
+                plan: plan(INDEX_PLAN),
+            },
+        );
+        store.save(&baselines).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded["db:abc"].plan.children[0].node_type, "Index Scan");
+    }
+}
@@ -0,0 +1,212 @@
+/**
+ * Query Benchmark Service
+ *
+ * This module measures the execution time of a query over repeated runs:
+ * - Runs go through `query_executor::execute_sql`, the same path as the SQL editor,
+ *   so the timings include fetching and converting the rows
+ * - Warmup runs are executed first and discarded
+ * - All runs use the same connection, so server caches stay warm; optionally the
+ *   cached plans are discarded (`DISCARD PLANS`) before each run
+ * - Only read-only statements (SELECT, WITH ... SELECT, EXPLAIN) can be benchmarked
+ */
+
+use crate::models::query::{BenchmarkResult, QueryResultType};
+use crate::services::query_executor::{self, parse_sql_statements};
+use crate::services::sql_lexer::{find_write_keyword, tokenize};
+use std::time::Instant;
+use tokio_postgres::Client;
+
+/// Maximum number of measured runs
+pub const MAX_RUNS: u32 = 1000;
+
+/// Maximum number of warmup runs
+pub const MAX_WARMUP_RUNS: u32 = 100;
+
+/// Keywords a benchmarked statement must start with
+const READ_ONLY_COMMANDS: [&str; 3] = ["SELECT", "WITH", "EXPLAIN"];
+
+/// Benchmark settings
+#[derive(Debug, Clone)]
+pub struct BenchmarkOptions {
+    /// Number of measured runs
+    pub runs: u32,
+    /// Number of runs executed and discarded before measuring
+    pub warmup_runs: u32,
+    /// Discard cached plans before every run
+    pub reset_between_runs: bool,
+}
+
+/// Timing statistics of a list of run times
+#[derive(Debug, Clone, PartialEq)]
+struct TimingStats {
+    min: f64,
+    max: f64,
+    mean: f64,
+    median: f64,
+    p95: f64,
+    std_dev: f64,
+}
+
+/// Check that `sql` is a single read-only statement and return it
+fn read_only_statement(sql: &str) -> Result<&str, String> {
+    let statement = match parse_sql_statements(sql.trim()).as_slice() {
+        [statement] => *statement,
+        [] => return Err("SQL statement is empty".to_string()),
+        _ => return Err("Benchmarking only supports a single statement".to_string()),
+    };
+
+    let tokens = tokenize(statement);
+    let starts_read_only = tokens
+        .first()
+        .is_some_and(|token| READ_ONLY_COMMANDS.iter().any(|command| token.is_keyword(statement, command)));
+    let writes = find_write_keyword(statement, &tokens);
+
+    match writes {
+        Some(token) => Err(format!(
+            "Benchmarking only supports read-only statements (SELECT, EXPLAIN), but the statement contains {}",
+            token.text(statement).to_uppercase()
+        )),
+        None if !starts_read_only => Err("Benchmarking only supports read-only statements (SELECT, EXPLAIN)".to_string()),
+        None => Ok(statement),
+    }
+}
+
+/// Compute min/max/mean/median/p95 and the sample standard deviation
+///
+/// `timings` must not be empty. The 95th percentile uses the nearest-rank method.
+fn summarize(timings: &[f64]) -> TimingStats {
+    let mut sorted = timings.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let count = sorted.len();
+
+    let mean = sorted.iter().sum::<f64>() / count as f64;
+    let median = if count.is_multiple_of(2) {
+        (sorted[count / 2 - 1] + sorted[count / 2]) / 2.0
+    } else {
+        sorted[count / 2]
+    };
+    let p95_rank = ((count as f64) * 0.95).ceil() as usize;
+    let std_dev = if count > 1 {
+        let variance = sorted.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (count - 1) as f64;
+        variance.sqrt()
+    } else {
+        0.0
+    };
+
+    TimingStats {
+        min: sorted[0],
+        max: sorted[count - 1],
+        mean,
+        median,
+        p95: sorted[p95_rank.clamp(1, count) - 1],
+        std_dev,
+    }
+}
+
+/// Run a query several times and report timing statistics
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `sql` - A single read-only statement (SELECT or EXPLAIN)
+/// * `options` - Number of runs, warmup runs and whether to reset between runs
+///
+/// # Returns
+/// * `Result<BenchmarkResult, String>` - Timing statistics in milliseconds, or error message
+///   when the statement isn't read-only or a run fails
+pub async fn benchmark_query(
+    client: &Client,
+    sql: &str,
+    options: &BenchmarkOptions,
+) -> Result<BenchmarkResult, String> {
+    if options.runs == 0 || options.runs > MAX_RUNS {
+        return Err(format!("Run count must be between 1 and {}", MAX_RUNS));
+    }
+    if options.warmup_runs > MAX_WARMUP_RUNS {
+        return Err(format!("Warmup run count cannot exceed {}", MAX_WARMUP_RUNS));
+    }
+    let statement = read_only_statement(sql)?;
+
+    let mut timings = Vec::with_capacity(options.runs as usize);
+    let mut row_count = None;
+
+    for run in 0..options.warmup_runs + options.runs {
+        if options.reset_between_runs {
+            client
+                .batch_execute("DISCARD PLANS")
+                .await
+                .map_err(|e| format!("Failed to reset cached plans: {}", e))?;
+        }
+
+        let start = Instant::now();
+        let result = query_executor::execute_sql(client, statement).await;
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        if result.result_type == QueryResultType::Error {
+            return Err(format!(
+                "Run {} failed: {}",
+                run + 1,
+                result.error.unwrap_or_default()
+            ));
+        }
+        if run >= options.warmup_runs {
+            timings.push(elapsed_ms);
+            row_count = result.rows.as_ref().map(Vec::len);
+        }
+    }
+
+    let stats = summarize(&timings);
+
+    Ok(BenchmarkResult {
+        runs: options.runs,
+        warmup_runs: options.warmup_runs,
+        reset_between_runs: options.reset_between_runs,
+        min_ms: stats.min,
+        max_ms: stats.max,
+        mean_ms: stats.mean,
+        median_ms: stats.median,
+        p95_ms: stats.p95,
+        std_dev_ms: stats.std_dev,
+        row_count,
+        timings_ms: timings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_statement() {
+        assert!(read_only_statement("SELECT * FROM users WHERE note = 'delete me';").is_ok());
+        assert!(read_only_statement("-- stats\nWITH t AS (SELECT 1) SELECT * FROM t").is_ok());
+        assert!(read_only_statement("EXPLAIN ANALYZE SELECT 1").is_ok());
+        assert!(read_only_statement("SELECT \"update\" FROM t").is_ok());
+
+        assert!(read_only_statement("DELETE FROM users").is_err());
+        assert!(read_only_statement("EXPLAIN ANALYZE DELETE FROM users").is_err());
+        assert!(read_only_statement("WITH d AS (DELETE FROM users RETURNING *) SELECT * FROM d").is_err());
+        assert!(read_only_statement("SELECT * INTO users_copy FROM users").is_err());
+        assert!(read_only_statement("VACUUM users").is_err());
+        assert!(read_only_statement("SELECT 1; SELECT 2").is_err());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let stats = summarize(&[5.0, 1.0, 3.0, 2.0, 4.0]);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 5.0);
+        assert_eq!(stats.mean, 3.0);
+        assert_eq!(stats.median, 3.0);
+        assert_eq!(stats.p95, 5.0);
+        assert!((stats.std_dev - 2.5f64.sqrt()).abs() < 1e-9);
+
+        // An even count averages the middle two; p95 of 20 timings is the 19th
+        let timings: Vec<f64> = (1..=20).map(f64::from).collect();
+        let stats = summarize(&timings);
+        assert_eq!(stats.median, 10.5);
+        assert_eq!(stats.p95, 19.0);
+
+        let single = summarize(&[7.5]);
+        assert_eq!((single.min, single.p95, single.std_dev), (7.5, 7.5, 0.0));
+    }
+}
@@ -0,0 +1,95 @@
+/**
+ * Query Cancel Service
+ *
+ * 此模块记录本应用正在执行的语句，并支持一次取消全部语句：
+ * - 执行前登记连接的取消令牌（CancelToken），执行结束后注销
+ * - 取消时向服务器发送取消请求（与 pg_cancel_backend 效果相同）
+ *
+ * 取消令牌只包含本应用会话的进程号和密钥，因此只会取消本应用发起的语句，
+ * 不会影响其他客户端的会话。取消请求是异步的：服务器收到请求时语句可能已经结束。
+ */
+
+use crate::services::tls::{self, TlsSettings};
+use std::collections::BTreeMap;
+use tokio_postgres::CancelToken;
+
+/// 一条正在执行的语句
+#[derive(Clone)]
+pub struct RunningQuery {
+    /// 登记编号
+    pub id: u64,
+    /// 数据库名称
+    pub database: String,
+    token: CancelToken,
+    tls: TlsSettings,
+}
+
+impl RunningQuery {
+    /// 请求取消这条语句
+    pub async fn cancel(&self) -> Result<(), String> {
+        tls::cancel_query(&self.token, &self.tls).await
+    }
+}
+
+/// 正在执行的语句登记表
+#[derive(Default)]
+pub struct QueryCancelRegistry {
+    next_id: u64,
+    running: BTreeMap<u64, RunningQuery>,
+}
+
+impl QueryCancelRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记一条即将执行的语句，返回登记编号（用于执行结束后注销）
+    pub fn register(&mut self, database: &str, token: CancelToken, tls: TlsSettings) -> u64 {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.running.insert(
+            id,
+            RunningQuery {
+                id,
+                database: database.to_string(),
+                token,
+                tls,
+            },
+        );
+        id
+    }
+
+    /// 语句执行结束后注销
+    pub fn unregister(&mut self, id: u64) {
+        self.running.remove(&id);
+    }
+
+    /// 当前正在执行的语句（按登记顺序）
+    pub fn running(&self) -> Vec<RunningQuery> {
+        self.running.values().cloned().collect()
+    }
+}
+
+/// 同时请求取消多条语句
+///
+/// # Arguments
+/// * `queries` - 要取消的语句
+///
+/// # Returns
+/// * `usize` - 成功发送的取消请求数量；发送失败的请求会记录到日志
+pub async fn cancel_all(queries: &[RunningQuery]) -> usize {
+    let results = futures_util::future::join_all(queries.iter().map(|query| query.cancel())).await;
+
+    queries
+        .iter()
+        .zip(results)
+        .filter(|(query, result)| match result {
+            Ok(()) => true,
+            Err(e) => {
+                log::error!("取消数据库 {} 上的语句 #{} 失败: {}", query.database, query.id, e);
+                false
+            }
+        })
+        .count()
+}
@@ -6,14 +6,27 @@
  * - Parsing query results and converting to QueryResult type
  * - Query execution time tracking
  * - Error handling and position extraction
+ * - Detecting cross-database (`database.schema.table`) references
+ * - Executing a statement with `$1..$N` parameters supplied as JSON values
+ * - Streaming large SELECT results in batches instead of buffering every row
  * 
  * Validates: Requirements 2.1, 2.3, 2.4, 2.5
  */
 
-use crate::models::query::{QueryResult, QueryResultType, ColumnInfo, ErrorPosition};
+use crate::models::query::{
+    QueryResult, QueryResultType, ColumnInfo, ErrorPosition, CopyDirection, ServerCopyStatement, ServerCopyTarget,
+    QueryChunk, QueryStreamSummary,
+};
+use crate::services::csv_import::copy_data;
+use crate::services::record_writer::json_to_text;
+use crate::services::sql_lexer::{tokenize, Token, TokenKind};
+use crate::services::sql_utils::qualified_name;
+use futures_util::{pin_mut, TryStreamExt};
+use std::io::Write;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::time::Instant;
-use tokio_postgres::{Client, Row, types::Type};
+use tokio_postgres::{Client, Column, Row, types::{FromSql, ToSql, Type}};
 
 /// Execute a SQL statement and return the result
 /// 
@@ -41,6 +54,16 @@ pub async fn execute_sql(client: &Client, sql: &str) -> QueryResult {
         );
     }
     
+    // Three-part names can't reach another database; explain instead of letting
+    // the server report "cross-database references are not implemented"
+    if let Some((message, position)) = check_cross_database_references(client, sql).await {
+        return QueryResult::error(
+            message,
+            Some(line_column_at(sql, position)),
+            start.elapsed().as_millis() as u64,
+        );
+    }
+    
     // Parse SQL into individual statements
     let statements = parse_sql_statements(sql);
     
@@ -50,7 +73,7 @@ pub async fn execute_sql(client: &Client, sql: &str) -> QueryResult {
     }
     
     // Execute multiple statements in order
-    execute_multiple_statements(client, &statements, start).await
+    execute_multiple_statements(client, sql, &statements, start).await
 }
 
 /// Execute a single SQL statement
@@ -60,11 +83,12 @@ async fn execute_single_statement(client: &Client, sql: &str, start: Instant) ->
     
     // Execute based on query type
     match query_type {
-        QueryResultType::Select => execute_select(client, sql, start).await,
+        QueryResultType::Select => execute_select(client, sql, &[], start).await,
         QueryResultType::Insert | QueryResultType::Update | QueryResultType::Delete => {
-            execute_dml(client, sql, query_type, start).await
+            execute_dml(client, sql, &[], query_type, start).await
         }
-        QueryResultType::Ddl => execute_ddl(client, sql, start).await,
+        QueryResultType::Ddl => execute_ddl(client, sql, &[], start).await,
+        QueryResultType::Copy => execute_copy(client, sql, start).await,
         QueryResultType::Error => {
             QueryResult::error(
                 "Unable to determine query type".to_string(),
@@ -75,6 +99,155 @@ async fn execute_single_statement(client: &Client, sql: &str, start: Instant) ->
     }
 }
 
+/// Execute a single SQL statement with bind parameters (`$1`, `$2`, ...)
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `sql` - A single SQL statement
+/// * `params` - Values for the statement's bind parameters, in order
+///
+/// # Returns
+/// * `QueryResult` - Result containing columns, rows, affected rows, or error
+pub async fn execute_with_params(
+    client: &Client,
+    sql: &str,
+    params: &[&(dyn ToSql + Sync)],
+) -> QueryResult {
+    let start = Instant::now();
+    let query_type = determine_query_type(sql);
+    
+    match query_type {
+        QueryResultType::Select => execute_select(client, sql, params, start).await,
+        QueryResultType::Insert | QueryResultType::Update | QueryResultType::Delete => {
+            execute_dml(client, sql, params, query_type, start).await
+        }
+        QueryResultType::Ddl => execute_ddl(client, sql, params, start).await,
+        QueryResultType::Copy | QueryResultType::Error => {
+            QueryResult::error(
+                "Unable to determine query type".to_string(),
+                None,
+                start.elapsed().as_millis() as u64,
+            )
+        }
+    }
+}
+
+/// Execute a single SQL statement with `$1..$N` parameters given as JSON values
+///
+/// The statement is prepared first so the server infers the type of each parameter.
+/// Every value is then bound as text and cast to that type, so JSON strings, numbers
+/// and booleans work for any parameter type (dates, uuids, numerics, ...). JSON null
+/// binds SQL NULL; arrays and objects are bound as their JSON text.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `sql` - A single SQL statement using `$1`, `$2`, ... placeholders
+/// * `params` - Value of each placeholder, `params[0]` is bound to `$1`
+///
+/// # Returns
+/// * `QueryResult` - Result containing columns, rows, affected rows, or error
+pub async fn execute_with_json_params(client: &Client, sql: &str, params: &[serde_json::Value]) -> QueryResult {
+    let start = Instant::now();
+    let sql = sql.trim();
+    if parse_sql_statements(sql).len() != 1 {
+        return QueryResult::error(
+            "A parameterized query must be a single statement".to_string(),
+            None,
+            start.elapsed().as_millis() as u64,
+        );
+    }
+
+    let statement = match client.prepare(sql).await {
+        Ok(statement) => statement,
+        Err(e) => {
+            return QueryResult::error(
+                format_error_message(&e),
+                extract_error_position(&e, sql),
+                start.elapsed().as_millis() as u64,
+            )
+        }
+    };
+    if statement.params().len() != params.len() {
+        return QueryResult::error(
+            format!(
+                "The statement has {} parameters but {} values were given",
+                statement.params().len(),
+                params.len()
+            ),
+            None,
+            start.elapsed().as_millis() as u64,
+        );
+    }
+
+    let (rewritten, replacements) = cast_parameters(sql, statement.params());
+    let values: Vec<Option<String>> = params.iter().map(json_to_text).collect();
+    let values: Vec<&(dyn ToSql + Sync)> = values.iter().map(|v| v as &(dyn ToSql + Sync)).collect();
+
+    let mut result = execute_with_params(client, &rewritten, &values).await;
+    // Report error positions in the SQL the user wrote, not in the rewritten SQL
+    result.error_position = result
+        .error_position
+        .map(|position| original_error_position(&position, sql, &rewritten, &replacements));
+    result
+}
+
+/// Type name for casting a text parameter to an inferred parameter type
+pub(crate) fn cast_type_name(ty: &Type) -> String {
+    qualified_name(ty.schema(), ty.name())
+}
+
+/// Replace each `$n` placeholder with its text parameter cast to the inferred type `types[n - 1]`
+///
+/// Placeholders inside string literals, quoted identifiers and comments are left alone.
+/// Also returns the byte range of each replaced placeholder in `sql` and of its
+/// replacement in the rewritten SQL, in order.
+fn cast_parameters(sql: &str, types: &[Type]) -> (String, Vec<(Range<usize>, Range<usize>)>) {
+    let mut rewritten = String::with_capacity(sql.len());
+    let mut replacements = Vec::new();
+    let mut last = 0;
+    for token in tokenize(sql) {
+        let Some(index) = token
+            .text(sql)
+            .strip_prefix('$')
+            .and_then(|digits| digits.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let replacement = match types.get(index.wrapping_sub(1)) {
+            Some(ty) if *ty != Type::TEXT && *ty != Type::UNKNOWN => {
+                format!("CAST(${}::text AS {})", index, cast_type_name(ty))
+            }
+            Some(_) => format!("${}::text", index),
+            None => continue,
+        };
+        rewritten.push_str(&sql[last..token.start]);
+        let rewritten_start = rewritten.len();
+        rewritten.push_str(&replacement);
+        replacements.push((token.start..token.end, rewritten_start..rewritten.len()));
+        last = token.end;
+    }
+    rewritten.push_str(&sql[last..]);
+    (rewritten, replacements)
+}
+
+/// Map an error position in SQL rewritten by `cast_parameters` back to the original `sql`
+///
+/// A position inside a replacement points at the start of its placeholder.
+fn original_error_position(
+    position: &ErrorPosition,
+    sql: &str,
+    rewritten: &str,
+    replacements: &[(Range<usize>, Range<usize>)],
+) -> ErrorPosition {
+    let offset = byte_offset_at(rewritten, position);
+    let original_offset = match replacements.iter().rev().find(|(_, replaced)| replaced.start <= offset) {
+        Some((original, replaced)) if offset < replaced.end => original.start,
+        Some((original, replaced)) => original.end + (offset - replaced.end),
+        None => offset,
+    };
+    line_column_at(sql, sql[..original_offset].chars().count() + 1)
+}
+
 /// Execute multiple SQL statements in order
 /// 
 /// Executes each statement sequentially and collects results.
@@ -82,13 +255,18 @@ async fn execute_single_statement(client: &Client, sql: &str, start: Instant) ->
 /// 
 /// For multiple statements, returns the result of the last statement,
 /// but accumulates affected rows for DML operations.
+///
+/// `statements` must be slices of `sql`, so that an error position within a
+/// statement can be reported relative to the whole SQL text.
 async fn execute_multiple_statements(
     client: &Client,
+    sql: &str,
     statements: &[&str],
     start: Instant,
 ) -> QueryResult {
     let mut last_result: Option<QueryResult> = None;
     let mut total_affected_rows: u64 = 0;
+    let mut warnings: Vec<String> = Vec::new();
     
     for (index, statement) in statements.iter().enumerate() {
         let stmt_start = Instant::now();
@@ -98,7 +276,7 @@ async fn execute_multiple_statements(
         if result.result_type == QueryResultType::Error {
             return QueryResult::error(
                 format!("Error in statement {}: {}", index + 1, result.error.unwrap_or_default()),
-                result.error_position,
+                result.error_position.map(|position| shift_error_position(sql, statement, position)),
                 start.elapsed().as_millis() as u64,
             );
         }
@@ -108,6 +286,10 @@ async fn execute_multiple_statements(
             total_affected_rows += affected;
         }
         
+        if let Some(warning) = &result.warning {
+            warnings.push(format!("Statement {}: {}", index + 1, warning));
+        }
+        
         last_result = Some(result);
     }
     
@@ -120,6 +302,11 @@ async fn execute_multiple_statements(
             result.affected_rows = Some(total_affected_rows);
         }
         
+        // Keep warnings from every statement, not just the last one
+        if !warnings.is_empty() {
+            result.warning = Some(warnings.join("\n"));
+        }
+        
         result
     } else {
         QueryResult::error(
@@ -132,112 +319,40 @@ async fn execute_multiple_statements(
 
 /// Parse SQL text into individual statements separated by semicolons
 /// 
-/// This is a simplified parser that splits on semicolons while being aware of:
-/// - String literals (single quotes)
-/// - Comments (-- and /* */)
-/// 
-/// Note: This is a basic implementation. A production parser would need to handle
-/// more edge cases like dollar-quoted strings, nested comments, etc.
-fn parse_sql_statements(sql: &str) -> Vec<&str> {
+/// Splits on the `;` tokens produced by `sql_lexer::tokenize`, so semicolons inside
+/// string literals, dollar-quoted bodies (`DO $$ ... $$`), quoted identifiers,
+/// comments and parentheses don't end a statement. Segments that hold only
+/// comments are dropped.
+pub(crate) fn parse_sql_statements(sql: &str) -> Vec<&str> {
     let mut statements = Vec::new();
     let mut current_start = 0;
-    let mut in_string = false;
-    let mut in_line_comment = false;
-    let mut in_block_comment = false;
-    let mut escape_next = false;
-    
-    let chars: Vec<char> = sql.chars().collect();
-    let len = chars.len();
+    let mut has_tokens = false;
     
-    let mut i = 0;
-    while i < len {
-        let ch = chars[i];
-        
-        // Handle escape sequences in strings
-        if escape_next {
-            escape_next = false;
-            i += 1;
-            continue;
-        }
-        
-        // Handle line comments
-        if !in_string && !in_block_comment && i + 1 < len && ch == '-' && chars[i + 1] == '-' {
-            in_line_comment = true;
-            i += 2;
-            continue;
-        }
-        
-        if in_line_comment {
-            if ch == '\n' {
-                in_line_comment = false;
+    for token in tokenize(sql) {
+        if token.kind == TokenKind::Symbol(';') && token.depth == 0 {
+            if has_tokens {
+                statements.push(sql[current_start..token.start].trim());
             }
-            i += 1;
-            continue;
-        }
-        
-        // Handle block comments
-        if !in_string && !in_line_comment && i + 1 < len && ch == '/' && chars[i + 1] == '*' {
-            in_block_comment = true;
-            i += 2;
-            continue;
-        }
-        
-        if in_block_comment {
-            if i + 1 < len && ch == '*' && chars[i + 1] == '/' {
-                in_block_comment = false;
-                i += 2;
-                continue;
-            }
-            i += 1;
-            continue;
-        }
-        
-        // Handle string literals
-        if ch == '\'' && !in_line_comment && !in_block_comment {
-            if in_string {
-                // Check for escaped quote (two single quotes)
-                if i + 1 < len && chars[i + 1] == '\'' {
-                    i += 2;
-                    continue;
-                }
-                in_string = false;
-            } else {
-                in_string = true;
-            }
-            i += 1;
-            continue;
-        }
-        
-        // Handle backslash escapes in strings
-        if in_string && ch == '\\' {
-            escape_next = true;
-            i += 1;
-            continue;
-        }
-        
-        // Handle semicolon (statement separator)
-        if ch == ';' && !in_string && !in_line_comment && !in_block_comment {
-            let statement = sql[current_start..i].trim();
-            if !statement.is_empty() {
-                statements.push(statement);
-            }
-            current_start = i + 1;
+            current_start = token.end;
+            has_tokens = false;
+        } else {
+            has_tokens = true;
         }
-        
-        i += 1;
     }
     
     // Add the last statement if there's any remaining text
-    let last_statement = sql[current_start..].trim();
-    if !last_statement.is_empty() {
-        statements.push(last_statement);
+    if has_tokens {
+        statements.push(sql[current_start..].trim());
     }
     
     statements
 }
 
 /// Determine the type of SQL query
-fn determine_query_type(sql: &str) -> QueryResultType {
+///
+/// EXPLAIN returns its plan as rows, so EXPLAIN of a query is treated as SELECT.
+/// EXPLAIN of any other statement is not supported (EXPLAIN ANALYZE would execute it).
+pub(crate) fn determine_query_type(sql: &str) -> QueryResultType {
     let sql_upper = sql.trim().to_uppercase();
     
     // Remove leading comments and whitespace
@@ -284,6 +399,11 @@ fn determine_query_type(sql: &str) -> QueryResultType {
     
     if sql_trimmed.starts_with("SELECT") || sql_trimmed.starts_with("WITH") {
         QueryResultType::Select
+    } else if sql_trimmed.starts_with("EXPLAIN") {
+        match explained_statement(&sql_trimmed) {
+            Some(statement) if determine_query_type(statement) == QueryResultType::Select => QueryResultType::Select,
+            _ => QueryResultType::Error,
+        }
     } else if sql_trimmed.starts_with("INSERT") {
         QueryResultType::Insert
     } else if sql_trimmed.starts_with("UPDATE") {
@@ -296,14 +416,430 @@ fn determine_query_type(sql: &str) -> QueryResultType {
         || sql_trimmed.starts_with("TRUNCATE")
     {
         QueryResultType::Ddl
+    } else if is_utility_statement(&sql_trimmed) {
+        // Savepoints, locks and anonymous code blocks return no result set
+        QueryResultType::Ddl
+    } else if sql_trimmed.starts_with("COPY") {
+        QueryResultType::Copy
     } else {
         QueryResultType::Error
     }
 }
 
+/// Whether an (upper-cased) statement is a savepoint, lock, or `DO` block
+fn is_utility_statement(sql_upper: &str) -> bool {
+    let mut words = sql_upper.split_whitespace();
+    match words.next() {
+        Some("SAVEPOINT") | Some("RELEASE") | Some("LOCK") | Some("DO") => true,
+        Some("ROLLBACK") => words.next() == Some("TO"),
+        _ => false,
+    }
+}
+
+/// The statement an EXPLAIN applies to, after the option list or ANALYZE/VERBOSE keywords
+fn explained_statement(sql: &str) -> Option<&str> {
+    let tokens = tokenize(sql);
+    let mut index = 1;
+
+    if tokens.get(index).is_some_and(|t| t.kind == TokenKind::Symbol('(')) {
+        index += tokens[index..]
+            .iter()
+            .position(|t| t.kind == TokenKind::Symbol(')') && t.depth == 0)?
+            + 1;
+    } else {
+        while tokens
+            .get(index)
+            .is_some_and(|t| ["ANALYZE", "ANALYSE", "VERBOSE"].iter().any(|k| t.is_keyword(sql, k)))
+        {
+            index += 1;
+        }
+    }
+
+    tokens.get(index).map(|t| &sql[t.start..])
+}
+
+/// A `database.schema.table` name found where a table is expected
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseQualifiedName {
+    /// First part of the name (lowercased unless it was quoted)
+    pub database: String,
+    /// The full name as written in the SQL
+    pub name: String,
+    /// 1-based character position of the name in the SQL
+    pub position: usize,
+}
+
+/// Keywords after which a table name is expected
+const RELATION_KEYWORDS: &[&str] = &["FROM", "JOIN", "UPDATE", "INTO", "TABLE", "ONLY", "USING", "TRUNCATE"];
+
+/// Keywords that end a FROM list (after which a comma no longer separates tables)
+const FROM_LIST_TERMINATORS: &[&str] = &[
+    "SELECT", "WHERE", "GROUP", "ORDER", "HAVING", "LIMIT", "OFFSET", "UNION", "INTERSECT",
+    "EXCEPT", "WINDOW", "FOR", "RETURNING", "SET", "VALUES", "ON",
+];
+
+/// Find three-part names (`database.schema.table`) used as table names
+///
+/// Only names in table positions are reported (after FROM, JOIN, UPDATE, INTO,
+/// TABLE, ONLY, USING, TRUNCATE, or after a comma in a FROM list), since a
+/// three-part name elsewhere may be a valid `schema.table.column` reference.
+/// Names inside strings, comments and function arguments (e.g.
+/// `EXTRACT(YEAR FROM t.c)`) are ignored.
+///
+/// # Arguments
+/// * `sql` - SQL text, possibly containing multiple statements
+///
+/// # Returns
+/// * `Vec<DatabaseQualifiedName>` - Three-part table names in order of appearance
+pub fn find_database_qualified_names(sql: &str) -> Vec<DatabaseQualifiedName> {
+    let tokens = tokenize(sql);
+    let mut names = Vec::new();
+
+    // For each open parenthesis: whether it contains a query (subquery) rather than
+    // function arguments or an expression
+    let mut parens: Vec<bool> = Vec::new();
+    let mut in_from_list = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let in_query = parens.last().copied().unwrap_or(true);
+
+        match token.kind {
+            TokenKind::Symbol('(') => {
+                let is_query = tokens
+                    .get(i + 1)
+                    .is_some_and(|next| ["SELECT", "WITH", "VALUES"].iter().any(|k| next.is_keyword(sql, k)));
+                parens.push(is_query);
+                continue;
+            }
+            TokenKind::Symbol(')') => {
+                parens.pop();
+                continue;
+            }
+            TokenKind::Symbol(';') => in_from_list = false,
+            TokenKind::Word if in_query => {
+                if token.is_keyword(sql, "FROM") {
+                    in_from_list = true;
+                } else if FROM_LIST_TERMINATORS.iter().any(|k| token.is_keyword(sql, k)) {
+                    in_from_list = false;
+                }
+            }
+            _ => {}
+        }
+
+        if !in_query || i == 0 {
+            continue;
+        }
+
+        let previous = &tokens[i - 1];
+        let at_table_position = if previous.kind == TokenKind::Symbol(',') {
+            in_from_list
+        } else {
+            RELATION_KEYWORDS.iter().any(|k| previous.is_keyword(sql, k))
+        };
+        if !at_table_position {
+            continue;
+        }
+
+        let Some(parts) = tokens.get(i..i + 5) else {
+            continue;
+        };
+        let is_three_part_name = parts[0].is_identifier()
+            && parts[1].kind == TokenKind::Symbol('.')
+            && parts[2].is_identifier()
+            && parts[3].kind == TokenKind::Symbol('.')
+            && parts[4].is_identifier()
+            && tokens.get(i + 5).is_none_or(|next| next.kind != TokenKind::Symbol('.'));
+        if is_three_part_name {
+            names.push(DatabaseQualifiedName {
+                database: token.identifier(sql),
+                name: sql[token.start..parts[4].end].to_string(),
+                position: sql[..token.start].chars().count() + 1,
+            });
+        }
+    }
+
+    names
+}
+
+/// Explain why a reference to another database can't be executed
+fn cross_database_message(reference: &DatabaseQualifiedName, current_database: &str) -> String {
+    format!(
+        "Cross-database reference: {} refers to database \"{}\", but this connection is to \"{}\". \
+         PostgreSQL cannot query another database through a three-part name (unlike MySQL or SQL Server); \
+         each connection sees only its own database. Switch to database \"{}\" to run this query, \
+         or use the postgres_fdw extension to access its tables from \"{}\".",
+        reference.name, reference.database, current_database, reference.database, current_database
+    )
+}
+
+/// Check the SQL for three-part table names that point at another database
+///
+/// The current database is only queried when such a name is present.
+/// Returns the explanatory message and the position of the first offending name.
+async fn check_cross_database_references(client: &Client, sql: &str) -> Option<(String, usize)> {
+    let names = find_database_qualified_names(sql);
+    if names.is_empty() {
+        return None;
+    }
+
+    let current_database: String = client
+        .query_one("SELECT current_database()", &[])
+        .await
+        .ok()?
+        .get(0);
+
+    names
+        .iter()
+        .find(|name| name.database != current_database)
+        .map(|name| (cross_database_message(name, &current_database), name.position))
+}
+
+/// Locate the FROM/TO clause of a COPY statement
+///
+/// Returns the direction and the index of the token after FROM/TO, or `None` if the
+/// tokens are not a COPY statement. A FROM inside `COPY (SELECT ... FROM t) TO ...`
+/// is skipped because it is inside the parentheses.
+fn copy_clause(sql: &str, tokens: &[Token]) -> Option<(CopyDirection, usize)> {
+    if !tokens.first()?.is_keyword(sql, "COPY") {
+        return None;
+    }
+
+    tokens.iter().enumerate().skip(1).find_map(|(i, token)| {
+        if token.depth != 0 {
+            None
+        } else if token.is_keyword(sql, "FROM") {
+            Some((CopyDirection::From, i + 1))
+        } else if token.is_keyword(sql, "TO") {
+            Some((CopyDirection::To, i + 1))
+        } else {
+            None
+        }
+    })
+}
+
+/// Value of a string literal token (`'...'` or `E'...'`), or `None` for other literals
+fn string_literal_value(text: &str) -> Option<String> {
+    if let Some(body) = text.strip_prefix("E'").or_else(|| text.strip_prefix("e'")) {
+        let body = body.strip_suffix('\'').unwrap_or(body);
+        let mut value = String::new();
+        let mut chars = body.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => value.extend(chars.next()),
+                '\'' => {
+                    chars.next();
+                    value.push('\'');
+                }
+                _ => value.push(ch),
+            }
+        }
+        return Some(value);
+    }
+
+    let body = text.strip_prefix('\'')?;
+    Some(body.strip_suffix('\'').unwrap_or(body).replace("''", "'"))
+}
+
+/// Explain where a server-side COPY runs and what to use instead
+fn server_copy_warning(direction: CopyDirection, target: ServerCopyTarget, location: &str) -> String {
+    match (target, direction) {
+        (ServerCopyTarget::File, CopyDirection::From) => format!(
+            "COPY FROM '{}' reads the file on the database server, not on this computer, \
+             and requires superuser or the pg_read_server_files role. \
+             To load a file from this computer, run the statement with FROM STDIN and choose the local file.",
+            location
+        ),
+        (ServerCopyTarget::File, CopyDirection::To) => format!(
+            "COPY TO '{}' writes the file on the database server, not on this computer, \
+             and requires superuser or the pg_write_server_files role. \
+             To save the output on this computer, run the statement with TO STDOUT and choose the local file.",
+            location
+        ),
+        (ServerCopyTarget::Program, _) => format!(
+            "COPY ... PROGRAM runs the shell command `{}` on the database server as the server's \
+             operating-system user, not on this computer, and requires superuser or the \
+             pg_execute_server_program role.",
+            location
+        ),
+    }
+}
+
+/// Find COPY statements that read or write the database server's filesystem
+///
+/// Recognizes `COPY ... FROM/TO 'file'` and `COPY ... FROM/TO PROGRAM 'command'`.
+/// `FROM STDIN` / `TO STDOUT` statements exchange data with the client and are not
+/// reported. For file targets, the same statement using STDIN/STDOUT is suggested so
+/// that it can be run with a file on this computer instead.
+///
+/// # Arguments
+/// * `sql` - SQL text, possibly containing multiple statements
+///
+/// # Returns
+/// * `Vec<ServerCopyStatement>` - Server-side COPY statements in order of appearance
+pub fn find_server_copies(sql: &str) -> Vec<ServerCopyStatement> {
+    let tokens = tokenize(sql);
+
+    tokens
+        .split(|token| token.depth == 0 && token.kind == TokenKind::Symbol(';'))
+        .filter_map(|statement| {
+            let (direction, index) = copy_clause(sql, statement)?;
+            let first = statement.get(index)?;
+
+            let (target, literal) = if first.is_keyword(sql, "PROGRAM") {
+                (ServerCopyTarget::Program, statement.get(index + 1)?)
+            } else {
+                (ServerCopyTarget::File, first)
+            };
+            if literal.kind != TokenKind::Literal {
+                return None;
+            }
+            let location = string_literal_value(literal.text(sql))?;
+
+            let client_sql = (target == ServerCopyTarget::File).then(|| {
+                let stream = match direction {
+                    CopyDirection::From => "STDIN",
+                    CopyDirection::To => "STDOUT",
+                };
+                let end = statement.last().map_or(literal.end, |token| token.end);
+                format!("{}{}{}", &sql[statement[0].start..literal.start], stream, &sql[literal.end..end])
+            });
+
+            Some(ServerCopyStatement {
+                direction,
+                target,
+                warning: server_copy_warning(direction, target, &location),
+                location,
+                position: sql[..first.start].chars().count() + 1,
+                client_sql,
+            })
+        })
+        .collect()
+}
+
+/// Execute a COPY statement
+///
+/// Server-side file and PROGRAM COPYs run as written, with a warning that they used
+/// the server's filesystem. `FROM STDIN` / `TO STDOUT` need a local file and are
+/// rejected here; run them with `execute_client_copy` instead.
+async fn execute_copy(client: &Client, sql: &str, start: Instant) -> QueryResult {
+    let Some(server_copy) = find_server_copies(sql).into_iter().next() else {
+        return QueryResult::error(
+            "COPY FROM STDIN and COPY TO STDOUT exchange data with this computer and cannot run as a query; \
+             run them with a local file instead"
+                .to_string(),
+            None,
+            start.elapsed().as_millis() as u64,
+        );
+    };
+
+    match client.execute(sql, &[]).await {
+        Ok(rows) => {
+            let mut result = QueryResult::dml(QueryResultType::Copy, rows, start.elapsed().as_millis() as u64);
+            result.warning = Some(server_copy.warning);
+            result
+        }
+        Err(e) => {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let error_position = extract_error_position(&e, sql);
+            let error_message = format_error_message(&e);
+            QueryResult::error(error_message, error_position, duration_ms)
+        }
+    }
+}
+
+/// Run a `COPY ... FROM STDIN` or `COPY ... TO STDOUT` statement with a file on this computer
+///
+/// FROM STDIN sends the contents of the file; TO STDOUT writes the output to the file,
+/// replacing it.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `sql` - A single COPY statement using FROM STDIN or TO STDOUT
+/// * `file_path` - Local file to read from or write to
+///
+/// # Returns
+/// * `QueryResult` - COPY result with the number of rows loaded (FROM STDIN only), or error
+pub async fn execute_client_copy(client: &Client, sql: &str, file_path: &str) -> QueryResult {
+    let start = Instant::now();
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    let tokens = tokenize(sql);
+
+    let direction = match copy_clause(sql, &tokens) {
+        Some((CopyDirection::From, i)) if tokens.get(i).is_some_and(|t| t.is_keyword(sql, "STDIN")) => {
+            CopyDirection::From
+        }
+        Some((CopyDirection::To, i)) if tokens.get(i).is_some_and(|t| t.is_keyword(sql, "STDOUT")) => {
+            CopyDirection::To
+        }
+        _ => {
+            return QueryResult::error(
+                "Expected a single COPY ... FROM STDIN or COPY ... TO STDOUT statement".to_string(),
+                None,
+                start.elapsed().as_millis() as u64,
+            );
+        }
+    };
+
+    // The row count is only reported for FROM STDIN; COPY TO STDOUT leaves it empty
+    let result = match direction {
+        CopyDirection::From => match std::fs::read(file_path) {
+            Ok(data) => copy_data(client, sql, &data)
+                .await
+                .map(Some)
+                .map_err(|e| (format_error_message(&e), extract_error_position(&e, sql))),
+            Err(e) => Err((format!("Cannot read file {}: {}", file_path, e), None)),
+        },
+        CopyDirection::To => copy_to_file(client, sql, file_path).await.map(|_| None),
+    };
+
+    let duration_ms = start.elapsed().as_millis() as u64;
+    match result {
+        Ok(rows) => {
+            let mut result = QueryResult::dml(QueryResultType::Copy, rows.unwrap_or_default(), duration_ms);
+            result.affected_rows = rows;
+            result
+        }
+        Err((message, position)) => QueryResult::error(message, position, duration_ms),
+    }
+}
+
+/// Stream the output of a `COPY ... TO STDOUT` statement into a local file
+///
+/// Returns the error message and position on failure.
+async fn copy_to_file(
+    client: &Client,
+    sql: &str,
+    file_path: &str,
+) -> Result<(), (String, Option<ErrorPosition>)> {
+    let mut file = std::fs::File::create(file_path)
+        .map_err(|e| (format!("Cannot create file {}: {}", file_path, e), None))?;
+
+    let stream = client
+        .copy_out(sql)
+        .await
+        .map_err(|e| (format_error_message(&e), extract_error_position(&e, sql)))?;
+    pin_mut!(stream);
+
+    while let Some(chunk) = stream
+        .try_next()
+        .await
+        .map_err(|e| (format_error_message(&e), extract_error_position(&e, sql)))?
+    {
+        file.write_all(&chunk)
+            .map_err(|e| (format!("Cannot write file {}: {}", file_path, e), None))?;
+    }
+
+    Ok(())
+}
+
 /// Execute a SELECT query
-async fn execute_select(client: &Client, sql: &str, start: Instant) -> QueryResult {
-    match client.query(sql, &[]).await {
+async fn execute_select(
+    client: &Client,
+    sql: &str,
+    params: &[&(dyn ToSql + Sync)],
+    start: Instant,
+) -> QueryResult {
+    match client.query(sql, params).await {
         Ok(rows) => {
             let duration_ms = start.elapsed().as_millis() as u64;
             
@@ -313,7 +849,7 @@ async fn execute_select(client: &Client, sql: &str, start: Instant) -> QueryResu
             }
             
             // Extract column information from the first row
-            let columns = extract_column_info(&rows[0]);
+            let columns = extract_column_info(rows[0].columns());
             
             // Convert rows to HashMap format
             let row_data = rows
@@ -325,28 +861,128 @@ async fn execute_select(client: &Client, sql: &str, start: Instant) -> QueryResu
         }
         Err(e) => {
             let duration_ms = start.elapsed().as_millis() as u64;
-            let error_position = extract_error_position(&e);
+            let error_position = extract_error_position(&e, sql);
             let error_message = format_error_message(&e);
             QueryResult::error(error_message, error_position, duration_ms)
         }
     }
 }
 
+/// Stream the rows of a SELECT query in batches
+///
+/// The query runs in a transaction through a portal that is read `batch_size` rows
+/// at a time, so only one batch is held in memory at a time. The first chunk
+/// carries the column information (and is sent even when the query returns no rows).
+/// Reading stops after `max_rows` rows; the server does not produce the remaining
+/// rows, and the summary reports `truncated`.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection (not inside a transaction)
+/// * `sql` - A single SELECT statement
+/// * `batch_size` - Number of rows per chunk
+/// * `max_rows` - Optional maximum number of rows to read
+/// * `on_chunk` - Receives each chunk; returning an error stops reading
+///
+/// # Returns
+/// * `Result<QueryStreamSummary, String>` - Row count and duration, or error message
+pub async fn stream_select<F>(
+    client: &mut Client,
+    sql: &str,
+    batch_size: usize,
+    max_rows: Option<u64>,
+    mut on_chunk: F,
+) -> Result<QueryStreamSummary, String>
+where
+    F: FnMut(QueryChunk) -> Result<(), String>,
+{
+    let start = Instant::now();
+    let sql = sql.trim();
+    
+    if batch_size == 0 {
+        return Err("Batch size must be greater than 0".to_string());
+    }
+    let statements = parse_sql_statements(sql);
+    if statements.len() != 1 || determine_query_type(statements[0]) != QueryResultType::Select {
+        return Err("Only a single SELECT statement can be streamed".to_string());
+    }
+    
+    // Portals only exist inside a transaction; dropping it without commit rolls back
+    let transaction = client.transaction().await.map_err(|e| format_error_message(&e))?;
+    let statement = transaction
+        .prepare(statements[0])
+        .await
+        .map_err(|e| format_error_message(&e))?;
+    let mut columns = Some(extract_column_info(statement.columns()));
+    let portal = transaction
+        .bind(&statement, &[])
+        .await
+        .map_err(|e| format_error_message(&e))?;
+    
+    let mut chunk_index = 0u64;
+    let mut total_rows = 0u64;
+    let mut truncated = false;
+    
+    loop {
+        let remaining = max_rows.map_or(u64::MAX, |max| max - total_rows.min(max));
+        if remaining == 0 {
+            // Ask for one more row only to tell whether the result was cut off
+            truncated = !transaction
+                .query_portal(&portal, 1)
+                .await
+                .map_err(|e| format_error_message(&e))?
+                .is_empty();
+            break;
+        }
+        let fetch = remaining.min(batch_size as u64).min(i32::MAX as u64) as i32;
+        let rows = transaction
+            .query_portal(&portal, fetch)
+            .await
+            .map_err(|e| format_error_message(&e))?;
+        let done = (rows.len() as i32) < fetch;
+        total_rows += rows.len() as u64;
+        
+        if !rows.is_empty() {
+            on_chunk(QueryChunk {
+                chunk_index,
+                columns: columns.take(),
+                rows: rows.iter().map(row_to_hashmap).collect(),
+            })?;
+            chunk_index += 1;
+        }
+        if done {
+            break;
+        }
+    }
+    
+    if columns.is_some() {
+        on_chunk(QueryChunk { chunk_index, columns, rows: Vec::new() })?;
+    }
+    
+    transaction.commit().await.map_err(|e| format_error_message(&e))?;
+    
+    Ok(QueryStreamSummary {
+        total_rows,
+        truncated,
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
 /// Execute a DML statement (INSERT, UPDATE, DELETE)
 async fn execute_dml(
     client: &Client,
     sql: &str,
+    params: &[&(dyn ToSql + Sync)],
     query_type: QueryResultType,
     start: Instant,
 ) -> QueryResult {
-    match client.execute(sql, &[]).await {
+    match client.execute(sql, params).await {
         Ok(affected_rows) => {
             let duration_ms = start.elapsed().as_millis() as u64;
             QueryResult::dml(query_type, affected_rows, duration_ms)
         }
         Err(e) => {
             let duration_ms = start.elapsed().as_millis() as u64;
-            let error_position = extract_error_position(&e);
+            let error_position = extract_error_position(&e, sql);
             let error_message = format_error_message(&e);
             QueryResult::error(error_message, error_position, duration_ms)
         }
@@ -354,24 +990,28 @@ async fn execute_dml(
 }
 
 /// Execute a DDL statement (CREATE, ALTER, DROP, etc.)
-async fn execute_ddl(client: &Client, sql: &str, start: Instant) -> QueryResult {
-    match client.execute(sql, &[]).await {
+async fn execute_ddl(
+    client: &Client,
+    sql: &str,
+    params: &[&(dyn ToSql + Sync)],
+    start: Instant,
+) -> QueryResult {
+    match client.execute(sql, params).await {
         Ok(_) => {
             let duration_ms = start.elapsed().as_millis() as u64;
             QueryResult::ddl(duration_ms)
         }
         Err(e) => {
             let duration_ms = start.elapsed().as_millis() as u64;
-            let error_position = extract_error_position(&e);
+            let error_position = extract_error_position(&e, sql);
             let error_message = format_error_message(&e);
             QueryResult::error(error_message, error_position, duration_ms)
         }
     }
 }
 
-/// Extract column information from a row
-fn extract_column_info(row: &Row) -> Vec<ColumnInfo> {
-    let columns = row.columns();
+/// Extract column information from the columns of a row or prepared statement
+fn extract_column_info(columns: &[Column]) -> Vec<ColumnInfo> {
     columns
         .iter()
         .map(|col| {
@@ -412,7 +1052,7 @@ fn format_type_name(pg_type: &Type) -> String {
 }
 
 /// Convert a PostgreSQL row to a HashMap
-fn row_to_hashmap(row: &Row) -> HashMap<String, serde_json::Value> {
+pub(crate) fn row_to_hashmap(row: &Row) -> HashMap<String, serde_json::Value> {
     let mut map = HashMap::new();
     
     for (idx, column) in row.columns().iter().enumerate() {
@@ -472,11 +1112,55 @@ fn row_to_hashmap(row: &Row) -> HashMap<String, serde_json::Value> {
                     .map(serde_json::Value::String)
                     .unwrap_or(serde_json::Value::Null)
             }
-            Type::TIMESTAMP | Type::TIMESTAMPTZ | Type::DATE | Type::TIME | Type::TIMETZ => {
-                row.try_get::<_, Option<String>>(idx)
+            // Dates and times as ISO 8601 strings
+            Type::TIMESTAMP => {
+                row.try_get::<_, Option<chrono::NaiveDateTime>>(idx)
                     .ok()
                     .flatten()
-                    .map(serde_json::Value::String)
+                    .map(|v| serde_json::Value::String(v.format("%Y-%m-%dT%H:%M:%S%.f").to_string()))
+                    .unwrap_or(serde_json::Value::Null)
+            }
+            Type::TIMESTAMPTZ => {
+                row.try_get::<_, Option<chrono::DateTime<chrono::Utc>>>(idx)
+                    .ok()
+                    .flatten()
+                    .map(|v| serde_json::Value::String(v.to_rfc3339()))
+                    .unwrap_or(serde_json::Value::Null)
+            }
+            Type::DATE => {
+                row.try_get::<_, Option<chrono::NaiveDate>>(idx)
+                    .ok()
+                    .flatten()
+                    .map(|v| serde_json::Value::String(v.to_string()))
+                    .unwrap_or(serde_json::Value::Null)
+            }
+            Type::TIME => {
+                row.try_get::<_, Option<chrono::NaiveTime>>(idx)
+                    .ok()
+                    .flatten()
+                    .map(|v| serde_json::Value::String(v.format("%H:%M:%S%.f").to_string()))
+                    .unwrap_or(serde_json::Value::Null)
+            }
+            Type::TIMETZ => {
+                row.try_get::<_, Option<TimeTzText>>(idx)
+                    .ok()
+                    .flatten()
+                    .map(|v| serde_json::Value::String(v.0))
+                    .unwrap_or(serde_json::Value::Null)
+            }
+            Type::INTERVAL => {
+                row.try_get::<_, Option<IntervalText>>(idx)
+                    .ok()
+                    .flatten()
+                    .map(|v| serde_json::Value::String(v.0))
+                    .unwrap_or(serde_json::Value::Null)
+            }
+            // Exact decimal text; a JSON number would lose precision
+            Type::NUMERIC => {
+                row.try_get::<_, Option<NumericText>>(idx)
+                    .ok()
+                    .flatten()
+                    .map(|v| serde_json::Value::String(v.0))
                     .unwrap_or(serde_json::Value::Null)
             }
             Type::UUID => {
@@ -487,11 +1171,9 @@ fn row_to_hashmap(row: &Row) -> HashMap<String, serde_json::Value> {
                     .unwrap_or(serde_json::Value::Null)
             }
             Type::JSON | Type::JSONB => {
-                // For JSON types, get as string and parse
-                row.try_get::<_, Option<String>>(idx)
+                row.try_get::<_, Option<serde_json::Value>>(idx)
                     .ok()
                     .flatten()
-                    .and_then(|s| serde_json::from_str(&s).ok())
                     .unwrap_or(serde_json::Value::Null)
             }
             _ => {
@@ -510,11 +1192,162 @@ fn row_to_hashmap(row: &Row) -> HashMap<String, serde_json::Value> {
     map
 }
 
+/// Read a big-endian integer of `N` bytes from the front of `raw`
+fn take_be<const N: usize>(raw: &mut &[u8]) -> Result<[u8; N], Box<dyn std::error::Error + Sync + Send>> {
+    if raw.len() < N {
+        return Err("unexpected end of binary value".into());
+    }
+    let (head, rest) = raw.split_at(N);
+    *raw = rest;
+    Ok(head.try_into()?)
+}
+
+/// `numeric` value as its exact decimal text (e.g. `-12.50`, `NaN`)
+struct NumericText(String);
+
+impl<'a> FromSql<'a> for NumericText {
+    fn from_sql(_: &Type, mut raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let ndigits = i16::from_be_bytes(take_be(&mut raw)?).max(0) as usize;
+        let weight = i16::from_be_bytes(take_be(&mut raw)?);
+        let sign = u16::from_be_bytes(take_be(&mut raw)?);
+        let dscale = u16::from_be_bytes(take_be(&mut raw)?) as usize;
+        let digits = (0..ndigits)
+            .map(|_| take_be(&mut raw).map(i16::from_be_bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(NumericText(format_numeric(&digits, weight, sign, dscale)))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::NUMERIC
+    }
+}
+
+/// Format the base-10000 digits of a binary `numeric`
+///
+/// `weight` is the power of 10000 of the first digit and `dscale` the number of
+/// decimal places to display.
+fn format_numeric(digits: &[i16], weight: i16, sign: u16, dscale: usize) -> String {
+    match sign {
+        0xC000 => return "NaN".to_string(),
+        0xD000 => return "Infinity".to_string(),
+        0xF000 => return "-Infinity".to_string(),
+        _ => {}
+    }
+    let digit = |i: i32| usize::try_from(i).ok().and_then(|i| digits.get(i)).copied().unwrap_or(0);
+    let weight = weight as i32;
+
+    let mut text = String::new();
+    if sign == 0x4000 {
+        text.push('-');
+    }
+    if weight < 0 {
+        text.push('0');
+    } else {
+        text.push_str(&digit(0).to_string());
+        for i in 1..=weight {
+            text.push_str(&format!("{:04}", digit(i)));
+        }
+    }
+
+    if dscale > 0 {
+        let mut fraction = String::new();
+        let mut i = weight + 1;
+        while fraction.len() < dscale {
+            fraction.push_str(&format!("{:04}", digit(i)));
+            i += 1;
+        }
+        fraction.truncate(dscale);
+        text.push('.');
+        text.push_str(&fraction);
+    }
+    text
+}
+
+/// `interval` value in PostgreSQL's default output style (e.g. `1 year 2 mons 3 days 04:05:06`)
+struct IntervalText(String);
+
+impl<'a> FromSql<'a> for IntervalText {
+    fn from_sql(_: &Type, mut raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let microseconds = i64::from_be_bytes(take_be(&mut raw)?);
+        let days = i32::from_be_bytes(take_be(&mut raw)?);
+        let months = i32::from_be_bytes(take_be(&mut raw)?);
+        Ok(IntervalText(format_interval(months, days, microseconds)))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::INTERVAL
+    }
+}
+
+/// Format an interval's months, days and microseconds like PostgreSQL's `postgres` style
+fn format_interval(months: i32, days: i32, microseconds: i64) -> String {
+    let unit = |value: i32, name: &str| format!("{} {}{}", value, name, if value == 1 { "" } else { "s" });
+
+    let mut parts = Vec::new();
+    if months / 12 != 0 {
+        parts.push(unit(months / 12, "year"));
+    }
+    if months % 12 != 0 {
+        parts.push(unit(months % 12, "mon"));
+    }
+    if days != 0 {
+        parts.push(unit(days, "day"));
+    }
+    if microseconds != 0 || parts.is_empty() {
+        let sign = if microseconds < 0 { "-" } else { "" };
+        parts.push(format!("{}{}", sign, format_clock(microseconds.unsigned_abs())));
+    }
+    parts.join(" ")
+}
+
+/// Format microseconds as `HH:MM:SS[.ffffff]` without trailing zeros in the fraction
+fn format_clock(microseconds: u64) -> String {
+    let seconds = microseconds / 1_000_000;
+    let mut text = format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let fraction = microseconds % 1_000_000;
+    if fraction != 0 {
+        text.push_str(format!(".{:06}", fraction).trim_end_matches('0'));
+    }
+    text
+}
+
+/// `time with time zone` value as text (e.g. `04:05:06+05:30`)
+struct TimeTzText(String);
+
+impl<'a> FromSql<'a> for TimeTzText {
+    fn from_sql(_: &Type, mut raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let microseconds = i64::from_be_bytes(take_be(&mut raw)?);
+        // Seconds west of UTC
+        let zone = i32::from_be_bytes(take_be(&mut raw)?);
+        Ok(TimeTzText(format_time_tz(microseconds, zone)))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::TIMETZ
+    }
+}
+
+/// Format a time of day and a zone given in seconds west of UTC
+fn format_time_tz(microseconds: i64, zone: i32) -> String {
+    let offset = -zone;
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    let mut text = format!("{}{}{:02}", format_clock(microseconds.max(0) as u64), sign, offset / 3600);
+    if !offset.is_multiple_of(3600) {
+        text.push_str(&format!(":{:02}", offset / 60 % 60));
+        if !offset.is_multiple_of(60) {
+            text.push_str(&format!(":{:02}", offset % 60));
+        }
+    }
+    text
+}
+
 /// Extract error position from PostgreSQL error
 /// 
 /// PostgreSQL provides error position in the POSITION field of the error.
-/// This function extracts the character position and converts it to line and column numbers.
-fn extract_error_position(error: &tokio_postgres::Error) -> Option<ErrorPosition> {
+/// This function extracts the character position and converts it to line and column numbers
+/// within `sql`, the statement that was sent to the server.
+fn extract_error_position(error: &tokio_postgres::Error, sql: &str) -> Option<ErrorPosition> {
     if let Some(db_error) = error.as_db_error() {
         // PostgreSQL provides position as a character offset from the start of the query
         // The position() method returns an ErrorPosition enum which can be Original or Internal
@@ -524,12 +1357,11 @@ fn extract_error_position(error: &tokio_postgres::Error) -> Option<ErrorPosition
             // - Internal { position: u32, query: String }: position in an internal query
             match position {
                 tokio_postgres::error::ErrorPosition::Original(pos) => {
-                    // For now, return line 1 with the character position as column
-                    // A more sophisticated implementation would need the original SQL
-                    // to calculate actual line and column numbers
-                    return Some(ErrorPosition::new(1, *pos as usize));
+                    return Some(line_column_at(sql, *pos as usize));
                 }
                 tokio_postgres::error::ErrorPosition::Internal { position, .. } => {
+                    // The position refers to a query generated by the server (e.g. a
+                    // function body), not to the text the user wrote
                     return Some(ErrorPosition::new(1, *position as usize));
                 }
             }
@@ -540,7 +1372,7 @@ fn extract_error_position(error: &tokio_postgres::Error) -> Option<ErrorPosition
         let message = db_error.message();
         if let Some(pos_str) = message.split("at character ").nth(1) {
             if let Ok(position) = pos_str.split_whitespace().next().unwrap_or("0").parse::<usize>() {
-                return Some(ErrorPosition::new(1, position));
+                return Some(line_column_at(sql, position));
             }
         }
     }
@@ -548,11 +1380,75 @@ fn extract_error_position(error: &tokio_postgres::Error) -> Option<ErrorPosition
     None
 }
 
+/// Byte offset in `sql` of a 1-based line and column, the inverse of `line_column_at`
+fn byte_offset_at(sql: &str, position: &ErrorPosition) -> usize {
+    let mut line = 1;
+    let mut column = 1;
+
+    for (index, c) in sql.char_indices() {
+        if line == position.line && column == position.column {
+            return index;
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+
+    sql.len()
+}
+
+/// Convert a 1-based character position in `sql` to a 1-based line and column
+///
+/// PostgreSQL counts positions in characters, not bytes, so multibyte characters
+/// (e.g. Chinese in comments or string literals) count as one. Columns are counted
+/// in characters as well. A position past the end points just after the last character.
+fn line_column_at(sql: &str, position: usize) -> ErrorPosition {
+    let mut line = 1;
+    let mut column = 1;
+    
+    for (index, (_, c)) in sql.char_indices().enumerate() {
+        if index + 1 >= position {
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    
+    ErrorPosition::new(line, column)
+}
+
+/// Make an error position within `statement` relative to `sql`, which contains it
+///
+/// `statement` must be a slice of `sql`.
+fn shift_error_position(sql: &str, statement: &str, position: ErrorPosition) -> ErrorPosition {
+    let offset = (statement.as_ptr() as usize).saturating_sub(sql.as_ptr() as usize).min(sql.len());
+    let Some(preceding) = sql.get(..offset) else {
+        return position;
+    };
+    
+    let lines_before = preceding.matches('\n').count();
+    if position.line > 1 {
+        return ErrorPosition::new(position.line + lines_before, position.column);
+    }
+    
+    // On the statement's first line, the column also moves by the text before it
+    let line_start = preceding.rfind('\n').map_or(0, |i| i + 1);
+    let columns_before = preceding[line_start..].chars().count();
+    ErrorPosition::new(1 + lines_before, position.column + columns_before)
+}
+
 /// Convert PostgreSQL error to user-friendly message
 /// 
 /// This function translates PostgreSQL error codes into more understandable messages
 /// for end users, while preserving the original technical error for debugging.
-fn format_error_message(error: &tokio_postgres::Error) -> String {
+pub(crate) fn format_error_message(error: &tokio_postgres::Error) -> String {
     if let Some(db_error) = error.as_db_error() {
         let code = db_error.code().code();
         let original_message = db_error.message();
@@ -634,6 +1530,41 @@ fn format_error_message(error: &tokio_postgres::Error) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_cast_parameters() {
+        let sql = "SELECT * FROM t WHERE id = $1 AND name = $2 AND note <> '$1' AND born > $3 -- $2";
+        assert_eq!(
+            cast_parameters(sql, &[Type::INT4, Type::TEXT, Type::DATE]).0,
+            "SELECT * FROM t WHERE id = CAST($1::text AS \"pg_catalog\".\"int4\") AND name = $2::text \
+             AND note <> '$1' AND born > CAST($3::text AS \"pg_catalog\".\"date\") -- $2"
+        );
+
+        // A placeholder used twice is cast twice; unknown indexes are left alone
+        assert_eq!(
+            cast_parameters("SELECT $1 + $1, $0, $$x$$", &[Type::INT8]).0,
+            "SELECT CAST($1::text AS \"pg_catalog\".\"int8\") + CAST($1::text AS \"pg_catalog\".\"int8\"), $0, $$x$$"
+        );
+    }
+
+    #[test]
+    fn test_original_error_position_after_parameters() {
+        let sql = "SELECT $1 AS a, $2 AS b,\n       $1 + bogus";
+        let (rewritten, replacements) = cast_parameters(sql, &[Type::INT4, Type::TEXT]);
+        let at = |text: &str, needle: &str| line_column_at(text, text[..text.rfind(needle).unwrap()].chars().count() + 1);
+        let map = |position: ErrorPosition| original_error_position(&position, sql, &rewritten, &replacements);
+
+        let position = map(at(&rewritten, "bogus"));
+        assert_eq!((position.line, position.column), (2, 13));
+        let position = map(at(&rewritten, "AS b"));
+        assert_eq!((position.line, position.column), (1, 20));
+
+        // Inside a replacement, before any placeholder
+        let position = map(at(&rewritten, "::text AS \"pg_catalog\".\"int4\") + bogus"));
+        assert_eq!((position.line, position.column), (2, 8));
+        let position = map(at(&rewritten, "SELECT"));
+        assert_eq!((position.line, position.column), (1, 1));
+    }
+
     #[test]
     fn test_determine_query_type() {
         assert_eq!(
@@ -672,6 +1603,38 @@ mod tests {
             determine_query_type("WITH cte AS (SELECT 1) SELECT * FROM cte"),
             QueryResultType::Select
         );
+        assert_eq!(
+            determine_query_type("EXPLAIN (ANALYZE, BUFFERS) SELECT * FROM users"),
+            QueryResultType::Select
+        );
+        assert_eq!(
+            determine_query_type("explain analyze verbose select 1"),
+            QueryResultType::Select
+        );
+        assert_eq!(
+            determine_query_type("EXPLAIN ANALYZE DELETE FROM users"),
+            QueryResultType::Error
+        );
+        assert_eq!(
+            determine_query_type("COPY users FROM STDIN"),
+            QueryResultType::Copy
+        );
+        assert_eq!(
+            determine_query_type("SAVEPOINT before_update"),
+            QueryResultType::Ddl
+        );
+        assert_eq!(
+            determine_query_type("rollback to savepoint before_update"),
+            QueryResultType::Ddl
+        );
+        assert_eq!(
+            determine_query_type("ROLLBACK"),
+            QueryResultType::Error
+        );
+        assert_eq!(
+            determine_query_type("DO $$ BEGIN RAISE NOTICE 'hello'; END $$"),
+            QueryResultType::Ddl
+        );
     }
 
     #[test]
@@ -792,6 +1755,40 @@ mod tests {
         assert!(statements[1].contains("INSERT INTO"));
     }
 
+    #[test]
+    fn test_parse_sql_statements_dollar_quoted_body() {
+        let sql = "DO $$ BEGIN RAISE NOTICE 'a'; RAISE NOTICE 'b'; END $$; \
+                   CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql; \
+                   SELECT 1; -- trailing comment";
+        let statements = parse_sql_statements(sql);
+        assert_eq!(
+            statements,
+            vec![
+                "DO $$ BEGIN RAISE NOTICE 'a'; RAISE NOTICE 'b'; END $$",
+                "CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql",
+                "SELECT 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_numeric_interval_and_timetz() {
+        // 12.50: one base-10000 digit before the point, 5000 after
+        assert_eq!(format_numeric(&[12, 5000], 0, 0, 2), "12.50");
+        assert_eq!(format_numeric(&[1], -1, 0x4000, 4), "-0.0001");
+        assert_eq!(format_numeric(&[1], 1, 0, 0), "10000");
+        assert_eq!(format_numeric(&[], 0, 0, 0), "0");
+        assert_eq!(format_numeric(&[], 0, 0xC000, 0), "NaN");
+
+        assert_eq!(format_interval(14, 3, 14_706_500_000), "1 year 2 mons 3 days 04:05:06.5");
+        assert_eq!(format_interval(0, -1, 0), "-1 days");
+        assert_eq!(format_interval(0, 0, 0), "00:00:00");
+        assert_eq!(format_interval(0, 0, -1_250_000), "-00:00:01.25");
+
+        assert_eq!(format_time_tz(14_706_000_000, -19_800), "04:05:06+05:30");
+        assert_eq!(format_time_tz(0, 28_800), "00:00:00-08");
+    }
+
     #[test]
     fn test_format_error_message_unique_constraint() {
         // Test that error code mapping exists for common PostgreSQL errors
@@ -817,4 +1814,154 @@ mod tests {
         assert_eq!(pos.line, 5);
         assert_eq!(pos.column, 10);
     }
+
+    #[test]
+    fn test_line_column_after_multibyte_characters() {
+        // "FRM" is character 30: the comment and literal hold multibyte characters,
+        // so its byte offset is much larger
+        let sql = "-- 查询用户\nSELECT '你好，世界' AS 问候 FRM users";
+        let position = line_column_at(sql, 30);
+        assert_eq!(sql.chars().nth(29), Some('F'));
+        assert_eq!(position.line, 2);
+        assert_eq!(position.column, 22);
+
+        let start = line_column_at(sql, 1);
+        assert_eq!((start.line, start.column), (1, 1));
+
+        // Past the end: just after the last character
+        let end = line_column_at("SELECT 'é'", 100);
+        assert_eq!((end.line, end.column), (1, 11));
+    }
+
+    #[test]
+    fn test_line_column_on_second_line() {
+        // PostgreSQL reports "FRM" at character 19
+        let sql = "SELECT id, name\n  FRM users\nWHERE id = 1";
+        let position = line_column_at(sql, 19);
+        assert_eq!((position.line, position.column), (2, 3));
+
+        // The newline itself belongs to the line it ends
+        let newline = line_column_at(sql, 16);
+        assert_eq!((newline.line, newline.column), (1, 16));
+
+        let third = line_column_at(sql, 29);
+        assert_eq!((third.line, third.column), (3, 1));
+
+        // Offsets past the end are clamped to the end of the last line
+        let end = line_column_at(sql, 1000);
+        assert_eq!((end.line, end.column), (3, 13));
+    }
+
+    #[test]
+    fn test_parse_sql_statements_multibyte() {
+        let sql = "INSERT INTO 日志 VALUES ('完成；'); SELECT '中文' FROM t";
+        let statements = parse_sql_statements(sql);
+        assert_eq!(statements, vec!["INSERT INTO 日志 VALUES ('完成；')", "SELECT '中文' FROM t"]);
+
+        let position = shift_error_position(sql, statements[1], ErrorPosition::new(1, 13));
+        assert_eq!((position.line, position.column), (1, 44));
+
+        let sql = "SELECT '一';\nSELECT\n  x FRM t";
+        let statements = parse_sql_statements(sql);
+        let position = shift_error_position(sql, statements[1], ErrorPosition::new(2, 5));
+        assert_eq!((position.line, position.column), (3, 5));
+    }
+    #[test]
+    fn test_find_database_qualified_names() {
+        let names = find_database_qualified_names("SELECT * FROM otherdb.public.users");
+        assert_eq!(
+            names,
+            vec![DatabaseQualifiedName {
+                database: "otherdb".to_string(),
+                name: "otherdb.public.users".to_string(),
+                position: 15,
+            }]
+        );
+
+        let names = find_database_qualified_names(
+            "SELECT * FROM public.users u JOIN Sales.Public.Orders o ON o.user_id = u.id",
+        );
+        assert_eq!(names.len(), 1);
+        assert_eq!(names[0].database, "sales");
+        assert_eq!(names[0].name, "Sales.Public.Orders");
+
+        let names = find_database_qualified_names("SELECT * FROM \"My DB\".public.t");
+        assert_eq!(names[0].database, "My DB");
+
+        // Comma-separated FROM list, subquery, and multiple statements
+        assert_eq!(find_database_qualified_names("SELECT * FROM a, db2.public.t").len(), 1);
+        assert_eq!(find_database_qualified_names("SELECT * FROM (SELECT * FROM db2.s.t) x").len(), 1);
+        assert_eq!(
+            find_database_qualified_names("INSERT INTO db2.public.t VALUES (1); UPDATE db3.public.t SET a = 1").len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_find_database_qualified_names_ignores_other_three_part_names() {
+        let queries = [
+            // schema.table.column references
+            "SELECT public.users.id FROM public.users",
+            "SELECT * FROM users WHERE public.users.id = 1",
+            // Function arguments
+            "SELECT EXTRACT(YEAR FROM public.events.created_at) FROM public.events",
+            // Strings, comments, dollar quotes
+            "SELECT 'FROM a.b.c' -- FROM x.y.z",
+            "SELECT 1 /* FROM a.b.c */",
+            "DO $$ BEGIN PERFORM 1 FROM db2.s.t; END $$",
+            "SELECT E'it\\'s FROM a.b.c' FROM t",
+            // Four-part names are a different error
+            "SELECT * FROM a.b.c.d",
+        ];
+
+        for sql in queries {
+            assert!(find_database_qualified_names(sql).is_empty(), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_find_server_copies_file() {
+        let copies = find_server_copies("COPY users (id, name) FROM '/tmp/users.csv' WITH (FORMAT csv, HEADER)");
+        assert_eq!(copies.len(), 1);
+        assert_eq!(copies[0].direction, CopyDirection::From);
+        assert_eq!(copies[0].target, ServerCopyTarget::File);
+        assert_eq!(copies[0].location, "/tmp/users.csv");
+        assert_eq!(copies[0].position, 28);
+        assert!(copies[0].warning.contains("database server"));
+        assert_eq!(
+            copies[0].client_sql.as_deref(),
+            Some("COPY users (id, name) FROM STDIN WITH (FORMAT csv, HEADER)")
+        );
+
+        let copies = find_server_copies("SELECT 1; copy (SELECT * FROM users) to E'C:\\\\out\\\\it''s.csv' csv;");
+        assert_eq!(copies.len(), 1);
+        assert_eq!(copies[0].direction, CopyDirection::To);
+        assert_eq!(copies[0].location, "C:\\out\\it's.csv");
+        assert_eq!(copies[0].client_sql.as_deref(), Some("copy (SELECT * FROM users) to STDOUT csv"));
+    }
+
+    #[test]
+    fn test_find_server_copies_program() {
+        let copies = find_server_copies("COPY logs FROM PROGRAM 'gunzip -c /var/log/app.gz'");
+        assert_eq!(copies.len(), 1);
+        assert_eq!(copies[0].target, ServerCopyTarget::Program);
+        assert_eq!(copies[0].location, "gunzip -c /var/log/app.gz");
+        assert_eq!(copies[0].position, 16);
+        assert!(copies[0].warning.contains("gunzip -c /var/log/app.gz"));
+        assert!(copies[0].client_sql.is_none());
+    }
+
+    #[test]
+    fn test_find_server_copies_ignores_client_copies() {
+        let queries = [
+            "COPY users FROM STDIN",
+            "COPY (SELECT * FROM users WHERE name = 'TO ''/tmp/x''') TO STDOUT WITH (FORMAT csv)",
+            "SELECT 'COPY users FROM ''/tmp/x'''",
+            "-- COPY users FROM '/tmp/x'\nSELECT 1",
+        ];
+
+        for sql in queries {
+            assert!(find_server_copies(sql).is_empty(), "{}", sql);
+        }
+    }
 }
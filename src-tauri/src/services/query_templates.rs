@@ -0,0 +1,350 @@
+/**
+ * Query Template Service
+ *
+ * This module saves reusable parameterized queries and runs them:
+ * - Templates use named placeholders (`:name`) and are stored in a local JSON file
+ * - When a template is run, each placeholder becomes a bind parameter (`$1`, `$2`, ...);
+ *   values are never interpolated into the SQL text
+ * - Parameter types are inferred by the server when the statement is prepared, and
+ *   values are bound as text with an explicit CAST to that type
+ * - Every placeholder must have a value before the template is executed
+ */
+
+use crate::models::query::QueryResult;
+use crate::services::query_executor::{self, cast_type_name};
+use crate::services::record_writer::json_to_text;
+use crate::services::sql_lexer::{tokenize, TokenKind};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio_postgres::types::{ToSql, Type};
+use tokio_postgres::Client;
+
+/// A saved query template
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueryTemplate {
+    /// Template id
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// SQL with `:name` placeholders
+    pub sql: String,
+    /// Placeholder names in order of first appearance
+    pub parameters: Vec<String>,
+    /// When the template was created
+    pub created_at: String,
+    /// When the template was last changed
+    pub updated_at: String,
+}
+
+/// Templates persisted as a JSON array in a single file
+#[derive(Debug, Clone)]
+pub struct QueryTemplateStore {
+    path: PathBuf,
+}
+
+impl QueryTemplateStore {
+    /// Create a store backed by the given file (created on first save)
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Load all templates; a missing file means no templates yet
+    pub fn load(&self) -> Result<Vec<QueryTemplate>, String> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read query template file {}: {}", self.path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Query template file {} is malformed: {}", self.path.display(), e))
+    }
+
+    /// Replace the stored templates
+    pub fn save(&self, templates: &[QueryTemplate]) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(templates)
+            .map_err(|e| format!("Failed to serialize query templates: {}", e))?;
+        std::fs::write(&self.path, contents)
+            .map_err(|e| format!("Failed to write query template file {}: {}", self.path.display(), e))
+    }
+
+    /// Find a template by id
+    pub fn get(&self, id: &str) -> Result<QueryTemplate, String> {
+        self.load()?
+            .into_iter()
+            .find(|template| template.id == id)
+            .ok_or_else(|| format!("Query template {} not found", id))
+    }
+
+    /// Create a template, or update it when `id` refers to an existing one
+    ///
+    /// The placeholders are parsed when saving, so invalid templates are rejected early.
+    pub fn upsert(&self, id: Option<&str>, name: &str, sql: &str) -> Result<QueryTemplate, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Template name cannot be empty".to_string());
+        }
+        let parameters = parameter_names(sql)?;
+
+        let mut templates = self.load()?;
+        let now = Local::now().to_rfc3339();
+
+        let template = match id {
+            Some(id) => {
+                let template = templates
+                    .iter_mut()
+                    .find(|template| template.id == id)
+                    .ok_or_else(|| format!("Query template {} not found", id))?;
+                template.name = name.to_string();
+                template.sql = sql.to_string();
+                template.parameters = parameters;
+                template.updated_at = now;
+                template.clone()
+            }
+            None => {
+                let template = QueryTemplate {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name: name.to_string(),
+                    sql: sql.to_string(),
+                    parameters,
+                    created_at: now.clone(),
+                    updated_at: now,
+                };
+                templates.push(template.clone());
+                template
+            }
+        };
+
+        self.save(&templates)?;
+        Ok(template)
+    }
+
+    /// Delete a template
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        let mut templates = self.load()?;
+        let count = templates.len();
+        templates.retain(|template| template.id != id);
+        if templates.len() == count {
+            return Err(format!("Query template {} not found", id));
+        }
+        self.save(&templates)
+    }
+}
+
+/// A `:name` placeholder and its byte range (including the colon)
+#[derive(Debug, Clone, PartialEq)]
+struct Placeholder {
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+/// Find the `:name` placeholders in a template
+///
+/// Casts (`::type`), assignments (`:=`) and array slices with numeric bounds (`[1:2]`)
+/// are not placeholders.
+/// Placeholders inside strings and comments are ignored. Positional parameters (`$1`)
+/// can't be mixed with named ones.
+fn find_placeholders(sql: &str) -> Result<Vec<Placeholder>, String> {
+    let tokens = tokenize(sql);
+    let mut placeholders = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        let text = token.text(sql);
+        if token.kind == TokenKind::Literal && text.len() > 1 && text.starts_with('$') && text.as_bytes()[1].is_ascii_digit() {
+            return Err(format!("Positional parameter {} is not allowed in templates; use :name parameters", text));
+        }
+
+        if token.kind != TokenKind::Symbol(':') {
+            continue;
+        }
+        let after_colon = i > 0 && tokens[i - 1].kind == TokenKind::Symbol(':') && tokens[i - 1].end == token.start;
+        if after_colon {
+            continue;
+        }
+        if let Some(next) = tokens.get(i + 1) {
+            if next.kind == TokenKind::Word && next.start == token.end {
+                placeholders.push(Placeholder {
+                    name: next.text(sql).to_string(),
+                    start: token.start,
+                    end: next.end,
+                });
+            }
+        }
+    }
+
+    Ok(placeholders)
+}
+
+/// Distinct placeholder names of a template, in order of first appearance
+///
+/// # Arguments
+/// * `sql` - SQL with `:name` placeholders
+///
+/// # Returns
+/// * `Result<Vec<String>, String>` - Placeholder names, or error message
+pub fn parameter_names(sql: &str) -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = Vec::new();
+    for placeholder in find_placeholders(sql)? {
+        if !names.contains(&placeholder.name) {
+            names.push(placeholder.name);
+        }
+    }
+    Ok(names)
+}
+
+/// Replace each placeholder with the text produced for its parameter index (1-based)
+fn render(sql: &str, placeholders: &[Placeholder], names: &[String], parameter: impl Fn(usize) -> String) -> String {
+    let mut rendered = String::with_capacity(sql.len());
+    let mut last = 0;
+    for placeholder in placeholders {
+        let index = names.iter().position(|name| *name == placeholder.name).unwrap_or(0) + 1;
+        rendered.push_str(&sql[last..placeholder.start]);
+        rendered.push_str(&parameter(index));
+        last = placeholder.end;
+    }
+    rendered.push_str(&sql[last..]);
+    rendered
+}
+
+/// A template with its placeholders replaced by bind parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundTemplate {
+    /// SQL with `$1`, `$2`, ... in place of the placeholders
+    pub sql: String,
+    /// Parameter names, the n-th name is bound to `$n`
+    pub names: Vec<String>,
+    /// Text value of each parameter (None for SQL NULL)
+    pub values: Vec<Option<String>>,
+}
+
+/// Replace the named placeholders of a template with `$n` bind parameters
+///
+/// A placeholder used several times is bound to the same parameter. Every
+/// placeholder must have a value in `params`; JSON null binds SQL NULL.
+///
+/// # Arguments
+/// * `sql` - SQL with `:name` placeholders
+/// * `params` - Parameter name -> value
+///
+/// # Returns
+/// * `Result<BoundTemplate, String>` - SQL with bind parameters and the values, or error message
+pub fn bind_parameters(sql: &str, params: &HashMap<String, serde_json::Value>) -> Result<BoundTemplate, String> {
+    let placeholders = find_placeholders(sql)?;
+    let names = parameter_names(sql)?;
+
+    let missing: Vec<&str> = names
+        .iter()
+        .filter(|name| !params.contains_key(*name))
+        .map(|name| name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Missing parameter values: {}", missing.join(", ")));
+    }
+
+    Ok(BoundTemplate {
+        sql: render(sql, &placeholders, &names, |index| format!("${}", index)),
+        values: names.iter().map(|name| json_to_text(&params[name])).collect(),
+        names,
+    })
+}
+
+/// Run a template with the given parameter values
+///
+/// The statement is first prepared to let the server infer each parameter's type;
+/// it then runs with every value bound as text and cast to that type, so strings
+/// such as dates, uuids and numerics work without client-side conversion.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `template` - Template to run
+/// * `params` - Parameter name -> value
+///
+/// # Returns
+/// * `Result<QueryResult, String>` - Query result, or error message when the
+///   parameters are invalid or the statement can't be prepared
+pub async fn run_template(
+    client: &Client,
+    template: &QueryTemplate,
+    params: &HashMap<String, serde_json::Value>,
+) -> Result<QueryResult, String> {
+    let bound = bind_parameters(&template.sql, params)?;
+
+    let statement = client
+        .prepare(&bound.sql)
+        .await
+        .map_err(|e| match e.as_db_error() {
+            Some(db_error) => format!("Invalid template SQL: {}", db_error.message()),
+            None => format!("Invalid template SQL: {}", e),
+        })?;
+    let types: Vec<Type> = statement.params().to_vec();
+
+    let placeholders = find_placeholders(&template.sql)?;
+    let sql = render(&template.sql, &placeholders, &bound.names, |index| match types.get(index - 1) {
+        Some(ty) if *ty != Type::TEXT && *ty != Type::UNKNOWN => {
+            format!("CAST(${}::text AS {})", index, cast_type_name(ty))
+        }
+        _ => format!("${}::text", index),
+    });
+
+    let values: Vec<&(dyn ToSql + Sync)> = bound
+        .values
+        .iter()
+        .map(|v| v as &(dyn ToSql + Sync))
+        .collect();
+
+    Ok(query_executor::execute_with_params(client, &sql, &values).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_placeholders() {
+        let names = parameter_names(
+            "SELECT id::text, :min_age FROM users WHERE age >= :min_age AND name = :name \
+             AND tags[1:2] = '{}' AND note <> ':skip' -- :comment",
+        )
+        .unwrap();
+        assert_eq!(names, vec!["min_age", "name"]);
+
+        assert!(parameter_names("SELECT * FROM users WHERE id = $1").is_err());
+        assert!(parameter_names("SELECT f(a := 1)").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bind_two_named_parameters() {
+        let mut params = HashMap::new();
+        params.insert("dept".to_string(), serde_json::json!("Sales"));
+        params.insert("min_salary".to_string(), serde_json::json!(5000));
+
+        let bound = bind_parameters(
+            "SELECT * FROM employees WHERE dept = :dept AND salary > :min_salary OR manager_dept = :dept",
+            &params,
+        )
+        .unwrap();
+
+        assert_eq!(
+            bound.sql,
+            "SELECT * FROM employees WHERE dept = $1 AND salary > $2 OR manager_dept = $1"
+        );
+        assert_eq!(bound.names, vec!["dept", "min_salary"]);
+        assert_eq!(bound.values, vec![Some("Sales".to_string()), Some("5000".to_string())]);
+    }
+
+    #[test]
+    fn test_bind_parameters_requires_every_value() {
+        let mut params = HashMap::new();
+        params.insert("dept".to_string(), serde_json::Value::Null);
+
+        let error = bind_parameters("SELECT * FROM t WHERE a = :dept AND b = :from_date AND c = :to_date", &params)
+            .unwrap_err();
+        assert_eq!(error, "Missing parameter values: from_date, to_date");
+
+        let bound = bind_parameters("SELECT * FROM t WHERE a IS NOT DISTINCT FROM :dept", &params).unwrap();
+        assert_eq!(bound.values, vec![None]);
+    }
+}
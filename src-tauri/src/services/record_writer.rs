@@ -0,0 +1,420 @@
+/**
+ * Record Writer Service
+ *
+ * 此模块负责将记录表单中的数据写入表中：
+ * - 插入、按主键更新和删除单条记录，表名始终带 schema 限定
+ * - 按主键更新或删除时没有匹配的记录会报错，提示主键可能已过期
+ * - 没有主键的表可以用 `{"ctid": "(页号,行号)"}` 定位一行；未提供任何主键时拒绝修改，避免影响整张表
+ * - 所有值以文本参数绑定，并显式转换为目标列的类型（uuid、date、inet、numeric 等）
+ * - 缓存每张表的列类型，避免每次插入都查询系统目录
+ */
+
+use crate::services::schema_service::get_writable_columns;
+use crate::services::sql_utils::{qualified_name, quote_identifier};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::Client;
+
+/// 行的物理位置（系统列），用于定位没有主键的表中的行
+pub(crate) const CTID_COLUMN: &str = "ctid";
+
+/// 未提供主键时的错误信息
+const NO_PRIMARY_KEY_ERROR: &str =
+    "表没有主键，拒绝修改：请传入主键列的值，或以 {\"ctid\": \"(页号,行号)\"} 定位要修改的行";
+
+/// 表的列类型缓存，键为 (数据库, schema, 表)
+///
+/// 表结构被修改后应调用 `invalidate`，以便下次插入时重新读取列类型。
+/// 在其他地方添加的列（例如在 SQL 编辑器中执行 `ALTER TABLE`）在第一次用到时重新读取。
+#[derive(Default)]
+pub struct ColumnTypeCache {
+    tables: HashMap<(String, String, String), HashMap<String, String>>,
+}
+
+impl ColumnTypeCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取表的列类型（列名 -> `format_type` 类型），未缓存时从数据库读取
+    ///
+    /// 缓存中缺少 `columns` 中的某一列时重新读取一次，列确实不存在时由调用方报错。
+    pub async fn get_column_types(
+        &mut self,
+        client: &Client,
+        database: &str,
+        schema: &str,
+        table: &str,
+        columns: &[&str],
+    ) -> Result<&HashMap<String, String>, String> {
+        let key = (database.to_string(), schema.to_string(), table.to_string());
+
+        let stale = self.tables.get(&key).is_none_or(|types| {
+            columns
+                .iter()
+                .any(|name| *name != CTID_COLUMN && !types.contains_key(*name))
+        });
+        if stale {
+            let columns = get_writable_columns(client, schema, table).await?;
+            if columns.is_empty() {
+                return Err(format!("表 {}.{} 不存在", schema, table));
+            }
+
+            let types = columns
+                .into_iter()
+                .map(|c| (c.name, c.data_type))
+                .collect();
+            self.tables.insert(key.clone(), types);
+        }
+
+        Ok(&self.tables[&key])
+    }
+
+    /// 移除表的缓存列类型
+    pub fn invalidate(&mut self, database: &str, schema: &str, table: &str) {
+        self.tables
+            .remove(&(database.to_string(), schema.to_string(), table.to_string()));
+    }
+
+    /// 移除某个数据库中所有表的缓存列类型
+    pub fn invalidate_database(&mut self, database: &str) {
+        self.tables.retain(|(db, _, _), _| db != database);
+    }
+
+    /// 移除某个模式下所有表的缓存列类型
+    pub fn invalidate_schema(&mut self, database: &str, schema: &str) {
+        self.tables
+            .retain(|(db, sch, _), _| db != database || sch != schema);
+    }
+}
+
+/// 插入一条记录
+///
+/// 每个值都以文本参数绑定，并通过 `CAST($n::text AS <列类型>)` 转换为列的实际类型，
+/// 因此字符串形式的 uuid、日期、网络地址、数值等都能可靠地插入。
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `cache` - 列类型缓存（只在读取列类型时加锁，执行语句前释放）
+/// * `database` - 数据库名称（用作缓存键）
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `data` - 列名 -> 值
+///
+/// # Returns
+/// * `Result<u64, String>` - 插入的行数或错误信息
+pub async fn insert_record(
+    client: &Client,
+    cache: &Mutex<ColumnTypeCache>,
+    database: &str,
+    schema: &str,
+    table: &str,
+    data: &serde_json::Map<String, serde_json::Value>,
+) -> Result<u64, String> {
+    if data.is_empty() {
+        return Err("没有要插入的数据".to_string());
+    }
+
+    let names: Vec<&str> = data.keys().map(String::as_str).collect();
+    let column_types = lookup_column_types(cache, client, database, schema, table, &names).await?;
+    let (columns, values) = typed_values(&column_types, data)?;
+
+    let sql = build_insert_statement(schema, table, &columns);
+    execute(client, &sql, &values).await
+}
+
+/// 按主键更新一条记录
+///
+/// 新值和主键值都以文本参数绑定并转换为列的实际类型。
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `cache` - 列类型缓存（只在读取列类型时加锁，执行语句前释放）
+/// * `database` - 数据库名称（用作缓存键）
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `primary_key` - 主键列名 -> 值，没有主键的表可传入 `{"ctid": "(页号,行号)"}`
+/// * `data` - 要更新的列名 -> 新值
+///
+/// # Returns
+/// * `Result<u64, String>` - 更新的行数，没有匹配主键的记录时返回错误
+pub async fn update_record(
+    client: &Client,
+    cache: &Mutex<ColumnTypeCache>,
+    database: &str,
+    schema: &str,
+    table: &str,
+    primary_key: &serde_json::Map<String, serde_json::Value>,
+    data: &serde_json::Map<String, serde_json::Value>,
+) -> Result<u64, String> {
+    if data.is_empty() {
+        return Err("没有要更新的数据".to_string());
+    }
+    if primary_key.is_empty() {
+        return Err(NO_PRIMARY_KEY_ERROR.to_string());
+    }
+
+    let names: Vec<&str> = data.keys().chain(primary_key.keys()).map(String::as_str).collect();
+    let column_types = lookup_column_types(cache, client, database, schema, table, &names).await?;
+    let (columns, mut values) = typed_values(&column_types, data)?;
+    let (key_columns, key_values) = typed_key_values(&column_types, primary_key)?;
+    values.extend(key_values);
+
+    let sql = build_update_statement(schema, table, &columns, &key_columns);
+    let rows = execute(client, &sql, &values).await?;
+    require_matched(rows, primary_key)
+}
+
+/// 按主键删除一条记录
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `cache` - 列类型缓存（只在读取列类型时加锁，执行语句前释放）
+/// * `database` - 数据库名称（用作缓存键）
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `primary_key` - 主键列名 -> 值，没有主键的表可传入 `{"ctid": "(页号,行号)"}`
+///
+/// # Returns
+/// * `Result<u64, String>` - 删除的行数，没有匹配主键的记录时返回错误
+pub async fn delete_record(
+    client: &Client,
+    cache: &Mutex<ColumnTypeCache>,
+    database: &str,
+    schema: &str,
+    table: &str,
+    primary_key: &serde_json::Map<String, serde_json::Value>,
+) -> Result<u64, String> {
+    if primary_key.is_empty() {
+        return Err(NO_PRIMARY_KEY_ERROR.to_string());
+    }
+
+    let names: Vec<&str> = primary_key.keys().map(String::as_str).collect();
+    let column_types = lookup_column_types(cache, client, database, schema, table, &names).await?;
+    let (key_columns, key_values) = typed_key_values(&column_types, primary_key)?;
+
+    let sql = build_delete_statement(schema, table, &key_columns);
+    let rows = execute(client, &sql, &key_values).await?;
+    require_matched(rows, primary_key)
+}
+
+/// 从缓存中复制出表的列类型
+///
+/// 锁只在读取列类型期间持有，执行写入语句时已经释放，一条慢语句不会阻塞其他记录的写入。
+async fn lookup_column_types(
+    cache: &Mutex<ColumnTypeCache>,
+    client: &Client,
+    database: &str,
+    schema: &str,
+    table: &str,
+    columns: &[&str],
+) -> Result<HashMap<String, String>, String> {
+    let mut cache = cache.lock().await;
+    let column_types = cache.get_column_types(client, database, schema, table, columns).await?;
+    Ok(column_types.clone())
+}
+
+/// 按主键修改记录后检查是否有记录被匹配
+///
+/// 没有匹配时通常是记录已被其他会话修改了主键或已删除，前端应提示刷新数据。
+fn require_matched(rows: u64, primary_key: &serde_json::Map<String, serde_json::Value>) -> Result<u64, String> {
+    if rows == 0 {
+        return Err(format!(
+            "没有找到主键为 {} 的记录，记录可能已被修改或删除，请刷新数据",
+            serde_json::Value::Object(primary_key.clone())
+        ));
+    }
+    Ok(rows)
+}
+
+/// (列名, 类型) 列表和用于绑定的文本值
+type TypedValues = (Vec<(String, String)>, Vec<Option<String>>);
+
+/// 查找每个值对应列的类型，返回 (列名, 类型) 和用于绑定的文本值
+fn typed_values(
+    column_types: &HashMap<String, String>,
+    data: &serde_json::Map<String, serde_json::Value>,
+) -> Result<TypedValues, String> {
+    let mut columns = Vec::with_capacity(data.len());
+    let mut values = Vec::with_capacity(data.len());
+    for (name, value) in data {
+        let data_type = column_types
+            .get(name)
+            .ok_or_else(|| format!("列 {} 不存在或不可写入", name))?;
+        columns.push((name.clone(), data_type.clone()));
+        values.push(json_to_text(value));
+    }
+    Ok((columns, values))
+}
+
+/// 与 `typed_values` 相同，但 `ctid` 按系统列 `tid` 类型处理
+fn typed_key_values(
+    column_types: &HashMap<String, String>,
+    primary_key: &serde_json::Map<String, serde_json::Value>,
+) -> Result<TypedValues, String> {
+    if !primary_key.contains_key(CTID_COLUMN) {
+        return typed_values(column_types, primary_key);
+    }
+
+    let mut key_types = column_types.clone();
+    key_types.insert(CTID_COLUMN.to_string(), "tid".to_string());
+    typed_values(&key_types, primary_key)
+}
+
+/// 以文本参数执行语句，返回影响的行数
+async fn execute(client: &Client, sql: &str, values: &[Option<String>]) -> Result<u64, String> {
+    let params: Vec<&(dyn ToSql + Sync)> = values
+        .iter()
+        .map(|v| v as &(dyn ToSql + Sync))
+        .collect();
+
+    client
+        .execute(sql, &params)
+        .await
+        .map_err(|e| match e.as_db_error() {
+            Some(db_error) => db_error.message().to_string(),
+            None => e.to_string(),
+        })
+}
+
+/// 构建带显式类型转换的参数化 INSERT 语句
+fn build_insert_statement(schema: &str, table: &str, columns: &[(String, String)]) -> String {
+    let column_list = columns
+        .iter()
+        .map(|(name, _)| quote_identifier(name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let value_list = columns
+        .iter()
+        .enumerate()
+        .map(|(i, (_, data_type))| format!("CAST(${}::text AS {})", i + 1, data_type))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        qualified_name(schema, table),
+        column_list,
+        value_list
+    )
+}
+
+/// 构建带显式类型转换的参数化 UPDATE 语句，新值的参数在前，主键的参数在后
+fn build_update_statement(
+    schema: &str,
+    table: &str,
+    columns: &[(String, String)],
+    key_columns: &[(String, String)],
+) -> String {
+    let set_list = typed_assignments(columns, 1).join(", ");
+    let where_list = typed_assignments(key_columns, columns.len() + 1).join(" AND ");
+
+    format!(
+        "UPDATE {} SET {} WHERE {}",
+        qualified_name(schema, table),
+        set_list,
+        where_list
+    )
+}
+
+/// 构建带显式类型转换的参数化 DELETE 语句
+fn build_delete_statement(schema: &str, table: &str, key_columns: &[(String, String)]) -> String {
+    format!(
+        "DELETE FROM {} WHERE {}",
+        qualified_name(schema, table),
+        typed_assignments(key_columns, 1).join(" AND ")
+    )
+}
+
+/// 生成 `"列" = CAST($n::text AS 类型)`，参数编号从 `first_param` 开始
+fn typed_assignments(columns: &[(String, String)], first_param: usize) -> Vec<String> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(i, (name, data_type))| {
+            format!("{} = CAST(${}::text AS {})", quote_identifier(name), first_param + i, data_type)
+        })
+        .collect()
+}
+
+/// 将 JSON 值转换为用于绑定的文本，null 转换为 SQL NULL
+pub(crate) fn json_to_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_insert_statement() {
+        let columns = vec![
+            ("id".to_string(), "uuid".to_string()),
+            ("born_on".to_string(), "date".to_string()),
+        ];
+        assert_eq!(
+            build_insert_statement("public", "people", &columns),
+            "INSERT INTO \"public\".\"people\" (\"id\", \"born_on\") \
+             VALUES (CAST($1::text AS uuid), CAST($2::text AS date))"
+        );
+    }
+
+    #[test]
+    fn test_build_update_and_delete_statements() {
+        let columns = vec![("born_on".to_string(), "date".to_string())];
+        let key_columns = vec![("id".to_string(), "uuid".to_string())];
+        assert_eq!(
+            build_update_statement("hr", "people", &columns, &key_columns),
+            "UPDATE \"hr\".\"people\" SET \"born_on\" = CAST($1::text AS date) \
+             WHERE \"id\" = CAST($2::text AS uuid)"
+        );
+        assert_eq!(
+            build_delete_statement("hr", "people", &key_columns),
+            "DELETE FROM \"hr\".\"people\" WHERE \"id\" = CAST($1::text AS uuid)"
+        );
+    }
+
+    #[test]
+    fn test_json_to_text() {
+        assert_eq!(json_to_text(&json!(null)), None);
+        assert_eq!(json_to_text(&json!("2024-01-31")), Some("2024-01-31".to_string()));
+        assert_eq!(json_to_text(&json!(12.5)), Some("12.5".to_string()));
+        assert_eq!(json_to_text(&json!(true)), Some("true".to_string()));
+        assert_eq!(json_to_text(&json!({"a": 1})), Some("{\"a\":1}".to_string()));
+    }
+
+    #[test]
+    fn test_typed_key_values_accepts_ctid() {
+        let column_types = HashMap::from([("name".to_string(), "text".to_string())]);
+
+        let key = json!({ "ctid": "(0,3)" });
+        let (key_columns, key_values) = typed_key_values(&column_types, key.as_object().unwrap()).unwrap();
+        assert_eq!(key_columns, vec![("ctid".to_string(), "tid".to_string())]);
+        assert_eq!(key_values, vec![Some("(0,3)".to_string())]);
+        assert_eq!(
+            build_delete_statement("public", "logs", &key_columns),
+            "DELETE FROM \"public\".\"logs\" WHERE \"ctid\" = CAST($1::text AS tid)"
+        );
+
+        // ctid 只能用于定位，不能作为要更新的列
+        let data = json!({ "ctid": "(0,4)" });
+        assert!(typed_values(&column_types, data.as_object().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_require_matched() {
+        let key = json!({ "id": 5 });
+        assert_eq!(require_matched(1, key.as_object().unwrap()), Ok(1));
+        let error = require_matched(0, key.as_object().unwrap()).unwrap_err();
+        assert!(error.contains("{\"id\":5}"));
+    }
+}
@@ -0,0 +1,207 @@
+/**
+ * Row Streamer Service
+ *
+ * 此模块提供大表的分批读取功能，供数据浏览器无限滚动使用：
+ * - 基于主键的 keyset 分页（避免 OFFSET 随页数增大而变慢）
+ * - 每批数据通过回调交给调用方（例如以事件形式发送给前端）
+ */
+
+use crate::models::data::TableRowsBatch;
+use crate::services::sql_utils::{qualified_name, quote_identifier};
+use tokio_postgres::types::ToSql;
+use tokio_postgres::Client;
+
+/// 按主键顺序分批读取整张表
+///
+/// 每次查询取出主键大于上一批最后一行的 `batch_size` 行，并将结果交给 `on_batch`。
+/// 当某次返回的行数少于 `batch_size` 时结束，最后一批的 `done` 为 true。
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `batch_size` - 每批行数
+/// * `after_key` - 可选的起始主键（上一次事件中的 `last_key`），用于从中断处继续
+/// * `on_batch` - 处理每一批数据的回调，返回错误时停止读取
+///
+/// # Returns
+/// * `Result<u64, String>` - 读取的总行数或错误信息
+pub async fn stream_table_rows<F>(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    batch_size: u32,
+    after_key: Option<Vec<String>>,
+    mut on_batch: F,
+) -> Result<u64, String>
+where
+    F: FnMut(TableRowsBatch) -> Result<(), String>,
+{
+    if batch_size == 0 {
+        return Err("批次大小必须大于 0".to_string());
+    }
+
+    let key_columns = get_primary_key_columns(client, schema, table).await?;
+    if key_columns.is_empty() {
+        return Err(format!("表 {}.{} 没有主键，无法按主键分页读取", schema, table));
+    }
+
+    if let Some(key) = &after_key {
+        if key.len() != key_columns.len() {
+            return Err(format!(
+                "起始主键包含 {} 个值，但表 {}.{} 的主键有 {} 列",
+                key.len(),
+                schema,
+                table,
+                key_columns.len()
+            ));
+        }
+    }
+
+    let first_query = build_keyset_query(schema, table, &key_columns, false, batch_size);
+    let next_query = build_keyset_query(schema, table, &key_columns, true, batch_size);
+
+    let mut last_key = after_key;
+    let mut batch_index = 0u64;
+    let mut total_rows = 0u64;
+
+    loop {
+        let rows = match &last_key {
+            Some(key) => {
+                let params: Vec<&(dyn ToSql + Sync)> =
+                    key.iter().map(|v| v as &(dyn ToSql + Sync)).collect();
+                client.query(&next_query, &params).await
+            }
+            None => client.query(&first_query, &[]).await,
+        }
+        .map_err(|e| format!("Failed to query table rows: {}", e))?;
+
+        let done = rows.len() < batch_size as usize;
+
+        let mut batch_rows = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let json: String = row.get(0);
+            let value = serde_json::from_str(&json)
+                .map_err(|e| format!("Failed to parse row data: {}", e))?;
+            batch_rows.push(value);
+        }
+
+        if let Some(last) = rows.last() {
+            last_key = Some(last.get::<_, Vec<String>>(1));
+        }
+        total_rows += batch_rows.len() as u64;
+
+        on_batch(TableRowsBatch {
+            schema: schema.to_string(),
+            table: table.to_string(),
+            batch_index,
+            rows: batch_rows,
+            last_key: last_key.clone(),
+            done,
+        })?;
+
+        if done {
+            break;
+        }
+        batch_index += 1;
+    }
+
+    Ok(total_rows)
+}
+
+/// 构建 keyset 分页查询
+///
+/// 查询返回两列：整行的 JSON 文本，以及文本形式的主键值数组。
+/// `after_key` 为 true 时，主键值通过 `$1..$n` 以文本传入并转换为列类型，
+/// 这样调用方无需关心主键的实际类型。
+fn build_keyset_query(
+    schema: &str,
+    table: &str,
+    key_columns: &[(String, String)],
+    after_key: bool,
+    batch_size: u32,
+) -> String {
+    let key_refs: Vec<String> = key_columns
+        .iter()
+        .map(|(name, _)| format!("__row.{}", quote_identifier(name)))
+        .collect();
+    let key_texts: Vec<String> = key_refs.iter().map(|k| format!("{}::text", k)).collect();
+
+    let mut query = format!(
+        "SELECT row_to_json(__row)::text, ARRAY[{}] FROM {} AS __row",
+        key_texts.join(", "),
+        qualified_name(schema, table)
+    );
+
+    if after_key {
+        let params: Vec<String> = key_columns
+            .iter()
+            .enumerate()
+            .map(|(i, (_, data_type))| format!("CAST(${}::text AS {})", i + 1, data_type))
+            .collect();
+        query.push_str(&format!(
+            " WHERE ({}) > ({})",
+            key_refs.join(", "),
+            params.join(", ")
+        ));
+    }
+
+    query.push_str(&format!(" ORDER BY {} LIMIT {}", key_refs.join(", "), batch_size));
+    query
+}
+
+/// 获取表的主键列（按主键定义顺序），返回 (列名, 类型)
+async fn get_primary_key_columns(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let query = r#"
+        SELECT
+            a.attname,
+            pg_catalog.format_type(a.atttypid, a.atttypmod)
+        FROM pg_catalog.pg_index i
+        JOIN pg_catalog.pg_class c ON c.oid = i.indrelid
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        CROSS JOIN LATERAL unnest(i.indkey) WITH ORDINALITY AS k(attnum, ord)
+        JOIN pg_catalog.pg_attribute a ON a.attrelid = c.oid AND a.attnum = k.attnum
+        WHERE n.nspname = $1 AND c.relname = $2 AND i.indisprimary
+        ORDER BY k.ord
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query primary key: {}", e))?;
+
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_first_batch_query() {
+        let keys = vec![("id".to_string(), "integer".to_string())];
+        let query = build_keyset_query("public", "users", &keys, false, 500);
+        assert_eq!(
+            query,
+            "SELECT row_to_json(__row)::text, ARRAY[__row.\"id\"::text] FROM \"public\".\"users\" AS __row \
+             ORDER BY __row.\"id\" LIMIT 500"
+        );
+    }
+
+    #[test]
+    fn test_build_next_batch_query_with_composite_key() {
+        let keys = vec![
+            ("order_id".to_string(), "bigint".to_string()),
+            ("line".to_string(), "smallint".to_string()),
+        ];
+        let query = build_keyset_query("sales", "order_lines", &keys, true, 100);
+        assert!(query.contains(
+            "WHERE (__row.\"order_id\", __row.\"line\") > (CAST($1::text AS bigint), CAST($2::text AS smallint))"
+        ));
+        assert!(query.ends_with("ORDER BY __row.\"order_id\", __row.\"line\" LIMIT 100"));
+    }
+}
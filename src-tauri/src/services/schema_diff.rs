@@ -0,0 +1,212 @@
+/**
+ * Schema Diff Service
+ *
+ * This module compares table schemas, either for a single table or for
+ * every table of two databases:
+ * - Per-table column, constraint, and index differences
+ * - Tables present in only one of two databases
+ * - Bounded-concurrency schema fetching for large databases
+ */
+
+use crate::models::schema::{
+    DatabaseSchemaDiff, DiffKind, SchemaItemDiff, TableSchema, TableSchemaDiff,
+};
+use crate::services::schema_service::{get_table_schema, list_base_tables};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use tokio_postgres::Client;
+
+/// Maximum number of tables whose schemas are fetched concurrently
+const MAX_CONCURRENT_FETCHES: usize = 8;
+
+/// Compare two versions of a table schema
+///
+/// # Arguments
+/// * `source` - Schema in the source database
+/// * `target` - Schema in the target database
+///
+/// # Returns
+/// * `TableSchemaDiff` - Column, constraint, and index differences (empty if identical)
+pub fn diff_table_schemas(source: &TableSchema, target: &TableSchema) -> TableSchemaDiff {
+    TableSchemaDiff {
+        schema: source.schema.clone(),
+        table_name: source.table_name.clone(),
+        columns: diff_items(&source.columns, &target.columns, |c| c.name.as_str()),
+        constraints: diff_items(&source.constraints, &target.constraints, |c| {
+            c.constraint_name.as_str()
+        }),
+        indexes: diff_items(&source.indexes, &target.indexes, |i| i.index_name.as_str()),
+    }
+}
+
+/// Compare the schemas of all tables in two databases
+///
+/// Tables are matched by schema and name. Schemas of tables present in both
+/// databases are fetched concurrently, at most `MAX_CONCURRENT_FETCHES` at a time.
+///
+/// # Arguments
+/// * `source` - Connection to the source database
+/// * `target` - Connection to the target database
+/// * `source_database` - Source database name (for the report)
+/// * `target_database` - Target database name (for the report)
+///
+/// # Returns
+/// * `Result<DatabaseSchemaDiff, String>` - Structured difference report or error message
+pub async fn diff_database_schemas(
+    source: &Client,
+    target: &Client,
+    source_database: &str,
+    target_database: &str,
+) -> Result<DatabaseSchemaDiff, String> {
+    let (source_tables, target_tables) =
+        tokio::try_join!(list_base_tables(source), list_base_tables(target))?;
+
+    let source_set: BTreeSet<(String, String)> = source_tables.into_iter().collect();
+    let target_set: BTreeSet<(String, String)> = target_tables.into_iter().collect();
+
+    let only_in_source = source_set
+        .difference(&target_set)
+        .map(|(schema, table)| format!("{}.{}", schema, table))
+        .collect();
+    let only_in_target = target_set
+        .difference(&source_set)
+        .map(|(schema, table)| format!("{}.{}", schema, table))
+        .collect();
+
+    let mut changed_tables: Vec<TableSchemaDiff> = stream::iter(source_set.intersection(&target_set))
+        .map(|(schema, table)| async move {
+            let (source_schema, target_schema) = tokio::try_join!(
+                get_table_schema(source, schema, table),
+                get_table_schema(target, schema, table)
+            )?;
+            Ok::<_, String>(diff_table_schemas(&source_schema, &target_schema))
+        })
+        .buffer_unordered(MAX_CONCURRENT_FETCHES)
+        .try_filter(|diff| std::future::ready(!is_empty_diff(diff)))
+        .try_collect()
+        .await?;
+
+    changed_tables.sort_by(|a, b| {
+        (a.schema.as_str(), a.table_name.as_str()).cmp(&(b.schema.as_str(), b.table_name.as_str()))
+    });
+
+    Ok(DatabaseSchemaDiff {
+        source_database: source_database.to_string(),
+        target_database: target_database.to_string(),
+        only_in_source,
+        only_in_target,
+        changed_tables,
+    })
+}
+
+/// Whether a table diff contains no differences
+fn is_empty_diff(diff: &TableSchemaDiff) -> bool {
+    diff.columns.is_empty() && diff.constraints.is_empty() && diff.indexes.is_empty()
+}
+
+/// Compare two lists of named items, matching them by name
+///
+/// Items are compared by their serialized form, so any field difference
+/// (type, nullability, default, ...) is reported as `Changed`.
+fn diff_items<T: Serialize>(
+    source: &[T],
+    target: &[T],
+    name_of: impl Fn(&T) -> &str,
+) -> Vec<SchemaItemDiff> {
+    let to_value = |item: &T| serde_json::to_value(item).unwrap_or(serde_json::Value::Null);
+
+    let source_map: BTreeMap<&str, serde_json::Value> =
+        source.iter().map(|item| (name_of(item), to_value(item))).collect();
+    let target_map: BTreeMap<&str, serde_json::Value> =
+        target.iter().map(|item| (name_of(item), to_value(item))).collect();
+
+    let mut diffs = Vec::new();
+
+    for (name, source_value) in &source_map {
+        match target_map.get(name) {
+            None => diffs.push(SchemaItemDiff {
+                name: name.to_string(),
+                kind: DiffKind::Removed,
+                source: Some(source_value.clone()),
+                target: None,
+            }),
+            Some(target_value) if target_value != source_value => diffs.push(SchemaItemDiff {
+                name: name.to_string(),
+                kind: DiffKind::Changed,
+                source: Some(source_value.clone()),
+                target: Some(target_value.clone()),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for (name, target_value) in &target_map {
+        if !source_map.contains_key(name) {
+            diffs.push(SchemaItemDiff {
+                name: name.to_string(),
+                kind: DiffKind::Added,
+                source: None,
+                target: Some(target_value.clone()),
+            });
+        }
+    }
+
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::schema::ColumnDefinition;
+
+    fn users_table(columns: Vec<ColumnDefinition>) -> TableSchema {
+        let mut schema = TableSchema::new("users".to_string(), "public".to_string());
+        for column in columns {
+            schema.add_column(column);
+        }
+        schema
+    }
+
+    #[test]
+    fn test_identical_tables_have_no_diff() {
+        let table = users_table(vec![
+            ColumnDefinition::new("id".to_string(), "integer".to_string(), false),
+        ]);
+
+        let diff = diff_table_schemas(&table, &table.clone());
+        assert!(is_empty_diff(&diff));
+    }
+
+    #[test]
+    fn test_column_added_removed_and_changed() {
+        let source = users_table(vec![
+            ColumnDefinition::new("id".to_string(), "integer".to_string(), false),
+            ColumnDefinition::new("name".to_string(), "text".to_string(), true),
+            ColumnDefinition::new("legacy".to_string(), "text".to_string(), true),
+        ]);
+        let target = users_table(vec![
+            ColumnDefinition::new("id".to_string(), "bigint".to_string(), false),
+            ColumnDefinition::new("name".to_string(), "text".to_string(), true),
+            ColumnDefinition::new("email".to_string(), "text".to_string(), true),
+        ]);
+
+        let diff = diff_table_schemas(&source, &target);
+        let kinds: Vec<(&str, DiffKind)> = diff
+            .columns
+            .iter()
+            .map(|d| (d.name.as_str(), d.kind))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                ("id", DiffKind::Changed),
+                ("legacy", DiffKind::Removed),
+                ("email", DiffKind::Added),
+            ]
+        );
+        assert!(diff.constraints.is_empty());
+        assert!(diff.indexes.is_empty());
+    }
+}
@@ -0,0 +1,555 @@
+/**
+ * Schema Export Service
+ *
+ * 此模块将整个数据库的结构导出为一个可执行的 DDL 脚本（不含数据）：
+ * - 结合 `schema_service` 的结构读取和 `ddl_generator` / `table_script` 的语句生成
+ * - 按依赖顺序输出：扩展、模式、枚举类型、独立序列、函数、表、
+ *   依赖表行类型的函数、视图（按视图间依赖排序）、外键、触发器、注释
+ * - 外键在所有表创建后单独添加，因此表之间的循环引用也能执行
+ * - 函数体校验被关闭（`check_function_bodies = false`），函数可以引用稍后创建的表
+ * - 扩展所属的对象不单独导出，由 CREATE EXTENSION 创建
+ * - 脚本不包含时间戳等易变内容，便于比较差异和纳入版本控制
+ * - 域、复合类型、范围类型和聚合函数等无法复现的对象以警告形式返回
+ */
+
+use crate::models::schema::SchemaScript;
+use crate::services::ddl_generator;
+use crate::services::sql_utils::{qualified_name, quote_identifier, quote_literal};
+use crate::services::table_script;
+use std::collections::HashSet;
+use tokio_postgres::Client;
+
+/// 生成重建整个数据库结构所需的 DDL 脚本
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `database` - 数据库名称（写入脚本头部注释）
+///
+/// # Returns
+/// * `Result<SchemaScript, String>` - DDL 脚本和无法复现的对象警告，或错误信息
+pub async fn script_database(client: &Client, database: &str) -> Result<SchemaScript, String> {
+    let mut warnings = get_unsupported_objects(client).await?;
+    let mut sections: Vec<(&str, Vec<String>)> = Vec::new();
+    let mut comments = Vec::new();
+
+    sections.push(("扩展", get_extension_statements(client).await?));
+
+    let mut schemas = Vec::new();
+    for (schema, comment) in get_schemas(client).await? {
+        schemas.push(ddl_generator::generate_create_schema(&schema, None, true));
+        if let Some(comment) = comment {
+            comments.push(format!("COMMENT ON SCHEMA {} IS {};", quote_identifier(&schema), quote_literal(&comment)));
+        }
+    }
+    sections.push(("模式", schemas));
+
+    let mut enums = Vec::new();
+    for (schema, name, labels, comment) in get_enum_types(client).await? {
+        enums.push(create_enum_statement(&schema, &name, &labels));
+        if let Some(comment) = comment {
+            comments.push(format!("COMMENT ON TYPE {} IS {};", qualified_name(&schema, &name), quote_literal(&comment)));
+        }
+    }
+    sections.push(("枚举类型", enums));
+
+    sections.push(("序列", get_sequence_statements(client).await?));
+
+    let functions = get_functions(client).await?;
+    for function in &functions {
+        if let Some(comment) = &function.comment {
+            comments.push(format!(
+                "COMMENT ON {} {} IS {};",
+                if function.is_procedure { "PROCEDURE" } else { "FUNCTION" },
+                function.signature,
+                quote_literal(comment)
+            ));
+        }
+    }
+    let (deferred_functions, functions): (Vec<_>, Vec<_>) =
+        functions.into_iter().partition(|function| function.after_tables);
+    sections.push(("函数", functions.into_iter().map(|function| function.definition).collect()));
+
+    let mut tables = Vec::new();
+    let mut foreign_keys = Vec::new();
+    for (schema, table) in list_tables(client).await? {
+        let statements = table_script::table_statements(client, &schema, &table, true).await?;
+        tables.push(statements.statements.join("\n\n"));
+        foreign_keys.extend(statements.foreign_keys);
+        warnings.extend(
+            statements
+                .warnings
+                .into_iter()
+                .map(|warning| format!("表 {}.{}: {}", schema, table, warning)),
+        );
+    }
+    sections.push(("表", tables));
+    sections.push((
+        "依赖表类型的函数",
+        deferred_functions.into_iter().map(|function| function.definition).collect(),
+    ));
+
+    let views = get_views(client).await?;
+    let dependencies: Vec<(u32, Vec<u32>)> = views.iter().map(|view| (view.oid, view.references.clone())).collect();
+    let mut view_statements = Vec::new();
+    for index in dependency_order(&dependencies) {
+        let view = &views[index];
+        let kind = if view.materialized { "MATERIALIZED VIEW" } else { "VIEW" };
+        let name = qualified_name(&view.schema, &view.name);
+        let query = view.definition.trim().trim_end_matches(';');
+        let mut statement = if view.materialized {
+            format!("CREATE {} {} AS\n{}\nWITH NO DATA;", kind, name, query)
+        } else {
+            format!("CREATE {} {} AS\n{};", kind, name, query)
+        };
+        for index_definition in &view.indexes {
+            statement.push_str(&format!("\n\n{};", index_definition));
+        }
+        view_statements.push(statement);
+        if let Some(comment) = &view.comment {
+            comments.push(format!("COMMENT ON {} {} IS {};", kind, name, quote_literal(comment)));
+        }
+    }
+    sections.push(("视图", view_statements));
+
+    sections.push(("外键", foreign_keys));
+    sections.push(("触发器", get_trigger_statements(client).await?));
+    sections.push(("注释", comments));
+
+    let mut script = vec![
+        format!("-- 数据库 {} 的结构脚本（不含数据）", database),
+        "-- 由 pg-db-tool 生成".to_string(),
+    ];
+    for warning in &warnings {
+        script.push(format!("-- 警告: {}", warning));
+    }
+    script.push(String::new());
+    script.push("SET check_function_bodies = false;".to_string());
+
+    for (title, statements) in sections.into_iter().filter(|(_, statements)| !statements.is_empty()) {
+        script.push(String::new());
+        script.push(format!("-- {}", title));
+        script.push(String::new());
+        script.push(statements.join("\n\n"));
+    }
+    script.push(String::new());
+
+    Ok(SchemaScript {
+        script: script.join("\n"),
+        warnings,
+    })
+}
+
+/// 生成 CREATE TYPE ... AS ENUM 语句
+fn create_enum_statement(schema: &str, name: &str, labels: &[String]) -> String {
+    let labels: Vec<String> = labels.iter().map(|label| quote_literal(label)).collect();
+    format!("CREATE TYPE {} AS ENUM ({});", qualified_name(schema, name), labels.join(", "))
+}
+
+/// 按依赖排序视图，被引用的视图排在引用它的视图之前
+///
+/// `views` 为 (视图 oid, 视图引用的对象 oid)，不是视图的引用对象被忽略。
+/// 返回排序后的下标；依赖允许时保持原有顺序，出现循环时剩余视图按原有顺序追加。
+fn dependency_order(views: &[(u32, Vec<u32>)]) -> Vec<usize> {
+    let view_oids: HashSet<u32> = views.iter().map(|(oid, _)| *oid).collect();
+    let mut created: HashSet<u32> = HashSet::new();
+    let mut order = Vec::with_capacity(views.len());
+
+    while order.len() < views.len() {
+        let ready: Vec<usize> = (0..views.len())
+            .filter(|index| !created.contains(&views[*index].0))
+            .filter(|index| {
+                views[*index]
+                    .1
+                    .iter()
+                    .all(|oid| !view_oids.contains(oid) || created.contains(oid) || *oid == views[*index].0)
+            })
+            .collect();
+
+        if ready.is_empty() {
+            order.extend((0..views.len()).filter(|index| !created.contains(&views[*index].0)));
+            break;
+        }
+        for index in ready {
+            created.insert(views[index].0);
+            order.push(index);
+        }
+    }
+
+    order
+}
+
+/// 读取扩展的 CREATE EXTENSION 语句（plpgsql 默认已安装，不包括在内）
+async fn get_extension_statements(client: &Client) -> Result<Vec<String>, String> {
+    let query = r#"
+        SELECT e.extname::text, n.nspname::text
+        FROM pg_extension e
+        JOIN pg_namespace n ON n.oid = e.extnamespace
+        WHERE e.extname <> 'plpgsql'
+        ORDER BY e.extname
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询扩展失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            format!(
+                "CREATE EXTENSION IF NOT EXISTS {} WITH SCHEMA {};",
+                quote_identifier(row.get(0)),
+                quote_identifier(row.get(1))
+            )
+        })
+        .collect())
+}
+
+/// 读取用户模式，返回 (模式名, 注释)
+async fn get_schemas(client: &Client) -> Result<Vec<(String, Option<String>)>, String> {
+    let query = r#"
+        SELECT n.nspname::text, obj_description(n.oid, 'pg_namespace')
+        FROM pg_namespace n
+        WHERE n.nspname NOT LIKE 'pg\_%' AND n.nspname <> 'information_schema'
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_namespace'::regclass AND d.objid = n.oid AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询模式失败: {}", e))?;
+
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+/// 读取枚举类型，返回 (模式名, 类型名, 标签, 注释)
+async fn get_enum_types(
+    client: &Client,
+) -> Result<Vec<(String, String, Vec<String>, Option<String>)>, String> {
+    let query = r#"
+        SELECT
+            n.nspname::text,
+            t.typname::text,
+            ARRAY(SELECT e.enumlabel::text FROM pg_enum e WHERE e.enumtypid = t.oid ORDER BY e.enumsortorder),
+            obj_description(t.oid, 'pg_type')
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        WHERE t.typtype = 'e'
+          AND n.nspname NOT LIKE 'pg\_%' AND n.nspname <> 'information_schema'
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_type'::regclass AND d.objid = t.oid AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname, t.typname
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询枚举类型失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get(0), row.get(1), row.get(2), row.get(3)))
+        .collect())
+}
+
+/// 读取不属于任何列的独立序列的 CREATE SEQUENCE 语句
+///
+/// serial 列的序列由表脚本创建，标识列的序列由 GENERATED AS IDENTITY 隐式创建。
+async fn get_sequence_statements(client: &Client) -> Result<Vec<String>, String> {
+    let query = r#"
+        SELECT
+            n.nspname::text,
+            c.relname::text,
+            format_type(s.seqtypid, NULL),
+            s.seqincrement,
+            s.seqmin,
+            s.seqmax,
+            s.seqstart,
+            s.seqcache,
+            s.seqcycle
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_sequence s ON s.seqrelid = c.oid
+        WHERE c.relkind = 'S'
+          AND n.nspname NOT LIKE 'pg\_%' AND n.nspname <> 'information_schema'
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_class'::regclass AND d.objid = c.oid AND d.deptype IN ('a', 'i', 'e')
+          )
+        ORDER BY n.nspname, c.relname
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询序列失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let cycle: bool = row.get(8);
+            format!(
+                "CREATE SEQUENCE IF NOT EXISTS {} AS {} INCREMENT BY {} MINVALUE {} MAXVALUE {} START WITH {} CACHE {} {};",
+                qualified_name(row.get(0), row.get(1)),
+                row.get::<_, String>(2),
+                row.get::<_, i64>(3),
+                row.get::<_, i64>(4),
+                row.get::<_, i64>(5),
+                row.get::<_, i64>(6),
+                row.get::<_, i64>(7),
+                if cycle { "CYCLE" } else { "NO CYCLE" }
+            )
+        })
+        .collect())
+}
+
+/// 函数或存储过程的定义
+struct FunctionDefinition {
+    /// `pg_get_functiondef` 生成的 CREATE OR REPLACE 语句
+    definition: String,
+    /// 带参数类型的名称，用于 COMMENT ON
+    signature: String,
+    is_procedure: bool,
+    /// 参数或返回值使用表的行类型，或函数体在创建时即被解析（BEGIN ATOMIC），需在表之后创建
+    after_tables: bool,
+    comment: Option<String>,
+}
+
+/// 读取普通函数和存储过程（不包括聚合函数和扩展中的函数）
+async fn get_functions(client: &Client) -> Result<Vec<FunctionDefinition>, String> {
+    let query = r#"
+        SELECT
+            pg_get_functiondef(p.oid),
+            quote_ident(n.nspname) || '.' || quote_ident(p.proname)
+                || '(' || pg_get_function_identity_arguments(p.oid) || ')',
+            p.prokind = 'p',
+            p.prosqlbody IS NOT NULL OR EXISTS (
+                SELECT 1
+                FROM pg_depend d
+                JOIN pg_type t ON t.oid = d.refobjid
+                LEFT JOIN pg_type e ON e.oid = t.typelem
+                WHERE d.classid = 'pg_proc'::regclass AND d.objid = p.oid
+                  AND d.refclassid = 'pg_type'::regclass
+                  AND (t.typrelid <> 0 OR e.typrelid <> 0)
+            ),
+            obj_description(p.oid, 'pg_proc')
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        WHERE p.prokind IN ('f', 'p')
+          AND n.nspname NOT LIKE 'pg\_%' AND n.nspname <> 'information_schema'
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_proc'::regclass AND d.objid = p.oid AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname, p.proname, pg_get_function_identity_arguments(p.oid)
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询函数失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| FunctionDefinition {
+            definition: format!("{};", row.get::<_, String>(0).trim_end()),
+            signature: row.get(1),
+            is_procedure: row.get(2),
+            after_tables: row.get(3),
+            comment: row.get(4),
+        })
+        .collect())
+}
+
+/// 读取普通表和分区表，返回 (模式名, 表名)
+async fn list_tables(client: &Client) -> Result<Vec<(String, String)>, String> {
+    let query = r#"
+        SELECT n.nspname::text, c.relname::text
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relkind IN ('r', 'p')
+          AND n.nspname NOT LIKE 'pg\_%' AND n.nspname <> 'information_schema'
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_class'::regclass AND d.objid = c.oid AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname, c.relname
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询表失败: {}", e))?;
+
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+/// 视图或物化视图的定义
+struct ViewDefinition {
+    oid: u32,
+    schema: String,
+    name: String,
+    materialized: bool,
+    /// `pg_get_viewdef` 生成的查询
+    definition: String,
+    /// 物化视图上的索引定义
+    indexes: Vec<String>,
+    /// 视图查询引用的表和视图的 oid
+    references: Vec<u32>,
+    comment: Option<String>,
+}
+
+/// 读取视图和物化视图
+async fn get_views(client: &Client) -> Result<Vec<ViewDefinition>, String> {
+    let query = r#"
+        SELECT
+            c.oid,
+            n.nspname::text,
+            c.relname::text,
+            c.relkind = 'm',
+            pg_get_viewdef(c.oid),
+            ARRAY(
+                SELECT pg_get_indexdef(i.indexrelid) FROM pg_index i
+                WHERE i.indrelid = c.oid ORDER BY i.indexrelid
+            ),
+            ARRAY(
+                SELECT DISTINCT d.refobjid
+                FROM pg_rewrite r
+                JOIN pg_depend d ON d.classid = 'pg_rewrite'::regclass AND d.objid = r.oid
+                WHERE r.ev_class = c.oid AND d.refclassid = 'pg_class'::regclass
+            ),
+            obj_description(c.oid, 'pg_class')
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE c.relkind IN ('v', 'm')
+          AND n.nspname NOT LIKE 'pg\_%' AND n.nspname <> 'information_schema'
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_class'::regclass AND d.objid = c.oid AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname, c.relname
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询视图失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ViewDefinition {
+            oid: row.get(0),
+            schema: row.get(1),
+            name: row.get(2),
+            materialized: row.get(3),
+            definition: row.get(4),
+            indexes: row.get(5),
+            references: row.get(6),
+            comment: row.get(7),
+        })
+        .collect())
+}
+
+/// 读取用户定义的触发器（不包括约束内部触发器和分区继承的触发器）
+async fn get_trigger_statements(client: &Client) -> Result<Vec<String>, String> {
+    let query = r#"
+        SELECT pg_get_triggerdef(t.oid)
+        FROM pg_trigger t
+        JOIN pg_class c ON c.oid = t.tgrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE NOT t.tgisinternal AND t.tgparentid = 0
+          AND n.nspname NOT LIKE 'pg\_%' AND n.nspname <> 'information_schema'
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_class'::regclass AND d.objid = c.oid AND d.deptype = 'e'
+          )
+        ORDER BY n.nspname, c.relname, t.tgname
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询触发器失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| format!("{};", row.get::<_, String>(0)))
+        .collect())
+}
+
+/// 列出脚本无法复现的对象：域、复合类型、范围类型和聚合函数
+async fn get_unsupported_objects(client: &Client) -> Result<Vec<String>, String> {
+    let query = r#"
+        SELECT
+            CASE t.typtype WHEN 'd' THEN '域' WHEN 'r' THEN '范围类型' ELSE '复合类型' END
+                || ' ' || n.nspname || '.' || t.typname
+        FROM pg_type t
+        JOIN pg_namespace n ON n.oid = t.typnamespace
+        WHERE (t.typtype IN ('d', 'r')
+               OR (t.typtype = 'c' AND EXISTS (
+                   SELECT 1 FROM pg_class c WHERE c.oid = t.typrelid AND c.relkind = 'c'
+               )))
+          AND n.nspname NOT LIKE 'pg\_%' AND n.nspname <> 'information_schema'
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_type'::regclass AND d.objid = t.oid AND d.deptype = 'e'
+          )
+        UNION ALL
+        SELECT '聚合函数 ' || n.nspname || '.' || p.proname
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        WHERE p.prokind = 'a'
+          AND n.nspname NOT LIKE 'pg\_%' AND n.nspname <> 'information_schema'
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_depend d
+              WHERE d.classid = 'pg_proc'::regclass AND d.objid = p.oid AND d.deptype = 'e'
+          )
+        ORDER BY 1
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("查询数据库对象失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| format!("{} 未包含在脚本中", row.get::<_, String>(0)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_enum_statement() {
+        let labels = vec!["new".to_string(), "it's shipped".to_string()];
+        assert_eq!(
+            create_enum_statement("sales", "Order Status", &labels),
+            "CREATE TYPE \"sales\".\"Order Status\" AS ENUM ('new', 'it''s shipped');"
+        );
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        // 10 引用 11，11 引用 12，13 只引用表 1
+        let views = vec![
+            (10, vec![11, 1]),
+            (11, vec![12]),
+            (12, vec![1]),
+            (13, vec![1]),
+        ];
+        assert_eq!(dependency_order(&views), vec![2, 3, 1, 0]);
+
+        // 循环引用时不丢失视图
+        let views = vec![(20, vec![21]), (21, vec![20]), (22, vec![])];
+        assert_eq!(dependency_order(&views), vec![2, 0, 1]);
+    }
+}
@@ -6,11 +6,29 @@
  * - Querying information_schema for column definitions
  * - Querying pg_constraint for constraint information
  * - Querying pg_indexes for index information
+ * - Querying pg_class.reloptions for table storage parameters
+ * - Reporting a table's tablespace and listing the tablespaces of the cluster
+ * - Listing views and materialized views and reading their definitions
+ * - Listing functions and procedures and reading their definitions
+ * - Listing sequences and setting their next value
+ * - Looking up writable column types for data import/export
+ * - Classifying column types (array, composite, JSON) for writing structured values
+ * - Previewing the values produced by column defaults
+ * - Collecting per-column edit constraints (nullability, length, enum, CHECK)
+ * - Checking schema existence and listing the objects a schema contains
+ * - Reading table and column comments from pg_description
  * 
  * Validates: Requirements 8.1, 8.2, 8.3, 8.4
  */
 
-use crate::models::schema::{TableSchema, ColumnDefinition, ConstraintDefinition, IndexDefinition};
+use crate::models::schema::{
+    TableSchema, ColumnDefinition, ConstraintDefinition, IndexDefinition, IdentityKind,
+    ColumnDefaultPreview, DefaultPreviewKind, ColumnEditConstraints,
+    ColumnDocumentation, TableDocumentation, TablespaceInfo, ViewInfo,
+    FunctionInfo, SequenceInfo, TriggerInfo,
+};
+use crate::services::sql_utils::qualified_name;
+use std::collections::HashMap;
 use tokio_postgres::Client;
 
 /// Get complete schema information for a table
@@ -36,6 +54,18 @@ pub async fn get_table_schema(
     // Get indexes
     let indexes = get_indexes(client, schema, table).await?;
     
+    // Get storage parameters
+    let storage_parameters = get_storage_parameters(client, schema, table).await?;
+    
+    // Get tablespace
+    let tablespace = get_tablespace(client, schema, table).await?;
+    
+    // Get triggers
+    let triggers = list_triggers(client, schema, table).await?;
+    
+    // Get table comment
+    let comment = get_table_comment(client, schema, table).await?;
+    
     // Mark primary key columns
     let mut columns_with_pk = mark_primary_key_columns(columns, &constraints);
     
@@ -48,6 +78,10 @@ pub async fn get_table_schema(
         columns: columns_with_pk,
         constraints,
         indexes,
+        storage_parameters,
+        tablespace,
+        triggers,
+        comment,
     })
 }
 
@@ -65,8 +99,15 @@ async fn get_columns(
             numeric_precision,
             numeric_scale,
             is_nullable,
-            column_default
+            column_default,
+            col_description(format('%I.%I', table_schema, table_name)::regclass, ordinal_position::int),
+            a.attidentity::text,
+            CASE WHEN a.attgenerated = 's' THEN pg_get_expr(d.adbin, d.adrelid) END
         FROM information_schema.columns
+        JOIN pg_attribute a
+            ON a.attrelid = format('%I.%I', table_schema, table_name)::regclass
+            AND a.attnum = ordinal_position::int
+        LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
         WHERE table_schema = $1 AND table_name = $2
         ORDER BY ordinal_position
     "#;
@@ -86,6 +127,13 @@ async fn get_columns(
             let numeric_scale: Option<i32> = row.get(4);
             let is_nullable: String = row.get(5);
             let column_default: Option<String> = row.get(6);
+            let comment: Option<String> = row.get(7);
+            let identity = match row.get::<_, String>(8).as_str() {
+                "a" => Some(IdentityKind::Always),
+                "d" => Some(IdentityKind::ByDefault),
+                _ => None,
+            };
+            let generated_expression: Option<String> = row.get(9);
             
             ColumnDefinition {
                 name: column_name,
@@ -95,8 +143,11 @@ async fn get_columns(
                 numeric_scale,
                 is_nullable: is_nullable == "YES",
                 column_default,
+                generated_expression,
+                identity,
                 is_primary_key: false, // Will be set later
                 is_unique: false, // Will be set later
+                comment,
             }
         })
         .collect();
@@ -113,7 +164,7 @@ async fn get_constraints(
     let query = r#"
         SELECT 
             con.conname AS constraint_name,
-            con.contype AS constraint_type,
+            con.contype::text AS constraint_type,
             ARRAY(
                 SELECT att.attname
                 FROM unnest(con.conkey) AS u(attnum)
@@ -128,8 +179,8 @@ async fn get_constraints(
                 JOIN pg_attribute att ON att.attnum = u.attnum AND att.attrelid = con.confrelid
                 ORDER BY u.attnum
             ) AS referenced_columns,
-            con.confdeltype AS on_delete_code,
-            con.confupdtype AS on_update_code,
+            con.confdeltype::text AS on_delete_code,
+            con.confupdtype::text AS on_update_code,
             pg_get_constraintdef(con.oid) AS constraint_def
         FROM pg_constraint con
         JOIN pg_class cl ON cl.oid = con.conrelid
@@ -334,6 +385,757 @@ fn extract_check_clause(constraint_def: &str) -> Option<String> {
     None
 }
 
+/// Get table storage parameters (fillfactor, autovacuum settings, ...) from pg_class.reloptions
+async fn get_storage_parameters(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<HashMap<String, String>, String> {
+    let query = r#"
+        SELECT opt.option_name, opt.option_value
+        FROM pg_class cl
+        JOIN pg_namespace ns ON ns.oid = cl.relnamespace
+        CROSS JOIN LATERAL pg_options_to_table(cl.reloptions) AS opt
+        WHERE ns.nspname = $1 AND cl.relname = $2
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query storage parameters: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect())
+}
+
+/// Get the tablespace of a table from pg_class.reltablespace
+///
+/// Returns None when the table is in the database's default tablespace
+/// (PostgreSQL stores 0 in that case, even if it was named explicitly).
+async fn get_tablespace(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Option<String>, String> {
+    let query = r#"
+        SELECT ts.spcname::text
+        FROM pg_class cl
+        JOIN pg_namespace ns ON ns.oid = cl.relnamespace
+        LEFT JOIN pg_tablespace ts ON ts.oid = cl.reltablespace
+        WHERE ns.nspname = $1 AND cl.relname = $2
+    "#;
+
+    let row = client
+        .query_opt(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query tablespace: {}", e))?;
+
+    Ok(row.and_then(|row| row.get(0)))
+}
+
+/// Get the table comment from pg_description
+async fn get_table_comment(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Option<String>, String> {
+    let query = r#"
+        SELECT obj_description(cl.oid, 'pg_class')
+        FROM pg_class cl
+        JOIN pg_namespace ns ON ns.oid = cl.relnamespace
+        WHERE ns.nspname = $1 AND cl.relname = $2
+    "#;
+
+    let row = client
+        .query_opt(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query table comment: {}", e))?;
+
+    Ok(row.and_then(|row| row.get(0)))
+}
+
+/// List the user-defined triggers of a table
+///
+/// Internal triggers (e.g. those enforcing foreign keys) are not included.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `table` - Table name
+///
+/// # Returns
+/// * `Result<Vec<TriggerInfo>, String>` - Triggers ordered by name, or error message
+pub async fn list_triggers(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<TriggerInfo>, String> {
+    // tgtype bits: 1 = ROW, 2 = BEFORE, 4 = INSERT, 8 = DELETE, 16 = UPDATE,
+    // 32 = TRUNCATE, 64 = INSTEAD OF
+    let query = r#"
+        SELECT
+            t.tgname::text,
+            CASE
+                WHEN t.tgtype & 2 <> 0 THEN 'BEFORE'
+                WHEN t.tgtype & 64 <> 0 THEN 'INSTEAD OF'
+                ELSE 'AFTER'
+            END,
+            array_remove(ARRAY[
+                CASE WHEN t.tgtype & 4 <> 0 THEN 'INSERT' END,
+                CASE WHEN t.tgtype & 16 <> 0 THEN 'UPDATE' END,
+                CASE WHEN t.tgtype & 8 <> 0 THEN 'DELETE' END,
+                CASE WHEN t.tgtype & 32 <> 0 THEN 'TRUNCATE' END
+            ], NULL),
+            t.tgtype & 1 <> 0,
+            format('%I.%I', pn.nspname, p.proname),
+            t.tgenabled <> 'D'
+        FROM pg_trigger t
+        JOIN pg_class c ON c.oid = t.tgrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_proc p ON p.oid = t.tgfoid
+        JOIN pg_namespace pn ON pn.oid = p.pronamespace
+        WHERE n.nspname = $1 AND c.relname = $2 AND NOT t.tgisinternal
+        ORDER BY t.tgname
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to list triggers: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TriggerInfo {
+            name: row.get(0),
+            timing: row.get(1),
+            events: row.get(2),
+            for_each_row: row.get(3),
+            function: row.get(4),
+            enabled: row.get(5),
+        })
+        .collect())
+}
+
+/// List the tablespaces of the cluster with their location and size
+///
+/// The size is only reported for tablespaces the current user may read
+/// (CREATE privilege on the tablespace, or membership in pg_read_all_stats).
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+///
+/// # Returns
+/// * `Result<Vec<TablespaceInfo>, String>` - Tablespaces ordered by name, or error message
+pub async fn list_tablespaces(client: &Client) -> Result<Vec<TablespaceInfo>, String> {
+    let query = r#"
+        SELECT
+            ts.spcname::text,
+            pg_get_userbyid(ts.spcowner)::text,
+            NULLIF(pg_tablespace_location(ts.oid), ''),
+            CASE
+                WHEN has_tablespace_privilege(ts.oid, 'CREATE')
+                  OR pg_has_role('pg_read_all_stats', 'MEMBER')
+                THEN pg_tablespace_size(ts.oid)
+            END,
+            ts.oid = (SELECT dattablespace FROM pg_database WHERE datname = current_database())
+        FROM pg_tablespace ts
+        ORDER BY ts.spcname
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("Failed to list tablespaces: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TablespaceInfo {
+            name: row.get(0),
+            owner: row.get(1),
+            location: row.get(2),
+            size_bytes: row.get(3),
+            is_database_default: row.get(4),
+        })
+        .collect())
+}
+
+/// List the views and materialized views of the database
+///
+/// System schemas are skipped.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+///
+/// # Returns
+/// * `Result<Vec<ViewInfo>, String>` - Views ordered by schema and name, or error message
+pub async fn list_views(client: &Client) -> Result<Vec<ViewInfo>, String> {
+    let query = r#"
+        SELECT table_schema::text AS schema, table_name::text AS name, false AS is_materialized
+        FROM information_schema.views
+        WHERE table_schema NOT IN ('pg_catalog', 'information_schema')
+        UNION ALL
+        SELECT schemaname::text, matviewname::text, true
+        FROM pg_matviews
+        WHERE schemaname NOT IN ('pg_catalog', 'information_schema')
+        ORDER BY schema, name
+    "#;
+
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("Failed to list views: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ViewInfo {
+            schema: row.get(0),
+            name: row.get(1),
+            is_materialized: row.get(2),
+        })
+        .collect())
+}
+
+/// Get the SELECT statement that defines a view or materialized view
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `view` - View name
+///
+/// # Returns
+/// * `Result<String, String>` - The definition as returned by `pg_get_viewdef`, or error message
+pub async fn get_view_definition(client: &Client, schema: &str, view: &str) -> Result<String, String> {
+    let query = r#"
+        SELECT pg_get_viewdef(c.oid, true)
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('v', 'm')
+    "#;
+
+    let row = client
+        .query_opt(query, &[&schema, &view])
+        .await
+        .map_err(|e| format!("Failed to get view definition: {}", e))?;
+
+    row.map(|row| row.get(0))
+        .ok_or_else(|| format!("View {}.{} not found", schema, view))
+}
+
+/// List the functions and procedures of a schema
+///
+/// Aggregates are skipped, since `pg_get_functiondef` can't show them.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+///
+/// # Returns
+/// * `Result<Vec<FunctionInfo>, String>` - Routines ordered by name and arguments, or error message
+pub async fn list_functions(client: &Client, schema: &str) -> Result<Vec<FunctionInfo>, String> {
+    let query = r#"
+        SELECT
+            p.proname::text,
+            pg_get_function_identity_arguments(p.oid),
+            CASE WHEN p.prokind <> 'p' THEN pg_get_function_result(p.oid) END,
+            l.lanname::text,
+            p.prokind = 'p'
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        JOIN pg_language l ON l.oid = p.prolang
+        WHERE n.nspname = $1 AND p.prokind <> 'a'
+        ORDER BY p.proname, 2
+    "#;
+
+    let rows = client
+        .query(query, &[&schema])
+        .await
+        .map_err(|e| format!("Failed to list functions: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| FunctionInfo {
+            schema: schema.to_string(),
+            name: row.get(0),
+            arguments: row.get(1),
+            return_type: row.get(2),
+            language: row.get(3),
+            is_procedure: row.get(4),
+        })
+        .collect())
+}
+
+/// Get the CREATE OR REPLACE statement of a function or procedure
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `name` - Routine name
+/// * `arguments` - Argument list as returned in `FunctionInfo::arguments`, to pick one of several overloads
+///
+/// # Returns
+/// * `Result<String, String>` - The definition as returned by `pg_get_functiondef`, or error message
+pub async fn get_function_definition(
+    client: &Client,
+    schema: &str,
+    name: &str,
+    arguments: &str,
+) -> Result<String, String> {
+    let query = r#"
+        SELECT pg_get_functiondef(p.oid)
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        WHERE n.nspname = $1
+          AND p.proname = $2
+          AND pg_get_function_identity_arguments(p.oid) = $3
+          AND p.prokind <> 'a'
+    "#;
+
+    let row = client
+        .query_opt(query, &[&schema, &name, &arguments])
+        .await
+        .map_err(|e| format!("Failed to get function definition: {}", e))?;
+
+    row.map(|row| row.get(0))
+        .ok_or_else(|| format!("Function {}.{}({}) not found", schema, name, arguments))
+}
+
+/// List the sequences of a schema with their current state
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+///
+/// # Returns
+/// * `Result<Vec<SequenceInfo>, String>` - Sequences ordered by name, or error message
+pub async fn list_sequences(client: &Client, schema: &str) -> Result<Vec<SequenceInfo>, String> {
+    let query = r#"
+        SELECT
+            s.sequencename::text,
+            s.data_type::text,
+            s.last_value,
+            s.start_value,
+            s.increment_by,
+            s.min_value,
+            s.max_value,
+            s.cycle,
+            (
+                SELECT d.refobjid::regclass::text || '.' || quote_ident(a.attname)
+                FROM pg_depend d
+                JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+                WHERE d.classid = 'pg_class'::regclass
+                  AND d.objid = c.oid
+                  AND d.refclassid = 'pg_class'::regclass
+                  AND d.deptype IN ('a', 'i')
+                LIMIT 1
+            )
+        FROM pg_sequences s
+        JOIN pg_namespace n ON n.nspname = s.schemaname
+        JOIN pg_class c ON c.relnamespace = n.oid AND c.relname = s.sequencename
+        WHERE s.schemaname = $1
+        ORDER BY s.sequencename
+    "#;
+
+    let rows = client
+        .query(query, &[&schema])
+        .await
+        .map_err(|e| format!("Failed to list sequences: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SequenceInfo {
+            schema: schema.to_string(),
+            name: row.get(0),
+            data_type: row.get(1),
+            last_value: row.get(2),
+            start_value: row.get(3),
+            increment: row.get(4),
+            min_value: row.get(5),
+            max_value: row.get(6),
+            cycle: row.get(7),
+            owned_by: row.get(8),
+        })
+        .collect())
+}
+
+/// Set the value the next `nextval` call on a sequence will return
+///
+/// Runs `setval(seq, value, false)`, so after a bulk import passing `MAX(id) + 1`
+/// makes the next generated id follow the imported rows.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `sequence` - Sequence name
+/// * `value` - Next value to return; must be within the sequence's min/max
+///
+/// # Returns
+/// * `Result<(), String>` - Success or error message
+pub async fn reset_sequence(client: &Client, schema: &str, sequence: &str, value: i64) -> Result<(), String> {
+    client
+        .execute(
+            "SELECT setval($1::text::regclass, $2, false)",
+            &[&qualified_name(schema, sequence), &value],
+        )
+        .await
+        .map_err(|e| format!("Failed to reset sequence: {}", e))?;
+
+    Ok(())
+}
+
+/// A column that can be written by INSERT/COPY, with its full type name
+pub struct WritableColumn {
+    /// Column name
+    pub name: String,
+    /// Full type as rendered by `format_type` (e.g. "character varying(100)")
+    pub data_type: String,
+    /// Whether the column is `GENERATED ALWAYS AS IDENTITY`
+    pub is_identity_always: bool,
+}
+
+/// Get the writable columns of a table in ordinal order
+///
+/// Dropped and generated columns are excluded, since they can't be
+/// targeted by INSERT or COPY.
+pub async fn get_writable_columns(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<WritableColumn>, String> {
+    let query = r#"
+        SELECT
+            a.attname,
+            pg_catalog.format_type(a.atttypid, a.atttypmod),
+            a.attidentity = 'a'
+        FROM pg_catalog.pg_attribute a
+        JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1 AND c.relname = $2
+          AND a.attnum > 0
+          AND NOT a.attisdropped
+          AND a.attgenerated = ''
+        ORDER BY a.attnum
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query columns: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| WritableColumn {
+            name: row.get(0),
+            data_type: row.get(1),
+            is_identity_always: row.get(2),
+        })
+        .collect())
+}
+
+/// How a column's type accepts structured (JSON array / object) values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnTypeKind {
+    /// An array type (e.g. `integer[]`)
+    Array,
+    /// A composite (row) type
+    Composite,
+    /// `json` or `jsonb`
+    Json,
+    /// Any other type
+    Other,
+}
+
+/// A column's full type name and kind
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnType {
+    /// Full type as rendered by `format_type` (e.g. "integer[]")
+    pub data_type: String,
+    /// Kind of the type
+    pub kind: ColumnTypeKind,
+}
+
+/// Get the type of each column of a table, keyed by column name
+///
+/// Domains are classified by their base type.
+pub async fn get_column_types(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<HashMap<String, ColumnType>, String> {
+    let query = r#"
+        SELECT
+            a.attname::text,
+            pg_catalog.format_type(a.atttypid, a.atttypmod),
+            CASE
+                WHEN bt.typcategory = 'A' THEN 'array'
+                WHEN bt.typtype = 'c' THEN 'composite'
+                WHEN bt.oid IN ('json'::regtype, 'jsonb'::regtype) THEN 'json'
+                ELSE 'other'
+            END
+        FROM pg_catalog.pg_attribute a
+        JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_catalog.pg_type t ON t.oid = a.atttypid
+        JOIN pg_catalog.pg_type bt ON bt.oid = CASE WHEN t.typtype = 'd' THEN t.typbasetype ELSE t.oid END
+        WHERE n.nspname = $1 AND c.relname = $2
+          AND a.attnum > 0
+          AND NOT a.attisdropped
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query column types: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let kind = match row.get::<_, &str>(2) {
+                "array" => ColumnTypeKind::Array,
+                "composite" => ColumnTypeKind::Composite,
+                "json" => ColumnTypeKind::Json,
+                _ => ColumnTypeKind::Other,
+            };
+            (row.get(0), ColumnType { data_type: row.get(1), kind })
+        })
+        .collect())
+}
+
+/// A column default as stored in the catalog
+pub struct ColumnDefault {
+    /// Column name
+    pub name: String,
+    /// Default expression (None for identity columns without an explicit default)
+    pub expression: Option<String>,
+    /// Whether the column is an identity column
+    pub is_identity: bool,
+}
+
+/// Get the default expressions of a table's columns in ordinal order
+///
+/// Only columns that have a default or are identity columns are returned.
+/// Generated columns are excluded since their values can't be supplied.
+pub async fn get_column_defaults(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ColumnDefault>, String> {
+    let query = r#"
+        SELECT
+            a.attname,
+            pg_catalog.pg_get_expr(d.adbin, d.adrelid),
+            a.attidentity <> ''
+        FROM pg_catalog.pg_attribute a
+        JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_catalog.pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+        WHERE n.nspname = $1 AND c.relname = $2
+          AND a.attnum > 0
+          AND NOT a.attisdropped
+          AND a.attgenerated = ''
+          AND (d.adbin IS NOT NULL OR a.attidentity <> '')
+        ORDER BY a.attnum
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query column defaults: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ColumnDefault {
+            name: row.get(0),
+            expression: row.get(1),
+            is_identity: row.get(2),
+        })
+        .collect())
+}
+
+/// Evaluate each column default of a table and return sample values
+///
+/// Expressions are evaluated in a read-only transaction that is always rolled
+/// back, so defaults with side effects can't modify the database. Sequence and
+/// identity defaults are not evaluated (that would consume a value) and are
+/// reported as `Auto`.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `table` - Table name
+///
+/// # Returns
+/// * `Result<Vec<ColumnDefaultPreview>, String>` - One preview per column with a default
+pub async fn preview_defaults(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ColumnDefaultPreview>, String> {
+    let defaults = get_column_defaults(client, schema, table).await?;
+
+    client
+        .batch_execute("BEGIN READ ONLY")
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    let mut previews = Vec::with_capacity(defaults.len());
+    for default in defaults {
+        let expression = match default.expression {
+            Some(expression) if !default.is_identity && !is_sequence_default(&expression) => expression,
+            expression => {
+                previews.push(ColumnDefaultPreview {
+                    column: default.name,
+                    expression,
+                    kind: DefaultPreviewKind::Auto,
+                    value: None,
+                    error: None,
+                });
+                continue;
+            }
+        };
+
+        let preview = match evaluate_default(client, &expression).await {
+            Ok(value) => ColumnDefaultPreview {
+                column: default.name,
+                expression: Some(expression),
+                kind: DefaultPreviewKind::Value,
+                value,
+                error: None,
+            },
+            Err(e) => ColumnDefaultPreview {
+                column: default.name,
+                expression: Some(expression),
+                kind: DefaultPreviewKind::Error,
+                value: None,
+                error: Some(e),
+            },
+        };
+        previews.push(preview);
+    }
+
+    client
+        .batch_execute("ROLLBACK")
+        .await
+        .map_err(|e| format!("Failed to rollback transaction: {}", e))?;
+
+    Ok(previews)
+}
+
+/// Evaluate a single default expression under a savepoint
+async fn evaluate_default(client: &Client, expression: &str) -> Result<Option<String>, String> {
+    client
+        .batch_execute("SAVEPOINT preview_default")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match client
+        .query_one(&format!("SELECT ({})::text", expression), &[])
+        .await
+    {
+        Ok(row) => {
+            client
+                .batch_execute("RELEASE SAVEPOINT preview_default")
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(row.get(0))
+        }
+        Err(e) => {
+            let message = e
+                .as_db_error()
+                .map(|db| db.message().to_string())
+                .unwrap_or_else(|| e.to_string());
+            client
+                .batch_execute("ROLLBACK TO SAVEPOINT preview_default")
+                .await
+                .map_err(|e| e.to_string())?;
+            Err(message)
+        }
+    }
+}
+
+/// Whether a default expression draws from a sequence (e.g. serial columns)
+fn is_sequence_default(expression: &str) -> bool {
+    expression.to_lowercase().contains("nextval(")
+}
+
+/// Get per-column constraints of a table for validating an edit form
+///
+/// Combines column definitions and CHECK constraints from `get_table_schema`
+/// with enum labels, so the UI can validate values before submitting.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `table` - Table name
+///
+/// # Returns
+/// * `Result<Vec<ColumnEditConstraints>, String>` - One entry per column in ordinal order
+pub async fn get_edit_constraints(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ColumnEditConstraints>, String> {
+    let table_schema = get_table_schema(client, schema, table).await?;
+    let enum_options = get_enum_options(client, schema, table).await?;
+
+    Ok(table_schema
+        .columns
+        .iter()
+        .map(|column| {
+            let check_expressions = table_schema
+                .constraints
+                .iter()
+                .filter(|c| c.constraint_type == "CHECK" && c.columns.contains(&column.name))
+                .filter_map(|c| c.check_clause.clone())
+                .collect();
+
+            ColumnEditConstraints {
+                name: column.name.clone(),
+                data_type: column.data_type.clone(),
+                nullable: column.is_nullable,
+                default: column.column_default.clone(),
+                max_length: column.character_maximum_length,
+                numeric_precision: column.numeric_precision,
+                numeric_scale: column.numeric_scale,
+                enum_options: enum_options.get(&column.name).cloned(),
+                check_expressions,
+            }
+        })
+        .collect())
+}
+
+/// Get the allowed labels of enum-typed columns, keyed by column name
+async fn get_enum_options(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    let query = r#"
+        SELECT
+            a.attname,
+            array_agg(e.enumlabel::text ORDER BY e.enumsortorder)
+        FROM pg_attribute a
+        JOIN pg_class c ON c.oid = a.attrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        JOIN pg_enum e ON e.enumtypid = a.atttypid
+        WHERE n.nspname = $1 AND c.relname = $2
+          AND a.attnum > 0
+          AND NOT a.attisdropped
+        GROUP BY a.attname
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query enum options: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect())
+}
+
 /// Get list of database objects for auto-completion
 /// 
 /// # Arguments
@@ -421,6 +1223,164 @@ async fn get_function_names(client: &Client) -> Result<Vec<String>, String> {
     Ok(functions)
 }
 
+/// List all user tables in the database as (schema, table) pairs
+///
+/// Only base tables are returned; views and foreign tables are skipped.
+pub async fn list_base_tables(client: &Client) -> Result<Vec<(String, String)>, String> {
+    let query = r#"
+        SELECT table_schema, table_name
+        FROM information_schema.tables
+        WHERE table_schema NOT IN ('pg_catalog', 'information_schema')
+          AND table_type = 'BASE TABLE'
+        ORDER BY table_schema, table_name
+    "#;
+    
+    let rows = client
+        .query(query, &[])
+        .await
+        .map_err(|e| format!("Failed to query tables: {}", e))?;
+    
+    Ok(rows
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect())
+}
+
+/// Get the primary key columns of a table, in key order
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `table` - Table name
+///
+/// # Returns
+/// * `Result<Vec<String>, String>` - Column names (empty when the table has no primary key), or error message
+pub async fn get_primary_key_columns(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<String>, String> {
+    let query = r#"
+        SELECT a.attname::text
+        FROM pg_constraint con
+        JOIN pg_class c ON c.oid = con.conrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        CROSS JOIN LATERAL unnest(con.conkey) WITH ORDINALITY AS k(attnum, ord)
+        JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = k.attnum
+        WHERE n.nspname = $1 AND c.relname = $2 AND con.contype = 'p'
+        ORDER BY k.ord
+    "#;
+    
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query primary key: {}", e))?;
+    
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+/// Check whether a schema exists
+pub async fn schema_exists(client: &Client, schema: &str) -> Result<bool, String> {
+    let row = client
+        .query_one(
+            "SELECT EXISTS (SELECT 1 FROM pg_namespace WHERE nspname = $1)",
+            &[&schema],
+        )
+        .await
+        .map_err(|e| format!("Failed to check schema: {}", e))?;
+
+    Ok(row.get(0))
+}
+
+/// List the objects that belong to a schema, e.g. `table sales.orders`
+///
+/// These are the objects `DROP SCHEMA ... CASCADE` would drop directly.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+///
+/// # Returns
+/// * `Result<Vec<String>, String>` - Object descriptions ordered by name, or error message
+pub async fn get_schema_dependents(client: &Client, schema: &str) -> Result<Vec<String>, String> {
+    let query = r#"
+        SELECT pg_describe_object(d.classid, d.objid, d.objsubid)
+        FROM pg_depend d
+        JOIN pg_namespace n ON n.oid = d.refobjid
+        WHERE d.refclassid = 'pg_namespace'::regclass
+          AND d.deptype = 'n'
+          AND n.nspname = $1
+        ORDER BY 1
+    "#;
+
+    let rows = client
+        .query(query, &[&schema])
+        .await
+        .map_err(|e| format!("Failed to query schema objects: {}", e))?;
+
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+/// Get the table comment and each column's comment
+///
+/// A lightweight alternative to `get_table_schema` for showing descriptions
+/// (e.g. as tooltips). Missing comments are returned as empty strings.
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `table` - Table name
+///
+/// # Returns
+/// * `Result<TableDocumentation, String>` - Table and column comments, or error message
+pub async fn get_table_documentation(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<TableDocumentation, String> {
+    let table_row = client
+        .query_opt(
+            r#"
+            SELECT COALESCE(obj_description(c.oid, 'pg_class'), '')
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'p', 'v', 'm', 'f')
+            "#,
+            &[&schema, &table],
+        )
+        .await
+        .map_err(|e| format!("Failed to query table comment: {}", e))?
+        .ok_or_else(|| format!("Table {}.{} does not exist", schema, table))?;
+
+    let query = r#"
+        SELECT a.attname::text, COALESCE(col_description(a.attrelid, a.attnum), '')
+        FROM pg_attribute a
+        JOIN pg_class c ON c.oid = a.attrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1
+          AND c.relname = $2
+          AND a.attnum > 0
+          AND NOT a.attisdropped
+        ORDER BY a.attnum
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query column comments: {}", e))?;
+
+    Ok(TableDocumentation {
+        table_comment: table_row.get(0),
+        columns: rows
+            .iter()
+            .map(|row| ColumnDocumentation {
+                name: row.get(0),
+                comment: row.get(1),
+            })
+            .collect(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -477,4 +1437,11 @@ mod tests {
         assert!(!marked[0].is_unique);
         assert!(marked[1].is_unique);
     }
+
+    #[test]
+    fn test_is_sequence_default() {
+        assert!(is_sequence_default("nextval('users_id_seq'::regclass)"));
+        assert!(!is_sequence_default("now()"));
+        assert!(!is_sequence_default("'active'::character varying"));
+    }
 }
@@ -0,0 +1,273 @@
+/**
+ * SQL Lexer
+ *
+ * This module splits SQL text into tokens for the lightweight statement analysis
+ * done before execution (DML previews, cross-database checks, COPY detection):
+ * - Skips whitespace and comments
+ * - Keeps string literals, dollar-quoted strings and quoted identifiers intact
+ * - Records the parenthesis depth of each token
+ */
+
+///   Kind of a lexical token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TokenKind {
+    /// Unquoted identifier or keyword
+    Word,
+    /// Double-quoted identifier
+    QuotedIdent,
+    /// String, number, or parameter
+    Literal,
+    Symbol(char),
+}
+
+/// A lexical token with its byte range and parenthesis depth
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Token {
+    pub(crate) kind: TokenKind,
+    /// Byte offset of the first character
+    pub(crate) start: usize,
+    /// Byte offset after the last character
+    pub(crate) end: usize,
+    /// Number of enclosing parentheses
+    pub(crate) depth: usize,
+}
+
+impl Token {
+    /// Source text of the token
+    pub(crate) fn text<'a>(&self, sql: &'a str) -> &'a str {
+        &sql[self.start..self.end]
+    }
+
+    /// Whether the token is the given keyword (case-insensitive, unquoted)
+    pub(crate) fn is_keyword(&self, sql: &str, keyword: &str) -> bool {
+        self.kind == TokenKind::Word && self.text(sql).eq_ignore_ascii_case(keyword)
+    }
+
+    /// Whether the token is a quoted or unquoted identifier
+    pub(crate) fn is_identifier(&self) -> bool {
+        matches!(self.kind, TokenKind::Word | TokenKind::QuotedIdent)
+    }
+
+    /// Identifier as PostgreSQL resolves it: quoted identifiers are unquoted,
+    /// unquoted ones are folded to lower case
+    pub(crate) fn identifier(&self, sql: &str) -> String {
+        let text = self.text(sql);
+        match self.kind {
+            TokenKind::QuotedIdent => text
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .unwrap_or(text)
+                .replace("\"\"", "\""),
+            _ => text.to_lowercase(),
+        }
+    }
+}
+
+/// Split SQL into tokens, skipping whitespace and comments
+///
+/// Parentheses are returned as symbols at the depth outside them; tokens between
+/// them have a depth one greater.
+pub(crate) fn tokenize(sql: &str) -> Vec<Token> {
+    let bytes = sql.as_bytes();
+    let len = bytes.len();
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < len {
+        let ch = bytes[i];
+
+        if ch.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        // Line comment
+        if ch == b'-' && bytes.get(i + 1) == Some(&b'-') {
+            while i < len && bytes[i] != b'\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        // Block comment
+        if ch == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            i += 2;
+            while i < len && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                i += 1;
+            }
+            i = (i + 2).min(len);
+            continue;
+        }
+
+        let start = i;
+
+        // String literal, optionally with an E prefix for backslash escapes
+        let escape_string = (ch == b'E' || ch == b'e') && bytes.get(i + 1) == Some(&b'\'');
+        if ch == b'\'' || escape_string {
+            i += if escape_string { 2 } else { 1 };
+            while i < len {
+                if escape_string && bytes[i] == b'\\' {
+                    i += 2;
+                    continue;
+                }
+                if bytes[i] == b'\'' {
+                    if bytes.get(i + 1) == Some(&b'\'') {
+                        i += 2;
+                        continue;
+                    }
+                    i += 1;
+                    break;
+                }
+                i += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Literal, start, end: i.min(len), depth });
+            continue;
+        }
+
+        // Quoted identifier
+        if ch == b'"' {
+            i += 1;
+            while i < len {
+                if bytes[i] == b'"' {
+                    if bytes.get(i + 1) == Some(&b'"') {
+                        i += 2;
+                        continue;
+                    }
+                    i += 1;
+                    break;
+                }
+                i += 1;
+            }
+            tokens.push(Token { kind: TokenKind::QuotedIdent, start, end: i.min(len), depth });
+            continue;
+        }
+
+        // Dollar-quoted string ($$...$$ or $tag$...$tag$) or positional parameter ($1)
+        if ch == b'$' {
+            let mut j = i + 1;
+            while j < len && (bytes[j].is_ascii_alphanumeric() || bytes[j] == b'_') {
+                j += 1;
+            }
+            if j < len && bytes[j] == b'$' && !bytes[i + 1].is_ascii_digit() {
+                let tag = &sql[i..=j];
+                i = match sql[j + 1..].find(tag) {
+                    Some(offset) => j + 1 + offset + tag.len(),
+                    None => len,
+                };
+            } else {
+                i = j;
+            }
+            tokens.push(Token { kind: TokenKind::Literal, start, end: i, depth });
+            continue;
+        }
+
+        // Identifier or keyword
+        if ch.is_ascii_alphabetic() || ch == b'_' || ch >= 0x80 {
+            while i < len
+                && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'$' || bytes[i] >= 0x80)
+            {
+                i += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Word, start, end: i, depth });
+            continue;
+        }
+
+        // Number
+        if ch.is_ascii_digit() {
+            while i < len && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                i += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Literal, start, end: i, depth });
+            continue;
+        }
+
+        // Symbol
+        match ch {
+            b'(' => {
+                tokens.push(Token { kind: TokenKind::Symbol('('), start, end: i + 1, depth });
+                depth += 1;
+            }
+            b')' => {
+                depth = depth.saturating_sub(1);
+                tokens.push(Token { kind: TokenKind::Symbol(')'), start, end: i + 1, depth });
+            }
+            _ => {
+                let ch = sql[i..].chars().next().unwrap_or(ch as char);
+                tokens.push(Token { kind: TokenKind::Symbol(ch), start, end: i + ch.len_utf8(), depth });
+                i += ch.len_utf8();
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    tokens
+}
+
+/// Keywords of statements that write data or change the schema
+///
+/// They are matched anywhere in a statement, which also covers data-modifying
+/// CTEs (`WITH d AS (DELETE ...)`), `SELECT ... INTO` and `EXPLAIN ANALYZE` of a write.
+pub(crate) const WRITE_KEYWORDS: [&str; 12] = [
+    "INSERT", "UPDATE", "DELETE", "MERGE", "INTO", "TRUNCATE", "CREATE", "DROP", "ALTER", "COPY", "CALL", "REFRESH",
+];
+
+/// First token of `sql` that makes the statement write or lock rows
+///
+/// Besides `WRITE_KEYWORDS` this finds the row locks `FOR SHARE` and `FOR KEY SHARE`
+/// (`FOR UPDATE` and `FOR NO KEY UPDATE` already contain `UPDATE`).
+pub(crate) fn find_write_keyword<'a>(sql: &str, tokens: &'a [Token]) -> Option<&'a Token> {
+    tokens.iter().enumerate().find_map(|(i, token)| {
+        let locks_rows = token.is_keyword(sql, "SHARE")
+            && i.checked_sub(1)
+                .is_some_and(|prev| tokens[prev].is_keyword(sql, "FOR") || tokens[prev].is_keyword(sql, "KEY"));
+        let writes = WRITE_KEYWORDS.iter().any(|keyword| token.is_keyword(sql, keyword));
+        (writes || locks_rows).then_some(token)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_skips_comments_and_strings() {
+        let sql = "SELECT 'a;b', $$x$$ /* c */ FROM \"My \"\"T\"\"\" -- d";
+        let tokens = tokenize(sql);
+        let kinds: Vec<TokenKind> = tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Word,
+                TokenKind::Literal,
+                TokenKind::Symbol(','),
+                TokenKind::Literal,
+                TokenKind::Word,
+                TokenKind::QuotedIdent,
+            ]
+        );
+        assert_eq!(tokens[5].identifier(sql), "My \"T\"");
+        assert_eq!(tokens[0].identifier(sql), "select");
+    }
+
+    #[test]
+    fn test_tokenize_depth() {
+        let sql = "f(a, (b))";
+        let depths: Vec<usize> = tokenize(sql).iter().map(|t| t.depth).collect();
+        assert_eq!(depths, vec![0, 0, 1, 1, 1, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_find_write_keyword() {
+        let found = |sql: &str| find_write_keyword(sql, &tokenize(sql)).map(|t| t.text(sql).to_uppercase());
+        assert_eq!(found("SELECT * FROM users WHERE note = 'delete me'"), None);
+        assert_eq!(found("SELECT \"update\" FROM t"), None);
+        assert_eq!(found("SELECT share FROM t"), None);
+        assert_eq!(found("WITH d AS (DELETE FROM t RETURNING *) SELECT * FROM d").as_deref(), Some("DELETE"));
+        assert_eq!(found("SELECT * INTO t2 FROM t").as_deref(), Some("INTO"));
+        assert_eq!(found("SELECT * FROM t FOR UPDATE").as_deref(), Some("UPDATE"));
+        assert_eq!(found("SELECT * FROM t FOR share").as_deref(), Some("SHARE"));
+        assert_eq!(found("SELECT * FROM t FOR KEY SHARE SKIP LOCKED").as_deref(), Some("SHARE"));
+    }
+}
@@ -8,13 +8,20 @@
  * - 影响的行数
  * - 错误信息
  * - 用户和数据库信息
+ *
+ * 同时提供历史记录的读取、筛选、分页以及导出（CSV/JSON）。
+ * 日志按天分文件保存，创建日志记录器时删除超过保留天数的日志，
+ * 总大小（包括 SQLite 日志文件）超过上限时从最旧的日志开始删除。
+ * 也可以改为写入单个 SQLite 文件（见 `sqlite_log`），历史查询和搜索由 SQLite 完成。
  */
 
-use chrono::Local;
+use chrono::{Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use super::sqlite_log;
 
 /// SQL 执行日志条目
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -135,15 +142,71 @@ impl SqlLogEntry {
     }
 }
 
+/// 默认保留天数
+pub const DEFAULT_MAX_AGE_DAYS: u32 = 90;
+
+/// 默认的日志总大小上限（512 MB）
+pub const DEFAULT_MAX_TOTAL_BYTES: u64 = 512 * 1024 * 1024;
+
+/// SQL 日志的存储方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlLogBackend {
+    /// 按天写入文本日志和 JSON Lines 文件
+    #[default]
+    File,
+    /// 写入日志目录中的单个 SQLite 文件
+    Sqlite,
+}
+
+/// SQL 日志设置，保留天数和大小上限设为 0 表示不限制
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SqlLoggerConfig {
+    /// 存储方式
+    #[serde(default)]
+    pub backend: SqlLogBackend,
+    /// 日志目录中按天划分的 SQL 日志文件和 SQLite 日志文件的总大小上限（字节）
+    #[serde(default = "default_max_total_bytes")]
+    pub max_total_bytes: u64,
+    /// 日志文件保留的天数
+    #[serde(default = "default_max_age_days")]
+    pub max_age_days: u32,
+}
+
+fn default_max_total_bytes() -> u64 {
+    DEFAULT_MAX_TOTAL_BYTES
+}
+
+fn default_max_age_days() -> u32 {
+    DEFAULT_MAX_AGE_DAYS
+}
+
+impl Default for SqlLoggerConfig {
+    fn default() -> Self {
+        Self {
+            backend: SqlLogBackend::File,
+            max_total_bytes: DEFAULT_MAX_TOTAL_BYTES,
+            max_age_days: DEFAULT_MAX_AGE_DAYS,
+        }
+    }
+}
+
 /// SQL 日志记录器
 pub struct SqlLogger {
+    log_dir: PathBuf,
+    config: SqlLoggerConfig,
     log_file_path: PathBuf,
     json_log_path: PathBuf,
 }
 
 impl SqlLogger {
-    /// 创建新的日志记录器
+    /// 创建新的日志记录器（使用默认的保留设置）
     pub fn new(log_dir: PathBuf) -> Result<Self, String> {
+        Self::with_config(log_dir, SqlLoggerConfig::default())
+    }
+
+    /// 使用指定的保留设置创建日志记录器，并按保留设置清理旧日志（清理失败只记录警告）
+    pub fn with_config(log_dir: PathBuf, config: SqlLoggerConfig) -> Result<Self, String> {
         // 确保日志目录存在
         std::fs::create_dir_all(&log_dir)
             .map_err(|e| format!("无法创建日志目录: {}", e))?;
@@ -153,14 +216,118 @@ impl SqlLogger {
         let log_file_path = log_dir.join(format!("sql_execution_{}.log", date));
         let json_log_path = log_dir.join(format!("sql_execution_{}.jsonl", date));
 
-        Ok(Self {
+        let logger = Self {
+            log_dir,
+            config,
             log_file_path,
             json_log_path,
-        })
+        };
+
+        match logger.prune() {
+            Ok(0) => {}
+            Ok(pruned) => log::info!("已清理 {} 个旧的 SQL 日志文件", pruned),
+            Err(e) => log::warn!("清理 SQL 日志失败: {}", e),
+        }
+        match logger.prune_records() {
+            Ok(0) => {}
+            Ok(pruned) => log::info!("已从 SQLite 日志中清理 {} 条旧记录", pruned),
+            Err(e) => log::warn!("清理 SQLite 日志失败: {}", e),
+        }
+
+        Ok(logger)
+    }
+
+    /// 删除超过保留天数的日志文件，总大小仍超过上限时继续从最旧的文件开始删除
+    ///
+    /// SQLite 日志文件的大小计入总大小，但文件本身不会被删除（见 `prune_records`）。
+    /// 当天的日志文件正在写入，不会被删除。
+    ///
+    /// # Returns
+    /// * `Result<usize, String>` - 删除的文件数或错误信息
+    pub fn prune(&self) -> Result<usize, String> {
+        self.prune_as_of(Local::now().date_naive())
+    }
+
+    /// 以 `today` 为当天执行清理
+    fn prune_as_of(&self, today: NaiveDate) -> Result<usize, String> {
+        let files = self.daily_log_files()?;
+
+        let mut total_bytes: u64 =
+            files.iter().map(|(_, _, size)| size).sum::<u64>() + sqlite_log::file_size(&self.log_dir);
+        let mut pruned = 0;
+        for (date, path, size) in files {
+            if date >= today {
+                break;
+            }
+
+            let too_old = self.config.max_age_days > 0
+                && (today - date).num_days() > i64::from(self.config.max_age_days);
+            let too_large = self.config.max_total_bytes > 0 && total_bytes > self.config.max_total_bytes;
+            if !too_old && !too_large {
+                continue;
+            }
+
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("无法删除日志文件 {}: {}", path.display(), e))?;
+            total_bytes -= size;
+            pruned += 1;
+        }
+
+        Ok(pruned)
+    }
+
+    /// 删除 SQLite 日志中超过保留天数的记录，SQLite 日志文件与按天的日志文件总大小仍超过上限时
+    /// 继续从最旧的记录开始删除；没有 SQLite 日志文件时不做任何操作
+    ///
+    /// # Returns
+    /// * `Result<usize, String>` - 删除的记录数或错误信息
+    pub fn prune_records(&self) -> Result<usize, String> {
+        if !sqlite_log::sqlite_log_path(&self.log_dir).exists() {
+            return Ok(0);
+        }
+
+        let mut pruned = 0;
+        if self.config.max_age_days > 0 {
+            let cutoff = Local::now().date_naive() - chrono::Duration::days(i64::from(self.config.max_age_days));
+            pruned += sqlite_log::delete_before(&self.log_dir, &cutoff.format("%Y-%m-%d").to_string())?;
+        }
+        if self.config.max_total_bytes > 0 {
+            let daily_bytes: u64 = self.daily_log_files()?.iter().map(|(_, _, size)| size).sum();
+            pruned += sqlite_log::shrink_to(&self.log_dir, self.config.max_total_bytes.saturating_sub(daily_bytes))?;
+        }
+        Ok(pruned)
+    }
+
+    /// 日志目录中按天划分的 SQL 日志文件 (日期, 路径, 大小)，最旧的在前
+    fn daily_log_files(&self) -> Result<Vec<(NaiveDate, PathBuf, u64)>, String> {
+        let dir_entries = std::fs::read_dir(&self.log_dir)
+            .map_err(|e| format!("无法读取日志目录: {}", e))?;
+
+        let mut files = Vec::new();
+        for dir_entry in dir_entries.flatten() {
+            let file_name = dir_entry.file_name().to_string_lossy().to_string();
+            let date = file_name
+                .strip_prefix(JSON_LOG_PREFIX)
+                .and_then(|rest| rest.strip_suffix(JSON_LOG_SUFFIX).or_else(|| rest.strip_suffix(TEXT_LOG_SUFFIX)))
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+            let (Some(date), Ok(metadata)) = (date, dir_entry.metadata()) else {
+                continue;
+            };
+            if metadata.is_file() {
+                files.push((date, dir_entry.path(), metadata.len()));
+            }
+        }
+
+        files.sort();
+        Ok(files)
     }
 
     /// 记录 SQL 执行日志
     pub fn log(&self, entry: &SqlLogEntry) -> Result<(), String> {
+        if self.config.backend == SqlLogBackend::Sqlite {
+            return sqlite_log::insert(&self.log_dir, entry);
+        }
+
         // 写入文本日志
         self.write_text_log(entry)?;
         
@@ -212,6 +379,306 @@ impl SqlLogger {
     }
 }
 
+/// JSON 日志文件名前缀
+const JSON_LOG_PREFIX: &str = "sql_execution_";
+
+/// JSON 日志文件扩展名
+const JSON_LOG_SUFFIX: &str = ".jsonl";
+
+/// 文本日志文件扩展名
+const TEXT_LOG_SUFFIX: &str = ".log";
+
+/// SQL 历史记录筛选条件，未设置的条件不参与筛选
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SqlHistoryFilter {
+    /// 数据库名称
+    #[serde(default)]
+    pub database: Option<String>,
+    /// 起始日期（YYYY-MM-DD，包含）
+    #[serde(default)]
+    pub start_date: Option<String>,
+    /// 结束日期（YYYY-MM-DD，包含）
+    #[serde(default)]
+    pub end_date: Option<String>,
+    /// 查询类型（SELECT/INSERT/UPDATE/DELETE/DDL/ERROR）
+    #[serde(default)]
+    pub query_type: Option<String>,
+    /// 执行状态（success/error）
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl SqlHistoryFilter {
+    /// 判断日志条目是否满足筛选条件
+    pub fn matches(&self, entry: &SqlLogEntry) -> bool {
+        if let Some(database) = &self.database {
+            if &entry.database != database {
+                return false;
+            }
+        }
+
+        if let Some(query_type) = &self.query_type {
+            if !entry.query_type.eq_ignore_ascii_case(query_type) {
+                return false;
+            }
+        }
+
+        if let Some(status) = &self.status {
+            if !entry.status.eq_ignore_ascii_case(status) {
+                return false;
+            }
+        }
+
+        // 时间戳以 YYYY-MM-DD 开头，可直接按字符串比较日期
+        let date = entry.timestamp.get(..10).unwrap_or(&entry.timestamp);
+        self.date_in_range(date)
+    }
+
+    /// 判断日期是否在筛选范围内
+    fn date_in_range(&self, date: &str) -> bool {
+        if let Some(start) = &self.start_date {
+            if date < start.as_str() {
+                return false;
+            }
+        }
+
+        if let Some(end) = &self.end_date {
+            if date > end.as_str() {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// 一页 SQL 历史记录
+#[derive(Debug, Serialize, Clone)]
+pub struct SqlHistoryPage {
+    /// 本页的日志条目（按时间倒序）
+    pub entries: Vec<SqlLogEntry>,
+    /// 满足筛选条件的条目总数
+    pub total: usize,
+    /// 跳过的条目数
+    pub offset: usize,
+    /// 本页最多返回的条目数
+    pub limit: usize,
+    /// 满足筛选条件的所有条目的执行耗时合计（毫秒）
+    pub total_duration_ms: u64,
+    /// 满足筛选条件的所有条目影响的行数合计
+    pub total_affected_rows: u64,
+}
+
+/// SQL 历史导出格式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryExportFormat {
+    Csv,
+    Json,
+}
+
+/// 读取日志目录中满足筛选条件的 SQL 历史记录
+///
+/// # Arguments
+/// * `log_dir` - 日志目录（包含按日期划分的 `sql_execution_YYYY-MM-DD.jsonl` 文件）
+/// * `filter` - 筛选条件
+///
+/// # Returns
+/// * `Result<Vec<SqlLogEntry>, String>` - 按时间倒序排列的日志条目或错误信息
+///
+/// 无法解析的行会被跳过；日期不在筛选范围内的日志文件不会被读取。
+pub fn read_history(log_dir: &Path, filter: &SqlHistoryFilter) -> Result<Vec<SqlLogEntry>, String> {
+    let dir_entries = std::fs::read_dir(log_dir)
+        .map_err(|e| format!("无法读取日志目录: {}", e))?;
+
+    let mut entries = Vec::new();
+    for dir_entry in dir_entries.flatten() {
+        let file_name = dir_entry.file_name().to_string_lossy().to_string();
+        let date = match file_name
+            .strip_prefix(JSON_LOG_PREFIX)
+            .and_then(|rest| rest.strip_suffix(JSON_LOG_SUFFIX))
+        {
+            Some(date) => date.to_string(),
+            None => continue,
+        };
+
+        if !filter.date_in_range(&date) {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(dir_entry.path())
+            .map_err(|e| format!("无法读取日志文件 {}: {}", file_name, e))?;
+
+        // 文件中越靠后的记录越新，倒序读取后，时间戳相同的记录在稳定排序后仍是新的在前
+        entries.extend(
+            content
+                .lines()
+                .rev()
+                .filter(|line| !line.trim().is_empty())
+                .filter_map(|line| serde_json::from_str::<SqlLogEntry>(line).ok())
+                .filter(|entry| filter.matches(entry)),
+        );
+    }
+
+    // 时间戳格式固定，字符串倒序即时间倒序
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(entries)
+}
+
+/// 分页读取 SQL 历史记录（按时间倒序）
+///
+/// # Arguments
+/// * `log_dir` - 日志目录
+/// * `backend` - 日志的存储方式
+/// * `filter` - 筛选条件
+/// * `limit` - 最多返回的条目数
+/// * `offset` - 跳过的条目数
+///
+/// # Returns
+/// * `Result<SqlHistoryPage, String>` - 一页日志条目及所有满足条件条目的合计，或错误信息
+pub fn query_history(
+    log_dir: &Path,
+    backend: SqlLogBackend,
+    filter: &SqlHistoryFilter,
+    limit: usize,
+    offset: usize,
+) -> Result<SqlHistoryPage, String> {
+    if backend == SqlLogBackend::Sqlite {
+        return sqlite_log::query_history(log_dir, filter, limit, offset);
+    }
+
+    let limit = limit.max(1);
+
+    let entries = read_history(log_dir, filter)?;
+    let total = entries.len();
+    let total_duration_ms = entries.iter().map(|entry| entry.duration_ms).sum();
+    let total_affected_rows = entries.iter().filter_map(|entry| entry.affected_rows).sum();
+    let entries = entries
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .collect();
+
+    Ok(SqlHistoryPage {
+        entries,
+        total,
+        offset,
+        limit,
+        total_duration_ms,
+        total_affected_rows,
+    })
+}
+
+/// 在所有日期的 SQL 历史记录中搜索 SQL 文本（不区分大小写）
+///
+/// # Arguments
+/// * `log_dir` - 日志目录
+/// * `backend` - 日志的存储方式
+/// * `term` - 要查找的文本
+/// * `limit` - 最多返回的条目数
+///
+/// # Returns
+/// * `Result<Vec<SqlLogEntry>, String>` - SQL 中包含该文本的日志条目（按时间倒序）或错误信息
+pub fn search_history(
+    log_dir: &Path,
+    backend: SqlLogBackend,
+    term: &str,
+    limit: usize,
+) -> Result<Vec<SqlLogEntry>, String> {
+    let term = term.trim();
+    if term.is_empty() {
+        return Err("搜索内容不能为空".to_string());
+    }
+    if backend == SqlLogBackend::Sqlite {
+        return sqlite_log::search_history(log_dir, term, limit);
+    }
+
+    let term = term.to_lowercase();
+
+    let entries = read_history(log_dir, &SqlHistoryFilter::default())?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| entry.sql.to_lowercase().contains(&term))
+        .take(limit.max(1))
+        .collect())
+}
+
+/// 将满足筛选条件的 SQL 历史记录导出到文件
+///
+/// # Arguments
+/// * `log_dir` - 日志目录
+/// * `backend` - 日志的存储方式
+/// * `filter` - 筛选条件
+/// * `format` - 导出格式（CSV 或 JSON）
+/// * `output_path` - 导出文件路径
+///
+/// # Returns
+/// * `Result<usize, String>` - 导出的条目数或错误信息
+pub fn export_history(
+    log_dir: &Path,
+    backend: SqlLogBackend,
+    filter: &SqlHistoryFilter,
+    format: HistoryExportFormat,
+    output_path: &Path,
+) -> Result<usize, String> {
+    let entries = match backend {
+        SqlLogBackend::File => read_history(log_dir, filter)?,
+        SqlLogBackend::Sqlite => sqlite_log::read_history(log_dir, filter)?,
+    };
+
+    let content = match format {
+        HistoryExportFormat::Csv => history_to_csv(&entries),
+        HistoryExportFormat::Json => serde_json::to_string_pretty(&entries)
+            .map_err(|e| format!("无法序列化历史记录: {}", e))?,
+    };
+
+    std::fs::write(output_path, content)
+        .map_err(|e| format!("无法写入导出文件: {}", e))?;
+
+    Ok(entries.len())
+}
+
+/// 将日志条目格式化为 CSV（带表头）
+fn history_to_csv(entries: &[SqlLogEntry]) -> String {
+    let mut csv = String::from(
+        "timestamp,database,status,query_type,duration_ms,affected_rows,returned_rows,sql,error,error_position\n",
+    );
+
+    for entry in entries {
+        let fields = [
+            entry.timestamp.clone(),
+            entry.database.clone(),
+            entry.status.clone(),
+            entry.query_type.clone(),
+            entry.duration_ms.to_string(),
+            entry.affected_rows.map(|n| n.to_string()).unwrap_or_default(),
+            entry.returned_rows.map(|n| n.to_string()).unwrap_or_default(),
+            entry.sql.clone(),
+            entry.error.clone().unwrap_or_default(),
+            entry.error_position.clone().unwrap_or_default(),
+        ];
+
+        let line = fields
+            .iter()
+            .map(|field| escape_csv_field(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push_str(&line);
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// 对包含逗号、引号或换行的字段加引号
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') || field.contains('\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,4 +763,284 @@ mod tests {
         // Cleanup
         let _ = std::fs::remove_dir_all(temp_dir);
     }
+
+    #[test]
+    fn test_prune_removes_expired_then_oldest_files() {
+        let temp_dir = env::temp_dir().join("sql_logger_prune_test");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        // 先创建日志记录器，避免创建时按当前日期清理掉下面的文件
+        let logger = SqlLogger::with_config(
+            temp_dir.clone(),
+            SqlLoggerConfig { max_total_bytes: 450, max_age_days: 30, ..Default::default() },
+        )
+        .unwrap();
+
+        let write = |name: &str, size: usize| std::fs::write(temp_dir.join(name), vec![b'x'; size]).unwrap();
+        write("sql_execution_2026-01-01.log", 100);
+        write("sql_execution_2026-01-01.jsonl", 100);
+        write("sql_execution_2026-03-01.jsonl", 100);
+        write("sql_execution_2026-03-09.jsonl", 100);
+        write("sql_execution_2026-03-10.jsonl", 300);
+        write("notes.txt", 1000);
+
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+
+        // 两个过期文件按天数删除，2026-03-01 按大小删除，当天的文件和其他文件保留
+        assert_eq!(logger.prune_as_of(today).unwrap(), 3);
+        assert!(!temp_dir.join("sql_execution_2026-01-01.log").exists());
+        assert!(!temp_dir.join("sql_execution_2026-03-01.jsonl").exists());
+        assert!(temp_dir.join("sql_execution_2026-03-09.jsonl").exists());
+        assert!(temp_dir.join("sql_execution_2026-03-10.jsonl").exists());
+        assert!(temp_dir.join("notes.txt").exists());
+
+        assert_eq!(logger.prune_as_of(today).unwrap(), 0);
+
+        // 0 表示不限制
+        let unlimited = SqlLogger::with_config(
+            temp_dir.clone(),
+            SqlLoggerConfig { max_total_bytes: 0, max_age_days: 0, ..Default::default() },
+        )
+        .unwrap();
+        assert_eq!(unlimited.prune_as_of(NaiveDate::from_ymd_opt(2030, 1, 1).unwrap()).unwrap(), 0);
+
+        let _ = std::fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn test_with_config_prunes_expired_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let today = Local::now().date_naive();
+        let file_name = |date: NaiveDate| format!("sql_execution_{}.jsonl", date.format("%Y-%m-%d"));
+        let expired = file_name(today - chrono::Duration::days(40));
+        std::fs::write(temp_dir.path().join(&expired), "{}").unwrap();
+        std::fs::write(temp_dir.path().join(file_name(today)), "{}").unwrap();
+
+        SqlLogger::with_config(
+            temp_dir.path().to_path_buf(),
+            SqlLoggerConfig { max_age_days: 30, ..Default::default() },
+        )
+        .unwrap();
+
+        assert!(!temp_dir.path().join(&expired).exists());
+        assert!(temp_dir.path().join(file_name(today)).exists());
+    }
+
+    #[test]
+    fn test_sqlite_log_counts_against_total_size() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log_dir = temp_dir.path().to_path_buf();
+        let config = SqlLoggerConfig {
+            backend: SqlLogBackend::Sqlite,
+            max_total_bytes: 0,
+            max_age_days: 0,
+        };
+        let logger = SqlLogger::with_config(log_dir.clone(), config.clone()).unwrap();
+        for _ in 0..200 {
+            logger
+                .log(&SqlLogEntry::success(
+                    "test_db".to_string(),
+                    format!("SELECT '{}'", "x".repeat(1000)),
+                    1,
+                    "SELECT".to_string(),
+                    None,
+                    Some(1),
+                ))
+                .unwrap();
+        }
+        std::fs::write(log_dir.join("sql_execution_2020-01-01.jsonl"), vec![b'x'; 1000]).unwrap();
+        let sqlite_size = sqlite_log::file_size(&log_dir);
+
+        // 上限小于 SQLite 日志文件：按天的旧日志文件先被删除，再从最旧的记录开始删除 SQLite 日志
+        let limit = sqlite_size / 2;
+        SqlLogger::with_config(log_dir.clone(), SqlLoggerConfig { max_total_bytes: limit, ..config }).unwrap();
+
+        assert!(!log_dir.join("sql_execution_2020-01-01.jsonl").exists());
+        assert!(sqlite_log::file_size(&log_dir) <= limit);
+        let page = query_history(&log_dir, SqlLogBackend::Sqlite, &SqlHistoryFilter::default(), 10, 0).unwrap();
+        assert!(page.total > 0 && page.total < 200);
+    }
+
+    #[test]
+    fn test_sqlite_backend_logs_and_queries_history() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = SqlLoggerConfig {
+            backend: SqlLogBackend::Sqlite,
+            ..Default::default()
+        };
+        let logger = SqlLogger::with_config(temp_dir.path().to_path_buf(), config).unwrap();
+
+        logger
+            .log(&SqlLogEntry::success(
+                "test_db".to_string(),
+                "UPDATE users SET active = true".to_string(),
+                8,
+                "UPDATE".to_string(),
+                Some(5),
+                None,
+            ))
+            .unwrap();
+
+        // 只写入 SQLite，不产生按天的日志文件
+        assert!(temp_dir.path().join(sqlite_log::SQLITE_LOG_FILE).exists());
+        assert!(!logger.get_json_log_path().exists());
+
+        let page = query_history(temp_dir.path(), SqlLogBackend::Sqlite, &SqlHistoryFilter::default(), 10, 0).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.total_affected_rows, 5);
+        assert_eq!(search_history(temp_dir.path(), SqlLogBackend::Sqlite, "USERS", 10).unwrap().len(), 1);
+        assert!(search_history(temp_dir.path(), SqlLogBackend::File, "users", 10).unwrap().is_empty());
+        assert_eq!(logger.prune_records().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_export_error_only_history() {
+        let temp_dir = env::temp_dir().join("sql_logger_export_test");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let logger = SqlLogger::new(temp_dir.clone()).unwrap();
+
+        logger
+            .log(&SqlLogEntry::success(
+                "test_db".to_string(),
+                "SELECT * FROM users".to_string(),
+                12,
+                "SELECT".to_string(),
+                None,
+                Some(3),
+            ))
+            .unwrap();
+        logger
+            .log(&SqlLogEntry::error(
+                "test_db".to_string(),
+                "SELECT * FROM missing, other".to_string(),
+                4,
+                "relation \"missing\" does not exist".to_string(),
+                Some("Line 1, Column 15".to_string()),
+            ))
+            .unwrap();
+        logger
+            .log(&SqlLogEntry::success(
+                "test_db".to_string(),
+                "UPDATE users SET active = true".to_string(),
+                8,
+                "UPDATE".to_string(),
+                Some(5),
+                None,
+            ))
+            .unwrap();
+        logger
+            .log(&SqlLogEntry::error(
+                "other_db".to_string(),
+                "DROP TABLE nope".to_string(),
+                2,
+                "table \"nope\" does not exist".to_string(),
+                None,
+            ))
+            .unwrap();
+
+        let filter = SqlHistoryFilter {
+            database: Some("test_db".to_string()),
+            status: Some("error".to_string()),
+            ..Default::default()
+        };
+
+        let page = query_history(&temp_dir, SqlLogBackend::File, &filter, 10, 0).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].duration_ms, 4);
+
+        // 新的在前，合计覆盖所有满足条件的条目而不只是本页
+        let page = query_history(&temp_dir, SqlLogBackend::File, &SqlHistoryFilter::default(), 2, 1).unwrap();
+        assert_eq!(page.total, 4);
+        assert_eq!(page.entries.len(), 2);
+        assert_eq!(page.entries[0].sql, "UPDATE users SET active = true");
+        assert_eq!(page.entries[1].sql, "SELECT * FROM missing, other");
+        assert_eq!(page.total_duration_ms, 12 + 4 + 8 + 2);
+        assert_eq!(page.total_affected_rows, 5);
+
+        let all_errors = SqlHistoryFilter {
+            status: Some("error".to_string()),
+            ..Default::default()
+        };
+        let csv_path = temp_dir.join("history.csv");
+        let exported = export_history(&temp_dir, SqlLogBackend::File, &all_errors, HistoryExportFormat::Csv, &csv_path).unwrap();
+        assert_eq!(exported, 2);
+
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("timestamp,database,status"));
+        assert!(csv.contains("\"SELECT * FROM missing, other\""));
+        assert!(csv.contains("\"relation \"\"missing\"\" does not exist\""));
+        assert!(!csv.contains("UPDATE users"));
+
+        let json_path = temp_dir.join("history.json");
+        export_history(&temp_dir, SqlLogBackend::File, &all_errors, HistoryExportFormat::Json, &json_path).unwrap();
+        let exported_entries: Vec<SqlLogEntry> =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(exported_entries.len(), 2);
+        assert!(exported_entries.iter().all(|e| e.status == "error"));
+
+        // 日期范围之外不返回任何条目
+        let past = SqlHistoryFilter {
+            end_date: Some("2000-01-01".to_string()),
+            ..Default::default()
+        };
+        assert!(read_history(&temp_dir, &past).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(temp_dir);
+    }
+
+    #[test]
+    fn test_search_history_across_daily_files() {
+        let temp_dir = env::temp_dir().join("sql_logger_search_test");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let entry = |timestamp: &str, database: &str, sql: &str, duration_ms: u64| {
+            let mut entry = SqlLogEntry::success(
+                database.to_string(),
+                sql.to_string(),
+                duration_ms,
+                "SELECT".to_string(),
+                None,
+                Some(1),
+            );
+            entry.timestamp = timestamp.to_string();
+            entry.to_json().unwrap()
+        };
+
+        std::fs::write(
+            temp_dir.join("sql_execution_2026-01-05.jsonl"),
+            format!(
+                "{}\n{}\n",
+                entry("2026-01-05 09:00:00.000", "hr", "SELECT * FROM Employees WHERE dept = 'IT'", 30),
+                entry("2026-01-05 09:05:00.000", "hr", "SELECT 1", 1),
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.join("sql_execution_2026-01-12.jsonl"),
+            format!(
+                "{}\nnot json\n",
+                entry("2026-01-12 14:30:00.000", "sales", "select count(*) from employees", 7),
+            ),
+        )
+        .unwrap();
+        // 文本日志不参与搜索
+        std::fs::write(temp_dir.join("sql_execution_2026-01-12.log"), "SQL: SELECT * FROM employees\n").unwrap();
+
+        let results = search_history(&temp_dir, SqlLogBackend::File, "EMPLOYEES", 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].timestamp, "2026-01-12 14:30:00.000");
+        assert_eq!(results[0].database, "sales");
+        assert_eq!(results[0].duration_ms, 7);
+        assert_eq!(results[1].database, "hr");
+
+        assert_eq!(search_history(&temp_dir, SqlLogBackend::File, "employees", 1).unwrap().len(), 1);
+        assert!(search_history(&temp_dir, SqlLogBackend::File, "orders", 10).unwrap().is_empty());
+        assert!(search_history(&temp_dir, SqlLogBackend::File, "  ", 10).is_err());
+
+        let _ = std::fs::remove_dir_all(temp_dir);
+    }
 }
@@ -0,0 +1,56 @@
+/**
+ * SQL Utilities
+ *
+ * 此模块提供构建 SQL 文本时使用的通用辅助函数：
+ * - 标识符引用（始终加双引号，保留大小写）
+ * - 字符串字面量转义
+ * - schema.table 限定名构建
+ */
+
+///   将标识符用双引号包裹，并转义其中的双引号
+///
+/// 与 `ddl_generator` 中按需引用的方式不同，此函数始终加引号，
+/// 因此可以安全地用于任意用户提供的表名、列名。
+pub fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// 构建带 schema 限定的表名，例如 `"public"."users"`
+pub fn qualified_name(schema: &str, table: &str) -> String {
+    format!("{}.{}", quote_identifier(schema), quote_identifier(table))
+}
+
+/// 将字符串转义为 SQL 字符串字面量，例如 `O'Brien` -> `'O''Brien'`
+///
+/// 含反斜杠的字符串使用 `E'...'` 形式，以避免受 `standard_conforming_strings` 设置影响。
+pub fn quote_literal(value: &str) -> String {
+    if value.contains('\\') {
+        format!("E'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(quote_identifier("users"), "\"users\"");
+        assert_eq!(quote_identifier("MyTable"), "\"MyTable\"");
+        assert_eq!(quote_identifier("we\"ird"), "\"we\"\"ird\"");
+    }
+
+    #[test]
+    fn test_qualified_name() {
+        assert_eq!(qualified_name("public", "users"), "\"public\".\"users\"");
+    }
+
+    #[test]
+    fn test_quote_literal() {
+        assert_eq!(quote_literal("hello"), "'hello'");
+        assert_eq!(quote_literal("O'Brien"), "'O''Brien'");
+        assert_eq!(quote_literal("C:\\temp"), "E'C:\\\\temp'");
+    }
+}
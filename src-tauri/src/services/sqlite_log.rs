@@ -0,0 +1,370 @@
+/**
+ * SQLite Log Store
+ *
+ * SQL 执行日志的 SQLite 后端：
+ * - 所有日志条目写入日志目录下的单个 `sql_history.sqlite3` 文件
+ * - 历史记录的筛选、分页、合计和搜索通过 SQL `WHERE` 子句完成，不需要逐个扫描日志文件
+ * - 按保留天数删除过期的记录，文件超过大小上限时从最旧的记录开始删除
+ */
+
+use rusqlite::{params, params_from_iter, Connection, Row};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::sql_logger::{SqlHistoryFilter, SqlHistoryPage, SqlLogEntry};
+
+/// SQLite 日志文件名
+pub const SQLITE_LOG_FILE: &str = "sql_history.sqlite3";
+
+/// 其他进程或线程正在写入时等待的时间
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sql_log (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp TEXT NOT NULL,
+        database TEXT NOT NULL,
+        sql TEXT NOT NULL,
+        status TEXT NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        query_type TEXT NOT NULL,
+        affected_rows INTEGER,
+        returned_rows INTEGER,
+        error TEXT,
+        error_position TEXT
+    );
+    CREATE INDEX IF NOT EXISTS sql_log_timestamp ON sql_log (timestamp);
+    CREATE INDEX IF NOT EXISTS sql_log_database ON sql_log (database, timestamp);
+";
+
+const ENTRY_COLUMNS: &str = "timestamp, database, sql, status, duration_ms, query_type, \
+                             affected_rows, returned_rows, error, error_position";
+
+/// SQLite 日志文件路径
+pub fn sqlite_log_path(log_dir: &Path) -> PathBuf {
+    log_dir.join(SQLITE_LOG_FILE)
+}
+
+/// 打开（必要时创建）日志目录中的 SQLite 日志
+fn open(log_dir: &Path) -> Result<Connection, String> {
+    let connection = Connection::open(sqlite_log_path(log_dir))
+        .map_err(|e| format!("无法打开 SQLite 日志: {}", e))?;
+    connection
+        .busy_timeout(BUSY_TIMEOUT)
+        .and_then(|_| connection.execute_batch(SCHEMA))
+        .map_err(|e| format!("无法初始化 SQLite 日志: {}", e))?;
+    Ok(connection)
+}
+
+/// 写入一条日志
+pub fn insert(log_dir: &Path, entry: &SqlLogEntry) -> Result<(), String> {
+    let connection = open(log_dir)?;
+    connection
+        .execute(
+            &format!(
+                "INSERT INTO sql_log ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                ENTRY_COLUMNS
+            ),
+            params![
+                entry.timestamp,
+                entry.database,
+                entry.sql,
+                entry.status,
+                entry.duration_ms as i64,
+                entry.query_type,
+                entry.affected_rows.map(|n| n as i64),
+                entry.returned_rows.map(|n| n as i64),
+                entry.error,
+                entry.error_position,
+            ],
+        )
+        .map_err(|e| format!("无法写入 SQLite 日志: {}", e))?;
+    Ok(())
+}
+
+/// 筛选条件对应的 `WHERE` 子句和参数
+fn where_clause(filter: &SqlHistoryFilter) -> (String, Vec<String>) {
+    let mut conditions = Vec::new();
+    let mut values = Vec::new();
+
+    if let Some(database) = &filter.database {
+        values.push(database.clone());
+        conditions.push(format!("database = ?{}", values.len()));
+    }
+    if let Some(query_type) = &filter.query_type {
+        values.push(query_type.clone());
+        conditions.push(format!("query_type = ?{} COLLATE NOCASE", values.len()));
+    }
+    if let Some(status) = &filter.status {
+        values.push(status.clone());
+        conditions.push(format!("status = ?{} COLLATE NOCASE", values.len()));
+    }
+    // 时间戳以 YYYY-MM-DD 开头，可直接按字符串比较日期
+    if let Some(start) = &filter.start_date {
+        values.push(start.clone());
+        conditions.push(format!("substr(timestamp, 1, 10) >= ?{}", values.len()));
+    }
+    if let Some(end) = &filter.end_date {
+        values.push(end.clone());
+        conditions.push(format!("substr(timestamp, 1, 10) <= ?{}", values.len()));
+    }
+
+    if conditions.is_empty() {
+        (String::new(), values)
+    } else {
+        (format!("WHERE {}", conditions.join(" AND ")), values)
+    }
+}
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<SqlLogEntry> {
+    Ok(SqlLogEntry {
+        timestamp: row.get(0)?,
+        database: row.get(1)?,
+        sql: row.get(2)?,
+        status: row.get(3)?,
+        duration_ms: row.get::<_, i64>(4)? as u64,
+        query_type: row.get(5)?,
+        affected_rows: row.get::<_, Option<i64>>(6)?.map(|n| n as u64),
+        returned_rows: row.get::<_, Option<i64>>(7)?.map(|n| n as usize),
+        error: row.get(8)?,
+        error_position: row.get(9)?,
+    })
+}
+
+/// 按条件查询日志条目（按时间倒序）
+fn select_entries(
+    connection: &Connection,
+    where_sql: &str,
+    values: Vec<String>,
+    limit: Option<usize>,
+    offset: usize,
+) -> Result<Vec<SqlLogEntry>, String> {
+    // LIMIT -1 表示不限制
+    let sql = format!(
+        "SELECT {} FROM sql_log {} ORDER BY timestamp DESC, id DESC LIMIT {} OFFSET {}",
+        ENTRY_COLUMNS,
+        where_sql,
+        limit.map(|n| n as i64).unwrap_or(-1),
+        offset
+    );
+    let mut statement = connection
+        .prepare(&sql)
+        .map_err(|e| format!("无法查询 SQLite 日志: {}", e))?;
+    let entries = statement
+        .query_map(params_from_iter(values), entry_from_row)
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|e| format!("无法读取 SQLite 日志: {}", e))?;
+    Ok(entries)
+}
+
+/// 读取满足筛选条件的所有日志条目（按时间倒序）
+pub fn read_history(log_dir: &Path, filter: &SqlHistoryFilter) -> Result<Vec<SqlLogEntry>, String> {
+    let connection = open(log_dir)?;
+    let (where_sql, values) = where_clause(filter);
+    select_entries(&connection, &where_sql, values, None, 0)
+}
+
+/// 分页查询日志条目，合计覆盖所有满足条件的条目
+pub fn query_history(
+    log_dir: &Path,
+    filter: &SqlHistoryFilter,
+    limit: usize,
+    offset: usize,
+) -> Result<SqlHistoryPage, String> {
+    let limit = limit.max(1);
+    let connection = open(log_dir)?;
+    let (where_sql, values) = where_clause(filter);
+
+    let (total, total_duration_ms, total_affected_rows) = connection
+        .query_row(
+            &format!(
+                "SELECT count(*), COALESCE(sum(duration_ms), 0), COALESCE(sum(affected_rows), 0) FROM sql_log {}",
+                where_sql
+            ),
+            params_from_iter(values.iter()),
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)),
+        )
+        .map_err(|e| format!("无法统计 SQLite 日志: {}", e))?;
+
+    let entries = select_entries(&connection, &where_sql, values, Some(limit), offset)?;
+
+    Ok(SqlHistoryPage {
+        entries,
+        total: total as usize,
+        offset,
+        limit,
+        total_duration_ms: total_duration_ms as u64,
+        total_affected_rows: total_affected_rows as u64,
+    })
+}
+
+/// 查找 SQL 文本包含 `term` 的日志条目（不区分大小写，按时间倒序）
+pub fn search_history(log_dir: &Path, term: &str, limit: usize) -> Result<Vec<SqlLogEntry>, String> {
+    let connection = open(log_dir)?;
+    // SQLite 的 LIKE 对 ASCII 字母不区分大小写；转义通配符，按字面匹配
+    let pattern = format!(
+        "%{}%",
+        term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+    );
+    select_entries(
+        &connection,
+        "WHERE sql LIKE ?1 ESCAPE '\\'",
+        vec![pattern],
+        Some(limit.max(1)),
+        0,
+    )
+}
+
+/// 删除早于 `before`（YYYY-MM-DD，不含）的日志条目
+///
+/// # Returns
+/// * `Result<usize, String>` - 删除的条目数或错误信息
+pub fn delete_before(log_dir: &Path, before: &str) -> Result<usize, String> {
+    let path = sqlite_log_path(log_dir);
+    if !path.exists() {
+        return Ok(0);
+    }
+    let connection = open(log_dir)?;
+    // 时间戳以 YYYY-MM-DD 开头，直接比较字符串即可使用 timestamp 索引
+    connection
+        .execute("DELETE FROM sql_log WHERE timestamp < ?1", params![before])
+        .map_err(|e| format!("无法清理 SQLite 日志: {}", e))
+}
+
+/// SQLite 日志文件的大小（字节），文件不存在时为 0
+pub fn file_size(log_dir: &Path) -> u64 {
+    std::fs::metadata(sqlite_log_path(log_dir)).map(|metadata| metadata.len()).unwrap_or(0)
+}
+
+/// 从最旧的条目开始删除，直到 SQLite 日志文件不超过 `max_bytes`（或没有剩余条目）
+///
+/// # Returns
+/// * `Result<usize, String>` - 删除的条目数或错误信息
+pub fn shrink_to(log_dir: &Path, max_bytes: u64) -> Result<usize, String> {
+    if file_size(log_dir) <= max_bytes {
+        return Ok(0);
+    }
+
+    let connection = open(log_dir)?;
+    let mut deleted = 0;
+    loop {
+        let size = file_size(log_dir);
+        let count: i64 = connection
+            .query_row("SELECT count(*) FROM sql_log", [], |row| row.get(0))
+            .map_err(|e| format!("无法读取 SQLite 日志: {}", e))?;
+        if size <= max_bytes || count == 0 {
+            return Ok(deleted);
+        }
+
+        // 按超出上限的比例删除最旧的条目，至少删除一条
+        let excess = (size - max_bytes) as f64 / size as f64;
+        let batch = ((count as f64 * excess).ceil() as i64).max(1);
+        deleted += connection
+            .execute(
+                "DELETE FROM sql_log WHERE id IN (SELECT id FROM sql_log ORDER BY timestamp, id LIMIT ?1)",
+                params![batch],
+            )
+            .map_err(|e| format!("无法清理 SQLite 日志: {}", e))?;
+        // 删除条目不会缩小文件，VACUUM 后空闲的页才会还给文件系统
+        connection
+            .execute_batch("VACUUM")
+            .map_err(|e| format!("无法压缩 SQLite 日志: {}", e))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, database: &str, sql: &str, affected_rows: Option<u64>) -> SqlLogEntry {
+        let mut entry = SqlLogEntry::success(
+            database.to_string(),
+            sql.to_string(),
+            10,
+            if affected_rows.is_some() { "UPDATE" } else { "SELECT" }.to_string(),
+            affected_rows,
+            affected_rows.is_none().then_some(1),
+        );
+        entry.timestamp = timestamp.to_string();
+        entry
+    }
+
+    #[test]
+    fn test_sqlite_history_filters_pages_and_searches() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log_dir = temp_dir.path();
+
+        insert(log_dir, &entry("2026-01-05 09:00:00.000", "hr", "SELECT * FROM Employees", None)).unwrap();
+        insert(log_dir, &entry("2026-01-12 10:00:00.000", "hr", "UPDATE employees SET active = true", Some(4))).unwrap();
+        insert(log_dir, &entry("2026-01-12 11:00:00.000", "sales", "SELECT 100%", None)).unwrap();
+        insert(
+            log_dir,
+            &SqlLogEntry::error("hr".to_string(), "SELECT * FROM missing".to_string(), 3, "no".to_string(), None),
+        )
+        .unwrap();
+
+        let hr = SqlHistoryFilter {
+            database: Some("hr".to_string()),
+            status: Some("SUCCESS".to_string()),
+            ..Default::default()
+        };
+        let page = query_history(log_dir, &hr, 1, 0).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.total_duration_ms, 20);
+        assert_eq!(page.total_affected_rows, 4);
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].affected_rows, Some(4));
+
+        let january_5 = SqlHistoryFilter {
+            start_date: Some("2026-01-05".to_string()),
+            end_date: Some("2026-01-05".to_string()),
+            ..Default::default()
+        };
+        let entries = read_history(log_dir, &january_5).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].returned_rows, Some(1));
+
+        let found = search_history(log_dir, "employees", 10).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].timestamp, "2026-01-12 10:00:00.000");
+
+        // 通配符按字面匹配
+        assert_eq!(search_history(log_dir, "0%", 10).unwrap().len(), 1);
+        assert!(search_history(log_dir, "_", 10).unwrap().is_empty());
+
+        assert_eq!(delete_before(log_dir, "2026-01-12").unwrap(), 1);
+        assert_eq!(read_history(log_dir, &SqlHistoryFilter::default()).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_shrink_to_deletes_oldest_entries_until_under_limit() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log_dir = temp_dir.path();
+
+        let sql = format!("SELECT '{}'", "x".repeat(1000));
+        for day in 1..=28 {
+            for hour in 0..20 {
+                let timestamp = format!("2026-02-{:02} {:02}:00:00.000", day, hour);
+                insert(log_dir, &entry(&timestamp, "hr", &sql, None)).unwrap();
+            }
+        }
+        let size = file_size(log_dir);
+        assert!(size > 500_000);
+
+        assert_eq!(shrink_to(log_dir, size).unwrap(), 0);
+
+        let deleted = shrink_to(log_dir, size / 2).unwrap();
+        assert!(deleted > 0);
+        assert!(file_size(log_dir) <= size / 2);
+
+        // 保留的是最新的条目
+        let remaining = read_history(log_dir, &SqlHistoryFilter::default()).unwrap();
+        assert_eq!(remaining.len(), 28 * 20 - deleted);
+        assert!(remaining.iter().any(|entry| entry.timestamp == "2026-02-28 19:00:00.000"));
+        assert!(!remaining.iter().any(|entry| entry.timestamp == "2026-02-01 00:00:00.000"));
+
+        // 上限小于空数据库的大小时删除全部条目后停止
+        shrink_to(log_dir, 1).unwrap();
+        assert!(read_history(log_dir, &SqlHistoryFilter::default()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+/**
+ * Startup Check Service
+ *
+ * 此模块在应用启动时检查配置的数据库是否可以连接：
+ * - 连接配置的默认数据库并读取服务器版本
+ * - 建立的连接归还连接池，后续命令直接复用
+ * - 检查有超时限制，失败不影响应用启动，只记录失败原因供界面提示
+ */
+
+use crate::services::connection_router::{self, ConnectionPools, Endpoint, RoutingConfig};
+use serde::Serialize;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// 启动检查的超时时间
+pub const STARTUP_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 启动连接检查的状态
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StartupConnectionState {
+    /// 检查尚未完成
+    Checking,
+    /// 连接成功
+    Ok,
+    /// 连接失败
+    Failed,
+}
+
+/// 启动连接检查的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupStatus {
+    /// 检查状态
+    pub state: StartupConnectionState,
+    /// 主库地址（host:port）
+    pub host: String,
+    /// 检查的数据库
+    pub database: String,
+    /// 服务器版本（连接成功时）
+    pub server_version: Option<String>,
+    /// 失败原因（连接失败时）
+    pub error: Option<String>,
+    /// 检查完成的时间（RFC 3339）
+    pub checked_at: Option<String>,
+}
+
+impl StartupStatus {
+    /// 检查开始前的状态
+    pub fn checking(config: &RoutingConfig, database: &str) -> Self {
+        Self {
+            state: StartupConnectionState::Checking,
+            host: format!("{}:{}", config.write.host, config.write.port),
+            database: database.to_string(),
+            server_version: None,
+            error: None,
+            checked_at: None,
+        }
+    }
+}
+
+/// 检查能否连接到主库上的指定数据库
+///
+/// 连接失败（包括超时）时返回 `Failed` 状态而不是错误，应用可以照常启动。
+///
+/// # Arguments
+/// * `pools` - 连接池表，连接成功后连接保留在连接池中；只在获取连接池时加锁，
+///   连接期间其他命令不会被阻塞
+/// * `config` - 路由配置
+/// * `database` - 要检查的数据库
+/// * `timeout` - 超时时间
+///
+/// # Returns
+/// * `StartupStatus` - 检查结果
+pub async fn check_connection(
+    pools: &Mutex<ConnectionPools>,
+    config: &RoutingConfig,
+    database: &str,
+    timeout: Duration,
+) -> StartupStatus {
+    let mut status = StartupStatus::checking(config, database);
+
+    let result = tokio::time::timeout(timeout, async {
+        let pool = connection_router::connection_pool(&mut *pools.lock().await, config, database, Endpoint::Write)?;
+        let connection = connection_router::get_connection(&pool).await?;
+        let row = connection
+            .query_one("SHOW server_version", &[])
+            .await
+            .map_err(|e| format!("查询服务器版本失败: {}", e))?;
+        Ok::<String, String>(row.get(0))
+    })
+    .await
+    .unwrap_or_else(|_| Err(format!("连接超时（{:?}）", timeout)));
+
+    match result {
+        Ok(version) => {
+            status.state = StartupConnectionState::Ok;
+            status.server_version = Some(version);
+        }
+        Err(e) => {
+            status.state = StartupConnectionState::Failed;
+            status.error = Some(e);
+        }
+    }
+    status.checked_at = Some(chrono::Local::now().to_rfc3339());
+
+    status
+}
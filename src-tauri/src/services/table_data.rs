@@ -0,0 +1,483 @@
+/**
+ * Table Data Service
+ *
+ * 此模块负责按页读取表数据供数据浏览器显示：
+ * - 读取列信息（类型、是否可空、是否主键）和主键列列表，供编辑和删除时定位行
+ * - 没有主键的普通表在每行中附带 `ctid`，可传给 update_record、delete_record 定位该行
+ * - 按列过滤（参数绑定值，列名按表的实际列校验）和排序
+ * - 统计总行数（过滤后的行数）；大表可改用统计信息中的估算行数，避免全表 COUNT(*)
+ * - 默认通过 row_to_json 返回带类型的 JSON 值（数字、布尔、嵌套 JSON 等）
+ * - 原始模式下将每列转换为文本（`col::text`），显示 PostgreSQL 的规范文本形式，
+ *   适用于工具没有专门处理的类型（自定义类型、几何类型、区间等）
+ */
+
+use crate::models::data::{ColumnFilter, FilterOperator, SortColumn, SortDirection};
+use crate::models::query::ColumnInfo;
+use crate::services::record_writer::{json_to_text, CTID_COLUMN};
+use crate::services::schema_service;
+use crate::services::sql_utils::{qualified_name, quote_identifier};
+use tokio_postgres::types::ToSql;
+use tokio_postgres::Client;
+
+/// 每页最多返回的行数
+pub const MAX_PAGE_SIZE: u32 = 1000;
+
+/// 一页表数据
+#[derive(Debug, Clone)]
+pub struct TablePage {
+    /// 列信息（按列顺序）
+    pub columns: Vec<ColumnInfo>,
+    /// 主键列（按主键定义顺序），表没有主键时为空
+    pub primary_key_columns: Vec<String>,
+    /// 没有主键时每行附带的定位列（`ctid`）；表有主键或不是普通表（视图、分区表等）时为 None
+    pub row_id_column: Option<String>,
+    /// 本页的行，每行为 列名 -> 值 的 JSON 对象
+    pub rows: Vec<serde_json::Value>,
+    /// 表的总行数（有过滤条件时为满足条件的行数）
+    pub total_rows: i64,
+    /// `total_rows` 是否为统计信息中的估算值
+    pub total_rows_estimated: bool,
+}
+
+/// 读取表数据时的过滤条件和排序
+#[derive(Debug, Clone, Default)]
+pub struct TableQuery {
+    /// 过滤条件，多个条件之间为 AND
+    pub filters: Vec<ColumnFilter>,
+    /// 排序列，按顺序优先
+    pub sort: Vec<SortColumn>,
+    /// 为 true 且没有过滤条件时，总行数使用统计信息中的估算值而不执行 `COUNT(*)`
+    pub estimate_count: bool,
+}
+
+/// 由 `TableQuery` 生成的 WHERE、ORDER BY 子句及绑定的参数
+#[derive(Debug, PartialEq)]
+struct QueryClauses {
+    /// ` WHERE ...`，没有过滤条件时为空
+    where_clause: String,
+    /// ` ORDER BY ...`，没有排序时为空
+    order_by: String,
+    /// `$1..$n` 的值（文本形式，在 SQL 中转换为列类型）
+    params: Vec<Option<String>>,
+}
+
+impl QueryClauses {
+    /// 分页子句，参数编号接在过滤参数之后
+    fn limit_clause(&self) -> String {
+        format!(" LIMIT ${} OFFSET ${}", self.params.len() + 1, self.params.len() + 2)
+    }
+}
+
+/// 读取表的一页数据
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `page` - 页码（从 1 开始）
+/// * `page_size` - 每页行数（1 到 `MAX_PAGE_SIZE`）
+/// * `raw_text` - 为 true 时每列都以 PostgreSQL 的文本形式返回（字符串或 null）
+/// * `query` - 过滤条件和排序
+///
+/// # Returns
+/// * `Result<TablePage, String>` - 列信息、主键列、本页数据和总行数，或错误信息
+pub async fn read_table_page(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    page: u32,
+    page_size: u32,
+    raw_text: bool,
+    query: &TableQuery,
+) -> Result<TablePage, String> {
+    validate_page(page, page_size)?;
+
+    let columns = get_columns(client, schema, table).await?;
+    if columns.is_empty() {
+        return Err(format!("表 {}.{} 不存在", schema, table));
+    }
+    let primary_key_columns = schema_service::get_primary_key_columns(client, schema, table).await?;
+    let with_ctid = primary_key_columns.is_empty() && is_plain_table(client, schema, table).await?;
+
+    let table_name = qualified_name(schema, table);
+    let clauses = build_query_clauses(&columns, query)?;
+    let filter_params: Vec<&(dyn ToSql + Sync)> =
+        clauses.params.iter().map(|p| p as &(dyn ToSql + Sync)).collect();
+
+    // 估算值来自整张表的统计信息，有过滤条件时仍精确统计
+    let total_rows_estimated = query.estimate_count && query.filters.is_empty();
+    let total_rows: i64 = if total_rows_estimated {
+        estimate_row_count(client, &table_name).await?
+    } else {
+        client
+            .query_one(&format!("SELECT COUNT(*) FROM {}{}", table_name, clauses.where_clause), &filter_params)
+            .await
+            .map_err(|e| format!("查询行数失败: {}", e))?
+            .get(0)
+    };
+
+    let limit = i64::from(page_size);
+    let offset = i64::from(page - 1) * limit;
+    let mut params = filter_params;
+    params.push(&limit);
+    params.push(&offset);
+
+    let rows = if raw_text {
+        let query = build_raw_query(&table_name, &columns, &clauses, with_ctid);
+        read_raw_rows(client, &query, &columns, &params, with_ctid).await?
+    } else {
+        read_typed_rows(client, &build_typed_query(&table_name, &clauses, with_ctid), &params, with_ctid).await?
+    };
+
+    Ok(TablePage {
+        columns,
+        primary_key_columns,
+        row_id_column: with_ctid.then(|| CTID_COLUMN.to_string()),
+        rows,
+        total_rows,
+        total_rows_estimated,
+    })
+}
+
+/// 从统计信息读取表的估算行数
+///
+/// 优先使用 `pg_class.reltuples`（最近一次 VACUUM/ANALYZE 的结果）；表从未分析过时
+/// （reltuples 为 -1，PostgreSQL 14 之前为 0）使用 `pg_stat_user_tables.n_live_tup`。
+async fn estimate_row_count(client: &Client, table_name: &str) -> Result<i64, String> {
+    let query = r#"
+        SELECT CASE
+            WHEN c.reltuples > 0 THEN c.reltuples::bigint
+            ELSE COALESCE(s.n_live_tup, 0)
+        END
+        FROM pg_class c
+        LEFT JOIN pg_stat_user_tables s ON s.relid = c.oid
+        WHERE c.oid = $1::text::regclass
+    "#;
+
+    client
+        .query_one(query, &[&table_name])
+        .await
+        .map(|row| row.get(0))
+        .map_err(|e| format!("查询估算行数失败: {}", e))
+}
+
+/// 表是否为普通表（relkind 'r'）
+///
+/// 只有普通表中的 `ctid` 能唯一定位一行：视图没有 `ctid`，分区表中不同分区的行可能有相同的 `ctid`。
+async fn is_plain_table(client: &Client, schema: &str, table: &str) -> Result<bool, String> {
+    let query = r#"
+        SELECT c.relkind = 'r'
+        FROM pg_catalog.pg_class c
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1 AND c.relname = $2
+    "#;
+
+    client
+        .query_opt(query, &[&schema, &table])
+        .await
+        .map(|row| row.is_some_and(|row| row.get(0)))
+        .map_err(|e| format!("查询表类型失败: {}", e))
+}
+
+/// 检查分页参数，页码从 1 开始，每页行数不超过 `MAX_PAGE_SIZE`
+fn validate_page(page: u32, page_size: u32) -> Result<(), String> {
+    if page == 0 {
+        return Err("页码必须从 1 开始".to_string());
+    }
+    if page_size == 0 || page_size > MAX_PAGE_SIZE {
+        return Err(format!("每页行数必须在 1 到 {} 之间，当前为 {}", MAX_PAGE_SIZE, page_size));
+    }
+    Ok(())
+}
+
+/// 获取表的列信息
+async fn get_columns(client: &Client, schema: &str, table: &str) -> Result<Vec<ColumnInfo>, String> {
+    let query = r#"
+        SELECT
+            a.attname::text,
+            pg_catalog.format_type(a.atttypid, a.atttypmod),
+            NOT a.attnotnull,
+            COALESCE((
+                SELECT true FROM pg_index i
+                WHERE i.indrelid = a.attrelid AND a.attnum = ANY(i.indkey) AND i.indisprimary
+            ), false)
+        FROM pg_catalog.pg_attribute a
+        JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1
+          AND c.relname = $2
+          AND a.attnum > 0
+          AND NOT a.attisdropped
+        ORDER BY a.attnum
+    "#;
+
+    let rows = client
+        .query(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("查询列信息失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ColumnInfo::new(row.get(0), row.get(1), row.get(2), row.get(3)))
+        .collect())
+}
+
+/// 以带类型的 JSON 读取行，`with_ctid` 为 true 时查询的第二列为 `ctid`
+async fn read_typed_rows(
+    client: &Client,
+    query: &str,
+    params: &[&(dyn ToSql + Sync)],
+    with_ctid: bool,
+) -> Result<Vec<serde_json::Value>, String> {
+    let rows = client
+        .query(query, params)
+        .await
+        .map_err(|e| format!("查询数据失败: {}", e))?;
+
+    rows.iter()
+        .map(|row| {
+            let json: String = row.get(0);
+            let mut value: serde_json::Value =
+                serde_json::from_str(&json).map_err(|e| format!("无法解析行数据: {}", e))?;
+            if with_ctid {
+                if let Some(object) = value.as_object_mut() {
+                    object.insert(CTID_COLUMN.to_string(), serde_json::Value::String(row.get(1)));
+                }
+            }
+            Ok(value)
+        })
+        .collect()
+}
+
+/// 以文本形式读取行，每列都转换为 text，`with_ctid` 为 true 时最后一列为 `ctid`
+async fn read_raw_rows(
+    client: &Client,
+    query: &str,
+    columns: &[ColumnInfo],
+    params: &[&(dyn ToSql + Sync)],
+    with_ctid: bool,
+) -> Result<Vec<serde_json::Value>, String> {
+    let rows = client
+        .query(query, params)
+        .await
+        .map_err(|e| format!("查询数据失败: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let mut object = serde_json::Map::new();
+            for (i, column) in columns.iter().enumerate() {
+                let value = row
+                    .get::<_, Option<String>>(i)
+                    .map(serde_json::Value::String)
+                    .unwrap_or(serde_json::Value::Null);
+                object.insert(column.name.clone(), value);
+            }
+            if with_ctid {
+                object.insert(CTID_COLUMN.to_string(), serde_json::Value::String(row.get(columns.len())));
+            }
+            serde_json::Value::Object(object)
+        })
+        .collect())
+}
+
+/// 构建带类型模式的查询：整行转换为 JSON，`with_ctid` 为 true 时附带 `ctid` 列
+fn build_typed_query(table_name: &str, clauses: &QueryClauses, with_ctid: bool) -> String {
+    format!(
+        "SELECT row_to_json(__row)::text{} FROM {} AS __row{}{}{}",
+        if with_ctid { ", __row.ctid::text AS ctid" } else { "" },
+        table_name,
+        clauses.where_clause,
+        clauses.order_by,
+        clauses.limit_clause()
+    )
+}
+
+/// 构建原始模式的查询：每列都转换为 text，`with_ctid` 为 true 时最后附带 `ctid` 列
+fn build_raw_query(table_name: &str, columns: &[ColumnInfo], clauses: &QueryClauses, with_ctid: bool) -> String {
+    let mut select_list: Vec<String> = columns
+        .iter()
+        .map(|column| format!("{}::text", quote_identifier(&column.name)))
+        .collect();
+    if with_ctid {
+        select_list.push(format!("ctid::text AS {}", CTID_COLUMN));
+    }
+
+    format!(
+        "SELECT {} FROM {}{}{}{}",
+        select_list.join(", "),
+        table_name,
+        clauses.where_clause,
+        clauses.order_by,
+        clauses.limit_clause()
+    )
+}
+
+/// 根据过滤条件和排序生成 WHERE、ORDER BY 子句
+///
+/// 列名必须是表的实际列，引用时加引号；值以文本参数绑定并转换为列类型，
+/// 不会拼接到 SQL 中。
+fn build_query_clauses(columns: &[ColumnInfo], query: &TableQuery) -> Result<QueryClauses, String> {
+    let find_column = |name: &str| {
+        columns
+            .iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| format!("列 {} 不存在", name))
+    };
+
+    let mut conditions = Vec::with_capacity(query.filters.len());
+    let mut params = Vec::new();
+
+    for filter in &query.filters {
+        let column = find_column(&filter.column)?;
+        let column_ref = quote_identifier(&column.name);
+
+        let bind_value = |params: &mut Vec<Option<String>>| {
+            let value = json_to_text(&filter.value)
+                .ok_or_else(|| format!("列 {} 的过滤条件缺少比较的值", column.name))?;
+            params.push(Some(value));
+            Ok::<_, String>(params.len())
+        };
+        let compare = |symbol: &str, param: usize| {
+            format!("{} {} CAST(${}::text AS {})", column_ref, symbol, param, column.type_name)
+        };
+
+        conditions.push(match filter.operator {
+            FilterOperator::IsNull => format!("{} IS NULL", column_ref),
+            FilterOperator::Like => {
+                format!("{}::text LIKE ${}::text", column_ref, bind_value(&mut params)?)
+            }
+            FilterOperator::Eq => compare("=", bind_value(&mut params)?),
+            FilterOperator::NotEq => compare("<>", bind_value(&mut params)?),
+            FilterOperator::Lt => compare("<", bind_value(&mut params)?),
+            FilterOperator::Gt => compare(">", bind_value(&mut params)?),
+        });
+    }
+
+    let mut sort_terms = Vec::with_capacity(query.sort.len());
+    for sort in &query.sort {
+        let column = find_column(&sort.column)?;
+        let direction = match sort.direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        sort_terms.push(format!("{} {}", quote_identifier(&column.name), direction));
+    }
+
+    Ok(QueryClauses {
+        where_clause: if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        },
+        order_by: if sort_terms.is_empty() {
+            String::new()
+        } else {
+            format!(" ORDER BY {}", sort_terms.join(", "))
+        },
+        params,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_page() {
+        assert!(validate_page(1, 1).is_ok());
+        assert!(validate_page(u32::MAX, MAX_PAGE_SIZE).is_ok());
+        assert_eq!(validate_page(0, 50).unwrap_err(), "页码必须从 1 开始");
+        assert!(validate_page(1, 0).is_err());
+        assert!(validate_page(1, MAX_PAGE_SIZE + 1).is_err());
+        assert!(validate_page(1, 5_000_000).is_err());
+    }
+
+    fn sample_columns() -> Vec<ColumnInfo> {
+        vec![
+            ColumnInfo::new("id".to_string(), "integer".to_string(), false, true),
+            ColumnInfo::new("Created At".to_string(), "timestamp with time zone".to_string(), true, false),
+        ]
+    }
+
+    #[test]
+    fn test_build_raw_query() {
+        let clauses = build_query_clauses(&sample_columns(), &TableQuery::default()).unwrap();
+
+        assert_eq!(
+            build_raw_query("\"public\".\"events\"", &sample_columns(), &clauses, false),
+            "SELECT \"id\"::text, \"Created At\"::text FROM \"public\".\"events\" LIMIT $1 OFFSET $2"
+        );
+        assert_eq!(
+            build_raw_query("\"public\".\"events\"", &sample_columns(), &clauses, true),
+            "SELECT \"id\"::text, \"Created At\"::text, ctid::text AS ctid FROM \"public\".\"events\" LIMIT $1 OFFSET $2"
+        );
+    }
+
+    #[test]
+    fn test_build_filtered_and_sorted_query() {
+        let query = TableQuery {
+            filters: vec![
+                ColumnFilter {
+                    column: "id".to_string(),
+                    operator: FilterOperator::NotEq,
+                    value: serde_json::json!(3),
+                },
+                ColumnFilter {
+                    column: "Created At".to_string(),
+                    operator: FilterOperator::IsNull,
+                    value: serde_json::Value::Null,
+                },
+                ColumnFilter {
+                    column: "Created At".to_string(),
+                    operator: FilterOperator::Like,
+                    value: serde_json::json!("2024-%"),
+                },
+            ],
+            sort: vec![SortColumn {
+                column: "Created At".to_string(),
+                direction: SortDirection::Desc,
+            }],
+            ..Default::default()
+        };
+        let clauses = build_query_clauses(&sample_columns(), &query).unwrap();
+
+        assert_eq!(
+            clauses.where_clause,
+            " WHERE \"id\" <> CAST($1::text AS integer) AND \"Created At\" IS NULL AND \"Created At\"::text LIKE $2::text"
+        );
+        assert_eq!(clauses.order_by, " ORDER BY \"Created At\" DESC");
+        assert_eq!(clauses.params, vec![Some("3".to_string()), Some("2024-%".to_string())]);
+        assert_eq!(
+            build_typed_query("\"public\".\"events\"", &clauses, false),
+            format!(
+                "SELECT row_to_json(__row)::text FROM \"public\".\"events\" AS __row{}{} LIMIT $3 OFFSET $4",
+                clauses.where_clause, clauses.order_by
+            )
+        );
+    }
+
+    #[test]
+    fn test_build_query_clauses_rejects_unknown_columns() {
+        let filter = |column: &str, value: serde_json::Value| TableQuery {
+            filters: vec![ColumnFilter {
+                column: column.to_string(),
+                operator: FilterOperator::Eq,
+                value,
+            }],
+            ..Default::default()
+        };
+
+        assert!(build_query_clauses(&sample_columns(), &filter("id; DROP TABLE events", serde_json::json!(1))).is_err());
+        assert!(build_query_clauses(&sample_columns(), &filter("id", serde_json::Value::Null)).is_err());
+
+        let sort = TableQuery {
+            sort: vec![SortColumn {
+                column: "\"id\"".to_string(),
+                direction: SortDirection::Asc,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(build_query_clauses(&sample_columns(), &sort).unwrap_err(), "列 \"id\" 不存在");
+    }
+}
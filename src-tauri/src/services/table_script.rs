@@ -0,0 +1,321 @@
+/**
+ * Table Script Service
+ *
+ * 此模块将已有的表反向生成为可执行的 DDL 脚本：
+ * - 通过 `schema_service` 读取 TableSchema，转换为 TableDesign 后交给
+ *   `ddl_generator::generate_create_table` 生成 CREATE TABLE、约束和索引
+ * - 列类型使用 `format_type` 的完整类型名（数组、自定义类型、域）
+ * - 表达式索引、部分索引等生成器无法表达的索引使用 `pg_get_indexdef` 的原始定义
+ * - serial 列依赖的序列在建表前创建，建表后恢复 OWNED BY 关系
+ * - 附加表和列的 COMMENT ON 语句
+ * - 标识列和生成列按原定义生成 GENERATED 子句
+ * - 分区等无法复现的特性以警告形式返回
+ */
+
+use crate::models::schema::{TableDesign, TableSchema, TableScript};
+use crate::services::schema_service::{self, ColumnType};
+use crate::services::sql_utils::{qualified_name, quote_identifier};
+use crate::services::ddl_generator;
+use std::collections::HashMap;
+use tokio_postgres::Client;
+
+/// 生成重建表所需的完整 DDL 脚本
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+///
+/// # Returns
+/// * `Result<TableScript, String>` - DDL 脚本和无法复现的特性警告，或错误信息
+pub async fn script_table(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<TableScript, String> {
+    let statements = table_statements(client, schema, table, false).await?;
+
+    Ok(TableScript {
+        script: statements.statements.join("\n\n"),
+        warnings: statements.warnings,
+    })
+}
+
+/// 表的 DDL 语句，供整库脚本按依赖顺序组合
+pub(crate) struct TableStatements {
+    /// 序列、CREATE TABLE、索引、序列归属和注释
+    pub statements: Vec<String>,
+    /// 单独生成的外键约束（ALTER TABLE ... ADD），需在被引用的表创建后执行
+    pub foreign_keys: Vec<String>,
+    /// 无法复现的特性
+    pub warnings: Vec<String>,
+}
+
+/// 生成重建表所需的 DDL 语句
+///
+/// `separate_foreign_keys` 为 true 时，外键不写入 CREATE TABLE，而是作为
+/// ALTER TABLE 语句放在 `foreign_keys` 中。
+pub(crate) async fn table_statements(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    separate_foreign_keys: bool,
+) -> Result<TableStatements, String> {
+    let features = client
+        .query_opt(
+            r#"
+            SELECT
+                c.oid,
+                c.relkind::text,
+                c.relispartition
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'p')
+            "#,
+            &[&schema, &table],
+        )
+        .await
+        .map_err(|e| format!("查询表信息失败: {}", e))?
+        .ok_or_else(|| format!("表 {}.{} 不存在", schema, table))?;
+
+    let table_oid: u32 = features.get(0);
+    let relkind: String = features.get(1);
+    let is_partition: bool = features.get(2);
+
+    let mut warnings = Vec::new();
+    if relkind == "p" {
+        warnings.push("表是分区表，脚本中不包含 PARTITION BY 子句和各个分区".to_string());
+    }
+    if is_partition {
+        warnings.push("表是其他表的分区，脚本中不包含 PARTITION OF 关系".to_string());
+    }
+
+    let table_schema = schema_service::get_table_schema(client, schema, table).await?;
+    let column_types = schema_service::get_column_types(client, schema, table).await?;
+    let raw_indexes = get_raw_index_definitions(client, table_oid).await?;
+    let owned_sequences = get_owned_sequences(client, table_oid).await?;
+
+    let raw_index_names: Vec<&str> = raw_indexes.iter().map(|(name, _)| name.as_str()).collect();
+    let mut design = table_design_from_schema(table_schema, &column_types, &raw_index_names);
+
+    let mut foreign_keys = Vec::new();
+    if separate_foreign_keys {
+        let (separate, inline): (Vec<_>, Vec<_>) = design
+            .constraints
+            .into_iter()
+            .partition(|c| c.constraint_type == "FOREIGN KEY");
+        design.constraints = inline;
+        foreign_keys = separate
+            .iter()
+            .map(|constraint| ddl_generator::generate_add_constraint(schema, table, constraint))
+            .collect();
+    }
+
+    let mut statements = Vec::new();
+    for (sequence_schema, sequence, _) in &owned_sequences {
+        statements.push(format!(
+            "CREATE SEQUENCE IF NOT EXISTS {};",
+            qualified_name(sequence_schema, sequence)
+        ));
+    }
+    statements.push(ddl_generator::generate_create_table(&design));
+    for (_, definition) in &raw_indexes {
+        statements.push(format!("{};", definition));
+    }
+    for (sequence_schema, sequence, column) in &owned_sequences {
+        statements.push(format!(
+            "ALTER SEQUENCE {} OWNED BY {}.{};",
+            qualified_name(sequence_schema, sequence),
+            qualified_name(schema, table),
+            quote_identifier(column)
+        ));
+    }
+
+    Ok(TableStatements {
+        statements,
+        foreign_keys,
+        warnings,
+    })
+}
+
+/// 将读取到的表结构转换为可交给 DDL 生成器的表设计
+///
+/// - 列类型替换为 `format_type` 的完整类型名（已包含长度和精度）
+/// - 清除列级 UNIQUE 标记，唯一约束只以表级约束生成一次
+/// - 去掉支撑主键/唯一约束的索引，以及 `raw_index_names` 中单独输出的索引
+fn table_design_from_schema(
+    table_schema: TableSchema,
+    column_types: &HashMap<String, ColumnType>,
+    raw_index_names: &[&str],
+) -> TableDesign {
+    let constraint_names: Vec<&str> = table_schema
+        .constraints
+        .iter()
+        .filter(|c| c.constraint_type == "PRIMARY KEY" || c.constraint_type == "UNIQUE")
+        .map(|c| c.constraint_name.as_str())
+        .collect();
+
+    let indexes = table_schema
+        .indexes
+        .iter()
+        .filter(|index| {
+            !constraint_names.contains(&index.index_name.as_str())
+                && !raw_index_names.contains(&index.index_name.as_str())
+        })
+        .cloned()
+        .collect();
+
+    let columns = table_schema
+        .columns
+        .into_iter()
+        .map(|mut column| {
+            if let Some(column_type) = column_types.get(&column.name) {
+                column.data_type = column_type.data_type.clone();
+                column.character_maximum_length = None;
+                column.numeric_precision = None;
+                column.numeric_scale = None;
+            }
+            column.is_unique = false;
+            column
+        })
+        .collect();
+
+    TableDesign {
+        table_name: table_schema.table_name,
+        schema: table_schema.schema,
+        columns,
+        constraints: table_schema.constraints,
+        indexes,
+        storage_parameters: table_schema.storage_parameters,
+        tablespace: table_schema.tablespace,
+        comment: table_schema.comment,
+    }
+}
+
+/// 读取生成器无法表达的索引（表达式、部分条件、INCLUDE 列、DESC/NULLS 排序）的原始定义
+///
+/// 返回 (索引名, `pg_get_indexdef` 定义)，不包括支撑约束的索引。
+async fn get_raw_index_definitions(
+    client: &Client,
+    table_oid: u32,
+) -> Result<Vec<(String, String)>, String> {
+    let query = r#"
+        SELECT i.relname::text, pg_get_indexdef(ix.indexrelid)
+        FROM pg_index ix
+        JOIN pg_class i ON i.oid = ix.indexrelid
+        WHERE ix.indrelid = $1
+          AND NOT EXISTS (SELECT 1 FROM pg_constraint con WHERE con.conindid = ix.indexrelid)
+          AND (
+              ix.indexprs IS NOT NULL
+              OR ix.indpred IS NOT NULL
+              OR ix.indnkeyatts < ix.indnatts
+              OR EXISTS (SELECT 1 FROM unnest(ix.indoption::int2[]) AS o(opt) WHERE o.opt <> 0)
+          )
+        ORDER BY i.relname
+    "#;
+
+    let rows = client
+        .query(query, &[&table_oid])
+        .await
+        .map_err(|e| format!("查询索引定义失败: {}", e))?;
+
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+/// 读取 serial 列所属的序列，返回 (序列模式, 序列名, 列名)
+///
+/// 标识列的序列由 GENERATED AS IDENTITY 隐式创建，不包括在内。
+async fn get_owned_sequences(
+    client: &Client,
+    table_oid: u32,
+) -> Result<Vec<(String, String, String)>, String> {
+    let query = r#"
+        SELECT sn.nspname::text, s.relname::text, a.attname::text
+        FROM pg_depend d
+        JOIN pg_class s ON s.oid = d.objid AND s.relkind = 'S'
+        JOIN pg_namespace sn ON sn.oid = s.relnamespace
+        JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+        WHERE d.classid = 'pg_class'::regclass
+          AND d.refclassid = 'pg_class'::regclass
+          AND d.refobjid = $1
+          AND d.deptype = 'a'
+        ORDER BY a.attnum
+    "#;
+
+    let rows = client
+        .query(query, &[&table_oid])
+        .await
+        .map_err(|e| format!("查询序列失败: {}", e))?;
+
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1), row.get(2))).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::schema::{ColumnDefinition, ConstraintDefinition, IndexDefinition};
+    use crate::services::schema_service::ColumnTypeKind;
+
+    fn column(name: &str, data_type: &str, length: Option<i32>) -> ColumnDefinition {
+        ColumnDefinition {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            character_maximum_length: length,
+            numeric_precision: None,
+            numeric_scale: None,
+            is_nullable: true,
+            column_default: None,
+            generated_expression: None,
+            identity: None,
+            is_primary_key: false,
+            is_unique: false,
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn test_table_design_from_schema() {
+        let mut table_schema = TableSchema::new("docs".to_string(), "public".to_string());
+        let mut code = column("code", "character varying", Some(20));
+        code.is_unique = true;
+        table_schema.add_column(code);
+        table_schema.add_column(column("tags", "ARRAY", None));
+        table_schema.add_constraint(ConstraintDefinition {
+            constraint_type: "UNIQUE".to_string(),
+            constraint_name: "docs_code_key".to_string(),
+            columns: vec!["code".to_string()],
+            referenced_table: None,
+            referenced_columns: None,
+            on_delete: None,
+            on_update: None,
+            check_clause: None,
+        });
+        table_schema.add_index(IndexDefinition::btree("docs_code_key".to_string(), vec!["code".to_string()], true));
+        table_schema.add_index(IndexDefinition::btree("docs_lower_code_idx".to_string(), vec![], false));
+        table_schema.add_index(IndexDefinition::btree("docs_tags_idx".to_string(), vec!["tags".to_string()], false));
+
+        let mut column_types = HashMap::new();
+        column_types.insert(
+            "code".to_string(),
+            ColumnType { data_type: "character varying(20)".to_string(), kind: ColumnTypeKind::Other },
+        );
+        column_types.insert(
+            "tags".to_string(),
+            ColumnType { data_type: "text[]".to_string(), kind: ColumnTypeKind::Array },
+        );
+
+        let design = table_design_from_schema(table_schema, &column_types, &["docs_lower_code_idx"]);
+
+        assert_eq!(design.columns[0].data_type, "character varying(20)");
+        assert_eq!(design.columns[0].character_maximum_length, None);
+        assert!(!design.columns[0].is_unique);
+        assert_eq!(design.columns[1].data_type, "text[]");
+        let index_names: Vec<&str> = design.indexes.iter().map(|i| i.index_name.as_str()).collect();
+        assert_eq!(index_names, vec!["docs_tags_idx"]);
+
+        let ddl = ddl_generator::generate_create_table(&design);
+        assert!(ddl.contains("code CHARACTER VARYING(20),"));
+        assert!(ddl.contains("CONSTRAINT docs_code_key UNIQUE (code)"));
+        assert_eq!(ddl.matches("UNIQUE").count(), 1);
+    }
+}
@@ -0,0 +1,71 @@
+/**
+ * Table Statistics Service
+ *
+ * This module reports how much space a table occupies (table data, indexes, TOAST),
+ * how many rows it holds according to the statistics collector, and when it was
+ * last vacuumed and analyzed. Only catalog functions and `pg_stat_user_tables`
+ * are read, so the result is instant regardless of the table's size.
+ */
+
+use crate::models::schema::TableStats;
+use tokio_postgres::Client;
+
+/// Get size, row estimate and maintenance history of a table
+///
+/// # Arguments
+/// * `client` - PostgreSQL client connection
+/// * `schema` - Schema name
+/// * `table` - Table name
+///
+/// # Returns
+/// * `Result<TableStats, String>` - Table statistics or error message
+pub async fn get_table_stats(
+    client: &Client,
+    schema: &str,
+    table: &str,
+) -> Result<TableStats, String> {
+    // reltuples is -1 (PG 14+) or 0 before the first ANALYZE/VACUUM; fall back to n_live_tup
+    let query = r#"
+        SELECT
+            pg_total_relation_size(c.oid),
+            pg_relation_size(c.oid),
+            pg_indexes_size(c.oid),
+            CASE WHEN c.reltuples > 0 THEN c.reltuples::int8 ELSE COALESCE(s.n_live_tup, 0) END,
+            s.n_live_tup,
+            s.n_dead_tup,
+            s.last_vacuum::text,
+            s.last_autovacuum::text,
+            s.last_analyze::text,
+            s.last_autoanalyze::text
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_stat_user_tables s ON s.relid = c.oid
+        WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'p', 'm')
+    "#;
+
+    let row = client
+        .query_opt(query, &[&schema, &table])
+        .await
+        .map_err(|e| format!("Failed to query table statistics: {}", e))?
+        .ok_or_else(|| format!("Table {}.{} does not exist", schema, table))?;
+
+    let total_bytes: i64 = row.get(0);
+    let table_bytes: i64 = row.get(1);
+    let indexes_bytes: i64 = row.get(2);
+
+    Ok(TableStats {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        total_bytes,
+        table_bytes,
+        indexes_bytes,
+        toast_bytes: (total_bytes - table_bytes - indexes_bytes).max(0),
+        estimated_rows: row.get(3),
+        live_rows: row.get(4),
+        dead_rows: row.get(5),
+        last_vacuum: row.get(6),
+        last_autovacuum: row.get(7),
+        last_analyze: row.get(8),
+        last_autoanalyze: row.get(9),
+    })
+}
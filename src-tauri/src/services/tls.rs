@@ -0,0 +1,513 @@
+/**
+ * TLS Service
+ *
+ * 此模块负责为数据库连接配置 TLS：
+ * - 解析 ssl_mode（disable/prefer/require/verify-ca/verify-full）
+ * - 加载根证书（ssl_root_cert）用于验证服务器证书
+ * - 加载客户端证书和私钥（ssl_cert/ssl_key）用于双向 TLS
+ * - 为 psql、pg_dump 等命令行工具生成对应的 libpq 环境变量
+ * - 在后台运行连接任务，并可将服务器通知和 LISTEN 异步通知转发给调用方，
+ *   或将服务器通知（RAISE NOTICE 等）缓存起来附加到查询结果中
+ */
+
+use native_tls::{Certificate, Identity, TlsConnector};
+use postgres_native_tls::MakeTlsConnector;
+use serde::{Deserialize, Serialize};
+use futures_util::StreamExt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use tokio_postgres::error::DbError;
+use tokio_postgres::{AsyncMessage, CancelToken, Client, Connection, Notification};
+
+/// 每个连接最多缓存的服务器通知条数，超出的通知只写入日志
+pub const MAX_BUFFERED_NOTICES: usize = 1000;
+
+/// 连接收到的服务器通知（`SEVERITY: message`），由连接任务写入，执行语句的调用方取出
+#[derive(Clone, Default)]
+pub struct NoticeBuffer {
+    notices: Arc<Mutex<Vec<String>>>,
+}
+
+impl NoticeBuffer {
+    /// 追加一条通知；已达上限时丢弃
+    fn push(&self, notice: String) -> bool {
+        let mut notices = self.notices.lock().unwrap_or_else(|e| e.into_inner());
+        if notices.len() >= MAX_BUFFERED_NOTICES {
+            return false;
+        }
+        notices.push(notice);
+        true
+    }
+
+    /// 取出并清空已缓存的通知
+    pub fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.notices.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// SSL 模式，取值与 libpq 的 sslmode 相同
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SslMode {
+    /// 不使用 TLS
+    #[default]
+    Disable,
+    /// 服务器支持时使用 TLS
+    Prefer,
+    /// 必须使用 TLS；配置了根证书时验证服务器证书，但不验证主机名
+    Require,
+    /// 必须使用 TLS，并验证服务器证书由受信任的 CA 签发
+    VerifyCa,
+    /// 必须使用 TLS，验证服务器证书并检查主机名与证书一致
+    VerifyFull,
+}
+
+impl SslMode {
+    /// libpq 中的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "disable",
+            SslMode::Prefer => "prefer",
+            SslMode::Require => "require",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyFull => "verify-full",
+        }
+    }
+
+    /// 连接字符串中的 sslmode 参数
+    ///
+    /// tokio-postgres 只支持 disable/prefer/require，证书验证由 TLS 连接器完成。
+    fn connection_param(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "disable",
+            SslMode::Prefer => "prefer",
+            SslMode::Require | SslMode::VerifyCa | SslMode::VerifyFull => "require",
+        }
+    }
+}
+
+impl FromStr for SslMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "disable" => Ok(SslMode::Disable),
+            "prefer" => Ok(SslMode::Prefer),
+            "require" => Ok(SslMode::Require),
+            "verify-ca" => Ok(SslMode::VerifyCa),
+            "verify-full" => Ok(SslMode::VerifyFull),
+            other => Err(format!("无效的 ssl_mode: {}", other)),
+        }
+    }
+}
+
+/// 连接使用的 TLS 配置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TlsSettings {
+    /// SSL 模式
+    pub ssl_mode: SslMode,
+    /// 根证书（PEM）路径，用于验证服务器证书；未设置时使用系统信任的证书
+    pub ssl_root_cert: Option<String>,
+    /// 客户端证书（PEM）路径
+    pub ssl_cert: Option<String>,
+    /// 客户端私钥（PKCS#8 PEM）路径
+    pub ssl_key: Option<String>,
+}
+
+impl TlsSettings {
+    /// 追加到连接字符串中的 TLS 参数
+    pub fn connection_params(&self) -> String {
+        format!("sslmode={}", self.ssl_mode.connection_param())
+    }
+
+    /// 传给 psql、pg_dump 等命令行工具的 libpq 环境变量
+    pub fn libpq_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![("PGSSLMODE", self.ssl_mode.as_str().to_string())];
+
+        if let Some(path) = &self.ssl_root_cert {
+            env.push(("PGSSLROOTCERT", path.clone()));
+        }
+        if let Some(path) = &self.ssl_cert {
+            env.push(("PGSSLCERT", path.clone()));
+        }
+        if let Some(path) = &self.ssl_key {
+            env.push(("PGSSLKEY", path.clone()));
+        }
+
+        env
+    }
+
+    /// 根据配置创建 TLS 连接器
+    ///
+    /// 证书和私钥文件在此时读取，文件不存在或不可读时返回明确的错误。
+    pub fn build_connector(&self) -> Result<MakeTlsConnector, String> {
+        let mut builder = TlsConnector::builder();
+
+        if let Some(path) = &self.ssl_root_cert {
+            let pem = read_file(path, "根证书 (ssl_root_cert)")?;
+            let certificate = Certificate::from_pem(&pem)
+                .map_err(|e| format!("无法解析根证书 {}: {}", path, e))?;
+            builder.add_root_certificate(certificate);
+        }
+
+        match (&self.ssl_cert, &self.ssl_key) {
+            (Some(cert_path), Some(key_path)) => {
+                let cert = read_file(cert_path, "客户端证书 (ssl_cert)")?;
+                let key = read_file(key_path, "客户端私钥 (ssl_key)")?;
+                let identity = Identity::from_pkcs8(&cert, &key).map_err(|e| {
+                    format!(
+                        "无法加载客户端证书 {} 和私钥 {}（私钥须为 PKCS#8 PEM 格式）: {}",
+                        cert_path, key_path, e
+                    )
+                })?;
+                builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => return Err("ssl_cert 和 ssl_key 必须同时配置".to_string()),
+        }
+
+        match self.ssl_mode {
+            SslMode::Disable | SslMode::Prefer | SslMode::Require => {
+                // 与 libpq 一致：只有配置了根证书时才验证服务器证书
+                if self.ssl_root_cert.is_none() {
+                    builder.danger_accept_invalid_certs(true);
+                }
+                builder.danger_accept_invalid_hostnames(true);
+            }
+            SslMode::VerifyCa => {
+                builder.danger_accept_invalid_hostnames(true);
+            }
+            SslMode::VerifyFull => {}
+        }
+
+        let connector = builder
+            .build()
+            .map_err(|e| format!("无法创建 TLS 连接器: {}", e))?;
+
+        Ok(MakeTlsConnector::new(connector))
+    }
+}
+
+/// 建立连接失败的原因
+#[derive(Debug)]
+pub enum ConnectError {
+    /// TLS 配置错误（证书文件不存在、格式错误等）
+    Config(String),
+    /// 连接或认证失败
+    Connection(tokio_postgres::Error),
+}
+
+impl ConnectError {
+    /// 是否值得重试：网络层面的失败可以重试，配置错误和服务器拒绝（如认证失败）不重试
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ConnectError::Config(_) => false,
+            ConnectError::Connection(e) => e.as_db_error().is_none(),
+        }
+    }
+}
+
+impl std::fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectError::Config(message) => write!(f, "{}", message),
+            // 服务器返回的错误（如认证失败、数据库不存在）只在 DbError 中包含具体信息
+            ConnectError::Connection(e) => match e.as_db_error() {
+                Some(db_error) => write!(
+                    f,
+                    "无法连接到数据库: {} (SQLSTATE {})",
+                    db_error.message(),
+                    db_error.code().code()
+                ),
+                None => write!(f, "无法连接到数据库: {}", e),
+            },
+        }
+    }
+}
+
+impl From<ConnectError> for String {
+    fn from(error: ConnectError) -> Self {
+        error.to_string()
+    }
+}
+
+/// 按 TLS 配置建立连接，并在后台运行连接任务
+///
+/// # Arguments
+/// * `connection_string` - 不含 sslmode 的连接字符串
+/// * `tls` - TLS 配置
+///
+/// # Returns
+/// * `Result<Client, ConnectError>` - 客户端连接或错误
+pub async fn connect(connection_string: &str, tls: &TlsSettings) -> Result<Client, ConnectError> {
+    let (client, _task) = connect_inner(connection_string, tls, MessageSenders::default()).await?;
+    Ok(client)
+}
+
+/// 按 TLS 配置建立连接，并返回后台连接任务的句柄
+///
+/// 调用方可以在不再使用连接时中止连接任务，确保套接字被关闭。
+///
+/// # Arguments
+/// * `connection_string` - 不含 sslmode 的连接字符串
+/// * `tls` - TLS 配置
+///
+/// # Returns
+/// * `Result<(Client, JoinHandle<()>), ConnectError>` - 客户端连接和连接任务句柄，或错误
+pub async fn connect_with_task(
+    connection_string: &str,
+    tls: &TlsSettings,
+) -> Result<(Client, JoinHandle<()>), ConnectError> {
+    connect_inner(connection_string, tls, MessageSenders::default()).await
+}
+
+/// 按 TLS 配置建立连接，并将服务器发送的通知（NOTICE、WARNING 等）缓存到返回的 `NoticeBuffer`
+///
+/// 适用于执行用户 SQL 的连接：执行前清空缓存，执行后取出通知附加到结果中，
+/// 使 PL/pgSQL 中 `RAISE NOTICE` 的输出对用户可见。
+///
+/// # Arguments
+/// * `connection_string` - 不含 sslmode 的连接字符串
+/// * `tls` - TLS 配置
+///
+/// # Returns
+/// * `Result<(Client, JoinHandle<()>, NoticeBuffer), ConnectError>` - 客户端连接、连接任务句柄和通知缓存，或错误
+pub async fn connect_with_notice_buffer(
+    connection_string: &str,
+    tls: &TlsSettings,
+) -> Result<(Client, JoinHandle<()>, NoticeBuffer), ConnectError> {
+    let buffer = NoticeBuffer::default();
+    let senders = MessageSenders {
+        notice_buffer: Some(buffer.clone()),
+        ..Default::default()
+    };
+    let (client, task) = connect_inner(connection_string, tls, senders).await?;
+    Ok((client, task, buffer))
+}
+
+/// 按 TLS 配置建立连接，并将服务器发送的通知（NOTICE、WARNING 等）转发给调用方
+///
+/// 适用于 REINDEX、VACUUM 等需要向用户展示服务器消息的维护操作。
+///
+/// # Arguments
+/// * `connection_string` - 不含 sslmode 的连接字符串
+/// * `tls` - TLS 配置
+///
+/// # Returns
+/// * `Result<(Client, UnboundedReceiver<DbError>), ConnectError>` - 客户端连接和通知接收端，或错误
+pub async fn connect_with_notices(
+    connection_string: &str,
+    tls: &TlsSettings,
+) -> Result<(Client, UnboundedReceiver<DbError>), ConnectError> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let senders = MessageSenders {
+        notices: Some(sender),
+        ..Default::default()
+    };
+    let (client, _task) = connect_inner(connection_string, tls, senders).await?;
+    Ok((client, receiver))
+}
+
+/// 按 TLS 配置建立连接，并将 LISTEN 收到的异步通知（NOTIFY）转发给调用方
+///
+/// # Arguments
+/// * `connection_string` - 不含 sslmode 的连接字符串
+/// * `tls` - TLS 配置
+///
+/// # Returns
+/// * `Result<(Client, UnboundedReceiver<Notification>), ConnectError>` - 客户端连接和异步通知接收端，或错误
+pub async fn connect_with_notifications(
+    connection_string: &str,
+    tls: &TlsSettings,
+) -> Result<(Client, UnboundedReceiver<Notification>), ConnectError> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let senders = MessageSenders {
+        notifications: Some(sender),
+        ..Default::default()
+    };
+    let (client, _task) = connect_inner(connection_string, tls, senders).await?;
+    Ok((client, receiver))
+}
+
+/// 按 TLS 配置向服务器发送取消请求，取消令牌所属会话正在执行的语句
+///
+/// 取消请求通过新的连接发送，只包含目标会话的进程号和密钥，不影响其他会话。
+///
+/// # Arguments
+/// * `token` - 目标会话的取消令牌（`Client::cancel_token`）
+/// * `tls` - 建立该会话时使用的 TLS 配置
+///
+/// # Returns
+/// * `Result<(), String>` - 请求是否已送达服务器；语句是否真正被取消由服务器决定
+pub async fn cancel_query(token: &CancelToken, tls: &TlsSettings) -> Result<(), String> {
+    let result = if tls.ssl_mode == SslMode::Disable {
+        token.cancel_query(tokio_postgres::NoTls).await
+    } else {
+        token.cancel_query(tls.build_connector()?).await
+    };
+
+    result.map_err(|e| format!("无法发送取消请求: {}", e))
+}
+
+/// 连接任务转发服务器消息的目标；未设置的消息写入日志
+#[derive(Default)]
+struct MessageSenders {
+    notices: Option<UnboundedSender<DbError>>,
+    notice_buffer: Option<NoticeBuffer>,
+    notifications: Option<UnboundedSender<Notification>>,
+}
+
+async fn connect_inner(
+    connection_string: &str,
+    tls: &TlsSettings,
+    senders: MessageSenders,
+) -> Result<(Client, JoinHandle<()>), ConnectError> {
+    let connection_string = format!("{} {}", connection_string, tls.connection_params());
+
+    if tls.ssl_mode == SslMode::Disable {
+        let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls)
+            .await
+            .map_err(ConnectError::Connection)?;
+        let task = spawn_connection(connection, senders);
+        return Ok((client, task));
+    }
+
+    let connector = tls.build_connector().map_err(ConnectError::Config)?;
+    let (client, connection) = tokio_postgres::connect(&connection_string, connector)
+        .await
+        .map_err(ConnectError::Connection)?;
+    let task = spawn_connection(connection, senders);
+
+    Ok((client, task))
+}
+
+/// 在后台运行连接任务，按 `senders` 转发服务器通知和异步通知
+fn spawn_connection<S, T>(mut connection: Connection<S, T>, senders: MessageSenders) -> JoinHandle<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut messages = futures_util::stream::poll_fn(move |cx| connection.poll_message(cx));
+        while let Some(message) = messages.next().await {
+            match message {
+                Ok(AsyncMessage::Notice(notice)) => match (&senders.notices, &senders.notice_buffer) {
+                    (Some(sender), _) => {
+                        let _ = sender.send(notice);
+                    }
+                    (None, Some(buffer)) => {
+                        let text = format!("{}: {}", notice.severity(), notice.message());
+                        log::info!("数据库通知 {}", text);
+                        if !buffer.push(text) {
+                            log::warn!("缓存的数据库通知已达 {} 条，后续通知不再附加到结果中", MAX_BUFFERED_NOTICES);
+                        }
+                    }
+                    (None, None) => log::info!("数据库通知 {}: {}", notice.severity(), notice.message()),
+                },
+                Ok(AsyncMessage::Notification(notification)) => match &senders.notifications {
+                    Some(sender) => {
+                        let _ = sender.send(notification);
+                    }
+                    None => log::info!("收到频道 {} 的异步通知", notification.channel()),
+                },
+                Ok(_) => {}
+                Err(e) => {
+                    log::error!("数据库连接错误: {}", e);
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// 读取证书或私钥文件
+fn read_file(path: &str, description: &str) -> Result<Vec<u8>, String> {
+    if !std::path::Path::new(path).is_file() {
+        return Err(format!("{}文件不存在: {}", description, path));
+    }
+
+    std::fs::read(path).map_err(|e| format!("无法读取{}文件 {}: {}", description, path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notice_buffer_take_and_limit() {
+        let buffer = NoticeBuffer::default();
+        let shared = buffer.clone();
+        assert!(shared.push("NOTICE: first".to_string()));
+        assert_eq!(buffer.take(), vec!["NOTICE: first".to_string()]);
+        assert!(buffer.take().is_empty());
+
+        for i in 0..MAX_BUFFERED_NOTICES {
+            assert!(shared.push(i.to_string()));
+        }
+        assert!(!shared.push("dropped".to_string()));
+        assert_eq!(buffer.take().len(), MAX_BUFFERED_NOTICES);
+    }
+
+    #[test]
+    fn test_parse_ssl_mode() {
+        assert_eq!("verify-full".parse::<SslMode>().unwrap(), SslMode::VerifyFull);
+        assert_eq!("Require".parse::<SslMode>().unwrap(), SslMode::Require);
+        assert!("verify_full".parse::<SslMode>().is_err());
+        assert_eq!(
+            serde_json::from_str::<SslMode>("\"verify-ca\"").unwrap(),
+            SslMode::VerifyCa
+        );
+    }
+
+    #[test]
+    fn test_verify_modes_connect_with_require() {
+        let settings = TlsSettings {
+            ssl_mode: SslMode::VerifyFull,
+            ..Default::default()
+        };
+        assert_eq!(settings.connection_params(), "sslmode=require");
+        assert_eq!(TlsSettings::default().connection_params(), "sslmode=disable");
+    }
+
+    #[test]
+    fn test_missing_client_certificate_file() {
+        let settings = TlsSettings {
+            ssl_mode: SslMode::VerifyFull,
+            ssl_root_cert: None,
+            ssl_cert: Some("/nonexistent/client.crt".to_string()),
+            ssl_key: Some("/nonexistent/client.key".to_string()),
+        };
+        let error = settings.build_connector().err().unwrap();
+        assert!(error.contains("ssl_cert"));
+        assert!(error.contains("/nonexistent/client.crt"));
+    }
+
+    #[test]
+    fn test_cert_without_key() {
+        let settings = TlsSettings {
+            ssl_mode: SslMode::Require,
+            ssl_root_cert: None,
+            ssl_cert: Some("client.crt".to_string()),
+            ssl_key: None,
+        };
+        assert!(settings.build_connector().is_err());
+    }
+
+    #[test]
+    fn test_libpq_env() {
+        let settings = TlsSettings {
+            ssl_mode: SslMode::VerifyFull,
+            ssl_root_cert: Some("/certs/root.crt".to_string()),
+            ssl_cert: Some("/certs/client.crt".to_string()),
+            ssl_key: Some("/certs/client.key".to_string()),
+        };
+        let env = settings.libpq_env();
+        assert!(env.contains(&("PGSSLMODE", "verify-full".to_string())));
+        assert!(env.contains(&("PGSSLROOTCERT", "/certs/root.crt".to_string())));
+        assert!(env.contains(&("PGSSLCERT", "/certs/client.crt".to_string())));
+        assert!(env.contains(&("PGSSLKEY", "/certs/client.key".to_string())));
+    }
+}
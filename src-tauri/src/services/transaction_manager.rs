@@ -5,16 +5,54 @@
  * - 批量更新行（batch_update_rows）
  * - 批量插入行（batch_insert_rows）
  * - 批量删除行（batch_delete_rows）
+ * - 按顺序执行插入、更新、删除的混合变更（batch_apply_changes）
  * - 所有操作在单个事务中执行
  * - 失败时自动回滚
+ * - 可选地在事务中延迟检查可延迟约束（SET CONSTRAINTS ALL DEFERRED）
+ * - 可选地用 SAVEPOINT 跳过失败的行，提交其余的行并返回每行的结果
+ * - 可选地指定事务隔离级别，序列化失败时标记为可重试
+ * - 失败时返回导致失败的行的下标和该行的数据，便于界面定位
+ * - 所有值以 `$N` 文本参数绑定，并显式转换为目标列的类型，不拼接到 SQL 中
+ * - 按目标列类型写入数组（integer[]、text[] 等）和复合类型的值
  * 
  * Validates: Requirements 10.2, 10.3, 16.1, 16.2
  */
 
-use crate::models::data::{RowUpdate, BatchOperationResponse};
+use crate::models::data::{
+    RowUpdate, RowChange, RowResult, BatchOperationResponse, BatchChangesResponse, IsolationLevel,
+};
+use crate::services::query_executor::format_error_message;
+use crate::services::record_writer::json_to_text;
+use crate::services::schema_service::{self, ColumnType, ColumnTypeKind};
+use crate::services::sql_utils::{qualified_name, quote_identifier};
 use std::collections::HashMap;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::types::ToSql;
 use tokio_postgres::Client;
 
+/// 批量操作的事务选项
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BatchOptions {
+    /// 在事务开始后执行 `SET CONSTRAINTS ALL DEFERRED`，将可延迟约束的检查推迟到提交时
+    ///
+    /// 只对声明为 DEFERRABLE 的约束（如外键）生效，适合按任意顺序批量导入相互引用的数据。
+    pub defer_constraints: bool,
+    /// 每行在单独的 SAVEPOINT 中执行，失败时只回滚该行，其余的行照常提交
+    ///
+    /// 响应的 `per_row_results` 给出每行的结果。为 false 时任何一行失败都回滚整个批次。
+    /// 混合变更（`batch_apply_changes`）不使用此选项，始终整体提交或回滚。
+    pub continue_on_error: bool,
+    /// 事务隔离级别，以 `BEGIN ISOLATION LEVEL ...` 开始事务；None 使用数据库的默认隔离级别
+    pub isolation: Option<IsolationLevel>,
+}
+
+/// 带 `$N` 占位符的语句及按顺序绑定的文本参数（None 为 SQL NULL）
+#[derive(Debug, PartialEq)]
+struct BoundStatement {
+    sql: String,
+    params: Vec<Option<String>>,
+}
+
 /// 批量更新多行数据
 /// 
 /// 在单个事务中执行多个UPDATE操作。如果任何操作失败，所有更改将被回滚。
@@ -43,6 +81,17 @@ pub async fn batch_update_rows(
     schema: &str,
     table: &str,
     updates: Vec<RowUpdate>,
+) -> BatchOperationResponse {
+    batch_update_rows_with_options(client, schema, table, updates, &BatchOptions::default()).await
+}
+
+/// 与 `batch_update_rows` 相同，但按 `options` 设置事务（例如延迟约束检查）
+pub async fn batch_update_rows_with_options(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    updates: Vec<RowUpdate>,
+    options: &BatchOptions,
 ) -> BatchOperationResponse {
     if updates.is_empty() {
         return BatchOperationResponse::error("没有要更新的行".to_string());
@@ -51,67 +100,15 @@ pub async fn batch_update_rows(
     log::info!("========== 批量更新行 ==========");
     log::info!("表: {}.{}, 更新数量: {}", schema, table, updates.len());
 
-    // 开始事务
-    match client.query("BEGIN", &[]).await {
-        Ok(_) => {
-            log::info!("事务已开始");
-        }
-        Err(e) => {
-            let error_msg = format!("无法开始事务: {}", e);
-            log::error!("{}", error_msg);
-            return BatchOperationResponse::error(error_msg);
-        }
-    };
-
-    let mut total_affected = 0u64;
-
-    // 执行每个更新操作
-    for (index, update) in updates.iter().enumerate() {
-        log::debug!("执行更新 {}/{}", index + 1, updates.len());
-
-        // 构建UPDATE语句
-        let sql = match build_update_statement(schema, table, update) {
-            Ok(sql) => sql,
-            Err(e) => {
-                // 回滚事务
-                let _ = client.query("ROLLBACK", &[]).await;
-                log::error!("构建UPDATE语句失败: {}", e);
-                return BatchOperationResponse::error(format!("构建UPDATE语句失败: {}", e));
-            }
-        };
-
-        log::debug!("SQL: {}", sql);
-
-        // 执行UPDATE
-        match client.execute(&sql, &[]).await {
-            Ok(affected) => {
-                total_affected += affected;
-                log::debug!("更新 {} 成功，影响 {} 行", index + 1, affected);
-            }
-            Err(e) => {
-                // 回滚事务
-                let _ = client.query("ROLLBACK", &[]).await;
-                let error_msg = format!("更新操作 {} 失败: {}. 所有更改已回滚", index + 1, e);
-                log::error!("{}", error_msg);
-                return BatchOperationResponse::error(error_msg);
-            }
-        }
-    }
+    let changes: Vec<RowChange> = updates
+        .into_iter()
+        .map(|update| RowChange::Update {
+            primary_key: update.primary_key,
+            changes: update.changes,
+        })
+        .collect();
 
-    // 提交事务
-    match client.query("COMMIT", &[]).await {
-        Ok(_) => {
-            log::info!("事务已提交，总共影响 {} 行", total_affected);
-            BatchOperationResponse::success(total_affected)
-        }
-        Err(e) => {
-            // 尝试回滚
-            let _ = client.query("ROLLBACK", &[]).await;
-            let error_msg = format!("提交事务失败: {}. 所有更改已回滚", e);
-            log::error!("{}", error_msg);
-            BatchOperationResponse::error(error_msg)
-        }
-    }
+    batch_response(execute_in_transaction(client, schema, table, &changes, options).await, options, &changes)
 }
 
 /// 批量插入多行数据
@@ -142,6 +139,17 @@ pub async fn batch_insert_rows(
     schema: &str,
     table: &str,
     rows: Vec<HashMap<String, serde_json::Value>>,
+) -> BatchOperationResponse {
+    batch_insert_rows_with_options(client, schema, table, rows, &BatchOptions::default()).await
+}
+
+/// 与 `batch_insert_rows` 相同，但按 `options` 设置事务（例如延迟约束检查）
+pub async fn batch_insert_rows_with_options(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    rows: Vec<HashMap<String, serde_json::Value>>,
+    options: &BatchOptions,
 ) -> BatchOperationResponse {
     if rows.is_empty() {
         return BatchOperationResponse::error("没有要插入的行".to_string());
@@ -150,67 +158,9 @@ pub async fn batch_insert_rows(
     log::info!("========== 批量插入行 ==========");
     log::info!("表: {}.{}, 插入数量: {}", schema, table, rows.len());
 
-    // 开始事务
-    match client.query("BEGIN", &[]).await {
-        Ok(_) => {
-            log::info!("事务已开始");
-        }
-        Err(e) => {
-            let error_msg = format!("无法开始事务: {}", e);
-            log::error!("{}", error_msg);
-            return BatchOperationResponse::error(error_msg);
-        }
-    };
-
-    let mut total_affected = 0u64;
-
-    // 执行每个插入操作
-    for (index, row) in rows.iter().enumerate() {
-        log::debug!("执行插入 {}/{}", index + 1, rows.len());
+    let changes: Vec<RowChange> = rows.into_iter().map(|row| RowChange::Insert { row }).collect();
 
-        // 构建INSERT语句
-        let sql = match build_insert_statement(schema, table, row) {
-            Ok(sql) => sql,
-            Err(e) => {
-                // 回滚事务
-                let _ = client.query("ROLLBACK", &[]).await;
-                log::error!("构建INSERT语句失败: {}", e);
-                return BatchOperationResponse::error(format!("构建INSERT语句失败: {}", e));
-            }
-        };
-
-        log::debug!("SQL: {}", sql);
-
-        // 执行INSERT
-        match client.execute(&sql, &[]).await {
-            Ok(affected) => {
-                total_affected += affected;
-                log::debug!("插入 {} 成功，影响 {} 行", index + 1, affected);
-            }
-            Err(e) => {
-                // 回滚事务
-                let _ = client.query("ROLLBACK", &[]).await;
-                let error_msg = format!("插入操作 {} 失败: {}. 所有更改已回滚", index + 1, e);
-                log::error!("{}", error_msg);
-                return BatchOperationResponse::error(error_msg);
-            }
-        }
-    }
-
-    // 提交事务
-    match client.query("COMMIT", &[]).await {
-        Ok(_) => {
-            log::info!("事务已提交，总共影响 {} 行", total_affected);
-            BatchOperationResponse::success(total_affected)
-        }
-        Err(e) => {
-            // 尝试回滚
-            let _ = client.query("ROLLBACK", &[]).await;
-            let error_msg = format!("提交事务失败: {}. 所有更改已回滚", e);
-            log::error!("{}", error_msg);
-            BatchOperationResponse::error(error_msg)
-        }
-    }
+    batch_response(execute_in_transaction(client, schema, table, &changes, options).await, options, &changes)
 }
 
 /// 批量删除多行数据
@@ -239,6 +189,17 @@ pub async fn batch_delete_rows(
     schema: &str,
     table: &str,
     primary_keys: Vec<HashMap<String, serde_json::Value>>,
+) -> BatchOperationResponse {
+    batch_delete_rows_with_options(client, schema, table, primary_keys, &BatchOptions::default()).await
+}
+
+/// 与 `batch_delete_rows` 相同，但按 `options` 设置事务（例如延迟约束检查）
+pub async fn batch_delete_rows_with_options(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    primary_keys: Vec<HashMap<String, serde_json::Value>>,
+    options: &BatchOptions,
 ) -> BatchOperationResponse {
     if primary_keys.is_empty() {
         return BatchOperationResponse::error("没有要删除的行".to_string());
@@ -247,49 +208,255 @@ pub async fn batch_delete_rows(
     log::info!("========== 批量删除行 ==========");
     log::info!("表: {}.{}, 删除数量: {}", schema, table, primary_keys.len());
 
-    // 开始事务
-    match client.query("BEGIN", &[]).await {
-        Ok(_) => {
-            log::info!("事务已开始");
-        }
-        Err(e) => {
-            let error_msg = format!("无法开始事务: {}", e);
-            log::error!("{}", error_msg);
-            return BatchOperationResponse::error(error_msg);
-        }
+    let changes: Vec<RowChange> = primary_keys
+        .into_iter()
+        .map(|primary_key| RowChange::Delete { primary_key })
+        .collect();
+
+    batch_response(execute_in_transaction(client, schema, table, &changes, options).await, options, &changes)
+}
+
+/// 在单个事务中按顺序执行插入、更新和删除的混合变更
+///
+/// 变更按给定顺序执行（例如先插入父行，再更新引用它的行）。如果任何变更失败，所有更改将被回滚。
+///
+/// # Arguments
+/// * `client` - PostgreSQL客户端连接
+/// * `schema` - 模式名称
+/// * `table` - 表名称
+/// * `changes` - 按顺序执行的变更
+///
+/// # Returns
+/// * `BatchChangesResponse` - 包含成功状态、每个变更影响的行数，或失败的变更下标和错误信息
+///
+/// # Example
+/// ```ignore
+/// let changes = vec![
+///     RowChange::Insert {
+///         row: HashMap::from([("id".to_string(), json!(3)), ("name".to_string(), json!("Carol"))]),
+///     },
+///     RowChange::Delete {
+///         primary_key: HashMap::from([("id".to_string(), json!(2))]),
+///     },
+/// ];
+/// let result = batch_apply_changes(&client, "public", "users", changes).await;
+/// ```
+pub async fn batch_apply_changes(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    changes: Vec<RowChange>,
+) -> BatchChangesResponse {
+    batch_apply_changes_with_options(client, schema, table, changes, &BatchOptions::default()).await
+}
+
+/// 与 `batch_apply_changes` 相同，但按 `options` 设置事务（例如延迟约束检查）
+pub async fn batch_apply_changes_with_options(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    changes: Vec<RowChange>,
+    options: &BatchOptions,
+) -> BatchChangesResponse {
+    if changes.is_empty() {
+        return BatchChangesResponse::error(None, "没有要执行的变更".to_string());
+    }
+
+    log::info!("========== 批量应用变更 ==========");
+    log::info!("表: {}.{}, 变更数量: {}", schema, table, changes.len());
+
+    let options = BatchOptions {
+        continue_on_error: false,
+        ..*options
     };
 
-    let mut total_affected = 0u64;
+    match execute_in_transaction(client, schema, table, &changes, &options).await {
+        Ok(results) => BatchChangesResponse::success(results.iter().map(|r| r.rows_affected).collect()),
+        Err(failure) => BatchChangesResponse::error(failure.failed_operation, failure.message),
+    }
+}
+
+/// 将事务执行结果转换为批量操作的响应
+///
+/// 失败由某一行引起时，响应中带上该行的下标和请求中的数据（`failed_index`、`failed_row`）。
+fn batch_response(
+    result: Result<Vec<RowResult>, BatchFailure>,
+    options: &BatchOptions,
+    changes: &[RowChange],
+) -> BatchOperationResponse {
+    match result {
+        Ok(results) if options.continue_on_error => BatchOperationResponse::with_row_results(results),
+        Ok(results) => BatchOperationResponse::success(results.iter().map(|r| r.rows_affected).sum()),
+        Err(failure) => BatchOperationResponse {
+            retryable: failure.retryable,
+            failed_index: failure.failed_operation,
+            failed_row: failure
+                .failed_operation
+                .and_then(|index| changes.get(index))
+                .map(request_row),
+            ..BatchOperationResponse::error(failure.message)
+        },
+    }
+}
+
+/// 变更在批量请求中的原始形式：插入为行数据，更新为 `{primary_key, changes}`，删除为主键
+fn request_row(change: &RowChange) -> serde_json::Value {
+    match change {
+        RowChange::Insert { row } => serde_json::json!(row),
+        RowChange::Update { primary_key, changes } => serde_json::json!({
+            "primary_key": primary_key,
+            "changes": changes,
+        }),
+        RowChange::Delete { primary_key } => serde_json::json!(primary_key),
+    }
+}
+
+/// 批次失败的原因
+struct BatchFailure {
+    /// 出错变更的下标；开始或提交事务失败时为 None
+    failed_operation: Option<usize>,
+    /// 错误信息
+    message: String,
+    /// 是否因序列化失败（SQLSTATE 40001）而失败，重新执行整个批次可能成功
+    retryable: bool,
+}
 
-    // 执行每个删除操作
-    for (index, pk) in primary_keys.iter().enumerate() {
-        log::debug!("执行删除 {}/{}", index + 1, primary_keys.len());
+impl BatchFailure {
+    fn new(failed_operation: Option<usize>, message: String) -> Self {
+        Self {
+            failed_operation,
+            message,
+            retryable: false,
+        }
+    }
+}
+
+/// 错误是否为序列化失败（40001 serialization_failure）
+fn is_serialization_failure(error: &tokio_postgres::Error) -> bool {
+    error.code() == Some(&SqlState::T_R_SERIALIZATION_FAILURE)
+}
+
+/// 在单个事务中按顺序执行变更，任何一步失败都回滚全部更改
+///
+/// 设置了 `continue_on_error` 时，每个变更在单独的 SAVEPOINT 中执行，失败的变更只回滚到
+/// 该 SAVEPOINT 并记录在结果中，事务继续执行后面的变更。
+///
+/// 序列化失败（40001）时即使设置了 `continue_on_error` 也回滚整个批次，并标记为可重试。
+///
+/// 成功时返回每个变更的结果；失败时返回出错变更的下标（开始或提交事务失败时为 None）和错误信息。
+async fn execute_in_transaction(
+    client: &Client,
+    schema: &str,
+    table: &str,
+    changes: &[RowChange],
+    options: &BatchOptions,
+) -> Result<Vec<RowResult>, BatchFailure> {
+    // 每个值都按目标列的类型转换
+    let column_types = schema_service::get_column_types(client, schema, table)
+        .await
+        .map_err(|e| {
+            log::error!("{}", e);
+            BatchFailure::new(None, e)
+        })?;
 
-        // 构建DELETE语句
-        let sql = match build_delete_statement(schema, table, pk) {
-            Ok(sql) => sql,
+    // 开始事务
+    if let Err(error_msg) = begin_transaction(client, options).await {
+        log::error!("{}", error_msg);
+        return Err(BatchFailure::new(None, error_msg));
+    }
+
+    let mut results = Vec::with_capacity(changes.len());
+
+    for (index, change) in changes.iter().enumerate() {
+        let (action, keyword) = describe_change(change);
+        log::debug!("执行{} {}/{}", action, index + 1, changes.len());
+
+        let statement = match build_change_statement(schema, table, change, &column_types) {
+            Ok(statement) => statement,
+            Err(e) if options.continue_on_error => {
+                log::warn!("构建{}语句失败，跳过第 {} 行: {}", keyword, index + 1, e);
+                results.push(RowResult {
+                    index,
+                    success: false,
+                    rows_affected: 0,
+                    error: Some(format!("构建{}语句失败: {}", keyword, e)),
+                });
+                continue;
+            }
             Err(e) => {
                 // 回滚事务
                 let _ = client.query("ROLLBACK", &[]).await;
-                log::error!("构建DELETE语句失败: {}", e);
-                return BatchOperationResponse::error(format!("构建DELETE语句失败: {}", e));
+                log::error!("构建{}语句失败: {}", keyword, e);
+                return Err(BatchFailure::new(Some(index), format!("构建{}语句失败: {}", keyword, e)));
             }
         };
 
-        log::debug!("SQL: {}", sql);
+        log::debug!("SQL: {}", statement.sql);
 
-        // 执行DELETE
-        match client.execute(&sql, &[]).await {
-            Ok(affected) => {
-                total_affected += affected;
-                log::debug!("删除 {} 成功，影响 {} 行", index + 1, affected);
+        let params: Vec<&(dyn ToSql + Sync)> = statement
+            .params
+            .iter()
+            .map(|v| v as &(dyn ToSql + Sync))
+            .collect();
+
+        if options.continue_on_error {
+            if let Err(e) = client.batch_execute("SAVEPOINT batch_row").await {
+                let _ = client.query("ROLLBACK", &[]).await;
+                let error_msg = format!("无法创建保存点: {}. 所有更改已回滚", e);
+                log::error!("{}", error_msg);
+                return Err(BatchFailure::new(Some(index), error_msg));
+            }
+        }
+
+        match client.execute(&statement.sql, &params).await {
+            Ok(rows) => {
+                results.push(RowResult {
+                    index,
+                    success: true,
+                    rows_affected: rows,
+                    error: None,
+                });
+                log::debug!("{} {} 成功，影响 {} 行", action, index + 1, rows);
+            }
+            Err(e) if options.continue_on_error && !is_serialization_failure(&e) => {
+                let error_msg = format_error_message(&e);
+                log::warn!("{}操作 {} 失败，已回滚该行: {}", action, index + 1, error_msg);
+                results.push(RowResult {
+                    index,
+                    success: false,
+                    rows_affected: 0,
+                    error: Some(format!("{}操作 {} 失败: {}", action, index + 1, error_msg)),
+                });
+                if let Err(e) = client.batch_execute("ROLLBACK TO SAVEPOINT batch_row").await {
+                    let _ = client.query("ROLLBACK", &[]).await;
+                    let error_msg = format!("无法回滚到保存点: {}. 所有更改已回滚", e);
+                    log::error!("{}", error_msg);
+                    return Err(BatchFailure::new(Some(index), error_msg));
+                }
             }
             Err(e) => {
                 // 回滚事务
                 let _ = client.query("ROLLBACK", &[]).await;
-                let error_msg = format!("删除操作 {} 失败: {}. 所有更改已回滚", index + 1, e);
+                let error_msg = format!(
+                    "{}操作 {} 失败: {}. 所有更改已回滚",
+                    action,
+                    index + 1,
+                    format_error_message(&e)
+                );
                 log::error!("{}", error_msg);
-                return BatchOperationResponse::error(error_msg);
+                return Err(BatchFailure {
+                    retryable: is_serialization_failure(&e),
+                    ..BatchFailure::new(Some(index), error_msg)
+                });
+            }
+        }
+
+        if options.continue_on_error {
+            if let Err(e) = client.batch_execute("RELEASE SAVEPOINT batch_row").await {
+                let _ = client.query("ROLLBACK", &[]).await;
+                let error_msg = format!("无法释放保存点: {}. 所有更改已回滚", e);
+                log::error!("{}", error_msg);
+                return Err(BatchFailure::new(Some(index), error_msg));
             }
         }
     }
@@ -297,126 +464,249 @@ pub async fn batch_delete_rows(
     // 提交事务
     match client.query("COMMIT", &[]).await {
         Ok(_) => {
-            log::info!("事务已提交，总共影响 {} 行", total_affected);
-            BatchOperationResponse::success(total_affected)
+            let affected: u64 = results.iter().map(|r| r.rows_affected).sum();
+            let failed = results.iter().filter(|r| !r.success).count();
+            if failed > 0 {
+                log::info!("事务已提交，总共影响 {} 行，跳过 {} 个失败的行", affected, failed);
+            } else {
+                log::info!("事务已提交，总共影响 {} 行", affected);
+            }
+            Ok(results)
         }
         Err(e) => {
             // 尝试回滚
             let _ = client.query("ROLLBACK", &[]).await;
-            let error_msg = format!("提交事务失败: {}. 所有更改已回滚", e);
+            let error_msg = format!("提交事务失败: {}. 所有更改已回滚", format_error_message(&e));
             log::error!("{}", error_msg);
-            BatchOperationResponse::error(error_msg)
+            Err(BatchFailure {
+                retryable: is_serialization_failure(&e),
+                ..BatchFailure::new(None, error_msg)
+            })
+        }
+    }
+}
+
+/// 变更的中文动作名称和SQL关键字（用于日志和错误信息）
+fn describe_change(change: &RowChange) -> (&'static str, &'static str) {
+    match change {
+        RowChange::Insert { .. } => ("插入", "INSERT"),
+        RowChange::Update { .. } => ("更新", "UPDATE"),
+        RowChange::Delete { .. } => ("删除", "DELETE"),
+    }
+}
+
+/// 根据变更类型构建对应的SQL语句
+fn build_change_statement(
+    schema: &str,
+    table: &str,
+    change: &RowChange,
+    column_types: &HashMap<String, ColumnType>,
+) -> Result<BoundStatement, String> {
+    match change {
+        RowChange::Insert { row } => build_insert_statement(schema, table, row, column_types),
+        RowChange::Update { primary_key, changes } => {
+            build_update_statement(schema, table, primary_key, changes, column_types)
         }
+        RowChange::Delete { primary_key } => build_delete_statement(schema, table, primary_key, column_types),
+    }
+}
+
+/// 按选项的隔离级别生成 BEGIN 语句
+fn begin_statement(options: &BatchOptions) -> &'static str {
+    match options.isolation {
+        Some(IsolationLevel::ReadCommitted) => "BEGIN ISOLATION LEVEL READ COMMITTED",
+        Some(IsolationLevel::RepeatableRead) => "BEGIN ISOLATION LEVEL REPEATABLE READ",
+        Some(IsolationLevel::Serializable) => "BEGIN ISOLATION LEVEL SERIALIZABLE",
+        None => "BEGIN",
     }
 }
 
+/// 开始事务，并按选项设置约束检查时机
+async fn begin_transaction(client: &Client, options: &BatchOptions) -> Result<(), String> {
+    client
+        .batch_execute(begin_statement(options))
+        .await
+        .map_err(|e| format!("无法开始事务: {}", e))?;
+    log::info!("事务已开始");
+
+    if options.defer_constraints {
+        if let Err(e) = client.batch_execute("SET CONSTRAINTS ALL DEFERRED").await {
+            let _ = client.batch_execute("ROLLBACK").await;
+            return Err(format!("无法延迟约束检查: {}", e));
+        }
+        log::info!("约束检查已延迟到提交时");
+    }
+
+    Ok(())
+}
+
 /// 构建UPDATE语句
 /// 
-/// 根据RowUpdate生成SQL UPDATE语句
+/// 根据主键和更改的字段生成参数化的SQL UPDATE语句，新值的参数在前，主键的参数在后
 fn build_update_statement(
     schema: &str,
     table: &str,
-    update: &RowUpdate,
-) -> Result<String, String> {
-    if update.changes.is_empty() {
+    primary_key: &HashMap<String, serde_json::Value>,
+    changes: &HashMap<String, serde_json::Value>,
+    column_types: &HashMap<String, ColumnType>,
+) -> Result<BoundStatement, String> {
+    if changes.is_empty() {
         return Err("没有要更新的字段".to_string());
     }
 
-    if update.primary_key.is_empty() {
+    if primary_key.is_empty() {
         return Err("主键不能为空".to_string());
     }
 
+    let mut params = Vec::with_capacity(changes.len() + primary_key.len());
+
     // 构建SET子句
-    let set_clauses: Vec<String> = update
-        .changes
-        .iter()
-        .map(|(col, val)| format!("{} = {}", col, format_value(val)))
-        .collect();
+    let set_clauses = bind_assignments(changes, column_types, &mut params)?;
 
     // 构建WHERE子句
-    let where_clauses: Vec<String> = update
-        .primary_key
-        .iter()
-        .map(|(col, val)| format!("{} = {}", col, format_value(val)))
-        .collect();
-
-    Ok(format!(
-        "UPDATE {}.{} SET {} WHERE {}",
-        schema,
-        table,
-        set_clauses.join(", "),
-        where_clauses.join(" AND ")
-    ))
+    let where_clauses = bind_assignments(primary_key, column_types, &mut params)?;
+
+    Ok(BoundStatement {
+        sql: format!(
+            "UPDATE {} SET {} WHERE {}",
+            qualified_name(schema, table),
+            set_clauses.join(", "),
+            where_clauses.join(" AND ")
+        ),
+        params,
+    })
 }
 
 /// 构建INSERT语句
 /// 
-/// 根据行数据生成SQL INSERT语句
+/// 根据行数据生成参数化的SQL INSERT语句
 fn build_insert_statement(
     schema: &str,
     table: &str,
     row: &HashMap<String, serde_json::Value>,
-) -> Result<String, String> {
+    column_types: &HashMap<String, ColumnType>,
+) -> Result<BoundStatement, String> {
     if row.is_empty() {
         return Err("没有要插入的数据".to_string());
     }
 
-    let columns: Vec<String> = row.keys().cloned().collect();
-    let values: Vec<String> = row.values().map(format_value).collect();
+    let mut params = Vec::with_capacity(row.len());
+    let mut columns = Vec::with_capacity(row.len());
+    let mut values = Vec::with_capacity(row.len());
+    for (col, val) in row {
+        columns.push(quote_identifier(col));
+        values.push(bind_value(col, val, column_types, &mut params)?);
+    }
 
-    Ok(format!(
-        "INSERT INTO {}.{} ({}) VALUES ({})",
-        schema,
-        table,
-        columns.join(", "),
-        values.join(", ")
-    ))
+    Ok(BoundStatement {
+        sql: format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            qualified_name(schema, table),
+            columns.join(", "),
+            values.join(", ")
+        ),
+        params,
+    })
 }
 
 /// 构建DELETE语句
 /// 
-/// 根据主键生成SQL DELETE语句
+/// 根据主键生成参数化的SQL DELETE语句
 fn build_delete_statement(
     schema: &str,
     table: &str,
     primary_key: &HashMap<String, serde_json::Value>,
-) -> Result<String, String> {
+    column_types: &HashMap<String, ColumnType>,
+) -> Result<BoundStatement, String> {
     if primary_key.is_empty() {
         return Err("主键不能为空".to_string());
     }
 
+    let mut params = Vec::with_capacity(primary_key.len());
+
     // 构建WHERE子句
-    let where_clauses: Vec<String> = primary_key
+    let where_clauses = bind_assignments(primary_key, column_types, &mut params)?;
+
+    Ok(BoundStatement {
+        sql: format!(
+            "DELETE FROM {} WHERE {}",
+            qualified_name(schema, table),
+            where_clauses.join(" AND ")
+        ),
+        params,
+    })
+}
+
+/// 为每个列生成 `"列" = <参数>` 形式的条件或赋值，参数追加到 `params`
+fn bind_assignments(
+    values: &HashMap<String, serde_json::Value>,
+    column_types: &HashMap<String, ColumnType>,
+    params: &mut Vec<Option<String>>,
+) -> Result<Vec<String>, String> {
+    values
         .iter()
-        .map(|(col, val)| format!("{} = {}", col, format_value(val)))
-        .collect();
+        .map(|(col, val)| {
+            let value = bind_value(col, val, column_types, params)?;
+            Ok(format!("{} = {}", quote_identifier(col), value))
+        })
+        .collect()
+}
 
-    Ok(format!(
-        "DELETE FROM {}.{} WHERE {}",
-        schema,
-        table,
-        where_clauses.join(" AND ")
-    ))
+/// 将JSON值追加为文本参数，返回按目标列类型转换该参数的表达式
+///
+/// 数组值写入数组列时绑定数组字面量（`CAST($1::text AS integer[])`），
+/// 对象值写入复合类型列时按字段名填充（`jsonb_populate_record`）；其他值以文本形式绑定后转换为列的类型。
+fn bind_value(
+    column: &str,
+    value: &serde_json::Value,
+    column_types: &HashMap<String, ColumnType>,
+    params: &mut Vec<Option<String>>,
+) -> Result<String, String> {
+    let column_type = column_types
+        .get(column)
+        .ok_or_else(|| format!("列 {} 不存在", column))?;
+    let placeholder = params.len() + 1;
+
+    let (param, expression) = match (value, &column_type.kind) {
+        (serde_json::Value::Array(_), ColumnTypeKind::Array) => (
+            Some(to_array_literal(value)),
+            format!("CAST(${}::text AS {})", placeholder, column_type.data_type),
+        ),
+        (serde_json::Value::Object(_), ColumnTypeKind::Composite) => (
+            Some(value.to_string()),
+            format!("jsonb_populate_record(NULL::{}, ${}::text::jsonb)", column_type.data_type, placeholder),
+        ),
+        _ => (
+            json_to_text(value),
+            format!("CAST(${}::text AS {})", placeholder, column_type.data_type),
+        ),
+    };
+
+    params.push(param);
+    Ok(expression)
 }
 
-/// 格式化JSON值为SQL字符串
-/// 
-/// 将serde_json::Value转换为适合SQL语句的字符串表示
-fn format_value(value: &serde_json::Value) -> String {
+/// 将JSON数组转换为PostgreSQL数组字面量（如 `{1,NULL,"a b"}`），嵌套数组对应多维数组
+fn to_array_literal(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Array(items) => {
+            let elements: Vec<String> = items.iter().map(to_array_element).collect();
+            format!("{{{}}}", elements.join(","))
+        }
+        _ => to_array_element(value),
+    }
+}
+
+/// 格式化数组字面量中的单个元素：字符串和对象加双引号并转义
+fn to_array_element(value: &serde_json::Value) -> String {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
     match value {
         serde_json::Value::Null => "NULL".to_string(),
         serde_json::Value::Bool(b) => b.to_string(),
         serde_json::Value::Number(n) => n.to_string(),
-        serde_json::Value::String(s) => {
-            // 转义单引号
-            let escaped = s.replace("'", "''");
-            format!("'{}'", escaped)
-        }
-        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
-            // 对于复杂类型，转换为JSON字符串
-            let json_str = value.to_string().replace("'", "''");
-            format!("'{}'", json_str)
-        }
+        serde_json::Value::String(s) => quote(s),
+        serde_json::Value::Array(_) => to_array_literal(value),
+        serde_json::Value::Object(_) => quote(&value.to_string()),
     }
 }
 
@@ -425,27 +715,90 @@ mod tests {
     use super::*;
     use serde_json::json;
 
-    #[test]
-    fn test_format_value_null() {
-        assert_eq!(format_value(&json!(null)), "NULL");
+    fn column(data_type: &str, kind: ColumnTypeKind) -> ColumnType {
+        ColumnType { data_type: data_type.to_string(), kind }
+    }
+
+    /// users 表的列类型
+    fn user_columns() -> HashMap<String, ColumnType> {
+        HashMap::from([
+            ("id".to_string(), column("integer", ColumnTypeKind::Other)),
+            ("name".to_string(), column("text", ColumnTypeKind::Other)),
+            ("age".to_string(), column("integer", ColumnTypeKind::Other)),
+            ("user_id".to_string(), column("integer", ColumnTypeKind::Other)),
+            ("role_id".to_string(), column("integer", ColumnTypeKind::Other)),
+        ])
     }
 
     #[test]
-    fn test_format_value_bool() {
-        assert_eq!(format_value(&json!(true)), "true");
-        assert_eq!(format_value(&json!(false)), "false");
+    fn test_batch_response_reports_failing_row() {
+        let changes = vec![
+            RowChange::Insert { row: HashMap::from([("id".to_string(), json!(1))]) },
+            RowChange::Update {
+                primary_key: HashMap::from([("id".to_string(), json!(2))]),
+                changes: HashMap::from([("age".to_string(), json!("x"))]),
+            },
+        ];
+
+        let response = batch_response(
+            Err(BatchFailure::new(Some(1), "更新操作 2 失败".to_string())),
+            &BatchOptions::default(),
+            &changes,
+        );
+        assert!(!response.success);
+        assert_eq!(response.failed_index, Some(1));
+        assert_eq!(
+            response.failed_row,
+            Some(json!({"primary_key": {"id": 2}, "changes": {"age": "x"}}))
+        );
+
+        // 提交失败不对应某一行
+        let response = batch_response(
+            Err(BatchFailure::new(None, "提交事务失败".to_string())),
+            &BatchOptions::default(),
+            &changes,
+        );
+        assert_eq!(response.failed_index, None);
+        assert_eq!(response.failed_row, None);
+
+        // 成功时的响应不变
+        let response = batch_response(
+            Ok(vec![RowResult { index: 0, success: true, rows_affected: 1, error: None }]),
+            &BatchOptions::default(),
+            &changes[..1],
+        );
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json.get("failed_index").is_none());
+        assert!(json.get("failed_row").is_none());
     }
 
     #[test]
-    fn test_format_value_number() {
-        assert_eq!(format_value(&json!(42)), "42");
-        assert_eq!(format_value(&json!(3.14)), "3.14");
+    fn test_begin_statement_uses_isolation_level() {
+        assert_eq!(begin_statement(&BatchOptions::default()), "BEGIN");
+
+        let options = BatchOptions {
+            isolation: Some(IsolationLevel::Serializable),
+            ..Default::default()
+        };
+        assert_eq!(begin_statement(&options), "BEGIN ISOLATION LEVEL SERIALIZABLE");
     }
 
     #[test]
-    fn test_format_value_string() {
-        assert_eq!(format_value(&json!("hello")), "'hello'");
-        assert_eq!(format_value(&json!("O'Brien")), "'O''Brien'");
+    fn test_bind_value_uses_column_type() {
+        let mut params = Vec::new();
+        assert_eq!(
+            bind_value("name", &json!("a'; rollback; --"), &user_columns(), &mut params).unwrap(),
+            "CAST($1::text AS text)"
+        );
+        assert_eq!(
+            bind_value("age", &json!(null), &user_columns(), &mut params).unwrap(),
+            "CAST($2::text AS integer)"
+        );
+        assert_eq!(params, vec![Some("a'; rollback; --".to_string()), None]);
+
+        let result = bind_value("missing", &json!(1), &user_columns(), &mut params);
+        assert_eq!(result.unwrap_err(), "列 missing 不存在");
+        assert_eq!(params.len(), 2);
     }
 
     #[test]
@@ -462,13 +815,17 @@ mod tests {
             changes,
         };
 
-        let sql = build_update_statement("public", "users", &update).unwrap();
-        
-        // 由于HashMap的顺序不确定，我们检查SQL包含所有必要部分
-        assert!(sql.starts_with("UPDATE public.users SET "));
-        assert!(sql.contains("name = 'Alice'"));
-        assert!(sql.contains("age = 30"));
-        assert!(sql.contains("WHERE id = 1"));
+        let statement =
+            build_update_statement("public", "users", &update.primary_key, &update.changes, &user_columns()).unwrap();
+
+        // 由于HashMap的顺序不确定，检查新值的参数在前、主键的参数在后
+        assert!(statement.sql.starts_with("UPDATE \"public\".\"users\" SET "));
+        assert!(statement.sql.ends_with(" WHERE \"id\" = CAST($3::text AS integer)"));
+        assert_eq!(statement.params.len(), 3);
+        assert_eq!(statement.params[2], Some("1".to_string()));
+        let name_param = if statement.sql.contains("\"name\" = CAST($1::text AS text)") { 0 } else { 1 };
+        assert_eq!(statement.params[name_param], Some("Alice".to_string()));
+        assert_eq!(statement.params[1 - name_param], Some("30".to_string()));
     }
 
     #[test]
@@ -481,7 +838,7 @@ mod tests {
             changes: HashMap::new(),
         };
 
-        let result = build_update_statement("public", "users", &update);
+        let result = build_update_statement("public", "users", &update.primary_key, &update.changes, &user_columns());
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "没有要更新的字段");
     }
@@ -496,7 +853,7 @@ mod tests {
             changes,
         };
 
-        let result = build_update_statement("public", "users", &update);
+        let result = build_update_statement("public", "users", &update.primary_key, &update.changes, &user_columns());
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "主键不能为空");
     }
@@ -505,36 +862,50 @@ mod tests {
     fn test_build_insert_statement() {
         let mut row = HashMap::new();
         row.insert("id".to_string(), json!(1));
-        row.insert("name".to_string(), json!("Alice"));
+        row.insert("name".to_string(), json!("O'Brien"));
         row.insert("age".to_string(), json!(30));
 
-        let sql = build_insert_statement("public", "users", &row).unwrap();
-        
-        assert!(sql.starts_with("INSERT INTO public.users ("));
-        assert!(sql.contains("id"));
-        assert!(sql.contains("name"));
-        assert!(sql.contains("age"));
-        assert!(sql.contains("VALUES ("));
-        assert!(sql.contains("1"));
-        assert!(sql.contains("'Alice'"));
-        assert!(sql.contains("30"));
+        let statement = build_insert_statement("public", "users", &row, &user_columns()).unwrap();
+
+        assert!(statement.sql.starts_with("INSERT INTO \"public\".\"users\" ("));
+        assert!(statement.sql.contains("\"id\""));
+        assert!(statement.sql.contains("\"name\""));
+        assert!(statement.sql.contains("\"age\""));
+        assert!(statement.sql.contains("VALUES (CAST($1::text AS "));
+        assert!(statement.sql.contains("CAST($3::text AS "));
+        assert!(!statement.sql.contains("O'Brien"));
+        assert_eq!(statement.params.len(), 3);
+        assert!(statement.params.contains(&Some("O'Brien".to_string())));
     }
 
     #[test]
     fn test_build_insert_statement_empty_row() {
         let row = HashMap::new();
-        let result = build_insert_statement("public", "users", &row);
+        let result = build_insert_statement("public", "users", &row, &user_columns());
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "没有要插入的数据");
     }
 
+    #[test]
+    fn test_build_insert_statement_unknown_column() {
+        let row = HashMap::from([("nonexistent_column".to_string(), json!(1))]);
+        let result = build_insert_statement("public", "users", &row, &user_columns());
+        assert_eq!(result.unwrap_err(), "列 nonexistent_column 不存在");
+    }
+
     #[test]
     fn test_build_delete_statement() {
         let mut primary_key = HashMap::new();
         primary_key.insert("id".to_string(), json!(1));
 
-        let sql = build_delete_statement("public", "users", &primary_key).unwrap();
-        assert_eq!(sql, "DELETE FROM public.users WHERE id = 1");
+        let statement = build_delete_statement("public", "users", &primary_key, &user_columns()).unwrap();
+        assert_eq!(
+            statement,
+            BoundStatement {
+                sql: "DELETE FROM \"public\".\"users\" WHERE \"id\" = CAST($1::text AS integer)".to_string(),
+                params: vec![Some("1".to_string())],
+            }
+        );
     }
 
     #[test]
@@ -543,20 +914,103 @@ mod tests {
         primary_key.insert("user_id".to_string(), json!(1));
         primary_key.insert("role_id".to_string(), json!(2));
 
-        let sql = build_delete_statement("public", "user_roles", &primary_key).unwrap();
-        
+        let statement = build_delete_statement("public", "user_roles", &primary_key, &user_columns()).unwrap();
+
         // 由于HashMap的顺序不确定，我们检查SQL包含所有必要部分
-        assert!(sql.starts_with("DELETE FROM public.user_roles WHERE "));
-        assert!(sql.contains("user_id = 1"));
-        assert!(sql.contains("role_id = 2"));
-        assert!(sql.contains(" AND "));
+        assert!(statement.sql.starts_with("DELETE FROM \"public\".\"user_roles\" WHERE "));
+        assert!(statement.sql.contains("\"user_id\" = CAST($"));
+        assert!(statement.sql.contains("\"role_id\" = CAST($"));
+        assert!(statement.sql.contains(" AND "));
+        assert_eq!(statement.params.len(), 2);
     }
 
     #[test]
     fn test_build_delete_statement_empty_primary_key() {
         let primary_key = HashMap::new();
-        let result = build_delete_statement("public", "users", &primary_key);
+        let result = build_delete_statement("public", "users", &primary_key, &user_columns());
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "主键不能为空");
     }
+
+    #[test]
+    fn test_build_change_statement() {
+        let insert = RowChange::Insert {
+            row: HashMap::from([("id".to_string(), json!(3))]),
+        };
+        let update = RowChange::Update {
+            primary_key: HashMap::from([("id".to_string(), json!(1))]),
+            changes: HashMap::from([("name".to_string(), json!("Alice"))]),
+        };
+        let delete = RowChange::Delete {
+            primary_key: HashMap::from([("id".to_string(), json!(2))]),
+        };
+
+        assert_eq!(
+            build_change_statement("public", "users", &insert, &user_columns()).unwrap().sql,
+            "INSERT INTO \"public\".\"users\" (\"id\") VALUES (CAST($1::text AS integer))"
+        );
+        assert_eq!(
+            build_change_statement("public", "users", &update, &user_columns()).unwrap(),
+            BoundStatement {
+                sql: "UPDATE \"public\".\"users\" SET \"name\" = CAST($1::text AS text) \
+                      WHERE \"id\" = CAST($2::text AS integer)"
+                    .to_string(),
+                params: vec![Some("Alice".to_string()), Some("1".to_string())],
+            }
+        );
+        assert_eq!(
+            build_change_statement("public", "users", &delete, &user_columns()).unwrap().sql,
+            "DELETE FROM \"public\".\"users\" WHERE \"id\" = CAST($1::text AS integer)"
+        );
+        assert_eq!(describe_change(&update), ("更新", "UPDATE"));
+    }
+
+    #[test]
+    fn test_to_array_literal() {
+        assert_eq!(to_array_literal(&json!([1, null, 3])), "{1,NULL,3}");
+        assert_eq!(to_array_literal(&json!([])), "{}");
+        assert_eq!(to_array_literal(&json!([[1, 2], [3, 4]])), "{{1,2},{3,4}}");
+        assert_eq!(
+            to_array_literal(&json!(["a b", "q\"x", "back\\slash", "NULL"])),
+            r#"{"a b","q\"x","back\\slash","NULL"}"#
+        );
+    }
+
+    #[test]
+    fn test_bind_value_structured_values() {
+        let column_types = HashMap::from([
+            ("scores".to_string(), column("integer[]", ColumnTypeKind::Array)),
+            ("tags".to_string(), column("text[]", ColumnTypeKind::Array)),
+            ("location".to_string(), column("geo_point", ColumnTypeKind::Composite)),
+            ("payload".to_string(), column("jsonb", ColumnTypeKind::Json)),
+        ]);
+        let mut params = Vec::new();
+
+        assert_eq!(
+            bind_value("scores", &json!([1, 2]), &column_types, &mut params).unwrap(),
+            "CAST($1::text AS integer[])"
+        );
+        assert_eq!(
+            bind_value("tags", &json!(["it's"]), &column_types, &mut params).unwrap(),
+            "CAST($2::text AS text[])"
+        );
+        assert_eq!(
+            bind_value("location", &json!({"x": 1}), &column_types, &mut params).unwrap(),
+            "jsonb_populate_record(NULL::geo_point, $3::text::jsonb)"
+        );
+        // JSON 列的数组和对象保持 JSON 文本
+        assert_eq!(
+            bind_value("payload", &json!([1]), &column_types, &mut params).unwrap(),
+            "CAST($4::text AS jsonb)"
+        );
+        assert_eq!(
+            params,
+            vec![
+                Some("{1,2}".to_string()),
+                Some("{\"it's\"}".to_string()),
+                Some("{\"x\":1}".to_string()),
+                Some("[1]".to_string()),
+            ]
+        );
+    }
 }
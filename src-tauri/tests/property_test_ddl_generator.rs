@@ -14,6 +14,7 @@ use pg_db_tool::models::schema::{
     TableChanges,
 };
 use pg_db_tool::services::ddl_generator::{generate_create_table, generate_alter_table};
+use std::collections::HashMap;
 
 // Strategy for generating valid PostgreSQL identifiers
 fn identifier_strategy() -> impl Strategy<Value = String> {
@@ -63,8 +64,11 @@ fn column_definition_strategy() -> impl Strategy<Value = ColumnDefinition> {
             numeric_scale,
             is_nullable: if is_primary_key { false } else { is_nullable },
             column_default: None,
+            generated_expression: None,
+            identity: None,
             is_primary_key,
             is_unique,
+            comment: None,
         }
     })
 }
@@ -142,6 +146,9 @@ fn table_design_strategy() -> impl Strategy<Value = TableDesign> {
             columns,
             constraints,
             indexes,
+            storage_parameters: HashMap::new(),
+            tablespace: None,
+            comment: None,
         }
     })
 }
@@ -224,15 +231,19 @@ fn table_changes_strategy() -> impl Strategy<Value = TableChanges> {
         prop::collection::vec(identifier_strategy(), 0..2),
         prop::collection::vec(identifier_strategy(), 0..2),
         prop::collection::vec(identifier_strategy(), 0..2),
-    ).prop_map(|(added_columns, dropped_columns, dropped_constraints, dropped_indexes)| {
+        prop::collection::vec((identifier_strategy(), identifier_strategy()), 0..2),
+    ).prop_map(|(added_columns, dropped_columns, dropped_constraints, dropped_indexes, renamed_constraints)| {
         TableChanges {
             added_columns,
             modified_columns: vec![], // Simplified for now
             dropped_columns,
             added_constraints: vec![],
             dropped_constraints,
+            renamed_constraints,
             added_indexes: vec![],
             dropped_indexes,
+            set_storage_parameters: HashMap::new(),
+            reset_storage_parameters: vec![],
         }
     })
 }
@@ -258,6 +269,7 @@ proptest! {
             || !changes.dropped_columns.is_empty()
             || !changes.added_constraints.is_empty()
             || !changes.dropped_constraints.is_empty()
+            || !changes.renamed_constraints.is_empty()
             || !changes.added_indexes.is_empty()
             || !changes.dropped_indexes.is_empty();
         
@@ -321,6 +333,21 @@ proptest! {
             );
         }
         
+        // Verify renamed constraints are in statements, before any drop or add
+        let first_other_index = statements
+            .iter()
+            .position(|s| !s.contains("RENAME CONSTRAINT"))
+            .unwrap_or(statements.len());
+        for (old_name, new_name) in &changes.renamed_constraints {
+            let position = statements.iter().position(|s| {
+                s.contains("RENAME CONSTRAINT") && s.contains(old_name.as_str()) && s.contains(new_name.as_str())
+            });
+            prop_assert!(
+                position.is_some_and(|i| i < first_other_index),
+                "Should have RENAME CONSTRAINT statement before drops/adds for: {} -> {}", old_name, new_name
+            );
+        }
+        
         // Verify dropped indexes are in statements
         for index_name in &changes.dropped_indexes {
             let found = statements.iter().any(|s| {
@@ -336,7 +363,7 @@ proptest! {
         let mut last_drop_index = None;
         let mut first_add_index = None;
         
-        for (i, statement) in statements.iter().enumerate() {
+        for (i, statement) in statements.iter().enumerate().skip(changes.renamed_constraints.len()) {
             let upper = statement.to_uppercase();
             if upper.contains("DROP") {
                 last_drop_index = Some(i);
@@ -373,8 +400,11 @@ mod unit_tests {
                     numeric_scale: None,
                     is_nullable: false,
                     column_default: None,
+                    generated_expression: None,
+                    identity: None,
                     is_primary_key: true,
                     is_unique: false,
+                    comment: None,
                 },
                 ColumnDefinition {
                     name: "name".to_string(),
@@ -384,8 +414,11 @@ mod unit_tests {
                     numeric_scale: None,
                     is_nullable: false,
                     column_default: None,
+                    generated_expression: None,
+                    identity: None,
                     is_primary_key: false,
                     is_unique: false,
+                    comment: None,
                 },
             ],
             constraints: vec![
@@ -395,6 +428,9 @@ mod unit_tests {
                 ),
             ],
             indexes: vec![],
+            storage_parameters: HashMap::new(),
+            tablespace: None,
+            comment: None,
         };
         
         let ddl = generate_create_table(&design);
@@ -418,16 +454,22 @@ mod unit_tests {
                     numeric_scale: None,
                     is_nullable: true,
                     column_default: None,
+                    generated_expression: None,
+                    identity: None,
                     is_primary_key: false,
                     is_unique: false,
+                    comment: None,
                 },
             ],
             modified_columns: vec![],
             dropped_columns: vec!["old_column".to_string()],
             added_constraints: vec![],
             dropped_constraints: vec![],
+            renamed_constraints: vec![],
             added_indexes: vec![],
             dropped_indexes: vec![],
+            set_storage_parameters: HashMap::new(),
+            reset_storage_parameters: vec![],
         };
         
         let statements = generate_alter_table("public", "users", &changes);
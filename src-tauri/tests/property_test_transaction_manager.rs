@@ -13,9 +13,9 @@
  */
 
 use pg_db_tool::services::transaction_manager;
-use pg_db_tool::models::data::RowUpdate;
+use pg_db_tool::models::data::{RowChange, RowUpdate};
 use proptest::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use serde_json::json;
 
 /// 获取测试数据库连接
@@ -481,3 +481,189 @@ proptest! {
         })?;
     }
 }
+
+/// 混合变更测试使用的初始行（id 1-10，col1 = 0）
+const MIXED_SEED_IDS: std::ops::RangeInclusive<i32> = 1..=10;
+
+/// 生成一组有序的混合变更
+///
+/// 插入使用 100 + 下标作为ID，保证不与初始行或其他插入冲突；更新和删除针对初始行，
+/// 同一行可能先被删除再被更新（影响 0 行，不视为失败）。
+fn arbitrary_mixed_changes() -> impl Strategy<Value = Vec<RowChange>> {
+    prop::collection::vec((0..3u8, MIXED_SEED_IDS, any::<i32>()), 2..8).prop_map(|ops| {
+        ops.into_iter()
+            .enumerate()
+            .map(|(index, (kind, id, value))| match kind {
+                0 => RowChange::Insert {
+                    row: HashMap::from([
+                        ("id".to_string(), json!(100 + index as i32)),
+                        ("col1".to_string(), json!(value)),
+                    ]),
+                },
+                1 => RowChange::Update {
+                    primary_key: HashMap::from([("id".to_string(), json!(id))]),
+                    changes: HashMap::from([("col1".to_string(), json!(value))]),
+                },
+                _ => RowChange::Delete {
+                    primary_key: HashMap::from([("id".to_string(), json!(id))]),
+                },
+            })
+            .collect()
+    })
+}
+
+/// 创建混合变更测试表并插入初始行，返回表名
+async fn create_mixed_table(client: &tokio_postgres::Client) -> String {
+    let table_name = format!("prop_test_mixed_{}", uuid::Uuid::new_v4().to_string().replace("-", "_"));
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE {0} (id INTEGER PRIMARY KEY, col1 INTEGER);
+             INSERT INTO {0} (id, col1) SELECT g, 0 FROM generate_series({1}, {2}) g;",
+            table_name,
+            MIXED_SEED_IDS.start(),
+            MIXED_SEED_IDS.end()
+        ))
+        .await
+        .unwrap();
+    table_name
+}
+
+/// 读取测试表的全部数据（id -> col1）
+async fn read_mixed_table(client: &tokio_postgres::Client, table_name: &str) -> BTreeMap<i32, i32> {
+    client
+        .query(&format!("SELECT id, col1 FROM {} ORDER BY id", table_name), &[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| (row.get(0), row.get(1)))
+        .collect()
+}
+
+fn key_id(primary_key: &HashMap<String, serde_json::Value>) -> i32 {
+    primary_key.get("id").unwrap().as_i64().unwrap() as i32
+}
+
+// Feature: database-advanced-features, Property 10: 事务原子性
+// 测试混合变更的事务原子性：任意位置的变更失败时，之前已执行的插入、更新和删除都应该被回滚
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(100))]
+
+    #[test]
+    fn property_batch_apply_changes_atomicity(
+        changes in arbitrary_mixed_changes(),
+        failing_position in any::<prop::sample::Index>()
+    ) {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let client = match get_test_client().await {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+                    return Ok(());
+                }
+            };
+
+            let table_name = create_mixed_table(&client).await;
+            let original_data = read_mixed_table(&client, &table_name).await;
+
+            // 在任意位置插入一个会失败的变更（更新不存在的列）
+            let position = failing_position.index(changes.len() + 1);
+            let mut failing_changes = changes.clone();
+            failing_changes.insert(position, RowChange::Update {
+                primary_key: HashMap::from([("id".to_string(), json!(1))]),
+                changes: HashMap::from([("nonexistent_column".to_string(), json!(999))]),
+            });
+
+            let result = transaction_manager::batch_apply_changes(
+                &client,
+                "public",
+                &table_name,
+                failing_changes
+            ).await;
+
+            // 验证操作失败，并指出失败的变更
+            prop_assert!(!result.success, "混合变更应该失败");
+            prop_assert!(result.error.is_some(), "应该有错误信息");
+            prop_assert_eq!(result.failed_operation, Some(position), "应该报告失败变更的下标");
+            prop_assert!(result.operation_rows_affected.is_empty(), "回滚后不应返回每个变更的影响行数");
+
+            // 验证数据未被修改（事务已回滚）
+            let current_data = read_mixed_table(&client, &table_name).await;
+            prop_assert_eq!(current_data, original_data, "所有更改应该已回滚");
+
+            let _ = client.execute(&format!("DROP TABLE {}", table_name), &[]).await;
+
+            Ok(())
+        })?;
+    }
+}
+
+// Feature: database-advanced-features, Property 10: 事务原子性
+// 测试成功的混合变更按顺序提交，并返回每个变更影响的行数
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(100))]
+
+    #[test]
+    fn property_batch_apply_changes_success_in_order(
+        changes in arbitrary_mixed_changes()
+    ) {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let client = match get_test_client().await {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+                    return Ok(());
+                }
+            };
+
+            let table_name = create_mixed_table(&client).await;
+
+            // 在内存中按顺序模拟变更，得到期望的数据和每个变更影响的行数
+            let mut expected_data = read_mixed_table(&client, &table_name).await;
+            let mut expected_affected = Vec::new();
+            for change in &changes {
+                let affected = match change {
+                    RowChange::Insert { row } => {
+                        let id = key_id(row);
+                        let value = row.get("col1").unwrap().as_i64().unwrap() as i32;
+                        expected_data.insert(id, value);
+                        1
+                    }
+                    RowChange::Update { primary_key, changes } => {
+                        let value = changes.get("col1").unwrap().as_i64().unwrap() as i32;
+                        match expected_data.get_mut(&key_id(primary_key)) {
+                            Some(current) => {
+                                *current = value;
+                                1
+                            }
+                            None => 0,
+                        }
+                    }
+                    RowChange::Delete { primary_key } => {
+                        expected_data.remove(&key_id(primary_key)).map_or(0, |_| 1)
+                    }
+                };
+                expected_affected.push(affected);
+            }
+
+            let result = transaction_manager::batch_apply_changes(
+                &client,
+                "public",
+                &table_name,
+                changes.clone()
+            ).await;
+
+            prop_assert!(result.success, "混合变更应该成功: {:?}", result.error);
+            prop_assert_eq!(&result.operation_rows_affected, &expected_affected, "每个变更影响的行数应该正确");
+            prop_assert_eq!(result.rows_affected, expected_affected.iter().sum::<u64>());
+
+            let current_data = read_mixed_table(&client, &table_name).await;
+            prop_assert_eq!(current_data, expected_data, "变更应该按顺序提交");
+
+            let _ = client.execute(&format!("DROP TABLE {}", table_name), &[]).await;
+
+            Ok(())
+        })?;
+    }
+}
@@ -0,0 +1,199 @@
+/**
+ * Integration tests for Activity Monitor
+ *
+ * 这些测试在实际数据库中构造锁等待场景，验证：
+ * - 被阻塞的会话及其阻塞者能够被识别
+ * - 阻塞者持有的锁出现在锁列表中
+ * - 终止阻塞者后被阻塞的查询得以继续
+ * - 未配置复制时的复制状态报告
+ * - 活动会话列表包含正在执行的查询及其耗时，不包含查询会话本身
+ * - 不能终止本工具自己的连接
+ */
+
+use pg_db_tool::models::monitoring::ServerRole;
+use pg_db_tool::services::activity_monitor;
+use std::time::Duration;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+async fn backend_pid(client: &tokio_postgres::Client) -> i32 {
+    client.query_one("SELECT pg_backend_pid()", &[]).await.unwrap().get(0)
+}
+
+#[tokio::test]
+async fn test_get_locks_identifies_blocker() {
+    let monitor = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let blocker = get_test_client().await.unwrap();
+    let waiter = get_test_client().await.unwrap();
+
+    monitor
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_activity_locks;
+             CREATE TABLE test_activity_locks (id INTEGER PRIMARY KEY);",
+        )
+        .await
+        .unwrap();
+
+    let blocker_pid = backend_pid(&blocker).await;
+    let waiter_pid = backend_pid(&waiter).await;
+
+    // 阻塞者持有排他锁但不提交
+    blocker
+        .batch_execute("BEGIN; LOCK TABLE test_activity_locks IN ACCESS EXCLUSIVE MODE;")
+        .await
+        .unwrap();
+
+    // 等待者的查询会一直等待该锁
+    let waiting_query = tokio::spawn(async move {
+        waiter.query("SELECT * FROM test_activity_locks", &[]).await.map(|rows| rows.len())
+    });
+
+    let mut report = None;
+    for _ in 0..50 {
+        let current = activity_monitor::get_locks(&monitor).await.expect("查询锁信息失败");
+        if current.blocking.iter().any(|p| p.blocked_pid == waiter_pid) {
+            report = Some(current);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let report = report.expect("应检测到被阻塞的会话");
+
+    let pair = report
+        .blocking
+        .iter()
+        .find(|p| p.blocked_pid == waiter_pid)
+        .unwrap();
+    assert_eq!(pair.blocking_pid, blocker_pid);
+    assert!(pair.blocked_query.as_deref().unwrap().contains("test_activity_locks"));
+    assert!(pair.blocking_query.as_deref().unwrap().contains("LOCK TABLE"));
+    assert_eq!(pair.blocking_state.as_deref(), Some("idle in transaction"));
+
+    assert!(report.locks.iter().any(|l| {
+        l.pid == blocker_pid
+            && l.granted
+            && l.mode == "AccessExclusiveLock"
+            && l.relation.as_deref() == Some("test_activity_locks")
+    }));
+    assert!(report.locks.iter().any(|l| l.pid == waiter_pid && !l.granted));
+
+    // 终止阻塞者后，等待的查询应继续执行
+    let terminated = activity_monitor::terminate_backend(&monitor, blocker_pid).await.unwrap();
+    assert!(terminated);
+
+    let result = tokio::time::timeout(Duration::from_secs(10), waiting_query)
+        .await
+        .expect("被阻塞的查询应在阻塞者终止后完成")
+        .unwrap();
+    assert_eq!(result.unwrap(), 0);
+
+    let _ = monitor.batch_execute("DROP TABLE test_activity_locks").await;
+}
+
+#[tokio::test]
+async fn test_list_and_terminate_active_sessions() {
+    let monitor = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let sleeper = get_test_client().await.unwrap();
+    let monitor_pid = backend_pid(&monitor).await;
+    let sleeper_pid = backend_pid(&sleeper).await;
+
+    let sleeping_query = tokio::spawn(async move { sleeper.batch_execute("SELECT pg_sleep(30)").await });
+
+    let mut session = None;
+    for _ in 0..50 {
+        let sessions = activity_monitor::list_active_sessions(&monitor).await.expect("查询活动会话失败");
+        assert!(sessions.iter().all(|s| s.pid != monitor_pid), "不应列出查询会话本身");
+        if let Some(found) = sessions
+            .into_iter()
+            .find(|s| s.pid == sleeper_pid && s.state.as_deref() == Some("active"))
+        {
+            session = Some(found);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let session = session.expect("应列出正在执行查询的会话");
+    assert!(session.query.as_deref().unwrap().contains("pg_sleep"));
+    assert!(session.duration_secs.unwrap() >= 0.0);
+    assert!(session.query_start.is_some());
+
+    let own = activity_monitor::terminate_backend(&monitor, monitor_pid).await;
+    assert!(own.is_err());
+
+    assert!(activity_monitor::terminate_backend(&monitor, sleeper_pid).await.unwrap());
+    let result = tokio::time::timeout(Duration::from_secs(10), sleeping_query)
+        .await
+        .expect("被终止的查询应立即结束")
+        .unwrap();
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_replication_status_not_configured() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let in_recovery: bool = client
+        .query_one("SELECT pg_is_in_recovery()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    let standby_count: i64 = client
+        .query_one("SELECT COUNT(*) FROM pg_stat_replication", &[])
+        .await
+        .unwrap()
+        .get(0);
+    if in_recovery || standby_count > 0 {
+        eprintln!("测试服务器已配置复制，跳过测试");
+        return;
+    }
+
+    // 未配置复制时应正常返回而不是报错
+    let status = activity_monitor::get_replication_status(&client)
+        .await
+        .expect("查询复制状态失败");
+    assert_eq!(status.role, ServerRole::Primary);
+    assert!(!status.configured);
+    assert!(status.standbys.is_empty());
+    assert!(status.wal_receiver.is_none());
+    assert!(status.current_lsn.is_some());
+}
@@ -0,0 +1,145 @@
+/**
+ * Integration tests for Bulk Load
+ *
+ * 这些测试验证批量导入时的触发器和约束控制：
+ * - 禁用触发器后导入数据不会触发触发器，重新启用后恢复
+ * - 延迟约束后可以先插入引用行、再插入被引用行
+ */
+
+use pg_db_tool::services::bulk_load;
+use pg_db_tool::services::transaction_manager::{self, BatchOptions};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+fn row(id: i64) -> HashMap<String, serde_json::Value> {
+    HashMap::from([("id".to_string(), json!(id))])
+}
+
+#[tokio::test]
+async fn test_disable_load_and_enable_triggers() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_bulk_load;
+             CREATE TABLE test_bulk_load (id INT PRIMARY KEY);
+             CREATE OR REPLACE FUNCTION test_bulk_load_reject() RETURNS trigger
+                 LANGUAGE plpgsql AS $$ BEGIN RAISE EXCEPTION 'trigger fired'; END $$;
+             CREATE TRIGGER test_bulk_load_reject BEFORE INSERT ON test_bulk_load
+                 FOR EACH ROW EXECUTE FUNCTION test_bulk_load_reject();",
+        )
+        .await
+        .unwrap();
+
+    // 触发器启用时插入失败
+    let result = transaction_manager::batch_insert_rows(&client, "public", "test_bulk_load", vec![row(1)]).await;
+    assert!(!result.success);
+
+    let disabled = bulk_load::disable_triggers(&client, "public", "test_bulk_load")
+        .await
+        .expect("禁用触发器失败");
+    assert_eq!(disabled, vec!["test_bulk_load_reject".to_string()]);
+
+    let rows = (1..=100).map(row).collect();
+    let result = transaction_manager::batch_insert_rows(&client, "public", "test_bulk_load", rows).await;
+    assert!(result.success, "禁用触发器后应能导入: {:?}", result.error);
+    assert_eq!(result.rows_affected, 100);
+
+    bulk_load::enable_triggers(&client, "public", "test_bulk_load")
+        .await
+        .expect("启用触发器失败");
+
+    let enabled: bool = client
+        .query_one(
+            "SELECT tgenabled <> 'D' FROM pg_trigger WHERE tgname = 'test_bulk_load_reject'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert!(enabled);
+
+    let result = transaction_manager::batch_insert_rows(&client, "public", "test_bulk_load", vec![row(101)]).await;
+    assert!(!result.success, "重新启用后触发器应生效");
+
+    assert!(bulk_load::disable_triggers(&client, "public", "test_bulk_load_missing").await.is_err());
+
+    let _ = client
+        .batch_execute("DROP TABLE test_bulk_load; DROP FUNCTION test_bulk_load_reject();")
+        .await;
+}
+
+#[tokio::test]
+async fn test_deferred_constraints_allow_out_of_order_inserts() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_bulk_load_node;
+             CREATE TABLE test_bulk_load_node (
+                 id INT PRIMARY KEY,
+                 parent_id INT REFERENCES test_bulk_load_node (id) DEFERRABLE
+             );",
+        )
+        .await
+        .unwrap();
+
+    let rows = || {
+        vec![
+            HashMap::from([("id".to_string(), json!(2)), ("parent_id".to_string(), json!(1))]),
+            HashMap::from([("id".to_string(), json!(1)), ("parent_id".to_string(), json!(null))]),
+        ]
+    };
+
+    let result = transaction_manager::batch_insert_rows(&client, "public", "test_bulk_load_node", rows()).await;
+    assert!(!result.success, "未延迟时应立即检查外键");
+
+    let options = BatchOptions { defer_constraints: true, ..Default::default() };
+    let result = transaction_manager::batch_insert_rows_with_options(
+        &client,
+        "public",
+        "test_bulk_load_node",
+        rows(),
+        &options,
+    )
+    .await;
+    assert!(result.success, "延迟约束后应能乱序插入: {:?}", result.error);
+
+    let _ = client.batch_execute("DROP TABLE test_bulk_load_node").await;
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "client-cert-tests")]
+
+/**
+ * Integration tests for client certificate (mutual TLS) connections
+ *
+ * 这些测试需要一台要求客户端证书的 PostgreSQL 服务器，默认不编译。运行方式：
+ *
+ * ```text
+ * PG_SSL_ROOT_CERT=/path/root.crt PG_SSL_CERT=/path/client.crt PG_SSL_KEY=/path/client.key \
+ *     cargo test --features client-cert-tests --test test_client_cert
+ * ```
+ *
+ * 验证：
+ * - 配置客户端证书后可以以 verify-full 模式连接
+ * - 不提供客户端证书时连接被服务器拒绝
+ * - 证书文件不存在时返回明确的错误
+ */
+
+use pg_db_tool::services::tls::{self, SslMode, TlsSettings};
+
+fn connection_string() -> String {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    )
+}
+
+fn cert_settings() -> TlsSettings {
+    TlsSettings {
+        ssl_mode: SslMode::VerifyFull,
+        ssl_root_cert: std::env::var("PG_SSL_ROOT_CERT").ok(),
+        ssl_cert: Some(std::env::var("PG_SSL_CERT").expect("需要设置 PG_SSL_CERT")),
+        ssl_key: Some(std::env::var("PG_SSL_KEY").expect("需要设置 PG_SSL_KEY")),
+    }
+}
+
+#[tokio::test]
+async fn test_connect_with_client_certificate() {
+    let client = tls::connect(&connection_string(), &cert_settings())
+        .await
+        .expect("使用客户端证书连接失败");
+
+    let ssl_in_use: bool = client
+        .query_one("SELECT ssl FROM pg_stat_ssl WHERE pid = pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert!(ssl_in_use);
+}
+
+#[tokio::test]
+async fn test_connect_without_client_certificate_is_rejected() {
+    let settings = TlsSettings {
+        ssl_cert: None,
+        ssl_key: None,
+        ..cert_settings()
+    };
+
+    assert!(tls::connect(&connection_string(), &settings).await.is_err());
+}
+
+#[tokio::test]
+async fn test_missing_key_file_reports_path() {
+    let settings = TlsSettings {
+        ssl_key: Some("/nonexistent/client.key".to_string()),
+        ..cert_settings()
+    };
+
+    let error = tls::connect(&connection_string(), &settings).await.err().unwrap().to_string();
+    assert!(error.contains("ssl_key"));
+    assert!(error.contains("/nonexistent/client.key"));
+}
@@ -0,0 +1,633 @@
+/**
+ * Integration tests for Connection Router
+ *
+ * 这些测试验证读写分离的路由行为。读端点与写端点指向同一服务器，
+ * 通过连接的 application_name 区分语句实际在哪个端点上执行。
+ * 同时验证连接被服务器终止后，下一次 SELECT 能透明地重新连接，
+ * 以及连接被移出连接池或连接池被释放后其后台连接任务会结束。
+ * 连接池复用归还的连接，连接数不超过上限，池中已断开或无响应（半开）的连接在取用时被透明地替换。
+ * 归还的连接在复用前重置会话状态，无法重置（如事务未结束）的连接被替换。
+ * 关闭数据库的连接池后，其连接的后台任务结束，返回关闭的连接数。
+ * 服务器不响应时，建立连接和健康检查在连接超时后返回错误。
+ * 执行期间的 RAISE NOTICE 等服务器通知附加到结果中，且只属于产生它们的那次执行。
+ */
+
+use pg_db_tool::models::query::QueryResultType;
+use pg_db_tool::services::connection_router::{
+    self, Endpoint, EndpointConfig, PooledConnection, ReconnectPolicy, RoutingConfig, DEFAULT_CONNECT_TIMEOUT,
+    DEFAULT_POOL_MAX_SIZE, LIVENESS_CHECK_TIMEOUT, READ_APPLICATION_NAME, WRITE_APPLICATION_NAME,
+};
+use pg_db_tool::services::query_executor;
+use pg_db_tool::services::tls::TlsSettings;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+
+fn test_endpoint() -> EndpointConfig {
+    EndpointConfig {
+        host: std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string()),
+        port: std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string()),
+        user: std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string()),
+        password: std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string()),
+        tls: TlsSettings::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_select_routes_to_read_endpoint_and_insert_to_write_endpoint() {
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let routing = RoutingConfig {
+        write: test_endpoint(),
+        read: Some(test_endpoint()),
+        prefer_replica_for_reads: true,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: DEFAULT_POOL_MAX_SIZE,
+    };
+    let mut pools = HashMap::new();
+
+    let write_pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let write = match connection_router::get_connection(&write_pool).await {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    write
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_connection_router;
+             CREATE TABLE test_connection_router (
+                 id INTEGER,
+                 written_by TEXT DEFAULT current_setting('application_name')
+             );",
+        )
+        .await
+        .unwrap();
+
+    // SELECT 应在读端点上执行
+    let select_sql = "SELECT current_setting('application_name') AS app";
+    assert_eq!(routing.route(select_sql), Endpoint::Read);
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, routing.route(select_sql)).unwrap();
+    assert_eq!(pools.len(), 2);
+    let read = connection_router::get_connection(&pool).await.unwrap();
+    let result = query_executor::execute_sql(&read, select_sql).await;
+    let rows = result.rows.expect("SELECT 应返回数据");
+    assert_eq!(rows[0]["app"], serde_json::json!(READ_APPLICATION_NAME));
+
+    // INSERT 应在写端点上执行
+    let insert_sql = "INSERT INTO test_connection_router (id) VALUES (1)";
+    assert_eq!(routing.route(insert_sql), Endpoint::Write);
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, routing.route(insert_sql)).unwrap();
+    assert_eq!(pools.len(), 2);
+    let connection = connection_router::get_connection(&pool).await.unwrap();
+    let result = query_executor::execute_sql(&connection, insert_sql).await;
+    assert_eq!(result.affected_rows, Some(1));
+
+    let written_by: String = write
+        .query_one("SELECT written_by FROM test_connection_router", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(written_by, WRITE_APPLICATION_NAME);
+
+    let _ = write
+        .batch_execute("DROP TABLE test_connection_router")
+        .await;
+}
+
+#[tokio::test]
+async fn test_select_reconnects_after_connection_is_killed() {
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let routing = RoutingConfig {
+        write: test_endpoint(),
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: DEFAULT_POOL_MAX_SIZE,
+    };
+    let mut pools = HashMap::new();
+
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let connection = match connection_router::get_connection(&pool).await {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let old_pid: i32 = connection
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+
+    // 从另一个连接终止该会话
+    let admin = admin_connection(&routing, &database).await;
+    admin
+        .execute("SELECT pg_terminate_backend($1)", &[&old_pid])
+        .await
+        .unwrap();
+
+    // 下一次 SELECT 应透明地重新连接并成功
+    let result = connection_router::execute_with_reconnect(&pool, connection, "SELECT pg_backend_pid() AS pid")
+        .await
+    .expect("重新连接失败");
+    assert_eq!(result.result_type, QueryResultType::Select, "{:?}", result.error);
+
+    let rows = result.rows.unwrap();
+    let new_pid = rows[0]["pid"].as_i64().unwrap();
+    assert_ne!(new_pid, old_pid as i64);
+}
+
+#[tokio::test]
+async fn test_notices_are_returned_with_result() {
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let routing = RoutingConfig {
+        write: test_endpoint(),
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: 1,
+    };
+    let mut pools = HashMap::new();
+
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let connection = match connection_router::get_connection(&pool).await {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let sql = "DO $$ BEGIN RAISE NOTICE 'hello %', 42; RAISE WARNING 'careful'; END $$";
+    let result = connection_router::execute_with_reconnect(&pool, connection, sql)
+        .await
+        .unwrap();
+    assert_eq!(result.result_type, QueryResultType::Ddl, "{:?}", result.error);
+    assert_eq!(result.notices, vec!["NOTICE: hello 42", "WARNING: careful"]);
+
+    // 同一个连接上的下一次执行不应带上之前的通知
+    let connection = connection_router::get_connection(&pool).await.unwrap();
+    let result = connection_router::execute_with_reconnect(&pool, connection, "SELECT 1 AS one")
+        .await
+        .unwrap();
+    assert!(result.notices.is_empty());
+}
+
+/// 等待任务结束，最多等待 5 秒
+async fn wait_finished(task: &tokio::task::AbortHandle) -> bool {
+    for _ in 0..50 {
+        if task.is_finished() {
+            return true;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    task.is_finished()
+}
+
+/// 从独立的连接池中取出一个连接，用于终止或观察其他会话
+async fn admin_connection(routing: &RoutingConfig, database: &str) -> PooledConnection {
+    let mut pools = HashMap::new();
+    let pool = connection_router::connection_pool(&mut pools, routing, database, Endpoint::Write).unwrap();
+    connection_router::get_connection(&pool).await.unwrap()
+}
+
+/// 等待服务器端会话退出，最多等待 5 秒
+async fn wait_session_gone(admin: &PooledConnection, pid: i32) -> bool {
+    for _ in 0..50 {
+        let session_alive: bool = admin
+            .query_one("SELECT EXISTS (SELECT 1 FROM pg_stat_activity WHERE pid = $1)", &[&pid])
+            .await
+            .unwrap()
+            .get(0);
+        if !session_alive {
+            return true;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn test_evicted_connection_or_dropped_pool_task_terminates() {
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let routing = RoutingConfig {
+        write: test_endpoint(),
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: DEFAULT_POOL_MAX_SIZE,
+    };
+    let mut pools = HashMap::new();
+
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let connection = match connection_router::get_connection(&pool).await {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let pid: i32 = connection
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    let task = connection.task();
+    assert!(!task.is_finished());
+
+    // 移出连接池后连接任务结束，服务器端的会话随之退出
+    drop(PooledConnection::take(connection));
+    assert!(wait_finished(&task).await, "移除连接后连接任务仍在运行");
+    assert_eq!(pool.status().size, 0);
+
+    let admin = admin_connection(&routing, &database).await;
+    assert!(wait_session_gone(&admin, pid).await, "移除连接后服务器端会话仍然存在");
+
+    // 连接池被释放时，池中空闲连接的任务同样结束
+    let connection = connection_router::get_connection(&pool).await.unwrap();
+    let idle_task = connection.task();
+    drop(connection);
+    assert!(!idle_task.is_finished());
+    drop(pools);
+    drop(pool);
+    assert!(wait_finished(&idle_task).await, "释放连接池后空闲连接的任务仍在运行");
+}
+
+#[tokio::test]
+async fn test_pool_reuses_connections_and_limits_size() {
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let routing = RoutingConfig {
+        write: test_endpoint(),
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: 2,
+    };
+    let mut pools = HashMap::new();
+
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let first = match connection_router::get_connection(&pool).await {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let second = connection_router::get_connection(&pool).await.unwrap();
+
+    // 同时取出的连接是不同的会话，可以并发使用
+    let first_pid: i32 = first.query_one("SELECT pg_backend_pid()", &[]).await.unwrap().get(0);
+    let second_pid: i32 = second.query_one("SELECT pg_backend_pid()", &[]).await.unwrap().get(0);
+    assert_ne!(first_pid, second_pid);
+
+    // 连接数达到上限时等待其他调用方归还连接
+    let waiting = tokio::time::timeout(Duration::from_millis(300), connection_router::get_connection(&pool)).await;
+    assert!(waiting.is_err(), "连接池已满时不应建立新连接");
+    assert_eq!(pool.status().size, 2);
+
+    // 归还的连接被复用
+    drop(first);
+    let reused = connection_router::get_connection(&pool).await.unwrap();
+    let reused_pid: i32 = reused.query_one("SELECT pg_backend_pid()", &[]).await.unwrap().get(0);
+    assert_eq!(reused_pid, first_pid);
+}
+
+#[tokio::test]
+async fn test_returned_connection_session_state_is_reset() {
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let routing = RoutingConfig {
+        write: test_endpoint(),
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: 1,
+    };
+    let mut pools = HashMap::new();
+
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let first = match connection_router::get_connection(&pool).await {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let first_pid: i32 = first.query_one("SELECT pg_backend_pid()", &[]).await.unwrap().get(0);
+    first
+        .batch_execute(
+            "SELECT set_config('pg_db_tool_test.marker', 'dirty', false);
+             SET search_path TO pg_catalog;
+             CREATE TEMP TABLE pool_reset_test (id int);
+             SELECT pg_advisory_lock(424242);",
+        )
+        .await
+        .unwrap();
+    drop(first);
+
+    // 同一个会话被复用，但上一个使用者留下的参数、临时表和咨询锁都已清除
+    let reused = connection_router::get_connection(&pool).await.unwrap();
+    let reused_pid: i32 = reused.query_one("SELECT pg_backend_pid()", &[]).await.unwrap().get(0);
+    assert_eq!(reused_pid, first_pid);
+    let marker: Option<String> = reused
+        .query_one("SELECT nullif(current_setting('pg_db_tool_test.marker', true), '')", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(marker, None);
+    let search_path: String = reused.query_one("SHOW search_path", &[]).await.unwrap().get(0);
+    assert_ne!(search_path, "pg_catalog");
+    let temp_table: Option<String> = reused
+        .query_one("SELECT to_regclass('pg_temp.pool_reset_test')::text", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(temp_table, None);
+    let advisory_locks: i64 = reused
+        .query_one(
+            "SELECT count(*) FROM pg_locks WHERE locktype = 'advisory' AND pid = pg_backend_pid()",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(advisory_locks, 0);
+
+    // 带着未结束事务归还的连接无法重置，被丢弃并换成新连接
+    reused.batch_execute("BEGIN").await.unwrap();
+    drop(reused);
+    let replaced = connection_router::get_connection(&pool).await.unwrap();
+    let replaced_pid: i32 = replaced.query_one("SELECT pg_backend_pid()", &[]).await.unwrap().get(0);
+    assert_ne!(replaced_pid, first_pid);
+}
+
+#[tokio::test]
+async fn test_pool_replaces_connection_killed_while_idle() {
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let routing = RoutingConfig {
+        write: test_endpoint(),
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: 1,
+    };
+    let mut pools = HashMap::new();
+
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let connection = match connection_router::get_connection(&pool).await {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let old_pid: i32 = connection
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    let old_task = connection.task();
+    drop(connection);
+
+    // 连接空闲时被服务器终止（如服务器重启）
+    let admin = admin_connection(&routing, &database).await;
+    admin
+        .execute("SELECT pg_terminate_backend($1)", &[&old_pid])
+        .await
+        .unwrap();
+    assert!(wait_session_gone(&admin, old_pid).await);
+    assert!(wait_finished(&old_task).await, "会话被终止后连接任务仍在运行");
+
+    // 再次取用时已断开的连接被丢弃，换成新连接
+    let connection = connection_router::get_connection(&pool).await.unwrap();
+    let new_pid: i32 = connection
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_ne!(new_pid, old_pid);
+    assert_eq!(pool.status().size, 1);
+}
+
+/// 在两个套接字之间单向转发数据，`frozen` 被置位后停止转发但不关闭套接字
+async fn forward(mut from: OwnedReadHalf, mut to: OwnedWriteHalf, frozen: Arc<AtomicBool>) {
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = match from.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => n,
+        };
+        if frozen.load(Ordering::SeqCst) {
+            std::future::pending::<()>().await;
+        }
+        if to.write_all(&buf[..n]).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// 转发到数据库服务器的 TCP 代理，返回监听端口和每个已建立连接的“冻结”开关
+///
+/// 冻结的连接不再有任何响应，但套接字保持打开，模拟网络中断造成的半开连接；之后新建的连接照常转发。
+async fn start_freezable_proxy(target: String) -> (u16, Arc<std::sync::Mutex<Vec<Arc<AtomicBool>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let connections = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let registry = connections.clone();
+    tokio::spawn(async move {
+        while let Ok((client, _)) = listener.accept().await {
+            let Ok(server) = TcpStream::connect(&target).await else {
+                continue;
+            };
+            let frozen = Arc::new(AtomicBool::new(false));
+            registry.lock().unwrap().push(frozen.clone());
+            let (client_read, client_write) = client.into_split();
+            let (server_read, server_write) = server.into_split();
+            tokio::spawn(forward(client_read, server_write, frozen.clone()));
+            tokio::spawn(forward(server_read, client_write, frozen));
+        }
+    });
+    (port, connections)
+}
+
+#[tokio::test]
+async fn test_pool_replaces_unresponsive_idle_connection() {
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let server = test_endpoint();
+    let (port, proxied) = start_freezable_proxy(format!("{}:{}", server.host, server.port)).await;
+
+    let mut write = test_endpoint();
+    write.host = "127.0.0.1".to_string();
+    write.port = port.to_string();
+    let routing = RoutingConfig {
+        write,
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: 1,
+    };
+    let mut pools = HashMap::new();
+
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let connection = match connection_router::get_connection(&pool).await {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let old_pid: i32 = connection
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    drop(connection);
+
+    // 模拟网络中断：空闲连接不再有响应，但客户端看不到连接关闭
+    for frozen in proxied.lock().unwrap().iter() {
+        frozen.store(true, Ordering::SeqCst);
+    }
+
+    // 取用时连接检查超时，该连接被丢弃并换成新连接
+    let started = Instant::now();
+    let connection = connection_router::get_connection(&pool).await.unwrap();
+    assert!(started.elapsed() < LIVENESS_CHECK_TIMEOUT + Duration::from_secs(5));
+    let new_pid: i32 = connection
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_ne!(new_pid, old_pid);
+    assert_eq!(pool.status().size, 1);
+}
+
+#[tokio::test]
+async fn test_close_database_pools_closes_connections() {
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let routing = RoutingConfig {
+        write: test_endpoint(),
+        read: Some(test_endpoint()),
+        prefer_replica_for_reads: true,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: DEFAULT_POOL_MAX_SIZE,
+    };
+    let mut pools = HashMap::new();
+
+    let write_pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let write = match connection_router::get_connection(&write_pool).await {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let read_pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Read).unwrap();
+    let read = connection_router::get_connection(&read_pool).await.unwrap();
+    let other_pool = connection_router::connection_pool(&mut pools, &routing, "postgres", Endpoint::Write).unwrap();
+    let other = connection_router::get_connection(&other_pool).await.unwrap();
+
+    let write_task = write.task();
+    let read_task = read.task();
+    let other_task = other.task();
+    drop(write);
+    drop(read);
+    drop(other);
+
+    // 主库和只读副本上的连接都被关闭，其他数据库的连接不受影响
+    assert_eq!(connection_router::close_database_pools(&mut pools, &database), 2);
+    assert_eq!(pools.len(), 1);
+    assert!(wait_finished(&write_task).await, "关闭后主库连接任务仍在运行");
+    assert!(wait_finished(&read_task).await, "关闭后副本连接任务仍在运行");
+    assert!(!other_task.is_finished());
+    assert!(connection_router::get_connection(&write_pool).await.is_err());
+
+    // 再次访问时创建新的连接池
+    let pool = connection_router::connection_pool(&mut pools, &routing, &database, Endpoint::Write).unwrap();
+    let _ = connection_router::get_connection(&pool).await.unwrap();
+
+    assert_eq!(connection_router::close_all_pools(&mut pools), 2);
+    assert!(pools.is_empty());
+    assert!(wait_finished(&other_task).await, "关闭所有连接后连接任务仍在运行");
+}
+
+#[tokio::test]
+async fn test_check_health() {
+    let routing = RoutingConfig {
+        write: test_endpoint(),
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: DEFAULT_POOL_MAX_SIZE,
+    };
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let health = match connection_router::check_health(&routing, &database).await {
+        Ok(health) => health,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    assert!(health.latency_ms > 0.0);
+    assert!(health.server_version.chars().next().is_some_and(|c| c.is_ascii_digit()));
+
+    // 数据库不存在时返回服务器的错误
+    let error = connection_router::check_health(&routing, "test_no_such_database").await.unwrap_err();
+    assert!(error.contains("test_no_such_database"), "unexpected error: {}", error);
+}
+
+#[tokio::test]
+async fn test_connect_times_out_when_server_does_not_respond() {
+    // 接受 TCP 连接但从不回应启动消息的“服务器”
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let mut sockets = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            sockets.push(socket);
+        }
+    });
+
+    let mut write = test_endpoint();
+    write.host = "127.0.0.1".to_string();
+    write.port = port.to_string();
+    let routing = RoutingConfig {
+        write,
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy::default(),
+        connect_timeout: Duration::from_millis(300),
+        pool_max_size: DEFAULT_POOL_MAX_SIZE,
+    };
+
+    // 超时不重试，即使重连策略允许重试
+    let started = Instant::now();
+    let mut pools = HashMap::new();
+    let pool = connection_router::connection_pool(&mut pools, &routing, "postgres", Endpoint::Write).unwrap();
+    let error = connection_router::get_connection(&pool)
+        .await
+        .err()
+        .expect("连接超时应返回错误");
+    assert!(error.contains("连接超时"), "unexpected error: {}", error);
+    assert!(started.elapsed() < Duration::from_secs(3));
+    assert_eq!(pool.status().size, 0);
+
+    let error = connection_router::check_health(&routing, "postgres").await.unwrap_err();
+    assert_eq!(error, "连接超时：0.3 秒内未能连接到数据库服务器");
+}
@@ -0,0 +1,246 @@
+/**
+ * Integration tests for CSV Import
+ *
+ * 这些测试验证 CSV 导入在实际数据库中的行为，包括：
+ * - Copy 模式下错误行导致整体回滚并报告行号
+ * - Staged 模式下导入有效行并收集被拒绝的行
+ * - 以流的方式导入 CSV 文件，出错时整体回滚
+ */
+
+use pg_db_tool::models::data::CsvImportMode;
+use pg_db_tool::services::csv_import;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+/// 第 2 个数据行的 age 不是合法整数
+const CSV_WITH_INVALID_ROW: &str = "id,name,age\n1,Alice,30\n2,Bob,abc\n3,\"Smith, Carol\",41\n";
+
+async fn create_people_table(client: &tokio_postgres::Client, table: &str) {
+    let _ = client.execute(&format!("DROP TABLE IF EXISTS {}", table), &[]).await;
+    client
+        .execute(
+            &format!("CREATE TABLE {} (id INTEGER PRIMARY KEY, name TEXT NOT NULL, age INTEGER)", table),
+            &[],
+        )
+        .await
+        .expect("创建测试表失败");
+}
+
+#[tokio::test]
+async fn test_copy_mode_reports_invalid_row_and_rolls_back() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    create_people_table(&client, "test_csv_import_copy").await;
+
+    let result = csv_import::import_csv(
+        &client,
+        "public",
+        "test_csv_import_copy",
+        CSV_WITH_INVALID_ROW.as_bytes(),
+        true,
+        CsvImportMode::Copy,
+    )
+    .await
+    .expect("导入应返回结构化结果");
+
+    assert!(result.rolled_back);
+    assert_eq!(result.rows_loaded, 0);
+    assert_eq!(result.rejected_rows.len(), 1);
+    assert_eq!(result.rejected_rows[0].row_number, 2);
+    assert_eq!(result.rejected_rows[0].column.as_deref(), Some("age"));
+
+    // 整个导入应已回滚
+    let row = client
+        .query_one("SELECT COUNT(*) FROM test_csv_import_copy", &[])
+        .await
+        .unwrap();
+    let count: i64 = row.get(0);
+    assert_eq!(count, 0);
+
+    let _ = client.execute("DROP TABLE test_csv_import_copy", &[]).await;
+}
+
+#[tokio::test]
+async fn test_staged_mode_loads_valid_rows_and_rejects_invalid_row() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    create_people_table(&client, "test_csv_import_staged").await;
+
+    let result = csv_import::import_csv(
+        &client,
+        "public",
+        "test_csv_import_staged",
+        CSV_WITH_INVALID_ROW.as_bytes(),
+        true,
+        CsvImportMode::Staged,
+    )
+    .await
+    .expect("Staged 导入应成功");
+
+    assert!(!result.rolled_back);
+    assert_eq!(result.rows_loaded, 2);
+    assert_eq!(result.rejected_rows.len(), 1);
+
+    let rejected = &result.rejected_rows[0];
+    assert_eq!(rejected.row_number, 2);
+    assert_eq!(rejected.column.as_deref(), Some("age"));
+    assert_eq!(
+        rejected.values,
+        vec![Some("2".to_string()), Some("Bob".to_string()), Some("abc".to_string())]
+    );
+
+    let rows = client
+        .query("SELECT id, name FROM test_csv_import_staged ORDER BY id", &[])
+        .await
+        .unwrap();
+    let ids: Vec<i32> = rows.iter().map(|r| r.get(0)).collect();
+    assert_eq!(ids, vec![1, 3]);
+    let name: String = rows[1].get(1);
+    assert_eq!(name, "Smith, Carol");
+
+    let _ = client.execute("DROP TABLE test_csv_import_staged", &[]).await;
+}
+
+#[tokio::test]
+async fn test_column_count_mismatch_is_rejected_before_import() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    create_people_table(&client, "test_csv_import_columns").await;
+
+    // 没有表头时按表的全部列导入，多出的一列直接报错
+    let result = csv_import::import_csv(
+        &client,
+        "public",
+        "test_csv_import_columns",
+        b"1,Alice,30,extra\n",
+        false,
+        CsvImportMode::Copy,
+    )
+    .await;
+    let error = result.expect_err("列数不一致时应返回错误");
+    assert!(error.contains("有 4 列"), "{}", error);
+
+    let result = csv_import::import_csv(
+        &client,
+        "public",
+        "test_csv_import_columns",
+        b"1,Alice\n",
+        false,
+        CsvImportMode::Staged,
+    )
+    .await;
+    assert!(result.is_err());
+
+    let result = csv_import::import_csv(&client, "public", "no_such_table_xyz", b"1\n", false, CsvImportMode::Copy).await;
+    assert!(result.is_err());
+
+    let _ = client.execute("DROP TABLE test_csv_import_columns", &[]).await;
+}
+
+#[tokio::test]
+async fn test_import_csv_file_streams_and_rolls_back() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    create_people_table(&client, "test_csv_import_file").await;
+    let dir = tempfile::tempdir().unwrap();
+
+    // 文件大于一次发送的数据块，表头的列顺序与表不同
+    let path = dir.path().join("people.csv");
+    let mut csv = String::from("age,id,name\n");
+    for i in 1..=5000 {
+        csv.push_str(&format!("{},{},\"Person, {}\"\n", i % 90, i, i));
+    }
+    std::fs::write(&path, csv).unwrap();
+
+    let rows = csv_import::import_csv_file(&client, "public", "test_csv_import_file", &path, true)
+        .await
+        .expect("导入文件失败");
+    assert_eq!(rows, 5000);
+    let row = client
+        .query_one("SELECT name, age FROM test_csv_import_file WHERE id = 4321", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, String>(0), "Person, 4321");
+    assert_eq!(row.get::<_, i32>(1), 4321 % 90);
+
+    // 出错时整个导入回滚，错误中包含出错的行号
+    let path = dir.path().join("invalid.csv");
+    std::fs::write(&path, "id,name,age\n9001,Alice,30\n9002,Bob,abc\n").unwrap();
+    let error = csv_import::import_csv_file(&client, "public", "test_csv_import_file", &path, true)
+        .await
+        .expect_err("错误行应使导入失败");
+    assert!(error.contains("line 3"), "{}", error);
+    let count: i64 = client
+        .query_one("SELECT count(*) FROM test_csv_import_file", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 5000);
+
+    // 列数不一致时不开始导入，后面的行列数不一致时报告该行的行号
+    let path = dir.path().join("extra.csv");
+    std::fs::write(&path, "1,Alice,30,extra\n").unwrap();
+    let error = csv_import::import_csv_file(&client, "public", "test_csv_import_file", &path, false)
+        .await
+        .expect_err("列数不一致时应返回错误");
+    assert!(error.contains("有 4 列"), "{}", error);
+
+    let path = dir.path().join("ragged.csv");
+    std::fs::write(&path, "id,name,age\n9001,Alice,30\n9002,\"Bob\nSmith\",31\n9003,Carol\n").unwrap();
+    let error = csv_import::import_csv_file(&client, "public", "test_csv_import_file", &path, true)
+        .await
+        .expect_err("列数不一致时应返回错误");
+    assert!(error.contains("第 5 行有 2 列"), "{}", error);
+
+    assert!(csv_import::import_csv_file(&client, "public", "test_csv_import_file", &dir.path().join("missing.csv"), true)
+        .await
+        .is_err());
+
+    let _ = client.execute("DROP TABLE test_csv_import_file", &[]).await;
+}
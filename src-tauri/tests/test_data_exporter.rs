@@ -0,0 +1,228 @@
+/**
+ * Integration tests for Data Exporter
+ *
+ * 这些测试验证生成的 INSERT 脚本可以重新执行并还原出相同的数据，包括：
+ * - 引号、反斜杠、NULL 等特殊值
+ * - jsonb、timestamptz、numeric、数组等需要类型转换的列
+ * - WHERE 过滤与 LIMIT
+ */
+
+use pg_db_tool::services::data_exporter;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+async fn create_sample_table(client: &tokio_postgres::Client, table: &str) {
+    client
+        .batch_execute(&format!(
+            "DROP TABLE IF EXISTS {table};
+             CREATE TABLE {table} (
+                 id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                 name TEXT,
+                 path VARCHAR(100),
+                 active BOOLEAN,
+                 price NUMERIC(10,2),
+                 ratio DOUBLE PRECISION,
+                 created_at TIMESTAMPTZ,
+                 tags TEXT[],
+                 meta JSONB,
+                 name_upper TEXT GENERATED ALWAYS AS (upper(name)) STORED
+             );
+             INSERT INTO {table} (name, path, active, price, ratio, created_at, tags, meta) VALUES
+                 ('O''Brien', 'C:\\temp\\new', true, 12.50, 'NaN', '2024-01-31 10:00:00+08', '{{a,\"b c\"}}', '{{\"k\": \"it''s\"}}'),
+                 (NULL, NULL, false, -3.00, 1.5e-10, NULL, NULL, NULL),
+                 ('plain', 'x', NULL, NULL, NULL, '2020-02-29 00:00:00+00', '{{}}', '[1, 2]');",
+            table = table
+        ))
+        .await
+        .expect("创建测试表失败");
+}
+
+async fn snapshot(client: &tokio_postgres::Client, table: &str) -> Vec<String> {
+    client
+        .query(&format!("SELECT t::text FROM {} t ORDER BY id", table), &[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.get(0))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_insert_script_round_trip() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let table = "test_data_exporter_round_trip";
+    create_sample_table(&client, table).await;
+    let before = snapshot(&client, table).await;
+
+    let script = data_exporter::generate_insert_script(&client, "public", table, None, None)
+        .await
+        .expect("生成 INSERT 脚本失败");
+    assert_eq!(script.matches("INSERT INTO").count(), 3);
+    assert!(!script.contains("name_upper"));
+
+    client
+        .batch_execute(&format!("TRUNCATE {}", table))
+        .await
+        .unwrap();
+    client.batch_execute(&script).await.expect("执行 INSERT 脚本失败");
+
+    let after = snapshot(&client, table).await;
+    assert_eq!(before, after);
+
+    let _ = client.batch_execute(&format!("DROP TABLE {}", table)).await;
+}
+
+#[tokio::test]
+async fn test_insert_script_with_where_and_limit() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let table = "test_data_exporter_filter";
+    create_sample_table(&client, table).await;
+
+    let script = data_exporter::generate_insert_script(&client, "public", table, Some("price IS NOT NULL"), Some(1))
+        .await
+        .expect("生成 INSERT 脚本失败");
+    assert_eq!(script.matches("INSERT INTO").count(), 1);
+
+    let result = data_exporter::generate_insert_script(&client, "public", "no_such_table_xyz", None, None).await;
+    assert!(result.is_err());
+
+    let _ = client.batch_execute(&format!("DROP TABLE {}", table)).await;
+}
+
+#[tokio::test]
+async fn test_export_table_csv() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let table = "test_data_exporter_csv";
+    client
+        .batch_execute(&format!(
+            "DROP TABLE IF EXISTS {table};
+             CREATE TABLE {table} (id INTEGER, note TEXT);
+             INSERT INTO {table} VALUES (1, 'a, b'), (2, 'say \"hi\"'), (3, E'line1\\nline2'), (4, NULL);",
+            table = table
+        ))
+        .await
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("export.csv");
+    let result = data_exporter::export_table_csv(&client, "public", table, &path)
+        .await
+        .expect("导出 CSV 失败");
+
+    // 引号内的换行不算作新行
+    assert_eq!(result.rows, 4);
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(result.bytes, content.len() as u64);
+    assert_eq!(
+        content,
+        "id,note\n1,\"a, b\"\n2,\"say \"\"hi\"\"\"\n3,\"line1\nline2\"\n4,\n"
+    );
+
+    // 表不存在时返回错误，并且不留下部分文件
+    let missing = dir.path().join("missing.csv");
+    let result = data_exporter::export_table_csv(&client, "public", "no_such_table_xyz", &missing).await;
+    assert!(result.is_err());
+    assert!(!missing.exists());
+
+    let _ = client.batch_execute(&format!("DROP TABLE {}", table)).await;
+}
+
+#[tokio::test]
+async fn test_export_table_json() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let table = "test_data_exporter_json";
+    client
+        .batch_execute(&format!(
+            "DROP TABLE IF EXISTS {table};
+             CREATE TABLE {table} (id INTEGER, name TEXT, active BOOLEAN, created_at TIMESTAMP, updated_at TIMESTAMPTZ, born DATE, meta JSONB, price NUMERIC(10, 2));
+             INSERT INTO {table} VALUES
+                 (1, 'Alice', true, '2024-01-02 03:04:05.5', '2024-01-02 03:04:05+00', '1990-05-06', '{{\"tags\": [\"a\"]}}', 12.5),
+                 (2, NULL, NULL, NULL, NULL, NULL, NULL, NULL);",
+            table = table
+        ))
+        .await
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("export.json");
+    let result = data_exporter::export_table_json(&client, "public", table, &path, true)
+        .await
+        .expect("导出 JSON 失败");
+    assert_eq!(result.rows, 2);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(result.bytes, content.len() as u64);
+    assert!(content.contains('\n'), "pretty 输出应包含换行");
+    // 键按列顺序排列
+    assert!(content.find("\"id\"").unwrap() < content.find("\"name\"").unwrap());
+
+    let rows: Vec<serde_json::Value> = serde_json::from_str(&content).unwrap();
+    assert_eq!(rows[0]["id"], 1);
+    assert_eq!(rows[0]["name"], "Alice");
+    assert_eq!(rows[0]["active"], true);
+    assert_eq!(rows[0]["created_at"], "2024-01-02T03:04:05.500");
+    assert_eq!(rows[0]["updated_at"], "2024-01-02T03:04:05+00:00");
+    assert_eq!(rows[0]["born"], "1990-05-06");
+    assert_eq!(rows[0]["meta"]["tags"][0], "a");
+    assert_eq!(rows[0]["price"], "12.50");
+    for column in ["name", "active", "created_at", "updated_at", "born", "meta", "price"] {
+        assert!(rows[1][column].is_null(), "{} 应为 null", column);
+    }
+
+    let compact = dir.path().join("compact.json");
+    let result = data_exporter::export_table_json(&client, "public", table, &compact, false).await.unwrap();
+    assert!(!std::fs::read_to_string(&compact).unwrap().contains('\n'));
+    assert_eq!(result.rows, 2);
+
+    let _ = client.batch_execute(&format!("DROP TABLE {}", table)).await;
+}
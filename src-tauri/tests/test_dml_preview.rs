@@ -0,0 +1,141 @@
+/**
+ * Integration tests for DML Preview
+ *
+ * 这些测试验证 UPDATE/DELETE 预览：
+ * - 返回将受影响的行数和行数据
+ * - UPDATE 返回被修改列的当前值
+ * - 预览不会修改任何数据
+ * - 没有 WHERE 子句时给出警告
+ */
+
+use pg_db_tool::services::dml_preview;
+use serde_json::json;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+async fn setup_table(client: &tokio_postgres::Client, table: &str) {
+    client
+        .batch_execute(&format!(
+            "DROP TABLE IF EXISTS {table};
+             CREATE TABLE {table} (id INTEGER PRIMARY KEY, status TEXT, amount INTEGER);
+             INSERT INTO {table}
+             SELECT g, CASE WHEN g % 3 = 0 THEN 'cancelled' ELSE 'open' END, g * 10
+             FROM generate_series(1, 30) g;"
+        ))
+        .await
+        .unwrap();
+}
+
+async fn row_count(client: &tokio_postgres::Client, table: &str) -> i64 {
+    client
+        .query_one(format!("SELECT count(*) FROM {}", table).as_str(), &[])
+        .await
+        .unwrap()
+        .get(0)
+}
+
+#[tokio::test]
+async fn test_delete_preview() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    setup_table(&client, "test_dml_preview_delete").await;
+
+    let preview = dml_preview::preview_affected_rows(
+        &client,
+        "DELETE FROM test_dml_preview_delete WHERE status = 'cancelled';",
+        5,
+    )
+    .await
+    .expect("预览 DELETE 失败");
+
+    assert_eq!(preview.affected_count, 10);
+    assert_eq!(preview.rows.len(), 5);
+    assert!(preview.truncated);
+    assert!(!preview.full_table);
+    assert!(preview.warning.is_none());
+    assert!(preview.rows.iter().all(|row| row["status"] == json!("cancelled")));
+    assert!(preview.columns.iter().any(|c| c.name == "amount"));
+
+    // 预览不应删除任何数据
+    assert_eq!(row_count(&client, "test_dml_preview_delete").await, 30);
+
+    // 没有 WHERE 子句时应警告全表操作
+    let full = dml_preview::preview_affected_rows(&client, "DELETE FROM test_dml_preview_delete", 100)
+        .await
+        .unwrap();
+    assert_eq!(full.affected_count, 30);
+    assert!(full.full_table);
+    assert!(full.warning.is_some());
+
+    let _ = client.batch_execute("DROP TABLE test_dml_preview_delete").await;
+}
+
+#[tokio::test]
+async fn test_update_preview() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    setup_table(&client, "test_dml_preview_update").await;
+
+    let preview = dml_preview::preview_affected_rows(
+        &client,
+        "UPDATE test_dml_preview_update t SET amount = amount + 1 WHERE t.id <= 3",
+        100,
+    )
+    .await
+    .expect("预览 UPDATE 失败");
+
+    assert_eq!(preview.affected_count, 3);
+    assert!(!preview.truncated);
+    assert_eq!(preview.columns.len(), 1);
+    assert_eq!(preview.columns[0].name, "amount");
+
+    let mut amounts: Vec<i64> = preview
+        .rows
+        .iter()
+        .map(|row| row["amount"].as_i64().unwrap())
+        .collect();
+    amounts.sort();
+    assert_eq!(amounts, vec![10, 20, 30]);
+
+    // 预览不应修改任何数据
+    let total: i64 = client
+        .query_one("SELECT sum(amount)::bigint FROM test_dml_preview_update", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(total, (1..=30).map(|g| g * 10).sum::<i64>());
+
+    let _ = client.batch_execute("DROP TABLE test_dml_preview_update").await;
+}
@@ -0,0 +1,141 @@
+/**
+ * Integration tests for Editor Transactions
+ *
+ * 这些测试验证 SQL 编辑器中的显式事务：
+ * - 事务中的修改在提交前对其他连接不可见，提交后可见
+ * - 回滚撤销事务中的全部修改
+ * - 事务中拒绝 COMMIT、ROLLBACK 等事务控制语句，但允许保存点
+ * - 闲置的事务被注销并自动回滚
+ */
+
+use pg_db_tool::models::query::QueryResultType;
+use pg_db_tool::services::connection_router::PooledClient;
+use pg_db_tool::services::editor_transaction::{self, EditorTransaction, TransactionRegistry};
+use pg_db_tool::services::tls::{self, TlsSettings};
+use std::time::Duration;
+
+fn connection_string() -> String {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    )
+}
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<PooledClient, tls::ConnectError> {
+    let (client, task) = tls::connect_with_task(&connection_string(), &TlsSettings::default()).await?;
+    Ok(PooledClient::new(client, task))
+}
+
+async fn count_rows(client: &PooledClient, table: &str) -> i64 {
+    client
+        .query_one(&format!("SELECT count(*) FROM {}", table), &[])
+        .await
+        .unwrap()
+        .get(0)
+}
+
+#[tokio::test]
+async fn test_commit_and_rollback() {
+    let observer = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    observer
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_editor_transaction;
+             CREATE TABLE test_editor_transaction (id INTEGER PRIMARY KEY);",
+        )
+        .await
+        .unwrap();
+
+    // 提交前其他连接看不到修改
+    let mut transaction = EditorTransaction::begin("test", get_test_client().await.unwrap())
+        .await
+        .expect("开启事务失败");
+    let result = transaction
+        .execute("INSERT INTO test_editor_transaction VALUES (1); INSERT INTO test_editor_transaction VALUES (2)")
+        .await;
+    assert_ne!(result.result_type, QueryResultType::Error, "{:?}", result.error);
+    let result = transaction.execute("SELECT * FROM test_editor_transaction").await;
+    assert_eq!(result.rows.unwrap().len(), 2);
+    assert_eq!(count_rows(&observer, "test_editor_transaction").await, 0);
+
+    transaction.commit().await.expect("提交事务失败");
+    assert_eq!(count_rows(&observer, "test_editor_transaction").await, 2);
+
+    // 回滚撤销全部修改，保存点可以使用
+    let mut transaction = EditorTransaction::begin("test", get_test_client().await.unwrap())
+        .await
+        .unwrap();
+    let result = transaction
+        .execute(
+            "DELETE FROM test_editor_transaction;
+             SAVEPOINT before_insert;
+             INSERT INTO test_editor_transaction VALUES (3);
+             ROLLBACK TO SAVEPOINT before_insert",
+        )
+        .await;
+    assert_ne!(result.result_type, QueryResultType::Error, "{:?}", result.error);
+
+    let result = transaction.execute("COMMIT").await;
+    assert_eq!(result.result_type, QueryResultType::Error);
+
+    transaction.rollback().await.expect("回滚事务失败");
+    assert_eq!(count_rows(&observer, "test_editor_transaction").await, 2);
+
+    let _ = observer.batch_execute("DROP TABLE test_editor_transaction").await;
+}
+
+#[tokio::test]
+async fn test_idle_transaction_is_rolled_back() {
+    let observer = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    observer
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_editor_transaction_idle;
+             CREATE TABLE test_editor_transaction_idle (id INTEGER PRIMARY KEY);",
+        )
+        .await
+        .unwrap();
+
+    let mut transaction = EditorTransaction::begin("test", get_test_client().await.unwrap())
+        .await
+        .unwrap();
+    transaction
+        .execute("LOCK TABLE test_editor_transaction_idle IN ACCESS EXCLUSIVE MODE")
+        .await;
+
+    let mut registry = TransactionRegistry::new();
+    let tx_id = registry.insert(transaction);
+    assert!(registry.get(&tx_id).is_some());
+    assert!(registry.take_idle(Duration::from_secs(3600)).is_empty());
+
+    let idle = registry.take_idle(Duration::ZERO);
+    assert_eq!(idle.len(), 1);
+    assert!(registry.get(&tx_id).is_none());
+    assert_eq!(editor_transaction::rollback_abandoned(idle).await, 1);
+
+    // 回滚后锁已释放
+    tokio::time::timeout(Duration::from_secs(5), count_rows(&observer, "test_editor_transaction_idle"))
+        .await
+        .expect("事务回滚后应释放锁");
+
+    let _ = observer.batch_execute("DROP TABLE test_editor_transaction_idle").await;
+}
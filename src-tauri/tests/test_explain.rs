@@ -0,0 +1,90 @@
+/**
+ * Integration tests for Explain Service
+ *
+ * 这些测试验证 explain_query 的行为，包括：
+ * - 只查看计划时不执行语句，不返回执行时间
+ * - ANALYZE 时返回规划和执行时间
+ * - 无法 EXPLAIN 的语句返回错误
+ */
+
+use pg_db_tool::services::explain;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_explain_query() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let sql = "SELECT g FROM generate_series(1, 100) g WHERE g > 10";
+
+    let plan = explain::explain_query(&client, sql, false).await.unwrap();
+    assert_eq!(plan.plan["Node Type"], "Function Scan");
+    assert!(plan.total_cost > 0.0);
+    assert!(plan.estimated_rows > 0.0);
+    assert!(plan.execution_time_ms.is_none());
+    assert!(plan.warning.is_none());
+
+    let analyzed = explain::explain_query(&client, sql, true).await.unwrap();
+    assert_eq!(analyzed.plan["Actual Rows"].as_f64(), Some(90.0));
+    assert!(analyzed.planning_time_ms.is_some());
+    assert!(analyzed.execution_time_ms.is_some());
+}
+
+#[tokio::test]
+async fn test_explain_query_rejects_unsupported_statements() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute("DROP TABLE IF EXISTS test_explain_rows; CREATE TABLE test_explain_rows (id INTEGER)")
+        .await
+        .unwrap();
+
+    // 不带 ANALYZE 时不会执行 DML
+    let plan = explain::explain_query(&client, "INSERT INTO test_explain_rows VALUES (1)", false).await.unwrap();
+    assert!(plan.warning.is_none());
+    let count: i64 = client.query_one("SELECT count(*) FROM test_explain_rows", &[]).await.unwrap().get(0);
+    assert_eq!(count, 0);
+
+    let analyzed = explain::explain_query(&client, "INSERT INTO test_explain_rows VALUES (1)", true).await.unwrap();
+    assert!(analyzed.warning.is_some());
+
+    assert!(explain::explain_query(&client, "DROP TABLE test_explain_rows", false).await.is_err());
+    assert!(explain::explain_query(&client, "EXPLAIN SELECT 1", false).await.is_err());
+    assert!(explain::explain_query(&client, "SELECT 1; SELECT 2", false).await.is_err());
+
+    let _ = client.batch_execute("DROP TABLE test_explain_rows").await;
+}
@@ -0,0 +1,138 @@
+/**
+ * Integration tests for Index Advisor
+ *
+ * 这些测试在实际数据库中构造冗余索引，验证：
+ * - 键列是另一索引前缀的较窄索引被标记为冗余
+ * - 较宽的索引不被标记为冗余
+ * - 主键和唯一索引永远不会被标记
+ * - 索引使用情况报告列出模式中的每个索引，标记未扫描过的和重复的索引
+ */
+
+use pg_db_tool::models::monitoring::IndexIssue;
+use pg_db_tool::services::index_advisor;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_find_redundant_index_pair() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_index_advisor;
+             CREATE TABLE test_index_advisor (
+                 id INTEGER PRIMARY KEY,
+                 customer_id INTEGER,
+                 created_at DATE,
+                 code TEXT UNIQUE
+             );
+             CREATE INDEX test_index_advisor_customer ON test_index_advisor (customer_id);
+             CREATE INDEX test_index_advisor_customer_created ON test_index_advisor (customer_id, created_at);
+             CREATE UNIQUE INDEX test_index_advisor_customer_unique ON test_index_advisor (customer_id, code);",
+        )
+        .await
+        .unwrap();
+
+    let candidates = index_advisor::find_redundant_indexes(&client)
+        .await
+        .expect("查找冗余索引失败");
+    let for_table: Vec<_> = candidates
+        .iter()
+        .filter(|c| c.table == "test_index_advisor")
+        .collect();
+
+    let narrow = for_table
+        .iter()
+        .find(|c| c.index_name == "test_index_advisor_customer")
+        .expect("较窄的索引应被标记");
+    assert_eq!(narrow.issue, IndexIssue::Redundant);
+    assert!(narrow.covered_by.is_some());
+    assert!(narrow.drop_statement.starts_with("DROP INDEX"));
+    assert!(narrow.drop_statement.contains("test_index_advisor_customer"));
+
+    assert!(!for_table
+        .iter()
+        .any(|c| c.index_name == "test_index_advisor_customer_created" && c.issue == IndexIssue::Redundant));
+
+    // 主键和唯一索引不能被建议删除
+    assert!(!for_table.iter().any(|c| {
+        c.index_name == "test_index_advisor_pkey"
+            || c.index_name == "test_index_advisor_code_key"
+            || c.index_name == "test_index_advisor_customer_unique"
+    }));
+
+    let _ = client.batch_execute("DROP TABLE test_index_advisor").await;
+}
+
+#[tokio::test]
+async fn test_index_usage_report() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_index_usage;
+             CREATE TABLE test_index_usage (id INTEGER PRIMARY KEY, customer_id INTEGER, created_at DATE);
+             CREATE INDEX test_index_usage_customer ON test_index_usage (customer_id);
+             CREATE INDEX test_index_usage_customer_created ON test_index_usage (customer_id, created_at);",
+        )
+        .await
+        .unwrap();
+
+    let report = index_advisor::get_index_usage(&client, "public")
+        .await
+        .expect("获取索引使用情况失败");
+    let for_table: Vec<_> = report.iter().filter(|i| i.table == "test_index_usage").collect();
+
+    assert_eq!(for_table.len(), 3);
+    assert!(for_table.iter().all(|i| i.schema == "public" && i.size_bytes > 0));
+    // 新建的表上还没有任何索引扫描
+    assert!(for_table.iter().all(|i| i.scans == 0 && i.possibly_unused));
+
+    let pkey = for_table.iter().find(|i| i.index_name == "test_index_usage_pkey").unwrap();
+    assert!(pkey.is_primary && pkey.is_unique);
+    assert!(pkey.duplicate_of.is_none());
+
+    let narrow = for_table.iter().find(|i| i.index_name == "test_index_usage_customer").unwrap();
+    assert_eq!(narrow.duplicate_of.as_deref(), Some("test_index_usage_customer_created"));
+
+    let other_schema = index_advisor::get_index_usage(&client, "test_index_usage_no_such_schema")
+        .await
+        .unwrap();
+    assert!(other_schema.is_empty());
+
+    let _ = client.batch_execute("DROP TABLE test_index_usage").await;
+}
@@ -0,0 +1,203 @@
+/**
+ * Integration tests for Maintenance
+ *
+ * 这些测试验证 REINDEX、VACUUM 和 ANALYZE 的执行：
+ * - 重建后的索引有效（pg_index.indisvalid）
+ * - VERBOSE 通知被转发给调用方
+ * - 不存在的目标报错
+ * - VACUUM FULL ANALYZE 和 ANALYZE 在自动提交连接上执行并更新维护时间
+ * - 非所有者执行 VACUUM 时服务器只警告并跳过，返回权限错误
+ */
+
+use pg_db_tool::models::monitoring::ReindexTarget;
+use pg_db_tool::services::maintenance;
+use pg_db_tool::services::tls::{self, TlsSettings};
+
+/// 获取测试数据库连接及其通知接收端
+async fn get_test_client() -> Result<
+    (tokio_postgres::Client, tokio::sync::mpsc::UnboundedReceiver<tokio_postgres::error::DbError>),
+    tls::ConnectError,
+> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    tls::connect_with_notices(&connection_string, &TlsSettings::default()).await
+}
+
+#[tokio::test]
+async fn test_reindex_table_leaves_indexes_valid() {
+    let (client, mut notices) = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_reindex;
+             CREATE TABLE test_reindex (id INT PRIMARY KEY, name TEXT);
+             CREATE INDEX test_reindex_name_idx ON test_reindex (name);
+             INSERT INTO test_reindex SELECT g, 'name ' || g FROM generate_series(1, 1000) g;",
+        )
+        .await
+        .unwrap();
+
+    let target = ReindexTarget::Table {
+        schema: "public".to_string(),
+        name: "test_reindex".to_string(),
+    };
+
+    for concurrently in [false, true] {
+        let mut streamed = Vec::new();
+        let result = maintenance::reindex(&client, &mut notices, &target, concurrently, |notice| {
+            streamed.push(notice.to_string())
+        })
+        .await
+        .expect("重建索引失败");
+
+        assert_eq!(result.concurrently, concurrently);
+        assert!(result.statement.starts_with("REINDEX (VERBOSE) TABLE"));
+        assert_eq!(result.notices, streamed);
+        assert!(
+            result.notices.iter().any(|n| n.contains("test_reindex_name_idx")),
+            "应收到每个索引的重建通知: {:?}",
+            result.notices
+        );
+
+        let rows = client
+            .query(
+                "SELECT ic.relname::text, i.indisvalid
+                 FROM pg_index i
+                 JOIN pg_class ic ON ic.oid = i.indexrelid
+                 WHERE i.indrelid = 'public.test_reindex'::regclass",
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 2, "CONCURRENTLY 不应遗留额外的索引");
+        for row in rows {
+            let name: String = row.get(0);
+            assert!(row.get::<_, bool>(1), "索引 {} 无效", name);
+        }
+    }
+
+    let _ = client.batch_execute("DROP TABLE test_reindex").await;
+}
+
+#[tokio::test]
+async fn test_reindex_missing_target() {
+    let (client, mut notices) = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let target = ReindexTarget::Index {
+        schema: "public".to_string(),
+        name: "test_reindex_missing_idx".to_string(),
+    };
+    let result = maintenance::reindex(&client, &mut notices, &target, false, |_| {}).await;
+
+    assert!(result.unwrap_err().contains("does not exist"));
+}
+
+#[tokio::test]
+async fn test_vacuum_and_analyze_table() {
+    let (client, mut notices) = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_vacuum;
+             CREATE TABLE test_vacuum (id INT PRIMARY KEY);
+             INSERT INTO test_vacuum SELECT g FROM generate_series(1, 1000) g;
+             DELETE FROM test_vacuum WHERE id % 2 = 0;",
+        )
+        .await
+        .unwrap();
+
+    let result = maintenance::vacuum_table(&client, &mut notices, "public", "test_vacuum", true, true)
+        .await
+        .expect("清理表失败");
+    assert_eq!(result.statement, "VACUUM FULL ANALYZE \"public\".\"test_vacuum\"");
+
+    let result = maintenance::analyze_table(&client, &mut notices, "public", "test_vacuum")
+        .await
+        .expect("分析表失败");
+    assert_eq!(result.statement, "ANALYZE \"public\".\"test_vacuum\"");
+
+    let row = client
+        .query_one(
+            "SELECT last_analyze IS NOT NULL, c.reltuples::int8
+             FROM pg_stat_user_tables s JOIN pg_class c ON c.oid = s.relid
+             WHERE s.relid = 'public.test_vacuum'::regclass",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert!(row.get::<_, bool>(0));
+    assert_eq!(row.get::<_, i64>(1), 500);
+
+    let missing = maintenance::vacuum_table(&client, &mut notices, "public", "test_vacuum_missing", false, false).await;
+    assert!(missing.unwrap_err().contains("does not exist"));
+
+    let _ = client.batch_execute("DROP TABLE test_vacuum").await;
+}
+
+#[tokio::test]
+async fn test_vacuum_without_ownership_is_permission_error() {
+    let (client, mut notices) = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let setup = client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_vacuum_owner;
+             DROP ROLE IF EXISTS test_vacuum_other;
+             CREATE ROLE test_vacuum_other;
+             CREATE TABLE test_vacuum_owner (id INT);
+             GRANT SELECT ON test_vacuum_owner TO test_vacuum_other;
+             SET ROLE test_vacuum_other;",
+        )
+        .await;
+    if let Err(e) = setup {
+        eprintln!("无法创建测试角色: {}. 跳过测试", e);
+        return;
+    }
+
+    let vacuum = maintenance::vacuum_table(&client, &mut notices, "public", "test_vacuum_owner", false, false).await;
+    let analyze = maintenance::analyze_table(&client, &mut notices, "public", "test_vacuum_owner").await;
+
+    client
+        .batch_execute(
+            "RESET ROLE;
+             DROP TABLE test_vacuum_owner;
+             DROP ROLE test_vacuum_other;",
+        )
+        .await
+        .unwrap();
+
+    assert!(vacuum.unwrap_err().starts_with("Permission denied"));
+    assert!(analyze.unwrap_err().starts_with("Permission denied"));
+}
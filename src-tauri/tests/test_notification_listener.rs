@@ -0,0 +1,105 @@
+/**
+ * Integration tests for Notification Listener
+ *
+ * 这些测试验证 LISTEN/NOTIFY：
+ * - 同时订阅两个频道并在两个频道上都收到通知
+ * - JSON 负载被解析为结构化的值
+ * - 重复订阅的频道不会重复 LISTEN
+ */
+
+use pg_db_tool::services::notification_listener::{NotificationEvent, NotificationListener};
+use pg_db_tool::services::tls::{self, TlsSettings};
+use std::time::Duration;
+
+fn connection_string() -> String {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    )
+}
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tls::ConnectError> {
+    tls::connect(&connection_string(), &TlsSettings::default()).await
+}
+
+#[tokio::test]
+async fn test_listen_on_two_channels() {
+    let sender = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let (client, notifications) = tls::connect_with_notifications(&connection_string(), &TlsSettings::default())
+        .await
+        .expect("无法建立监听连接");
+
+    let (events_tx, mut events_rx) = tokio::sync::mpsc::unbounded_channel::<NotificationEvent>();
+    let mut listener = NotificationListener::new(client, notifications, move |event| {
+        let _ = events_tx.send(event);
+    });
+
+    let channels = vec![
+        "test_listen_orders".to_string(),
+        "test_listen_Audit".to_string(),
+        "test_listen_orders".to_string(),
+    ];
+    let added = listener.listen(&channels).await.expect("订阅失败");
+    assert_eq!(added, vec!["test_listen_orders".to_string(), "test_listen_Audit".to_string()]);
+
+    // 已订阅的频道不会重复 LISTEN
+    let added = listener.listen(&["test_listen_orders".to_string()]).await.unwrap();
+    assert!(added.is_empty());
+    assert_eq!(listener.channels().len(), 2);
+
+    sender
+        .batch_execute(
+            "SELECT pg_notify('test_listen_orders', '{\"id\": 7, \"status\": \"paid\"}');
+             SELECT pg_notify('test_listen_Audit', 'plain text');",
+        )
+        .await
+        .unwrap();
+
+    let mut received = Vec::new();
+    while received.len() < 2 {
+        let event = tokio::time::timeout(Duration::from_secs(5), events_rx.recv())
+            .await
+            .expect("等待通知超时")
+            .expect("监听连接已关闭");
+        received.push(event);
+    }
+    received.sort_by(|a, b| a.channel.cmp(&b.channel));
+
+    assert_eq!(received[0].channel, "test_listen_Audit");
+    assert_eq!(received[0].payload, "plain text");
+    assert_eq!(received[0].parsed, None);
+
+    assert_eq!(received[1].channel, "test_listen_orders");
+    assert_eq!(
+        received[1].parsed,
+        Some(serde_json::json!({"id": 7, "status": "paid"}))
+    );
+
+    // 取消订阅后不再收到该频道的通知
+    let removed = listener.unlisten(&["test_listen_Audit".to_string()]).await.unwrap();
+    assert_eq!(removed, vec!["test_listen_Audit".to_string()]);
+    sender
+        .batch_execute("SELECT pg_notify('test_listen_Audit', 'ignored'); SELECT pg_notify('test_listen_orders', 'last');")
+        .await
+        .unwrap();
+    let event = tokio::time::timeout(Duration::from_secs(5), events_rx.recv())
+        .await
+        .expect("等待通知超时")
+        .unwrap();
+    assert_eq!(event.channel, "test_listen_orders");
+    assert_eq!(event.payload, "last");
+}
@@ -0,0 +1,156 @@
+/**
+ * Integration tests for Object Search
+ *
+ * 这些测试验证数据库对象搜索：
+ * - 按子串不区分大小写地匹配表名，通配符按字面匹配
+ * - 按正则表达式匹配列名，并返回列所属的表
+ * - 按注释匹配和分页
+ */
+
+use pg_db_tool::models::schema::SearchObjectKind;
+use pg_db_tool::services::object_search::{self, ObjectSearch};
+
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+/// 在指定模式中创建搜索测试用的对象
+async fn setup(client: &tokio_postgres::Client, schema: &str) {
+    client
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {schema} CASCADE;
+             CREATE SCHEMA {schema};
+             CREATE TABLE {schema}.customer_orders (id INT, order_total NUMERIC, note TEXT);
+             CREATE TABLE {schema}.customer_profiles (id INT, created_at TIMESTAMPTZ, updated_at TIMESTAMPTZ);
+             CREATE TABLE {schema}.invoices (id INT, customer_id INT);
+             CREATE TABLE {schema}.\"CustomerNotes\" (id INT);
+             CREATE VIEW {schema}.customer_summary AS SELECT id FROM {schema}.customer_orders;
+             COMMENT ON TABLE {schema}.invoices IS 'Billing documents sent to customers';"
+        ))
+        .await
+        .unwrap();
+}
+
+fn search(pattern: &str, regex: bool, kinds: Vec<SearchObjectKind>) -> ObjectSearch {
+    ObjectSearch {
+        pattern: pattern.to_string(),
+        regex,
+        kinds,
+        include_comments: false,
+        page: 1,
+        page_size: 50,
+    }
+}
+
+#[tokio::test]
+async fn test_search_tables_by_substring() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let schema = "test_object_search_tables";
+    setup(&client, schema).await;
+
+    // 不区分大小写，视图不在 table 类型中
+    let result = object_search::search_objects(&client, &search("CUSTOMER", false, vec![SearchObjectKind::Table]))
+        .await
+        .expect("搜索失败");
+    let mut tables: Vec<&str> = result
+        .matches
+        .iter()
+        .filter(|m| m.schema == schema)
+        .map(|m| m.name.as_str())
+        .collect();
+    tables.sort();
+    assert_eq!(tables, vec!["CustomerNotes", "customer_orders", "customer_profiles"]);
+    assert!(result.matches.iter().all(|m| m.kind == SearchObjectKind::Table && m.table.is_none()));
+
+    // _ 按字面匹配，不是单字符通配符
+    let result = object_search::search_objects(&client, &search("customer_", false, vec![SearchObjectKind::Table]))
+        .await
+        .expect("搜索失败");
+    assert!(result.matches.iter().all(|m| m.name != "CustomerNotes"));
+
+    // 同时匹配注释
+    let mut by_comment = search("billing", false, vec![SearchObjectKind::Table]);
+    by_comment.include_comments = true;
+    let result = object_search::search_objects(&client, &by_comment).await.expect("搜索失败");
+    let invoices = result
+        .matches
+        .iter()
+        .find(|m| m.schema == schema && m.name == "invoices")
+        .expect("应按注释匹配到 invoices");
+    assert!(!invoices.name_matched);
+
+    client.batch_execute(&format!("DROP SCHEMA {} CASCADE", schema)).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_search_columns_by_pattern() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+    let schema = "test_object_search_columns";
+    setup(&client, schema).await;
+
+    let result = object_search::search_objects(
+        &client,
+        &search("^(created|updated)_at$", true, vec![SearchObjectKind::Column]),
+    )
+    .await
+    .expect("搜索失败");
+    let columns: Vec<(&str, Option<&str>)> = result
+        .matches
+        .iter()
+        .filter(|m| m.schema == schema)
+        .map(|m| (m.name.as_str(), m.table.as_deref()))
+        .collect();
+    assert_eq!(
+        columns,
+        vec![("created_at", Some("customer_profiles")), ("updated_at", Some("customer_profiles"))]
+    );
+
+    // 分页：每页一个，总数不变
+    let mut paged = search("^customer_id$|^order_total$", true, vec![SearchObjectKind::Column]);
+    paged.page_size = 1;
+    let first = object_search::search_objects(&client, &paged).await.expect("搜索失败");
+    paged.page = 2;
+    let second = object_search::search_objects(&client, &paged).await.expect("搜索失败");
+    assert_eq!(first.matches.len(), 1);
+    assert_eq!(second.matches.len(), 1);
+    assert_eq!(first.total, second.total);
+    assert_ne!(first.matches[0], second.matches[0]);
+
+    let invalid = object_search::search_objects(&client, &search("[", true, vec![SearchObjectKind::Column])).await;
+    assert!(invalid.unwrap_err().contains("正则表达式无效"));
+
+    client.batch_execute(&format!("DROP SCHEMA {} CASCADE", schema)).await.unwrap();
+}
@@ -0,0 +1,70 @@
+/**
+ * Integration tests for PostgreSQL Tools
+ *
+ * 这些测试验证运行命令行工具时的进度读取：
+ * - 标准错误的每一行都按顺序传给回调，结束后返回完整输出和退出状态
+ * - pg_dump 通过临时 .pgpass 文件认证，并在 `-v` 模式下逐行报告进度
+ */
+
+use pg_db_tool::services::pg_tools::PgTool;
+use pg_db_tool::services::pgpass::PgPassFile;
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_run_with_progress_reports_each_stderr_line() {
+    let tool = PgTool::new("pg_dump", Some("sh".to_string()));
+    let mut command = tool.command();
+    command
+        .arg("-c")
+        .arg("echo out; echo 'pg_dump: first' >&2; printf 'pg_dump: second\\r\\n' >&2; printf 'last' >&2; exit 3");
+
+    let mut lines = Vec::new();
+    let output = tool
+        .run_with_progress(command, |line| lines.push(line.to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(lines, vec!["pg_dump: first", "pg_dump: second", "last"]);
+    assert_eq!(output.stderr, "pg_dump: first\npg_dump: second\nlast\n");
+    assert!(!output.success);
+}
+
+#[tokio::test]
+async fn test_pg_dump_progress_with_pgpass_file() {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+
+    let pgpass = PgPassFile::new(&user, &password).unwrap();
+    let tool = PgTool::new("pg_dump", None);
+    let mut command = tool.command();
+    command
+        .arg("-h").arg(&host)
+        .arg("-p").arg(&port)
+        .arg("-U").arg(&user)
+        .arg("-w")  // 不提示输入密码，密码只能来自 .pgpass 文件
+        .arg("-v")
+        .arg("--schema-only")
+        .arg("-f").arg(if cfg!(windows) { "NUL" } else { "/dev/null" })
+        .arg("postgres")
+        .env_remove("PGPASSWORD")
+        .env("PGPASSFILE", pgpass.path());
+
+    let mut lines = Vec::new();
+    let output = match tool.run_with_progress(command, |line| lines.push(line.to_string())).await {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("无法运行 pg_dump: {}. 跳过测试", e);
+            return;
+        }
+    };
+    if !output.success {
+        eprintln!("无法连接到测试数据库: {}. 跳过测试", output.stderr);
+        return;
+    }
+
+    assert!(lines.len() > 1, "pg_dump -v 应逐行输出进度");
+    assert!(lines.iter().all(|line| !line.ends_with('\n') && !line.ends_with('\r')));
+    assert!(lines.iter().any(|line| line.starts_with("pg_dump:")));
+}
@@ -0,0 +1,95 @@
+/**
+ * Integration tests for Plan Baseline
+ *
+ * 这些测试验证执行计划基线的保存与比较：
+ * - 首次比较时保存基线
+ * - 删除索引后，索引扫描变为顺序扫描的变化会被报告
+ */
+
+use pg_db_tool::models::query::PlanChangeKind;
+use pg_db_tool::services::plan_baseline::{self, PlanBaselineStore};
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_index_scan_becoming_seq_scan_is_reported() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_plan_baseline;
+             CREATE TABLE test_plan_baseline (id INT, payload TEXT);
+             INSERT INTO test_plan_baseline SELECT g, md5(g::text) FROM generate_series(1, 20000) g;
+             CREATE INDEX test_plan_baseline_id_idx ON test_plan_baseline (id);
+             ANALYZE test_plan_baseline;",
+        )
+        .await
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let store = PlanBaselineStore::new(dir.path().join("plan_baselines.json"));
+    let sql = "SELECT * FROM test_plan_baseline WHERE id = 42";
+
+    let first = plan_baseline::compare_plans(&client, &store, "personnel_db", sql, false)
+        .await
+        .expect("保存基线失败");
+    assert!(first.baseline_created);
+    assert!(first.changes.is_empty());
+
+    // 仅格式不同的查询使用同一个基线
+    let same = plan_baseline::compare_plans(&client, &store, "personnel_db", "select *\n from test_plan_baseline where id = 42;", false)
+        .await
+        .unwrap();
+    assert!(!same.baseline_created);
+    assert!(!same.plan_changed);
+
+    client
+        .batch_execute("DROP INDEX test_plan_baseline_id_idx")
+        .await
+        .unwrap();
+
+    let second = plan_baseline::compare_plans(&client, &store, "personnel_db", sql, false)
+        .await
+        .expect("比较执行计划失败");
+    assert!(!second.baseline_created);
+    assert!(second.plan_changed);
+    assert!(second.cost_change_percent > 0.0);
+
+    let scan_change = second
+        .changes
+        .iter()
+        .find(|change| change.kind == PlanChangeKind::NodeTypeChanged)
+        .expect("应报告节点类型变化");
+    assert!(scan_change.before_node_type.as_deref().unwrap().contains("Index"));
+    assert_eq!(scan_change.after_node_type.as_deref(), Some("Seq Scan"));
+
+    let _ = client.batch_execute("DROP TABLE test_plan_baseline").await;
+}
@@ -0,0 +1,96 @@
+/**
+ * Integration tests for Query Benchmark
+ *
+ * 这些测试验证查询性能测试：
+ * - 多次运行简单查询，统计结果合理
+ * - 预热次数不计入统计，重置执行计划不影响运行
+ * - 拒绝修改数据的语句
+ */
+
+use pg_db_tool::services::query_benchmark::{self, BenchmarkOptions};
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_benchmark_trivial_select() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let options = BenchmarkOptions {
+        runs: 50,
+        warmup_runs: 5,
+        reset_between_runs: false,
+    };
+    let result = query_benchmark::benchmark_query(&client, "SELECT 1 AS one", &options)
+        .await
+        .expect("性能测试失败");
+
+    assert_eq!(result.runs, 50);
+    assert_eq!(result.timings_ms.len(), 50);
+    assert_eq!(result.row_count, Some(1));
+    assert!(result.timings_ms.iter().all(|t| *t > 0.0));
+    assert!(result.min_ms > 0.0);
+    assert!(result.min_ms <= result.median_ms);
+    assert!(result.median_ms <= result.p95_ms);
+    assert!(result.p95_ms <= result.max_ms);
+    assert!(result.min_ms <= result.mean_ms && result.mean_ms <= result.max_ms);
+    assert!(result.std_dev_ms >= 0.0);
+    assert!(result.std_dev_ms <= result.max_ms - result.min_ms);
+}
+
+#[tokio::test]
+async fn test_benchmark_reset_and_explain() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let options = BenchmarkOptions {
+        runs: 3,
+        warmup_runs: 0,
+        reset_between_runs: true,
+    };
+    let result =
+        query_benchmark::benchmark_query(&client, "EXPLAIN SELECT * FROM pg_class", &options)
+            .await
+            .expect("性能测试失败");
+    assert_eq!(result.timings_ms.len(), 3);
+
+    let rejected = query_benchmark::benchmark_query(&client, "DELETE FROM pg_class", &options).await;
+    assert!(rejected.is_err());
+
+    let too_many = BenchmarkOptions { runs: 0, ..options };
+    assert!(query_benchmark::benchmark_query(&client, "SELECT 1", &too_many)
+        .await
+        .is_err());
+}
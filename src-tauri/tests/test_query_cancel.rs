@@ -0,0 +1,94 @@
+/**
+ * Integration tests for Query Cancel
+ *
+ * 这些测试验证一次取消本应用正在执行的全部语句：
+ * - 同时执行的多条 pg_sleep 查询都会被取消
+ * - 未登记的会话不受影响
+ */
+
+use pg_db_tool::services::query_cancel::{self, QueryCancelRegistry};
+use pg_db_tool::services::tls::{SslMode, TlsSettings};
+use std::time::{Duration, Instant};
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+fn no_tls() -> TlsSettings {
+    TlsSettings {
+        ssl_mode: SslMode::Disable,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_all_queries() {
+    let mut clients = Vec::new();
+    for _ in 0..3 {
+        match get_test_client().await {
+            Ok(c) => clients.push(c),
+            Err(e) => {
+                eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+                return;
+            }
+        }
+    }
+    let other = clients.pop().unwrap();
+
+    let mut registry = QueryCancelRegistry::new();
+    let ids: Vec<u64> = clients
+        .iter()
+        .map(|client| registry.register("personnel_db", client.cancel_token(), no_tls()))
+        .collect();
+    assert_eq!(registry.running().len(), 2);
+
+    let start = Instant::now();
+    let sleeps = futures_util::future::join_all(
+        clients.iter().map(|client| client.batch_execute("SELECT pg_sleep(30)")),
+    );
+    // 未登记的会话执行一条较短的查询，不应被取消
+    let unaffected = other.batch_execute("SELECT pg_sleep(1)");
+
+    let cancel = async {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        query_cancel::cancel_all(&registry.running()).await
+    };
+
+    let (results, unaffected_result, requested) = tokio::join!(sleeps, unaffected, cancel);
+
+    assert_eq!(requested, 2, "应发送两个取消请求");
+    assert!(start.elapsed() < Duration::from_secs(10), "查询应被立即取消");
+    for result in results {
+        let error = result.expect_err("查询应被取消");
+        assert_eq!(error.code(), Some(&tokio_postgres::error::SqlState::QUERY_CANCELED));
+    }
+    assert!(unaffected_result.is_ok(), "其他会话不应受影响");
+
+    for id in ids {
+        registry.unregister(id);
+    }
+    assert!(registry.running().is_empty());
+
+    // 取消后连接仍可继续使用
+    clients[0].batch_execute("SELECT 1").await.expect("连接应仍可使用");
+}
@@ -733,6 +733,35 @@ async fn test_error_position_extraction() {
     }
 }
 
+#[tokio::test]
+async fn test_error_position_after_multibyte_characters() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Skipping test - cannot connect to database: {}", e);
+            return;
+        }
+    };
+    
+    // The comment and literal contain multibyte characters before the error
+    let sql = "-- 查询用户\nSELECT '你好' AS 问候,\n       missing_col FROM pg_class";
+    let result = query_executor::execute_sql(&client, sql).await;
+    
+    assert_eq!(result.result_type, QueryResultType::Error);
+    let pos = result.error_position.expect("Error position should be reported");
+    assert_eq!(pos.line, 3);
+    assert_eq!(pos.column, 8);
+    
+    // In a later statement, the position is relative to the whole SQL text
+    let sql = "SELECT '第一条';\nSELECT '第二条' AS 名称, missing_col FROM pg_class";
+    let result = query_executor::execute_sql(&client, sql).await;
+    
+    assert_eq!(result.result_type, QueryResultType::Error);
+    let pos = result.error_position.expect("Error position should be reported");
+    assert_eq!(pos.line, 2);
+    assert_eq!(pos.column, 21);
+}
+
 #[tokio::test]
 async fn test_error_message_includes_technical_details() {
     let client = match get_test_client().await {
@@ -763,3 +792,278 @@ async fn test_error_message_includes_technical_details() {
     // Clean up
     let _ = query_executor::execute_sql(&client, "DROP TABLE test_error_details").await;
 }
+
+#[tokio::test]
+async fn test_execute_cross_database_reference() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Skipping test - cannot connect to database: {}", e);
+            return;
+        }
+    };
+    
+    let sql = "SELECT * FROM some_other_db.public.users";
+    let result = query_executor::execute_sql(&client, sql).await;
+    
+    assert_eq!(result.result_type, QueryResultType::Error);
+    let error = result.error.unwrap();
+    assert!(error.contains("Cross-database reference"), "{}", error);
+    assert!(error.contains("some_other_db"));
+    assert!(error.contains("postgres_fdw"));
+    assert_eq!(result.error_position.unwrap().column, 15);
+    
+    // A three-part name that points at the current database is allowed
+    let sql = "SELECT COUNT(*) AS n FROM postgres.pg_catalog.pg_class";
+    let result = query_executor::execute_sql(&client, sql).await;
+    assert_eq!(result.result_type, QueryResultType::Select, "{:?}", result.error);
+}
+
+#[tokio::test]
+async fn test_execute_server_side_copy_returns_warning() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Skipping test - cannot connect to database: {}", e);
+            return;
+        }
+    };
+    
+    let sql = "COPY (SELECT 1) TO PROGRAM 'cat > /dev/null'";
+    let result = query_executor::execute_sql(&client, sql).await;
+    
+    assert_eq!(result.result_type, QueryResultType::Copy, "{:?}", result.error);
+    assert_eq!(result.affected_rows, Some(1));
+    let warning = result.warning.unwrap();
+    assert!(warning.contains("database server"), "{}", warning);
+    assert!(warning.contains("cat > /dev/null"));
+    
+    // STDIN needs a local file and is not run as a plain query
+    let result = query_executor::execute_sql(&client, "COPY (SELECT 1) TO STDOUT").await;
+    assert_eq!(result.result_type, QueryResultType::Error);
+}
+
+#[tokio::test]
+async fn test_execute_client_copy_with_rewritten_statement() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Skipping test - cannot connect to database: {}", e);
+            return;
+        }
+    };
+    
+    client
+        .batch_execute(
+            "CREATE TEMP TABLE copy_source AS SELECT g AS id, 'name ' || g AS name FROM generate_series(1, 5) g;
+             CREATE TEMP TABLE copy_target (id INT, name TEXT);",
+        )
+        .await
+        .unwrap();
+    let file = std::env::temp_dir().join(format!("pg_db_tool_copy_{}.csv", std::process::id()));
+    let file_path = file.to_str().unwrap();
+    
+    // Rewrite server-side file COPYs and run them with the local file instead
+    let copies = query_executor::find_server_copies("COPY copy_source TO '/srv/out.csv' WITH (FORMAT csv)");
+    let export_sql = copies[0].client_sql.as_deref().unwrap();
+    assert_eq!(export_sql, "COPY copy_source TO STDOUT WITH (FORMAT csv)");
+    let result = query_executor::execute_client_copy(&client, export_sql, file_path).await;
+    assert_eq!(result.result_type, QueryResultType::Copy, "{:?}", result.error);
+    assert_eq!(std::fs::read_to_string(&file).unwrap().lines().count(), 5);
+    
+    let copies = query_executor::find_server_copies("COPY copy_target FROM '/srv/out.csv' WITH (FORMAT csv)");
+    let import_sql = copies[0].client_sql.as_deref().unwrap();
+    let result = query_executor::execute_client_copy(&client, import_sql, file_path).await;
+    assert_eq!(result.result_type, QueryResultType::Copy, "{:?}", result.error);
+    assert_eq!(result.affected_rows, Some(5));
+    
+    let row = client
+        .query_one("SELECT COUNT(*) FROM copy_target t JOIN copy_source s USING (id, name)", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 5);
+    
+    // Only STDIN/STDOUT statements are accepted
+    let result = query_executor::execute_client_copy(&client, "SELECT 1", file_path).await;
+    assert_eq!(result.result_type, QueryResultType::Error);
+    
+    let _ = std::fs::remove_file(&file);
+}
+
+#[tokio::test]
+async fn test_execute_with_json_params() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Skipping test - cannot connect to database: {}", e);
+            return;
+        }
+    };
+    
+    let sql = "SELECT id, name FROM (VALUES (1, 'O''Brien'), (2, 'x'), (3, 'O''Brien')) AS t (id, name) \
+               WHERE name = $1 AND id >= $2 AND ($3 OR $4::date > DATE '2024-01-01')";
+    let params = vec![
+        serde_json::json!("O'Brien"),
+        serde_json::json!(2),
+        serde_json::json!(false),
+        serde_json::json!("2024-03-01"),
+    ];
+    let result = query_executor::execute_with_json_params(&client, sql, &params).await;
+    assert_eq!(result.result_type, QueryResultType::Select, "{:?}", result.error);
+    let rows = result.rows.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["id"], serde_json::json!(3));
+    assert_eq!(rows[0]["name"], serde_json::json!("O'Brien"));
+    
+    // Input is never executed as SQL
+    let params = vec![serde_json::json!("x'; DROP TABLE t; --"), serde_json::json!(null)];
+    let result = query_executor::execute_with_json_params(&client, "SELECT $1 AS value, $2::int IS NULL AS missing", &params).await;
+    assert_eq!(result.result_type, QueryResultType::Select, "{:?}", result.error);
+    let rows = result.rows.unwrap();
+    assert_eq!(rows[0]["value"], serde_json::json!("x'; DROP TABLE t; --"));
+    assert_eq!(rows[0]["missing"], serde_json::json!(true));
+    
+    // The number of values must match the placeholders
+    let result = query_executor::execute_with_json_params(&client, "SELECT $1::int", &[]).await;
+    assert_eq!(result.result_type, QueryResultType::Error);
+    
+    let result = query_executor::execute_with_json_params(&client, "SELECT 1; SELECT 2", &[]).await;
+    assert_eq!(result.result_type, QueryResultType::Error);
+
+    // Error positions after a placeholder refer to the SQL as written
+    let params = vec![serde_json::json!(1), serde_json::json!("a")];
+    let result = query_executor::execute_with_json_params(
+        &client,
+        "SELECT $1::int AS value, $2 AS label, no_such_column",
+        &params,
+    )
+    .await;
+    assert_eq!(result.result_type, QueryResultType::Error);
+    let position = result.error_position.unwrap();
+    assert_eq!((position.line, position.column), (1, 39));
+}
+
+#[tokio::test]
+async fn test_stream_select_in_chunks() {
+    let mut client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Skipping test - cannot connect to database: {}", e);
+            return;
+        }
+    };
+    
+    let sql = "SELECT g AS id, 'row ' || g AS label FROM generate_series(1, 1234) g ORDER BY g";
+    let mut chunks = Vec::new();
+    let summary = query_executor::stream_select(&mut client, sql, 500, None, |chunk| {
+        chunks.push(chunk);
+        Ok(())
+    })
+    .await
+    .expect("stream should succeed");
+    
+    assert_eq!(summary.total_rows, 1234);
+    assert!(!summary.truncated);
+    let sizes: Vec<usize> = chunks.iter().map(|c| c.rows.len()).collect();
+    assert_eq!(sizes, vec![500, 500, 234]);
+    assert_eq!(chunks.iter().map(|c| c.chunk_index).collect::<Vec<_>>(), vec![0, 1, 2]);
+    
+    // Only the first chunk carries the columns
+    let columns = chunks[0].columns.as_ref().unwrap();
+    assert_eq!(columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["id", "label"]);
+    assert!(chunks[1].columns.is_none());
+    assert_eq!(chunks[2].rows.last().unwrap()["id"], serde_json::json!(1234));
+    
+    // An empty result still sends one chunk with the columns
+    let mut chunks = Vec::new();
+    let summary = query_executor::stream_select(&mut client, "SELECT 1 AS n WHERE false", 500, None, |chunk| {
+        chunks.push(chunk);
+        Ok(())
+    })
+    .await
+    .unwrap();
+    assert_eq!(summary.total_rows, 0);
+    assert_eq!(chunks.len(), 1);
+    assert!(chunks[0].rows.is_empty());
+    assert_eq!(chunks[0].columns.as_ref().unwrap()[0].name, "n");
+}
+
+#[tokio::test]
+async fn test_stream_select_row_limit() {
+    let mut client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Skipping test - cannot connect to database: {}", e);
+            return;
+        }
+    };
+    
+    let sql = "SELECT g FROM generate_series(1, 100000) g";
+    let mut received = 0;
+    let summary = query_executor::stream_select(&mut client, sql, 300, Some(1000), |chunk| {
+        received += chunk.rows.len();
+        Ok(())
+    })
+    .await
+    .unwrap();
+    assert_eq!(summary.total_rows, 1000);
+    assert_eq!(received, 1000);
+    assert!(summary.truncated);
+    
+    // A limit equal to the row count does not truncate
+    let summary = query_executor::stream_select(&mut client, "SELECT g FROM generate_series(1, 10) g", 300, Some(10), |_| Ok(()))
+        .await
+        .unwrap();
+    assert!(!summary.truncated);
+    
+    // The server stops producing rows once the limit is reached
+    let summary = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        query_executor::stream_select(&mut client, "SELECT generate_series(1, 1000000000) AS g", 500, Some(1000), |_| Ok(())),
+    )
+    .await
+    .expect("a truncated stream should not read the whole result")
+    .unwrap();
+    assert_eq!(summary.total_rows, 1000);
+    assert!(summary.truncated);
+    
+    // The connection is usable after a truncated stream
+    let result = query_executor::execute_sql(&client, "SELECT 1 AS n").await;
+    assert_eq!(result.result_type, QueryResultType::Select);
+    
+    // Only a single SELECT can be streamed
+    assert!(query_executor::stream_select(&mut client, "DELETE FROM pg_class WHERE false", 300, None, |_| Ok(())).await.is_err());
+    assert!(query_executor::stream_select(&mut client, "SELECT 1; SELECT 2", 300, None, |_| Ok(())).await.is_err());
+}
+
+#[tokio::test]
+async fn test_numeric_interval_and_timetz_match_server_text() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Skipping test - cannot connect to database: {}", e);
+            return;
+        }
+    };
+    
+    // Each value is returned next to the server's own text output
+    let values = [
+        "0::numeric", "12.50::numeric", "-0.0001::numeric", "123456789012345678901234567890.123456789::numeric",
+        "10000::numeric", "-99990000.5::numeric", "'NaN'::numeric", "1e-20::numeric",
+        "'1 year 2 mons 3 days 04:05:06.5'::interval", "'-1 day'::interval", "'0'::interval",
+        "'36:00:00'::interval", "'-00:00:01.25'::interval", "'14 mons'::interval",
+        "'04:05:06+05:30'::timetz", "'23:59:59.999-08'::timetz", "'00:00:00+00'::timetz",
+    ];
+    for value in values {
+        let sql = format!("SELECT {} AS v, ({})::text AS t", value, value);
+        let result = query_executor::execute_sql(&client, &sql).await;
+        assert_eq!(result.result_type, QueryResultType::Select, "{}: {:?}", value, result.error);
+        let row = &result.rows.unwrap()[0];
+        assert_eq!(row["v"], row["t"], "{}", value);
+    }
+    
+    let result = query_executor::execute_sql(&client, "SELECT '{\"a\": [1]}'::jsonb AS j, NULL::numeric AS n").await;
+    let row = &result.rows.unwrap()[0];
+    assert_eq!(row["j"], serde_json::json!({"a": [1]}));
+    assert!(row["n"].is_null());
+}
@@ -0,0 +1,126 @@
+/**
+ * Integration tests for Query Templates
+ *
+ * 这些测试验证查询模板的保存和执行：
+ * - 模板保存到文件，记录参数名，可以更新和删除
+ * - 两个命名参数按推断的类型绑定（整数、日期），值不会拼接到 SQL 中
+ * - 缺少参数值时不执行
+ */
+
+use pg_db_tool::models::query::QueryResultType;
+use pg_db_tool::services::query_templates::{self, QueryTemplateStore};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+fn temp_store(name: &str) -> QueryTemplateStore {
+    let path = std::env::temp_dir().join(format!("pg_db_tool_{}_{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    QueryTemplateStore::new(path)
+}
+
+#[test]
+fn test_save_update_and_delete_template() {
+    let store = temp_store("templates_crud");
+
+    let template = store
+        .upsert(None, "按部门查询", "SELECT * FROM employees WHERE dept = :dept AND age > :age")
+        .unwrap();
+    assert_eq!(template.parameters, vec!["dept", "age"]);
+    assert_eq!(store.load().unwrap().len(), 1);
+
+    let updated = store
+        .upsert(Some(&template.id), "按部门查询", "SELECT * FROM employees WHERE dept = :dept")
+        .unwrap();
+    assert_eq!(updated.id, template.id);
+    assert_eq!(store.get(&template.id).unwrap().parameters, vec!["dept"]);
+
+    assert!(store.upsert(None, "  ", "SELECT 1").is_err());
+    assert!(store.upsert(Some("missing"), "x", "SELECT 1").is_err());
+
+    store.delete(&template.id).unwrap();
+    assert!(store.load().unwrap().is_empty());
+    assert!(store.delete(&template.id).is_err());
+}
+
+#[tokio::test]
+async fn test_run_template_with_two_named_parameters() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "CREATE TEMP TABLE template_people (name TEXT, age INT, joined DATE);
+             INSERT INTO template_people VALUES
+                 ('Alice', 35, '2020-05-01'),
+                 ('Bob', 28, '2019-03-15'),
+                 ('Carol', 41, '2024-06-30'),
+                 ('Dave', 52, '2018-11-20');",
+        )
+        .await
+        .unwrap();
+
+    let store = temp_store("templates_run");
+    let template = store
+        .upsert(
+            None,
+            "资深员工",
+            "SELECT name FROM template_people WHERE age >= :min_age AND joined < :joined_before ORDER BY name",
+        )
+        .unwrap();
+
+    let mut params = HashMap::new();
+    params.insert("min_age".to_string(), json!(30));
+    params.insert("joined_before".to_string(), json!("2024-01-01"));
+
+    let result = query_templates::run_template(&client, &template, &params).await.unwrap();
+    assert_eq!(result.result_type, QueryResultType::Select, "{:?}", result.error);
+    let names: Vec<&str> = result
+        .rows
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|row| row["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Alice", "Dave"]);
+
+    // 值作为参数绑定，不会被当作 SQL 执行
+    params.insert("joined_before".to_string(), json!("2024-01-01' OR '1'='1"));
+    let result = query_templates::run_template(&client, &template, &params).await.unwrap();
+    assert_eq!(result.result_type, QueryResultType::Error);
+
+    // 缺少参数值时不执行
+    params.remove("min_age");
+    let error = query_templates::run_template(&client, &template, &params).await.unwrap_err();
+    assert!(error.contains("min_age"), "{}", error);
+
+    let _ = std::fs::remove_file(std::env::temp_dir().join(format!("pg_db_tool_templates_run_{}.json", std::process::id())));
+}
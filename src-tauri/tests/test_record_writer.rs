@@ -0,0 +1,444 @@
+/**
+ * Integration tests for Record Writer
+ *
+ * 这些测试验证以字符串形式提交的值能按列类型正确插入：
+ * - uuid、date 等非文本类型
+ * - null 值
+ * - 布尔值、数字和包含引号、反斜杠、分号的字符串按字面插入
+ * - 不存在的列报错
+ * - 在非 public 模式中插入、更新、删除记录，不影响 public 中的同名表
+ * - 复合主键包含引号和反斜杠时按主键更新、删除，主键不匹配时报错
+ * - 没有主键的表拒绝不带定位条件的修改，可以按 ctid 更新、删除一行
+ */
+
+use pg_db_tool::services::record_writer::{self, ColumnTypeCache};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_insert_uuid_and_date_from_strings() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_record_writer;
+             CREATE TABLE test_record_writer (
+                 id UUID PRIMARY KEY,
+                 born_on DATE,
+                 address INET,
+                 salary NUMERIC(10, 2),
+                 note TEXT
+             );",
+        )
+        .await
+        .unwrap();
+
+    let cache = Mutex::new(ColumnTypeCache::new());
+    let data = json!({
+        "id": "6f1c2a9e-3b4d-4e5f-8a7b-9c0d1e2f3a4b",
+        "born_on": "1990-05-17",
+        "address": "192.168.1.10",
+        "salary": "12345.60",
+        "note": null
+    });
+
+    let inserted = record_writer::insert_record(
+        &client,
+        &cache,
+        "personnel_db",
+        "public",
+        "test_record_writer",
+        data.as_object().unwrap(),
+    )
+    .await
+    .expect("插入记录失败");
+    assert_eq!(inserted, 1);
+
+    let row = client
+        .query_one(
+            "SELECT id::text, born_on::text, host(address), salary::text, note FROM test_record_writer",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, String>(0), "6f1c2a9e-3b4d-4e5f-8a7b-9c0d1e2f3a4b");
+    assert_eq!(row.get::<_, String>(1), "1990-05-17");
+    assert_eq!(row.get::<_, String>(2), "192.168.1.10");
+    assert_eq!(row.get::<_, String>(3), "12345.60");
+    assert_eq!(row.get::<_, Option<String>>(4), None);
+
+    // 不存在的列应报错
+    let bad = json!({ "id": "00000000-0000-0000-0000-000000000001", "missing": "x" });
+    let result = record_writer::insert_record(
+        &client,
+        &cache,
+        "personnel_db",
+        "public",
+        "test_record_writer",
+        bad.as_object().unwrap(),
+    )
+    .await;
+    assert!(result.is_err());
+
+    // 缓存之后添加的列在第一次用到时重新读取
+    client
+        .batch_execute("ALTER TABLE test_record_writer ADD COLUMN added_later INTEGER")
+        .await
+        .unwrap();
+    let later = json!({ "id": "00000000-0000-0000-0000-000000000002", "added_later": "7" });
+    let inserted = record_writer::insert_record(
+        &client,
+        &cache,
+        "personnel_db",
+        "public",
+        "test_record_writer",
+        later.as_object().unwrap(),
+    )
+    .await
+    .expect("插入新添加的列失败");
+    assert_eq!(inserted, 1);
+
+    let _ = client.batch_execute("DROP TABLE test_record_writer").await;
+}
+
+#[tokio::test]
+async fn test_insert_values_literally() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_record_writer_literal;
+             CREATE TABLE test_record_writer_literal (
+                 id INT PRIMARY KEY,
+                 active BOOLEAN,
+                 ratio DOUBLE PRECISION,
+                 name TEXT,
+                 note TEXT
+             );",
+        )
+        .await
+        .unwrap();
+
+    let payload = "O'Brien\\n; DROP TABLE test_record_writer_literal; --";
+    let cache = Mutex::new(ColumnTypeCache::new());
+    let data = json!({
+        "id": 7,
+        "active": true,
+        "ratio": 0.25,
+        "name": payload,
+        "note": null
+    });
+
+    record_writer::insert_record(
+        &client,
+        &cache,
+        "personnel_db",
+        "public",
+        "test_record_writer_literal",
+        data.as_object().unwrap(),
+    )
+    .await
+    .expect("插入记录失败");
+
+    let row = client
+        .query_one("SELECT id, active, ratio, name, note FROM test_record_writer_literal", &[])
+        .await
+        .expect("表应仍然存在");
+    assert_eq!(row.get::<_, i32>(0), 7);
+    assert!(row.get::<_, bool>(1));
+    assert_eq!(row.get::<_, f64>(2), 0.25);
+    assert_eq!(row.get::<_, String>(3), payload);
+    assert_eq!(row.get::<_, Option<String>>(4), None);
+
+    let _ = client.batch_execute("DROP TABLE test_record_writer_literal").await;
+}
+
+#[tokio::test]
+async fn test_edit_record_in_non_public_schema() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP SCHEMA IF EXISTS test_record_writer_hr CASCADE;
+             CREATE SCHEMA test_record_writer_hr;
+             CREATE TABLE test_record_writer_hr.test_record_writer_people (id INT PRIMARY KEY, name TEXT, born_on DATE);
+             DROP TABLE IF EXISTS public.test_record_writer_people;
+             CREATE TABLE public.test_record_writer_people (id INT PRIMARY KEY, name TEXT, born_on DATE);
+             INSERT INTO public.test_record_writer_people VALUES (1, 'public', NULL);",
+        )
+        .await
+        .unwrap();
+
+    let cache = Mutex::new(ColumnTypeCache::new());
+    let schema = "test_record_writer_hr";
+    let table = "test_record_writer_people";
+
+    let data = json!({ "id": "1", "name": "Alice", "born_on": "1990-05-17" });
+    let inserted = record_writer::insert_record(&client, &cache, "personnel_db", schema, table, data.as_object().unwrap())
+        .await
+        .expect("插入记录失败");
+    assert_eq!(inserted, 1);
+
+    let key = json!({ "id": 1 });
+    let changes = json!({ "name": "Alice Smith", "born_on": "1991-01-02" });
+    let updated = record_writer::update_record(
+        &client,
+        &cache,
+        "personnel_db",
+        schema,
+        table,
+        key.as_object().unwrap(),
+        changes.as_object().unwrap(),
+    )
+    .await
+    .expect("更新记录失败");
+    assert_eq!(updated, 1);
+
+    let row = client
+        .query_one("SELECT name, born_on::text FROM test_record_writer_hr.test_record_writer_people WHERE id = 1", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, String>(0), "Alice Smith");
+    assert_eq!(row.get::<_, String>(1), "1991-01-02");
+
+    let deleted = record_writer::delete_record(&client, &cache, "personnel_db", schema, table, key.as_object().unwrap())
+        .await
+        .expect("删除记录失败");
+    assert_eq!(deleted, 1);
+
+    let remaining: i64 = client
+        .query_one("SELECT COUNT(*) FROM test_record_writer_hr.test_record_writer_people", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(remaining, 0);
+
+    // public 中的同名表不受影响
+    let public_name: String = client
+        .query_one("SELECT name FROM public.test_record_writer_people WHERE id = 1", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(public_name, "public");
+
+    let _ = client
+        .batch_execute(
+            "DROP SCHEMA test_record_writer_hr CASCADE;
+             DROP TABLE public.test_record_writer_people;",
+        )
+        .await;
+}
+
+#[tokio::test]
+async fn test_update_and_delete_by_composite_key() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            r"DROP TABLE IF EXISTS test_record_writer_composite;
+             CREATE TABLE test_record_writer_composite (
+                 region TEXT,
+                 code TEXT,
+                 amount INT,
+                 PRIMARY KEY (region, code)
+             );
+             INSERT INTO test_record_writer_composite VALUES
+                 ('O''Brien', 'a\b', 1),
+                 ('O''Brien', 'other', 2);",
+        )
+        .await
+        .unwrap();
+
+    let cache = Mutex::new(ColumnTypeCache::new());
+    let table = "test_record_writer_composite";
+    let key = json!({ "region": "O'Brien", "code": "a\\b" });
+    let changes = json!({ "amount": 10 });
+
+    let updated = record_writer::update_record(
+        &client,
+        &cache,
+        "personnel_db",
+        "public",
+        table,
+        key.as_object().unwrap(),
+        changes.as_object().unwrap(),
+    )
+    .await
+    .expect("更新记录失败");
+    assert_eq!(updated, 1);
+
+    let amounts: Vec<i32> = client
+        .query("SELECT amount FROM test_record_writer_composite ORDER BY code", &[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    assert_eq!(amounts, vec![10, 2]);
+
+    let deleted = record_writer::delete_record(&client, &cache, "personnel_db", "public", table, key.as_object().unwrap())
+        .await
+        .expect("删除记录失败");
+    assert_eq!(deleted, 1);
+
+    // 主键已不存在：更新和删除都应报错而不是静默成功
+    let stale_delete =
+        record_writer::delete_record(&client, &cache, "personnel_db", "public", table, key.as_object().unwrap()).await;
+    assert!(stale_delete.unwrap_err().contains("没有找到主键"));
+    let stale_update = record_writer::update_record(
+        &client,
+        &cache,
+        "personnel_db",
+        "public",
+        table,
+        key.as_object().unwrap(),
+        changes.as_object().unwrap(),
+    )
+    .await;
+    assert!(stale_update.is_err());
+
+    let _ = client.batch_execute("DROP TABLE test_record_writer_composite").await;
+}
+
+#[tokio::test]
+async fn test_edit_table_without_primary_key_by_ctid() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_record_writer_no_pk;
+             CREATE TABLE test_record_writer_no_pk (name TEXT, amount INT);
+             INSERT INTO test_record_writer_no_pk VALUES ('same', 1), ('same', 2);",
+        )
+        .await
+        .unwrap();
+
+    let cache = Mutex::new(ColumnTypeCache::new());
+    let table = "test_record_writer_no_pk";
+    let changes = json!({ "amount": 10 });
+
+    // 没有定位条件时拒绝修改，两行都保持不变
+    let no_key = json!({});
+    let error = record_writer::update_record(
+        &client,
+        &cache,
+        "personnel_db",
+        "public",
+        table,
+        no_key.as_object().unwrap(),
+        changes.as_object().unwrap(),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.contains("表没有主键"), "{}", error);
+    let error = record_writer::delete_record(&client, &cache, "personnel_db", "public", table, no_key.as_object().unwrap())
+        .await
+        .unwrap_err();
+    assert!(error.contains("表没有主键"), "{}", error);
+
+    let ctid: String = client
+        .query_one("SELECT ctid::text FROM test_record_writer_no_pk WHERE amount = 2", &[])
+        .await
+        .unwrap()
+        .get(0);
+    let key = json!({ "ctid": ctid });
+
+    let updated = record_writer::update_record(
+        &client,
+        &cache,
+        "personnel_db",
+        "public",
+        table,
+        key.as_object().unwrap(),
+        changes.as_object().unwrap(),
+    )
+    .await
+    .expect("按 ctid 更新记录失败");
+    assert_eq!(updated, 1);
+
+    let amounts: Vec<i32> = client
+        .query("SELECT amount FROM test_record_writer_no_pk ORDER BY amount", &[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    assert_eq!(amounts, vec![1, 10]);
+
+    // 更新后行的 ctid 已改变，旧的 ctid 不再匹配
+    let stale = record_writer::delete_record(&client, &cache, "personnel_db", "public", table, key.as_object().unwrap()).await;
+    assert!(stale.unwrap_err().contains("没有找到"));
+
+    let ctid: String = client
+        .query_one("SELECT ctid::text FROM test_record_writer_no_pk WHERE amount = 1", &[])
+        .await
+        .unwrap()
+        .get(0);
+    let deleted = record_writer::delete_record(&client, &cache, "personnel_db", "public", table, json!({ "ctid": ctid }).as_object().unwrap())
+        .await
+        .expect("按 ctid 删除记录失败");
+    assert_eq!(deleted, 1);
+
+    let remaining: i64 = client
+        .query_one("SELECT count(*) FROM test_record_writer_no_pk", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(remaining, 1);
+
+    let _ = client.batch_execute("DROP TABLE test_record_writer_no_pk").await;
+}
@@ -0,0 +1,140 @@
+/**
+ * Integration tests for Row Streamer
+ *
+ * 这些测试验证按主键 keyset 分页读取大表的行为，包括：
+ * - 每一行恰好被读取一次
+ * - 最后一批标记为 done，批次序号连续
+ * - 使用 last_key 从中断处继续读取
+ */
+
+use pg_db_tool::models::data::TableRowsBatch;
+use pg_db_tool::services::row_streamer;
+use std::collections::HashSet;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+async fn create_large_table(client: &tokio_postgres::Client, table: &str, rows: i32) {
+    client
+        .batch_execute(&format!(
+            "DROP TABLE IF EXISTS {table};
+             CREATE TABLE {table} (id INTEGER PRIMARY KEY, label TEXT);
+             INSERT INTO {table} SELECT g, 'row ' || g FROM generate_series(1, {rows}) g;",
+            table = table,
+            rows = rows
+        ))
+        .await
+        .expect("创建测试表失败");
+}
+
+async fn collect_batches(
+    client: &tokio_postgres::Client,
+    table: &str,
+    batch_size: u32,
+    after_key: Option<Vec<String>>,
+) -> (u64, Vec<TableRowsBatch>) {
+    let mut batches = Vec::new();
+    let total = row_streamer::stream_table_rows(client, "public", table, batch_size, after_key, |batch| {
+        batches.push(batch);
+        Ok(())
+    })
+    .await
+    .expect("流式读取失败");
+    (total, batches)
+}
+
+fn batch_ids(batches: &[TableRowsBatch]) -> Vec<i64> {
+    batches
+        .iter()
+        .flat_map(|b| b.rows.iter().map(|r| r["id"].as_i64().unwrap()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_stream_delivers_every_row_exactly_once() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let table = "test_row_streamer_large";
+    create_large_table(&client, table, 10_000).await;
+
+    // 批次大小不能整除总行数，也要测试恰好整除的情况
+    for batch_size in [512u32, 1000] {
+        let (total, batches) = collect_batches(&client, table, batch_size, None).await;
+        assert_eq!(total, 10_000);
+
+        let ids = batch_ids(&batches);
+        assert_eq!(ids.len(), 10_000);
+        let unique: HashSet<i64> = ids.iter().copied().collect();
+        assert_eq!(unique.len(), 10_000);
+        assert_eq!(ids, (1..=10_000).collect::<Vec<i64>>());
+
+        for (i, batch) in batches.iter().enumerate() {
+            assert_eq!(batch.batch_index, i as u64);
+            assert_eq!(batch.done, i == batches.len() - 1);
+            assert!(batch.rows.len() <= batch_size as usize);
+        }
+        assert_eq!(
+            batches.last().unwrap().last_key,
+            Some(vec!["10000".to_string()])
+        );
+    }
+
+    let _ = client.batch_execute(&format!("DROP TABLE {}", table)).await;
+}
+
+#[tokio::test]
+async fn test_stream_resumes_from_last_key() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let table = "test_row_streamer_resume";
+    create_large_table(&client, table, 250).await;
+
+    let (total, batches) = collect_batches(&client, table, 100, Some(vec!["120".to_string()])).await;
+    assert_eq!(total, 130);
+    assert_eq!(batch_ids(&batches), (121..=250).collect::<Vec<i64>>());
+
+    // 没有主键的表无法按 keyset 分页
+    client
+        .batch_execute("DROP TABLE IF EXISTS test_row_streamer_no_pk; CREATE TABLE test_row_streamer_no_pk (id INTEGER)")
+        .await
+        .unwrap();
+    let result = row_streamer::stream_table_rows(&client, "public", "test_row_streamer_no_pk", 100, None, |_| Ok(())).await;
+    assert!(result.is_err());
+
+    let _ = client
+        .batch_execute(&format!("DROP TABLE {}; DROP TABLE test_row_streamer_no_pk", table))
+        .await;
+}
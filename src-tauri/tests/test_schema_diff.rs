@@ -0,0 +1,107 @@
+/**
+ * Integration tests for Schema Diff
+ *
+ * 这些测试在两个实际数据库之间比较表结构，验证：
+ * - 只存在于一个数据库中的表
+ * - 两个数据库中同名表的列差异
+ * - 结构相同的表不出现在报告中
+ */
+
+use pg_db_tool::models::schema::DiffKind;
+use pg_db_tool::services::schema_diff;
+
+/// 获取指定数据库的连接
+async fn get_client(database: &str) -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+/// 重新创建一个空的测试数据库并返回其连接
+async fn recreate_database(admin: &tokio_postgres::Client, database: &str) -> tokio_postgres::Client {
+    admin
+        .batch_execute(&format!("DROP DATABASE IF EXISTS {}", database))
+        .await
+        .expect("删除测试数据库失败");
+    admin
+        .batch_execute(&format!("CREATE DATABASE {}", database))
+        .await
+        .expect("创建测试数据库失败");
+    get_client(database).await.expect("连接测试数据库失败")
+}
+
+#[tokio::test]
+async fn test_diff_database_schemas_reports_column_difference() {
+    let admin_database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let admin = match get_client(&admin_database).await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let source = recreate_database(&admin, "test_schema_diff_source").await;
+    let target = recreate_database(&admin, "test_schema_diff_target").await;
+
+    source
+        .batch_execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT);
+             CREATE TABLE audit_log (id BIGINT PRIMARY KEY);",
+        )
+        .await
+        .unwrap();
+    target
+        .batch_execute(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, email VARCHAR(255));
+             CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT);
+             CREATE TABLE feature_flags (name TEXT PRIMARY KEY);",
+        )
+        .await
+        .unwrap();
+
+    let report = schema_diff::diff_database_schemas(
+        &source,
+        &target,
+        "test_schema_diff_source",
+        "test_schema_diff_target",
+    )
+    .await
+    .expect("比较数据库结构失败");
+
+    assert_eq!(report.only_in_source, vec!["public.audit_log".to_string()]);
+    assert_eq!(report.only_in_target, vec!["public.feature_flags".to_string()]);
+
+    // 只有 users 表结构不同
+    assert_eq!(report.changed_tables.len(), 1);
+    let users = &report.changed_tables[0];
+    assert_eq!(users.table_name, "users");
+    assert_eq!(users.columns.len(), 1);
+    assert_eq!(users.columns[0].name, "email");
+    assert_eq!(users.columns[0].kind, DiffKind::Added);
+    assert!(users.constraints.is_empty());
+    assert!(users.indexes.is_empty());
+
+    drop(source);
+    drop(target);
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let _ = admin.batch_execute("DROP DATABASE IF EXISTS test_schema_diff_source").await;
+    let _ = admin.batch_execute("DROP DATABASE IF EXISTS test_schema_diff_target").await;
+}
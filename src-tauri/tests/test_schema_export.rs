@@ -0,0 +1,139 @@
+/**
+ * Integration tests for Schema Export
+ *
+ * 这些测试验证整库结构脚本的往返一致性：
+ * - 在空数据库中执行脚本可以重建模式、枚举、表、外键、视图、函数和触发器
+ * - 重建后的数据库表结构与原数据库相同，再次导出的脚本也完全相同
+ */
+
+use pg_db_tool::services::{schema_diff, schema_export};
+
+/// 获取指定数据库的连接
+async fn get_client(database: &str) -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+/// 重新创建一个空的测试数据库并返回其连接
+async fn recreate_database(admin: &tokio_postgres::Client, database: &str) -> tokio_postgres::Client {
+    admin
+        .batch_execute(&format!("DROP DATABASE IF EXISTS {}", database))
+        .await
+        .expect("删除测试数据库失败");
+    admin
+        .batch_execute(&format!("CREATE DATABASE {}", database))
+        .await
+        .expect("创建测试数据库失败");
+    get_client(database).await.expect("连接测试数据库失败")
+}
+
+#[tokio::test]
+async fn test_schema_script_recreates_database() {
+    let admin_database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let admin = match get_client(&admin_database).await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let source = recreate_database(&admin, "test_schema_export_source").await;
+    let target = recreate_database(&admin, "test_schema_export_target").await;
+
+    source
+        .batch_execute(
+            "CREATE SCHEMA sales;
+             COMMENT ON SCHEMA sales IS 'Sales data';
+             CREATE TYPE sales.order_status AS ENUM ('new', 'paid', 'it''s shipped');
+             CREATE SEQUENCE sales.invoice_no START 1000 INCREMENT 5;
+             CREATE FUNCTION sales.normalize_code(code text) RETURNS text
+                 LANGUAGE sql IMMUTABLE AS $$ SELECT upper(trim(code)) $$;
+             CREATE TABLE sales.orders (
+                 id INT PRIMARY KEY,
+                 customer_id INT,
+                 status sales.order_status DEFAULT 'new',
+                 invoice BIGINT DEFAULT nextval('sales.invoice_no')
+             );
+             CREATE TABLE sales.customers (
+                 id SERIAL PRIMARY KEY,
+                 code TEXT NOT NULL CHECK (code = sales.normalize_code(code)),
+                 last_order_id INT REFERENCES sales.orders (id)
+             );
+             ALTER TABLE sales.orders ADD FOREIGN KEY (customer_id) REFERENCES sales.customers (id);
+             CREATE INDEX orders_status_idx ON sales.orders (status);
+             CREATE VIEW sales.paid_orders AS SELECT * FROM sales.orders WHERE status = 'paid';
+             CREATE VIEW sales.a_paid_count AS SELECT count(*) AS total FROM sales.paid_orders;
+             CREATE MATERIALIZED VIEW sales.order_totals AS
+                 SELECT customer_id, count(*) AS orders FROM sales.orders GROUP BY customer_id;
+             CREATE FUNCTION sales.customer_orders(c sales.customers) RETURNS SETOF sales.orders
+                 LANGUAGE sql AS $$ SELECT * FROM sales.orders WHERE customer_id = c.id $$;
+             CREATE FUNCTION sales.touch() RETURNS trigger
+                 LANGUAGE plpgsql AS $$ BEGIN RETURN NEW; END $$;
+             CREATE TRIGGER orders_touch BEFORE UPDATE ON sales.orders
+                 FOR EACH ROW EXECUTE FUNCTION sales.touch();
+             COMMENT ON TYPE sales.order_status IS 'Order status';
+             COMMENT ON TABLE sales.orders IS 'Orders';
+             COMMENT ON VIEW sales.paid_orders IS 'Paid orders';
+             COMMENT ON FUNCTION sales.normalize_code(text) IS 'Normalize customer codes';",
+        )
+        .await
+        .unwrap();
+
+    let script = schema_export::script_database(&source, "shop")
+        .await
+        .expect("生成结构脚本失败");
+    assert!(script.warnings.is_empty(), "不应有警告: {:?}", script.warnings);
+
+    // 类型在表之前，表在外键之前，被引用的视图在引用它的视图之前
+    let position = |text: &str| script.script.find(text).unwrap_or_else(|| panic!("脚本中缺少 {}", text));
+    assert!(position("CREATE TYPE \"sales\".\"order_status\"") < position("CREATE TABLE sales.orders"));
+    assert!(position("CREATE TABLE sales.orders") < position("ALTER TABLE sales.orders ADD CONSTRAINT"));
+    assert!(position("CREATE VIEW \"sales\".\"paid_orders\"") < position("CREATE VIEW \"sales\".\"a_paid_count\""));
+
+    target
+        .batch_execute(&script.script)
+        .await
+        .unwrap_or_else(|e| panic!("执行结构脚本失败: {}\n{}", e, script.script));
+
+    let report = schema_diff::diff_database_schemas(
+        &source,
+        &target,
+        "test_schema_export_source",
+        "test_schema_export_target",
+    )
+    .await
+    .expect("比较数据库结构失败");
+    assert!(report.only_in_source.is_empty(), "缺少表: {:?}", report.only_in_source);
+    assert!(report.only_in_target.is_empty());
+    assert!(report.changed_tables.is_empty(), "表结构不同: {:?}", report.changed_tables);
+
+    // 视图、函数、触发器和注释同样被重建，再次导出的脚本完全相同
+    let recreated = schema_export::script_database(&target, "shop")
+        .await
+        .expect("生成重建后的结构脚本失败");
+    assert_eq!(recreated.script, script.script);
+
+    drop(source);
+    drop(target);
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let _ = admin.batch_execute("DROP DATABASE IF EXISTS test_schema_export_source").await;
+    let _ = admin.batch_execute("DROP DATABASE IF EXISTS test_schema_export_target").await;
+}
@@ -0,0 +1,87 @@
+/**
+ * Integration tests for schema creation and dropping
+ *
+ * 这些测试验证：
+ * - 创建模式（包括需要引用的名称和 IF NOT EXISTS）
+ * - 在新模式中建表后能列出其中的对象
+ * - 使用 CASCADE 删除模式及其中的表
+ */
+
+use pg_db_tool::services::{ddl_generator, schema_service};
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_create_schema_with_table_and_drop_cascade() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let schema = "Test Schema Mgmt";
+    let _ = client
+        .batch_execute("DROP SCHEMA IF EXISTS \"Test Schema Mgmt\" CASCADE")
+        .await;
+
+    ddl_generator::validate_schema_name(schema).expect("模式名称应有效");
+    client
+        .batch_execute(&ddl_generator::generate_create_schema(schema, None, false))
+        .await
+        .expect("创建模式失败");
+    assert!(schema_service::schema_exists(&client, schema).await.unwrap());
+
+    // IF NOT EXISTS 对已存在的模式不报错
+    client
+        .batch_execute(&ddl_generator::generate_create_schema(schema, None, true))
+        .await
+        .expect("IF NOT EXISTS 不应报错");
+
+    client
+        .batch_execute("CREATE TABLE \"Test Schema Mgmt\".orders (id SERIAL PRIMARY KEY, note TEXT)")
+        .await
+        .unwrap();
+
+    let dependents = schema_service::get_schema_dependents(&client, schema).await.unwrap();
+    assert!(
+        dependents.contains(&"table \"Test Schema Mgmt\".orders".to_string()),
+        "应列出模式中的表: {:?}",
+        dependents
+    );
+
+    // 不使用 CASCADE 时非空模式无法删除
+    assert!(client
+        .batch_execute(&ddl_generator::generate_drop_schema(schema, false))
+        .await
+        .is_err());
+
+    client
+        .batch_execute(&ddl_generator::generate_drop_schema(schema, true))
+        .await
+        .expect("CASCADE 删除模式失败");
+    assert!(!schema_service::schema_exists(&client, schema).await.unwrap());
+}
@@ -0,0 +1,683 @@
+/**
+ * Integration tests for Schema Service
+ *
+ * 这些测试验证在实际数据库中读取表结构相关信息的行为，包括：
+ * - 列默认值预览（常量、now()、序列）
+ * - 表存储参数（fillfactor、autovacuum）的创建、修改与读取
+ * - 修改列类型时使用 USING 子句转换已有数据
+ * - 编辑表单使用的列约束（长度限制、CHECK、枚举）
+ * - 表注释与列注释
+ * - 表空间的创建与读取、表空间列表
+ * - 视图与物化视图的列表和定义
+ * - 函数与存储过程的列表和定义
+ * - 序列的列表与重置
+ * - 表结构中的触发器
+ * - 建表时生成注释，并通过表结构读取回来
+ */
+
+use pg_db_tool::models::schema::{ColumnDefinition, ColumnModification, DefaultPreviewKind, TableChanges, TableDesign};
+use pg_db_tool::services::{ddl_generator, schema_service};
+use std::collections::HashMap;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_preview_defaults() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_preview_defaults;
+             CREATE TABLE test_preview_defaults (
+                 id SERIAL PRIMARY KEY,
+                 status VARCHAR(20) DEFAULT 'new',
+                 created_at TIMESTAMPTZ DEFAULT now(),
+                 note TEXT
+             );",
+        )
+        .await
+        .expect("创建测试表失败");
+
+    let previews = schema_service::preview_defaults(&client, "public", "test_preview_defaults")
+        .await
+        .expect("预览默认值失败");
+
+    // note 没有默认值，不应出现在结果中
+    let columns: Vec<&str> = previews.iter().map(|p| p.column.as_str()).collect();
+    assert_eq!(columns, vec!["id", "status", "created_at"]);
+
+    // 序列默认值只标记为 auto，不应被求值
+    assert_eq!(previews[0].kind, DefaultPreviewKind::Auto);
+    assert_eq!(previews[0].value, None);
+
+    // 常量默认值
+    assert_eq!(previews[1].kind, DefaultPreviewKind::Value);
+    assert_eq!(previews[1].value.as_deref(), Some("new"));
+
+    // now() 默认值应返回当前时间
+    assert_eq!(previews[2].kind, DefaultPreviewKind::Value);
+    let sample = previews[2].value.clone().expect("now() 应返回值");
+    let row = client
+        .query_one("SELECT abs(extract(epoch FROM now() - $1::text::timestamptz)) < 60", &[&sample])
+        .await
+        .unwrap();
+    let is_recent: bool = row.get(0);
+    assert!(is_recent);
+
+    // 预览不应消耗序列值，也不应留下未结束的事务
+    let row = client
+        .query_one("INSERT INTO test_preview_defaults DEFAULT VALUES RETURNING id", &[])
+        .await
+        .unwrap();
+    let id: i32 = row.get(0);
+    assert_eq!(id, 1);
+
+    let _ = client.batch_execute("DROP TABLE test_preview_defaults").await;
+}
+
+#[tokio::test]
+async fn test_storage_parameters_round_trip() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let _ = client.batch_execute("DROP TABLE IF EXISTS test_storage_params").await;
+
+    // 创建表时设置 fillfactor
+    let mut design = TableDesign {
+        table_name: "test_storage_params".to_string(),
+        schema: "public".to_string(),
+        columns: vec![ColumnDefinition::new("id".to_string(), "integer".to_string(), false)],
+        constraints: vec![],
+        indexes: vec![],
+        storage_parameters: HashMap::new(),
+        tablespace: None,
+        comment: None,
+    };
+    design.storage_parameters.insert("fillfactor".to_string(), "70".to_string());
+
+    client
+        .batch_execute(&ddl_generator::generate_create_table(&design))
+        .await
+        .expect("创建测试表失败");
+
+    let schema = schema_service::get_table_schema(&client, "public", "test_storage_params")
+        .await
+        .unwrap();
+    assert_eq!(schema.storage_parameters, design.storage_parameters);
+
+    // 修改 autovacuum 设置并重置 fillfactor
+    let mut set_storage_parameters = HashMap::new();
+    set_storage_parameters.insert("autovacuum_vacuum_scale_factor".to_string(), "0.1".to_string());
+    set_storage_parameters.insert("autovacuum_enabled".to_string(), "false".to_string());
+
+    let changes = TableChanges {
+        added_columns: vec![],
+        modified_columns: vec![],
+        dropped_columns: vec![],
+        added_constraints: vec![],
+        dropped_constraints: vec![],
+        renamed_constraints: vec![],
+        added_indexes: vec![],
+        dropped_indexes: vec![],
+        set_storage_parameters: set_storage_parameters.clone(),
+        reset_storage_parameters: vec!["fillfactor".to_string()],
+    };
+
+    for statement in ddl_generator::generate_alter_table("public", "test_storage_params", &changes) {
+        client.batch_execute(&statement).await.expect("修改存储参数失败");
+    }
+
+    let schema = schema_service::get_table_schema(&client, "public", "test_storage_params")
+        .await
+        .unwrap();
+    assert_eq!(schema.storage_parameters, set_storage_parameters);
+
+    let _ = client.batch_execute("DROP TABLE test_storage_params").await;
+}
+
+#[tokio::test]
+async fn test_alter_column_type_with_using_expression() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_alter_using;
+             CREATE TABLE test_alter_using (id INTEGER, amount TEXT);
+             INSERT INTO test_alter_using VALUES (1, '12'), (2, ' 7 '), (3, '');",
+        )
+        .await
+        .expect("创建测试表失败");
+
+    let mut modification = ColumnModification {
+        old_name: "amount".to_string(),
+        new_definition: ColumnDefinition {
+            name: "amount".to_string(),
+            data_type: "integer".to_string(),
+            character_maximum_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            is_nullable: true,
+            column_default: None,
+            generated_expression: None,
+            identity: None,
+            is_primary_key: false,
+            is_unique: false,
+            comment: None,
+        },
+        old_definition: None,
+        using_expression: None,
+    };
+    let table_schema = schema_service::get_table_schema(&client, "public", "test_alter_using")
+        .await
+        .unwrap();
+    modification.old_definition = table_schema.columns.into_iter().find(|c| c.name == "amount");
+    let changes = |modification: &ColumnModification| TableChanges {
+        added_columns: vec![],
+        modified_columns: vec![modification.clone()],
+        dropped_columns: vec![],
+        added_constraints: vec![],
+        dropped_constraints: vec![],
+        renamed_constraints: vec![],
+        added_indexes: vec![],
+        dropped_indexes: vec![],
+        set_storage_parameters: HashMap::new(),
+        reset_storage_parameters: vec![],
+    };
+
+    // 只有类型变化；text -> integer 没有隐式转换，不带 USING 时失败
+    let statements = ddl_generator::generate_alter_table("public", "test_alter_using", &changes(&modification));
+    assert_eq!(statements.len(), 1);
+    assert!(client.batch_execute(&statements[0]).await.is_err());
+
+    modification.using_expression = Some("NULLIF(trim(amount), '')::integer".to_string());
+    for statement in ddl_generator::generate_alter_table("public", "test_alter_using", &changes(&modification)) {
+        client.batch_execute(&statement).await.expect("修改列类型失败");
+    }
+
+    let rows = client
+        .query("SELECT amount FROM test_alter_using ORDER BY id", &[])
+        .await
+        .unwrap();
+    let amounts: Vec<Option<i32>> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(amounts, vec![Some(12), Some(7), None]);
+
+    let _ = client.batch_execute("DROP TABLE test_alter_using").await;
+}
+
+#[tokio::test]
+async fn test_get_edit_constraints() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_edit_constraints;
+             DROP TYPE IF EXISTS test_edit_status;
+             CREATE TYPE test_edit_status AS ENUM ('draft', 'published', 'archived');
+             CREATE TABLE test_edit_constraints (
+                 id SERIAL PRIMARY KEY,
+                 code VARCHAR(10) NOT NULL,
+                 price NUMERIC(8,2) CHECK (price >= 0),
+                 status test_edit_status DEFAULT 'draft',
+                 CONSTRAINT code_upper CHECK (code = upper(code))
+             );",
+        )
+        .await
+        .expect("创建测试表失败");
+
+    let constraints = schema_service::get_edit_constraints(&client, "public", "test_edit_constraints")
+        .await
+        .expect("获取编辑约束失败");
+
+    let names: Vec<&str> = constraints.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["id", "code", "price", "status"]);
+
+    let code = &constraints[1];
+    assert!(!code.nullable);
+    assert_eq!(code.max_length, Some(10));
+    assert_eq!(code.check_expressions.len(), 1);
+    assert!(code.check_expressions[0].contains("upper"));
+
+    let price = &constraints[2];
+    assert!(price.nullable);
+    assert_eq!(price.numeric_precision, Some(8));
+    assert_eq!(price.numeric_scale, Some(2));
+    assert_eq!(price.check_expressions.len(), 1);
+    assert!(price.check_expressions[0].contains(">= "));
+
+    let status = &constraints[3];
+    assert_eq!(
+        status.enum_options,
+        Some(vec!["draft".to_string(), "published".to_string(), "archived".to_string()])
+    );
+    assert!(status.default.as_deref().unwrap().contains("draft"));
+    assert!(status.check_expressions.is_empty());
+    assert!(constraints[0].enum_options.is_none());
+
+    let _ = client
+        .batch_execute("DROP TABLE test_edit_constraints; DROP TYPE test_edit_status;")
+        .await;
+}
+
+#[tokio::test]
+async fn test_table_documentation() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_table_documentation;
+             CREATE TABLE test_table_documentation (id INT PRIMARY KEY, email TEXT, note TEXT);
+             COMMENT ON TABLE test_table_documentation IS '用户表';
+             COMMENT ON COLUMN test_table_documentation.email IS '登录邮箱';
+             ALTER TABLE test_table_documentation DROP COLUMN note;
+             ALTER TABLE test_table_documentation ADD COLUMN created_at TIMESTAMPTZ;",
+        )
+        .await
+        .expect("创建测试表失败");
+
+    let documentation = schema_service::get_table_documentation(&client, "public", "test_table_documentation")
+        .await
+        .expect("获取表注释失败");
+
+    assert_eq!(documentation.table_comment, "用户表");
+
+    // 已删除的列不应出现，没有注释的列返回空字符串
+    let columns: Vec<(&str, &str)> = documentation
+        .columns
+        .iter()
+        .map(|c| (c.name.as_str(), c.comment.as_str()))
+        .collect();
+    assert_eq!(columns, vec![("id", ""), ("email", "登录邮箱"), ("created_at", "")]);
+
+    // 删除表注释后返回空字符串
+    client
+        .batch_execute("COMMENT ON TABLE test_table_documentation IS NULL")
+        .await
+        .unwrap();
+    let documentation = schema_service::get_table_documentation(&client, "public", "test_table_documentation")
+        .await
+        .expect("获取表注释失败");
+    assert_eq!(documentation.table_comment, "");
+
+    let missing = schema_service::get_table_documentation(&client, "public", "no_such_documented_table").await;
+    assert!(missing.is_err());
+
+    let _ = client.batch_execute("DROP TABLE test_table_documentation").await;
+}
+
+#[tokio::test]
+async fn test_list_tablespaces() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let tablespaces = schema_service::list_tablespaces(&client).await.expect("列出表空间失败");
+
+    let pg_default = tablespaces.iter().find(|t| t.name == "pg_default").expect("应包含 pg_default");
+    assert!(pg_default.location.is_none(), "内置表空间没有目录");
+    assert!(tablespaces.iter().any(|t| t.name == "pg_global"));
+    assert_eq!(tablespaces.iter().filter(|t| t.is_database_default).count(), 1);
+}
+
+#[tokio::test]
+async fn test_tablespace_round_trip() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let design = |tablespace: &str| TableDesign {
+        table_name: "test_tablespace_table".to_string(),
+        schema: "public".to_string(),
+        columns: vec![ColumnDefinition::new("id".to_string(), "integer".to_string(), false)],
+        constraints: vec![],
+        indexes: vec![],
+        storage_parameters: HashMap::new(),
+        tablespace: Some(tablespace.to_string()),
+        comment: None,
+    };
+
+    // 显式指定数据库的默认表空间时，PostgreSQL 不记录表空间，读取结果为 None
+    let _ = client.batch_execute("DROP TABLE IF EXISTS test_tablespace_table").await;
+    client
+        .batch_execute(&ddl_generator::generate_create_table(&design("pg_default")))
+        .await
+        .expect("创建测试表失败");
+    let schema = schema_service::get_table_schema(&client, "public", "test_tablespace_table")
+        .await
+        .unwrap();
+    assert_eq!(schema.tablespace, None);
+    let _ = client.batch_execute("DROP TABLE test_tablespace_table").await;
+
+    // 非默认表空间需要事先在服务器上创建，通过 PG_TEST_TABLESPACE 指定
+    let tablespace = match std::env::var("PG_TEST_TABLESPACE") {
+        Ok(name) => name,
+        Err(_) => {
+            eprintln!("未设置 PG_TEST_TABLESPACE，跳过非默认表空间测试");
+            return;
+        }
+    };
+
+    client
+        .batch_execute(&ddl_generator::generate_create_table(&design(&tablespace)))
+        .await
+        .expect("在指定表空间中创建表失败");
+    let schema = schema_service::get_table_schema(&client, "public", "test_tablespace_table")
+        .await
+        .unwrap();
+    assert_eq!(schema.tablespace, Some(tablespace));
+
+    let _ = client.batch_execute("DROP TABLE test_tablespace_table").await;
+}
+
+#[tokio::test]
+async fn test_list_views_and_definition() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP MATERIALIZED VIEW IF EXISTS test_views_matview;
+             DROP VIEW IF EXISTS test_views_view;
+             DROP TABLE IF EXISTS test_views_base;
+             CREATE TABLE test_views_base (id INT, active BOOLEAN);
+             CREATE VIEW test_views_view AS SELECT id FROM test_views_base WHERE active;
+             CREATE MATERIALIZED VIEW test_views_matview AS SELECT count(*) AS total FROM test_views_base;",
+        )
+        .await
+        .unwrap();
+
+    let views = schema_service::list_views(&client).await.expect("列出视图失败");
+    let find = |name: &str| views.iter().find(|v| v.schema == "public" && v.name == name);
+    assert!(!find("test_views_view").expect("应包含普通视图").is_materialized);
+    assert!(find("test_views_matview").expect("应包含物化视图").is_materialized);
+    assert!(find("test_views_base").is_none());
+
+    let definition = schema_service::get_view_definition(&client, "public", "test_views_view")
+        .await
+        .expect("获取视图定义失败");
+    assert!(definition.contains("FROM test_views_base"), "{}", definition);
+    assert!(definition.contains("WHERE"), "{}", definition);
+
+    let definition = schema_service::get_view_definition(&client, "public", "test_views_matview")
+        .await
+        .expect("获取物化视图定义失败");
+    assert!(definition.contains("count(*)"), "{}", definition);
+
+    assert!(schema_service::get_view_definition(&client, "public", "test_views_base").await.is_err());
+
+    client
+        .batch_execute(
+            "DROP MATERIALIZED VIEW test_views_matview;
+             DROP VIEW test_views_view;
+             DROP TABLE test_views_base;",
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_list_functions_and_definition() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP SCHEMA IF EXISTS test_functions CASCADE;
+             CREATE SCHEMA test_functions;
+             CREATE FUNCTION test_functions.add(a integer, b integer) RETURNS integer
+                 LANGUAGE sql AS 'SELECT a + b';
+             CREATE FUNCTION test_functions.add(a text, b text) RETURNS text
+                 LANGUAGE sql AS 'SELECT a || b';
+             CREATE PROCEDURE test_functions.noop() LANGUAGE plpgsql AS $$ BEGIN NULL; END $$;
+             CREATE AGGREGATE test_functions.total(integer) (SFUNC = int4pl, STYPE = integer);",
+        )
+        .await
+        .unwrap();
+
+    let functions = schema_service::list_functions(&client, "test_functions").await.expect("列出函数失败");
+    // 聚合函数不在列表中
+    assert_eq!(functions.len(), 3);
+
+    let add = functions.iter().find(|f| f.arguments == "a integer, b integer").expect("应包含整数版本");
+    assert_eq!(add.name, "add");
+    assert_eq!(add.return_type.as_deref(), Some("integer"));
+    assert_eq!(add.language, "sql");
+    assert!(!add.is_procedure);
+
+    let noop = functions.iter().find(|f| f.name == "noop").unwrap();
+    assert!(noop.is_procedure);
+    assert!(noop.return_type.is_none());
+    assert_eq!(noop.language, "plpgsql");
+
+    // 通过参数列表区分重载
+    let definition = schema_service::get_function_definition(&client, "test_functions", "add", "a text, b text")
+        .await
+        .expect("获取函数定义失败");
+    assert!(definition.starts_with("CREATE OR REPLACE FUNCTION test_functions.add(a text, b text)"), "{}", definition);
+    assert!(definition.contains("a || b"));
+
+    assert!(schema_service::get_function_definition(&client, "test_functions", "add", "a bigint").await.is_err());
+    assert!(schema_service::get_function_definition(&client, "test_functions", "total", "integer").await.is_err());
+
+    client.batch_execute("DROP SCHEMA test_functions CASCADE").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_list_and_reset_sequences() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP SCHEMA IF EXISTS test_sequences CASCADE;
+             CREATE SCHEMA test_sequences;
+             CREATE TABLE test_sequences.items (id serial PRIMARY KEY, name text);
+             CREATE SEQUENCE test_sequences.standalone INCREMENT BY 5 START WITH 100;
+             INSERT INTO test_sequences.items (id, name) VALUES (1, 'a'), (2, 'b'), (10, 'c');",
+        )
+        .await
+        .unwrap();
+
+    let sequences = schema_service::list_sequences(&client, "test_sequences").await.expect("列出序列失败");
+    assert_eq!(sequences.len(), 2);
+
+    let serial = sequences.iter().find(|s| s.name == "items_id_seq").expect("应包含 serial 列的序列");
+    assert_eq!(serial.data_type, "integer");
+    assert_eq!(serial.owned_by.as_deref(), Some("test_sequences.items.id"));
+    // 显式插入 id 不会推进序列
+    assert!(serial.last_value.is_none());
+
+    let standalone = sequences.iter().find(|s| s.name == "standalone").unwrap();
+    assert_eq!(standalone.start_value, 100);
+    assert_eq!(standalone.increment, 5);
+    assert!(standalone.owned_by.is_none());
+
+    // 导入后把序列对齐到 MAX(id) + 1
+    schema_service::reset_sequence(&client, "test_sequences", "items_id_seq", 11).await.expect("重置序列失败");
+    let row = client
+        .query_one("INSERT INTO test_sequences.items (name) VALUES ('d') RETURNING id", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i32>(0), 11);
+
+    let sequences = schema_service::list_sequences(&client, "test_sequences").await.unwrap();
+    let serial = sequences.iter().find(|s| s.name == "items_id_seq").unwrap();
+    assert_eq!(serial.last_value, Some(11));
+
+    // 超出范围的值由数据库拒绝
+    assert!(schema_service::reset_sequence(&client, "test_sequences", "items_id_seq", 0).await.is_err());
+    assert!(schema_service::reset_sequence(&client, "test_sequences", "missing", 1).await.is_err());
+
+    client.batch_execute("DROP SCHEMA test_sequences CASCADE").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_table_schema_includes_triggers() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP SCHEMA IF EXISTS test_triggers CASCADE;
+             CREATE SCHEMA test_triggers;
+             CREATE TABLE test_triggers.parents (id integer PRIMARY KEY);
+             CREATE TABLE test_triggers.items (
+                 id integer PRIMARY KEY,
+                 parent_id integer REFERENCES test_triggers.parents (id),
+                 updated_at timestamp
+             );
+             CREATE FUNCTION test_triggers.touch() RETURNS trigger LANGUAGE plpgsql AS $$
+                 BEGIN NEW.updated_at := now(); RETURN NEW; END $$;
+             CREATE TRIGGER items_touch BEFORE INSERT OR UPDATE ON test_triggers.items
+                 FOR EACH ROW EXECUTE FUNCTION test_triggers.touch();
+             CREATE TRIGGER items_audit AFTER DELETE OR TRUNCATE ON test_triggers.items
+                 FOR EACH STATEMENT EXECUTE FUNCTION test_triggers.touch();
+             ALTER TABLE test_triggers.items DISABLE TRIGGER items_audit;",
+        )
+        .await
+        .unwrap();
+
+    let table_schema = schema_service::get_table_schema(&client, "test_triggers", "items")
+        .await
+        .expect("获取表结构失败");
+    // 外键的内部触发器不在列表中
+    assert_eq!(table_schema.triggers.len(), 2);
+
+    let audit = &table_schema.triggers[0];
+    assert_eq!(audit.name, "items_audit");
+    assert_eq!(audit.timing, "AFTER");
+    assert_eq!(audit.events, vec!["DELETE", "TRUNCATE"]);
+    assert!(!audit.for_each_row);
+    assert!(!audit.enabled);
+
+    let touch = &table_schema.triggers[1];
+    assert_eq!(touch.name, "items_touch");
+    assert_eq!(touch.timing, "BEFORE");
+    assert_eq!(touch.events, vec!["INSERT", "UPDATE"]);
+    assert!(touch.for_each_row);
+    assert_eq!(touch.function, "test_triggers.touch");
+    assert!(touch.enabled);
+
+    let triggers = schema_service::list_triggers(&client, "test_triggers", "parents").await.unwrap();
+    assert!(triggers.is_empty());
+
+    client.batch_execute("DROP SCHEMA test_triggers CASCADE").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_comments_round_trip() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let _ = client.batch_execute("DROP TABLE IF EXISTS test_comments_round_trip").await;
+
+    let mut name = ColumnDefinition::new("name".to_string(), "text".to_string(), true);
+    name.comment = Some("Customer's name".to_string());
+    let design = TableDesign {
+        table_name: "test_comments_round_trip".to_string(),
+        schema: "public".to_string(),
+        columns: vec![ColumnDefinition::new("id".to_string(), "integer".to_string(), false), name],
+        constraints: vec![],
+        indexes: vec![],
+        storage_parameters: HashMap::new(),
+        tablespace: None,
+        comment: Some("客户表".to_string()),
+    };
+
+    client
+        .batch_execute(&ddl_generator::generate_create_table(&design))
+        .await
+        .expect("创建测试表失败");
+
+    let schema = schema_service::get_table_schema(&client, "public", "test_comments_round_trip")
+        .await
+        .unwrap();
+    assert_eq!(schema.comment.as_deref(), Some("客户表"));
+    assert_eq!(schema.columns[0].comment, None);
+    assert_eq!(schema.columns[1].comment.as_deref(), Some("Customer's name"));
+
+    let _ = client.batch_execute("DROP TABLE test_comments_round_trip").await;
+}
@@ -0,0 +1,81 @@
+/**
+ * Integration tests for Startup Check
+ *
+ * 这些测试验证启动连接检查的状态报告：
+ * - 可以连接时返回 ok 和服务器版本，连接保留在连接池中
+ * - 无法连接时返回 failed 和失败原因，而不是错误
+ */
+
+use pg_db_tool::services::connection_router::{
+    EndpointConfig, ReconnectPolicy, RoutingConfig, DEFAULT_CONNECT_TIMEOUT, DEFAULT_POOL_MAX_SIZE,
+};
+use pg_db_tool::services::startup_check::{self, StartupConnectionState};
+use pg_db_tool::services::tls::TlsSettings;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+fn routing_config(host: String, port: String) -> RoutingConfig {
+    RoutingConfig {
+        write: EndpointConfig {
+            host,
+            port,
+            user: std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string()),
+            password: std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string()),
+            tls: TlsSettings::default(),
+        },
+        read: None,
+        prefer_replica_for_reads: false,
+        reconnect: ReconnectPolicy {
+            max_retries: 0,
+            ..ReconnectPolicy::default()
+        },
+        connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+        pool_max_size: DEFAULT_POOL_MAX_SIZE,
+    }
+}
+
+#[tokio::test]
+async fn test_reachable_database_reports_ok() {
+    let routing = routing_config(
+        std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string()),
+        std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string()),
+    );
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+    let pools = Mutex::new(HashMap::new());
+
+    let status = startup_check::check_connection(&pools, &routing, &database, Duration::from_secs(10)).await;
+    if status.state == StartupConnectionState::Failed {
+        eprintln!("无法连接到测试数据库: {:?}. 跳过测试", status.error);
+        return;
+    }
+
+    assert_eq!(status.state, StartupConnectionState::Ok);
+    assert_eq!(status.database, database);
+    assert!(status.server_version.is_some());
+    assert!(status.error.is_none());
+    assert!(status.checked_at.is_some());
+    // 检查时建立的连接供后续命令复用
+    let pools = pools.lock().await;
+    assert_eq!(pools.len(), 1);
+    assert!(pools.values().all(|pool| pool.status().size == 1));
+}
+
+#[tokio::test]
+async fn test_unreachable_database_reports_failed() {
+    // 端口 1 上没有 PostgreSQL 服务
+    let routing = routing_config("127.0.0.1".to_string(), "1".to_string());
+    let pools = Mutex::new(HashMap::new());
+
+    let status = startup_check::check_connection(&pools, &routing, "postgres", Duration::from_secs(10)).await;
+
+    assert_eq!(status.state, StartupConnectionState::Failed);
+    assert_eq!(status.host, "127.0.0.1:1");
+    assert!(status.server_version.is_none());
+    assert!(status.error.as_deref().is_some_and(|e| !e.is_empty()));
+    assert!(status.checked_at.is_some());
+    assert!(pools.lock().await.values().all(|pool| pool.status().size == 0));
+
+    let serialized = serde_json::to_value(&status).unwrap();
+    assert_eq!(serialized["state"], "failed");
+}
@@ -0,0 +1,425 @@
+/**
+ * Integration tests for Table Data
+ *
+ * 这些测试验证分页读取表数据：
+ * - 默认模式返回带类型的 JSON 值
+ * - 原始模式返回 PostgreSQL 的文本形式
+ * - 分页和总行数，以及按统计信息估算的总行数
+ * - 主键列列表
+ * - 没有主键的表每行附带 ctid，可以用它更新该行
+ */
+
+use pg_db_tool::models::data::{ColumnFilter, FilterOperator, SortColumn, SortDirection};
+use pg_db_tool::services::record_writer::{self, ColumnTypeCache};
+use pg_db_tool::services::table_data::{self, TableQuery};
+use tokio::sync::Mutex;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_typed_and_raw_output() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "SET TimeZone = 'UTC';
+             DROP TABLE IF EXISTS test_table_data_raw;
+             CREATE TABLE test_table_data_raw (id INT PRIMARY KEY, created_at TIMESTAMPTZ, amount NUMERIC(10, 2), note TEXT);
+             INSERT INTO test_table_data_raw VALUES (1, '2024-03-01 12:34:56+00', 12.50, NULL);",
+        )
+        .await
+        .unwrap();
+
+    let typed = table_data::read_table_page(&client, "public", "test_table_data_raw", 1, 50, false, &TableQuery::default())
+        .await
+        .expect("读取表数据失败");
+    let raw = table_data::read_table_page(&client, "public", "test_table_data_raw", 1, 50, true, &TableQuery::default())
+        .await
+        .expect("读取表数据失败");
+
+    assert_eq!(typed.columns.len(), 4);
+    assert!(typed.columns[0].is_primary_key);
+    assert_eq!(typed.columns[1].type_name, "timestamp with time zone");
+    assert_eq!(typed.total_rows, 1);
+    assert_eq!(raw.total_rows, 1);
+
+    // 默认模式：row_to_json 使用 ISO 8601 格式，数字为 JSON 数字
+    let typed_row = &typed.rows[0];
+    assert_eq!(typed_row["id"], serde_json::json!(1));
+    assert_eq!(typed_row["created_at"], serde_json::json!("2024-03-01T12:34:56+00:00"));
+    assert!(typed_row["amount"].is_number());
+    assert!(typed_row["note"].is_null());
+
+    // 原始模式：文本形式遵循 DateStyle，保留 numeric 的精度
+    let raw_row = &raw.rows[0];
+    assert_eq!(raw_row["id"], serde_json::json!("1"));
+    assert_eq!(raw_row["created_at"], serde_json::json!("2024-03-01 12:34:56+00"));
+    assert_eq!(raw_row["amount"], serde_json::json!("12.50"));
+    assert!(raw_row["note"].is_null());
+
+    client.batch_execute("DROP TABLE test_table_data_raw").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_pagination() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_table_data_pages;
+             CREATE TABLE test_table_data_pages (id INT);
+             INSERT INTO test_table_data_pages SELECT generate_series(1, 25);",
+        )
+        .await
+        .unwrap();
+
+    let last_page = table_data::read_table_page(&client, "public", "test_table_data_pages", 3, 10, false, &TableQuery::default())
+        .await
+        .expect("读取表数据失败");
+    assert_eq!(last_page.total_rows, 25);
+    assert_eq!(last_page.rows.len(), 5);
+
+    let missing = table_data::read_table_page(&client, "public", "no_such_table_data", 1, 10, false, &TableQuery::default()).await;
+    assert!(missing.is_err());
+
+    client.batch_execute("DROP TABLE test_table_data_pages").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_primary_key_columns() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_table_data_lines;
+             DROP TABLE IF EXISTS test_table_data_no_pk;
+             CREATE TABLE test_table_data_lines (
+                 note TEXT,
+                 line SMALLINT,
+                 order_id BIGINT,
+                 PRIMARY KEY (order_id, line)
+             );
+             CREATE TABLE test_table_data_no_pk (id INT);",
+        )
+        .await
+        .unwrap();
+
+    // 复合主键按主键定义顺序返回，而不是列顺序
+    let lines = table_data::read_table_page(&client, "public", "test_table_data_lines", 1, 10, false, &TableQuery::default())
+        .await
+        .expect("读取表数据失败");
+    assert_eq!(lines.primary_key_columns, vec!["order_id", "line"]);
+    assert!(!lines.columns[0].is_primary_key);
+    assert!(lines.columns[1].is_primary_key);
+    assert!(lines.columns[2].is_primary_key);
+
+    let no_pk = table_data::read_table_page(&client, "public", "test_table_data_no_pk", 1, 10, false, &TableQuery::default())
+        .await
+        .expect("读取表数据失败");
+    assert!(no_pk.primary_key_columns.is_empty());
+    assert_eq!(no_pk.row_id_column.as_deref(), Some("ctid"));
+    assert!(lines.row_id_column.is_none());
+
+    client
+        .batch_execute("DROP TABLE test_table_data_lines; DROP TABLE test_table_data_no_pk;")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_filters_and_sort() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_table_data_filter;
+             CREATE TABLE test_table_data_filter (id INT PRIMARY KEY, name TEXT, joined DATE);
+             INSERT INTO test_table_data_filter
+             SELECT g, CASE WHEN g % 5 = 0 THEN NULL ELSE 'user ' || g END, DATE '2024-01-01' + g
+             FROM generate_series(1, 40) g;",
+        )
+        .await
+        .unwrap();
+
+    let filter = |column: &str, operator, value| ColumnFilter {
+        column: column.to_string(),
+        operator,
+        value,
+    };
+
+    // 按日期过滤并倒序排列，总行数为过滤后的行数
+    let query = TableQuery {
+        filters: vec![
+            filter("joined", FilterOperator::Gt, serde_json::json!("2024-01-21")),
+            filter("name", FilterOperator::Like, serde_json::json!("user 3%")),
+        ],
+        sort: vec![SortColumn {
+            column: "id".to_string(),
+            direction: SortDirection::Desc,
+        }],
+        ..Default::default()
+    };
+    let page = table_data::read_table_page(&client, "public", "test_table_data_filter", 1, 3, false, &query)
+        .await
+        .expect("读取表数据失败");
+    assert_eq!(page.total_rows, 8);
+    let ids: Vec<i64> = page.rows.iter().map(|r| r["id"].as_i64().unwrap()).collect();
+    assert_eq!(ids, vec![39, 38, 37]);
+
+    let raw = table_data::read_table_page(&client, "public", "test_table_data_filter", 3, 3, true, &query)
+        .await
+        .expect("读取表数据失败");
+    assert_eq!(raw.rows.len(), 2);
+    assert_eq!(raw.rows[1]["id"], "31");
+
+    let nulls = TableQuery {
+        filters: vec![
+            filter("name", FilterOperator::IsNull, serde_json::Value::Null),
+            filter("id", FilterOperator::NotEq, serde_json::json!(10)),
+            filter("id", FilterOperator::Lt, serde_json::json!(30)),
+        ],
+        ..Default::default()
+    };
+    let page = table_data::read_table_page(&client, "public", "test_table_data_filter", 1, 50, false, &nulls)
+        .await
+        .expect("读取表数据失败");
+    assert_eq!(page.total_rows, 4);
+
+    // 值按参数绑定，不会作为 SQL 执行
+    let injection = TableQuery {
+        filters: vec![filter("name", FilterOperator::Eq, serde_json::json!("x' OR '1'='1"))],
+        ..Default::default()
+    };
+    let page = table_data::read_table_page(&client, "public", "test_table_data_filter", 1, 50, false, &injection)
+        .await
+        .expect("读取表数据失败");
+    assert_eq!(page.total_rows, 0);
+
+    let unknown = TableQuery {
+        filters: vec![filter("no_such_column", FilterOperator::Eq, serde_json::json!(1))],
+        ..Default::default()
+    };
+    assert!(table_data::read_table_page(&client, "public", "test_table_data_filter", 1, 50, false, &unknown).await.is_err());
+
+    client.batch_execute("DROP TABLE test_table_data_filter").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_same_table_name_in_two_schemas() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    // 同名的表按给定的 schema 读取，不受 search_path 影响
+    client
+        .batch_execute(
+            "DROP SCHEMA IF EXISTS \"Test Table Data\" CASCADE;
+             CREATE SCHEMA \"Test Table Data\";
+             DROP TABLE IF EXISTS public.test_table_data_shadow;
+             CREATE TABLE public.test_table_data_shadow (id INT PRIMARY KEY);
+             CREATE TABLE \"Test Table Data\".test_table_data_shadow (id INT PRIMARY KEY, label TEXT);
+             INSERT INTO public.test_table_data_shadow VALUES (1);
+             INSERT INTO \"Test Table Data\".test_table_data_shadow VALUES (1, 'a'), (2, 'b');
+             SET search_path TO \"Test Table Data\", public;",
+        )
+        .await
+        .unwrap();
+
+    let public = table_data::read_table_page(&client, "public", "test_table_data_shadow", 1, 10, false, &TableQuery::default())
+        .await
+        .expect("读取表数据失败");
+    assert_eq!(public.total_rows, 1);
+    assert_eq!(public.columns.len(), 1);
+
+    let other = table_data::read_table_page(&client, "Test Table Data", "test_table_data_shadow", 1, 10, false, &TableQuery::default())
+        .await
+        .expect("读取表数据失败");
+    assert_eq!(other.total_rows, 2);
+    assert_eq!(other.rows[1]["label"], "b");
+
+    client
+        .batch_execute(
+            "RESET search_path;
+             DROP TABLE public.test_table_data_shadow;
+             DROP SCHEMA \"Test Table Data\" CASCADE;",
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_estimated_total_rows() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_table_data_estimate;
+             CREATE TABLE test_table_data_estimate (id INT PRIMARY KEY);
+             INSERT INTO test_table_data_estimate SELECT generate_series(1, 500);
+             ANALYZE test_table_data_estimate;",
+        )
+        .await
+        .unwrap();
+
+    let estimate = TableQuery {
+        estimate_count: true,
+        ..Default::default()
+    };
+    let page = table_data::read_table_page(&client, "public", "test_table_data_estimate", 1, 10, false, &estimate)
+        .await
+        .expect("读取表数据失败");
+    assert!(page.total_rows_estimated);
+    assert_eq!(page.total_rows, 500);
+    assert_eq!(page.rows.len(), 10);
+
+    // 有过滤条件时估算值不适用，仍精确统计
+    let filtered = TableQuery {
+        filters: vec![ColumnFilter {
+            column: "id".to_string(),
+            operator: FilterOperator::Lt,
+            value: serde_json::json!(11),
+        }],
+        estimate_count: true,
+        ..Default::default()
+    };
+    let page = table_data::read_table_page(&client, "public", "test_table_data_estimate", 1, 10, false, &filtered)
+        .await
+        .expect("读取表数据失败");
+    assert!(!page.total_rows_estimated);
+    assert_eq!(page.total_rows, 10);
+
+    let exact = table_data::read_table_page(&client, "public", "test_table_data_estimate", 1, 10, false, &TableQuery::default())
+        .await
+        .expect("读取表数据失败");
+    assert!(!exact.total_rows_estimated);
+    assert_eq!(exact.total_rows, 500);
+
+    client.batch_execute("DROP TABLE test_table_data_estimate").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_update_row_by_returned_ctid() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let table = "test_table_data_ctid";
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_table_data_ctid;
+             CREATE TABLE test_table_data_ctid (name TEXT, amount INT);
+             INSERT INTO test_table_data_ctid VALUES ('a', 1), ('b', 2);",
+        )
+        .await
+        .unwrap();
+
+    let query = TableQuery {
+        filters: vec![ColumnFilter {
+            column: "name".to_string(),
+            operator: FilterOperator::Eq,
+            value: serde_json::json!("b"),
+        }],
+        ..Default::default()
+    };
+
+    // 原始模式同样附带 ctid
+    let raw = table_data::read_table_page(&client, "public", table, 1, 10, true, &query)
+        .await
+        .expect("读取表数据失败");
+    assert!(raw.rows[0]["ctid"].is_string());
+
+    let page = table_data::read_table_page(&client, "public", table, 1, 10, false, &query)
+        .await
+        .expect("读取表数据失败");
+    assert_eq!(page.rows.len(), 1);
+    assert_eq!(page.rows[0]["amount"], 2);
+    let ctid = page.rows[0]["ctid"].clone();
+    assert_eq!(ctid, raw.rows[0]["ctid"]);
+
+    let cache = Mutex::new(ColumnTypeCache::new());
+    let key = serde_json::json!({ "ctid": ctid });
+    let changes = serde_json::json!({ "amount": "20" });
+    let updated = record_writer::update_record(
+        &client,
+        &cache,
+        "personnel_db",
+        "public",
+        table,
+        key.as_object().unwrap(),
+        changes.as_object().unwrap(),
+    )
+    .await
+    .expect("按 ctid 更新记录失败");
+    assert_eq!(updated, 1);
+
+    let amounts: Vec<i32> = client
+        .query("SELECT amount FROM test_table_data_ctid ORDER BY name", &[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    assert_eq!(amounts, vec![1, 20]);
+
+    client.batch_execute("DROP TABLE test_table_data_ctid").await.unwrap();
+}
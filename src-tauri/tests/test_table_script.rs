@@ -0,0 +1,167 @@
+/**
+ * Integration tests for Table Script
+ *
+ * 这些测试验证建表脚本的往返一致性：
+ * - 生成脚本、删除表、重新执行脚本后表结构不变
+ * - 注释、存储参数、表达式索引和 serial 序列被保留
+ * - 标识列和生成列被保留
+ */
+
+use pg_db_tool::models::schema::IdentityKind;
+use pg_db_tool::services::{schema_diff, schema_service, table_script};
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_script_round_trip() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_script_orders;
+             DROP TABLE IF EXISTS test_script_customers;
+             CREATE TABLE test_script_customers (id INT PRIMARY KEY);
+             CREATE TABLE test_script_orders (
+                 id SERIAL PRIMARY KEY,
+                 code VARCHAR(20) NOT NULL UNIQUE,
+                 customer_id INT REFERENCES test_script_customers (id) ON DELETE CASCADE,
+                 amount NUMERIC(10, 2) DEFAULT 0 CHECK (amount >= 0),
+                 tags TEXT[],
+                 note TEXT
+             ) WITH (fillfactor = 80);
+             CREATE INDEX test_script_orders_customer_idx ON test_script_orders (customer_id);
+             CREATE INDEX test_script_orders_lower_note_idx ON test_script_orders (lower(note)) WHERE note IS NOT NULL;
+             COMMENT ON TABLE test_script_orders IS 'Customer''s orders';
+             COMMENT ON COLUMN test_script_orders.amount IS 'Order amount';",
+        )
+        .await
+        .unwrap();
+
+    let before = schema_service::get_table_schema(&client, "public", "test_script_orders").await.unwrap();
+    let docs_before = schema_service::get_table_documentation(&client, "public", "test_script_orders").await.unwrap();
+
+    let script = table_script::script_table(&client, "public", "test_script_orders")
+        .await
+        .expect("生成建表脚本失败");
+    assert!(script.warnings.is_empty(), "不应有警告: {:?}", script.warnings);
+
+    client.batch_execute("DROP TABLE test_script_orders;").await.unwrap();
+    client
+        .batch_execute(&script.script)
+        .await
+        .unwrap_or_else(|e| panic!("执行建表脚本失败: {}\n{}", e, script.script));
+
+    let after = schema_service::get_table_schema(&client, "public", "test_script_orders").await.unwrap();
+    let docs_after = schema_service::get_table_documentation(&client, "public", "test_script_orders").await.unwrap();
+
+    let diff = schema_diff::diff_table_schemas(&before, &after);
+    assert!(diff.columns.is_empty(), "列不一致: {:?}", diff.columns);
+    assert!(diff.constraints.is_empty(), "约束不一致: {:?}", diff.constraints);
+    assert!(diff.indexes.is_empty(), "索引不一致: {:?}", diff.indexes);
+    assert_eq!(before.storage_parameters, after.storage_parameters);
+    assert_eq!(docs_before.table_comment, docs_after.table_comment);
+    for (a, b) in docs_before.columns.iter().zip(&docs_after.columns) {
+        assert_eq!(a.comment, b.comment);
+    }
+
+    // 部分表达式索引按原定义重建
+    let predicate: String = client
+        .query_one(
+            "SELECT pg_get_indexdef('test_script_orders_lower_note_idx'::regclass)",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert!(predicate.contains("lower(note)") && predicate.contains("WHERE"));
+
+    // serial 序列仍归属于列，删除表时一起删除
+    client.batch_execute("DROP TABLE test_script_orders; DROP TABLE test_script_customers;").await.unwrap();
+    let sequence_left: bool = client
+        .query_one("SELECT to_regclass('public.test_script_orders_id_seq') IS NOT NULL", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert!(!sequence_left);
+}
+
+#[tokio::test]
+async fn test_script_keeps_identity_and_generated_columns() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_script_features;
+             CREATE TABLE test_script_features (
+                 id INT GENERATED ALWAYS AS IDENTITY,
+                 code INT GENERATED BY DEFAULT AS IDENTITY,
+                 price INT,
+                 doubled INT GENERATED ALWAYS AS (price * 2) STORED
+             );",
+        )
+        .await
+        .unwrap();
+
+    let before = schema_service::get_table_schema(&client, "public", "test_script_features").await.unwrap();
+    assert_eq!(before.columns[0].identity, Some(IdentityKind::Always));
+    assert_eq!(before.columns[1].identity, Some(IdentityKind::ByDefault));
+    assert_eq!(before.columns[2].identity, None);
+    assert_eq!(before.columns[3].generated_expression.as_deref(), Some("(price * 2)"));
+    assert_eq!(before.columns[3].column_default, None);
+
+    let script = table_script::script_table(&client, "public", "test_script_features")
+        .await
+        .expect("生成建表脚本失败");
+    assert!(script.warnings.is_empty(), "不应有警告: {:?}", script.warnings);
+
+    client.batch_execute("DROP TABLE test_script_features;").await.unwrap();
+    client
+        .batch_execute(&script.script)
+        .await
+        .unwrap_or_else(|e| panic!("执行建表脚本失败: {}\n{}", e, script.script));
+
+    let after = schema_service::get_table_schema(&client, "public", "test_script_features").await.unwrap();
+    for (a, b) in before.columns.iter().zip(&after.columns) {
+        assert_eq!(a.identity, b.identity, "列 {} 的标识类型不一致", a.name);
+        assert_eq!(a.generated_expression, b.generated_expression, "列 {} 的生成表达式不一致", a.name);
+    }
+
+    client.batch_execute("DROP TABLE test_script_features;").await.unwrap();
+
+    let missing = table_script::script_table(&client, "public", "test_script_features").await;
+    assert!(missing.is_err());
+}
@@ -0,0 +1,76 @@
+/**
+ * Integration tests for Table Statistics
+ *
+ * 这些测试验证表的大小和维护记录：
+ * - 总大小等于表数据、索引和 TOAST 之和
+ * - VACUUM ANALYZE 后有估算行数和最近维护时间
+ * - 不存在的表返回错误
+ */
+
+use pg_db_tool::services::table_stats;
+
+/// 获取测试数据库连接
+async fn get_test_client() -> Result<tokio_postgres::Client, tokio_postgres::Error> {
+    let host = std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = std::env::var("PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let password = std::env::var("PG_PASSWORD").unwrap_or_else(|_| "postgres".to_string());
+    let database = std::env::var("PG_DATABASE").unwrap_or_else(|_| "personnel_db".to_string());
+
+    let connection_string = format!(
+        "host={} port={} user={} password={} dbname={}",
+        host, port, user, password, database
+    );
+
+    let (client, connection) = tokio_postgres::connect(&connection_string, tokio_postgres::NoTls).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Connection error: {}", e);
+        }
+    });
+
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_table_stats_sizes_and_maintenance() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_table_stats;
+             CREATE TABLE test_table_stats (id INT PRIMARY KEY, note TEXT);
+             INSERT INTO test_table_stats SELECT g, repeat('x', 100) FROM generate_series(1, 1000) g;",
+        )
+        .await
+        .unwrap();
+
+    // VACUUM 不能在多语句的批处理中执行（批处理作为一个隐式事务执行）
+    client.batch_execute("VACUUM ANALYZE test_table_stats").await.unwrap();
+
+    let stats = table_stats::get_table_stats(&client, "public", "test_table_stats")
+        .await
+        .expect("获取表统计信息失败");
+
+    assert!(stats.table_bytes > 0);
+    assert!(stats.indexes_bytes > 0);
+    assert_eq!(
+        stats.total_bytes,
+        stats.table_bytes + stats.indexes_bytes + stats.toast_bytes
+    );
+    assert_eq!(stats.estimated_rows, 1000);
+    assert!(stats.last_vacuum.is_some());
+    assert!(stats.last_analyze.is_some());
+
+    let missing = table_stats::get_table_stats(&client, "public", "test_table_stats_missing").await;
+    assert!(missing.unwrap_err().contains("does not exist"));
+
+    client.batch_execute("DROP TABLE test_table_stats").await.unwrap();
+}
@@ -6,12 +6,17 @@
  * - 批量插入操作
  * - 批量删除操作
  * - 事务原子性（失败时回滚）
+ * - 失败时返回导致失败的行的下标和数据
+ * - 跳过失败的行并提交其余的行（continue_on_error）
+ * - 指定隔离级别，序列化失败时标记为可重试
+ * - 数组列和复合类型列的写入
+ * - 包含引号、分号等的值按字面写入，不会被当作SQL执行
  * 
  * Validates: Requirements 10.2, 10.3, 16.1, 16.2
  */
 
-use pg_db_tool::services::transaction_manager;
-use pg_db_tool::models::data::RowUpdate;
+use pg_db_tool::services::transaction_manager::{self, BatchOptions};
+use pg_db_tool::models::data::{IsolationLevel, RowUpdate};
 use std::collections::HashMap;
 use serde_json::json;
 
@@ -235,7 +240,13 @@ async fn test_batch_insert_rows_rollback_on_duplicate() {
 
     // 验证结果
     assert!(!result.success, "批量插入应该失败");
-    assert!(result.error.is_some(), "应该有错误信息");
+    assert!(
+        result.error.as_deref().is_some_and(|e| e.contains("duplicate key")),
+        "错误信息应包含服务器消息: {:?}",
+        result.error
+    );
+    assert_eq!(result.failed_index, Some(1), "应该指出失败的是第二行");
+    assert_eq!(result.failed_row, Some(json!({"id": 1, "name": "Bob"})));
 
     // 验证没有数据被插入（事务已回滚）
     let rows = client.query("SELECT COUNT(*) FROM test_batch_insert_rollback", &[]).await.unwrap();
@@ -400,3 +411,316 @@ async fn test_empty_deletes() {
     assert!(result.error.is_some());
     assert_eq!(result.error.unwrap(), "没有要删除的行");
 }
+
+#[tokio::test]
+async fn test_batch_insert_and_update_array_columns() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_array_columns;
+             CREATE TABLE test_array_columns (id INTEGER PRIMARY KEY, scores INT[], tags TEXT[]);",
+        )
+        .await
+        .unwrap();
+
+    let rows = vec![
+        HashMap::from([
+            ("id".to_string(), json!(1)),
+            ("scores".to_string(), json!([90, null, 75])),
+            ("tags".to_string(), json!(["a b", "it's", "q\"x"])),
+        ]),
+        HashMap::from([
+            ("id".to_string(), json!(2)),
+            ("scores".to_string(), json!([])),
+            ("tags".to_string(), json!(["NULL"])),
+        ]),
+    ];
+
+    let result = transaction_manager::batch_insert_rows(&client, "public", "test_array_columns", rows).await;
+    assert!(result.success, "插入数组列失败: {:?}", result.error);
+    assert_eq!(result.rows_affected, 2);
+
+    let row = client
+        .query_one("SELECT scores, tags FROM test_array_columns WHERE id = 1", &[])
+        .await
+        .unwrap();
+    let scores: Vec<Option<i32>> = row.get(0);
+    let tags: Vec<Option<String>> = row.get(1);
+    assert_eq!(scores, vec![Some(90), None, Some(75)]);
+    assert_eq!(
+        tags,
+        vec![Some("a b".to_string()), Some("it's".to_string()), Some("q\"x".to_string())]
+    );
+
+    // 字符串 "NULL" 是普通字符串而不是 NULL
+    let row = client
+        .query_one("SELECT scores, tags FROM test_array_columns WHERE id = 2", &[])
+        .await
+        .unwrap();
+    let scores: Vec<Option<i32>> = row.get(0);
+    let tags: Vec<Option<String>> = row.get(1);
+    assert!(scores.is_empty());
+    assert_eq!(tags, vec![Some("NULL".to_string())]);
+
+    let updates = vec![RowUpdate {
+        primary_key: HashMap::from([("id".to_string(), json!(2))]),
+        changes: HashMap::from([
+            ("scores".to_string(), json!([1, 2, 3])),
+            ("tags".to_string(), json!(["x", null])),
+        ]),
+    }];
+    let result = transaction_manager::batch_update_rows(&client, "public", "test_array_columns", updates).await;
+    assert!(result.success, "更新数组列失败: {:?}", result.error);
+
+    let row = client
+        .query_one("SELECT scores, tags FROM test_array_columns WHERE id = 2", &[])
+        .await
+        .unwrap();
+    let scores: Vec<Option<i32>> = row.get(0);
+    let tags: Vec<Option<String>> = row.get(1);
+    assert_eq!(scores, vec![Some(1), Some(2), Some(3)]);
+    assert_eq!(tags, vec![Some("x".to_string()), None]);
+
+    let _ = client.batch_execute("DROP TABLE test_array_columns").await;
+}
+
+#[tokio::test]
+async fn test_batch_insert_composite_column() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_composite_column;
+             DROP TYPE IF EXISTS test_geo_point;
+             CREATE TYPE test_geo_point AS (x DOUBLE PRECISION, y DOUBLE PRECISION, label TEXT);
+             CREATE TABLE test_composite_column (id INTEGER PRIMARY KEY, location test_geo_point);",
+        )
+        .await
+        .unwrap();
+
+    let rows = vec![HashMap::from([
+        ("id".to_string(), json!(1)),
+        ("location".to_string(), json!({"x": 1.5, "y": -2, "label": "home"})),
+    ])];
+
+    let result = transaction_manager::batch_insert_rows(&client, "public", "test_composite_column", rows).await;
+    assert!(result.success, "插入复合类型列失败: {:?}", result.error);
+
+    let row = client
+        .query_one("SELECT (location).x, (location).y, (location).label FROM test_composite_column", &[])
+        .await
+        .unwrap();
+    let x: f64 = row.get(0);
+    let y: f64 = row.get(1);
+    let label: String = row.get(2);
+    assert_eq!((x, y, label.as_str()), (1.5, -2.0, "home"));
+
+    let _ = client
+        .batch_execute("DROP TABLE test_composite_column; DROP TYPE test_geo_point;")
+        .await;
+}
+
+#[tokio::test]
+async fn test_batch_values_stored_verbatim() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS test_batch_verbatim;
+             CREATE TABLE test_batch_verbatim (id TEXT PRIMARY KEY, note TEXT, active BOOLEAN, amount NUMERIC);",
+        )
+        .await
+        .unwrap();
+
+    let key = "k'1\\";
+    let note = "a'; rollback; --";
+    let rows = vec![HashMap::from([
+        ("id".to_string(), json!(key)),
+        ("note".to_string(), json!("placeholder")),
+        ("active".to_string(), json!(false)),
+        ("amount".to_string(), json!(null)),
+    ])];
+    let result = transaction_manager::batch_insert_rows(&client, "public", "test_batch_verbatim", rows).await;
+    assert!(result.success, "插入失败: {:?}", result.error);
+
+    let updates = vec![RowUpdate {
+        primary_key: HashMap::from([("id".to_string(), json!(key))]),
+        changes: HashMap::from([
+            ("note".to_string(), json!(note)),
+            ("active".to_string(), json!(true)),
+            ("amount".to_string(), json!(12.5)),
+        ]),
+    }];
+    let result = transaction_manager::batch_update_rows(&client, "public", "test_batch_verbatim", updates).await;
+    assert!(result.success, "更新失败: {:?}", result.error);
+    assert_eq!(result.rows_affected, 1);
+
+    let row = client
+        .query_one("SELECT id, note, active, amount::text FROM test_batch_verbatim", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, String>(0), key);
+    assert_eq!(row.get::<_, String>(1), note);
+    assert!(row.get::<_, bool>(2));
+    assert_eq!(row.get::<_, String>(3), "12.5");
+
+    let primary_keys = vec![HashMap::from([("id".to_string(), json!(key))])];
+    let result = transaction_manager::batch_delete_rows(&client, "public", "test_batch_verbatim", primary_keys).await;
+    assert!(result.success, "删除失败: {:?}", result.error);
+    assert_eq!(result.rows_affected, 1);
+
+    let _ = client.batch_execute("DROP TABLE test_batch_verbatim").await;
+}
+
+#[tokio::test]
+async fn test_batch_insert_rows_continue_on_error() {
+    let client = match get_test_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let _ = client.execute("DROP TABLE IF EXISTS test_batch_continue_on_error", &[]).await;
+    client
+        .batch_execute(
+            "CREATE TABLE test_batch_continue_on_error (id INTEGER PRIMARY KEY, name VARCHAR(100));
+             INSERT INTO test_batch_continue_on_error VALUES (2, 'Existing');",
+        )
+        .await
+        .unwrap();
+
+    // 第二行主键重复，第三行列名不存在
+    let rows = vec![
+        HashMap::from([("id".to_string(), json!(1)), ("name".to_string(), json!("Alice"))]),
+        HashMap::from([("id".to_string(), json!(2)), ("name".to_string(), json!("Bob"))]),
+        HashMap::from([("id".to_string(), json!(3)), ("missing".to_string(), json!("x"))]),
+        HashMap::from([("id".to_string(), json!(4)), ("name".to_string(), json!("Dave"))]),
+    ];
+    let options = BatchOptions { continue_on_error: true, ..Default::default() };
+
+    let result = transaction_manager::batch_insert_rows_with_options(
+        &client,
+        "public",
+        "test_batch_continue_on_error",
+        rows.clone(),
+        &options,
+    )
+    .await;
+
+    assert!(result.success, "批次应提交: {:?}", result.error);
+    assert_eq!(result.rows_affected, 2);
+    let outcomes: Vec<bool> = result.per_row_results.iter().map(|r| r.success).collect();
+    assert_eq!(outcomes, vec![true, false, false, true]);
+    assert_eq!(result.per_row_results[1].index, 1);
+    assert!(result.per_row_results[1].error.as_deref().unwrap().contains("duplicate key"));
+
+    let ids: Vec<i32> = client
+        .query("SELECT id FROM test_batch_continue_on_error ORDER BY id", &[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    assert_eq!(ids, vec![1, 2, 4]);
+
+    // 默认仍然整体回滚
+    client.execute("DELETE FROM test_batch_continue_on_error WHERE id <> 2", &[]).await.unwrap();
+    let result = transaction_manager::batch_insert_rows(&client, "public", "test_batch_continue_on_error", rows).await;
+    assert!(!result.success);
+    assert!(result.per_row_results.is_empty());
+    let count: i64 = client
+        .query_one("SELECT COUNT(*) FROM test_batch_continue_on_error", &[])
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(count, 1);
+
+    let _ = client.execute("DROP TABLE test_batch_continue_on_error", &[]).await;
+}
+
+#[tokio::test]
+async fn test_serializable_batch_reports_retryable_failure() {
+    let (client, other) = match tokio::try_join!(get_test_client(), get_test_client()) {
+        Ok(clients) => clients,
+        Err(e) => {
+            eprintln!("无法连接到测试数据库: {}. 跳过测试", e);
+            return;
+        }
+    };
+
+    let _ = client.execute("DROP TABLE IF EXISTS test_batch_serializable", &[]).await;
+    client
+        .batch_execute(
+            "CREATE TABLE test_batch_serializable (id INTEGER PRIMARY KEY, balance INTEGER);
+             INSERT INTO test_batch_serializable VALUES (1, 100);",
+        )
+        .await
+        .unwrap();
+
+    // 另一个事务先锁住该行，批量更新等待锁；对方提交后，可串行化事务无法基于旧快照更新
+    other
+        .batch_execute("BEGIN; UPDATE test_batch_serializable SET balance = 50 WHERE id = 1;")
+        .await
+        .unwrap();
+
+    let updates = vec![RowUpdate {
+        primary_key: HashMap::from([("id".to_string(), json!(1))]),
+        changes: HashMap::from([("balance".to_string(), json!(0))]),
+    }];
+    let options = BatchOptions {
+        isolation: Some(IsolationLevel::Serializable),
+        ..Default::default()
+    };
+
+    let (result, _) = tokio::join!(
+        transaction_manager::batch_update_rows_with_options(
+            &client,
+            "public",
+            "test_batch_serializable",
+            updates.clone(),
+            &options,
+        ),
+        async {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            other.batch_execute("COMMIT").await.unwrap();
+        }
+    );
+
+    assert!(!result.success);
+    assert!(result.retryable, "序列化失败应可重试: {:?}", result.error);
+
+    // 重试成功
+    let result = transaction_manager::batch_update_rows_with_options(
+        &client,
+        "public",
+        "test_batch_serializable",
+        updates,
+        &options,
+    )
+    .await;
+    assert!(result.success, "{:?}", result.error);
+    assert!(!result.retryable);
+
+    let _ = client.execute("DROP TABLE test_batch_serializable", &[]).await;
+}